@@ -29,6 +29,10 @@ pub const STD_LIB: &[(&str, &str)] = &[
         "qsharp-library-source:Std/Canon.qs",
         include_str!("../std/src/Std/Canon.qs"),
     ),
+    (
+        "qsharp-library-source:Std/Collections.qs",
+        include_str!("../std/src/Std/Collections.qs"),
+    ),
     (
         "qsharp-library-source:Std/Convert.qs",
         include_str!("../std/src/Std/Convert.qs"),
@@ -41,6 +45,10 @@ pub const STD_LIB: &[(&str, &str)] = &[
         "qsharp-library-source:Std/Diagnostics.qs",
         include_str!("../std/src/Std/Diagnostics.qs"),
     ),
+    (
+        "qsharp-library-source:Std/Environment.qs",
+        include_str!("../std/src/Std/Environment.qs"),
+    ),
     (
         "qsharp-library-source:Std/InternalHelpers.qs",
         include_str!("../std/src/Std/InternalHelpers.qs"),
@@ -49,6 +57,10 @@ pub const STD_LIB: &[(&str, &str)] = &[
         "qsharp-library-source:Std/Intrinsic.qs",
         include_str!("../std/src/Std/Intrinsic.qs"),
     ),
+    (
+        "qsharp-library-source:Std/IO.qs",
+        include_str!("../std/src/Std/IO.qs"),
+    ),
     (
         "qsharp-library-source:Std/Logical.qs",
         include_str!("../std/src/Std/Logical.qs"),
@@ -85,6 +97,10 @@ pub const STD_LIB: &[(&str, &str)] = &[
         "qsharp-library-source:Std/StatePreparation.qs",
         include_str!("../std/src/Std/StatePreparation.qs"),
     ),
+    (
+        "qsharp-library-source:Std/Strings.qs",
+        include_str!("../std/src/Std/Strings.qs"),
+    ),
     (
         "qsharp-library-source:Std/TableLookup.qs",
         include_str!("../std/src/Std/TableLookup.qs"),
@@ -4,6 +4,7 @@
 mod arithmetic;
 mod arrays;
 mod canon;
+mod collections;
 mod convert;
 mod core;
 mod diagnostics;
@@ -12,6 +13,7 @@ mod logical;
 mod math;
 mod measurement;
 mod state_preparation;
+mod strings;
 mod table_lookup;
 
 use indoc::indoc;
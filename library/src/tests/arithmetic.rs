@@ -505,6 +505,41 @@ fn check_inc_by_i_general() {
     );
 }
 
+#[test]
+fn check_inc_by_i_functors() {
+    test_expression(
+        {
+            "{
+                import Std.Arithmetic.*;
+
+                use y0 = Qubit[4];
+                ApplyXorInPlace(5, y0);
+                Adjoint IncByI(3, y0); // 5 -= 3
+                let i0 = MeasureInteger(y0);
+
+                use ctl = Qubit();
+                use y1 = Qubit[4];
+                ApplyXorInPlace(5, y1);
+                Controlled IncByI([ctl], (7, y1)); // control is |0>, no change
+                let i1 = MeasureInteger(y1);
+
+                use y2 = Qubit[4];
+                ApplyXorInPlace(5, y2);
+                X(ctl);
+                Controlled IncByI([ctl], (7, y2)); // 5 += 7
+                X(ctl);
+                let i2 = MeasureInteger(y2);
+
+                return (i0, i1, i2);
+        }"
+        },
+        &Value::Tuple(
+            vec![Value::Int(2), Value::Int(5), Value::Int(12)].into(),
+            None,
+        ),
+    );
+}
+
 //
 // IncByIUsingIncByLE
 //
@@ -1,7 +1,8 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use super::test_expression;
+use super::{test_expression, test_expression_fails};
+use expect_test::expect;
 use num_bigint::BigInt;
 use qsc::interpret::Value;
 
@@ -107,6 +108,12 @@ fn check_chunks() {
     );
 }
 
+#[test]
+fn check_chunks_fails_for_non_positive_size() {
+    let err = test_expression_fails("Microsoft.Quantum.Arrays.Chunks(0, [1, 2])");
+    expect!["intrinsic callable `Chunks` failed: `chunkSize` must be positive"].assert_eq(&err);
+}
+
 #[test]
 fn check_circularly_shifted() {
     test_expression(
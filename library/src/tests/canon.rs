@@ -581,6 +581,28 @@ fn check_apply_controlled_on_int() {
     );
 }
 
+#[test]
+fn check_apply_controlled_on_int_functors() {
+    test_expression(
+        indoc! {r#"{
+            use aux = Qubit();
+            use c = Qubit[2];
+            use t = Qubit();
+            X(aux);
+            X(c[1]);
+            H(t);
+            Controlled Microsoft.Quantum.Canon.ApplyControlledOnInt([aux], (2, S, c, t));
+            Adjoint Microsoft.Quantum.Canon.ApplyControlledOnInt(2, S, c, t);
+            H(t);
+            let result = MResetZ(t);
+            X(c[1]);
+            X(aux);
+            result
+        }"#},
+        &Value::RESULT_ZERO,
+    );
+}
+
 #[test]
 fn check_apply_controlled_on_bitstring() {
     test_expression(
@@ -0,0 +1,107 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{test_expression, test_expression_fails};
+use expect_test::expect;
+use qsc::interpret::Value;
+
+#[test]
+fn check_distinct() {
+    test_expression(
+        "Std.Collections.Distinct([3, 1, 3, 2, 1])",
+        &Value::Array(vec![Value::Int(3), Value::Int(1), Value::Int(2)].into()),
+    );
+    test_expression(
+        "{
+            let empty : String[] = [];
+            Std.Collections.Distinct(empty)
+        }",
+        &Value::Array(vec![].into()),
+    );
+}
+
+#[test]
+fn check_counts_of_measurement_patterns() {
+    test_expression(
+        "Std.Collections.Counts([[Zero, One], [One, One], [Zero, One]])",
+        &Value::Array(
+            vec![
+                Value::Tuple(
+                    [
+                        Value::Array(vec![Value::RESULT_ZERO, Value::RESULT_ONE].into()),
+                        Value::Int(2),
+                    ]
+                    .into(),
+                    None,
+                ),
+                Value::Tuple(
+                    [
+                        Value::Array(vec![Value::RESULT_ONE, Value::RESULT_ONE].into()),
+                        Value::Int(1),
+                    ]
+                    .into(),
+                    None,
+                ),
+            ]
+            .into(),
+        ),
+    );
+}
+
+#[test]
+fn check_grouped_by_key() {
+    test_expression(
+        "Std.Collections.GroupedByKey([(\"a\", 1), (\"b\", 2), (\"a\", 3)])",
+        &Value::Array(
+            vec![
+                Value::Tuple(
+                    [
+                        Value::String("a".into()),
+                        Value::Array(vec![Value::Int(1), Value::Int(3)].into()),
+                    ]
+                    .into(),
+                    None,
+                ),
+                Value::Tuple(
+                    [
+                        Value::String("b".into()),
+                        Value::Array(vec![Value::Int(2)].into()),
+                    ]
+                    .into(),
+                    None,
+                ),
+            ]
+            .into(),
+        ),
+    );
+}
+
+#[test]
+fn check_set_operations() {
+    test_expression(
+        "Std.Collections.Union([1L, 2L], [2L, 3L])",
+        &Value::Array(
+            vec![
+                Value::BigInt(1.into()),
+                Value::BigInt(2.into()),
+                Value::BigInt(3.into()),
+            ]
+            .into(),
+        ),
+    );
+    test_expression(
+        "Std.Collections.Intersection([1, 2, 3, 2], [3, 2])",
+        &Value::Array(vec![Value::Int(2), Value::Int(3)].into()),
+    );
+    test_expression(
+        "Std.Collections.Difference([1, 2, 3, 1], [2])",
+        &Value::Array(vec![Value::Int(1), Value::Int(3)].into()),
+    );
+}
+
+#[test]
+fn check_distinct_fails_for_unhashable_elements() {
+    let err = test_expression_fails("Std.Collections.Distinct([(1, 1.0), (1, 1.0)])");
+    expect!["intrinsic callable `Distinct` failed: values of type Double cannot be used as keys"]
+        .assert_eq(&err);
+}
@@ -42,6 +42,42 @@ fn check_operations_are_equal() {
     );
 }
 
+#[test]
+fn check_operations_are_equal_for_rewritten_circuits() {
+    test_expression(
+        "{
+            import Std.Diagnostics.*;
+            operation Cnot(qs : Qubit[]) : Unit is Adj {
+                CNOT(qs[0], qs[1]);
+            }
+            operation CnotFromCz(qs : Qubit[]) : Unit is Adj {
+                within {
+                    H(qs[1]);
+                } apply {
+                    CZ(qs[0], qs[1]);
+                }
+            }
+            operation RelativePhase(qs : Qubit[]) : Unit is Adj {
+                S(qs[0]);
+            }
+            operation OtherRelativePhase(qs : Qubit[]) : Unit is Adj {
+                T(qs[0]);
+            }
+            operation GlobalPhaseOfZ(qs : Qubit[]) : Unit is Adj {
+                Rz(Std.Math.PI(), qs[0]);
+            }
+            operation JustZ(qs : Qubit[]) : Unit is Adj {
+                Z(qs[0]);
+            }
+            // Global phase is not observable, so operations that differ only by it are equal.
+            [CheckOperationsAreEqual(2, Cnot, CnotFromCz),
+             CheckOperationsAreEqual(1, RelativePhase, OtherRelativePhase),
+             CheckOperationsAreEqual(1, GlobalPhaseOfZ, JustZ)]
+        }",
+        &Value::Array(vec![Value::Bool(true), Value::Bool(false), Value::Bool(true)].into()),
+    );
+}
+
 #[test]
 fn check_dumpoperation_for_i() {
     let output = test_expression(
@@ -7,7 +7,10 @@ use expect_test::expect;
 use indoc::indoc;
 use qsc::{SparseSim, interpret::Value, target::Profile};
 
-use super::{test_expression, test_expression_fails, test_expression_with_lib_and_profile_and_sim};
+use super::{
+    test_expression, test_expression_fails, test_expression_fails_with_lib_and_profile_and_sim,
+    test_expression_with_lib_and_profile_and_sim,
+};
 
 // These tests verify multi-controlled decomposition logic for gate operations. Each test
 // manually allocates 2N qubits, performs the decomposed operation from the library on the first N,
@@ -3994,6 +3997,35 @@ fn test_apply_unitary_with_swap_matrix() {
     .assert_eq(&dump);
 }
 
+#[test]
+fn test_apply_unitary_with_three_qubit_matrix() {
+    test_expression(
+        indoc! {"
+        {
+            open Std.Math;
+            let one = new Complex { Real = 1.0, Imag = 0.0 };
+            let zero = new Complex { Real = 0.0, Imag = 0.0 };
+            // A Fredkin gate, which swaps the last two qubits when the first one is set.
+            let permutation = [0, 1, 2, 3, 4, 6, 5, 7];
+            mutable matrix = [];
+            for row in 0..7 {
+                mutable entries = [];
+                for col in 0..7 {
+                    set entries += [col == permutation[row] ? one | zero];
+                }
+                set matrix += [entries];
+            }
+            use qs = Qubit[3];
+            X(qs[0]);
+            X(qs[1]);
+            ApplyUnitary(matrix, qs);
+            MResetEachZ(qs)
+        }
+        "},
+        &Value::Array(vec![Value::RESULT_ONE, Value::RESULT_ZERO, Value::RESULT_ONE].into()),
+    );
+}
+
 #[test]
 fn test_apply_unitary_fails_when_matrix_not_square() {
     let err = test_expression_fails(indoc! {"
@@ -4062,3 +4094,98 @@ fn test_apply_unitary_fails_when_matrix_not_unitary() {
 
     expect!["intrinsic callable `Apply` failed: matrix is not unitary"].assert_eq(&err);
 }
+
+#[test]
+fn test_base_apply_unitary_with_hadamard_matrix() {
+    let mut sim = SparseSim::default();
+    let dump = test_expression_with_lib_and_profile_and_sim(
+        indoc! {"{
+            open Std.Math;
+            let qs = QIR.Runtime.AllocateQubitArray(1);
+            let one_sqrt_2 = new Complex { Real = 1.0 / Sqrt(2.0), Imag = 0.0 };
+            ApplyUnitary(
+                [
+                    [one_sqrt_2, one_sqrt_2],
+                    [one_sqrt_2, NegationC(one_sqrt_2)]
+                ],
+                qs
+            );
+            Std.Diagnostics.DumpMachine();
+            let result : Result[] = [];
+            result
+        }"},
+        "",
+        Profile::Base,
+        &mut sim,
+        &Value::Array(Vec::new().into()),
+    );
+
+    expect![[r#"
+        STATE:
+        |0⟩: 0.7071+0.0000𝑖
+        |1⟩: 0.7071+0.0000𝑖
+    "#]]
+    .assert_eq(&dump);
+}
+
+#[test]
+fn test_base_apply_unitary_with_cnot_matrix() {
+    let mut sim = SparseSim::default();
+    let dump = test_expression_with_lib_and_profile_and_sim(
+        indoc! {"{
+            open Std.Math;
+            let qs = QIR.Runtime.AllocateQubitArray(2);
+            let one = new Complex { Real = 1.0, Imag = 0.0 };
+            let zero = new Complex { Real = 0.0, Imag = 0.0 };
+            H(qs[0]);
+            ApplyUnitary(
+                [
+                    [one, zero, zero, zero],
+                    [zero, one, zero, zero],
+                    [zero, zero, zero, one],
+                    [zero, zero, one, zero]
+                ],
+                qs
+            );
+            Std.Diagnostics.DumpMachine();
+            let result : Result[] = [];
+            result
+        }"},
+        "",
+        Profile::Base,
+        &mut sim,
+        &Value::Array(Vec::new().into()),
+    );
+
+    expect![[r#"
+        STATE:
+        |00⟩: 0.7071+0.0000𝑖
+        |11⟩: 0.7071+0.0000𝑖
+    "#]]
+    .assert_eq(&dump);
+}
+
+#[test]
+fn test_base_apply_unitary_fails_when_matrix_not_unitary() {
+    let err = test_expression_fails_with_lib_and_profile_and_sim(
+        indoc! {"{
+            open Std.Math;
+            let qs = QIR.Runtime.AllocateQubitArray(1);
+            let zero = new Complex { Real = 0.0, Imag = 0.0 };
+            ApplyUnitary(
+                [
+                    [zero, zero],
+                    [zero, zero]
+                ],
+                qs
+            );
+            let result : Result[] = [];
+            result
+        }"},
+        "",
+        Profile::Base,
+        &mut SparseSim::default(),
+    );
+
+    expect!["program failed: matrix passed to ApplyUnitary must be unitary."].assert_eq(&err);
+}
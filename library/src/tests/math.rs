@@ -1,8 +1,9 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use super::test_expression;
+use super::{test_expression, test_expression_fails};
 use core::f64::consts::E;
+use expect_test::expect;
 use num_bigint::BigInt;
 use qsc::interpret::Value;
 use std::{f64::consts::PI, str::FromStr};
@@ -456,6 +457,20 @@ fn check_exp_mod_i() {
     test_expression("Microsoft.Quantum.Math.ExpModI(1,10,10)", &Value::Int(1));
     test_expression("Microsoft.Quantum.Math.ExpModI(10,0,10)", &Value::Int(1));
     test_expression("Microsoft.Quantum.Math.ExpModI(2,10,10)", &Value::Int(4));
+    test_expression(
+        "Microsoft.Quantum.Math.ExpModI(3,1000000005,1000000007)",
+        &Value::Int(333_333_336),
+    );
+    test_expression(
+        "Microsoft.Quantum.Math.ExpModI(123456789,987654321,2305843009213693951)",
+        &Value::Int(50_357_601_586_279_104),
+    );
+}
+
+#[test]
+fn check_exp_mod_i_fails_for_negative_power() {
+    let err = test_expression_fails("Microsoft.Quantum.Math.ExpModI(2,-1,10)");
+    expect!["intrinsic callable `ExpModI` failed: `power` must be non-negative"].assert_eq(&err);
 }
 
 #[test]
@@ -497,6 +512,13 @@ fn check_inverse_mod_l() {
     );
 }
 
+#[test]
+fn check_inverse_mod_i_fails_when_not_coprime() {
+    let err = test_expression_fails("Microsoft.Quantum.Math.InverseModI(4,10)");
+    expect!["intrinsic callable `InverseModI` failed: `a` and `modulus` must be co-prime"]
+        .assert_eq(&err);
+}
+
 //
 // GCD, etc.
 //
@@ -786,6 +808,15 @@ fn check_factorial_l() {
     );
 }
 
+#[test]
+fn check_factorial_l_rejects_large_input() {
+    let err = test_expression_fails("Microsoft.Quantum.Math.FactorialL(10001)");
+    expect![
+        "intrinsic callable `FactorialL` failed: The factorial is only supported for inputs up to 10000."
+    ]
+    .assert_eq(&err);
+}
+
 #[test]
 fn check_approximate_factorial() {
     test_expression(
@@ -840,6 +871,24 @@ fn check_binom() {
     test_expression("Microsoft.Quantum.Math.Binom(4, 4)", &Value::Int(1));
 }
 
+#[test]
+fn check_binom_l() {
+    test_expression(
+        "Microsoft.Quantum.Math.BinomL(4, 2)",
+        &Value::BigInt(BigInt::from(6)),
+    );
+    test_expression(
+        "Microsoft.Quantum.Math.BinomL(4, 5)",
+        &Value::BigInt(BigInt::from(0)),
+    );
+    test_expression(
+        "Microsoft.Quantum.Math.BinomL(100, 50)",
+        &Value::BigInt(
+            BigInt::from_str("100891344545564193334812497256").expect("Cannot parse static string"),
+        ),
+    );
+}
+
 #[test]
 fn check_square_norm() {
     test_expression(
@@ -65,6 +65,20 @@ fn check_measure_each_z() {
     );
 }
 
+#[test]
+fn check_multi_m() {
+    test_expression(
+        indoc! {r#"{
+            use register = Qubit[3];
+            X(register[1]);
+            let results = Microsoft.Quantum.Measurement.MultiM(register);
+            ResetAll(register);
+            results
+        }"#},
+        &Value::Array(vec![Value::RESULT_ZERO, Value::RESULT_ONE, Value::RESULT_ZERO].into()),
+    );
+}
+
 #[test]
 fn check_mreset_each_z() {
     test_expression(
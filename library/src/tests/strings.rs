@@ -0,0 +1,92 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{test_expression, test_expression_fails};
+use expect_test::expect;
+use qsc::interpret::Value;
+
+#[test]
+fn check_string_length_counts_characters() {
+    test_expression("Std.Strings.StringLength(\"\")", &Value::Int(0));
+    test_expression("Std.Strings.StringLength(\"|ψ⟩\")", &Value::Int(3));
+}
+
+#[test]
+fn check_substring() {
+    test_expression(
+        "Std.Strings.Substring(\"Hello, world\", 7, 5)",
+        &Value::String("world".into()),
+    );
+    test_expression(
+        "Std.Strings.Substring(\"|ψ⟩\", 1, 1)",
+        &Value::String("ψ".into()),
+    );
+}
+
+#[test]
+fn check_substring_fails_out_of_range() {
+    let err = test_expression_fails("Std.Strings.Substring(\"abc\", 2, 2)");
+    expect!["intrinsic callable `Substring` failed: substring is out of range"].assert_eq(&err);
+}
+
+#[test]
+fn check_split() {
+    test_expression(
+        "Std.Strings.Split(\"0.5,0.25,,1\", \",\")",
+        &Value::Array(
+            vec![
+                Value::String("0.5".into()),
+                Value::String("0.25".into()),
+                Value::String("".into()),
+                Value::String("1".into()),
+            ]
+            .into(),
+        ),
+    );
+}
+
+#[test]
+fn check_contains_and_case_conversion() {
+    test_expression("Std.Strings.Contains(\"H2O\", \"2O\")", &Value::Bool(true));
+    test_expression("Std.Strings.Contains(\"H2O\", \"h2\")", &Value::Bool(false));
+    test_expression(
+        "Std.Strings.Uppercased(\"h2o\")",
+        &Value::String("H2O".into()),
+    );
+    test_expression(
+        "Std.Strings.Lowercased(\"H2O\")",
+        &Value::String("h2o".into()),
+    );
+}
+
+#[test]
+fn check_parsing() {
+    test_expression(
+        "Std.Strings.TryStringAsInt(\"-42\")",
+        &Value::Tuple([Value::Bool(true), Value::Int(-42)].into(), None),
+    );
+    test_expression(
+        "Std.Strings.TryStringAsInt(\"4.2\")",
+        &Value::Tuple([Value::Bool(false), Value::Int(0)].into(), None),
+    );
+    test_expression(
+        "Std.Strings.TryStringAsDouble(\"1.5e-3\")",
+        &Value::Tuple([Value::Bool(true), Value::Double(1.5e-3)].into(), None),
+    );
+    test_expression("Std.Strings.StringAsInt(\"7\")", &Value::Int(7));
+    test_expression("Std.Strings.StringAsDouble(\"0.25\")", &Value::Double(0.25));
+}
+
+#[test]
+fn check_string_as_int_fails_on_invalid_input() {
+    let err = test_expression_fails("Std.Strings.StringAsInt(\"seven\")");
+    expect!["program failed: `seven` is not a valid Int"].assert_eq(&err);
+}
+
+#[test]
+fn check_legacy_namespace() {
+    test_expression(
+        "Microsoft.Quantum.Strings.StringLength(\"abc\")",
+        &Value::Int(3),
+    );
+}
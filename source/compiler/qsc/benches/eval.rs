@@ -231,6 +231,40 @@ pub fn large_nested_iteration(c: &mut Criterion) {
     });
 }
 
+pub fn classical_loop(c: &mut Criterion) {
+    c.bench_function("Classical loop of 1M iterations", |b| {
+        let sources = SourceMap::new(
+            [("none".into(), "".into())],
+            Some(
+                indoc! {"{
+                    mutable sum = 0;
+                    for i in 0..999_999 {
+                        set sum += i % 7;
+                    }
+                    sum
+                }"}
+                .into(),
+            ),
+        );
+        let (std_id, store) = qsc::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+        let mut evaluator = Interpreter::new(
+            sources,
+            PackageType::Exe,
+            TargetCapabilityFlags::all(),
+            LanguageFeatures::default(),
+            store,
+            &[(std_id, None)],
+        )
+        .expect("code should compile");
+
+        b.iter(move || {
+            let mut out = Vec::new();
+            let mut rec = GenericReceiver::new(&mut out);
+            assert!(evaluator.eval_entry(&mut rec).is_ok());
+        });
+    });
+}
+
 criterion_group!(
     benches,
     teleport,
@@ -241,5 +275,6 @@ criterion_group!(
     array_update,
     array_literal,
     large_nested_iteration,
+    classical_loop,
 );
 criterion_main!(benches);
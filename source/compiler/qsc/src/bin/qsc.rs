@@ -0,0 +1,294 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! The `qsc` command-line compiler.
+
+allocator::assign_global!();
+
+use clap::{Args, Parser, Subcommand, ValueEnum, crate_version};
+use miette::{Diagnostic, Report};
+use qsc::{
+    PackageType, PassContext, compile, diagnostic_json, explain, lower_hir_to_fir, sarif,
+    target::Profile,
+};
+use qsc_codegen::qir::fir_to_qir;
+use qsc_data_structures::{error::WithSource, source::SourceMap, target::TargetCapabilityFlags};
+use qsc_partial_eval::ProgramEntry;
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+    sync::Arc,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "qsc", version = crate_version!())]
+#[command(author, about, next_line_help = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Compile Q# source files and generate QIR for a target profile.
+    Build(BuildArgs),
+    /// Print an extended explanation of a diagnostic code, such as Qsc.Resolve.NotFound.
+    Explain {
+        /// The diagnostic code to explain.
+        code: String,
+    },
+}
+
+/// A compiler output that `build` can write.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Emit {
+    /// The parsed syntax tree, as an indented tree of nodes.
+    Ast,
+    /// The lowered high-level IR after all passes.
+    Hir,
+    /// The resolution of every bound or resolved name node in the syntax tree.
+    Names,
+    /// The generated QIR.
+    Qir,
+}
+
+/// How diagnostics are written to standard error.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum DiagnosticFormat {
+    /// Rendered with source snippets, for people.
+    #[default]
+    Human,
+    /// A JSON array, in the schema documented by `qsc::diagnostic_json`.
+    Json,
+    /// A SARIF 2.1.0 log.
+    Sarif,
+}
+
+#[derive(Debug, Args)]
+struct BuildArgs {
+    /// Paths to the Q# source files to compile.
+    #[arg(required = true, num_args = 1..)]
+    sources: Vec<PathBuf>,
+
+    /// An entry expression to use instead of the `@EntryPoint()` callable.
+    #[arg(short, long)]
+    entry: Option<String>,
+
+    /// The target profile of the generated QIR: base, adaptive_ri or adaptive_rif.
+    #[arg(short, long, default_value = "base", value_parser = parse_profile)]
+    profile: Profile,
+
+    /// Opt into a language feature, which gates syntax that is still evolving. Can be repeated.
+    #[arg(
+        long = "language-feature",
+        value_parser = ["v2-preview-syntax", "match-expressions"],
+    )]
+    language_features: Vec<String>,
+
+    /// The outputs to write, as a comma-separated list. With more than one, each output is
+    /// preceded by a comment line naming it.
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "qir")]
+    emit: Vec<Emit>,
+
+    /// Write the output to this file instead of standard output. Only one output can be emitted
+    /// when this is given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// The format of the reported diagnostics.
+    #[arg(long, value_enum, default_value_t)]
+    diagnostic_format: DiagnosticFormat,
+}
+
+fn parse_profile(value: &str) -> Result<Profile, String> {
+    match Profile::from_str(value) {
+        Ok(Profile::Unrestricted) => {
+            Err("QIR cannot be generated for the unrestricted profile".to_string())
+        }
+        Ok(profile) => Ok(profile),
+        Err(()) => Err(format!(
+            "unknown profile `{value}`, expected base, adaptive_ri or adaptive_rif"
+        )),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Build(args) => build(&args),
+        Command::Explain { code } => explain(&code),
+    }
+}
+
+fn build(args: &BuildArgs) -> ExitCode {
+    if args.output.is_some() && args.emit.len() > 1 {
+        eprintln!("error: --output can only be used when emitting a single output");
+        return ExitCode::FAILURE;
+    }
+
+    let mut files = Vec::new();
+    for path in &args.sources {
+        match fs::read_to_string(path) {
+            Ok(contents) => files.push((path.display().to_string().into(), contents.into())),
+            Err(e) => {
+                eprintln!("error: could not read {}: {e}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let sources = SourceMap::new(files, args.entry.as_deref().map(Arc::from));
+
+    let capabilities = TargetCapabilityFlags::from(args.profile);
+    let (std_id, mut store) = compile::package_store_with_stdlib(capabilities);
+    let (unit, errors) = compile::compile(
+        &store,
+        &[(std_id, None)],
+        sources,
+        PackageType::Exe,
+        capabilities,
+        args.language_features.iter().collect(),
+    );
+    if !errors.is_empty() {
+        report(args.diagnostic_format, errors);
+        return ExitCode::FAILURE;
+    }
+
+    let mut outputs = Vec::new();
+    for &kind in &args.emit {
+        match kind {
+            Emit::Ast => outputs.push((kind, unit.ast.package.to_string())),
+            Emit::Hir => outputs.push((kind, unit.package.to_string())),
+            Emit::Names => {
+                let mut names = String::new();
+                for (id, res) in unit.ast.names.iter() {
+                    writeln!(names, "{id}: {res:?}").expect("writing to string should succeed");
+                }
+                outputs.push((kind, names));
+            }
+            Emit::Qir => {}
+        }
+    }
+    if !args.emit.contains(&Emit::Qir) {
+        return write_outputs(args.output.as_deref(), &outputs);
+    }
+
+    let package_id = store.insert(unit);
+    let sources = &store
+        .get(package_id)
+        .expect("package should be in store")
+        .sources;
+    let (fir_store, fir_package_id) = lower_hir_to_fir(&store, package_id);
+    let compute_properties =
+        match PassContext::run_fir_passes_on_fir(&fir_store, fir_package_id, capabilities) {
+            Ok(compute_properties) => compute_properties,
+            Err(errors) => {
+                let errors = errors
+                    .into_iter()
+                    .map(|e| WithSource::from_map(sources, e))
+                    .collect();
+                report(args.diagnostic_format, errors);
+                return ExitCode::FAILURE;
+            }
+        };
+
+    let package = fir_store.get(fir_package_id);
+    let entry = ProgramEntry {
+        exec_graph: package.entry_exec_graph.clone(),
+        expr: (
+            fir_package_id,
+            package
+                .entry
+                .expect("package must have an entry expression"),
+        )
+            .into(),
+    };
+    match fir_to_qir(&fir_store, capabilities, Some(compute_properties), &entry) {
+        Ok(qir) => {
+            // QIR is generated last, so put it back in the requested order.
+            let position = args
+                .emit
+                .iter()
+                .position(|&kind| kind == Emit::Qir)
+                .expect("QIR should be requested");
+            outputs.insert(position, (Emit::Qir, qir));
+            write_outputs(args.output.as_deref(), &outputs)
+        }
+        Err(e) => {
+            let source_package_id = e.span().map_or(package_id, |span| span.package);
+            let sources = &store
+                .get(source_package_id)
+                .expect("package should be in store")
+                .sources;
+            report(
+                args.diagnostic_format,
+                vec![WithSource::from_map(sources, e)],
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn explain(code: &str) -> ExitCode {
+    if let Some(text) = explain::explanation(code) {
+        print!("{text}");
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("error: no explanation for `{code}`");
+        eprintln!("codes with an explanation:");
+        for code in explain::codes() {
+            eprintln!("    {code}");
+        }
+        ExitCode::FAILURE
+    }
+}
+
+fn report<E: Diagnostic + Send + Sync + 'static>(
+    format: DiagnosticFormat,
+    errors: Vec<WithSource<E>>,
+) {
+    match format {
+        DiagnosticFormat::Human => {
+            for error in errors {
+                eprintln!("{:?}", Report::new(error));
+            }
+        }
+        DiagnosticFormat::Json => eprintln!("{}", diagnostic_json::to_json(&errors)),
+        DiagnosticFormat::Sarif => eprintln!("{}", sarif::to_sarif(&errors)),
+    }
+}
+
+fn write_outputs(path: Option<&Path>, outputs: &[(Emit, String)]) -> ExitCode {
+    let contents = if let [(_, contents)] = outputs {
+        contents.clone()
+    } else {
+        outputs
+            .iter()
+            .fold(String::new(), |mut all, (kind, contents)| {
+                let name = kind
+                    .to_possible_value()
+                    .expect("emit kinds should not be skipped");
+                writeln!(all, "; {}", name.get_name()).expect("writing to string should succeed");
+                all.push_str(contents);
+                if !contents.ends_with('\n') {
+                    all.push('\n');
+                }
+                all
+            })
+    };
+    match path {
+        Some(path) => match fs::write(path, &contents) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: could not write {}: {e}", path.display());
+                ExitCode::FAILURE
+            }
+        },
+        None => {
+            print!("{contents}");
+            ExitCode::SUCCESS
+        }
+    }
+}
@@ -1,16 +1,35 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use crate::events::{CompileStats, CompilerEvents, SharedEvents, count_by_severity, start_clock};
 use miette::{Diagnostic, Report};
+use qsc_ast::ast::NodeId;
 use qsc_data_structures::{
-    error::WithSource, language_features::LanguageFeatures, source::SourceMap, span::Span,
-    target::TargetCapabilityFlags,
+    error::WithSource,
+    index_map::IndexMap,
+    language_features::LanguageFeatures,
+    source::{SourceContents, SourceMap, SourceName},
+    span::Span,
+    target::{Profile, TargetCapabilityFlags},
 };
 pub use qsc_frontend::compile::Dependencies;
-use qsc_frontend::compile::{CompileUnit, PackageStore};
+pub use qsc_frontend::compile::timings::{Phase, Timings};
+use qsc_frontend::{
+    compile::{CompileUnit, PackageStore, timings::timed},
+    resolve::Res,
+};
+use qsc_hir::hir::PackageId;
 use qsc_passes::{PackageType, run_core_passes, run_default_passes};
+use rustc_hash::FxHashMap;
+use std::{
+    rc::Rc,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+};
 use thiserror::Error;
 
+#[cfg(test)]
+mod tests;
+
 pub type Error = WithSource<ErrorKind>;
 
 #[derive(Clone, Debug, Diagnostic, Error)]
@@ -52,6 +71,250 @@ pub enum ErrorKind {
     EntryPointProfileInProject(#[label] Span),
 }
 
+/// Builds a Q# package against the core and standard libraries.
+///
+/// This is the supported way for hosts to embed the compiler. Unlike [`compile`], the builder
+/// creates its own package store and compiles any dependencies into it, so the caller only
+/// provides sources and settings. By default the package is compiled as a library for the
+/// unrestricted profile with default language features.
+#[derive(Clone, Debug)]
+pub struct CompilerBuilder {
+    sources: Vec<(SourceName, SourceContents)>,
+    entry: Option<Arc<str>>,
+    dependencies: Vec<(Arc<str>, Vec<(SourceName, SourceContents)>)>,
+    package_type: PackageType,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+    time_phases: bool,
+    events: Option<SharedEvents>,
+}
+
+impl Default for CompilerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompilerBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            entry: None,
+            dependencies: Vec::new(),
+            package_type: PackageType::Lib,
+            capabilities: TargetCapabilityFlags::all(),
+            language_features: LanguageFeatures::default(),
+            time_phases: false,
+            events: None,
+        }
+    }
+
+    /// Adds a source file with the given name to the package.
+    #[must_use]
+    pub fn source(
+        mut self,
+        name: impl Into<SourceName>,
+        contents: impl Into<SourceContents>,
+    ) -> Self {
+        self.sources.push((name.into(), contents.into()));
+        self
+    }
+
+    /// Sets the entry expression of the package.
+    #[must_use]
+    pub fn entry_expr(mut self, expr: impl Into<Arc<str>>) -> Self {
+        self.entry = Some(expr.into());
+        self
+    }
+
+    /// Adds a library package, available to the package under the given alias. Each
+    /// dependency can itself use the dependencies added before it.
+    #[must_use]
+    pub fn dependency(
+        mut self,
+        alias: impl Into<Arc<str>>,
+        sources: impl IntoIterator<Item = (SourceName, SourceContents)>,
+    ) -> Self {
+        self.dependencies
+            .push((alias.into(), sources.into_iter().collect()));
+        self
+    }
+
+    #[must_use]
+    pub fn package_type(mut self, package_type: PackageType) -> Self {
+        self.package_type = package_type;
+        self
+    }
+
+    /// Sets the target capabilities from a target profile.
+    #[must_use]
+    pub fn target_profile(self, profile: Profile) -> Self {
+        self.capabilities(profile.into())
+    }
+
+    #[must_use]
+    pub fn capabilities(mut self, capabilities: TargetCapabilityFlags) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Sets the language features, which select the language version.
+    #[must_use]
+    pub fn language_features(mut self, language_features: LanguageFeatures) -> Self {
+        self.language_features = language_features;
+        self
+    }
+
+    /// Records the time spent in each compilation phase and parsing each source file,
+    /// available from [`Compilation::timings`]. Times cover the package and its dependencies,
+    /// but not the core and standard libraries.
+    #[must_use]
+    pub fn time_phases(mut self, time_phases: bool) -> Self {
+        self.time_phases = time_phases;
+        self
+    }
+
+    /// Reports the start and end of the build to the given host events.
+    #[must_use]
+    pub fn events(mut self, events: Rc<dyn CompilerEvents>) -> Self {
+        self.events = Some(SharedEvents(events));
+        self
+    }
+
+    /// Compiles the dependencies and then the package. Errors from every package are
+    /// collected in the result rather than stopping the build.
+    #[must_use]
+    pub fn build(self) -> Compilation {
+        if let Some(SharedEvents(events)) = &self.events {
+            events.compile_started();
+        }
+        let clock = start_clock();
+        let (std_id, mut store) = package_store_with_stdlib(self.capabilities);
+        let mut dependencies = vec![(std_id, None)];
+        let mut errors = Vec::new();
+        let mut timings = self.time_phases.then(Timings::default);
+
+        for (alias, sources) in self.dependencies {
+            let (unit, mut dependency_errors) = compile_timed(
+                &store,
+                &dependencies,
+                SourceMap::new(sources, None),
+                PackageType::Lib,
+                self.capabilities,
+                self.language_features,
+                timings.as_mut(),
+            );
+            errors.append(&mut dependency_errors);
+            let package_id = store.insert(unit);
+            dependencies.push((package_id, Some(alias)));
+        }
+
+        let (unit, mut package_errors) = compile_timed(
+            &store,
+            &dependencies,
+            SourceMap::new(self.sources, self.entry),
+            self.package_type,
+            self.capabilities,
+            self.language_features,
+            timings.as_mut(),
+        );
+        errors.append(&mut package_errors);
+
+        if let Some(SharedEvents(events)) = &self.events {
+            let (error_count, warning_count) = count_by_severity(&errors);
+            events.compile_finished(&CompileStats {
+                duration: clock.map(|clock| clock.elapsed()),
+                error_count,
+                warning_count,
+            });
+        }
+
+        Compilation {
+            store,
+            dependencies,
+            unit,
+            errors,
+            timings,
+        }
+    }
+}
+
+/// A package compiled by a [`CompilerBuilder`], along with the store holding its dependencies.
+#[derive(Debug)]
+pub struct Compilation {
+    store: PackageStore,
+    dependencies: Vec<(PackageId, Option<Arc<str>>)>,
+    unit: CompileUnit,
+    errors: Vec<Error>,
+    timings: Option<Timings>,
+}
+
+impl Compilation {
+    /// The errors from compiling the package and its dependencies.
+    #[must_use]
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    #[must_use]
+    pub fn ast(&self) -> &qsc_ast::ast::Package {
+        &self.unit.ast.package
+    }
+
+    /// The resolution of each name in the AST, keyed by the node ID of the name.
+    #[must_use]
+    pub fn resolutions(&self) -> &IndexMap<NodeId, Res> {
+        &self.unit.ast.names
+    }
+
+    #[must_use]
+    pub fn hir(&self) -> &qsc_hir::hir::Package {
+        &self.unit.package
+    }
+
+    #[must_use]
+    pub fn sources(&self) -> &SourceMap {
+        &self.unit.sources
+    }
+
+    #[must_use]
+    pub fn unit(&self) -> &CompileUnit {
+        &self.unit
+    }
+
+    /// The time spent in each compilation phase, if requested with
+    /// [`CompilerBuilder::time_phases`].
+    #[must_use]
+    pub fn timings(&self) -> Option<&Timings> {
+        self.timings.as_ref()
+    }
+
+    /// The store containing the core and standard libraries and any dependencies.
+    #[must_use]
+    pub fn package_store(&self) -> &PackageStore {
+        &self.store
+    }
+
+    /// The dependencies of the package, as passed to the compiler.
+    #[must_use]
+    pub fn dependencies(&self) -> &Dependencies {
+        &self.dependencies
+    }
+
+    #[must_use]
+    pub fn into_parts(
+        self,
+    ) -> (
+        PackageStore,
+        Vec<(PackageId, Option<Arc<str>>)>,
+        CompileUnit,
+        Vec<Error>,
+    ) {
+        (self.store, self.dependencies, self.unit, self.errors)
+    }
+}
+
 /// Compiles a package from its AST representation.
 #[must_use]
 #[allow(clippy::module_name_repetitions)]
@@ -71,7 +334,7 @@ pub fn compile_ast(
         capabilities,
         vec![],
     );
-    process_compile_unit(store, package_type, unit)
+    process_compile_unit(store, package_type, unit, None)
 }
 
 /// Compiles a package from its source representation.
@@ -84,14 +347,35 @@ pub fn compile(
     capabilities: TargetCapabilityFlags,
     language_features: LanguageFeatures,
 ) -> (CompileUnit, Vec<Error>) {
-    let unit = qsc_frontend::compile::compile(
+    compile_timed(
         store,
         dependencies,
         sources,
+        package_type,
         capabilities,
         language_features,
+        None,
+    )
+}
+
+fn compile_timed(
+    store: &PackageStore,
+    dependencies: &Dependencies,
+    sources: SourceMap,
+    package_type: PackageType,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+    mut timings: Option<&mut Timings>,
+) -> (CompileUnit, Vec<Error>) {
+    let unit = qsc_frontend::compile::compile_timed(
+        store,
+        dependencies,
+        sources,
+        capabilities,
+        language_features,
+        timings.as_deref_mut(),
     );
-    process_compile_unit(store, package_type, unit)
+    process_compile_unit(store, package_type, unit, timings)
 }
 
 #[must_use]
@@ -100,6 +384,7 @@ fn process_compile_unit(
     store: &PackageStore,
     package_type: PackageType,
     mut unit: CompileUnit,
+    timings: Option<&mut Timings>,
 ) -> (CompileUnit, Vec<Error>) {
     let mut errors = Vec::new();
     for error in unit.errors.drain(..) {
@@ -107,7 +392,10 @@ fn process_compile_unit(
     }
 
     if errors.is_empty() {
-        for error in run_default_passes(store.core(), &mut unit, package_type) {
+        let pass_errors = timed(timings, Phase::Passes, || {
+            run_default_passes(store.core(), &mut unit, package_type)
+        });
+        for error in pass_errors {
             errors.push(WithSource::from_map(&unit.sources, error.into()));
         }
     }
@@ -115,13 +403,31 @@ fn process_compile_unit(
     (unit, errors)
 }
 
+type StdlibStores = FxHashMap<TargetCapabilityFlags, (PackageId, PackageStore)>;
+
+static STDLIB_STORES: OnceLock<Mutex<StdlibStores>> = OnceLock::new();
+
+/// Returns a package store containing the core and standard libraries compiled for the given
+/// capabilities. The libraries are compiled once per process for each set of capabilities, and
+/// later calls, from any thread, return a cheap clone of that store.
+///
+/// Callers that ask for capabilities that haven't been compiled yet wait while the libraries are
+/// compiled, so concurrent first calls compile them only once.
 #[must_use]
 pub fn package_store_with_stdlib(
     capabilities: TargetCapabilityFlags,
 ) -> (qsc_hir::hir::PackageId, PackageStore) {
-    let mut store = PackageStore::new(core());
-    let std_id = store.insert(std(&store, capabilities));
-    (std_id, store)
+    STDLIB_STORES
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(capabilities)
+        .or_insert_with(|| {
+            let mut store = PackageStore::new(core());
+            let std_id = store.insert(std(&store, capabilities));
+            (std_id, store)
+        })
+        .clone()
 }
 
 /// Compiles the core library.
@@ -0,0 +1,267 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{CompilerBuilder, Phase, package_store_with_stdlib};
+use crate::{CompileUnit, PackageType, TargetCapabilityFlags};
+use miette::Diagnostic;
+use qsc_data_structures::target::Profile;
+use qsc_frontend::resolve::Res;
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+#[test]
+fn builder_compiles_sources() {
+    let compilation = CompilerBuilder::new()
+        .source("Main.qs", "function Answer() : Int { 42 }")
+        .build();
+
+    assert!(
+        compilation.errors().is_empty(),
+        "{:#?}",
+        compilation.errors()
+    );
+    assert!(compilation.sources().find_by_name("Main.qs").is_some());
+    assert_eq!(compilation.ast().nodes.len(), 1);
+    assert!(!compilation.hir().items.is_empty());
+}
+
+#[test]
+fn builder_resolves_names_into_std() {
+    let compilation = CompilerBuilder::new()
+        .source("Main.qs", "function Foo() : Int { Std.Math.AbsI(-1) }")
+        .build();
+
+    assert!(
+        compilation.errors().is_empty(),
+        "{:#?}",
+        compilation.errors()
+    );
+    assert!(
+        compilation
+            .resolutions()
+            .values()
+            .any(|res| matches!(res, Res::Item(id, _) if id.package.is_some()))
+    );
+}
+
+#[test]
+fn builder_reports_errors() {
+    let compilation = CompilerBuilder::new()
+        .source("Main.qs", "function Foo() : Int { Bar() }")
+        .build();
+
+    assert_eq!(compilation.errors().len(), 1);
+}
+
+#[derive(Default)]
+struct RecordedEvents(RefCell<Vec<String>>);
+
+impl crate::events::CompilerEvents for RecordedEvents {
+    fn compile_started(&self) {
+        self.0.borrow_mut().push("started".to_string());
+    }
+
+    fn compile_finished(&self, stats: &crate::events::CompileStats) {
+        self.0.borrow_mut().push(format!(
+            "finished with {} errors and {} warnings",
+            stats.error_count, stats.warning_count
+        ));
+    }
+}
+
+#[test]
+fn builder_reports_events() {
+    let events = Rc::new(RecordedEvents::default());
+    let compilation = CompilerBuilder::new()
+        .source("Main.qs", "function Foo() : Int { Bar() }")
+        .events(events.clone())
+        .build();
+
+    assert_eq!(compilation.errors().len(), 1);
+    assert_eq!(
+        *events.0.borrow(),
+        ["started", "finished with 1 errors and 0 warnings"]
+    );
+}
+
+#[test]
+fn builder_uses_dependencies_by_alias() {
+    let compilation = CompilerBuilder::new()
+        .dependency(
+            "MyLib",
+            [(
+                Arc::from("Lib.qs"),
+                Arc::from("namespace Helpers { function Answer() : Int { 42 } export Answer; }"),
+            )],
+        )
+        .source(
+            "Main.qs",
+            "@EntryPoint() function Main() : Int { MyLib.Helpers.Answer() }",
+        )
+        .package_type(PackageType::Exe)
+        .build();
+
+    assert!(
+        compilation.errors().is_empty(),
+        "{:#?}",
+        compilation.errors()
+    );
+    assert_eq!(compilation.dependencies().len(), 2);
+}
+
+#[test]
+fn builder_resolves_re_exports_from_dependency_facade() {
+    let compilation = CompilerBuilder::new()
+        .dependency(
+            "MyLib",
+            [(
+                Arc::from("Lib.qs"),
+                Arc::from(
+                    "namespace Internal.Math { function Answer() : Int { 42 } }
+                    namespace Facade { export Internal.Math.Answer, Internal.Math.Answer as Reply; }",
+                ),
+            )],
+        )
+        .source(
+            "Main.qs",
+            "@EntryPoint() function Main() : Int { MyLib.Facade.Answer() + MyLib.Facade.Reply() }",
+        )
+        .package_type(PackageType::Exe)
+        .build();
+
+    assert!(
+        compilation.errors().is_empty(),
+        "{:#?}",
+        compilation.errors()
+    );
+}
+
+#[test]
+fn builder_type_checks_against_intrinsic_only_dependency() {
+    let vendor = || {
+        [(
+            Arc::from("Vendor.qs"),
+            Arc::from(
+                "namespace Instructions {
+                    operation Rzz(theta : Double, q0 : Qubit, q1 : Qubit) : Unit { body intrinsic; }
+                    export Rzz;
+                }",
+            ),
+        )]
+    };
+    let compile = |body: &str| {
+        CompilerBuilder::new()
+            .dependency("Vendor", vendor())
+            .source(
+                "Main.qs",
+                format!("operation Main() : Unit {{ use (a, b) = (Qubit(), Qubit()); {body} }}"),
+            )
+            .build()
+    };
+
+    let valid = compile("Vendor.Instructions.Rzz(1.0, a, b);");
+    assert!(valid.errors().is_empty(), "{:#?}", valid.errors());
+
+    let invalid = compile("Vendor.Instructions.Rzz(1, a, b);");
+    assert_eq!(
+        invalid
+            .errors()
+            .iter()
+            .filter_map(|error| error.code().map(|code| code.to_string()))
+            .collect::<Vec<_>>(),
+        ["Qsc.TypeCk.TyMismatch"]
+    );
+}
+
+#[test]
+fn builder_applies_target_profile() {
+    let source = "@Config(Adaptive) function Foo() : Unit {} function Bar() : Unit { Foo() }";
+    let unrestricted = CompilerBuilder::new().source("Main.qs", source).build();
+    let base = CompilerBuilder::new()
+        .source("Main.qs", source)
+        .target_profile(Profile::Base)
+        .build();
+
+    assert!(
+        unrestricted.errors().is_empty(),
+        "{:#?}",
+        unrestricted.errors()
+    );
+    assert_eq!(base.errors().len(), 1);
+}
+
+#[test]
+fn stdlib_store_is_shared_between_calls() {
+    let (std_id, first) = package_store_with_stdlib(TargetCapabilityFlags::all());
+    let (second_std_id, mut second) = package_store_with_stdlib(TargetCapabilityFlags::all());
+    assert_eq!(std_id, second_std_id);
+    assert!(std::ptr::eq(
+        first.get(std_id).expect("std should be in store"),
+        second.get(std_id).expect("std should be in store"),
+    ));
+
+    let id = second.insert(CompileUnit::default());
+    assert!(second.get(id).is_some());
+    assert!(first.get(id).is_none());
+}
+
+#[test]
+fn stdlib_store_is_shared_between_threads() {
+    let (std_id, store) = package_store_with_stdlib(TargetCapabilityFlags::all());
+    let (other_std_id, other_store) =
+        std::thread::spawn(|| package_store_with_stdlib(TargetCapabilityFlags::all()))
+            .join()
+            .expect("thread should not panic");
+    assert_eq!(std_id, other_std_id);
+    assert!(std::ptr::eq(
+        store.get(std_id).expect("std should be in store"),
+        other_store.get(std_id).expect("std should be in store"),
+    ));
+}
+
+#[test]
+fn builder_records_phase_timings_when_requested() {
+    let compilation = CompilerBuilder::new()
+        .dependency(
+            "MyLib",
+            [(
+                Arc::from("Lib.qs"),
+                Arc::from("function Helper() : Int { 1 }"),
+            )],
+        )
+        .source("Main.qs", "function Answer() : Int { 42 }")
+        .time_phases(true)
+        .build();
+
+    let timings = compilation.timings().expect("timings should be recorded");
+    assert_eq!(
+        timings
+            .phases()
+            .iter()
+            .map(|(phase, _)| *phase)
+            .collect::<Vec<_>>(),
+        [
+            Phase::Parse,
+            Phase::Resolve,
+            Phase::Typeck,
+            Phase::Lower,
+            Phase::Passes
+        ]
+    );
+    assert_eq!(
+        timings
+            .files()
+            .iter()
+            .map(|(name, _)| name.as_ref())
+            .collect::<Vec<_>>(),
+        ["Lib.qs", "Main.qs"]
+    );
+}
+
+#[test]
+fn builder_does_not_record_timings_by_default() {
+    let compilation = CompilerBuilder::new()
+        .source("Main.qs", "function Answer() : Int { 42 }")
+        .build();
+
+    assert!(compilation.timings().is_none());
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Rendering of diagnostics as JSON, for build systems and editors that consume compiler output
+//! without a language server.
+//!
+//! The output is an array with one object per diagnostic:
+//!
+//! ```json
+//! [{
+//!   "code": "Qsc.Resolve.NotFound",
+//!   "severity": "error",
+//!   "message": "`Bar` not found",
+//!   "file": "Main.qs",
+//!   "range": { "start": { "line": 0, "column": 23 }, "end": { "line": 0, "column": 26 } },
+//!   "related": [{ "file": "Main.qs", "range": { ... }, "message": "..." }],
+//!   "help": "..."
+//! }]
+//! ```
+//!
+//! `severity` is one of `error`, `warning` or `advice`. Lines and columns are zero-based, with
+//! columns counted in UTF-16 code units as in the language server protocol. `code`, `file`,
+//! `range` and `help` are omitted when the diagnostic has none, and `related` is omitted when
+//! empty. The related spans are the labels other than the primary one, and `message` is omitted
+//! from a related span that has no label text.
+
+#[cfg(test)]
+mod tests;
+
+use crate::error::WithSource;
+use miette::{Diagnostic, LabeledSpan, Severity};
+use qsc_data_structures::line_column::{Encoding, Position};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct JsonDiagnostic {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    severity: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    range: Option<JsonRange>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related: Vec<Related>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Related {
+    file: String,
+    range: JsonRange,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonRange {
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+#[derive(Serialize)]
+struct JsonPosition {
+    line: u32,
+    column: u32,
+}
+
+impl From<Position> for JsonPosition {
+    fn from(position: Position) -> Self {
+        Self {
+            line: position.line,
+            column: position.column,
+        }
+    }
+}
+
+/// Renders the diagnostics as a JSON array, in the schema described by the module documentation.
+#[must_use]
+pub fn to_json<E: Diagnostic + Send + Sync>(diagnostics: &[WithSource<E>]) -> String {
+    let diagnostics = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut labels = diagnostic
+                .labels()
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            if let Some(primary) = labels.iter().position(LabeledSpan::primary) {
+                labels[..=primary].rotate_right(1);
+            }
+            let mut labels = labels.iter().map(|label| resolve(diagnostic, label));
+            let primary = labels.next();
+
+            JsonDiagnostic {
+                code: diagnostic.code().map(|code| code.to_string()),
+                severity: match diagnostic.severity() {
+                    None | Some(Severity::Error) => "error",
+                    Some(Severity::Warning) => "warning",
+                    Some(Severity::Advice) => "advice",
+                },
+                message: diagnostic.to_string(),
+                related: labels.collect(),
+                file: primary.as_ref().map(|primary| primary.file.clone()),
+                range: primary.map(|primary| primary.range),
+                help: diagnostic.help().map(|help| help.to_string()),
+            }
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&diagnostics).expect("diagnostics should serialize to JSON")
+}
+
+fn resolve<E: Diagnostic + Send + Sync>(
+    diagnostic: &WithSource<E>,
+    label: &LabeledSpan,
+) -> Related {
+    let (source, span) = diagnostic.resolve_span(label.inner());
+    let position = |offset: usize| {
+        let offset = u32::try_from(offset).expect("offset should fit into u32");
+        Position::from_utf8_byte_offset(Encoding::Utf16, &source.contents, offset).into()
+    };
+    Related {
+        file: source.name.to_string(),
+        range: JsonRange {
+            start: position(span.offset()),
+            end: position(span.offset() + span.len()),
+        },
+        message: label.label().map(ToString::to_string),
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::to_json;
+use crate::compile::CompilerBuilder;
+use serde_json::{Value, json};
+
+fn json_for(source: &str) -> Value {
+    let compilation = CompilerBuilder::new().source("Main.qs", source).build();
+    serde_json::from_str(&to_json(compilation.errors())).expect("output should be valid JSON")
+}
+
+#[test]
+fn compile_error_is_reported_with_code_and_range() {
+    let diagnostics = json_for("function Foo() : Int { Bar() }");
+
+    assert_eq!(
+        diagnostics,
+        json!([{
+            "code": "Qsc.Resolve.NotFound",
+            "severity": "error",
+            "message": "`Bar` not found",
+            "file": "Main.qs",
+            "range": {
+                "start": { "line": 0, "column": 23 },
+                "end": { "line": 0, "column": 26 }
+            }
+        }])
+    );
+}
+
+#[test]
+fn columns_are_counted_in_utf16_code_units() {
+    let diagnostics = json_for("function Foo() : Int {\n    let s = \"🙂\"; Bar()\n}");
+
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({ "start": { "line": 1, "column": 18 }, "end": { "line": 1, "column": 21 } })
+    );
+}
+
+#[test]
+fn no_diagnostics_produce_empty_array() {
+    assert_eq!(json_for("function Foo() : Int { 42 }"), json!([]));
+}
@@ -0,0 +1,83 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Hooks for hosts to observe compilation and evaluation, for example to report telemetry.
+//! The compiler only calls the hooks, so what is done with the data is up to the host.
+
+use std::{
+    fmt,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// Callbacks that hosts implement to be told about compilation and evaluation. Every callback
+/// does nothing by default, so hosts only implement the ones they need.
+pub trait CompilerEvents {
+    /// Called before a package, or a fragment in an interpreter, is compiled.
+    fn compile_started(&self) {}
+
+    /// Called after a package, or a fragment in an interpreter, is compiled.
+    fn compile_finished(&self, _stats: &CompileStats) {}
+
+    /// Called before an interpreter starts evaluating.
+    fn eval_started(&self) {}
+
+    /// Called after an interpreter finishes evaluating, whether or not evaluation succeeded.
+    fn eval_finished(&self, _stats: &EvalStats) {}
+}
+
+/// Statistics about a compilation, passed to [`CompilerEvents::compile_finished`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompileStats {
+    /// The time the compilation took, or `None` on platforms without a clock.
+    pub duration: Option<Duration>,
+    /// The number of diagnostics with error severity.
+    pub error_count: usize,
+    /// The number of diagnostics with warning severity.
+    pub warning_count: usize,
+}
+
+/// Statistics about an evaluation, passed to [`CompilerEvents::eval_finished`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EvalStats {
+    /// The time the evaluation took, or `None` on platforms without a clock.
+    pub duration: Option<Duration>,
+    /// Whether the evaluation completed without a runtime error.
+    pub succeeded: bool,
+    /// The number of calls to intrinsic callables, across all shots.
+    pub intrinsic_count: u64,
+    /// The number of qubits allocated, across all shots.
+    pub qubit_count: u64,
+    /// The number of shots requested, which is 1 for anything but a multi-shot run.
+    pub shot_count: u32,
+}
+
+/// A shared handle to the events of a host, which can be stored in types that derive `Debug`.
+#[derive(Clone)]
+pub(crate) struct SharedEvents(pub Rc<dyn CompilerEvents>);
+
+impl fmt::Debug for SharedEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CompilerEvents")
+    }
+}
+
+/// Starts measuring a duration, if the platform has a clock.
+pub(crate) fn start_clock() -> Option<Instant> {
+    (!cfg!(target_arch = "wasm32")).then(Instant::now)
+}
+
+/// Counts the diagnostics with error and warning severity, in that order.
+pub(crate) fn count_by_severity<'a, D: miette::Diagnostic + 'a>(
+    diagnostics: impl IntoIterator<Item = &'a D>,
+) -> (usize, usize) {
+    diagnostics
+        .into_iter()
+        .fold((0, 0), |(errors, warnings), diagnostic| {
+            match diagnostic.severity() {
+                Some(miette::Severity::Warning) => (errors, warnings + 1),
+                Some(miette::Severity::Advice) => (errors, warnings),
+                Some(miette::Severity::Error) | None => (errors + 1, warnings),
+            }
+        })
+}
@@ -0,0 +1,42 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Extended explanations of diagnostic codes, with an example of the error and how to fix it.
+//! The text of each explanation is compiled in from `explain/<code>.md`.
+
+#[cfg(test)]
+mod tests;
+
+const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "Qsc.BorrowCk.Mutability",
+        include_str!("explain/Qsc.BorrowCk.Mutability.md"),
+    ),
+    (
+        "Qsc.Resolve.Ambiguous",
+        include_str!("explain/Qsc.Resolve.Ambiguous.md"),
+    ),
+    (
+        "Qsc.Resolve.NotFound",
+        include_str!("explain/Qsc.Resolve.NotFound.md"),
+    ),
+    (
+        "Qsc.TypeCk.TyMismatch",
+        include_str!("explain/Qsc.TypeCk.TyMismatch.md"),
+    ),
+];
+
+/// Returns the explanation of the diagnostic code, if there is one. Codes are matched without
+/// regard to case.
+#[must_use]
+pub fn explanation(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(explained, _)| explained.eq_ignore_ascii_case(code))
+        .map(|&(_, text)| text)
+}
+
+/// Returns the diagnostic codes that have an explanation, in sorted order.
+pub fn codes() -> impl Iterator<Item = &'static str> {
+    EXPLANATIONS.iter().map(|&(code, _)| code)
+}
@@ -0,0 +1,53 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{EXPLANATIONS, codes, explanation};
+use crate::compile::CompilerBuilder;
+use miette::Diagnostic;
+
+/// Returns the first `qsharp` code block of the explanation, which shows the error.
+fn erroneous_example(text: &str) -> &str {
+    let start = text
+        .find("```qsharp\n")
+        .expect("explanation should have an example")
+        + "```qsharp\n".len();
+    let end = start + text[start..].find("```").expect("example should be closed");
+    &text[start..end]
+}
+
+#[test]
+fn codes_are_sorted_and_unique() {
+    let codes = codes().collect::<Vec<_>>();
+    let mut sorted = codes.clone();
+    sorted.sort_unstable();
+    sorted.dedup();
+    assert_eq!(codes, sorted);
+}
+
+#[test]
+fn lookup_ignores_case() {
+    assert_eq!(
+        explanation("qsc.resolve.notfound"),
+        explanation("Qsc.Resolve.NotFound")
+    );
+    assert!(explanation("Qsc.Resolve.NotFound").is_some());
+    assert!(explanation("Qsc.NoSuchCode").is_none());
+}
+
+#[test]
+fn each_example_reports_its_code() {
+    for &(code, text) in EXPLANATIONS {
+        let compilation = CompilerBuilder::new()
+            .source("Example.qs", erroneous_example(text))
+            .build();
+        let reported = compilation
+            .errors()
+            .iter()
+            .filter_map(|error| error.code().map(|code| code.to_string()))
+            .collect::<Vec<_>>();
+        assert!(
+            reported.iter().any(|reported| reported == code),
+            "example for {code} reported {reported:?}"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::{language_features::LanguageFeatures, target::Profile};
+use serde::Serialize;
+
+/// The target profiles, language features and backends supported by this build of the
+/// compiler, so hosts can check compatibility at startup.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Features {
+    /// The compiler version.
+    pub version: &'static str,
+    /// The version of the standard library. The library is compiled into the compiler, so this
+    /// always matches the compiler version.
+    pub std_version: &'static str,
+    /// The opt-in language features, by the names used in `qsharp.json`.
+    pub language_features: Vec<String>,
+    /// The target profiles, by their canonical names.
+    pub target_profiles: Vec<&'static str>,
+    /// The backends programs can be run or compiled on.
+    pub backends: Vec<&'static str>,
+}
+
+impl Features {
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("features should serialize to JSON")
+    }
+}
+
+#[must_use]
+pub fn features() -> Features {
+    Features {
+        version: env!("CARGO_PKG_VERSION"),
+        std_version: env!("CARGO_PKG_VERSION"),
+        language_features: LanguageFeatures::all().into(),
+        target_profiles: [
+            Profile::Unrestricted,
+            Profile::Base,
+            Profile::AdaptiveRI,
+            Profile::AdaptiveRIF,
+        ]
+        .iter()
+        .map(Profile::to_str)
+        .collect(),
+        backends: vec![
+            "sparse-simulator",
+            "noisy-simulator",
+            "circuit-tracer",
+            "qir",
+        ],
+    }
+}
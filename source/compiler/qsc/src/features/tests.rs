@@ -0,0 +1,42 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::features;
+use serde_json::{Value, json};
+
+#[test]
+fn features_list_profiles_language_features_and_backends() {
+    let features = features();
+
+    assert_eq!(features.std_version, features.version);
+    assert_eq!(
+        features.language_features,
+        ["v2-preview-syntax", "script-mode", "match-expressions"]
+    );
+    assert_eq!(
+        features.target_profiles,
+        ["Unrestricted", "Base", "Adaptive_RI", "Adaptive_RIF"]
+    );
+}
+
+#[test]
+fn features_json_uses_camel_case_keys() {
+    let json: Value =
+        serde_json::from_str(&features().to_json()).expect("features should be valid JSON");
+
+    assert_eq!(json["version"], json!(env!("CARGO_PKG_VERSION")));
+    assert_eq!(json["stdVersion"], json["version"]);
+    assert_eq!(
+        json["languageFeatures"],
+        json!(["v2-preview-syntax", "script-mode", "match-expressions"])
+    );
+    assert_eq!(
+        json["backends"],
+        json!([
+            "sparse-simulator",
+            "noisy-simulator",
+            "circuit-tracer",
+            "qir"
+        ])
+    );
+}
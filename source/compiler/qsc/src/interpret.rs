@@ -11,10 +11,11 @@ mod package_tests;
 #[cfg(test)]
 mod tests;
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, str::FromStr, sync::Arc};
 
 use crate::{
     error::{self, WithStack},
+    events::{CompileStats, CompilerEvents, EvalStats, count_by_severity, start_clock},
     incremental::Compiler,
     location::Location,
 };
@@ -37,15 +38,18 @@ use qsc_data_structures::{
     target::TargetCapabilityFlags,
 };
 use qsc_eval::{
-    Env, ErrorBehavior, State, VariableInfo,
-    backend::{Backend, SparseSim, TracingBackend},
+    Env, ErrorBehavior, EvalCounts, State, VariableInfo,
+    backend::{Backend, SimulatorStats, SparseSim, Tolerance, TracingBackend},
+    intrinsic::HostIntrinsics,
     output::Receiver,
 };
 pub use qsc_eval::{
-    StepAction, StepResult,
-    debug::Frame,
+    QubitInfo, ScopeInfo, StepAction, StepResult,
+    debug::{Frame, QubitWatchKind},
+    histogram::Histogram,
     noise::PauliNoise,
     output::{self, GenericReceiver},
+    trace::{ExecutionTrace, TraceCallback, TraceEvent, TraceOp, TraceSink},
     val::Closure,
     val::Range as ValueRange,
     val::Result,
@@ -71,7 +75,7 @@ use qsc_lowerer::{
 use qsc_partial_eval::ProgramEntry;
 use qsc_passes::{PackageType, PassContext};
 use qsc_rca::PackageStoreComputeProperties;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use thiserror::Error;
 
 impl Error {
@@ -114,6 +118,12 @@ pub enum Error {
     #[error("partial evaluation error")]
     #[diagnostic(transparent)]
     PartialEvaluation(#[from] WithSource<qsc_partial_eval::Error>),
+    #[error("qubit {0} is not allocated")]
+    #[diagnostic(code("Qsc.Interpret.QubitNotAllocated"))]
+    QubitNotAllocated(usize),
+    #[error("qubit {0} appears more than once")]
+    #[diagnostic(code("Qsc.Interpret.DuplicateQubit"))]
+    DuplicateQubit(usize),
 }
 
 /// A Q# interpreter.
@@ -155,14 +165,33 @@ pub struct Interpreter {
     /// The classical seed, if any. This needs to be passed to the evaluator for use in intrinsic
     /// calls that produce classical random numbers.
     classical_seed: Option<u64>,
+    /// The tolerance for diagnostic checks such as `CheckZero`, cached here so that it can be
+    /// applied to each new instance of the simulator.
+    tolerance: Tolerance,
     /// The evaluator environment.
     env: Env,
     /// The execution graph configuration to use for evaluation.
     eval_config: ExecGraphConfig,
+    /// Host-provided implementations for intrinsic callables, keyed by fully qualified name.
+    host_intrinsics: HostIntrinsics,
+    /// The maximum depth of nested calls during evaluation, if any.
+    max_call_depth: Option<usize>,
+    /// Warnings from compiling the fragments most recently evaluated, not yet taken.
+    warnings: Vec<Error>,
+    /// The host's hooks for compilation and evaluation events, if any.
+    events: Option<Rc<dyn CompilerEvents>>,
 }
 
 pub type InterpretResult = std::result::Result<Value, Vec<Error>>;
 
+/// A snapshot of the simulator state and the classical environment of an [`Interpreter`],
+/// taken with [`Interpreter::checkpoint`] and restored with [`Interpreter::restore`].
+#[derive(Clone)]
+pub struct Checkpoint {
+    sim: SparseSim,
+    env: Env,
+}
+
 /// Indicates whether an UDT is an `OpenQASM` `Angle` or a `Complex` number.
 /// This information is needed in the Python interop layer to give special
 /// treatment to the instances of these UDTs.
@@ -178,8 +207,8 @@ pub enum UdtKind {
 /// An item tagged with its name and the namespace it was defined in.
 pub struct TaggedItem {
     pub item_id: qsc_hir::hir::ItemId,
-    pub name: Rc<str>,
-    pub namespace: Vec<Rc<str>>,
+    pub name: Arc<str>,
+    pub namespace: Vec<Arc<str>>,
 }
 
 #[derive(PartialEq, Eq, Copy, Clone)]
@@ -365,15 +394,20 @@ impl Interpreter {
             }),
             quantum_seed: None,
             classical_seed: None,
+            tolerance: Tolerance::default(),
             package,
             source_package: map_hir_package_to_fir(source_package_id),
             eval_config,
+            host_intrinsics: HostIntrinsics::default(),
+            max_call_depth: Some(qsc_eval::DEFAULT_MAX_CALL_DEPTH),
+            warnings: Vec::new(),
+            events: None,
         })
     }
 
     /// Given a package ID, returns all the global items in the package.
     /// Note this does not currently include re-exports.
-    fn package_globals(&self, package_id: PackageId) -> Vec<(Vec<Rc<str>>, Rc<str>, Value)> {
+    fn package_globals(&self, package_id: PackageId) -> Vec<(Vec<Arc<str>>, Arc<str>, Value)> {
         let mut exported_items = Vec::new();
         let package = &self
             .compiler
@@ -398,13 +432,13 @@ impl Interpreter {
     }
 
     /// Get the global callables defined in the user source passed into initialization of the interpreter as `Value` instances.
-    pub fn source_globals(&self) -> Vec<(Vec<Rc<str>>, Rc<str>, Value)> {
+    pub fn source_globals(&self) -> Vec<(Vec<Arc<str>>, Arc<str>, Value)> {
         self.package_globals(self.source_package)
     }
 
     /// Get the global callables defined in the open package being interpreted as `Value` instances, which will include any items
     /// defined by calls to `eval_fragments` and the like.
-    pub fn user_globals(&self) -> Vec<(Vec<Rc<str>>, Rc<str>, Value)> {
+    pub fn user_globals(&self) -> Vec<(Vec<Arc<str>>, Arc<str>, Value)> {
         self.package_globals(self.package)
     }
 
@@ -641,6 +675,43 @@ impl Interpreter {
         self.classical_seed = seed;
     }
 
+    /// Sets the numeric tolerance used by diagnostic checks such as `CheckZero` and
+    /// `CheckAllZero`, for this interpreter's simulator and any simulator it creates later.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance) {
+        self.tolerance = tolerance;
+        self.sim.tolerance = tolerance;
+    }
+
+    /// Sets the maximum depth of nested calls, or `None` for no limit. Exceeding it fails
+    /// evaluation with a diagnostic instead of exhausting memory on unbounded recursion.
+    pub fn set_max_call_depth(&mut self, max_call_depth: Option<usize>) {
+        self.max_call_depth = max_call_depth;
+    }
+
+    /// Registers a host implementation for the `body intrinsic` callable with the given
+    /// fully qualified name (for example, `Test.Foo`). Registered implementations take
+    /// precedence over the built-in intrinsics, and an `Err` returned from `f` is reported
+    /// as a runtime failure of the intrinsic.
+    pub fn register_intrinsic(
+        &mut self,
+        name: &str,
+        f: impl Fn(Value) -> std::result::Result<Value, String> + 'static,
+    ) {
+        self.host_intrinsics.register(name, f);
+    }
+
+    /// Allows the program to read and write files on the host through the `Std.IO` intrinsics,
+    /// which fail by default.
+    pub fn enable_file_io(&mut self) {
+        self.host_intrinsics.enable_file_io();
+    }
+
+    /// Allows the program to read the given command-line arguments and the host's environment
+    /// variables through the `Std.Environment` intrinsics, which fail by default.
+    pub fn enable_environment_access(&mut self, args: Vec<String>) {
+        self.host_intrinsics.enable_environment_access(args);
+    }
+
     pub fn check_source_lints(&self) -> Vec<Lint> {
         if let Some(compile_unit) = self
             .compiler
@@ -670,17 +741,22 @@ impl Interpreter {
     pub fn eval_entry(&mut self, receiver: &mut impl Receiver) -> InterpretResult {
         let graph = self.get_entry_exec_graph()?;
         self.expr_graph = Some(graph.clone());
-        eval(
-            self.source_package,
-            self.classical_seed,
-            graph,
-            self.eval_config,
-            self.compiler.package_store(),
-            &self.fir_store,
-            &mut Env::default(),
-            &mut TracingBackend::new(&mut self.sim, self.circuit_tracer.as_mut()),
-            receiver,
-        )
+        observe_eval(self.events.clone().as_deref(), 1, |counts| {
+            eval(
+                self.source_package,
+                self.classical_seed,
+                graph,
+                self.eval_config,
+                self.compiler.package_store(),
+                &self.fir_store,
+                &self.host_intrinsics,
+                self.max_call_depth,
+                &mut Env::default(),
+                &mut TracingBackend::new(&mut self.sim, self.circuit_tracer.as_mut()),
+                receiver,
+                counts,
+            )
+        })
     }
 
     /// Executes the entry expression until the end of execution, using the given simulator backend
@@ -695,17 +771,22 @@ impl Interpreter {
         if self.quantum_seed.is_some() {
             sim.set_seed(self.quantum_seed);
         }
-        eval(
-            self.source_package,
-            self.classical_seed,
-            graph,
-            self.eval_config,
-            self.compiler.package_store(),
-            &self.fir_store,
-            &mut Env::default(),
-            &mut TracingBackend::no_tracer(sim),
-            receiver,
-        )
+        observe_eval(self.events.clone().as_deref(), 1, |counts| {
+            eval(
+                self.source_package,
+                self.classical_seed,
+                graph,
+                self.eval_config,
+                self.compiler.package_store(),
+                &self.fir_store,
+                &self.host_intrinsics,
+                self.max_call_depth,
+                &mut Env::default(),
+                &mut TracingBackend::no_tracer(sim),
+                receiver,
+                counts,
+            )
+        })
     }
 
     fn get_entry_exec_graph(&self) -> std::result::Result<ExecGraph, Vec<Error>> {
@@ -727,10 +808,23 @@ impl Interpreter {
     ) -> InterpretResult {
         let label = self.next_line_label();
 
-        let mut increment = self
-            .compiler
-            .compile_fragments_fail_fast(&label, fragments)
-            .map_err(into_errors)?;
+        if let Some(events) = &self.events {
+            events.compile_started();
+        }
+        let clock = start_clock();
+        let increment = self.compiler.compile_fragments_fail_fast(&label, fragments);
+        if let Some(events) = &self.events {
+            let (error_count, warning_count) = match &increment {
+                Ok(increment) => (0, increment.warnings.len()),
+                Err(errors) => count_by_severity(errors),
+            };
+            events.compile_finished(&CompileStats {
+                duration: clock.map(|clock| clock.elapsed()),
+                error_count,
+                warning_count,
+            });
+        }
+        let mut increment = increment.map_err(into_errors)?;
 
         // Clear the entry expression, as we are evaluating fragments and a fragment with a `@EntryPoint` attribute
         // should not change what gets executed.
@@ -761,11 +855,27 @@ impl Interpreter {
         self.eval_increment(receiver, increment)
     }
 
+    /// Sets the hooks that are told when fragments are compiled and when evaluation starts and
+    /// finishes.
+    pub fn set_events(&mut self, events: Rc<dyn CompilerEvents>) {
+        self.events = Some(events);
+    }
+
+    /// Takes the warnings from compiling the fragments most recently evaluated, such as callables
+    /// that replace ones declared in earlier fragments.
+    pub fn take_warnings(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.warnings)
+    }
+
     fn eval_increment(
         &mut self,
         receiver: &mut impl Receiver,
-        increment: Increment,
+        mut increment: Increment,
     ) -> InterpretResult {
+        self.warnings = std::mem::take(&mut increment.warnings)
+            .into_iter()
+            .map(|warning| Error::Compile(warning.into_with_source()))
+            .collect();
         let (graph, _) = self.lower(&increment)?;
         self.expr_graph = Some(graph.clone());
 
@@ -777,17 +887,22 @@ impl Interpreter {
         // here to keep the package stores consistent.
         self.compiler.update(increment);
 
-        eval(
-            self.package,
-            self.classical_seed,
-            graph,
-            self.eval_config,
-            self.compiler.package_store(),
-            &self.fir_store,
-            &mut self.env,
-            &mut TracingBackend::new(&mut self.sim, self.circuit_tracer.as_mut()),
-            receiver,
-        )
+        observe_eval(self.events.clone().as_deref(), 1, |counts| {
+            eval(
+                self.package,
+                self.classical_seed,
+                graph,
+                self.eval_config,
+                self.compiler.package_store(),
+                &self.fir_store,
+                &self.host_intrinsics,
+                self.max_call_depth,
+                &mut self.env,
+                &mut TracingBackend::new(&mut self.sim, self.circuit_tracer.as_mut()),
+                receiver,
+                counts,
+            )
+        })
     }
 
     /// Invokes the given callable with the given arguments using the current environment, simulator, and compilation.
@@ -797,24 +912,29 @@ impl Interpreter {
         callable: Value,
         args: Value,
     ) -> InterpretResult {
-        qsc_eval::invoke(
-            self.package,
-            self.classical_seed,
-            &self.fir_store,
-            self.eval_config,
-            &mut self.env,
-            &mut TracingBackend::new(&mut self.sim, self.circuit_tracer.as_mut()),
-            receiver,
-            callable,
-            args,
-        )
-        .map_err(|(error, call_stack)| {
-            eval_error(
-                self.compiler.package_store(),
+        observe_eval(self.events.clone().as_deref(), 1, |counts| {
+            qsc_eval::invoke(
+                self.package,
+                self.classical_seed,
                 &self.fir_store,
-                call_stack,
-                error,
+                &self.host_intrinsics,
+                self.max_call_depth,
+                self.eval_config,
+                &mut self.env,
+                &mut TracingBackend::new(&mut self.sim, self.circuit_tracer.as_mut()),
+                receiver,
+                callable,
+                args,
+                counts,
             )
+            .map_err(|(error, call_stack)| {
+                eval_error(
+                    self.compiler.package_store(),
+                    &self.fir_store,
+                    call_stack,
+                    error,
+                )
+            })
         })
     }
 
@@ -832,12 +952,41 @@ impl Interpreter {
             Some(noise) => SparseSim::new_with_noise(&noise),
             None => SparseSim::new(),
         };
+        sim.tolerance = self.tolerance;
         if let Some(loss) = qubit_loss {
             sim.set_loss(loss);
         }
         self.invoke_with_sim(&mut sim, receiver, callable, args)
     }
 
+    /// Invokes the given callable once for each set of `Double` parameters, each time with a fresh
+    /// environment and simulator but reusing the current compilation. A parameter set with a single
+    /// value is passed as a `Double`, otherwise as a tuple of `Double`s. Results are returned in
+    /// the order of the parameter sets, paired with the parameters that produced them.
+    pub fn invoke_sweep(
+        &mut self,
+        receiver: &mut impl Receiver,
+        callable: &Value,
+        parameter_sets: &[Vec<f64>],
+        noise: Option<PauliNoise>,
+        qubit_loss: Option<f64>,
+    ) -> Vec<(Vec<f64>, InterpretResult)> {
+        parameter_sets
+            .iter()
+            .map(|parameters| {
+                let args = match parameters.as_slice() {
+                    [parameter] => Value::Double(*parameter),
+                    parameters => {
+                        Value::Tuple(parameters.iter().map(|p| Value::Double(*p)).collect(), None)
+                    }
+                };
+                let result =
+                    self.invoke_with_noise(receiver, callable.clone(), args, noise, qubit_loss);
+                (parameters.clone(), result)
+            })
+            .collect()
+    }
+
     /// Runs the given entry expression on a new instance of the environment and simulator,
     /// but using the current compilation.
     pub fn run(
@@ -851,17 +1000,198 @@ impl Interpreter {
             Some(noise) => SparseSim::new_with_noise(&noise),
             None => SparseSim::new(),
         };
+        sim.tolerance = self.tolerance;
         if let Some(loss) = qubit_loss {
             sim.set_loss(loss);
         }
         self.run_with_sim(&mut sim, receiver, expr)
     }
 
+    /// Runs the given entry expression, or the entry point if none is given, on a new instance of
+    /// the simulator while collecting its memory usage statistics.
+    pub fn run_with_stats(
+        &mut self,
+        receiver: &mut impl Receiver,
+        expr: Option<&str>,
+    ) -> (InterpretResult, SimulatorStats) {
+        let mut sim = SparseSim::new();
+        sim.tolerance = self.tolerance;
+        sim.collect_stats();
+        let result = self.run_with_sim(&mut sim, receiver, expr);
+        (result, sim.stats.unwrap_or_default())
+    }
+
+    /// Runs the given entry expression, or the entry point if none is given, on a new instance of
+    /// the simulator while recording every quantum operation it performs.
+    pub fn run_with_trace(
+        &mut self,
+        receiver: &mut impl Receiver,
+        expr: Option<&str>,
+    ) -> (InterpretResult, ExecutionTrace) {
+        let (result, events) = self.run_with_trace_sink(receiver, expr, Vec::new());
+        (result, ExecutionTrace::with_sink(events))
+    }
+
+    /// Runs the given entry expression, or the entry point if none is given, on a new instance of
+    /// the simulator while passing every quantum operation it performs to the given sink as soon
+    /// as it is performed. Returns the sink once execution finishes.
+    pub fn run_with_trace_sink<S: TraceSink>(
+        &mut self,
+        receiver: &mut impl Receiver,
+        expr: Option<&str>,
+        sink: S,
+    ) -> (InterpretResult, S) {
+        let mut sim = SparseSim::new();
+        sim.tolerance = self.tolerance;
+        let mut trace = ExecutionTrace::with_sink(sink);
+        let result = self.run_with_tracing_backend(
+            &mut TracingBackend::new(&mut sim, Some(&mut trace)),
+            receiver,
+            expr,
+            self.eval_config,
+        );
+        (result, trace.into_sink())
+    }
+
+    /// Gets the location in user code that performed a traced operation, if any.
+    #[must_use]
+    pub fn get_trace_location(
+        &self,
+        event: &TraceEvent,
+        position_encoding: Encoding,
+    ) -> Option<Location> {
+        event
+            .location(&[self.package, self.source_package])
+            .map(|(package, span)| {
+                Location::from(
+                    span,
+                    map_fir_package_to_hir(package),
+                    self.compiler.package_store(),
+                    position_encoding,
+                )
+            })
+    }
+
+    /// Runs the given entry expression, or the entry point if none is given, for the given number
+    /// of shots on a single new instance of the simulator, and aggregates the results into a
+    /// histogram.
+    /// # Errors
+    /// Returns the errors from the first shot that fails.
+    pub fn run_shots(
+        &mut self,
+        receiver: &mut impl Receiver,
+        expr: Option<&str>,
+        shots: u32,
+        noise: Option<PauliNoise>,
+        qubit_loss: Option<f64>,
+    ) -> std::result::Result<Histogram, Vec<Error>> {
+        let mut sim = match noise {
+            Some(noise) => SparseSim::new_with_noise(&noise),
+            None => SparseSim::new(),
+        };
+        sim.tolerance = self.tolerance;
+        if let Some(loss) = qubit_loss {
+            sim.set_loss(loss);
+        }
+        // Seed the simulator once so that the shots are not all identical.
+        if self.quantum_seed.is_some() {
+            sim.set_seed(self.quantum_seed);
+        }
+        let (package_id, graph) = if let Some(expr) = expr {
+            let (graph, _) = self.compile_entry_expr(expr)?;
+            (self.package, graph)
+        } else {
+            (self.source_package, self.get_entry_exec_graph()?)
+        };
+
+        observe_eval(self.events.clone().as_deref(), shots, |counts| {
+            let mut histogram = Histogram::new();
+            for shot in 0..shots {
+                // Offset the classical seed per shot so that classical randomness also varies
+                // between shots.
+                let classical_seed = self
+                    .classical_seed
+                    .map(|seed| seed.wrapping_add(u64::from(shot)));
+                let value = eval(
+                    package_id,
+                    classical_seed,
+                    graph.clone(),
+                    self.eval_config,
+                    self.compiler.package_store(),
+                    &self.fir_store,
+                    &self.host_intrinsics,
+                    self.max_call_depth,
+                    &mut Env::default(),
+                    &mut TracingBackend::no_tracer(&mut sim),
+                    receiver,
+                    counts,
+                )?;
+                histogram.record(value);
+            }
+            Ok(histogram)
+        })
+    }
+
     /// Gets the current quantum state of the simulator.
     pub fn get_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
         self.sim.capture_quantum_state()
     }
 
+    /// Gets the scopes of the evaluator environment with the variables bound in each, from the
+    /// outermost scope to the innermost one. Compiler-generated variables are omitted.
+    #[must_use]
+    pub fn get_scopes(&self) -> Vec<ScopeInfo> {
+        user_scopes(self.env.get_scopes())
+    }
+
+    /// Gets the currently allocated qubits with their allocation locations and the names of
+    /// the variables they are bound to.
+    #[must_use]
+    pub fn get_qubits(&self) -> Vec<QubitInfo> {
+        self.env.get_qubits()
+    }
+
+    /// Takes a snapshot of the current quantum state and the values of all variables, so that
+    /// execution can later be rolled back to this point with [`Interpreter::restore`].
+    #[must_use]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            sim: self.sim.clone(),
+            env: self.env.clone(),
+        }
+    }
+
+    /// Restores the quantum state and variables captured by [`Interpreter::checkpoint`].
+    /// Items declared after the checkpoint was taken remain available.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) {
+        self.sim = checkpoint.sim.clone();
+        self.env = checkpoint.env.clone();
+    }
+
+    /// Gets the expectation value of the Pauli operator that applies each given Pauli to the
+    /// qubit with the corresponding id, for the current quantum state of the simulator.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a qubit id is not allocated or appears more than once.
+    pub fn get_pauli_expectation(&mut self, paulis: &[(fir::Pauli, usize)]) -> Result<f64, Error> {
+        let (state, qubit_count) = self.sim.capture_quantum_state();
+        let mut seen = FxHashSet::default();
+        for &(_, q) in paulis {
+            if q >= qubit_count {
+                return Err(Error::QubitNotAllocated(q));
+            }
+            if !seen.insert(q) {
+                return Err(Error::DuplicateQubit(q));
+            }
+        }
+        Ok(qsc_eval::state::pauli_expectation(
+            &state,
+            qubit_count,
+            paulis,
+        ))
+    }
+
     /// Get the current circuit representation of the program.
     pub fn get_circuit(&self) -> Circuit {
         self.circuit_tracer
@@ -1073,23 +1403,28 @@ impl Interpreter {
             tracing_backend.set_seed(self.quantum_seed);
         }
 
-        eval(
-            self.package,
-            self.classical_seed,
-            graph,
-            self.eval_config,
-            self.compiler.package_store(),
-            &self.fir_store,
-            &mut Env::default(),
-            &mut tracing_backend,
-            receiver,
-        )
+        observe_eval(self.events.clone().as_deref(), 1, |counts| {
+            eval(
+                self.package,
+                self.classical_seed,
+                graph,
+                self.eval_config,
+                self.compiler.package_store(),
+                &self.fir_store,
+                &self.host_intrinsics,
+                self.max_call_depth,
+                &mut Env::default(),
+                &mut tracing_backend,
+                receiver,
+                counts,
+            )
+        })
     }
 
     fn run_with_tracing_backend<B: Backend>(
         &mut self,
         tracing_backend: &mut TracingBackend<'_, B>,
-        out: &mut GenericReceiver,
+        out: &mut impl Receiver,
         entry_expr: Option<&str>,
         config: ExecGraphConfig,
     ) -> InterpretResult {
@@ -1104,17 +1439,22 @@ impl Interpreter {
         if self.quantum_seed.is_some() {
             tracing_backend.set_seed(self.quantum_seed);
         }
-        eval(
-            package_id,
-            self.classical_seed,
-            graph,
-            config,
-            self.compiler.package_store(),
-            &self.fir_store,
-            &mut Env::default(),
-            tracing_backend,
-            out,
-        )
+        observe_eval(self.events.clone().as_deref(), 1, |counts| {
+            eval(
+                package_id,
+                self.classical_seed,
+                graph,
+                config,
+                self.compiler.package_store(),
+                &self.fir_store,
+                &self.host_intrinsics,
+                self.max_call_depth,
+                &mut Env::default(),
+                tracing_backend,
+                out,
+                counts,
+            )
+        })
     }
 
     /// Invokes the given callable with the given arguments on the given simulator with a new instance of the environment
@@ -1143,24 +1483,29 @@ impl Interpreter {
         args: Value,
         config: ExecGraphConfig,
     ) -> InterpretResult {
-        qsc_eval::invoke(
-            self.package,
-            self.classical_seed,
-            &self.fir_store,
-            config,
-            &mut Env::default(),
-            tracing_backend,
-            receiver,
-            callable,
-            args,
-        )
-        .map_err(|(error, call_stack)| {
-            eval_error(
-                self.compiler.package_store(),
+        observe_eval(self.events.clone().as_deref(), 1, |counts| {
+            qsc_eval::invoke(
+                self.package,
+                self.classical_seed,
                 &self.fir_store,
-                call_stack,
-                error,
+                &self.host_intrinsics,
+                self.max_call_depth,
+                config,
+                &mut Env::default(),
+                tracing_backend,
+                receiver,
+                callable,
+                args,
+                counts,
             )
+            .map_err(|(error, call_stack)| {
+                eval_error(
+                    self.compiler.package_store(),
+                    &self.fir_store,
+                    call_stack,
+                    error,
+                )
+            })
         })
     }
 
@@ -1203,9 +1548,60 @@ impl Interpreter {
         }
 
         self.lower_and_update_package(unit_addition);
+        self.reload_redefined_callables(unit_addition);
         Ok((self.lowerer.take_exec_graph(), None))
     }
 
+    /// Replaces the earlier definitions of any top-level callables the increment redefines with
+    /// the new ones, so that callables compiled before the redefinition call the new body too.
+    /// Earlier definitions with a different signature are kept, since their callers were checked
+    /// against the old signature. Returns the replaced definitions so they can be restored.
+    fn reload_redefined_callables(
+        &mut self,
+        unit: &qsc_frontend::incremental::Increment,
+    ) -> Vec<(fir::LocalItemId, fir::ItemKind)> {
+        let package = self.fir_store.get_mut(self.package);
+        let mut replaced = Vec::new();
+        for hir_item in unit.hir.items.values() {
+            if hir_item.parent.is_some()
+                || !matches!(hir_item.kind, qsc_hir::hir::ItemKind::Callable(_))
+            {
+                continue;
+            }
+            let new_id = fir::LocalItemId::from(usize::from(hir_item.id));
+            let Some(fir::ItemKind::Callable(new_decl)) =
+                package.items.get(new_id).map(|item| &item.kind)
+            else {
+                continue;
+            };
+            let new_decl = new_decl.clone();
+            let input_ty = |input: PatId| package.pats.get(input).map(|pat| &pat.ty);
+            let redefined = package
+                .items
+                .iter()
+                .filter(|(id, item)| {
+                    *id != new_id
+                        && item.parent.is_none()
+                        && matches!(&item.kind, fir::ItemKind::Callable(decl)
+                            if decl.name.name == new_decl.name.name
+                                && decl.kind == new_decl.kind
+                                && decl.generics == new_decl.generics
+                                && decl.output == new_decl.output
+                                && decl.functors == new_decl.functors
+                                && input_ty(decl.input) == input_ty(new_decl.input))
+                })
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+            for id in redefined {
+                let item = package.items.get_mut(id).expect("item should exist");
+                let old_kind =
+                    std::mem::replace(&mut item.kind, fir::ItemKind::Callable(new_decl.clone()));
+                replaced.push((id, old_kind));
+            }
+        }
+        replaced
+    }
+
     fn lower_and_update_package(&mut self, unit: &qsc_frontend::incremental::Increment) {
         {
             let fir_package = self.fir_store.get_mut(self.package);
@@ -1221,6 +1617,9 @@ impl Interpreter {
         unit: &qsc_frontend::incremental::Increment,
     ) -> std::result::Result<(ExecGraph, Option<PackageStoreComputeProperties>), Vec<Error>> {
         self.lower_and_update_package(unit);
+        // Redefinitions are applied before the analysis so that the compute properties
+        // of the replaced callables describe their new bodies.
+        let replaced = self.reload_redefined_callables(unit);
 
         let cap_results =
             PassContext::run_fir_passes_on_fir(&self.fir_store, self.package, self.capabilities);
@@ -1229,6 +1628,13 @@ impl Interpreter {
             // if there are errors, convert them to interpreter errors
             // and revert the update to the lowerer/FIR store.
             let fir_package = self.fir_store.get_mut(self.package);
+            for (id, kind) in replaced {
+                fir_package
+                    .items
+                    .get_mut(id)
+                    .expect("item should exist")
+                    .kind = kind;
+            }
             self.lowerer.revert_last_increment(fir_package);
 
             let source_package = self
@@ -1319,6 +1725,19 @@ pub struct Debugger {
     position_encoding: Encoding,
     /// The current state of the evaluator.
     state: State,
+    /// The conditions of the breakpoints that only stop execution conditionally.
+    breakpoint_conditions: FxHashMap<StmtId, BreakpointCondition>,
+    /// The number of times each conditional breakpoint has been hit with its condition met.
+    hit_counts: FxHashMap<StmtId, u32>,
+    /// The compiled conditions of the conditional breakpoints, with the names and types of the
+    /// locals they were compiled against.
+    compiled_conditions: FxHashMap<StmtId, (Value, Vec<(Rc<str>, String)>)>,
+    /// The state of the evaluator and the simulator at the start of the session, from which
+    /// execution is replayed to step backwards.
+    start: (State, Checkpoint),
+    /// The positions where execution stopped before each step, with the breakpoint hit counts
+    /// at that point.
+    history: Vec<(u64, FxHashMap<StmtId, u32>)>,
 }
 
 impl Debugger {
@@ -1342,16 +1761,22 @@ impl Debugger {
         let source_package_id = interpreter.source_package;
         let unit = interpreter.fir_store.get(source_package_id);
         let entry_exec_graph = unit.entry_exec_graph.clone();
+        let state = State::new(
+            source_package_id,
+            entry_exec_graph,
+            ExecGraphConfig::Debug,
+            None,
+            ErrorBehavior::StopOnError,
+        );
         Ok(Self {
+            start: (state.clone(), interpreter.checkpoint()),
             interpreter,
             position_encoding,
-            state: State::new(
-                source_package_id,
-                entry_exec_graph,
-                ExecGraphConfig::Debug,
-                None,
-                ErrorBehavior::StopOnError,
-            ),
+            state,
+            breakpoint_conditions: FxHashMap::default(),
+            hit_counts: FxHashMap::default(),
+            compiled_conditions: FxHashMap::default(),
+            history: Vec::new(),
         })
     }
 
@@ -1359,20 +1784,30 @@ impl Debugger {
         let source_package_id = interpreter.source_package;
         let unit = interpreter.fir_store.get(source_package_id);
         let entry_exec_graph = unit.entry_exec_graph.clone();
+        let mut state = State::new(
+            source_package_id,
+            entry_exec_graph,
+            ExecGraphConfig::Debug,
+            None,
+            ErrorBehavior::StopOnError,
+        );
+        state.set_host_intrinsics(interpreter.host_intrinsics.clone());
+        state.set_max_call_depth(interpreter.max_call_depth);
         Self {
+            start: (state.clone(), interpreter.checkpoint()),
             interpreter,
             position_encoding,
-            state: State::new(
-                source_package_id,
-                entry_exec_graph,
-                ExecGraphConfig::Debug,
-                None,
-                ErrorBehavior::StopOnError,
-            ),
+            state,
+            breakpoint_conditions: FxHashMap::default(),
+            hit_counts: FxHashMap::default(),
+            compiled_conditions: FxHashMap::default(),
+            history: Vec::new(),
         }
     }
 
     /// Resumes execution with specified `StepAction`.
+    /// Breakpoints with a condition only stop execution when their condition is met, otherwise
+    /// the step carries on as if the breakpoint was not there.
     /// # Errors
     /// Returns a vector of errors if evaluating the entry point fails.
     pub fn eval_step(
@@ -1380,6 +1815,174 @@ impl Debugger {
         receiver: &mut impl Receiver,
         breakpoints: &[StmtId],
         step: StepAction,
+    ) -> std::result::Result<StepResult, Vec<Error>> {
+        let events = self.interpreter.events.clone();
+        if let Some(events) = &events {
+            events.eval_started();
+        }
+        let clock = start_clock();
+        let start_counts = self.state.counts();
+        let result = self.step(receiver, breakpoints, step);
+        if let Some(events) = &events {
+            let counts = self.state.counts() - start_counts;
+            events.eval_finished(&EvalStats {
+                duration: clock.map(|clock| clock.elapsed()),
+                succeeded: !matches!(result, Err(_) | Ok(StepResult::Fail(_))),
+                intrinsic_count: counts.intrinsic_calls,
+                qubit_count: counts.qubits_allocated,
+                shot_count: 1,
+            });
+        }
+        result
+    }
+
+    fn step(
+        &mut self,
+        receiver: &mut impl Receiver,
+        breakpoints: &[StmtId],
+        step: StepAction,
+    ) -> std::result::Result<StepResult, Vec<Error>> {
+        self.history
+            .push((self.state.position(), self.hit_counts.clone()));
+        let start_depth = self.state.capture_stack().len();
+        let mut action = step;
+        loop {
+            let result = self.eval_step_once(receiver, breakpoints, action)?;
+            let depth = self.state.capture_stack().len();
+            match result {
+                StepResult::BreakpointHit(id) if self.should_break(receiver, id) => {
+                    return Ok(result);
+                }
+                StepResult::BreakpointHit(_) | StepResult::StepOut => {}
+                _ => return Ok(result),
+            }
+
+            // Execution stopped at a statement without completing the requested step, so
+            // resume it relative to the frame the step started in.
+            action = match step {
+                StepAction::In => return Ok(StepResult::StepIn),
+                StepAction::Next if depth <= start_depth => return Ok(StepResult::Next),
+                StepAction::Out if depth < start_depth => return Ok(StepResult::StepOut),
+                StepAction::Next | StepAction::Out => StepAction::Out,
+                StepAction::Continue => StepAction::Continue,
+            };
+        }
+    }
+
+    /// Steps back to where execution stopped before the most recent step, or returns `None` if
+    /// no step has been taken. Execution is replayed from the start of the session, which is
+    /// deterministic because the simulator and the random number generators are restored along
+    /// with the rest of the state, so measurements have the same outcomes as the first time.
+    /// Output produced during the replay is discarded, and expressions evaluated with
+    /// operations allowed are not replayed.
+    /// # Errors
+    /// Returns a vector of errors if replaying the execution fails.
+    pub fn step_back(&mut self) -> std::result::Result<Option<StepResult>, Vec<Error>> {
+        let Some((target, hit_counts)) = self.history.pop() else {
+            return Ok(None);
+        };
+        self.hit_counts = hit_counts;
+        let (state, checkpoint) = &self.start;
+        self.state.restore(state);
+        self.interpreter.restore(checkpoint);
+        if let Some(tracer) = self.interpreter.circuit_tracer.as_mut() {
+            tracer.reset();
+        }
+
+        let mut sink = std::io::sink();
+        let mut receiver = GenericReceiver::new(&mut sink);
+        let mut result = StepResult::StepIn;
+        if target > 0 {
+            self.state.replay_to(target);
+        }
+        while self.state.position() < target {
+            result = self.eval_step_once(&mut receiver, &[], StepAction::Continue)?;
+            if matches!(result, StepResult::Return(_) | StepResult::Fail(_)) {
+                break;
+            }
+        }
+        Ok(Some(result))
+    }
+
+    /// Sets the condition under which the breakpoint at the given statement stops execution,
+    /// resetting its hit count.
+    pub fn set_breakpoint_condition(&mut self, id: StmtId, condition: BreakpointCondition) {
+        self.hit_counts.remove(&id);
+        self.compiled_conditions.remove(&id);
+        self.breakpoint_conditions.insert(id, condition);
+    }
+
+    /// Sets a data breakpoint on the qubit with the given id, so that execution stops with
+    /// [`StepResult::QubitWatchHit`] after an intrinsic changes the qubit in the given way.
+    pub fn watch_qubit(&mut self, qubit: usize, kind: QubitWatchKind) {
+        self.state
+            .watch_qubit(&mut self.interpreter.sim, qubit, kind);
+    }
+
+    /// Removes the data breakpoint on the qubit with the given id.
+    pub fn unwatch_qubit(&mut self, qubit: usize) {
+        self.state.unwatch_qubit(qubit);
+    }
+
+    /// Removes the conditions and hit counts of all breakpoints, so they stop unconditionally.
+    pub fn clear_breakpoint_conditions(&mut self) {
+        self.breakpoint_conditions.clear();
+        self.hit_counts.clear();
+        self.compiled_conditions.clear();
+    }
+
+    /// Checks whether the breakpoint that was hit should stop execution. A condition that fails
+    /// to evaluate to a `Bool` stops execution, so the user can see and fix it.
+    fn should_break(&mut self, receiver: &mut impl Receiver, id: StmtId) -> bool {
+        let Some(condition) = self.breakpoint_conditions.get(&id).cloned() else {
+            return true;
+        };
+        if let Some(expr) = &condition.condition {
+            match self.eval_condition(receiver, id, expr) {
+                Ok(Value::Bool(false)) => return false,
+                Ok(Value::Bool(true)) => {}
+                _ => return true,
+            }
+        }
+        let hits = self.hit_counts.entry(id).or_default();
+        *hits = hits.saturating_add(1);
+        condition
+            .hit_condition
+            .is_none_or(|hit_condition| hit_condition.is_met(*hits))
+    }
+
+    /// Evaluates the condition of the breakpoint at the given statement in the current frame. The
+    /// condition is compiled on the first hit and reused while the locals it mentions keep the
+    /// same types.
+    fn eval_condition(
+        &mut self,
+        receiver: &mut impl Receiver,
+        id: StmtId,
+        expr: &str,
+    ) -> InterpretResult {
+        let frame_id = self.state.capture_stack().len();
+        let locals = self.expr_locals(frame_id, expr);
+        let key = locals
+            .iter()
+            .map(|local| (local.name.clone(), local.type_name.clone()))
+            .collect::<Vec<_>>();
+        let callable = match self.compiled_conditions.get(&id) {
+            Some((callable, cached)) if *cached == key => callable.clone(),
+            _ => {
+                let callable = self.compile_expr(receiver, frame_id, expr, &locals, false)?;
+                self.compiled_conditions.insert(id, (callable.clone(), key));
+                callable
+            }
+        };
+        self.interpreter
+            .invoke(receiver, callable, locals_arg(locals))
+    }
+
+    fn eval_step_once(
+        &mut self,
+        receiver: &mut impl Receiver,
+        breakpoints: &[StmtId],
+        step: StepAction,
     ) -> std::result::Result<StepResult, Vec<Error>> {
         self.state
             .eval(
@@ -1479,6 +2082,99 @@ impl Debugger {
             .collect()
     }
 
+    /// Gets the currently allocated qubits, labeled with the innermost variables they are bound to.
+    #[must_use]
+    pub fn get_qubits(&self) -> Vec<QubitInfo> {
+        self.interpreter.get_qubits()
+    }
+
+    /// Gets the scopes of the given call stack frame with the variables bound in each, from the
+    /// outermost scope to the innermost one.
+    #[must_use]
+    pub fn get_scopes(&self, frame_id: usize) -> Vec<ScopeInfo> {
+        self.interpreter
+            .get_scopes()
+            .into_iter()
+            .filter(|scope| scope.frame_id == frame_id)
+            .collect()
+    }
+
+    /// Evaluates a Q# expression in the context of the given call stack frame while execution is
+    /// paused. The expression can refer to the locals visible in the frame, by their innermost
+    /// binding, and to the items of the namespace declaring the frame's callable, in addition to
+    /// anything reachable by its fully qualified name. Frame `0` is the entry expression.
+    ///
+    /// Unless `allow_operations` is set, the expression is evaluated as the body of a function,
+    /// so calling an operation is a compilation error and the quantum state cannot be changed.
+    /// Locals are read-only: assigning to one does not change the paused program.
+    /// # Errors
+    /// Returns a vector of errors if compiling or evaluating the expression fails.
+    pub fn eval_expr(
+        &mut self,
+        receiver: &mut impl Receiver,
+        frame_id: usize,
+        expr: &str,
+        allow_operations: bool,
+    ) -> InterpretResult {
+        let locals = self.expr_locals(frame_id, expr);
+        let callable = self.compile_expr(receiver, frame_id, expr, &locals, allow_operations)?;
+        self.interpreter
+            .invoke(receiver, callable, locals_arg(locals))
+    }
+
+    /// Gets the locals of the given frame that the expression mentions, with later bindings
+    /// shadowing earlier ones of the same name.
+    fn expr_locals(&self, frame_id: usize, expr: &str) -> Vec<VariableInfo> {
+        let words: FxHashSet<&str> = expr
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .collect();
+        let mut locals: Vec<VariableInfo> = Vec::new();
+        for local in self.get_locals(frame_id) {
+            if words.contains(&*local.name) {
+                locals.retain(|l| l.name != local.name);
+                locals.push(local);
+            }
+        }
+        locals
+    }
+
+    /// Compiles the expression into a callable that takes the given locals as arguments.
+    fn compile_expr(
+        &mut self,
+        receiver: &mut impl Receiver,
+        frame_id: usize,
+        expr: &str,
+        locals: &[VariableInfo],
+        allow_operations: bool,
+    ) -> InterpretResult {
+        let namespace = frame_id
+            .checked_sub(1)
+            .and_then(|index| self.state.capture_stack().get(index).map(|frame| frame.id))
+            .and_then(|id| {
+                debug::get_frame_namespace(self.interpreter.compiler.package_store(), id)
+            });
+
+        let params = locals
+            .iter()
+            .map(|local| local.name.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let arrow = if allow_operations { "=>" } else { "->" };
+        let mut body = String::new();
+        if let Some(namespace) = namespace {
+            body.push_str(&format!("open {namespace};\n"));
+        }
+        // Lambda parameters cannot be annotated, so the types of the locals are pinned down by
+        // rebinding them, leaving the rest to be inferred from their use.
+        for local in locals {
+            if let Some(ty) = debug::value_ty_source(&local.value) {
+                body.push_str(&format!("let {0} : {ty} = {0};\n", local.name));
+            }
+        }
+        let lambda = format!("({params}) {arrow} {{\n{body}({expr})\n}}");
+        self.interpreter.eval_fragments(receiver, &lambda)
+    }
+
     fn source_package(&self) -> &CompileUnit {
         self.interpreter
             .compiler
@@ -1488,6 +2184,33 @@ impl Debugger {
     }
 }
 
+fn user_scopes(scopes: Vec<ScopeInfo>) -> Vec<ScopeInfo> {
+    scopes
+        .into_iter()
+        .map(|mut scope| {
+            scope.variables.retain(|v| !v.name.starts_with('@'));
+            scope
+        })
+        .collect()
+}
+
+/// Builds the parameter sets for a sweep over the Cartesian product of the given axes, varying the
+/// last axis fastest.
+#[must_use]
+pub fn parameter_grid(axes: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    axes.iter().fold(vec![Vec::new()], |sets, axis| {
+        sets.iter()
+            .flat_map(|set| {
+                axis.iter().map(move |value| {
+                    let mut set = set.clone();
+                    set.push(*value);
+                    set
+                })
+            })
+            .collect()
+    })
+}
+
 /// Wrapper function for `qsc_eval::eval` that handles error conversion.
 #[allow(clippy::too_many_arguments)]
 fn eval<B: Backend>(
@@ -1497,9 +2220,12 @@ fn eval<B: Backend>(
     exec_graph_config: ExecGraphConfig,
     package_store: &PackageStore,
     fir_store: &fir::PackageStore,
+    host_intrinsics: &HostIntrinsics,
+    max_call_depth: Option<usize>,
     env: &mut Env,
     tracing_backend: &mut TracingBackend<'_, B>,
     receiver: &mut impl Receiver,
+    counts: &mut EvalCounts,
 ) -> InterpretResult {
     qsc_eval::eval(
         package,
@@ -1507,13 +2233,41 @@ fn eval<B: Backend>(
         exec_graph,
         exec_graph_config,
         fir_store,
+        host_intrinsics,
+        max_call_depth,
         env,
         tracing_backend,
         receiver,
+        counts,
     )
     .map_err(|(error, call_stack)| eval_error(package_store, fir_store, call_stack, error))
 }
 
+/// Tells the host's events, if any, that an evaluation of the given number of shots starts, runs
+/// it, and then tells them how it went.
+fn observe_eval<T>(
+    events: Option<&dyn CompilerEvents>,
+    shot_count: u32,
+    eval: impl FnOnce(&mut EvalCounts) -> std::result::Result<T, Vec<Error>>,
+) -> std::result::Result<T, Vec<Error>> {
+    if let Some(events) = events {
+        events.eval_started();
+    }
+    let clock = start_clock();
+    let mut counts = EvalCounts::default();
+    let result = eval(&mut counts);
+    if let Some(events) = events {
+        events.eval_finished(&EvalStats {
+            duration: clock.map(|clock| clock.elapsed()),
+            succeeded: result.is_ok(),
+            intrinsic_count: counts.intrinsic_calls,
+            qubit_count: counts.qubits_allocated,
+            shot_count,
+        });
+    }
+    result
+}
+
 /// Represents a stack frame for debugging.
 pub struct StackFrame {
     /// The name of the callable.
@@ -1532,6 +2286,72 @@ pub struct BreakpointSpan {
     pub range: Range,
 }
 
+/// The condition under which a breakpoint stops execution.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BreakpointCondition {
+    /// A Q# `Bool` expression evaluated in the frame of the breakpoint, which must be `true` for
+    /// the breakpoint to stop execution.
+    pub condition: Option<String>,
+    /// The condition on the number of times the breakpoint has been hit with its condition met.
+    pub hit_condition: Option<HitCondition>,
+}
+
+/// A condition on the number of times a breakpoint has been hit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HitCondition {
+    /// Stops on exactly the given hit.
+    Equal(u32),
+    /// Stops on every hit from the given one on.
+    AtLeast(u32),
+    /// Stops on every hit that is a multiple of the given number.
+    Multiple(u32),
+}
+
+impl HitCondition {
+    #[must_use]
+    pub fn is_met(self, hits: u32) -> bool {
+        match self {
+            HitCondition::Equal(n) => hits == n,
+            HitCondition::AtLeast(n) => hits >= n,
+            HitCondition::Multiple(n) => n != 0 && hits % n == 0,
+        }
+    }
+}
+
+impl FromStr for HitCondition {
+    type Err = ();
+
+    /// Parses a hit condition such as `5`, `== 5`, `>= 5`, `> 4`, or `% 2`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let (op, count) = match s.find(|c: char| c.is_ascii_digit()) {
+            Some(index) => s.split_at(index),
+            None => return Err(()),
+        };
+        let count: u32 = count.trim().parse().map_err(|_| ())?;
+        match op.trim() {
+            "" | "=" | "==" => Ok(HitCondition::Equal(count)),
+            ">=" => Ok(HitCondition::AtLeast(count)),
+            ">" => count.checked_add(1).map(HitCondition::AtLeast).ok_or(()),
+            "%" => Ok(HitCondition::Multiple(count)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Packs the values of the locals into the argument of a callable compiled from an expression.
+fn locals_arg(locals: Vec<VariableInfo>) -> Value {
+    let mut args = locals
+        .into_iter()
+        .map(|local| local.value)
+        .collect::<Vec<_>>();
+    if args.len() == 1 {
+        args.pop().expect("there should be one argument")
+    } else {
+        Value::Tuple(args.into(), None)
+    }
+}
+
 struct BreakpointCollector<'a> {
     statements: FxHashSet<BreakpointSpan>,
     sources: &'a SourceMap,
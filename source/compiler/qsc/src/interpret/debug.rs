@@ -5,14 +5,14 @@
 mod tests;
 
 use qsc_data_structures::line_column::{Encoding, Position};
-use qsc_eval::debug::Frame;
+use qsc_eval::{debug::Frame, val::Value};
 use qsc_fir::fir::{Global, PackageStoreLookup, StoreItemId};
 use qsc_frontend::compile::PackageStore;
 use qsc_hir::hir;
 use qsc_hir::hir::{Item, ItemKind};
 use qsc_lowerer::map_fir_package_to_hir;
 use std::fmt::Write;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[must_use]
 pub(crate) fn format_call_stack(
@@ -64,6 +64,49 @@ pub(crate) fn format_call_stack(
     trace
 }
 
+/// Gets the name of the namespace that declares the callable of the given frame, if any.
+#[must_use]
+pub(crate) fn get_frame_namespace(store: &PackageStore, id: StoreItemId) -> Option<Arc<str>> {
+    get_item_parent(store, id).and_then(|item| get_ns_name(&item))
+}
+
+/// Renders the Q# type of the given value, when it can be determined from the value alone.
+/// Callables, user-defined types, and empty arrays have no such type.
+#[must_use]
+pub(crate) fn value_ty_source(value: &Value) -> Option<String> {
+    match value {
+        Value::BigInt(_) => Some("BigInt".into()),
+        Value::Bool(_) => Some("Bool".into()),
+        Value::Double(_) => Some("Double".into()),
+        Value::Int(_) => Some("Int".into()),
+        Value::Pauli(_) => Some("Pauli".into()),
+        Value::Qubit(_) => Some("Qubit".into()),
+        Value::Range(_) => Some("Range".into()),
+        Value::Result(_) => Some("Result".into()),
+        Value::String(_) => Some("String".into()),
+        Value::Array(items) => {
+            let item_ty = value_ty_source(items.first()?)?;
+            items
+                .iter()
+                .all(|item| value_ty_source(item).as_ref() == Some(&item_ty))
+                .then(|| format!("{item_ty}[]"))
+        }
+        Value::Tuple(items, None) if items.is_empty() => Some("Unit".into()),
+        Value::Tuple(items, None) => {
+            let tys = items
+                .iter()
+                .map(value_ty_source)
+                .collect::<Option<Vec<_>>>()?;
+            if tys.len() == 1 {
+                Some(format!("({},)", tys[0]))
+            } else {
+                Some(format!("({})", tys.join(", ")))
+            }
+        }
+        Value::Closure(_) | Value::Global(..) | Value::Tuple(_, Some(_)) | Value::Var(_) => None,
+    }
+}
+
 #[must_use]
 fn get_item_parent(store: &PackageStore, id: StoreItemId) -> Option<Item> {
     let package = map_fir_package_to_hir(id.package);
@@ -91,7 +134,7 @@ fn get_item_file_name(store: &PackageStore, id: StoreItemId) -> Option<String> {
 }
 
 #[must_use]
-fn get_ns_name(item: &Item) -> Option<Rc<str>> {
+fn get_ns_name(item: &Item) -> Option<Arc<str>> {
     let ItemKind::Namespace(ns, _) = &item.kind else {
         return None;
     };
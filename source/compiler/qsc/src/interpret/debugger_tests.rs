@@ -239,4 +239,516 @@ mod given_debugger {
             Ok(())
         }
     }
+
+    #[cfg(test)]
+    mod eval_expr {
+        use qsc_data_structures::{source::SourceMap, target::TargetCapabilityFlags};
+
+        use super::*;
+
+        fn debugger_paused_in_b() -> Result<Debugger, Vec<crate::interpret::Error>> {
+            let sources = SourceMap::new([("test".into(), STEPPING_SOURCE.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut debugger = Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )?;
+            let ids = get_breakpoint_ids(&debugger, "test");
+            expect_bp(&mut debugger, &ids, ids[0]);
+            expect_in(&mut debugger);
+            expect_next(&mut debugger);
+            expect_next(&mut debugger);
+            Ok(debugger)
+        }
+
+        fn eval_expr(
+            debugger: &mut Debugger,
+            frame_id: usize,
+            expr: &str,
+            allow_operations: bool,
+        ) -> crate::interpret::InterpretResult {
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            debugger.eval_expr(&mut receiver, frame_id, expr, allow_operations)
+        }
+
+        fn expect_continue_returns(debugger: &mut Debugger, expected: &str) {
+            match step(debugger, &[], StepAction::Continue).0 {
+                Ok(StepResult::Return(value)) => assert_eq!(value.to_string(), expected),
+                Ok(v) => panic!("Expected Return, got {v:?}"),
+                Err(e) => panic!("Expected Return, got {e:?}"),
+            }
+        }
+
+        #[test]
+        fn locals_of_frame_are_available() -> Result<(), Vec<crate::interpret::Error>> {
+            let mut debugger = debugger_paused_in_b()?;
+            let value = eval_expr(&mut debugger, 2, "g + h", false)?;
+            assert_eq!(value.to_string(), "30");
+            let value = eval_expr(&mut debugger, 2, "[g, h]", false)?;
+            assert_eq!(value.to_string(), "[10, 20]");
+            expect_continue_returns(&mut debugger, "42");
+            Ok(())
+        }
+
+        #[test]
+        fn paused_debugger_reports_call_stack_and_locals()
+        -> Result<(), Vec<crate::interpret::Error>> {
+            let mut debugger = debugger_paused_in_b()?;
+            let frames = debugger.get_stack_frames();
+            let names = frames
+                .iter()
+                .map(|frame| frame.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["A", "B"]);
+            let locals = debugger
+                .get_locals(2)
+                .into_iter()
+                .map(|local| format!("{} = {}", local.name, local.value))
+                .collect::<Vec<_>>();
+            assert_eq!(locals, ["g = 10", "h = 20"]);
+            expect_continue_returns(&mut debugger, "42");
+            Ok(())
+        }
+
+        #[test]
+        fn operation_calls_are_rejected_unless_allowed() -> Result<(), Vec<crate::interpret::Error>>
+        {
+            let mut debugger = debugger_paused_in_b()?;
+            eval_expr(&mut debugger, 2, "C(g, h)", false)
+                .expect_err("calling an operation should fail");
+            let value = eval_expr(&mut debugger, 2, "C(g, h)", true)?;
+            assert_eq!(value.to_string(), "42");
+            expect_continue_returns(&mut debugger, "42");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod given_conditional_breakpoint {
+    use super::*;
+    use crate::interpret::{BreakpointCondition, HitCondition};
+    use qsc_data_structures::{source::SourceMap, target::TargetCapabilityFlags};
+
+    static LOOP_SOURCE: &str = r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                mutable sum = 0;
+                for i in 0..9 {
+                    sum += i;
+                }
+                sum
+            }
+        }"#;
+
+    fn debugger_with_loop_breakpoint() -> Result<(Debugger, StmtId), Vec<crate::interpret::Error>> {
+        let sources = SourceMap::new([("test".into(), LOOP_SOURCE.into())], None);
+        let (std_id, store) =
+            crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+        let debugger = Debugger::new(
+            sources,
+            TargetCapabilityFlags::all(),
+            Encoding::Utf8,
+            LanguageFeatures::default(),
+            store,
+            &[(std_id, None)],
+        )?;
+        let id = debugger
+            .get_breakpoints("test")
+            .iter()
+            .find(|bp| bp.range.start.line == 6)
+            .expect("loop body should have a breakpoint")
+            .id
+            .into();
+        Ok((debugger, id))
+    }
+
+    fn continue_to_bp(debugger: &mut Debugger, id: StmtId) -> i64 {
+        match step(debugger, &[id], StepAction::Continue).0 {
+            Ok(StepResult::BreakpointHit(actual_id)) => assert!(actual_id == id),
+            Ok(v) => panic!("Expected BP, got {v:?}"),
+            Err(e) => panic!("Expected BP, got {e:?}"),
+        }
+        let mut cursor = Cursor::new(Vec::<u8>::new());
+        let mut receiver = CursorReceiver::new(&mut cursor);
+        match debugger.eval_expr(&mut receiver, 1, "i", false) {
+            Ok(qsc_eval::val::Value::Int(i)) => i,
+            v => panic!("Expected Int, got {v:?}"),
+        }
+    }
+
+    fn continue_to_return(debugger: &mut Debugger, id: StmtId) {
+        match step(debugger, &[id], StepAction::Continue).0 {
+            Ok(StepResult::Return(value)) => assert_eq!(value.to_string(), "45"),
+            Ok(v) => panic!("Expected Return, got {v:?}"),
+            Err(e) => panic!("Expected Return, got {e:?}"),
+        }
+    }
+
+    #[test]
+    fn stops_only_when_condition_is_true() -> Result<(), Vec<crate::interpret::Error>> {
+        let (mut debugger, id) = debugger_with_loop_breakpoint()?;
+        debugger.set_breakpoint_condition(
+            id,
+            BreakpointCondition {
+                condition: Some("i == 4 or sum >= 36".into()),
+                hit_condition: None,
+            },
+        );
+        assert_eq!(continue_to_bp(&mut debugger, id), 4);
+        assert_eq!(continue_to_bp(&mut debugger, id), 9);
+        continue_to_return(&mut debugger, id);
+        Ok(())
+    }
+
+    #[test]
+    fn stops_only_on_matching_hits() -> Result<(), Vec<crate::interpret::Error>> {
+        let (mut debugger, id) = debugger_with_loop_breakpoint()?;
+        debugger.set_breakpoint_condition(
+            id,
+            BreakpointCondition {
+                condition: None,
+                hit_condition: Some(HitCondition::Multiple(3)),
+            },
+        );
+        assert_eq!(continue_to_bp(&mut debugger, id), 2);
+        assert_eq!(continue_to_bp(&mut debugger, id), 5);
+        assert_eq!(continue_to_bp(&mut debugger, id), 8);
+        continue_to_return(&mut debugger, id);
+        Ok(())
+    }
+
+    #[test]
+    fn hit_count_applies_to_hits_meeting_condition() -> Result<(), Vec<crate::interpret::Error>> {
+        let (mut debugger, id) = debugger_with_loop_breakpoint()?;
+        debugger.set_breakpoint_condition(
+            id,
+            BreakpointCondition {
+                condition: Some("i % 2 == 1".into()),
+                hit_condition: Some(HitCondition::Equal(2)),
+            },
+        );
+        assert_eq!(continue_to_bp(&mut debugger, id), 3);
+        continue_to_return(&mut debugger, id);
+        Ok(())
+    }
+
+    #[test]
+    fn condition_is_compiled_once() -> Result<(), Vec<crate::interpret::Error>> {
+        let (mut debugger, id) = debugger_with_loop_breakpoint()?;
+        debugger.set_breakpoint_condition(
+            id,
+            BreakpointCondition {
+                condition: Some("i == 9".into()),
+                hit_condition: None,
+            },
+        );
+        let lines = debugger.interpreter.lines;
+        match step(&mut debugger, &[id], StepAction::Continue).0 {
+            Ok(StepResult::BreakpointHit(actual_id)) => assert!(actual_id == id),
+            Ok(v) => panic!("Expected BP, got {v:?}"),
+            Err(e) => panic!("Expected BP, got {e:?}"),
+        }
+        assert_eq!(debugger.interpreter.lines, lines + 1);
+        continue_to_return(&mut debugger, id);
+        Ok(())
+    }
+
+    #[test]
+    fn hit_conditions_are_parsed() {
+        assert_eq!("5".parse(), Ok(HitCondition::Equal(5)));
+        assert_eq!("== 5".parse(), Ok(HitCondition::Equal(5)));
+        assert_eq!(">= 5".parse(), Ok(HitCondition::AtLeast(5)));
+        assert_eq!("> 5".parse(), Ok(HitCondition::AtLeast(6)));
+        assert_eq!("%2".parse(), Ok(HitCondition::Multiple(2)));
+        assert_eq!("< 5".parse::<HitCondition>(), Err(()));
+        assert_eq!("five".parse::<HitCondition>(), Err(()));
+        assert_eq!(format!("> {}", u32::MAX).parse::<HitCondition>(), Err(()));
+    }
+}
+
+#[cfg(test)]
+mod given_qubit_watch {
+    use super::*;
+    use crate::interpret::QubitWatchKind;
+    use qsc_data_structures::{source::SourceMap, target::TargetCapabilityFlags};
+
+    static BELL_SOURCE: &str = r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use qs = Qubit[2];
+                X(qs[1]);
+                H(qs[0]);
+                CNOT(qs[0], qs[1]);
+                MResetEachZ(qs)
+            }
+        }"#;
+
+    fn debugger_paused_after_allocation() -> Result<Debugger, Vec<crate::interpret::Error>> {
+        let sources = SourceMap::new([("test".into(), BELL_SOURCE.into())], None);
+        let (std_id, store) =
+            crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+        let mut debugger = Debugger::new(
+            sources,
+            TargetCapabilityFlags::all(),
+            Encoding::Utf8,
+            LanguageFeatures::default(),
+            store,
+            &[(std_id, None)],
+        )?;
+        let id: StmtId = debugger
+            .get_breakpoints("test")
+            .iter()
+            .find(|bp| bp.range.start.line == 5)
+            .expect("statement should have a breakpoint")
+            .id
+            .into();
+        expect_bp(&mut debugger, &[id], id);
+        Ok(debugger)
+    }
+
+    fn expect_watch_hit(debugger: &mut Debugger, expected_qubit: usize) {
+        match step(debugger, &[], StepAction::Continue).0 {
+            Ok(StepResult::QubitWatchHit(qubit)) => assert_eq!(qubit, expected_qubit),
+            Ok(v) => panic!("Expected QubitWatchHit, got {v:?}"),
+            Err(e) => panic!("Expected QubitWatchHit, got {e:?}"),
+        }
+    }
+
+    fn basis_states(debugger: &mut Debugger) -> Vec<String> {
+        let (state, _) = debugger.capture_quantum_state();
+        state.iter().map(|(label, _)| label.to_string()).collect()
+    }
+
+    #[test]
+    fn stops_when_reduced_state_changes() -> Result<(), Vec<crate::interpret::Error>> {
+        let mut debugger = debugger_paused_after_allocation()?;
+        debugger.watch_qubit(0, QubitWatchKind::StateChange);
+        // `X` on the other qubit leaves the watched one unchanged, so the first stop is after `H`.
+        expect_watch_hit(&mut debugger, 0);
+        assert_eq!(basis_states(&mut debugger), ["1", "3"]);
+        expect_watch_hit(&mut debugger, 0);
+        assert_eq!(basis_states(&mut debugger), ["1", "2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn stops_when_qubit_becomes_entangled() -> Result<(), Vec<crate::interpret::Error>> {
+        let mut debugger = debugger_paused_after_allocation()?;
+        debugger.watch_qubit(0, QubitWatchKind::Entanglement);
+        expect_watch_hit(&mut debugger, 0);
+        assert_eq!(basis_states(&mut debugger), ["1", "2"]);
+        match step(&mut debugger, &[], StepAction::Continue).0 {
+            Ok(StepResult::Return(_)) => {}
+            Ok(v) => panic!("Expected Return, got {v:?}"),
+            Err(e) => panic!("Expected Return, got {e:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unwatched_qubit_does_not_stop() -> Result<(), Vec<crate::interpret::Error>> {
+        let mut debugger = debugger_paused_after_allocation()?;
+        debugger.watch_qubit(0, QubitWatchKind::StateChange);
+        debugger.unwatch_qubit(0);
+        match step(&mut debugger, &[], StepAction::Continue).0 {
+            Ok(StepResult::Return(_)) => {}
+            Ok(v) => panic!("Expected Return, got {v:?}"),
+            Err(e) => panic!("Expected Return, got {e:?}"),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod given_step_back {
+    use super::*;
+    use crate::interpret::{BreakpointCondition, HitCondition};
+    use qsc_data_structures::{source::SourceMap, target::TargetCapabilityFlags};
+
+    static MEASURE_SOURCE: &str = r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                H(q);
+                let r = M(q);
+                Reset(q);
+                r
+            }
+        }"#;
+
+    static BLOCK_SOURCE: &str = r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                mutable y = 0;
+                for i in 0..2 {
+                    set y += i;
+                }
+                y
+            }
+        }"#;
+
+    fn new_debugger() -> Result<Debugger, Vec<crate::interpret::Error>> {
+        debugger_for(MEASURE_SOURCE)
+    }
+
+    fn debugger_for(source: &str) -> Result<Debugger, Vec<crate::interpret::Error>> {
+        let sources = SourceMap::new([("test".into(), source.into())], None);
+        let (std_id, store) =
+            crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+        Debugger::new(
+            sources,
+            TargetCapabilityFlags::all(),
+            Encoding::Utf8,
+            LanguageFeatures::default(),
+            store,
+            &[(std_id, None)],
+        )
+    }
+
+    fn current_line(debugger: &Debugger) -> u32 {
+        debugger
+            .get_stack_frames()
+            .last()
+            .expect("execution should be paused in a frame")
+            .location
+            .range
+            .start
+            .line
+    }
+
+    fn local(debugger: &Debugger, name: &str) -> String {
+        debugger
+            .get_locals(1)
+            .into_iter()
+            .find(|local| &*local.name == name)
+            .expect("local should be bound")
+            .value
+            .to_string()
+    }
+
+    #[test]
+    fn replays_to_previous_stop_with_same_measurements() -> Result<(), Vec<crate::interpret::Error>>
+    {
+        let mut debugger = new_debugger()?;
+        let id: StmtId = debugger
+            .get_breakpoints("test")
+            .iter()
+            .find(|bp| bp.range.start.line == 5)
+            .expect("statement should have a breakpoint")
+            .id
+            .into();
+        expect_bp(&mut debugger, &[id], id);
+        expect_next(&mut debugger);
+        expect_next(&mut debugger);
+        assert_eq!(current_line(&debugger), 7);
+        let measured = local(&debugger, "r");
+
+        let result = debugger.step_back()?;
+        assert!(matches!(result, Some(StepResult::StepIn)));
+        assert_eq!(current_line(&debugger), 6);
+
+        expect_next(&mut debugger);
+        assert_eq!(current_line(&debugger), 7);
+        assert_eq!(local(&debugger, "r"), measured);
+        match step(&mut debugger, &[], StepAction::Continue).0 {
+            Ok(StepResult::Return(value)) => assert_eq!(value.to_string(), measured),
+            Ok(v) => panic!("Expected Return, got {v:?}"),
+            Err(e) => panic!("Expected Return, got {e:?}"),
+        }
+        Ok(())
+    }
+
+    fn breakpoint_on_line(debugger: &Debugger, line: u32) -> StmtId {
+        debugger
+            .get_breakpoints("test")
+            .iter()
+            .find(|bp| bp.range.start.line == line)
+            .expect("statement should have a breakpoint")
+            .id
+            .into()
+    }
+
+    #[test]
+    fn replays_to_previous_stop_at_block_exit() -> Result<(), Vec<crate::interpret::Error>> {
+        let mut debugger = debugger_for(BLOCK_SOURCE)?;
+        let id = breakpoint_on_line(&debugger, 6);
+        expect_bp(&mut debugger, &[id], id);
+        expect_next(&mut debugger);
+        assert_eq!(current_line(&debugger), 7);
+        expect_next(&mut debugger);
+
+        let result = debugger.step_back()?;
+        assert!(matches!(result, Some(StepResult::Next)));
+        assert_eq!(current_line(&debugger), 7);
+        assert_eq!(local(&debugger, "y"), "0");
+        Ok(())
+    }
+
+    #[test]
+    fn restores_hit_counts_of_previous_stop() -> Result<(), Vec<crate::interpret::Error>> {
+        let mut debugger = debugger_for(BLOCK_SOURCE)?;
+        let id = breakpoint_on_line(&debugger, 6);
+        debugger.set_breakpoint_condition(
+            id,
+            BreakpointCondition {
+                condition: None,
+                hit_condition: Some(HitCondition::Equal(2)),
+            },
+        );
+        expect_bp(&mut debugger, &[id], id);
+        assert_eq!(local(&debugger, "i"), "1");
+
+        assert!(matches!(debugger.step_back()?, Some(StepResult::StepIn)));
+        expect_bp(&mut debugger, &[id], id);
+        assert_eq!(local(&debugger, "i"), "1");
+        Ok(())
+    }
+
+    #[test]
+    fn steps_report_eval_events_with_counts() -> Result<(), Vec<crate::interpret::Error>> {
+        #[derive(Default)]
+        struct RecordedStats(std::cell::RefCell<Vec<crate::events::EvalStats>>);
+
+        impl crate::events::CompilerEvents for RecordedStats {
+            fn eval_finished(&self, stats: &crate::events::EvalStats) {
+                self.0.borrow_mut().push(stats.clone());
+            }
+        }
+
+        let mut debugger = new_debugger()?;
+        let events = std::rc::Rc::new(RecordedStats::default());
+        debugger.interpreter.set_events(events.clone());
+        match step(&mut debugger, &[], StepAction::Continue).0 {
+            Ok(StepResult::Return(_)) => {}
+            Ok(v) => panic!("Expected Return, got {v:?}"),
+            Err(e) => panic!("Expected Return, got {e:?}"),
+        }
+
+        let stats = events.0.borrow();
+        let [stats] = &stats[..] else {
+            panic!("expected one evaluation, got {stats:?}");
+        };
+        assert!(stats.succeeded);
+        assert_eq!((stats.shot_count, stats.qubit_count), (1, 1));
+        assert!(stats.intrinsic_count > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_to_step_back_to_at_start() -> Result<(), Vec<crate::interpret::Error>> {
+        let mut debugger = new_debugger()?;
+        assert!(debugger.step_back()?.is_none());
+        Ok(())
+    }
 }
@@ -141,6 +141,242 @@ mod given_interpreter {
             is_only_value(&result, &output, &Value::String("Hello".into()));
         }
 
+        #[test]
+        fn callables_can_be_redefined_with_warning() {
+            let mut interpreter = get_interpreter();
+
+            let (result, output) = line(&mut interpreter, "function Foo() : Int { 1 }");
+            is_only_value(&result, &output, &Value::unit());
+            assert!(interpreter.take_warnings().is_empty());
+
+            let (result, output) = line(&mut interpreter, "function Foo() : Int { 2 }");
+            is_only_value(&result, &output, &Value::unit());
+            let warnings = interpreter.take_warnings();
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(
+                warnings[0].code().map(|code| code.to_string()).as_deref(),
+                Some("Qsc.Resolve.Redefinition")
+            );
+            assert_eq!(warnings[0].severity(), Some(miette::Severity::Warning));
+            assert!(interpreter.take_warnings().is_empty());
+
+            let (result, output) = line(&mut interpreter, "Foo()");
+            is_only_value(&result, &output, &Value::Int(2));
+        }
+
+        #[test]
+        fn events_are_reported_for_fragments() {
+            #[derive(Default)]
+            struct RecordedEvents(std::cell::RefCell<Vec<String>>);
+
+            impl crate::events::CompilerEvents for RecordedEvents {
+                fn compile_finished(&self, stats: &crate::events::CompileStats) {
+                    self.0.borrow_mut().push(format!(
+                        "compiled with {} errors and {} warnings",
+                        stats.error_count, stats.warning_count
+                    ));
+                }
+
+                fn eval_finished(&self, stats: &crate::events::EvalStats) {
+                    self.0
+                        .borrow_mut()
+                        .push(format!("evaluated, succeeded: {}", stats.succeeded));
+                }
+            }
+
+            let mut interpreter = get_interpreter();
+            let events = std::rc::Rc::new(RecordedEvents::default());
+            interpreter.set_events(events.clone());
+
+            line(&mut interpreter, "function Foo() : Int { 1 }")
+                .0
+                .expect("line should succeed");
+            line(&mut interpreter, "function Foo() : Int { 2 }")
+                .0
+                .expect("line should succeed");
+            line(&mut interpreter, "Foo(")
+                .0
+                .expect_err("line should fail to compile");
+            line(&mut interpreter, "fail \"boom\"")
+                .0
+                .expect_err("line should fail at runtime");
+
+            assert_eq!(
+                *events.0.borrow(),
+                [
+                    "compiled with 0 errors and 0 warnings",
+                    "evaluated, succeeded: true",
+                    "compiled with 0 errors and 1 warnings",
+                    "evaluated, succeeded: true",
+                    "compiled with 1 errors and 0 warnings",
+                    "compiled with 0 errors and 0 warnings",
+                    "evaluated, succeeded: false",
+                ]
+            );
+        }
+
+        #[test]
+        fn eval_events_report_invocations_and_shots_with_counts() {
+            #[derive(Default)]
+            struct RecordedStats(std::cell::RefCell<Vec<crate::events::EvalStats>>);
+
+            impl crate::events::CompilerEvents for RecordedStats {
+                fn eval_finished(&self, stats: &crate::events::EvalStats) {
+                    self.0.borrow_mut().push(stats.clone());
+                }
+            }
+
+            let mut interpreter = get_interpreter();
+            line(
+                &mut interpreter,
+                "operation Foo() : Unit { use q = Qubit(); H(q); Reset(q); }",
+            )
+            .0
+            .expect("line should succeed");
+            let events = std::rc::Rc::new(RecordedStats::default());
+            interpreter.set_events(events.clone());
+
+            invoke(&mut interpreter, "Foo", Value::unit())
+                .0
+                .expect("invoke should succeed");
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            interpreter
+                .run_shots(&mut receiver, Some("Foo()"), 3, None, None)
+                .expect("run_shots should succeed");
+
+            let stats = events.0.borrow();
+            // Evaluating `Foo` to get the callable to invoke is reported first.
+            let [_, invoked, shots] = &stats[..] else {
+                panic!("expected three evaluations, got {stats:?}");
+            };
+            assert!(invoked.succeeded);
+            assert_eq!((invoked.shot_count, invoked.qubit_count), (1, 1));
+            assert!(invoked.intrinsic_count >= 4);
+            assert!(shots.succeeded);
+            assert_eq!((shots.shot_count, shots.qubit_count), (3, 3));
+            assert_eq!(shots.intrinsic_count, 3 * invoked.intrinsic_count);
+        }
+
+        #[test]
+        fn redefined_callable_is_used_by_earlier_callers() {
+            let mut interpreter = get_interpreter();
+            for source in [
+                "function Foo() : Int { 1 }",
+                "function Bar() : Int { Foo() }",
+                "function Foo() : Int { 2 }",
+            ] {
+                line(&mut interpreter, source)
+                    .0
+                    .expect("line should succeed");
+            }
+            let (result, output) = line(&mut interpreter, "Bar()");
+            is_only_value(&result, &output, &Value::Int(2));
+        }
+
+        #[test]
+        fn redefined_callable_with_new_signature_is_not_used_by_earlier_callers() {
+            let mut interpreter = get_interpreter();
+            for source in [
+                "function Foo() : Int { 1 }",
+                "function Bar() : Int { Foo() }",
+                "function Foo() : String { \"two\" }",
+            ] {
+                line(&mut interpreter, source)
+                    .0
+                    .expect("line should succeed");
+            }
+            let (result, output) = line(&mut interpreter, "Bar()");
+            is_only_value(&result, &output, &Value::Int(1));
+            let (result, output) = line(&mut interpreter, "Foo()");
+            is_only_value(&result, &output, &Value::String("two".into()));
+        }
+
+        #[test]
+        fn redefined_callable_is_used_by_earlier_callers_with_restricted_capabilities() {
+            let mut interpreter =
+                get_interpreter_with_capabilities(TargetCapabilityFlags::Adaptive);
+            for source in [
+                "function Foo() : Int { 1 }",
+                "function Bar() : Int { Foo() }",
+                "function Foo() : Int { 2 }",
+            ] {
+                line(&mut interpreter, source)
+                    .0
+                    .expect("line should succeed");
+            }
+            let (result, output) = line(&mut interpreter, "Bar()");
+            is_only_value(&result, &output, &Value::Int(2));
+        }
+
+        #[test]
+        fn redefinition_failing_profile_validation_keeps_earlier_definition() {
+            let mut interpreter =
+                get_interpreter_with_capabilities(TargetCapabilityFlags::Adaptive);
+            for source in [
+                "operation Foo() : Int { 1 }",
+                "operation Bar() : Int { Foo() }",
+            ] {
+                line(&mut interpreter, source)
+                    .0
+                    .expect("line should succeed");
+            }
+            let (result, _) = line(
+                &mut interpreter,
+                "operation Foo() : Int { use q = Qubit(); mutable x = 1; if MResetZ(q) == One { set x = 2; } x }",
+            );
+            result.expect_err("redefinition should fail profile validation");
+            let (result, output) = line(&mut interpreter, "Bar()");
+            is_only_value(&result, &output, &Value::Int(1));
+        }
+
+        #[test]
+        fn match_expression_selects_first_matching_arm() {
+            let mut interpreter =
+                get_interpreter_with_language_features(LanguageFeatures::MatchExpressions);
+            line(
+                &mut interpreter,
+                r#"function F(i : Int) : String { match i { 0 => "zero", -1 => "minus one", _ => "other" } }"#,
+            )
+            .0
+            .expect("line should succeed");
+            let (result, output) = line(&mut interpreter, "(F(0), F(-1), F(5))");
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    [
+                        Value::String("zero".into()),
+                        Value::String("minus one".into()),
+                        Value::String("other".into()),
+                    ]
+                    .into(),
+                    None,
+                ),
+            );
+        }
+
+        #[test]
+        fn match_expression_evaluates_scrutinee_once() {
+            let mut interpreter =
+                get_interpreter_with_language_features(LanguageFeatures::MatchExpressions);
+            let (result, output) = line(
+                &mut interpreter,
+                "{ use q = Qubit(); X(q); match MResetZ(q) { Zero => 0, One => 1 } }",
+            );
+            is_only_value(&result, &output, &Value::Int(1));
+        }
+
+        #[test]
+        fn opens_persist_across_lines() {
+            let mut interpreter = get_interpreter();
+            line(&mut interpreter, "open Std.Math;")
+                .0
+                .expect("line should succeed");
+            let (result, output) = line(&mut interpreter, "AbsI(-3)");
+            is_only_value(&result, &output, &Value::Int(3));
+        }
+
         #[test]
         fn invalid_statements_return_error() {
             let mut interpreter = get_interpreter();
@@ -1511,6 +1747,507 @@ mod given_interpreter {
             }
         }
 
+        #[test]
+        fn run_shots_aggregates_results() {
+            let mut interpreter = get_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let histogram = interpreter
+                .run_shots(
+                    &mut receiver,
+                    Some("{ use q = Qubit(); X(q); (MResetZ(q), 3) }"),
+                    10,
+                    None,
+                    None,
+                )
+                .expect("run_shots should succeed");
+            assert_eq!(histogram.shots(), 10);
+            expect![[r#"
+                (One, 3): 10 (100.00%, 95% CI 72.25%..100.00%)
+            "#]]
+            .assert_eq(&histogram.to_string());
+        }
+
+        #[test]
+        fn run_shots_varies_classical_randomness_between_shots() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_classical_seed(Some(42));
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let mut run_shots = || {
+                interpreter
+                    .run_shots(
+                        &mut receiver,
+                        Some("Std.Random.DrawRandomInt(0, 1000000)"),
+                        10,
+                        None,
+                        None,
+                    )
+                    .expect("run_shots should succeed")
+            };
+            let histogram = run_shots();
+            assert!(
+                histogram.buckets().len() > 1,
+                "shots should not all draw the same value"
+            );
+            assert_eq!(histogram, run_shots(), "seeded runs should be repeatable");
+        }
+
+        #[test]
+        fn run_shots_with_pauli_noise_injects_errors_after_gates() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_quantum_seed(Some(0));
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let noise = qsc_eval::noise::PauliNoise::from_probabilities(0.0, 0.0, 0.2)
+                .expect("phase flip noise should be valid");
+            // Only a phase flip between the two Hadamards changes the measured result, so about
+            // 20% of the shots should measure `One`.
+            let histogram = interpreter
+                .run_shots(
+                    &mut receiver,
+                    Some("{ use q = Qubit(); H(q); H(q); MResetZ(q) }"),
+                    1000,
+                    Some(noise),
+                    None,
+                )
+                .expect("run_shots should succeed");
+            let ones = histogram.count(&Value::RESULT_ONE);
+            assert!(
+                (150..250).contains(&ones),
+                "expected about 200 shots to measure One, got {ones}"
+            );
+        }
+
+        #[test]
+        fn run_shots_runtime_failure() {
+            let mut interpreter = get_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            interpreter
+                .run_shots(&mut receiver, Some(r#"{ fail "failed"; }"#), 10, None, None)
+                .expect_err("run_shots should fail");
+        }
+
+        #[test]
+        fn failure_in_nested_use_blocks_releases_qubits() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "operation Fails() : Unit { use qs = Qubit[2]; X(qs[0]); within { H(qs[1]); } apply { use aux = Qubit(); X(aux); fail \"boom\"; } }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Fails()");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: program failed: boom
+                      explicit fail [line_0] [fail "boom"]
+                "#]],
+            );
+            assert!(interpreter.get_qubits().is_empty());
+            let (result, output) = line(&mut interpreter, "use qs = Qubit[3]; MeasureEachZ(qs)");
+            is_only_value(
+                &result,
+                &output,
+                &Value::Array(vec![Value::RESULT_ZERO; 3].into()),
+            );
+        }
+
+        #[test]
+        fn failure_in_apply_block_does_not_reverse_within_block() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "use q = Qubit();");
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                "within { X(q); } apply { fail \"boom\"; }",
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: program failed: boom
+                      explicit fail [line_1] [fail "boom"]
+                "#]],
+            );
+            let (result, output) = line(&mut interpreter, "M(q)");
+            is_only_value(&result, &output, &Value::RESULT_ONE);
+        }
+
+        #[test]
+        fn runtime_error_can_be_matched_by_code() {
+            let mut interpreter = get_interpreter();
+            let (result, _) = line(
+                &mut interpreter,
+                "function Div(a : Int, b : Int) : Int { a / b } Div(1, 0)",
+            );
+            let errors = result.expect_err("division by zero should fail");
+            assert_eq!(errors.len(), 1, "{errors:?}");
+            let error = &errors[0];
+            assert!(matches!(error, crate::interpret::Error::Eval(_)));
+            assert_eq!(
+                error.code().map(|code| code.to_string()).as_deref(),
+                Some("Qsc.Eval.DivZero")
+            );
+            assert!(error.labels().into_iter().flatten().next().is_some());
+            let trace = error
+                .stack_trace()
+                .expect("runtime error should carry a call stack");
+            assert!(trace.contains("at Div in"), "{trace}");
+        }
+
+        #[test]
+        fn unbounded_recursion_exceeds_max_call_depth() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_max_call_depth(Some(100));
+            let (result, output) = line(
+                &mut interpreter,
+                "function Rec(n : Int) : Int { Rec(n + 1) }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Rec(0)");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: maximum call depth of 100 exceeded
+                      call exceeds the maximum depth [line_0] [Rec]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn deep_recursion_below_max_call_depth_does_not_grow_native_stack() {
+            // Calls are pushed onto the evaluator's exec graph stack rather than recursing natively,
+            // so a call chain far deeper than a fixed-size native stack could hold still completes.
+            std::thread::Builder::new()
+                .stack_size(8 * 1024 * 1024)
+                .spawn(|| {
+                    let mut interpreter = get_interpreter();
+                    let (result, output) = line(
+                        &mut interpreter,
+                        "function Depth(n : Int) : Int { if n == 0 { 0 } else { 1 + Depth(n - 1) } }",
+                    );
+                    is_only_value(&result, &output, &Value::unit());
+                    let (result, output) = line(&mut interpreter, "Depth(50000)");
+                    is_only_value(&result, &output, &Value::Int(50000));
+                })
+                .expect("thread should spawn")
+                .join()
+                .expect("thread should not panic");
+        }
+
+        #[test]
+        fn get_scopes_reports_bindings_with_mutability() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "mutable x = (1, [2.0, 3.0]); let y = \"a\";",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let scopes = interpreter
+                .get_scopes()
+                .iter()
+                .map(|scope| {
+                    let variables = scope
+                        .variables
+                        .iter()
+                        .map(|v| {
+                            format!(
+                                "{:?} {}: {} = {}",
+                                v.mutability, v.name, v.type_name, v.value
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    format!("frame {}:\n{variables}", scope.frame_id)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            expect![[r#"
+                frame 0:
+                Mutable x: Tuple = (1, [2.0, 3.0])
+                Immutable y: String = a"#]]
+            .assert_eq(&scopes);
+        }
+
+        #[test]
+        fn run_with_trace_records_operations() {
+            let mut interpreter = get_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let (result, trace) = interpreter.run_with_trace(
+                &mut receiver,
+                Some(
+                    "{ use qs = Qubit[2]; H(qs[0]); CNOT(qs[0], qs[1]); X(qs[1]); MResetZ(qs[1]) }",
+                ),
+            );
+            is_only_value(&result, &receiver.dump(), &Value::RESULT_ZERO);
+            let ops = trace
+                .events()
+                .iter()
+                .map(|event| event.op.to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            expect![[r#"
+                allocate q[0]
+                allocate q[1]
+                H q[0]
+                X q[1] controlled by q[0]
+                X q[1]
+                MResetZ q[1] => Zero
+                label q[1] "qs[1]"
+                release q[1]
+                label q[0] "qs[0]"
+                release q[0]"#]]
+            .assert_eq(&ops);
+        }
+
+        #[test]
+        fn run_with_stats_reports_high_water_marks() {
+            let mut interpreter = get_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let (result, stats) = interpreter.run_with_stats(
+                &mut receiver,
+                Some("{ use qs = Qubit[3]; for q in qs { H(q); } ResetAll(qs); }"),
+            );
+            is_only_value(&result, &receiver.dump(), &Value::unit());
+            expect![[r#"
+                SimulatorStats {
+                    qubit_allocations: 3,
+                    qubits_in_use: 0,
+                    peak_qubits: 3,
+                    peak_state_size: 8,
+                }"#]]
+            .assert_eq(&format!("{stats:#?}"));
+        }
+
+        #[test]
+        fn trace_location_points_to_user_code() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "operation Foo() : Unit { use q = Qubit(); Z(q); }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let (result, trace) = interpreter.run_with_trace(&mut receiver, Some("Foo()"));
+            is_only_value(&result, &receiver.dump(), &Value::unit());
+            let gate = trace
+                .events()
+                .iter()
+                .find(|event| matches!(event.op, crate::interpret::TraceOp::Gate { .. }))
+                .expect("trace should contain a gate");
+            let location = interpreter
+                .get_trace_location(gate, qsc_data_structures::line_column::Encoding::Utf8)
+                .expect("gate should have a user code location");
+            expect!["line_0 0:42"].assert_eq(&format!(
+                "{} {}:{}",
+                location.source, location.range.start.line, location.range.start.column
+            ));
+        }
+
+        #[test]
+        fn trace_sink_receives_events_during_execution() {
+            let mut interpreter = get_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let mut ops = Vec::new();
+            let (result, _) = interpreter.run_with_trace_sink(
+                &mut receiver,
+                Some("{ use q = Qubit(); H(q); Reset(q); }"),
+                crate::interpret::TraceCallback(|event: crate::interpret::TraceEvent| {
+                    ops.push(event.op.to_string());
+                }),
+            );
+            is_only_value(&result, &receiver.dump(), &Value::unit());
+            expect![[r#"
+                allocate q[0]
+                H q[0]
+                Reset q[0]
+                label q[0] "q"
+                release q[0]"#]]
+            .assert_eq(&ops.join("\n"));
+        }
+
+        #[test]
+        fn get_qubits_labels_qubits_with_variable_names() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "use aux = Qubit(); use register = Qubit[2];",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let qubits = interpreter
+                .get_qubits()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
+            expect![[r#"
+                q[0] "aux"
+                q[1] "register[0]"
+                q[2] "register[1]""#]]
+            .assert_eq(&qubits);
+        }
+
+        #[test]
+        fn get_qubits_reports_allocation_sites_in_user_code() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "use aux = Qubit(); use register = Qubit[2];",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let sites = interpreter
+                .get_qubits()
+                .iter()
+                .map(|q| {
+                    let location = crate::location::Location::from(
+                        q.allocated_at.span,
+                        q.allocated_at.package,
+                        interpreter.compiler.package_store(),
+                        qsc_data_structures::line_column::Encoding::Utf8,
+                    );
+                    format!(
+                        "{} {}:{}",
+                        location.source, location.range.start.line, location.range.start.column
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            expect![[r#"
+                line_0 0:4
+                line_0 0:23
+                line_0 0:23"#]]
+            .assert_eq(&sites);
+        }
+
+        #[test]
+        fn restore_checkpoint_rolls_back_state_and_variables() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "use q = Qubit(); X(q); mutable x = 1;");
+            is_only_value(&result, &output, &Value::unit());
+            let checkpoint = interpreter.checkpoint();
+            let (result, output) = line(&mut interpreter, "H(q); set x = 2;");
+            is_only_value(&result, &output, &Value::unit());
+            interpreter.restore(&checkpoint);
+            let (result, output) = line(&mut interpreter, "x");
+            is_only_value(&result, &output, &Value::Int(1));
+            let (result, output) = line(&mut interpreter, "M(q)");
+            is_only_value(&result, &output, &Value::RESULT_ONE);
+        }
+
+        #[test]
+        fn pauli_expectation_of_allocated_qubits() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "use qs = Qubit[2]; X(qs[1]);");
+            is_only_value(&result, &output, &Value::unit());
+            let expectation = interpreter
+                .get_pauli_expectation(&[(crate::fir::Pauli::Z, 0), (crate::fir::Pauli::Z, 1)])
+                .expect("qubits should be valid");
+            assert!((expectation + 1.0).abs() < 1e-9);
+        }
+
+        #[test]
+        fn pauli_expectation_of_unallocated_qubit_fails() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "use q = Qubit();");
+            is_only_value(&result, &output, &Value::unit());
+            let err = interpreter
+                .get_pauli_expectation(&[(crate::fir::Pauli::Z, 1)])
+                .expect_err("qubit 1 should not be allocated");
+            expect!["qubit 1 is not allocated"].assert_eq(&err.to_string());
+        }
+
+        #[test]
+        fn pauli_expectation_of_duplicate_qubit_fails() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "use q = Qubit();");
+            is_only_value(&result, &output, &Value::unit());
+            let err = interpreter
+                .get_pauli_expectation(&[(crate::fir::Pauli::Z, 0), (crate::fir::Pauli::X, 0)])
+                .expect_err("duplicate qubit should fail");
+            expect!["qubit 0 appears more than once"].assert_eq(&err.to_string());
+        }
+
+        #[test]
+        fn checkpoint_can_be_restored_more_than_once() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "use q = Qubit();");
+            is_only_value(&result, &output, &Value::unit());
+            let checkpoint = interpreter.checkpoint();
+            for _ in 0..2 {
+                let (result, output) = line(&mut interpreter, "X(q); M(q)");
+                is_only_value(&result, &output, &Value::RESULT_ONE);
+                interpreter.restore(&checkpoint);
+            }
+        }
+
+        #[test]
+        fn invoke_sweep_over_parameter_grid() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "function Product(a : Double, b : Double) : Double { a * b }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let callable = interpreter
+                .eval_fragments(&mut receiver, "Product")
+                .expect("callable should be evaluated");
+            let parameter_sets =
+                crate::interpret::parameter_grid(&[vec![1.0, 2.0], vec![3.0, 4.0]]);
+            let results =
+                interpreter.invoke_sweep(&mut receiver, &callable, &parameter_sets, None, None);
+            let results = results
+                .into_iter()
+                .map(|(parameters, result)| {
+                    format!(
+                        "{parameters:?} => {}",
+                        result.expect("invocation should succeed")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            expect![[r#"
+                [1.0, 3.0] => 3.0
+                [1.0, 4.0] => 4.0
+                [2.0, 3.0] => 6.0
+                [2.0, 4.0] => 8.0"#]]
+            .assert_eq(&results);
+        }
+
+        #[test]
+        fn invoke_sweep_single_parameter() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "operation Prepare(theta : Double) : Result { use q = Qubit(); Rx(theta, q); MResetZ(q) }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let callable = interpreter
+                .eval_fragments(&mut receiver, "Prepare")
+                .expect("callable should be evaluated");
+            let results = interpreter.invoke_sweep(
+                &mut receiver,
+                &callable,
+                &[vec![0.0], vec![std::f64::consts::PI]],
+                None,
+                None,
+            );
+            assert_eq!(results.len(), 2);
+            is_only_value(&results[0].1, "", &Value::RESULT_ZERO);
+            is_only_value(&results[1].1, "", &Value::RESULT_ONE);
+        }
+
         #[test]
         fn run_parse_error() {
             let mut interpreter = get_interpreter();
@@ -1577,6 +2314,377 @@ mod given_interpreter {
             let (result, output) = line(&mut interpreter, "123");
             is_only_value(&result, &output, &Value::Int(123));
         }
+
+        #[test]
+        fn host_intrinsic_is_called() {
+            let mut interpreter = get_interpreter();
+            interpreter
+                .register_intrinsic("Test.AddOne", |arg| Ok(Value::Int(arg.unwrap_int() + 1)));
+            let (result, output) = line(
+                &mut interpreter,
+                "namespace Test { function AddOne(x : Int) : Int { body intrinsic; } }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Test.AddOne(41)");
+            is_only_value(&result, &output, &Value::Int(42));
+        }
+
+        #[test]
+        fn host_intrinsic_error_is_runtime_error() {
+            let mut interpreter = get_interpreter();
+            interpreter.register_intrinsic("Test.Check", |_| Err("bad input".to_string()));
+            let (result, output) = line(
+                &mut interpreter,
+                "namespace Test { function Check(x : Int) : Int { body intrinsic; } }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Test.Check(1)");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: intrinsic callable `Test.Check` failed: bad input
+                       [line_0] [function Check(x : Int) : Int { body intrinsic; }]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_with_boxed_backend() {
+            use qsc_eval::backend::{Backend, SparseSim};
+
+            let mut interpreter = get_interpreter();
+            let mut sim: Box<dyn Backend> = Box::new(SparseSim::new());
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result = interpreter.run_with_sim(
+                &mut sim,
+                &mut receiver,
+                Some("{ use q = Qubit(); X(q); MResetZ(q) }"),
+            );
+            is_only_value(&result, &receiver.dump(), &Value::RESULT_ONE);
+        }
+
+        #[test]
+        fn run_with_dense_backend() {
+            use qsc_eval::backend::DenseSim;
+
+            let mut interpreter = get_interpreter();
+            let mut sim = DenseSim::new();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result = interpreter.run_with_sim(
+                &mut sim,
+                &mut receiver,
+                Some(indoc! {"{
+                    use qs = Qubit[3];
+                    H(qs[0]);
+                    CNOT(qs[0], qs[1]);
+                    CNOT(qs[1], qs[2]);
+                    Rz(0.5, qs[2]);
+                    Adjoint Rz(0.5, qs[2]);
+                    CNOT(qs[1], qs[2]);
+                    CNOT(qs[0], qs[1]);
+                    H(qs[0]);
+                    X(qs[2]);
+                    let results = MResetEachZ(qs);
+                    results
+                }"}),
+            );
+            is_only_value(
+                &result,
+                &receiver.dump(),
+                &Value::Array(
+                    vec![Value::RESULT_ZERO, Value::RESULT_ZERO, Value::RESULT_ONE].into(),
+                ),
+            );
+        }
+
+        mod backend_agreement {
+            use super::*;
+            use num_bigint::BigUint;
+            use num_complex::Complex64;
+            use qsc_eval::{
+                backend::{Backend, DenseSim, SparseSim},
+                output::{Error, Receiver},
+            };
+
+            const SEED: u64 = 42;
+
+            /// The states dumped by `DumpMachine`, each sorted by basis label.
+            #[derive(Default)]
+            struct Dumps(Vec<(Vec<(BigUint, Complex64)>, usize)>);
+
+            impl Receiver for Dumps {
+                fn state(
+                    &mut self,
+                    mut state: Vec<(BigUint, Complex64)>,
+                    qubit_count: usize,
+                ) -> Result<(), Error> {
+                    state.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+                    self.0.push((state, qubit_count));
+                    Ok(())
+                }
+
+                fn matrix(&mut self, _: Vec<Vec<Complex64>>) -> Result<(), Error> {
+                    Ok(())
+                }
+
+                fn message(&mut self, _: &str) -> Result<(), Error> {
+                    Ok(())
+                }
+            }
+
+            fn run(sim: &mut impl Backend, expr: &str) -> (Value, Dumps) {
+                sim.set_seed(Some(SEED));
+                let mut interpreter = get_interpreter();
+                let mut dumps = Dumps::default();
+                let value = interpreter
+                    .run_with_sim(sim, &mut dumps, Some(expr))
+                    .expect("program should run");
+                (value, dumps)
+            }
+
+            /// Runs the program on the sparse and dense simulators with the same seed, and checks
+            /// that they return the same value and dump the same states up to global phase. The
+            /// backends draw measurement outcomes from different random sources, so programs
+            /// should only measure qubits that are in a basis state.
+            fn assert_backends_agree(expr: &str) {
+                let (sparse_value, sparse) = run(&mut SparseSim::new(), expr);
+                let (dense_value, dense) = run(&mut DenseSim::new(), expr);
+                assert_eq!(dense_value, sparse_value);
+                assert_eq!(
+                    dense.0.len(),
+                    sparse.0.len(),
+                    "backends should dump equally often"
+                );
+                for ((actual, actual_count), (expected, expected_count)) in
+                    dense.0.iter().zip(&sparse.0)
+                {
+                    assert_eq!(actual_count, expected_count);
+                    assert_eq!(
+                        actual.iter().map(|(label, _)| label).collect::<Vec<_>>(),
+                        expected.iter().map(|(label, _)| label).collect::<Vec<_>>(),
+                    );
+                    let Some(((_, first), (_, expected_first))) =
+                        actual.first().zip(expected.first())
+                    else {
+                        continue;
+                    };
+                    let phase = expected_first / first;
+                    for ((_, amplitude), (_, expected_amplitude)) in actual.iter().zip(expected) {
+                        assert!(
+                            (amplitude * phase - expected_amplitude).norm() < 1e-10,
+                            "dense state {actual:?} should match sparse state {expected:?}"
+                        );
+                    }
+                }
+            }
+
+            #[test]
+            fn entangling_circuit_and_its_inverse() {
+                assert_backends_agree(indoc! {"{
+                    use qs = Qubit[3];
+                    H(qs[0]);
+                    CNOT(qs[0], qs[1]);
+                    Ry(0.3, qs[2]);
+                    CCNOT(qs[0], qs[2], qs[1]);
+                    Std.Diagnostics.DumpMachine();
+                    Adjoint CCNOT(qs[0], qs[2], qs[1]);
+                    Adjoint Ry(0.3, qs[2]);
+                    CNOT(qs[0], qs[1]);
+                    H(qs[0]);
+                    Std.Diagnostics.DumpMachine();
+                    MResetEachZ(qs)
+                }"});
+            }
+
+            #[test]
+            fn rotations_and_phase_gates_on_superpositions() {
+                assert_backends_agree(indoc! {"{
+                    use qs = Qubit[4];
+                    for q in qs {
+                        H(q);
+                    }
+                    T(qs[0]);
+                    S(qs[1]);
+                    Rx(0.7, qs[2]);
+                    Rz(-1.1, qs[3]);
+                    Std.Diagnostics.DumpMachine();
+                    Rzz(0.4, qs[0], qs[3]);
+                    Controlled Ry([qs[1]], (0.9, qs[2]));
+                    Std.Diagnostics.DumpMachine();
+                    ResetAll(qs);
+                }"});
+            }
+
+            #[test]
+            fn swaps_and_releases_across_unentangled_qubits() {
+                assert_backends_agree(indoc! {"{
+                    use outer = Qubit[2];
+                    X(outer[0]);
+                    H(outer[1]);
+                    {
+                        use inner = Qubit[2];
+                        SWAP(outer[0], inner[1]);
+                        CNOT(outer[1], inner[0]);
+                        Std.Diagnostics.DumpMachine();
+                        CNOT(outer[1], inner[0]);
+                        SWAP(outer[0], inner[1]);
+                    }
+                    Std.Diagnostics.DumpMachine();
+                    H(outer[1]);
+                    MResetEachZ(outer)
+                }"});
+            }
+
+            #[test]
+            fn measuring_basis_states_gives_equal_results() {
+                assert_backends_agree(indoc! {"{
+                    use qs = Qubit[3];
+                    X(qs[1]);
+                    CNOT(qs[1], qs[2]);
+                    let results = [M(qs[0]), M(qs[1]), M(qs[2])];
+                    Std.Diagnostics.DumpMachine();
+                    ResetAll(qs);
+                    results
+                }"});
+            }
+        }
+
+        #[test]
+        fn unregistered_intrinsic_is_unknown() {
+            let mut interpreter = get_interpreter();
+            interpreter.register_intrinsic("Other.Check", |_| Ok(Value::Int(0)));
+            let (result, output) = line(
+                &mut interpreter,
+                "namespace Test { function Check(x : Int) : Int { body intrinsic; } }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, _) = line(&mut interpreter, "Test.Check(1)");
+            result.expect_err("unregistered intrinsic should fail");
+        }
+
+        #[test]
+        fn file_io_is_disabled_by_default() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "Std.IO.ReadTextFile(\"data.txt\")");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: intrinsic callable `ReadTextFile` failed: file access is not enabled by the host
+                       [line_0] [Std.IO.ReadTextFile]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn file_io_round_trips_when_enabled() {
+            let mut interpreter = get_interpreter();
+            interpreter.enable_file_io();
+            let path = std::env::temp_dir().join(format!("qsc_file_io_{}.txt", std::process::id()));
+            let path = path
+                .to_str()
+                .expect("temp path should be valid UTF-8")
+                .replace('\\', "/");
+            let (result, output) = line(
+                &mut interpreter,
+                &format!("Std.IO.WriteTextFile(\"{path}\", \"0.5,0.25\")"),
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                &format!("Std.IO.ReadTextFile(\"{path}\")"),
+            );
+            is_only_value(&result, &output, &Value::String("0.5,0.25".into()));
+            std::fs::remove_file(&path).expect("file should be removed");
+        }
+
+        #[test]
+        fn environment_access_is_disabled_by_default() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "Std.Environment.CommandLineArguments()");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: intrinsic callable `CommandLineArguments` failed: environment access is not enabled by the host
+                       [line_0] [Std.Environment.CommandLineArguments]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn environment_access_returns_arguments_and_variables_when_enabled() {
+            let mut interpreter = get_interpreter();
+            interpreter.enable_environment_access(vec!["100".to_string()]);
+            let (result, output) = line(&mut interpreter, "Std.Environment.CommandLineArguments()");
+            is_only_value(
+                &result,
+                &output,
+                &Value::Array(vec![Value::String("100".into())].into()),
+            );
+            let (result, output) = line(
+                &mut interpreter,
+                "Std.Environment.TryEnvironmentVariable(\"QSC_TEST_UNSET_VARIABLE\")",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple([Value::Bool(false), Value::String("".into())].into(), None),
+            );
+        }
+
+        #[test]
+        fn struct_values_compare_structurally() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "struct Pair { First : Int, Second : (Double, String[]) }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                "new Pair { First = 1, Second = (2.0, [\"a\"]) } == new Pair { First = 1, Second = (2.0, [\"a\"]) }",
+            );
+            is_only_value(&result, &output, &Value::Bool(true));
+            let (result, output) = line(
+                &mut interpreter,
+                "new Pair { First = 1, Second = (2.0, [\"a\"]) } != new Pair { First = 1, Second = (2.0, [\"b\"]) }",
+            );
+            is_only_value(&result, &output, &Value::Bool(true));
+        }
+
+        #[test]
+        fn nested_arrays_tuples_and_ranges_compare_structurally() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "[(1, PauliX, 0..2), (2, PauliZ, 3..-1..0)] == [(1, PauliX, 0..2), (2, PauliZ, 3..-1..0)]",
+            );
+            is_only_value(&result, &output, &Value::Bool(true));
+            let (result, output) = line(&mut interpreter, "[[1, 2], [3]] == [[1, 2], [3, 4]]");
+            is_only_value(&result, &output, &Value::Bool(false));
+        }
+
+        #[test]
+        fn struct_with_callable_field_does_not_support_equality() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "struct Op { F : Int -> Int }");
+            is_only_value(&result, &output, &Value::unit());
+            let (result, _) = line(
+                &mut interpreter,
+                "function Id(x : Int) : Int { x } new Op { F = Id } == new Op { F = Id }",
+            );
+            let errors = result.expect_err("comparing callables should fail");
+            assert!(
+                errors.iter().any(|e| e
+                    .code()
+                    .is_some_and(|code| code.to_string() == "Qsc.TypeCk.MissingClassEq")),
+                "{errors:?}"
+            );
+        }
     }
 
     fn get_interpreter() -> Interpreter {
@@ -1608,6 +2716,21 @@ mod given_interpreter {
         .expect("interpreter should be created")
     }
 
+    fn get_interpreter_with_language_features(language_features: LanguageFeatures) -> Interpreter {
+        let (std_id, store) =
+            crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+        let dependencies = &[(std_id, None)];
+        Interpreter::new(
+            SourceMap::default(),
+            PackageType::Lib,
+            TargetCapabilityFlags::all(),
+            language_features,
+            store,
+            dependencies,
+        )
+        .expect("interpreter should be created")
+    }
+
     fn is_only_value(result: &InterpretResult, output: &str, value: &Value) {
         assert_eq!("", output);
 
@@ -1726,6 +2849,38 @@ mod given_interpreter {
             is_unit_with_output_eval_entry(&result, &output, "hello there...");
         }
 
+        #[test]
+        fn run_shots_without_expr_runs_entry_point() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Int {
+                    7
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut interpreter = Interpreter::new(
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created");
+
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let histogram = interpreter
+                .run_shots(&mut receiver, None, 3, None, None)
+                .expect("run_shots should succeed");
+            assert_eq!(histogram.shots(), 3);
+            assert_eq!(histogram.count(&Value::Int(7)), 3);
+        }
+
         #[test]
         fn invalid_partial_application_should_fail_not_panic() {
             // Found via fuzzing, see #2363
@@ -2131,11 +3286,8 @@ mod given_interpreter {
                 LanguageFeatures::default(),
             );
 
-            let mut store = crate::PackageStore::new(crate::compile::core());
-            let dependencies = vec![(
-                store.insert(crate::compile::std(&store, capabilities)),
-                None,
-            )];
+            let (std_id, mut store) = crate::compile::package_store_with_stdlib(capabilities);
+            let dependencies = vec![(std_id, None)];
 
             let (mut unit, errors) = crate::compile::compile(
                 &store,
@@ -3,12 +3,21 @@
 
 pub mod codegen;
 pub mod compile;
+pub mod diagnostic_json;
 pub mod error;
+pub mod events;
+pub mod explain;
+mod features;
 pub mod incremental;
 pub mod interpret;
 pub mod location;
+#[cfg(feature = "tracing")]
+pub mod logging;
 pub mod packages;
+pub mod remote;
+pub mod sarif;
 
+pub use features::{Features, features};
 pub use qsc_formatter::formatter;
 
 pub use qsc_frontend::compile::{CompileUnit, PackageStore};
@@ -50,8 +59,8 @@ pub mod line_column {
 }
 
 pub use qsc_eval::{
-    backend::{Backend, SparseSim},
-    noise::PauliNoise,
+    backend::{Backend, DenseSim, SimulatorStats, SparseSim, Tolerance},
+    noise::{NoiseConfig, PauliNoise},
     state::{
         fmt_basis_state_label, fmt_complex, format_state_id, get_matrix_latex, get_phase,
         get_state_latex,
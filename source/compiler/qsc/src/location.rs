@@ -218,15 +218,10 @@ mod tests {
     }
 
     fn compile_package() -> (PackageStore, PackageId, PackageId) {
-        let mut store = PackageStore::new(compile::core());
-        let mut dependencies = Vec::new();
-
         let (package_type, capabilities) = (PackageType::Lib, TargetCapabilityFlags::all());
 
-        let std = compile::std(&store, capabilities);
-        let std_package_id = store.insert(std);
-
-        dependencies.push((std_package_id, None));
+        let (std_package_id, mut store) = compile::package_store_with_stdlib(capabilities);
+        let dependencies = vec![(std_package_id, None)];
         let sources = SourceMap::new(
             [
                 ("foo.qs".into(), "namespace Foo { }".into()),
@@ -0,0 +1,26 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Structured logging of the lexer, parser, resolver, type checker, and evaluator, which emit
+//! `tracing` spans and events when built with the `tracing` feature.
+//!
+//! Hosts call [`init`] once at startup to write them out. The Python package does so when it is
+//! built with its own `tracing` feature, for example with `maturin develop --features tracing`.
+
+use tracing_subscriber::{EnvFilter, fmt::format::FmtSpan};
+
+/// The environment variable holding the filter directives, such as
+/// `QSC_LOG=qsc_frontend=debug,qsc_eval=trace`.
+pub const FILTER_ENV_VAR: &str = "QSC_LOG";
+
+/// Installs a global subscriber that writes spans, with their durations, and events to stderr,
+/// filtered by the directives in [`FILTER_ENV_VAR`]. Only warnings are written if it is unset.
+/// Does nothing if a global subscriber is already installed.
+pub fn init() {
+    let filter = EnvFilter::try_from_env(FILTER_ENV_VAR).unwrap_or_else(|_| EnvFilter::new("warn"));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
@@ -0,0 +1,292 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Submission of compiled QIR programs to a remote job service.
+//!
+//! The service itself is reached through a [`JobTransport`] supplied by the host, so the
+//! same client can be used with a REST endpoint, a cloud SDK, or an in-process mock.
+
+#[cfg(test)]
+mod tests;
+
+use async_trait::async_trait;
+use miette::Diagnostic;
+use qsc_eval::val::{self, Value};
+use qsc_hir::ty::{Prim, Ty};
+use std::rc::Rc;
+use thiserror::Error;
+
+/// Describes where and how a job should be run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProviderProfile {
+    /// The provider that owns the target, for example `quantinuum`.
+    pub provider: String,
+    /// The target to run the job on, for example `quantinuum.sim.h1-1e`.
+    pub target: String,
+    /// The number of shots to request.
+    pub shots: u32,
+    /// The maximum number of status polls before the job is considered timed out.
+    pub max_polls: u32,
+}
+
+/// The status of a submitted job, as reported by the transport.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JobStatus {
+    Waiting,
+    Executing,
+    /// The job completed, with a histogram of raw output strings and their shot counts.
+    Succeeded(Vec<(String, u32)>),
+    Failed(String),
+    Cancelled,
+}
+
+/// The transport used to talk to the remote job service.
+#[async_trait(?Send)]
+pub trait JobTransport {
+    /// Submits the QIR program and returns the identifier of the created job.
+    async fn submit(
+        &self,
+        profile: &ProviderProfile,
+        name: &str,
+        qir: &str,
+    ) -> Result<String, String>;
+
+    /// Gets the current status of a job. Implementations are responsible for any delay
+    /// between consecutive polls.
+    async fn status(&self, profile: &ProviderProfile, job_id: &str) -> Result<JobStatus, String>;
+}
+
+#[derive(Clone, Debug, Diagnostic, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("job submission failed: {0}")]
+    #[diagnostic(code("Qsc.Remote.SubmitFailed"))]
+    SubmitFailed(String),
+
+    #[error("could not get status of job `{0}`: {1}")]
+    #[diagnostic(code("Qsc.Remote.StatusFailed"))]
+    StatusFailed(String, String),
+
+    #[error("job `{0}` failed: {1}")]
+    #[diagnostic(code("Qsc.Remote.JobFailed"))]
+    JobFailed(String, String),
+
+    #[error("job `{0}` was cancelled")]
+    #[diagnostic(code("Qsc.Remote.JobCancelled"))]
+    JobCancelled(String),
+
+    #[error("job `{0}` did not complete after {1} status checks")]
+    #[diagnostic(code("Qsc.Remote.Timeout"))]
+    Timeout(String, u32),
+
+    #[error("could not interpret job output `{0}`")]
+    #[diagnostic(code("Qsc.Remote.InvalidOutput"))]
+    InvalidOutput(String),
+}
+
+/// A client that submits QIR to a remote job service and maps the results back into values.
+pub struct RemoteClient<T: JobTransport> {
+    transport: T,
+    profile: ProviderProfile,
+}
+
+impl<T: JobTransport> RemoteClient<T> {
+    #[must_use]
+    pub fn new(transport: T, profile: ProviderProfile) -> Self {
+        Self { transport, profile }
+    }
+
+    #[must_use]
+    pub fn profile(&self) -> &ProviderProfile {
+        &self.profile
+    }
+
+    /// Submits the given QIR program, waits for it to complete, and returns the histogram
+    /// of output values with their shot counts. The outputs are interpreted as values of
+    /// `output_ty`, the declared return type of the program's entry point.
+    pub async fn run(
+        &self,
+        name: &str,
+        qir: &str,
+        output_ty: &Ty,
+    ) -> Result<Vec<(Value, u32)>, Error> {
+        let job_id = self
+            .transport
+            .submit(&self.profile, name, qir)
+            .await
+            .map_err(Error::SubmitFailed)?;
+
+        for _ in 0..self.profile.max_polls {
+            let status = self
+                .transport
+                .status(&self.profile, &job_id)
+                .await
+                .map_err(|e| Error::StatusFailed(job_id.clone(), e))?;
+            match status {
+                JobStatus::Waiting | JobStatus::Executing => {}
+                JobStatus::Succeeded(histogram) => {
+                    return parse_histogram(&histogram, output_ty);
+                }
+                JobStatus::Failed(message) => return Err(Error::JobFailed(job_id, message)),
+                JobStatus::Cancelled => return Err(Error::JobCancelled(job_id)),
+            }
+        }
+
+        Err(Error::Timeout(job_id, self.profile.max_polls))
+    }
+}
+
+/// Converts a histogram of raw output strings into values of the given type.
+pub fn parse_histogram(
+    histogram: &[(String, u32)],
+    output_ty: &Ty,
+) -> Result<Vec<(Value, u32)>, Error> {
+    histogram
+        .iter()
+        .map(|(output, count)| {
+            parse_output(output, output_ty)
+                .map(|value| (value, *count))
+                .ok_or_else(|| Error::InvalidOutput(output.clone()))
+        })
+        .collect()
+}
+
+/// Parses a single output string as reported by a job service, for example `[0, 1]` or
+/// `(true, 5)`, as a value of the given type. Arrays and tuples are written with brackets
+/// and parentheses, `Result` values as `0`, `1`, `Zero`, `One` or `Loss`, strings as
+/// double-quoted literals with backslash escapes, and the remaining primitive types as their
+/// Q# literals. Returns `None` if the output does not match the type or the type cannot be
+/// reported by a job.
+#[must_use]
+pub fn parse_output(output: &str, ty: &Ty) -> Option<Value> {
+    let mut parser = OutputParser { rest: output };
+    let value = parser.value(ty)?;
+    parser.rest.trim().is_empty().then_some(value)
+}
+
+struct OutputParser<'a> {
+    rest: &'a str,
+}
+
+impl OutputParser<'_> {
+    fn value(&mut self, ty: &Ty) -> Option<Value> {
+        self.rest = self.rest.trim_start();
+        match ty {
+            Ty::Array(item) => {
+                if !self.eat('[') {
+                    return None;
+                }
+                let mut items = Vec::new();
+                self.rest = self.rest.trim_start();
+                if !self.eat(']') {
+                    loop {
+                        items.push(self.value(item)?);
+                        if self.separator(']')? {
+                            break;
+                        }
+                    }
+                }
+                Some(Value::Array(items.into()))
+            }
+            Ty::Tuple(tys) => {
+                if !self.eat('(') {
+                    return None;
+                }
+                let mut items = Vec::with_capacity(tys.len());
+                for (index, ty) in tys.iter().enumerate() {
+                    items.push(self.value(ty)?);
+                    if self.separator(')')? != (index + 1 == tys.len()) {
+                        return None;
+                    }
+                }
+                if tys.is_empty() {
+                    self.rest = self.rest.trim_start();
+                    if !self.eat(')') {
+                        return None;
+                    }
+                }
+                Some(Value::Tuple(items.into(), None))
+            }
+            Ty::Prim(Prim::String) => self.string().map(|s| Value::String(Rc::from(s))),
+            Ty::Prim(prim) => {
+                let end = self
+                    .rest
+                    .find(|c: char| matches!(c, ',' | ']' | ')') || c.is_whitespace())
+                    .unwrap_or(self.rest.len());
+                let (token, rest) = self.rest.split_at(end);
+                self.rest = rest;
+                parse_scalar(token, *prim)
+            }
+            _ => None,
+        }
+    }
+
+    /// Consumes the separator after an item, returning `Some(true)` if it closed the
+    /// enclosing array or tuple and `Some(false)` if it was a comma.
+    fn separator(&mut self, close: char) -> Option<bool> {
+        self.rest = self.rest.trim_start();
+        if self.eat(close) {
+            Some(true)
+        } else if self.eat(',') {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let mut chars = self.rest.strip_prefix('"')?.char_indices();
+        let mut value = String::new();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[index + 2..];
+                    return Some(value);
+                }
+                '\\' => value.push(match chars.next()?.1 {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    c @ ('"' | '\\') => c,
+                    _ => return None,
+                }),
+                c => value.push(c),
+            }
+        }
+        None
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn parse_scalar(token: &str, prim: Prim) -> Option<Value> {
+    match prim {
+        Prim::Result => match token {
+            "0" | "Zero" => Some(Value::RESULT_ZERO),
+            "1" | "One" => Some(Value::RESULT_ONE),
+            "Loss" => Some(Value::Result(val::Result::Loss)),
+            _ => None,
+        },
+        Prim::Bool => match token {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        Prim::Int => token.parse().map(Value::Int).ok(),
+        Prim::BigInt => token
+            .strip_suffix('L')
+            .unwrap_or(token)
+            .parse()
+            .map(Value::BigInt)
+            .ok(),
+        Prim::Double => token.parse().map(Value::Double).ok(),
+        _ => None,
+    }
+}
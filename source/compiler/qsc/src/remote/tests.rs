@@ -0,0 +1,153 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{Error, JobStatus, JobTransport, ProviderProfile, RemoteClient, parse_output};
+use async_trait::async_trait;
+use expect_test::expect;
+use qsc_hir::ty::{Prim, Ty};
+use std::cell::{Cell, RefCell};
+
+struct MockTransport {
+    statuses: RefCell<Vec<JobStatus>>,
+    polls: Cell<u32>,
+}
+
+impl MockTransport {
+    fn new(mut statuses: Vec<JobStatus>) -> Self {
+        // Statuses are popped from the end, so store them in reverse order.
+        statuses.reverse();
+        Self {
+            statuses: RefCell::new(statuses),
+            polls: Cell::new(0),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl JobTransport for MockTransport {
+    async fn submit(
+        &self,
+        profile: &ProviderProfile,
+        name: &str,
+        qir: &str,
+    ) -> Result<String, String> {
+        if qir.is_empty() {
+            Err("empty program".to_string())
+        } else {
+            Ok(format!("{}/{name}", profile.target))
+        }
+    }
+
+    async fn status(&self, _profile: &ProviderProfile, _job_id: &str) -> Result<JobStatus, String> {
+        self.polls.set(self.polls.get() + 1);
+        Ok(self
+            .statuses
+            .borrow_mut()
+            .pop()
+            .unwrap_or(JobStatus::Executing))
+    }
+}
+
+fn results() -> Ty {
+    Ty::Array(Box::new(Ty::Prim(Prim::Result)))
+}
+
+fn profile() -> ProviderProfile {
+    ProviderProfile {
+        provider: "mock".to_string(),
+        target: "mock.sim".to_string(),
+        shots: 10,
+        max_polls: 3,
+    }
+}
+
+#[tokio::test]
+async fn successful_job_returns_histogram_values() {
+    let transport = MockTransport::new(vec![
+        JobStatus::Waiting,
+        JobStatus::Executing,
+        JobStatus::Succeeded(vec![("[0, 1]".to_string(), 4), ("[1, 1]".to_string(), 6)]),
+    ]);
+    let client = RemoteClient::new(transport, profile());
+    let histogram = client
+        .run("job", "qir", &results())
+        .await
+        .expect("job should succeed");
+    let histogram = histogram
+        .iter()
+        .map(|(value, count)| format!("{value}: {count}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    expect!["[Zero, One]: 4, [One, One]: 6"].assert_eq(&histogram);
+    assert_eq!(client.transport.polls.get(), 3);
+}
+
+#[tokio::test]
+async fn job_that_never_completes_times_out() {
+    let client = RemoteClient::new(MockTransport::new(Vec::new()), profile());
+    let error = client
+        .run("job", "qir", &results())
+        .await
+        .expect_err("job should time out");
+    assert_eq!(error, Error::Timeout("mock.sim/job".to_string(), 3));
+}
+
+#[tokio::test]
+async fn failed_job_reports_error() {
+    let transport = MockTransport::new(vec![JobStatus::Failed("out of credits".to_string())]);
+    let client = RemoteClient::new(transport, profile());
+    let error = client
+        .run("job", "qir", &results())
+        .await
+        .expect_err("job should fail");
+    expect!["job `mock.sim/job` failed: out of credits"].assert_eq(&error.to_string());
+}
+
+#[tokio::test]
+async fn failed_submission_reports_error() {
+    let client = RemoteClient::new(MockTransport::new(Vec::new()), profile());
+    let error = client
+        .run("job", "", &results())
+        .await
+        .expect_err("submission should fail");
+    expect!["job submission failed: empty program"].assert_eq(&error.to_string());
+}
+
+#[test]
+fn parse_nested_output() {
+    let ty = Ty::Tuple(vec![
+        Ty::Prim(Prim::Bool),
+        results(),
+        Ty::Prim(Prim::Int),
+        Ty::Prim(Prim::Double),
+    ]);
+    let value = parse_output("(true, [1, 0], 5, 1.5)", &ty).expect("output should parse");
+    expect!["(true, [One, Zero], 5, 1.5)"].assert_eq(&value.to_string());
+}
+
+#[test]
+fn parse_int_output_is_not_read_as_result() {
+    let ty = Ty::Array(Box::new(Ty::Prim(Prim::Int)));
+    let value = parse_output("[0, 1]", &ty).expect("output should parse");
+    expect!["Array([Int(0), Int(1)])"].assert_eq(&format!("{value:?}"));
+}
+
+#[test]
+fn parse_string_output_with_separators() {
+    let ty = Ty::Tuple(vec![Ty::Prim(Prim::String), Ty::Prim(Prim::Int)]);
+    let value = parse_output(r#"("a, b] c) \"d\"", 1)"#, &ty).expect("output should parse");
+    expect![[r#"Tuple([String("a, b] c) \"d\""), Int(1)], None)"#]]
+        .assert_eq(&format!("{value:?}"));
+}
+
+#[test]
+fn parse_invalid_output_fails() {
+    assert!(parse_output("[0, 1", &results()).is_none());
+    assert!(parse_output("[0 1]", &results()).is_none());
+    assert!(parse_output("[0, 2]", &results()).is_none());
+    assert!(parse_output("(0, 1)", &results()).is_none());
+    let pair = Ty::Tuple(vec![Ty::Prim(Prim::Int), Ty::Prim(Prim::Int)]);
+    assert!(parse_output("(1)", &pair).is_none());
+    assert!(parse_output("(1, 2, 3)", &pair).is_none());
+    assert!(parse_output(r#""unterminated"#, &Ty::Prim(Prim::String)).is_none());
+}
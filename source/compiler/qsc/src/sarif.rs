@@ -0,0 +1,196 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Rendering of diagnostics as a SARIF 2.1.0 log, the format read by code scanning tools to
+//! annotate pull requests.
+
+#[cfg(test)]
+mod tests;
+
+use crate::error::WithSource;
+use miette::{Diagnostic, LabeledSpan, Severity};
+use qsc_data_structures::line_column::{Encoding, Position};
+use serde::Serialize;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Serialize)]
+struct Log {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    column_kind: &'static str,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+struct Rule {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    level: &'static str,
+    message: Message,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+}
+
+/// Renders the diagnostics as a SARIF log with a single run.
+///
+/// The primary label of each diagnostic becomes its location and any other labels become
+/// related locations. Artifact URIs are the source names, and lines and columns are one-based
+/// with columns counted in UTF-16 code units. Each distinct diagnostic code is listed as a rule,
+/// using the help text of its first occurrence.
+#[must_use]
+pub fn to_sarif<E: Diagnostic + Send + Sync>(diagnostics: &[WithSource<E>]) -> String {
+    let mut rules = Vec::<Rule>::new();
+    let mut results = Vec::new();
+    for diagnostic in diagnostics {
+        let rule_id = diagnostic.code().map(|code| code.to_string());
+        if let Some(id) = &rule_id
+            && !rules.iter().any(|rule| &rule.id == id)
+        {
+            rules.push(Rule {
+                id: id.clone(),
+                help: diagnostic.help().map(|help| Message {
+                    text: help.to_string(),
+                }),
+            });
+        }
+
+        let mut labels = diagnostic
+            .labels()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        if let Some(primary) = labels.iter().position(LabeledSpan::primary) {
+            labels[..=primary].rotate_right(1);
+        }
+        let mut locations = labels.iter().map(|label| location(diagnostic, label));
+        let primary = locations.next();
+
+        results.push(SarifResult {
+            rule_id,
+            level: match diagnostic.severity() {
+                None | Some(Severity::Error) => "error",
+                Some(Severity::Warning) => "warning",
+                Some(Severity::Advice) => "note",
+            },
+            message: Message {
+                text: diagnostic.to_string(),
+            },
+            related_locations: locations.collect(),
+            locations: primary.into_iter().collect(),
+        });
+    }
+
+    let log = Log {
+        schema: SCHEMA,
+        version: "2.1.0",
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "qsc",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: "https://github.com/microsoft/qsharp",
+                    rules,
+                },
+            },
+            column_kind: "utf16CodeUnits",
+            results,
+        }],
+    };
+    serde_json::to_string_pretty(&log).expect("SARIF log should serialize to JSON")
+}
+
+fn location<E: Diagnostic + Send + Sync>(
+    diagnostic: &WithSource<E>,
+    label: &LabeledSpan,
+) -> Location {
+    let (source, span) = diagnostic.resolve_span(label.inner());
+    let position = |offset: usize| {
+        let offset = u32::try_from(offset).expect("offset should fit into u32");
+        Position::from_utf8_byte_offset(Encoding::Utf16, &source.contents, offset)
+    };
+    let start = position(span.offset());
+    let end = position(span.offset() + span.len());
+    Location {
+        physical_location: PhysicalLocation {
+            artifact_location: ArtifactLocation {
+                uri: source.name.to_string(),
+            },
+            region: Region {
+                start_line: start.line + 1,
+                start_column: start.column + 1,
+                end_line: end.line + 1,
+                end_column: end.column + 1,
+            },
+        },
+        message: label.label().map(|text| Message {
+            text: text.to_string(),
+        }),
+    }
+}
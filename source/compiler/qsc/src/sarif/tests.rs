@@ -0,0 +1,56 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::to_sarif;
+use crate::compile::CompilerBuilder;
+use serde_json::{Value, json};
+
+fn sarif_for(source: &str) -> Value {
+    let compilation = CompilerBuilder::new().source("Main.qs", source).build();
+    serde_json::from_str(&to_sarif(compilation.errors())).expect("SARIF log should be valid JSON")
+}
+
+#[test]
+fn compile_error_is_reported_with_location_and_rule() {
+    let log = sarif_for("function Foo() : Int { Bar() }");
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "qsc");
+    assert_eq!(
+        run["tool"]["driver"]["rules"],
+        json!([{ "id": "Qsc.Resolve.NotFound" }])
+    );
+    assert_eq!(
+        run["results"],
+        json!([{
+            "ruleId": "Qsc.Resolve.NotFound",
+            "level": "error",
+            "message": { "text": "`Bar` not found" },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "Main.qs" },
+                    "region": { "startLine": 1, "startColumn": 24, "endLine": 1, "endColumn": 27 }
+                }
+            }]
+        }])
+    );
+}
+
+#[test]
+fn columns_are_counted_in_utf16_code_units() {
+    let log = sarif_for("function Foo() : Int {\n    let s = \"🙂\"; Bar()\n}");
+
+    assert_eq!(
+        log["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["region"],
+        json!({ "startLine": 2, "startColumn": 19, "endLine": 2, "endColumn": 22 })
+    );
+}
+
+#[test]
+fn no_diagnostics_produce_empty_run() {
+    let log = sarif_for("function Foo() : Int { 42 }");
+
+    assert_eq!(log["runs"][0]["results"], json!([]));
+    assert_eq!(log["runs"][0]["tool"]["driver"]["rules"], json!([]));
+}
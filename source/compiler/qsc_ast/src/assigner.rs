@@ -3,8 +3,8 @@
 
 use crate::{
     ast::{
-        Attr, Block, CallableDecl, Expr, FieldAssign, FunctorExpr, Ident, Item, Namespace, NodeId,
-        Package, Pat, Path, QubitInit, SpecDecl, Stmt, Ty, TyDef,
+        Attr, Block, CallableDecl, Expr, FieldAssign, FunctorExpr, Ident, Item, MatchArm,
+        Namespace, NodeId, Package, Pat, Path, QubitInit, SpecDecl, Stmt, Ty, TyDef,
     },
     mut_visit::{self, MutVisitor},
 };
@@ -117,6 +117,11 @@ impl MutVisitor for Assigner {
         mut_visit::walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &mut MatchArm) {
+        self.assign(&mut arm.id);
+        mut_visit::walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &mut Pat) {
         self.assign(&mut pat.id);
         mut_visit::walk_pat(self, pat);
@@ -13,7 +13,7 @@ use std::{
     fmt::{self, Display, Formatter, Write},
     hash::{Hash, Hasher},
     iter::once,
-    rc::Rc,
+    sync::Arc,
 };
 
 fn set_indentation<'a, 'b>(
@@ -163,7 +163,7 @@ pub struct Namespace {
     /// The span.
     pub span: Span,
     /// The documentation.
-    pub doc: Rc<str>,
+    pub doc: Arc<str>,
     /// The namespace name.
     pub name: Box<[Ident]>,
     /// The items in the namespace.
@@ -223,7 +223,7 @@ pub struct Item {
     /// The span.
     pub span: Span,
     /// The documentation.
-    pub doc: Rc<str>,
+    pub doc: Arc<str>,
     /// The attributes.
     pub attrs: Box<[Box<Attr>]>,
     /// The item kind.
@@ -894,6 +894,8 @@ pub enum ExprKind {
     Lambda(CallableKind, Box<Pat>, Box<Expr>),
     /// A literal.
     Lit(Box<Lit>),
+    /// A match expression: `match a { b => c, _ => d }`.
+    Match(Box<Expr>, Box<[Box<MatchArm>]>),
     /// Parentheses: `(a)`.
     Paren(Box<Expr>),
     /// A path: `a` or `a.b`.
@@ -941,6 +943,7 @@ impl Display for ExprKind {
             ExprKind::Interpolate(components) => display_interpolate(indent, components)?,
             ExprKind::Lambda(kind, param, expr) => display_lambda(indent, *kind, param, expr)?,
             ExprKind::Lit(lit) => write!(indent, "Lit: {lit}")?,
+            ExprKind::Match(scrutinee, arms) => display_match(indent, scrutinee, arms)?,
             ExprKind::Paren(e) => write!(indent, "Paren: {e}")?,
             ExprKind::Path(p) => write!(indent, "Path: {p}")?,
             ExprKind::Range(start, step, end) => {
@@ -1127,6 +1130,20 @@ fn display_lambda(
     Ok(())
 }
 
+fn display_match(
+    mut indent: Indented<Formatter>,
+    scrutinee: &Expr,
+    arms: &[Box<MatchArm>],
+) -> fmt::Result {
+    write!(indent, "Match:")?;
+    indent = set_indentation(indent, 1);
+    write!(indent, "\n{scrutinee}")?;
+    for arm in arms {
+        write!(indent, "\n{arm}")?;
+    }
+    Ok(())
+}
+
 fn display_range(
     mut indent: Indented<Formatter>,
     start: Option<&Expr>,
@@ -1260,13 +1277,43 @@ impl Display for FieldAssign {
     }
 }
 
+/// An arm of a match expression.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchArm {
+    /// The node ID.
+    pub id: NodeId,
+    /// The span.
+    pub span: Span,
+    /// The pattern, which is either a literal or a hole, `_`, that matches any value.
+    pub pat: Box<Expr>,
+    /// The value of the match expression when the pattern matches.
+    pub body: Box<Expr>,
+}
+
+impl WithSpan for MatchArm {
+    fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+}
+
+impl Display for MatchArm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut indent = set_indentation(indented(f), 0);
+        write!(indent, "MatchArm {} {}:", self.id, self.span)?;
+        indent = set_indentation(indent, 1);
+        write!(indent, "\n{}", self.pat)?;
+        write!(indent, "\n{}", self.body)?;
+        Ok(())
+    }
+}
+
 /// An interpolated string component.
 #[derive(Clone, Debug, PartialEq)]
 pub enum StringComponent {
     /// An expression.
     Expr(Box<Expr>),
     /// A string literal.
-    Lit(Rc<str>),
+    Lit(Arc<str>),
 }
 
 /// A pattern.
@@ -1520,7 +1567,7 @@ pub struct Ident {
     /// The span.
     pub span: Span,
     /// The identifier name.
-    pub name: Rc<str>,
+    pub name: Arc<str>,
 }
 
 impl Default for Ident {
@@ -1554,8 +1601,8 @@ pub trait Idents {
 
     /// The full dot-separated name represented by this [`Ident`] sequence.
     /// E.g. `a.b.c`
-    fn full_name(&self) -> Rc<str> {
-        let mut strs = self.rc_str_iter();
+    fn full_name(&self) -> Arc<str> {
+        let mut strs = self.arc_str_iter();
         let first = strs.next();
         let Some(first) = first else {
             // No parts, empty string
@@ -1589,8 +1636,8 @@ pub trait Idents {
         self.iter().map(|ident| ident.name.as_ref())
     }
 
-    /// Iterates over the identifier names as `Rc<str>`s.
-    fn rc_str_iter(&self) -> impl Iterator<Item = &Rc<str>> {
+    /// Iterates over the identifier names as `Arc<str>`s.
+    fn arc_str_iter(&self) -> impl Iterator<Item = &Arc<str>> {
         self.iter().map(|ident| &ident.name)
     }
 
@@ -1694,7 +1741,7 @@ pub enum Lit {
     /// A measurement result literal.
     Result(Result),
     /// A string literal.
-    String(Rc<str>),
+    String(Arc<str>),
 }
 
 impl Display for Lit {
@@ -4,5 +4,6 @@
 pub mod assigner;
 pub mod ast;
 pub mod mut_visit;
+pub mod rewrite;
 pub mod validate;
 pub mod visit;
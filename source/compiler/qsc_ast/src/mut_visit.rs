@@ -3,9 +3,9 @@
 
 use crate::ast::{
     Attr, Block, CallableBody, CallableDecl, Expr, ExprKind, FieldAccess, FieldAssign, FieldDef,
-    FunctorExpr, FunctorExprKind, Ident, ImportKind, ImportOrExportItem, Item, ItemKind, Namespace,
-    Package, Pat, PatKind, Path, PathKind, QubitInit, QubitInitKind, SpecBody, SpecDecl, Stmt,
-    StmtKind, StringComponent, StructDecl, TopLevelNode, Ty, TyDef, TyDefKind, TyKind,
+    FunctorExpr, FunctorExprKind, Ident, ImportKind, ImportOrExportItem, Item, ItemKind, MatchArm,
+    Namespace, Package, Pat, PatKind, Path, PathKind, QubitInit, QubitInitKind, SpecBody, SpecDecl,
+    Stmt, StmtKind, StringComponent, StructDecl, TopLevelNode, Ty, TyDef, TyDefKind, TyKind,
     TypeParameter,
 };
 use qsc_data_structures::span::Span;
@@ -75,6 +75,10 @@ pub trait MutVisitor: Sized {
         walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &mut MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &mut Pat) {
         walk_pat(self, pat);
     }
@@ -362,6 +366,10 @@ pub fn walk_expr(vis: &mut impl MutVisitor, expr: &mut Expr) {
             vis.visit_pat(pat);
             vis.visit_expr(expr);
         }
+        ExprKind::Match(scrutinee, arms) => {
+            vis.visit_expr(scrutinee);
+            arms.iter_mut().for_each(|a| vis.visit_match_arm(a));
+        }
         ExprKind::Paren(expr) | ExprKind::Return(expr) | ExprKind::UnOp(_, expr) => {
             vis.visit_expr(expr);
         }
@@ -411,6 +419,12 @@ pub fn walk_field_assign(vis: &mut impl MutVisitor, assign: &mut FieldAssign) {
     vis.visit_expr(&mut assign.value);
 }
 
+pub fn walk_match_arm(vis: &mut impl MutVisitor, arm: &mut MatchArm) {
+    vis.visit_span(&mut arm.span);
+    vis.visit_expr(&mut arm.pat);
+    vis.visit_expr(&mut arm.body);
+}
+
 pub fn walk_pat(vis: &mut impl MutVisitor, pat: &mut Pat) {
     vis.visit_span(&mut pat.span);
 
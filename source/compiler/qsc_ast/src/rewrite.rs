@@ -0,0 +1,175 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Reusable [`MutVisitor`] implementations for passes that rewrite the AST, such as desugaring,
+//! inlining, and loop unrolling.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    assigner::Assigner,
+    ast::{
+        Attr, Block, CallableDecl, Expr, FieldAssign, FieldDef, FunctorExpr, Ident, Item, MatchArm,
+        Namespace, NodeId, Pat, Path, QubitInit, SpecDecl, Stmt, StructDecl, Ty, TyDef,
+    },
+    mut_visit::{self, MutVisitor},
+};
+use qsc_data_structures::span::Span;
+use std::mem;
+
+/// Assigns a fresh node id to every node in a subtree, so that a copy of the subtree can be placed
+/// elsewhere in the same package without duplicating ids. Names are resolved after the AST is
+/// rewritten, so no references need to be updated.
+pub struct NodeIdRefresher<'a> {
+    assigner: &'a mut Assigner,
+}
+
+impl<'a> NodeIdRefresher<'a> {
+    #[must_use]
+    pub fn new(assigner: &'a mut Assigner) -> Self {
+        Self { assigner }
+    }
+
+    fn refresh(&mut self, id: &mut NodeId) {
+        *id = self.assigner.next_id();
+    }
+}
+
+impl MutVisitor for NodeIdRefresher<'_> {
+    fn visit_namespace(&mut self, namespace: &mut Namespace) {
+        self.refresh(&mut namespace.id);
+        mut_visit::walk_namespace(self, namespace);
+    }
+
+    fn visit_item(&mut self, item: &mut Item) {
+        self.refresh(&mut item.id);
+        mut_visit::walk_item(self, item);
+    }
+
+    fn visit_attr(&mut self, attr: &mut Attr) {
+        self.refresh(&mut attr.id);
+        mut_visit::walk_attr(self, attr);
+    }
+
+    fn visit_ty_def(&mut self, def: &mut TyDef) {
+        self.refresh(&mut def.id);
+        mut_visit::walk_ty_def(self, def);
+    }
+
+    fn visit_callable_decl(&mut self, decl: &mut CallableDecl) {
+        self.refresh(&mut decl.id);
+        mut_visit::walk_callable_decl(self, decl);
+    }
+
+    fn visit_struct_decl(&mut self, decl: &mut StructDecl) {
+        self.refresh(&mut decl.id);
+        mut_visit::walk_struct_decl(self, decl);
+    }
+
+    fn visit_field_def(&mut self, def: &mut FieldDef) {
+        self.refresh(&mut def.id);
+        mut_visit::walk_field_def(self, def);
+    }
+
+    fn visit_spec_decl(&mut self, decl: &mut SpecDecl) {
+        self.refresh(&mut decl.id);
+        mut_visit::walk_spec_decl(self, decl);
+    }
+
+    fn visit_functor_expr(&mut self, expr: &mut FunctorExpr) {
+        self.refresh(&mut expr.id);
+        mut_visit::walk_functor_expr(self, expr);
+    }
+
+    fn visit_ty(&mut self, ty: &mut Ty) {
+        self.refresh(&mut ty.id);
+        mut_visit::walk_ty(self, ty);
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        self.refresh(&mut block.id);
+        mut_visit::walk_block(self, block);
+    }
+
+    fn visit_stmt(&mut self, stmt: &mut Stmt) {
+        self.refresh(&mut stmt.id);
+        mut_visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        self.refresh(&mut expr.id);
+        mut_visit::walk_expr(self, expr);
+    }
+
+    fn visit_field_assign(&mut self, assign: &mut FieldAssign) {
+        self.refresh(&mut assign.id);
+        mut_visit::walk_field_assign(self, assign);
+    }
+
+    fn visit_match_arm(&mut self, arm: &mut MatchArm) {
+        self.refresh(&mut arm.id);
+        mut_visit::walk_match_arm(self, arm);
+    }
+
+    fn visit_pat(&mut self, pat: &mut Pat) {
+        self.refresh(&mut pat.id);
+        mut_visit::walk_pat(self, pat);
+    }
+
+    fn visit_qubit_init(&mut self, init: &mut QubitInit) {
+        self.refresh(&mut init.id);
+        mut_visit::walk_qubit_init(self, init);
+    }
+
+    fn visit_path(&mut self, path: &mut Path) {
+        self.refresh(&mut path.id);
+        mut_visit::walk_path(self, path);
+    }
+
+    fn visit_ident(&mut self, ident: &mut Ident) {
+        self.refresh(&mut ident.id);
+        mut_visit::walk_ident(self, ident);
+    }
+}
+
+/// Gives a span to every node in a subtree that does not have one yet, leaving existing spans
+/// untouched.
+pub struct SpanFiller {
+    span: Span,
+}
+
+impl SpanFiller {
+    #[must_use]
+    pub fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+impl MutVisitor for SpanFiller {
+    fn visit_span(&mut self, span: &mut Span) {
+        if *span == Span::default() {
+            *span = self.span;
+        }
+    }
+}
+
+/// Rewrites the expressions in a subtree from the leaves up. Each expression is passed to the fold
+/// function after its subexpressions have been folded, and is replaced by the returned expression.
+pub struct ExprFolder<F> {
+    fold: F,
+}
+
+impl<F: FnMut(Expr) -> Expr> ExprFolder<F> {
+    #[must_use]
+    pub fn new(fold: F) -> Self {
+        Self { fold }
+    }
+}
+
+impl<F: FnMut(Expr) -> Expr> MutVisitor for ExprFolder<F> {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        mut_visit::walk_expr(self, expr);
+        *expr = (self.fold)(mem::take(expr));
+    }
+}
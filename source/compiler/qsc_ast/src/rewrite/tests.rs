@@ -0,0 +1,159 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ExprFolder, NodeIdRefresher, SpanFiller};
+use crate::{
+    assigner::Assigner,
+    ast::{BinOp, Expr, ExprKind, Lit, NodeId},
+    mut_visit::MutVisitor,
+    visit::{self, Visitor},
+};
+use qsc_data_structures::span::Span;
+
+fn int(id: usize, span: Span, value: i64) -> Box<Expr> {
+    Box::new(Expr {
+        id: id.into(),
+        span,
+        kind: Box::new(ExprKind::Lit(Box::new(Lit::Int(value)))),
+    })
+}
+
+fn add(id: usize, span: Span, lhs: Box<Expr>, rhs: Box<Expr>) -> Expr {
+    Expr {
+        id: id.into(),
+        span,
+        kind: Box::new(ExprKind::BinOp(BinOp::Add, lhs, rhs)),
+    }
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span { lo, hi }
+}
+
+struct ExprIds(Vec<NodeId>);
+
+impl<'a> Visitor<'a> for ExprIds {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        self.0.push(expr.id);
+        visit::walk_expr(self, expr);
+    }
+}
+
+fn expr_ids(expr: &Expr) -> Vec<NodeId> {
+    let mut ids = ExprIds(Vec::new());
+    ids.visit_expr(expr);
+    ids.0
+}
+
+fn fold_int_add(expr: Expr) -> Expr {
+    let sum = match &*expr.kind {
+        ExprKind::BinOp(BinOp::Add, lhs, rhs) => match (&*lhs.kind, &*rhs.kind) {
+            (ExprKind::Lit(lhs), ExprKind::Lit(rhs)) => match (&**lhs, &**rhs) {
+                (Lit::Int(lhs), Lit::Int(rhs)) => Some(lhs + rhs),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match sum {
+        Some(sum) => Expr {
+            kind: Box::new(ExprKind::Lit(Box::new(Lit::Int(sum)))),
+            ..expr
+        },
+        None => expr,
+    }
+}
+
+#[test]
+fn refresher_assigns_fresh_id_to_every_node() {
+    let mut expr = add(2, span(0, 5), int(0, span(0, 1), 1), int(1, span(4, 5), 2));
+    let mut assigner = Assigner::new();
+    for _ in 0..3 {
+        assigner.next_id();
+    }
+
+    NodeIdRefresher::new(&mut assigner).visit_expr(&mut expr);
+
+    assert_eq!(
+        expr_ids(&expr),
+        [NodeId::from(3), NodeId::from(4), NodeId::from(5)]
+    );
+    assert_eq!(assigner.next_id(), NodeId::from(6));
+}
+
+#[test]
+fn refresher_keeps_spans() {
+    let mut expr = add(2, span(0, 5), int(0, span(0, 1), 1), int(1, span(4, 5), 2));
+    let expected = expr.clone();
+
+    NodeIdRefresher::new(&mut Assigner::new()).visit_expr(&mut expr);
+
+    assert_eq!(expr.span, expected.span);
+    let (ExprKind::BinOp(_, lhs, rhs), ExprKind::BinOp(_, expected_lhs, expected_rhs)) =
+        (&*expr.kind, &*expected.kind)
+    else {
+        panic!("expression should still be a binary operator");
+    };
+    assert_eq!(lhs.span, expected_lhs.span);
+    assert_eq!(rhs.span, expected_rhs.span);
+}
+
+#[test]
+fn span_filler_only_fills_missing_spans() {
+    let mut expr = add(
+        2,
+        Span::default(),
+        int(0, span(0, 1), 1),
+        int(1, Span::default(), 2),
+    );
+
+    SpanFiller::new(span(10, 20)).visit_expr(&mut expr);
+
+    assert_eq!(expr.span, span(10, 20));
+    let ExprKind::BinOp(_, lhs, rhs) = &*expr.kind else {
+        panic!("expression should still be a binary operator");
+    };
+    assert_eq!(lhs.span, span(0, 1));
+    assert_eq!(rhs.span, span(10, 20));
+}
+
+#[test]
+fn folder_rewrites_from_leaves_up() {
+    // (1 + 2) + 3
+    let mut expr = add(
+        4,
+        span(0, 13),
+        Box::new(add(
+            2,
+            span(1, 6),
+            int(0, span(1, 2), 1),
+            int(1, span(5, 6), 2),
+        )),
+        int(3, span(12, 13), 3),
+    );
+
+    ExprFolder::new(fold_int_add).visit_expr(&mut expr);
+
+    assert_eq!(expr, *int(4, span(0, 13), 6));
+}
+
+#[test]
+fn folder_leaves_unmatched_exprs_unchanged() {
+    let mut expr = add(
+        2,
+        span(0, 5),
+        int(0, span(0, 1), 1),
+        Box::new(Expr {
+            id: 1.into(),
+            span: span(4, 5),
+            kind: Box::new(ExprKind::Lit(Box::new(Lit::Bool(true)))),
+        }),
+    );
+    let expected = expr.clone();
+
+    ExprFolder::new(fold_int_add).visit_expr(&mut expr);
+
+    assert_eq!(expr, expected);
+}
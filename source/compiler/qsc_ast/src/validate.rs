@@ -1,10 +1,13 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+#[cfg(test)]
+mod tests;
+
 use crate::{
     ast::{
-        Attr, Block, CallableDecl, Expr, FieldAssign, FunctorExpr, Ident, Item, Namespace, NodeId,
-        Package, Pat, Path, QubitInit, SpecDecl, Stmt, Ty, TyDef,
+        Attr, Block, CallableDecl, Expr, FieldAssign, FunctorExpr, Ident, Item, MatchArm,
+        Namespace, NodeId, Package, Pat, Path, QubitInit, SpecDecl, Stmt, Ty, TyDef,
     },
     visit::{self, Visitor},
 };
@@ -104,6 +107,11 @@ impl Visitor<'_> for Validator {
         visit::walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &MatchArm) {
+        self.check(arm.id, arm);
+        visit::walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &Pat) {
         self.check(pat.id, pat);
         visit::walk_pat(self, pat);
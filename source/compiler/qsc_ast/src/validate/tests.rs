@@ -0,0 +1,57 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::Validator;
+use crate::{
+    assigner::Assigner,
+    ast::{BinOp, Expr, ExprKind, Lit, NodeId, Package},
+    mut_visit::MutVisitor,
+    visit::Visitor,
+};
+use qsc_data_structures::span::Span;
+
+fn id(n: usize) -> NodeId {
+    n.into()
+}
+
+fn int(id: NodeId, value: i64) -> Box<Expr> {
+    Box::new(Expr {
+        id,
+        span: Span::default(),
+        kind: Box::new(ExprKind::Lit(Box::new(Lit::Int(value)))),
+    })
+}
+
+fn package(ids: [NodeId; 4]) -> Package {
+    let [package, add, lhs, rhs] = ids;
+    Package {
+        id: package,
+        nodes: Box::default(),
+        entry: Some(Box::new(Expr {
+            id: add,
+            span: Span::default(),
+            kind: Box::new(ExprKind::BinOp(BinOp::Add, int(lhs, 1), int(rhs, 2))),
+        })),
+    }
+}
+
+#[test]
+fn assigned_package_passes_validation() {
+    let mut package = package([NodeId::default(); 4]);
+    Assigner::new().visit_package(&mut package);
+    Validator::default().visit_package(&package);
+}
+
+#[test]
+#[should_panic(expected = "default node ID should be replaced")]
+fn default_id_is_rejected() {
+    let package = package([id(0), id(1), NodeId::default(), id(3)]);
+    Validator::default().visit_package(&package);
+}
+
+#[test]
+#[should_panic(expected = "duplicate node ID")]
+fn duplicate_id_is_rejected() {
+    let package = package([id(0), id(1), id(2), id(2)]);
+    Validator::default().visit_package(&package);
+}
@@ -32,6 +32,7 @@ use std::{
     hash::Hash,
     mem::replace,
     rc::Rc,
+    sync::Arc,
 };
 
 /// Circuit builder that implements the `Tracer` trait to build a circuit
@@ -75,6 +76,7 @@ impl Tracer for CircuitTracer {
             is_adjoint,
             &GateInputs { targets, controls },
             display_args,
+            theta.into_iter().collect(),
             called_at,
         );
     }
@@ -129,6 +131,7 @@ impl Tracer for CircuitTracer {
             } else {
                 vec![classical_args]
             },
+            vec![],
             map_stack_frames_to_locations(stack),
         );
     }
@@ -155,6 +158,11 @@ impl CircuitTracer {
         }
     }
 
+    /// Discards everything traced so far, so that tracing starts over with an empty circuit.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config, &self.user_package_ids);
+    }
+
     #[must_use]
     pub fn with_qubit_input_params(
         config: TracerConfig,
@@ -659,10 +667,12 @@ impl OperationOrGroup {
         targets: &[QubitWire],
         controls: &[QubitWire],
         args: Vec<String>,
+        params: Vec<f64>,
     ) -> Self {
         Self::new_single(Operation::Unitary(Unitary {
             gate: name.to_string(),
             args,
+            params,
             children: vec![],
             targets: targets
                 .iter()
@@ -788,6 +798,7 @@ impl OperationOrGroup {
             op: Operation::Unitary(Unitary {
                 gate: String::new(), // to be filled in later
                 args: vec![],
+                params: vec![],
                 children: vec![],
                 targets: all_qubits
                     .iter()
@@ -990,6 +1001,7 @@ impl OperationListBuilder {
         is_adjoint: bool,
         inputs: &GateInputs,
         args: Vec<String>,
+        params: Vec<f64>,
         call_stack: Vec<LocationMetadata>,
     ) {
         let targets = inputs
@@ -1003,7 +1015,7 @@ impl OperationListBuilder {
             .map(|q| wire_map.qubit_wire(*q))
             .collect::<Vec<_>>();
         self.push_op(
-            OperationOrGroup::new_unitary(name, is_adjoint, &targets, &controls, args),
+            OperationOrGroup::new_unitary(name, is_adjoint, &targets, &controls, args, params),
             call_stack,
         );
     }
@@ -1085,7 +1097,7 @@ impl Hash for ScopeId {
 pub enum LexicalScope {
     Top,
     Callable {
-        name: Rc<str>,
+        name: Arc<str>,
         functor_app: FunctorApp,
         location: PackageOffset,
     },
@@ -93,13 +93,13 @@ impl FakeCompilation {
 
 #[derive(Default)]
 struct Scopes {
-    id_to_name: FxHashMap<StoreItemId, Rc<str>>,
-    name_to_id: FxHashMap<Rc<str>, StoreItemId>,
+    id_to_name: FxHashMap<StoreItemId, Arc<str>>,
+    name_to_id: FxHashMap<Arc<str>, StoreItemId>,
 }
 
 impl Scopes {
     fn get_or_create_scope(&mut self, package_id: usize, name: &str, is_adjoint: bool) -> ScopeId {
-        let name: Rc<str> = name.into();
+        let name: Arc<str> = name.into();
         let item_id = if let Some(item_id) = self.name_to_id.get(&name) {
             *item_id
         } else {
@@ -486,3 +486,48 @@ fn measurement_target_propagated_to_group() {
         .find(|reg| *reg == &measurement_op.results[0])
         .expect("expected measurement result in group operation's targets");
 }
+
+#[test]
+fn rotation_angles_are_exported_at_full_precision() {
+    let mut builder = CircuitTracer::new(
+        TracerConfig {
+            max_operations: 10,
+            source_locations: false,
+            group_by_scope: false,
+        },
+        &FakeCompilation::user_package_ids(),
+    );
+
+    builder.qubit_allocate(&[], 0);
+    builder.gate(
+        &[],
+        "Rz",
+        false,
+        &[0],
+        &[],
+        Some(std::f64::consts::PI / 8.0),
+    );
+
+    let circuit = builder.finish(&FakeCompilation::default());
+
+    // The rendered circuit shows the rounded angle...
+    expect![[r#"
+        q_0    ─ Rz(0.3927) ──
+    "#]]
+    .assert_eq(&circuit.to_string());
+
+    // ...while the exported program keeps the exact one.
+    let quil = crate::circuit_to_quil::circuit_to_quil(&circuit).expect("circuit should export");
+    expect![[r#"
+        RZ(0.39269908169872414) 0
+    "#]]
+    .assert_eq(&quil);
+    let angle = quil
+        .trim()
+        .strip_prefix("RZ(")
+        .and_then(|rest| rest.strip_suffix(") 0"))
+        .expect("export should be a single RZ gate")
+        .parse::<f64>()
+        .expect("angle should parse");
+    assert_eq!(angle.to_bits(), (std::f64::consts::PI / 8.0).to_bits());
+}
@@ -265,6 +265,11 @@ pub struct Unitary {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub args: Vec<String>,
+    /// The exact values of the numeric arguments, such as rotation angles, which `args` only
+    /// holds rounded for display.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub params: Vec<f64>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub children: ComponentGrid,
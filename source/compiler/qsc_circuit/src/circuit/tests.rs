@@ -61,6 +61,7 @@ fn unitary(gate: &str, targets: Vec<Register>) -> Operation {
     Operation::Unitary(Unitary {
         gate: gate.to_string(),
         args: vec![],
+        params: vec![],
         is_adjoint: false,
         controls: vec![],
         targets,
@@ -73,6 +74,7 @@ fn ctl_unitary(gate: &str, targets: Vec<Register>, controls: Vec<Register>) -> O
     Operation::Unitary(Unitary {
         gate: gate.to_string(),
         args: vec![],
+        params: vec![],
         is_adjoint: false,
         controls,
         targets,
@@ -210,6 +212,7 @@ fn with_args() {
         component_grid: op_grid_to_comp_grid(vec![vec![Operation::Unitary(Unitary {
             gate: "rx".to_string(),
             args: vec!["1.5708".to_string()],
+            params: vec![],
             is_adjoint: false,
             controls: vec![],
             targets: vec![Register::quantum(0)],
@@ -231,6 +234,7 @@ fn two_targets() {
         component_grid: op_grid_to_comp_grid(vec![vec![Operation::Unitary(Unitary {
             gate: "rzz".to_string(),
             args: vec!["1.0000".to_string()],
+            params: vec![],
             is_adjoint: false,
             controls: vec![],
             targets: vec![Register::quantum(0), Register::quantum(2)],
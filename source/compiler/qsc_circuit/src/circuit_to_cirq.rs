@@ -0,0 +1,243 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use serde::Serialize;
+use std::f64::consts::PI;
+
+use crate::{
+    Circuit,
+    export::{ExportError, Gate, Instruction, instructions},
+};
+
+#[derive(Serialize)]
+#[serde(tag = "cirq_type", rename = "Circuit")]
+struct CirqCircuit {
+    moments: Vec<Moment>,
+}
+
+#[derive(Default, Serialize)]
+#[serde(tag = "cirq_type")]
+struct Moment {
+    operations: Vec<GateOperation>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "cirq_type")]
+struct GateOperation {
+    gate: CirqGate,
+    qubits: Vec<LineQubit>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "cirq_type")]
+struct LineQubit {
+    x: usize,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "cirq_type")]
+enum CirqGate {
+    HPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    XPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    YPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    ZPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    CXPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    CCXPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    CZPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    SwapPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    XXPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    YYPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    ZZPowGate {
+        exponent: f64,
+        global_shift: f64,
+    },
+    Rx {
+        rads: f64,
+    },
+    Ry {
+        rads: f64,
+    },
+    Rz {
+        rads: f64,
+    },
+    ControlledGate {
+        sub_gate: Box<CirqGate>,
+        num_controls: usize,
+        control_values: Vec<Vec<u8>>,
+        control_qid_shape: Vec<u8>,
+    },
+    MeasurementGate {
+        num_qubits: usize,
+        key: String,
+        invert_mask: Vec<bool>,
+        qid_shape: Vec<u8>,
+    },
+    ResetChannel {
+        dimension: u8,
+    },
+}
+
+/// Exports the circuit in Cirq's JSON serialization format, readable with `cirq.read_json`.
+///
+/// Qubits become `LineQubit`s with the circuit qubit ids, and each result is measured under the
+/// key `c{qubit}_{result}`. Operations are packed into the earliest moment their qubits allow.
+pub fn circuit_to_cirq_json(circuit: &Circuit) -> Result<String, ExportError> {
+    let mut moments: Vec<Moment> = Vec::new();
+    // The index of the first moment in which each qubit is free.
+    let mut free_at = Vec::<usize>::new();
+    for instr in instructions(circuit)? {
+        let qubits = instr.qubits();
+        let index = qubits
+            .iter()
+            .map(|&q| free_at.get(q).copied().unwrap_or_default())
+            .max()
+            .unwrap_or_default();
+        for &q in &qubits {
+            if free_at.len() <= q {
+                free_at.resize(q + 1, 0);
+            }
+            free_at[q] = index + 1;
+        }
+        if moments.len() <= index {
+            moments.resize_with(index + 1, Moment::default);
+        }
+        moments[index].operations.push(GateOperation {
+            gate: cirq_gate(&instr),
+            qubits: qubits.into_iter().map(|x| LineQubit { x }).collect(),
+        });
+    }
+
+    Ok(serde_json::to_string_pretty(&CirqCircuit { moments })
+        .expect("circuit should serialize to JSON"))
+}
+
+fn cirq_gate(instr: &Instruction) -> CirqGate {
+    match instr {
+        Instruction::Gate {
+            gate,
+            adjoint,
+            controls,
+            ..
+        } => {
+            let sign = if *adjoint { -1.0 } else { 1.0 };
+            match (gate, controls.len()) {
+                (Gate::X, 1) => CirqGate::CXPowGate {
+                    exponent: 1.0,
+                    global_shift: 0.0,
+                },
+                (Gate::X, 2) => CirqGate::CCXPowGate {
+                    exponent: 1.0,
+                    global_shift: 0.0,
+                },
+                (Gate::Z, 1) => CirqGate::CZPowGate {
+                    exponent: 1.0,
+                    global_shift: 0.0,
+                },
+                (_, 0) => uncontrolled_gate(*gate, sign),
+                (_, n) => CirqGate::ControlledGate {
+                    sub_gate: Box::new(uncontrolled_gate(*gate, sign)),
+                    num_controls: n,
+                    control_values: vec![vec![1]; n],
+                    control_qid_shape: vec![2; n],
+                },
+            }
+        }
+        Instruction::Measure {
+            result: (qubit, result),
+            ..
+        } => CirqGate::MeasurementGate {
+            num_qubits: 1,
+            key: format!("c{qubit}_{result}"),
+            invert_mask: Vec::new(),
+            qid_shape: vec![2],
+        },
+        Instruction::Reset(_) => CirqGate::ResetChannel { dimension: 2 },
+    }
+}
+
+fn uncontrolled_gate(gate: Gate, sign: f64) -> CirqGate {
+    // Cirq's two-qubit rotations are exponentiated Pauli products; a global shift of -1/2 makes
+    // them match the R** rotations exactly, including phase.
+    match gate {
+        Gate::H => CirqGate::HPowGate {
+            exponent: 1.0,
+            global_shift: 0.0,
+        },
+        Gate::X => CirqGate::XPowGate {
+            exponent: 1.0,
+            global_shift: 0.0,
+        },
+        Gate::Y => CirqGate::YPowGate {
+            exponent: 1.0,
+            global_shift: 0.0,
+        },
+        Gate::Z => CirqGate::ZPowGate {
+            exponent: 1.0,
+            global_shift: 0.0,
+        },
+        Gate::S => CirqGate::ZPowGate {
+            exponent: sign * 0.5,
+            global_shift: 0.0,
+        },
+        Gate::T => CirqGate::ZPowGate {
+            exponent: sign * 0.25,
+            global_shift: 0.0,
+        },
+        Gate::SX => CirqGate::XPowGate {
+            exponent: sign * 0.5,
+            global_shift: 0.0,
+        },
+        Gate::Swap => CirqGate::SwapPowGate {
+            exponent: 1.0,
+            global_shift: 0.0,
+        },
+        Gate::Rx(rads) => CirqGate::Rx { rads },
+        Gate::Ry(rads) => CirqGate::Ry { rads },
+        Gate::Rz(rads) => CirqGate::Rz { rads },
+        Gate::Rxx(theta) => CirqGate::XXPowGate {
+            exponent: theta / PI,
+            global_shift: -0.5,
+        },
+        Gate::Ryy(theta) => CirqGate::YYPowGate {
+            exponent: theta / PI,
+            global_shift: -0.5,
+        },
+        Gate::Rzz(theta) => CirqGate::ZZPowGate {
+            exponent: theta / PI,
+            global_shift: -0.5,
+        },
+    }
+}
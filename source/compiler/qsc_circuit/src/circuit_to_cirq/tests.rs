@@ -0,0 +1,214 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::*;
+use expect_test::{Expect, expect};
+
+fn check(contents: &str, expect: &Expect) {
+    let circuit = serde_json::from_str::<Circuit>(contents).expect("circuit should deserialize");
+    let actual = match circuit_to_cirq_json(&circuit) {
+        Ok(json) => json,
+        Err(e) => format!("Error: {e}"),
+    };
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn operations_are_packed_into_moments() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    { "components": [{ "kind": "unitary", "gate": "H", "targets": [{ "qubit": 0 }] }] },
+    {
+      "components": [
+        { "kind": "unitary", "gate": "X", "targets": [{ "qubit": 1 }], "controls": [{ "qubit": 0 }] }
+      ]
+    },
+    {
+      "components": [
+        {
+          "kind": "measurement",
+          "gate": "M",
+          "qubits": [{ "qubit": 1 }],
+          "results": [{ "qubit": 1, "result": 0 }]
+        },
+        { "kind": "unitary", "gate": "T", "targets": [{ "qubit": 2 }], "isAdjoint": true }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1, "numResults": 1 }, { "id": 2 }]
+}"#,
+        &expect![[r#"
+            {
+              "cirq_type": "Circuit",
+              "moments": [
+                {
+                  "cirq_type": "Moment",
+                  "operations": [
+                    {
+                      "cirq_type": "GateOperation",
+                      "gate": {
+                        "cirq_type": "HPowGate",
+                        "exponent": 1.0,
+                        "global_shift": 0.0
+                      },
+                      "qubits": [
+                        {
+                          "cirq_type": "LineQubit",
+                          "x": 0
+                        }
+                      ]
+                    },
+                    {
+                      "cirq_type": "GateOperation",
+                      "gate": {
+                        "cirq_type": "ZPowGate",
+                        "exponent": -0.25,
+                        "global_shift": 0.0
+                      },
+                      "qubits": [
+                        {
+                          "cirq_type": "LineQubit",
+                          "x": 2
+                        }
+                      ]
+                    }
+                  ]
+                },
+                {
+                  "cirq_type": "Moment",
+                  "operations": [
+                    {
+                      "cirq_type": "GateOperation",
+                      "gate": {
+                        "cirq_type": "CXPowGate",
+                        "exponent": 1.0,
+                        "global_shift": 0.0
+                      },
+                      "qubits": [
+                        {
+                          "cirq_type": "LineQubit",
+                          "x": 0
+                        },
+                        {
+                          "cirq_type": "LineQubit",
+                          "x": 1
+                        }
+                      ]
+                    }
+                  ]
+                },
+                {
+                  "cirq_type": "Moment",
+                  "operations": [
+                    {
+                      "cirq_type": "GateOperation",
+                      "gate": {
+                        "cirq_type": "MeasurementGate",
+                        "num_qubits": 1,
+                        "key": "c1_0",
+                        "invert_mask": [],
+                        "qid_shape": [
+                          2
+                        ]
+                      },
+                      "qubits": [
+                        {
+                          "cirq_type": "LineQubit",
+                          "x": 1
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }"#]],
+    );
+}
+
+#[test]
+fn controlled_rotation_uses_controlled_gate() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "Ry",
+          "args": ["0.5"],
+          "targets": [{ "qubit": 1 }],
+          "controls": [{ "qubit": 0 }]
+        }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1 }]
+}"#,
+        &expect![[r#"
+            {
+              "cirq_type": "Circuit",
+              "moments": [
+                {
+                  "cirq_type": "Moment",
+                  "operations": [
+                    {
+                      "cirq_type": "GateOperation",
+                      "gate": {
+                        "cirq_type": "ControlledGate",
+                        "sub_gate": {
+                          "cirq_type": "Ry",
+                          "rads": 0.5
+                        },
+                        "num_controls": 1,
+                        "control_values": [
+                          [
+                            1
+                          ]
+                        ],
+                        "control_qid_shape": [
+                          2
+                        ]
+                      },
+                      "qubits": [
+                        {
+                          "cirq_type": "LineQubit",
+                          "x": 0
+                        },
+                        {
+                          "cirq_type": "LineQubit",
+                          "x": 1
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }"#]],
+    );
+}
+
+#[test]
+fn joint_measurement_is_unsupported() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        {
+          "kind": "measurement",
+          "gate": "M",
+          "qubits": [{ "qubit": 0 }, { "qubit": 1 }],
+          "results": [{ "qubit": 0, "result": 0 }]
+        }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0, "numResults": 1 }, { "id": 1 }]
+}"#,
+        &expect!["Error: joint measurement of 2 qubits cannot be exported"],
+    );
+}
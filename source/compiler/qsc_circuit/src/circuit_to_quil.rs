@@ -0,0 +1,263 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use rustc_hash::FxHashMap;
+use std::fmt::Write;
+
+use crate::{
+    Circuit,
+    export::{ExportError, Gate, Instruction, Segment, Subcircuit, segments},
+};
+
+const SX_DEF: &str = "DEFGATE SX:
+    0.5+0.5i, 0.5-0.5i
+    0.5-0.5i, 0.5+0.5i
+";
+
+const RXX_DEF: &str = "DEFGATE RXX(%theta):
+    cos(%theta/2), 0, 0, -i*sin(%theta/2)
+    0, cos(%theta/2), -i*sin(%theta/2), 0
+    0, -i*sin(%theta/2), cos(%theta/2), 0
+    -i*sin(%theta/2), 0, 0, cos(%theta/2)
+";
+
+const RYY_DEF: &str = "DEFGATE RYY(%theta):
+    cos(%theta/2), 0, 0, i*sin(%theta/2)
+    0, cos(%theta/2), -i*sin(%theta/2), 0
+    0, -i*sin(%theta/2), cos(%theta/2), 0
+    i*sin(%theta/2), 0, 0, cos(%theta/2)
+";
+
+const RZZ_DEF: &str = "DEFGATE RZZ(%theta):
+    cis(-%theta/2), 0, 0, 0
+    0, cis(%theta/2), 0, 0
+    0, 0, cis(%theta/2), 0
+    0, 0, 0, cis(-%theta/2)
+";
+
+const RESERVED_NAMES: &[&str] = &[
+    "H",
+    "X",
+    "Y",
+    "Z",
+    "S",
+    "T",
+    "SX",
+    "SWAP",
+    "RX",
+    "RY",
+    "RZ",
+    "RXX",
+    "RYY",
+    "RZZ",
+    "CNOT",
+    "CCNOT",
+    "CZ",
+    "MEASURE",
+    "RESET",
+    "DECLARE",
+    "CONTROLLED",
+    "DAGGER",
+    "I",
+    "PHASE",
+    "CPHASE",
+    "HALT",
+    "WAIT",
+    "LABEL",
+    "JUMP",
+    "PRAGMA",
+    "DEFGATE",
+    "DEFCIRCUIT",
+];
+
+/// Exports the circuit as a Quil program.
+///
+/// Qubits keep their circuit ids and measurement results are written to the `ro` register in
+/// the order they are measured. Gates missing from the Quil standard gate set are declared with
+/// `DEFGATE` before use. Grouped operations that are applied more than once with the same gates
+/// are declared once with `DEFCIRCUIT` and called at each use, rather than repeating their gates.
+/// Two groups are the same when they have the same name and their gates lower to the same Quil.
+pub fn circuit_to_quil(circuit: &Circuit) -> Result<String, ExportError> {
+    let (subcircuits, segments) = segments(circuit, |instrs| {
+        let mut text = String::new();
+        let mut ro = FxHashMap::default();
+        for instr in instrs {
+            write_instruction(&mut text, instr, circuit_param, &mut Vec::new(), &mut ro);
+        }
+        text
+    })?;
+    let names = circuit_names(&subcircuits);
+
+    let mut defs = Vec::new();
+    let mut ro = FxHashMap::default();
+    let mut circuits = String::new();
+    for (subcircuit, name) in subcircuits.iter().zip(&names) {
+        let params = (0..subcircuit.num_qubits)
+            .map(circuit_param)
+            .collect::<Vec<_>>();
+        writeln!(circuits, "DEFCIRCUIT {name} {}:", params.join(" "))
+            .expect("could not write to circuits");
+        for segment in &subcircuit.body {
+            circuits.push_str("    ");
+            write_segment(
+                &mut circuits,
+                segment,
+                circuit_param,
+                &names,
+                &mut defs,
+                &mut ro,
+            );
+        }
+        circuits.push('\n');
+    }
+
+    let mut body = String::new();
+    for segment in &segments {
+        write_segment(
+            &mut body,
+            segment,
+            |q| q.to_string(),
+            &names,
+            &mut defs,
+            &mut ro,
+        );
+    }
+
+    let mut quil = String::new();
+    for def in defs {
+        quil.push_str(def);
+        quil.push('\n');
+    }
+    quil.push_str(&circuits);
+    if !ro.is_empty() {
+        writeln!(quil, "DECLARE ro BIT[{}]\n", ro.len()).expect("could not write to quil");
+    }
+    quil.push_str(&body);
+    Ok(quil)
+}
+
+/// Names the qubit parameter of a `DEFCIRCUIT` with the given index.
+fn circuit_param(q: usize) -> String {
+    format!("q{q}")
+}
+
+/// Writes the segment as a line of Quil, calling subcircuits by the given names.
+fn write_segment(
+    out: &mut String,
+    segment: &Segment,
+    qubit: impl Fn(usize) -> String,
+    names: &[String],
+    defs: &mut Vec<&'static str>,
+    ro: &mut FxHashMap<(usize, usize), usize>,
+) {
+    match segment {
+        Segment::Instruction(instr) => write_instruction(out, instr, qubit, defs, ro),
+        Segment::Call(index, qubits) => {
+            let qubits = qubits
+                .iter()
+                .map(|&q| qubit(q))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(out, "{} {qubits}", names[*index]).expect("could not write call");
+        }
+    }
+}
+
+/// Writes the instruction as a line of Quil, naming each qubit with `qubit` and recording any
+/// gate definitions and measurement results it needs.
+fn write_instruction(
+    out: &mut String,
+    instr: &Instruction,
+    qubit: impl Fn(usize) -> String,
+    defs: &mut Vec<&'static str>,
+    ro: &mut FxHashMap<(usize, usize), usize>,
+) {
+    match instr {
+        Instruction::Gate {
+            gate,
+            adjoint,
+            controls,
+            targets,
+        } => {
+            let def = match gate {
+                Gate::SX => Some(SX_DEF),
+                Gate::Rxx(_) => Some(RXX_DEF),
+                Gate::Ryy(_) => Some(RYY_DEF),
+                Gate::Rzz(_) => Some(RZZ_DEF),
+                _ => None,
+            };
+            if let Some(def) = def
+                && !defs.contains(&def)
+            {
+                defs.push(def);
+            }
+            let qubits = controls
+                .iter()
+                .chain(targets)
+                .map(|&q| qubit(q))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let gate = gate_call(*gate, *adjoint, controls.len());
+            writeln!(out, "{gate} {qubits}").expect("could not write instruction");
+        }
+        Instruction::Measure { qubit: q, result } => {
+            let len = ro.len();
+            let index = *ro.entry(*result).or_insert(len);
+            writeln!(out, "MEASURE {} ro[{index}]", qubit(*q))
+                .expect("could not write instruction");
+        }
+        Instruction::Reset(q) => {
+            writeln!(out, "RESET {}", qubit(*q)).expect("could not write instruction");
+        }
+    }
+}
+
+/// Returns a distinct Quil identifier for each subcircuit, based on the name of its operation and
+/// avoiding the names of the Quil standard gates and keywords.
+fn circuit_names(subcircuits: &[Subcircuit]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for subcircuit in subcircuits {
+        let base = subcircuit
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while names.contains(&name) || RESERVED_NAMES.contains(&name.to_ascii_uppercase().as_str())
+        {
+            suffix += 1;
+            name = format!("{base}_{suffix}");
+        }
+        names.push(name);
+    }
+    names
+}
+
+fn gate_call(gate: Gate, adjoint: bool, num_controls: usize) -> String {
+    // Prefer the named standard gates for the common controlled forms.
+    let (name, num_controls) = match (gate, num_controls) {
+        (Gate::X, 1) => ("CNOT".to_string(), 0),
+        (Gate::X, 2) => ("CCNOT".to_string(), 0),
+        (Gate::Z, 1) => ("CZ".to_string(), 0),
+        (Gate::H, n) => ("H".to_string(), n),
+        (Gate::X, n) => ("X".to_string(), n),
+        (Gate::Y, n) => ("Y".to_string(), n),
+        (Gate::Z, n) => ("Z".to_string(), n),
+        (Gate::S, n) => ("S".to_string(), n),
+        (Gate::T, n) => ("T".to_string(), n),
+        (Gate::SX, n) => ("SX".to_string(), n),
+        (Gate::Swap, n) => ("SWAP".to_string(), n),
+        (Gate::Rx(theta), n) => (format!("RX({theta})"), n),
+        (Gate::Ry(theta), n) => (format!("RY({theta})"), n),
+        (Gate::Rz(theta), n) => (format!("RZ({theta})"), n),
+        (Gate::Rxx(theta), n) => (format!("RXX({theta})"), n),
+        (Gate::Ryy(theta), n) => (format!("RYY({theta})"), n),
+        (Gate::Rzz(theta), n) => (format!("RZZ({theta})"), n),
+    };
+    let dagger = if adjoint { "DAGGER " } else { "" };
+    format!("{}{dagger}{name}", "CONTROLLED ".repeat(num_controls))
+}
@@ -0,0 +1,458 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::*;
+use expect_test::{Expect, expect};
+
+fn check(contents: &str, expect: &Expect) {
+    let circuit = serde_json::from_str::<Circuit>(contents).expect("circuit should deserialize");
+    let actual = match circuit_to_quil(&circuit) {
+        Ok(quil) => quil,
+        Err(e) => format!("Error: {e}"),
+    };
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn bell_pair_with_measurements() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    { "components": [{ "kind": "unitary", "gate": "H", "targets": [{ "qubit": 0 }] }] },
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "X",
+          "targets": [{ "qubit": 1 }],
+          "controls": [{ "qubit": 0 }]
+        }
+      ]
+    },
+    {
+      "components": [
+        {
+          "kind": "measurement",
+          "gate": "M",
+          "qubits": [{ "qubit": 0 }],
+          "results": [{ "qubit": 0, "result": 0 }]
+        },
+        {
+          "kind": "measurement",
+          "gate": "MResetZ",
+          "qubits": [{ "qubit": 1 }],
+          "results": [{ "qubit": 1, "result": 0 }]
+        }
+      ]
+    },
+    { "components": [{ "kind": "ket", "gate": "0", "targets": [{ "qubit": 1 }] }] }
+  ],
+  "qubits": [{ "id": 0, "numResults": 1 }, { "id": 1, "numResults": 1 }]
+}"#,
+        &expect![[r#"
+            DECLARE ro BIT[2]
+
+            H 0
+            CNOT 0 1
+            MEASURE 0 ro[0]
+            MEASURE 1 ro[1]
+            RESET 1
+        "#]],
+    );
+}
+
+#[test]
+fn adjoint_and_controlled_gates_use_modifiers() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        { "kind": "unitary", "gate": "S", "targets": [{ "qubit": 0 }], "isAdjoint": true },
+        {
+          "kind": "unitary",
+          "gate": "H",
+          "targets": [{ "qubit": 3 }],
+          "controls": [{ "qubit": 1 }, { "qubit": 2 }]
+        }
+      ]
+    },
+    {
+      "components": [
+        { "kind": "unitary", "gate": "Rx", "args": ["0.5"], "targets": [{ "qubit": 0 }], "isAdjoint": true }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1 }, { "id": 2 }, { "id": 3 }]
+}"#,
+        &expect![[r#"
+            DAGGER S 0
+            CONTROLLED CONTROLLED H 1 2 3
+            RX(-0.5) 0
+        "#]],
+    );
+}
+
+#[test]
+fn gates_outside_standard_set_are_defined_once() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    { "components": [{ "kind": "unitary", "gate": "SX", "targets": [{ "qubit": 0 }] }] },
+    {
+      "components": [
+        { "kind": "unitary", "gate": "Rzz", "args": ["1"], "targets": [{ "qubit": 0 }, { "qubit": 1 }] }
+      ]
+    },
+    {
+      "components": [
+        { "kind": "unitary", "gate": "Rzz", "args": ["2"], "targets": [{ "qubit": 1 }, { "qubit": 0 }] }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1 }]
+}"#,
+        &expect![[r#"
+            DEFGATE SX:
+                0.5+0.5i, 0.5-0.5i
+                0.5-0.5i, 0.5+0.5i
+
+            DEFGATE RZZ(%theta):
+                cis(-%theta/2), 0, 0, 0
+                0, cis(%theta/2), 0, 0
+                0, 0, cis(%theta/2), 0
+                0, 0, 0, cis(-%theta/2)
+
+            SX 0
+            RZZ(1) 0 1
+            RZZ(2) 1 0
+        "#]],
+    );
+}
+
+#[test]
+fn grouped_operations_are_flattened() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "PrepareBell",
+          "targets": [{ "qubit": 0 }, { "qubit": 1 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "H", "targets": [{ "qubit": 0 }] }] },
+            {
+              "components": [
+                { "kind": "unitary", "gate": "X", "targets": [{ "qubit": 1 }], "controls": [{ "qubit": 0 }] }
+              ]
+            }
+          ]
+        }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1 }]
+}"#,
+        &expect![[r#"
+            H 0
+            CNOT 0 1
+        "#]],
+    );
+}
+
+#[test]
+fn repeated_groups_are_defined_once_as_circuits() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "PrepareBell",
+          "targets": [{ "qubit": 0 }, { "qubit": 1 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "H", "targets": [{ "qubit": 0 }] }] },
+            {
+              "components": [
+                { "kind": "unitary", "gate": "X", "targets": [{ "qubit": 1 }], "controls": [{ "qubit": 0 }] }
+              ]
+            }
+          ]
+        },
+        {
+          "kind": "unitary",
+          "gate": "PrepareBell",
+          "targets": [{ "qubit": 3 }, { "qubit": 2 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "H", "targets": [{ "qubit": 3 }] }] },
+            {
+              "components": [
+                { "kind": "unitary", "gate": "X", "targets": [{ "qubit": 2 }], "controls": [{ "qubit": 3 }] }
+              ]
+            }
+          ]
+        }
+      ]
+    },
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "SX",
+          "targets": [{ "qubit": 0 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "SX", "targets": [{ "qubit": 0 }] }] }
+          ]
+        },
+        {
+          "kind": "unitary",
+          "gate": "SX",
+          "targets": [{ "qubit": 1 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "SX", "targets": [{ "qubit": 1 }] }] }
+          ]
+        }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1 }, { "id": 2 }, { "id": 3 }]
+}"#,
+        &expect![[r#"
+            DEFGATE SX:
+                0.5+0.5i, 0.5-0.5i
+                0.5-0.5i, 0.5+0.5i
+
+            DEFCIRCUIT PrepareBell q0 q1:
+                H q0
+                CNOT q0 q1
+
+            DEFCIRCUIT SX_2 q0:
+                SX q0
+
+            PrepareBell 0 1
+            PrepareBell 3 2
+            SX_2 0
+            SX_2 1
+        "#]],
+    );
+}
+
+#[test]
+fn groups_with_different_arguments_are_not_shared() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "Rotate",
+          "args": ["0.5"],
+          "targets": [{ "qubit": 0 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "Rz", "args": ["0.5"], "targets": [{ "qubit": 0 }] }] }
+          ]
+        },
+        {
+          "kind": "unitary",
+          "gate": "Rotate",
+          "args": ["0.5"],
+          "targets": [{ "qubit": 1 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "Rz", "args": ["0.5"], "targets": [{ "qubit": 1 }] }] }
+          ]
+        }
+      ]
+    },
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "Rotate",
+          "args": ["1"],
+          "targets": [{ "qubit": 0 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "Rz", "args": ["1"], "targets": [{ "qubit": 0 }] }] }
+          ]
+        }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1 }]
+}"#,
+        &expect![[r#"
+            DEFCIRCUIT Rotate q0:
+                RZ(0.5) q0
+
+            Rotate 0
+            Rotate 1
+            RZ(1) 0
+        "#]],
+    );
+}
+
+#[test]
+fn repeated_groups_nested_in_shared_circuits_are_shared() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "Layer",
+          "targets": [{ "qubit": 0 }, { "qubit": 1 }],
+          "children": [
+            {
+              "components": [
+                {
+                  "kind": "unitary",
+                  "gate": "Flip",
+                  "targets": [{ "qubit": 0 }],
+                  "children": [
+                    { "components": [{ "kind": "unitary", "gate": "X", "targets": [{ "qubit": 0 }] }] }
+                  ]
+                },
+                {
+                  "kind": "unitary",
+                  "gate": "Flip",
+                  "targets": [{ "qubit": 1 }],
+                  "children": [
+                    { "components": [{ "kind": "unitary", "gate": "X", "targets": [{ "qubit": 1 }] }] }
+                  ]
+                }
+              ]
+            }
+          ]
+        },
+        {
+          "kind": "unitary",
+          "gate": "Layer",
+          "targets": [{ "qubit": 2 }, { "qubit": 3 }],
+          "children": [
+            {
+              "components": [
+                {
+                  "kind": "unitary",
+                  "gate": "Flip",
+                  "targets": [{ "qubit": 2 }],
+                  "children": [
+                    { "components": [{ "kind": "unitary", "gate": "X", "targets": [{ "qubit": 2 }] }] }
+                  ]
+                },
+                {
+                  "kind": "unitary",
+                  "gate": "Flip",
+                  "targets": [{ "qubit": 3 }],
+                  "children": [
+                    { "components": [{ "kind": "unitary", "gate": "X", "targets": [{ "qubit": 3 }] }] }
+                  ]
+                }
+              ]
+            }
+          ]
+        }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1 }, { "id": 2 }, { "id": 3 }]
+}"#,
+        &expect![[r#"
+            DEFCIRCUIT Flip q0:
+                X q0
+
+            DEFCIRCUIT Layer q0 q1:
+                Flip q0
+                Flip q1
+
+            Layer 0 1
+            Layer 2 3
+        "#]],
+    );
+}
+
+#[test]
+fn circuit_names_avoid_quil_keywords() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "Wait",
+          "targets": [{ "qubit": 0 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "X", "targets": [{ "qubit": 0 }] }] }
+          ]
+        },
+        {
+          "kind": "unitary",
+          "gate": "Wait",
+          "targets": [{ "qubit": 1 }],
+          "children": [
+            { "components": [{ "kind": "unitary", "gate": "X", "targets": [{ "qubit": 1 }] }] }
+          ]
+        }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0 }, { "id": 1 }]
+}"#,
+        &expect![[r#"
+            DEFCIRCUIT Wait_2 q0:
+                X q0
+
+            Wait_2 0
+            Wait_2 1
+        "#]],
+    );
+}
+
+#[test]
+fn custom_gate_is_unsupported() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    { "components": [{ "kind": "unitary", "gate": "Foo", "targets": [{ "qubit": 0 }] }] }
+  ],
+  "qubits": [{ "id": 0 }]
+}"#,
+        &expect!["Error: gate `Foo` cannot be exported"],
+    );
+}
+
+#[test]
+fn classically_controlled_gate_is_unsupported() {
+    check(
+        r#"
+{
+  "componentGrid": [
+    {
+      "components": [
+        {
+          "kind": "unitary",
+          "gate": "X",
+          "targets": [{ "qubit": 1 }],
+          "controls": [{ "qubit": 0, "result": 0 }]
+        }
+      ]
+    }
+  ],
+  "qubits": [{ "id": 0, "numResults": 1 }, { "id": 1 }]
+}"#,
+        &expect!["Error: gate `X` is classically controlled and cannot be exported"],
+    );
+}
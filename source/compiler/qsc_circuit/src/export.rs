@@ -0,0 +1,366 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Lowering of a circuit into the flat gate list shared by the exporters for other toolkits.
+
+use crate::{
+    Circuit, Operation,
+    circuit::{ComponentGrid, Ket, Measurement, Register, Unitary},
+};
+use miette::Diagnostic;
+use rustc_hash::FxHashMap;
+use thiserror::Error;
+
+#[derive(Clone, Debug, Diagnostic, Error, PartialEq)]
+pub enum ExportError {
+    #[error("gate `{0}` cannot be exported")]
+    #[diagnostic(code("Qsc.Circuit.UnsupportedGate"))]
+    #[diagnostic(help(
+        "only the intrinsic gates and the groups of operations built from them can be exported"
+    ))]
+    UnsupportedGate(String),
+    #[error("argument `{1}` of gate `{0}` is not a number")]
+    #[diagnostic(code("Qsc.Circuit.InvalidGateArgument"))]
+    InvalidGateArgument(String, String),
+    #[error("gate `{0}` is classically controlled and cannot be exported")]
+    #[diagnostic(code("Qsc.Circuit.ClassicallyControlledGate"))]
+    ClassicallyControlledGate(String),
+    #[error("joint measurement of {0} qubits cannot be exported")]
+    #[diagnostic(code("Qsc.Circuit.JointMeasurement"))]
+    JointMeasurement(usize),
+    #[error("ket `|{0}>` cannot be exported")]
+    #[diagnostic(code("Qsc.Circuit.UnsupportedKet"))]
+    #[diagnostic(help("only the `|0>` ket, which resets its targets, can be exported"))]
+    UnsupportedKet(String),
+}
+
+/// An intrinsic gate, with any rotation angle in radians.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Gate {
+    H,
+    X,
+    Y,
+    Z,
+    S,
+    T,
+    SX,
+    Swap,
+    Rx(f64),
+    Ry(f64),
+    Rz(f64),
+    Rxx(f64),
+    Ryy(f64),
+    Rzz(f64),
+}
+
+impl Gate {
+    fn num_targets(self) -> usize {
+        match self {
+            Gate::Swap | Gate::Rxx(_) | Gate::Ryy(_) | Gate::Rzz(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Instruction {
+    /// A gate, where `adjoint` is only ever set for gates that are not their own inverse and
+    /// cannot be inverted by negating their angle.
+    Gate {
+        gate: Gate,
+        adjoint: bool,
+        controls: Vec<usize>,
+        targets: Vec<usize>,
+    },
+    /// A Z-basis measurement of `qubit` into the result identified by `(qubit, result)`.
+    Measure {
+        qubit: usize,
+        result: (usize, usize),
+    },
+    Reset(usize),
+}
+
+impl Instruction {
+    fn map_qubits(self, mut f: impl FnMut(usize) -> usize) -> Self {
+        match self {
+            Instruction::Gate {
+                gate,
+                adjoint,
+                controls,
+                targets,
+            } => Instruction::Gate {
+                gate,
+                adjoint,
+                controls: controls.into_iter().map(&mut f).collect(),
+                targets: targets.into_iter().map(&mut f).collect(),
+            },
+            Instruction::Measure { qubit, result } => Instruction::Measure {
+                qubit: f(qubit),
+                result,
+            },
+            Instruction::Reset(qubit) => Instruction::Reset(f(qubit)),
+        }
+    }
+
+    pub(crate) fn qubits(&self) -> Vec<usize> {
+        match self {
+            Instruction::Gate {
+                controls, targets, ..
+            } => controls.iter().chain(targets).copied().collect(),
+            Instruction::Measure { qubit, .. } | Instruction::Reset(qubit) => vec![*qubit],
+        }
+    }
+}
+
+/// Flattens the circuit, including the children of any grouped operations, into a list of
+/// instructions in execution order.
+pub(crate) fn instructions(circuit: &Circuit) -> Result<Vec<Instruction>, ExportError> {
+    let mut instrs = Vec::new();
+    push_grid(&circuit.component_grid, &mut instrs)?;
+    Ok(instrs)
+}
+
+/// A grouped operation that is shared by every occurrence of it, where each qubit is numbered by
+/// its first use within the group so that identical groups applied to different qubits share it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Subcircuit {
+    pub(crate) name: String,
+    /// The body of the subcircuit, which may call the subcircuits that come before it.
+    pub(crate) body: Vec<Segment>,
+    pub(crate) num_qubits: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Segment {
+    Instruction(Instruction),
+    /// A call to the subcircuit with the given index, applied to the given qubits.
+    Call(usize, Vec<usize>),
+}
+
+impl Segment {
+    fn map_qubits(self, f: impl FnMut(usize) -> usize) -> Self {
+        match self {
+            Segment::Instruction(instr) => Segment::Instruction(instr.map_qubits(f)),
+            Segment::Call(index, qubits) => {
+                Segment::Call(index, qubits.into_iter().map(f).collect())
+            }
+        }
+    }
+}
+
+/// Lowers the circuit like [`instructions`], except that grouped operations occurring more than
+/// once with the same name and the same lowered text are kept as calls to a shared subcircuit.
+/// The text of a group is given by `lower` for its instructions, with each qubit numbered by its
+/// first use within the group, so its classical arguments, adjoint form and qubit order are all
+/// reflected in it. Repeated groups nested in a shared subcircuit are shared as well, and every
+/// subcircuit comes after the subcircuits it calls. Groups containing measurements are always
+/// flattened, since their results are not local to the group.
+pub(crate) fn segments(
+    circuit: &Circuit,
+    lower: impl Fn(&[Instruction]) -> String,
+) -> Result<(Vec<Subcircuit>, Vec<Segment>), ExportError> {
+    let mut sharing = Sharing {
+        lower,
+        counts: FxHashMap::default(),
+        indices: FxHashMap::default(),
+        subcircuits: Vec::new(),
+    };
+    sharing.count_groups(&circuit.component_grid)?;
+    let mut segments = Vec::new();
+    sharing.push_segments(&circuit.component_grid, &mut segments)?;
+    Ok((sharing.subcircuits, segments))
+}
+
+struct Sharing<F> {
+    lower: F,
+    /// The number of occurrences of each group, keyed by its name and lowered text.
+    counts: FxHashMap<String, usize>,
+    /// The index of the shared subcircuit of each group, keyed like `counts`.
+    indices: FxHashMap<String, usize>,
+    subcircuits: Vec<Subcircuit>,
+}
+
+impl<F: Fn(&[Instruction]) -> String> Sharing<F> {
+    fn count_groups(&mut self, grid: &ComponentGrid) -> Result<(), ExportError> {
+        for col in grid {
+            for op in col.components.iter().filter(|op| !op.children().is_empty()) {
+                let (key, ..) = self.group(op)?;
+                *self.counts.entry(key).or_default() += 1;
+                self.count_groups(op.children())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn push_segments(
+        &mut self,
+        grid: &ComponentGrid,
+        segments: &mut Vec<Segment>,
+    ) -> Result<(), ExportError> {
+        for col in grid {
+            for op in &col.components {
+                if op.children().is_empty() {
+                    let mut instrs = Vec::new();
+                    push_operation(op, &mut instrs)?;
+                    segments.extend(instrs.into_iter().map(Segment::Instruction));
+                    continue;
+                }
+                let (key, instrs, qubits) = self.group(op)?;
+                let repeated = self.counts.get(&key).is_some_and(|&count| count > 1);
+                let has_measurement = instrs
+                    .iter()
+                    .any(|instr| matches!(instr, Instruction::Measure { .. }));
+                if !repeated || has_measurement {
+                    self.push_segments(op.children(), segments)?;
+                    continue;
+                }
+                let index = match self.indices.get(&key).copied() {
+                    Some(index) => index,
+                    None => {
+                        let mut body = Vec::new();
+                        self.push_segments(op.children(), &mut body)?;
+                        let local = |qubit| {
+                            qubits
+                                .iter()
+                                .position(|&q| q == qubit)
+                                .expect("qubit should be used by the group")
+                        };
+                        self.subcircuits.push(Subcircuit {
+                            name: op.gate(),
+                            body: body
+                                .into_iter()
+                                .map(|segment| segment.map_qubits(local))
+                                .collect(),
+                            num_qubits: qubits.len(),
+                        });
+                        let index = self.subcircuits.len() - 1;
+                        self.indices.insert(key, index);
+                        index
+                    }
+                };
+                segments.push(Segment::Call(index, qubits));
+            }
+        }
+        Ok(())
+    }
+
+    /// Flattens a grouped operation, returning its key, its instructions with each qubit numbered by
+    /// its first use, and the qubits it is applied to in the order of that numbering.
+    fn group(&self, op: &Operation) -> Result<(String, Vec<Instruction>, Vec<usize>), ExportError> {
+        let mut instrs = Vec::new();
+        push_grid(op.children(), &mut instrs)?;
+        let mut qubits = Vec::new();
+        let instrs = instrs
+            .into_iter()
+            .map(|instr| {
+                instr.map_qubits(|qubit| {
+                    qubits.iter().position(|&q| q == qubit).unwrap_or_else(|| {
+                        qubits.push(qubit);
+                        qubits.len() - 1
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        let key = format!("{}\n{}", op.gate(), (self.lower)(&instrs));
+        Ok((key, instrs, qubits))
+    }
+}
+
+fn push_grid(grid: &ComponentGrid, instrs: &mut Vec<Instruction>) -> Result<(), ExportError> {
+    for col in grid {
+        for op in &col.components {
+            if op.children().is_empty() {
+                push_operation(op, instrs)?;
+            } else {
+                push_grid(op.children(), instrs)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn push_operation(op: &Operation, instrs: &mut Vec<Instruction>) -> Result<(), ExportError> {
+    match op {
+        Operation::Measurement(measurement) => push_measurement(measurement, instrs),
+        Operation::Unitary(unitary) => {
+            instrs.push(lower_unitary(unitary)?);
+            Ok(())
+        }
+        Operation::Ket(ket) => push_ket(ket, instrs),
+    }
+}
+
+fn push_measurement(
+    measurement: &Measurement,
+    instrs: &mut Vec<Instruction>,
+) -> Result<(), ExportError> {
+    let [qubit] = measurement.qubits.as_slice() else {
+        return Err(ExportError::JointMeasurement(measurement.qubits.len()));
+    };
+    // The reset half of `MResetZ` is traced as a separate `|0>` ket that follows it.
+    if !matches!(measurement.gate.as_str(), "M" | "MResetZ") {
+        return Err(ExportError::UnsupportedGate(measurement.gate.clone()));
+    }
+    let result = measurement
+        .results
+        .iter()
+        .find_map(|r| r.result.map(|result| (r.qubit, result)))
+        .unwrap_or((qubit.qubit, 0));
+    instrs.push(Instruction::Measure {
+        qubit: qubit.qubit,
+        result,
+    });
+    Ok(())
+}
+
+fn push_ket(ket: &Ket, instrs: &mut Vec<Instruction>) -> Result<(), ExportError> {
+    if ket.gate != "0" {
+        return Err(ExportError::UnsupportedKet(ket.gate.clone()));
+    }
+    instrs.extend(ket.targets.iter().map(|t| Instruction::Reset(t.qubit)));
+    Ok(())
+}
+
+fn lower_unitary(unitary: &Unitary) -> Result<Instruction, ExportError> {
+    let angle = || {
+        if let Some(&param) = unitary.params.first() {
+            return Ok(param);
+        }
+        let arg = unitary.args.first().map_or("", String::as_str);
+        arg.trim()
+            .parse::<f64>()
+            .map_err(|_| ExportError::InvalidGateArgument(unitary.gate.clone(), arg.to_string()))
+    };
+    // Rotations are inverted by negating their angle and the remaining gates other than S, T
+    // and SX are their own inverse, so only those three keep the adjoint flag.
+    let sign = if unitary.is_adjoint { -1.0 } else { 1.0 };
+    let (gate, adjoint) = match unitary.gate.as_str() {
+        "H" => (Gate::H, false),
+        "X" => (Gate::X, false),
+        "Y" => (Gate::Y, false),
+        "Z" => (Gate::Z, false),
+        "S" => (Gate::S, unitary.is_adjoint),
+        "T" => (Gate::T, unitary.is_adjoint),
+        "SX" => (Gate::SX, unitary.is_adjoint),
+        "SWAP" => (Gate::Swap, false),
+        "Rx" => (Gate::Rx(sign * angle()?), false),
+        "Ry" => (Gate::Ry(sign * angle()?), false),
+        "Rz" => (Gate::Rz(sign * angle()?), false),
+        "Rxx" => (Gate::Rxx(sign * angle()?), false),
+        "Ryy" => (Gate::Ryy(sign * angle()?), false),
+        "Rzz" => (Gate::Rzz(sign * angle()?), false),
+        _ => return Err(ExportError::UnsupportedGate(unitary.gate.clone())),
+    };
+    if unitary.targets.len() != gate.num_targets() {
+        return Err(ExportError::UnsupportedGate(unitary.gate.clone()));
+    }
+    if unitary.controls.iter().any(Register::is_classical) {
+        return Err(ExportError::ClassicallyControlledGate(unitary.gate.clone()));
+    }
+    Ok(Instruction::Gate {
+        gate,
+        adjoint,
+        controls: unitary.controls.iter().map(|c| c.qubit).collect(),
+        targets: unitary.targets.iter().map(|t| t.qubit).collect(),
+    })
+}
@@ -3,12 +3,16 @@
 
 mod builder;
 mod circuit;
+mod export;
 pub mod operations;
 
 pub use builder::{CircuitTracer, TracerConfig};
 pub use circuit::{
     CURRENT_VERSION, Circuit, CircuitGroup, ComponentColumn, Operation, operation_list_to_grid,
 };
+pub use export::ExportError;
 pub use operations::Error;
+pub mod circuit_to_cirq;
 pub mod circuit_to_qsharp;
+pub mod circuit_to_quil;
 pub mod json_to_circuit;
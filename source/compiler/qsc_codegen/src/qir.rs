@@ -7,6 +7,8 @@ mod instruction_tests;
 #[cfg(test)]
 mod tests;
 
+pub mod reader;
+
 use qsc_data_structures::target::TargetCapabilityFlags;
 use qsc_eval::val::Value;
 use qsc_lowerer::map_hir_package_to_fir;
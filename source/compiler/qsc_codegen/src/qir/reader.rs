@@ -0,0 +1,1061 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::rc::Rc;
+
+use miette::Diagnostic;
+use qsc_data_structures::{index_map::IndexMap, target::TargetCapabilityFlags};
+use qsc_eval::{
+    backend::Backend,
+    val::{self, Value},
+};
+use qsc_rir::rir::{
+    Block, BlockId, Callable, CallableId, CallableType, ConditionCode, FcmpConditionCode,
+    Instruction, Literal, Operand, Program, Ty, Variable, VariableId,
+};
+use rustc_hash::FxHashMap;
+use thiserror::Error;
+
+#[derive(Clone, Debug, Diagnostic, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("line {0}: unsupported QIR `{1}`")]
+    #[diagnostic(help(
+        "only the subset of LLVM IR that is emitted for the base and adaptive profiles is supported"
+    ))]
+    #[diagnostic(code("Qsc.QirReader.Unsupported"))]
+    Unsupported(usize, String),
+
+    #[error("the QIR does not define an entry point")]
+    #[diagnostic(code("Qsc.QirReader.MissingEntryPoint"))]
+    MissingEntryPoint,
+
+    #[error("callable `{0}` cannot be simulated")]
+    #[diagnostic(code("Qsc.QirReader.UnsupportedCallable"))]
+    UnsupportedCallable(String),
+
+    #[error("division by zero")]
+    #[diagnostic(code("Qsc.QirReader.DivisionByZero"))]
+    DivisionByZero,
+
+    #[error("result {0} cannot be read because its qubit was lost")]
+    #[diagnostic(code("Qsc.QirReader.LostResult"))]
+    LostResult(u32),
+
+    #[error("qubit {0} is used, but only {1} qubits are allocated")]
+    #[diagnostic(code("Qsc.QirReader.QubitOutOfRange"))]
+    QubitOutOfRange(u32, u32),
+
+    #[error("expected {0}, found {1}")]
+    #[diagnostic(code("Qsc.QirReader.TypeMismatch"))]
+    TypeMismatch(String, String),
+
+    #[error("variable {0} is used before it is assigned")]
+    #[diagnostic(code("Qsc.QirReader.UnassignedVariable"))]
+    UnassignedVariable(u32),
+
+    #[error("block {0} is not defined")]
+    #[diagnostic(code("Qsc.QirReader.MissingBlock"))]
+    MissingBlock(u32),
+
+    #[error("block {0} does not end with a terminator")]
+    #[diagnostic(code("Qsc.QirReader.MissingTerminator"))]
+    MissingTerminator(u32),
+
+    #[error("the phi node for variable {0} has no value for predecessor block {1}")]
+    #[diagnostic(code("Qsc.QirReader.MissingPhiPredecessor"))]
+    MissingPhiPredecessor(u32, u32),
+
+    #[error("record length {0} is negative")]
+    #[diagnostic(code("Qsc.QirReader.NegativeRecordLength"))]
+    NegativeRecordLength(i64),
+
+    #[error("a tuple or array record is not followed by all of its items")]
+    #[diagnostic(code("Qsc.QirReader.IncompleteRecord"))]
+    IncompleteRecord,
+}
+
+/// Parses QIR in the LLVM IR text format into a program. The supported subset is what is emitted
+/// by code generation for the base and adaptive profiles: static qubits and results, typed pointers,
+/// a single defined entry point, and integer, floating-point and boolean instructions. Names of
+/// values and blocks do not need to follow the `%var_N` and `block_N` convention used by code
+/// generation, so QIR produced by other tools can be read too.
+pub fn parse(qir: &str) -> Result<Program, Error> {
+    let mut parser = Parser::default();
+    let mut body = Vec::new();
+    let mut in_body = false;
+    for (idx, line) in qir.lines().enumerate() {
+        let line_num = idx + 1;
+        let line = line.trim();
+        if in_body {
+            if line == "}" {
+                in_body = false;
+            } else {
+                body.push((line_num, strip_comment(line)));
+            }
+        } else if line.starts_with("define ") {
+            parser.entry_point(line_num, line)?;
+            in_body = true;
+        } else if line.starts_with("declare ") {
+            parser.declaration(line_num, line)?;
+        } else if line.starts_with('@') && line.contains(" internal constant ") {
+            parser.constant(line_num, line)?;
+        } else if line.starts_with("attributes #0") {
+            parser.entry_point_attributes(line);
+        } else if line.starts_with('!') && line.contains("_computations\"") {
+            parser.module_flag(line);
+        }
+    }
+    if !parser.entry_defined {
+        return Err(Error::MissingEntryPoint);
+    }
+    parser.body(&body)?;
+    Ok(parser.finish())
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split_once(';')
+        .map_or(line, |(code, _)| code)
+        .trim_end()
+}
+
+#[derive(Default)]
+struct Parser {
+    program: Program,
+    callable_ids: FxHashMap<String, CallableId>,
+    tag_indices: FxHashMap<String, usize>,
+    entry_defined: bool,
+    num_qubits_defined: bool,
+    entry_block: Option<BlockId>,
+    blocks: Names,
+    variables: Names,
+}
+
+impl Parser {
+    fn next_callable_id(&self) -> CallableId {
+        CallableId(
+            self.program
+                .callables
+                .iter()
+                .map(|(id, _)| id.0 + 1)
+                .max()
+                .unwrap_or_default(),
+        )
+    }
+
+    fn add_callable(&mut self, callable: Callable) -> CallableId {
+        let id = self.next_callable_id();
+        self.callable_ids.insert(callable.name.clone(), id);
+        self.program.callables.insert(id, callable);
+        id
+    }
+
+    /// Parses `@name = internal constant [N x i8] c"...\00"`, which holds an output tag.
+    fn constant(&mut self, line_num: usize, line: &str) -> Result<(), Error> {
+        let (name, rest) = line
+            .split_once(" = ")
+            .ok_or_else(|| unsupported(line_num, line))?;
+        let name = name.trim_start_matches('@');
+        if name == "empty_tag" {
+            return Ok(());
+        }
+        let tag = rest
+            .split_once("c\"")
+            .and_then(|(_, tag)| tag.strip_suffix("\\00\""))
+            .ok_or_else(|| unsupported(line_num, line))?;
+        self.tag_indices
+            .insert(name.to_string(), self.program.tags.len());
+        self.program.tags.push(tag.to_string());
+        Ok(())
+    }
+
+    /// Parses `declare <ty> @name(<tys>) [#1]`.
+    fn declaration(&mut self, line_num: usize, line: &str) -> Result<(), Error> {
+        let (output_type, name, args) = signature(line.trim_start_matches("declare "))
+            .ok_or_else(|| unsupported(line_num, line))?;
+        let input_type = split_top_level(args)
+            .into_iter()
+            .map(|ty| parse_ty(ty).flatten())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| unsupported(line_num, line))?;
+        let irreversible = line.ends_with("#1");
+        self.add_callable(Callable {
+            call_type: call_type(name, irreversible),
+            name: name.to_string(),
+            input_type,
+            output_type,
+            body: None,
+        });
+        Ok(())
+    }
+
+    /// Parses `define <ty> @name() #0 {`.
+    fn entry_point(&mut self, line_num: usize, line: &str) -> Result<(), Error> {
+        if self.entry_defined {
+            return Err(unsupported(line_num, line));
+        }
+        let (output_type, name, args) = signature(line.trim_start_matches("define "))
+            .ok_or_else(|| unsupported(line_num, line))?;
+        if !args.trim().is_empty() {
+            return Err(unsupported(line_num, line));
+        }
+        // The body is filled in once all blocks have been parsed.
+        self.program.entry = self.add_callable(Callable {
+            name: name.to_string(),
+            input_type: Vec::new(),
+            output_type,
+            body: None,
+            call_type: CallableType::Regular,
+        });
+        self.entry_defined = true;
+        Ok(())
+    }
+
+    fn entry_point_attributes(&mut self, line: &str) {
+        let attribute = |name: &str| {
+            line.split_once(&format!("\"{name}\"=\""))
+                .and_then(|(_, rest)| rest.split_once('"'))
+                .map(|(value, _)| value)
+        };
+        if let Some(num_qubits) = attribute("required_num_qubits").and_then(|v| v.parse().ok()) {
+            self.program.num_qubits = num_qubits;
+            self.num_qubits_defined = true;
+        }
+        if let Some(num_results) = attribute("required_num_results").and_then(|v| v.parse().ok()) {
+            self.program.num_results = num_results;
+        }
+        if attribute("qir_profiles") == Some("adaptive_profile") {
+            self.program.config.capabilities |= TargetCapabilityFlags::Adaptive;
+        }
+    }
+
+    fn module_flag(&mut self, line: &str) {
+        if line.contains("\"int_computations\"") {
+            self.program.config.capabilities |= TargetCapabilityFlags::IntegerComputations;
+        } else if line.contains("\"float_computations\"") {
+            self.program.config.capabilities |= TargetCapabilityFlags::FloatingPointComputations;
+        }
+    }
+
+    fn body(&mut self, lines: &[(usize, &str)]) -> Result<(), Error> {
+        let labels = lines
+            .iter()
+            .filter_map(|(_, line)| line.strip_suffix(':'))
+            .collect::<Vec<_>>();
+        self.blocks = Names::new(labels.iter().copied(), "block_");
+        let values = lines
+            .iter()
+            .flat_map(|(_, line)| local_names(line))
+            .filter(|name| !labels.contains(name))
+            .collect::<Vec<_>>();
+        self.variables = Names::new(values.into_iter(), "var_");
+
+        let mut block: Option<(BlockId, Vec<Instruction>)> = None;
+        for &(line_num, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(label) = line.strip_suffix(':') {
+                if let Some((id, instrs)) = block.take() {
+                    self.program.blocks.insert(id, Block(instrs));
+                }
+                let id = self.blocks.id(label);
+                self.entry_block.get_or_insert(id);
+                block = Some((id, Vec::new()));
+                continue;
+            }
+            let instr = self
+                .instruction(line)
+                .ok_or_else(|| unsupported(line_num, line))?;
+            let (_, instrs) = block.get_or_insert_with(|| {
+                // The entry block may be unlabeled.
+                let id = self.blocks.id("");
+                self.entry_block.get_or_insert(id);
+                (id, Vec::new())
+            });
+            instrs.push(instr);
+        }
+        if let Some((id, instrs)) = block {
+            self.program.blocks.insert(id, Block(instrs));
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Program {
+        self.program
+            .callables
+            .get_mut(self.program.entry)
+            .expect("entry point should be defined")
+            .body = Some(self.entry_block.unwrap_or_default());
+        if !self.num_qubits_defined {
+            // Without the attribute, allocate every qubit that the program uses.
+            self.program.num_qubits = self
+                .program
+                .blocks
+                .values()
+                .flat_map(|block| &block.0)
+                .filter_map(|instr| match instr {
+                    Instruction::Call(_, args, _) => Some(args),
+                    _ => None,
+                })
+                .flatten()
+                .filter_map(|arg| match arg {
+                    Operand::Literal(Literal::Qubit(q)) => Some(q + 1),
+                    _ => None,
+                })
+                .max()
+                .unwrap_or_default();
+        }
+        self.program
+    }
+
+    fn instruction(&mut self, line: &str) -> Option<Instruction> {
+        let (target, rhs) = match line.split_once(" = ") {
+            Some((target, rhs)) if target.starts_with('%') => (Some(&target[1..]), rhs),
+            _ => (None, line),
+        };
+        let (op, rest) = rhs.split_once(' ').unwrap_or((rhs, ""));
+        Some(match op {
+            "call" => {
+                let (output_type, name, args) = signature(rest)?;
+                let args = split_top_level(args)
+                    .into_iter()
+                    .map(|arg| self.typed_operand(arg))
+                    .collect::<Option<Vec<_>>>()?;
+                let id = match self.callable_ids.get(name) {
+                    Some(id) => *id,
+                    None => self.add_callable(Callable {
+                        name: name.to_string(),
+                        input_type: args.iter().map(operand_ty).collect(),
+                        output_type,
+                        body: None,
+                        call_type: call_type(name, false),
+                    }),
+                };
+                let var = output_type.and_then(|ty| self.target(target, ty));
+                Instruction::Call(id, args, var)
+            }
+            "br" => match split_top_level(rest).as_slice() {
+                [label] => Instruction::Jump(self.label(label)?),
+                [cond, true_label, false_label] => {
+                    let Operand::Variable(cond) = self.typed_operand(cond)? else {
+                        return None;
+                    };
+                    Instruction::Branch(cond, self.label(true_label)?, self.label(false_label)?)
+                }
+                _ => return None,
+            },
+            "ret" => Instruction::Return,
+            "icmp" | "fcmp" => {
+                let (code, rest) = rest.split_once(' ')?;
+                let (ty, lhs, rhs) = self.binary_operands(rest)?;
+                let var = self.target(target, Ty::Boolean)?;
+                if op == "icmp" {
+                    Instruction::Icmp(condition_code(code)?, lhs, rhs, var)
+                } else {
+                    Instruction::Fcmp(fcmp_condition_code(code)?, lhs, rhs, var)
+                }
+            }
+            "phi" => {
+                let (ty, incoming) = rest.split_once(' ')?;
+                let ty = parse_ty(ty)??;
+                let args = split_top_level(incoming)
+                    .into_iter()
+                    .map(|arg| {
+                        let (value, label) =
+                            arg.strip_prefix('[')?.strip_suffix(']')?.split_once(',')?;
+                        Some((
+                            self.operand(ty, value.trim())?,
+                            self.blocks.id(label.trim().strip_prefix('%')?),
+                        ))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Instruction::Phi(args, self.target(target, ty)?)
+            }
+            _ => {
+                let (ty, lhs, rhs) = self.binary_operands(rest)?;
+                let var = self.target(target, ty)?;
+                match (op, ty, rhs) {
+                    ("add", Ty::Integer, _) => Instruction::Add(lhs, rhs, var),
+                    ("sub", Ty::Integer, _) => Instruction::Sub(lhs, rhs, var),
+                    ("mul", Ty::Integer, _) => Instruction::Mul(lhs, rhs, var),
+                    ("sdiv", Ty::Integer, _) => Instruction::Sdiv(lhs, rhs, var),
+                    ("srem", Ty::Integer, _) => Instruction::Srem(lhs, rhs, var),
+                    ("shl", Ty::Integer, _) => Instruction::Shl(lhs, rhs, var),
+                    ("ashr", Ty::Integer, _) => Instruction::Ashr(lhs, rhs, var),
+                    ("fadd", Ty::Double, _) => Instruction::Fadd(lhs, rhs, var),
+                    ("fsub", Ty::Double, _) => Instruction::Fsub(lhs, rhs, var),
+                    ("fmul", Ty::Double, _) => Instruction::Fmul(lhs, rhs, var),
+                    ("fdiv", Ty::Double, _) => Instruction::Fdiv(lhs, rhs, var),
+                    ("and", Ty::Boolean, _) => Instruction::LogicalAnd(lhs, rhs, var),
+                    ("or", Ty::Boolean, _) => Instruction::LogicalOr(lhs, rhs, var),
+                    ("xor", Ty::Boolean, Operand::Literal(Literal::Bool(true))) => {
+                        Instruction::LogicalNot(lhs, var)
+                    }
+                    ("and", Ty::Integer, _) => Instruction::BitwiseAnd(lhs, rhs, var),
+                    ("or", Ty::Integer, _) => Instruction::BitwiseOr(lhs, rhs, var),
+                    ("xor", Ty::Integer, Operand::Literal(Literal::Integer(-1))) => {
+                        Instruction::BitwiseNot(lhs, var)
+                    }
+                    ("xor", Ty::Integer, _) => Instruction::BitwiseXor(lhs, rhs, var),
+                    _ => return None,
+                }
+            }
+        })
+    }
+
+    fn target(&mut self, name: Option<&str>, ty: Ty) -> Option<Variable> {
+        Some(Variable {
+            variable_id: self.variables.var(name?),
+            ty,
+        })
+    }
+
+    /// Parses `<ty> <lhs>, <rhs>`, skipping any `nsw`, `nuw` or `exact` flags before the type.
+    fn binary_operands(&mut self, rest: &str) -> Option<(Ty, Operand, Operand)> {
+        let mut rest = rest;
+        while let Some(stripped) = ["nsw ", "nuw ", "exact "]
+            .iter()
+            .find_map(|flag| rest.strip_prefix(flag))
+        {
+            rest = stripped;
+        }
+        let (ty, operands) = rest.split_once(' ')?;
+        let ty = parse_ty(ty)??;
+        let (lhs, rhs) = operands.split_once(',')?;
+        Some((
+            ty,
+            self.operand(ty, lhs.trim())?,
+            self.operand(ty, rhs.trim())?,
+        ))
+    }
+
+    fn label(&mut self, label: &str) -> Option<BlockId> {
+        Some(self.blocks.id(label.strip_prefix("label %")?))
+    }
+
+    /// Parses an operand preceded by its type, such as `i64 %var_1` or
+    /// `%Qubit* inttoptr (i64 1 to %Qubit*)`.
+    fn typed_operand(&mut self, operand: &str) -> Option<Operand> {
+        let (ty, value) = operand.split_once(' ')?;
+        let ty = parse_ty(ty)??;
+        self.operand(ty, value.trim())
+    }
+
+    fn operand(&mut self, ty: Ty, value: &str) -> Option<Operand> {
+        if let Some(name) = value.strip_prefix('%') {
+            return Some(Operand::Variable(Variable {
+                variable_id: self.variables.var(name),
+                ty,
+            }));
+        }
+        let literal = match ty {
+            Ty::Boolean => Literal::Bool(value.parse().ok()?),
+            Ty::Integer => Literal::Integer(value.parse().ok()?),
+            Ty::Double => Literal::Double(value.parse().ok()?),
+            Ty::Qubit => Literal::Qubit(pointer_id(value)?),
+            Ty::Result => Literal::Result(pointer_id(value)?),
+            Ty::Pointer if value == "null" => Literal::Pointer,
+            Ty::Pointer => {
+                // getelementptr inbounds ([N x i8], [N x i8]* @name, i64 0, i64 0)
+                let (_, rest) = value.split_once('@')?;
+                let (name, _) = rest.split_once(',')?;
+                if name == "empty_tag" {
+                    Literal::EmptyTag
+                } else {
+                    let idx = *self.tag_indices.get(name)?;
+                    Literal::Tag(idx, self.program.tags[idx].len())
+                }
+            }
+        };
+        Some(Operand::Literal(literal))
+    }
+}
+
+/// Maps the names of blocks or values to ids. When every name has the form `<prefix>N`, as emitted
+/// by code generation, the ids are the numbers in the names. Otherwise ids are assigned in order of
+/// first appearance.
+#[derive(Default)]
+struct Names {
+    ids: FxHashMap<String, u32>,
+}
+
+impl Names {
+    fn new<'a>(names: impl Iterator<Item = &'a str>, prefix: &str) -> Self {
+        let names = names.collect::<Vec<_>>();
+        let numbered = names.iter().all(|name| {
+            name.strip_prefix(prefix)
+                .is_some_and(|n| n.parse::<u32>().is_ok())
+        });
+        let mut ids = FxHashMap::default();
+        for name in &names {
+            let next = u32::try_from(ids.len()).expect("number of names should fit in u32");
+            let id = if numbered {
+                name[prefix.len()..]
+                    .parse()
+                    .expect("name should end with a number")
+            } else {
+                next
+            };
+            ids.entry((*name).to_string()).or_insert(id);
+        }
+        Self { ids }
+    }
+
+    fn id(&mut self, name: &str) -> BlockId {
+        BlockId(self.get(name))
+    }
+
+    fn var(&mut self, name: &str) -> VariableId {
+        VariableId(self.get(name))
+    }
+
+    fn get(&mut self, name: &str) -> u32 {
+        let next = self.ids.values().map(|id| id + 1).max().unwrap_or_default();
+        *self.ids.entry(name.to_string()).or_insert(next)
+    }
+}
+
+/// Returns the names of the local values referenced in a line, such as `var_1` for `%var_1`.
+fn local_names(line: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = line;
+    while let Some(idx) = rest.find('%') {
+        rest = &rest[idx + 1..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || "_.$-".contains(c)))
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+        if !name.is_empty() && name != "Qubit" && name != "Result" {
+            names.push(name);
+        }
+        rest = &rest[end..];
+    }
+    names
+}
+
+/// Parses `<ty> @name(<args>)` followed by anything, returning the type, the name and the
+/// arguments.
+fn signature(text: &str) -> Option<(Option<Ty>, &str, &str)> {
+    let (ty, rest) = text.split_once(" @")?;
+    let (name, rest) = rest.split_once('(')?;
+    let args = &rest[..matching_paren(rest)?];
+    Some((parse_ty(ty.trim())?, name, args))
+}
+
+/// Returns the index of the parenthesis that closes an already opened parenthesis.
+fn matching_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(idx),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits a comma separated list, ignoring commas nested in parentheses or brackets.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in text.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(text[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = text[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+/// Parses a type, where `void` is `Some(None)`.
+fn parse_ty(ty: &str) -> Option<Option<Ty>> {
+    Some(Some(match ty {
+        "void" => return Some(None),
+        "i1" => Ty::Boolean,
+        "i64" => Ty::Integer,
+        "double" => Ty::Double,
+        "%Qubit*" => Ty::Qubit,
+        "%Result*" => Ty::Result,
+        "i8*" => Ty::Pointer,
+        _ => return None,
+    }))
+}
+
+/// Parses a static qubit or result, written as `null` or `inttoptr (i64 N to %Qubit*)`.
+fn pointer_id(value: &str) -> Option<u32> {
+    if value == "null" {
+        return Some(0);
+    }
+    value
+        .strip_prefix("inttoptr (i64 ")?
+        .split_once(' ')?
+        .0
+        .parse()
+        .ok()
+}
+
+fn operand_ty(operand: &Operand) -> Ty {
+    match operand {
+        Operand::Literal(literal) => match literal {
+            Literal::Bool(_) => Ty::Boolean,
+            Literal::Integer(_) => Ty::Integer,
+            Literal::Double(_) => Ty::Double,
+            Literal::Qubit(_) => Ty::Qubit,
+            Literal::Result(_) => Ty::Result,
+            Literal::Pointer | Literal::Tag(..) | Literal::EmptyTag => Ty::Pointer,
+        },
+        Operand::Variable(var) => var.ty,
+    }
+}
+
+fn call_type(name: &str, irreversible: bool) -> CallableType {
+    match name {
+        "__quantum__qis__reset__body" => CallableType::Reset,
+        "__quantum__qis__m__body"
+        | "__quantum__qis__mz__body"
+        | "__quantum__qis__mresetz__body" => CallableType::Measurement,
+        "__quantum__rt__read_result" | "__quantum__qis__read_result__body" => CallableType::Readout,
+        _ if name.starts_with("__quantum__rt__") && name.ends_with("_record_output") => {
+            CallableType::OutputRecording
+        }
+        _ if irreversible => CallableType::Measurement,
+        _ => CallableType::Regular,
+    }
+}
+
+fn condition_code(code: &str) -> Option<ConditionCode> {
+    Some(match code {
+        "eq" => ConditionCode::Eq,
+        "ne" => ConditionCode::Ne,
+        "sgt" => ConditionCode::Sgt,
+        "sge" => ConditionCode::Sge,
+        "slt" => ConditionCode::Slt,
+        "sle" => ConditionCode::Sle,
+        _ => return None,
+    })
+}
+
+fn fcmp_condition_code(code: &str) -> Option<FcmpConditionCode> {
+    Some(match code {
+        "false" => FcmpConditionCode::False,
+        "oeq" => FcmpConditionCode::OrderedAndEqual,
+        "ogt" => FcmpConditionCode::OrderedAndGreaterThan,
+        "oge" => FcmpConditionCode::OrderedAndGreaterThanOrEqual,
+        "olt" => FcmpConditionCode::OrderedAndLessThan,
+        "ole" => FcmpConditionCode::OrderedAndLessThanOrEqual,
+        "one" => FcmpConditionCode::OrderedAndNotEqual,
+        "ord" => FcmpConditionCode::Ordered,
+        "ueq" => FcmpConditionCode::UnorderedOrEqual,
+        "ugt" => FcmpConditionCode::UnorderedOrGreaterThan,
+        "uge" => FcmpConditionCode::UnorderedOrGreaterThanOrEqual,
+        "ult" => FcmpConditionCode::UnorderedOrLessThan,
+        "ule" => FcmpConditionCode::UnorderedOrLessThanOrEqual,
+        "une" => FcmpConditionCode::UnorderedOrNotEqual,
+        "uno" => FcmpConditionCode::Unordered,
+        "true" => FcmpConditionCode::True,
+        _ => return None,
+    })
+}
+
+fn unsupported(line_num: usize, line: &str) -> Error {
+    Error::Unsupported(line_num, line.to_string())
+}
+
+/// Runs a program on the given backend, returning the value recorded as its output.
+/// Output recording calls form a tree: tuple and array records are followed by the records of
+/// their items.
+/// Returns an error if the program is malformed, such as by using a variable before it is
+/// assigned or a value of the wrong type, or if it calls a callable that cannot be simulated.
+pub fn simulate(program: &Program, backend: &mut impl Backend) -> Result<Value, Error> {
+    let qubits = (0..program.num_qubits)
+        .map(|_| backend.qubit_allocate())
+        .collect::<Vec<_>>();
+    let mut sim = Simulation {
+        program,
+        backend,
+        qubits,
+        results: FxHashMap::default(),
+        vars: IndexMap::default(),
+        records: Vec::new(),
+    };
+    let outcome = program
+        .callables
+        .get(program.entry)
+        .and_then(|entry| entry.body)
+        .ok_or(Error::MissingEntryPoint)
+        .and_then(|entry_block_id| sim.run(entry_block_id));
+    for qubit in sim.qubits {
+        sim.backend.qubit_release(qubit);
+    }
+    outcome?;
+    let mut records = sim.records.into_iter();
+    let mut values = Vec::new();
+    while let Some(record) = records.next() {
+        values.push(record_value(record, &mut records)?);
+    }
+    Ok(if values.len() == 1 {
+        values.swap_remove(0)
+    } else if values.is_empty() {
+        Value::unit()
+    } else {
+        Value::Tuple(values.into(), None)
+    })
+}
+
+struct Simulation<'a, B: Backend> {
+    program: &'a Program,
+    backend: &'a mut B,
+    qubits: Vec<usize>,
+    results: FxHashMap<u32, val::Result>,
+    vars: IndexMap<VariableId, Literal>,
+    records: Vec<Record>,
+}
+
+enum Record {
+    Value(Value),
+    Tuple(usize),
+    Array(usize),
+}
+
+fn record_value(record: Record, rest: &mut std::vec::IntoIter<Record>) -> Result<Value, Error> {
+    let len = match record {
+        Record::Value(value) => return Ok(value),
+        Record::Tuple(len) | Record::Array(len) => len,
+    };
+    let mut items = Vec::with_capacity(len.min(rest.len()));
+    for _ in 0..len {
+        let item = rest.next().ok_or(Error::IncompleteRecord)?;
+        items.push(record_value(item, rest)?);
+    }
+    Ok(if matches!(record, Record::Tuple(_)) {
+        Value::Tuple(items.into(), None)
+    } else {
+        Value::Array(Rc::new(items))
+    })
+}
+
+impl<B: Backend> Simulation<'_, B> {
+    /// Runs the blocks of the program from the entry block until it returns.
+    fn run(&mut self, entry_block_id: BlockId) -> Result<(), Error> {
+        let program = self.program;
+        let mut block_id = entry_block_id;
+        let mut prev_block_id = block_id;
+        'blocks: loop {
+            let block = program
+                .blocks
+                .get(block_id)
+                .ok_or(Error::MissingBlock(block_id.0))?;
+            for instr in &block.0 {
+                let next = match instr {
+                    Instruction::Jump(next) => *next,
+                    Instruction::Branch(cond, true_id, false_id) => {
+                        if self.bool(&Operand::Variable(*cond))? {
+                            *true_id
+                        } else {
+                            *false_id
+                        }
+                    }
+                    Instruction::Return => return Ok(()),
+                    Instruction::Phi(args, var) => {
+                        let (value, _) =
+                            args.iter().find(|(_, pred)| *pred == prev_block_id).ok_or(
+                                Error::MissingPhiPredecessor(var.variable_id.0, prev_block_id.0),
+                            )?;
+                        let value = self.eval(value)?;
+                        self.vars.insert(var.variable_id, value);
+                        continue;
+                    }
+                    _ => {
+                        self.instruction(instr)?;
+                        continue;
+                    }
+                };
+                prev_block_id = block_id;
+                block_id = next;
+                continue 'blocks;
+            }
+            return Err(Error::MissingTerminator(block_id.0));
+        }
+    }
+
+    fn eval(&self, operand: &Operand) -> Result<Literal, Error> {
+        match operand {
+            Operand::Literal(literal) => Ok(*literal),
+            Operand::Variable(var) => self
+                .vars
+                .get(var.variable_id)
+                .copied()
+                .ok_or(Error::UnassignedVariable(var.variable_id.0)),
+        }
+    }
+
+    fn int(&self, operand: &Operand) -> Result<i64, Error> {
+        match self.eval(operand)? {
+            Literal::Integer(i) => Ok(i),
+            literal => Err(type_mismatch("Integer", literal)),
+        }
+    }
+
+    fn double(&self, operand: &Operand) -> Result<f64, Error> {
+        match self.eval(operand)? {
+            Literal::Double(d) => Ok(d),
+            literal => Err(type_mismatch("Double", literal)),
+        }
+    }
+
+    fn bool(&self, operand: &Operand) -> Result<bool, Error> {
+        match self.eval(operand)? {
+            Literal::Bool(b) => Ok(b),
+            literal => Err(type_mismatch("Boolean", literal)),
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn instruction(&mut self, instr: &Instruction) -> Result<(), Error> {
+        let (value, var) = match instr {
+            Instruction::Call(id, args, var) => {
+                let program = self.program;
+                let value = self.call(program.get_callable(*id), args)?;
+                match (value, var) {
+                    (Some(value), Some(var)) => (value, *var),
+                    _ => return Ok(()),
+                }
+            }
+            Instruction::Store(value, var) => (self.eval(value)?, *var),
+            Instruction::Add(lhs, rhs, var) => (
+                Literal::Integer(self.int(lhs)?.wrapping_add(self.int(rhs)?)),
+                *var,
+            ),
+            Instruction::Sub(lhs, rhs, var) => (
+                Literal::Integer(self.int(lhs)?.wrapping_sub(self.int(rhs)?)),
+                *var,
+            ),
+            Instruction::Mul(lhs, rhs, var) => (
+                Literal::Integer(self.int(lhs)?.wrapping_mul(self.int(rhs)?)),
+                *var,
+            ),
+            Instruction::Sdiv(lhs, rhs, var) => (
+                Literal::Integer(
+                    self.int(lhs)?
+                        .checked_div(self.int(rhs)?)
+                        .ok_or(Error::DivisionByZero)?,
+                ),
+                *var,
+            ),
+            Instruction::Srem(lhs, rhs, var) => (
+                Literal::Integer(
+                    self.int(lhs)?
+                        .checked_rem(self.int(rhs)?)
+                        .ok_or(Error::DivisionByZero)?,
+                ),
+                *var,
+            ),
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            Instruction::Shl(lhs, rhs, var) => (
+                Literal::Integer(self.int(lhs)?.wrapping_shl(self.int(rhs)? as u32)),
+                *var,
+            ),
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            Instruction::Ashr(lhs, rhs, var) => (
+                Literal::Integer(self.int(lhs)?.wrapping_shr(self.int(rhs)? as u32)),
+                *var,
+            ),
+            Instruction::Fadd(lhs, rhs, var) => {
+                (Literal::Double(self.double(lhs)? + self.double(rhs)?), *var)
+            }
+            Instruction::Fsub(lhs, rhs, var) => {
+                (Literal::Double(self.double(lhs)? - self.double(rhs)?), *var)
+            }
+            Instruction::Fmul(lhs, rhs, var) => {
+                (Literal::Double(self.double(lhs)? * self.double(rhs)?), *var)
+            }
+            Instruction::Fdiv(lhs, rhs, var) => {
+                (Literal::Double(self.double(lhs)? / self.double(rhs)?), *var)
+            }
+            Instruction::Icmp(code, lhs, rhs, var) => {
+                let (lhs, rhs) = (self.eval(lhs)?, self.eval(rhs)?);
+                let ordering = match (lhs, rhs) {
+                    (Literal::Integer(lhs), Literal::Integer(rhs)) => lhs.cmp(&rhs),
+                    (Literal::Bool(lhs), Literal::Bool(rhs)) => lhs.cmp(&rhs),
+                    (Literal::Integer(_), literal) => {
+                        return Err(type_mismatch("Integer", literal));
+                    }
+                    (Literal::Bool(_), literal) => return Err(type_mismatch("Boolean", literal)),
+                    (literal, _) => return Err(type_mismatch("Integer or Boolean", literal)),
+                };
+                let result = match code {
+                    ConditionCode::Eq => ordering.is_eq(),
+                    ConditionCode::Ne => ordering.is_ne(),
+                    ConditionCode::Sgt => ordering.is_gt(),
+                    ConditionCode::Sge => ordering.is_ge(),
+                    ConditionCode::Slt => ordering.is_lt(),
+                    ConditionCode::Sle => ordering.is_le(),
+                };
+                (Literal::Bool(result), *var)
+            }
+            Instruction::Fcmp(code, lhs, rhs, var) => {
+                let (lhs, rhs) = (self.double(lhs)?, self.double(rhs)?);
+                let unordered = lhs.is_nan() || rhs.is_nan();
+                let result = match code {
+                    FcmpConditionCode::False => false,
+                    FcmpConditionCode::True => true,
+                    FcmpConditionCode::Ordered => !unordered,
+                    FcmpConditionCode::Unordered => unordered,
+                    FcmpConditionCode::OrderedAndEqual => lhs == rhs,
+                    FcmpConditionCode::OrderedAndGreaterThan => lhs > rhs,
+                    FcmpConditionCode::OrderedAndGreaterThanOrEqual => lhs >= rhs,
+                    FcmpConditionCode::OrderedAndLessThan => lhs < rhs,
+                    FcmpConditionCode::OrderedAndLessThanOrEqual => lhs <= rhs,
+                    FcmpConditionCode::OrderedAndNotEqual => !unordered && lhs != rhs,
+                    FcmpConditionCode::UnorderedOrEqual => unordered || lhs == rhs,
+                    FcmpConditionCode::UnorderedOrGreaterThan => unordered || lhs > rhs,
+                    FcmpConditionCode::UnorderedOrGreaterThanOrEqual => unordered || lhs >= rhs,
+                    FcmpConditionCode::UnorderedOrLessThan => unordered || lhs < rhs,
+                    FcmpConditionCode::UnorderedOrLessThanOrEqual => unordered || lhs <= rhs,
+                    FcmpConditionCode::UnorderedOrNotEqual => unordered || lhs != rhs,
+                };
+                (Literal::Bool(result), *var)
+            }
+            Instruction::LogicalNot(value, var) => (Literal::Bool(!self.bool(value)?), *var),
+            Instruction::LogicalAnd(lhs, rhs, var) => {
+                (Literal::Bool(self.bool(lhs)? && self.bool(rhs)?), *var)
+            }
+            Instruction::LogicalOr(lhs, rhs, var) => {
+                (Literal::Bool(self.bool(lhs)? || self.bool(rhs)?), *var)
+            }
+            Instruction::BitwiseNot(value, var) => (Literal::Integer(!self.int(value)?), *var),
+            Instruction::BitwiseAnd(lhs, rhs, var) => {
+                (Literal::Integer(self.int(lhs)? & self.int(rhs)?), *var)
+            }
+            Instruction::BitwiseOr(lhs, rhs, var) => {
+                (Literal::Integer(self.int(lhs)? | self.int(rhs)?), *var)
+            }
+            Instruction::BitwiseXor(lhs, rhs, var) => {
+                (Literal::Integer(self.int(lhs)? ^ self.int(rhs)?), *var)
+            }
+            Instruction::Jump(..)
+            | Instruction::Branch(..)
+            | Instruction::Phi(..)
+            | Instruction::Return => {
+                panic!("terminators and phi nodes should be handled by the caller")
+            }
+        };
+        self.vars.insert(var.variable_id, value);
+        Ok(())
+    }
+
+    fn call(&mut self, callable: &Callable, args: &[Operand]) -> Result<Option<Literal>, Error> {
+        let args = args
+            .iter()
+            .map(|arg| self.eval(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let qubits = &self.qubits;
+        let q = |literal: &Literal| match literal {
+            Literal::Qubit(q) => qubits.get(*q as usize).copied().ok_or_else(|| {
+                Error::QubitOutOfRange(
+                    *q,
+                    u32::try_from(qubits.len()).expect("qubit count should fit in u32"),
+                )
+            }),
+            literal => Err(type_mismatch("Qubit", *literal)),
+        };
+        let name = callable
+            .name
+            .strip_prefix("__quantum__qis__")
+            .or_else(|| callable.name.strip_prefix("__quantum__rt__"))
+            .unwrap_or(&callable.name);
+        match (name, args.as_slice()) {
+            ("initialize", _) => {}
+            ("x__body", [a]) => self.backend.x(q(a)?),
+            ("y__body", [a]) => self.backend.y(q(a)?),
+            ("z__body", [a]) => self.backend.z(q(a)?),
+            ("h__body", [a]) => self.backend.h(q(a)?),
+            ("s__body", [a]) => self.backend.s(q(a)?),
+            ("s__adj", [a]) => self.backend.sadj(q(a)?),
+            ("t__body", [a]) => self.backend.t(q(a)?),
+            ("t__adj", [a]) => self.backend.tadj(q(a)?),
+            ("sx__body", [a]) => self.backend.sx(q(a)?),
+            ("reset__body", [a]) => self.backend.reset(q(a)?),
+            ("rx__body", [Literal::Double(theta), a]) => self.backend.rx(*theta, q(a)?),
+            ("ry__body", [Literal::Double(theta), a]) => self.backend.ry(*theta, q(a)?),
+            ("rz__body", [Literal::Double(theta), a]) => self.backend.rz(*theta, q(a)?),
+            ("rxx__body", [Literal::Double(theta), a, b]) => {
+                self.backend.rxx(*theta, q(a)?, q(b)?);
+            }
+            ("ryy__body", [Literal::Double(theta), a, b]) => {
+                self.backend.ryy(*theta, q(a)?, q(b)?);
+            }
+            ("rzz__body", [Literal::Double(theta), a, b]) => {
+                self.backend.rzz(*theta, q(a)?, q(b)?);
+            }
+            ("cx__body", [a, b]) => self.backend.cx(q(a)?, q(b)?),
+            ("cy__body", [a, b]) => self.backend.cy(q(a)?, q(b)?),
+            ("cz__body", [a, b]) => self.backend.cz(q(a)?, q(b)?),
+            ("swap__body", [a, b]) => self.backend.swap(q(a)?, q(b)?),
+            ("ccx__body", [a, b, c]) => self.backend.ccx(q(a)?, q(b)?, q(c)?),
+            ("m__body" | "mz__body", [a, Literal::Result(r)]) => {
+                let result = self.backend.m(q(a)?);
+                self.results.insert(*r, result);
+            }
+            ("mresetz__body", [a, Literal::Result(r)]) => {
+                let result = self.backend.mresetz(q(a)?);
+                self.results.insert(*r, result);
+            }
+            ("read_result" | "read_result__body", [Literal::Result(r)]) => {
+                return match self.results.get(r) {
+                    Some(val::Result::Val(b)) => Ok(Some(Literal::Bool(*b))),
+                    Some(_) => Err(Error::LostResult(*r)),
+                    None => Ok(Some(Literal::Bool(false))),
+                };
+            }
+            ("result_record_output", [Literal::Result(r), _]) => {
+                let result = self
+                    .results
+                    .get(r)
+                    .copied()
+                    .unwrap_or(val::Result::Val(false));
+                self.records.push(Record::Value(Value::Result(result)));
+            }
+            ("bool_record_output", [Literal::Bool(b), _]) => {
+                self.records.push(Record::Value(Value::Bool(*b)));
+            }
+            ("int_record_output", [Literal::Integer(i), _]) => {
+                self.records.push(Record::Value(Value::Int(*i)));
+            }
+            ("double_record_output", [Literal::Double(d), _]) => {
+                self.records.push(Record::Value(Value::Double(*d)));
+            }
+            ("tuple_record_output", [Literal::Integer(len), _]) => {
+                self.records.push(Record::Tuple(record_len(*len)?));
+            }
+            ("array_record_output", [Literal::Integer(len), _]) => {
+                self.records.push(Record::Array(record_len(*len)?));
+            }
+            _ => return Err(Error::UnsupportedCallable(callable.name.clone())),
+        }
+        Ok(None)
+    }
+}
+
+fn record_len(len: i64) -> Result<usize, Error> {
+    usize::try_from(len).map_err(|_| Error::NegativeRecordLength(len))
+}
+
+fn type_mismatch(expected: &str, found: Literal) -> Error {
+    Error::TypeMismatch(expected.to_string(), found.to_string())
+}
@@ -0,0 +1,292 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{Error, parse, simulate};
+use crate::qir::ToQir;
+use indoc::indoc;
+use qsc_data_structures::target::TargetCapabilityFlags;
+use qsc_eval::{backend::SparseSim, val::Value};
+use qsc_rir::{
+    builder,
+    rir::{
+        Block, BlockId, Callable, CallableId, CallableType, Instruction, Literal, Operand, Program,
+        Ty, Variable, VariableId,
+    },
+};
+
+fn qubit(id: u32) -> Operand {
+    Operand::Literal(Literal::Qubit(id))
+}
+
+fn result(id: u32) -> Operand {
+    Operand::Literal(Literal::Result(id))
+}
+
+#[test]
+fn generated_qir_round_trips() {
+    let mut program = Program::default();
+    program.callables.insert(
+        CallableId(0),
+        Callable {
+            name: "main".to_string(),
+            input_type: Vec::new(),
+            output_type: Some(Ty::Integer),
+            body: Some(BlockId(0)),
+            call_type: CallableType::Regular,
+        },
+    );
+    program
+        .callables
+        .insert(CallableId(1), builder::initialize_decl());
+    program.callables.insert(CallableId(2), builder::h_decl());
+    program.callables.insert(CallableId(3), builder::cx_decl());
+    program
+        .callables
+        .insert(CallableId(4), builder::mresetz_decl());
+    program
+        .callables
+        .insert(CallableId(5), builder::read_result_decl());
+    program.callables.insert(CallableId(6), builder::x_decl());
+    program
+        .callables
+        .insert(CallableId(7), builder::result_record_decl());
+    let cond = Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Boolean,
+    };
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Pointer)],
+                None,
+            ),
+            Instruction::Call(CallableId(2), vec![qubit(0)], None),
+            Instruction::Call(CallableId(3), vec![qubit(0), qubit(1)], None),
+            Instruction::Call(CallableId(4), vec![qubit(0), result(0)], None),
+            Instruction::Call(CallableId(5), vec![result(0)], Some(cond)),
+            Instruction::Branch(cond, BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(CallableId(6), vec![qubit(1)], None),
+            Instruction::Jump(BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Call(
+                CallableId(7),
+                vec![result(0), Operand::Literal(Literal::Tag(0, 3))],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+    program.tags.push("0_r".to_string());
+    program.num_qubits = 2;
+    program.num_results = 1;
+    program.config.capabilities =
+        TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::IntegerComputations;
+
+    let qir = ToQir::<String>::to_qir(&program, &program);
+    let parsed = parse(&qir).expect("generated QIR should parse");
+
+    assert_eq!(ToQir::<String>::to_qir(&parsed, &parsed), qir);
+}
+
+#[test]
+fn qir_from_other_tools_simulates() {
+    let program = parse(indoc! {r#"
+        %Qubit = type opaque
+        %Result = type opaque
+
+        define void @main() #0 {
+        entry:
+          call void @__quantum__qis__h__body(%Qubit* null)
+          call void @__quantum__qis__cx__body(%Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*))
+          call void @__quantum__qis__mz__body(%Qubit* null, %Result* null)
+          call void @__quantum__qis__mz__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+          call void @__quantum__rt__array_record_output(i64 2, i8* null)
+          call void @__quantum__rt__result_record_output(%Result* null, i8* null)
+          call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+          ret void
+        }
+
+        declare void @__quantum__qis__h__body(%Qubit*)
+
+        declare void @__quantum__qis__cx__body(%Qubit*, %Qubit*)
+
+        declare void @__quantum__qis__mz__body(%Qubit*, %Result*) #1
+
+        declare void @__quantum__rt__array_record_output(i64, i8*)
+
+        declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+        attributes #0 = { "entry_point" "required_num_qubits"="2" "required_num_results"="2" }
+        attributes #1 = { "irreversible" }
+    "#})
+    .expect("QIR should parse");
+
+    for _ in 0..10 {
+        let Value::Array(results) =
+            simulate(&program, &mut SparseSim::new()).expect("simulation should succeed")
+        else {
+            panic!("output should be an array");
+        };
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], results[1]);
+    }
+}
+
+#[test]
+fn classical_computation_on_measurement_results_simulates() {
+    let program = parse(indoc! {r#"
+        define i64 @ENTRYPOINT__main() #0 {
+        block_0:
+          call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+          call void @__quantum__qis__mresetz__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+          %var_0 = call i1 @__quantum__rt__read_result(%Result* inttoptr (i64 0 to %Result*))
+          br i1 %var_0, label %block_1, label %block_2
+        block_1:
+          %var_1 = add i64 3, 4
+          br label %block_3
+        block_2:
+          br label %block_3
+        block_3:
+          %var_2 = phi i64 [%var_1, %block_1], [0, %block_2]
+          call void @__quantum__rt__int_record_output(i64 %var_2, i8* null)
+          ret i64 0
+        }
+
+        attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="adaptive_profile" "required_num_qubits"="1" "required_num_results"="1" }
+    "#})
+    .expect("QIR should parse");
+
+    assert_eq!(simulate(&program, &mut SparseSim::new()), Ok(Value::Int(7)));
+}
+
+#[test]
+fn unsupported_instruction_fails_with_line() {
+    let qir = indoc! {"
+        define void @main() #0 {
+        entry:
+          %x = alloca i64
+          ret void
+        }
+    "};
+
+    assert_eq!(
+        parse(qir).map(|_| ()),
+        Err(Error::Unsupported(3, "%x = alloca i64".to_string()))
+    );
+}
+
+fn simulate_qir(body: &str) -> Result<Value, Error> {
+    let qir = format!("define i64 @ENTRYPOINT__main() #0 {{\n{body}\n}}\n");
+    let program = parse(&qir).expect("QIR should parse");
+    simulate(&program, &mut SparseSim::new())
+}
+
+#[test]
+fn qubit_count_is_derived_without_attribute() {
+    let program = parse(indoc! {"
+        define void @main() #0 {
+        entry:
+          call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 2 to %Qubit*))
+          ret void
+        }
+
+        declare void @__quantum__qis__x__body(%Qubit*)
+
+        attributes #0 = { \"entry_point\" }
+    "})
+    .expect("QIR should parse");
+
+    assert_eq!(program.num_qubits, 3);
+    assert_eq!(simulate(&program, &mut SparseSim::new()), Ok(Value::unit()));
+}
+
+#[test]
+fn negative_record_length_fails() {
+    assert_eq!(
+        simulate_qir(indoc! {"
+            block_0:
+              call void @__quantum__rt__array_record_output(i64 -1, i8* null)
+              ret i64 0
+        "}),
+        Err(Error::NegativeRecordLength(-1))
+    );
+}
+
+#[test]
+fn record_without_items_fails() {
+    assert_eq!(
+        simulate_qir(indoc! {"
+            block_0:
+              call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
+              call void @__quantum__rt__int_record_output(i64 1, i8* null)
+              ret i64 0
+        "}),
+        Err(Error::IncompleteRecord)
+    );
+}
+
+#[test]
+fn operand_of_wrong_type_fails() {
+    assert_eq!(
+        simulate_qir(indoc! {"
+            block_0:
+              %var_0 = icmp eq i64 1, 1
+              %var_1 = add i64 %var_0, 1
+              ret i64 0
+        "}),
+        Err(Error::TypeMismatch(
+            "Integer".to_string(),
+            "Bool(true)".to_string()
+        ))
+    );
+}
+
+#[test]
+fn unassigned_variable_fails() {
+    assert_eq!(
+        simulate_qir(indoc! {"
+            block_0:
+              %var_1 = add i64 %var_0, 1
+              ret i64 0
+        "}),
+        Err(Error::UnassignedVariable(0))
+    );
+}
+
+#[test]
+fn block_without_terminator_fails() {
+    assert_eq!(
+        simulate_qir(indoc! {"
+            block_0:
+              %var_0 = add i64 1, 1
+        "}),
+        Err(Error::MissingTerminator(0))
+    );
+}
+
+#[test]
+fn phi_without_value_for_predecessor_fails() {
+    assert_eq!(
+        simulate_qir(indoc! {"
+            block_0:
+              br label %block_1
+            block_1:
+              %var_0 = phi i64 [1, %block_2]
+              ret i64 0
+            block_2:
+              br label %block_1
+        "}),
+        Err(Error::MissingPhiPredecessor(0, 0))
+    );
+}
@@ -510,6 +510,16 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
                     self.visit_expr(expr);
                 }
             }
+            ExprKind::Match(scrutinee, arms) => {
+                self.write("match ");
+                self.visit_expr(scrutinee);
+                self.writeln(" {");
+                for arm in arms.as_ref() {
+                    self.visit_match_arm(arm);
+                    self.writeln(",");
+                }
+                self.write("}");
+            }
             ExprKind::Index(array, index) => {
                 self.visit_expr(array);
                 self.write("[");
@@ -736,6 +746,12 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
         self.visit_expr(&assign.value);
     }
 
+    fn visit_match_arm(&mut self, arm: &'_ ast::MatchArm) {
+        self.visit_expr(&arm.pat);
+        self.write(" => ");
+        self.visit_expr(&arm.body);
+    }
+
     fn visit_pat(&mut self, pat: &'_ Pat) {
         match &*pat.kind {
             PatKind::Bind(name, ty) => {
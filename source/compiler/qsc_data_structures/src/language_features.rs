@@ -10,6 +10,8 @@ pub struct LanguageFeatures(u8);
 bitflags! {
     impl LanguageFeatures: u8 {
         const V2PreviewSyntax = 0b1;
+        const ScriptMode = 0b10;
+        const MatchExpressions = 0b100;
     }
 }
 
@@ -33,6 +35,8 @@ where
         iter.into_iter().fold(LanguageFeatures::empty(), |acc, x| {
             acc | match x.as_ref() {
                 "v2-preview-syntax" => LanguageFeatures::V2PreviewSyntax,
+                "script-mode" => LanguageFeatures::ScriptMode,
+                "match-expressions" => LanguageFeatures::MatchExpressions,
                 _ => LanguageFeatures::empty(),
             }
         })
@@ -45,6 +49,12 @@ impl From<LanguageFeatures> for Vec<String> {
         if features.contains(LanguageFeatures::V2PreviewSyntax) {
             result.push("v2-preview-syntax".to_string());
         }
+        if features.contains(LanguageFeatures::ScriptMode) {
+            result.push("script-mode".to_string());
+        }
+        if features.contains(LanguageFeatures::MatchExpressions) {
+            result.push("match-expressions".to_string());
+        }
         result
     }
 }
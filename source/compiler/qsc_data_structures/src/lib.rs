@@ -10,4 +10,5 @@ pub mod line_column;
 pub mod namespaces;
 pub mod source;
 pub mod span;
+pub mod sync_cell;
 pub mod target;
@@ -4,9 +4,10 @@
 #[cfg(test)]
 mod tests;
 
+use crate::sync_cell::SyncCell;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::fmt::Write;
-use std::{cell::RefCell, collections::BTreeMap, fmt::Display, iter::Peekable, ops::Deref, rc::Rc};
+use std::{collections::BTreeMap, fmt::Display, iter::Peekable, ops::Deref, sync::Arc};
 
 pub const PRELUDE: [[&str; 2]; 5] = [
     ["Std", "Canon"],
@@ -59,11 +60,12 @@ impl Display for NamespaceId {
 }
 
 /// A reference counted cell that supports interior mutability for namespace tree nodes.
-/// Interior mutability is required to update the tree when inserting new data structures.
-type NamespaceTreeCell = Rc<RefCell<NamespaceTreeNode>>;
+/// Interior mutability is required to update the tree when inserting new data structures. The cell
+/// is thread safe so that a tree in a compiled package can be read from several threads.
+type NamespaceTreeCell = Arc<SyncCell<NamespaceTreeNode>>;
 
 /// An entry in the memoization table for namespace ID lookups.
-type MemoEntry = (Vec<Rc<str>>, NamespaceTreeCell);
+type MemoEntry = (Vec<Arc<str>>, NamespaceTreeCell);
 
 /// Denotes that a namespace from an external package has been overridden by a local package namespace.
 /// This renders the contents of the foreign namespace unaccessible.
@@ -89,7 +91,7 @@ pub struct ClobberedNamespace;
 pub struct NamespaceTreeRoot {
     assigner: usize,
     tree: NamespaceTreeCell,
-    memo: RefCell<FxHashMap<NamespaceId, MemoEntry>>,
+    memo: SyncCell<FxHashMap<NamespaceId, MemoEntry>>,
 }
 
 impl std::fmt::Debug for NamespaceTreeRoot {
@@ -116,8 +118,8 @@ impl NamespaceTreeRoot {
     pub fn new_from_parts(assigner: usize, tree: NamespaceTreeNode) -> Self {
         Self {
             assigner,
-            tree: Rc::new(RefCell::new(tree)),
-            memo: RefCell::new(FxHashMap::default()),
+            tree: Arc::new(SyncCell::new(tree)),
+            memo: SyncCell::new(FxHashMap::default()),
         }
     }
 
@@ -132,7 +134,7 @@ impl NamespaceTreeRoot {
     #[must_use]
     pub fn insert_or_find_namespace(
         &mut self,
-        ns: impl IntoIterator<Item = Rc<str>>,
+        ns: impl IntoIterator<Item = Arc<str>>,
     ) -> NamespaceId {
         self.tree
             .borrow_mut()
@@ -152,7 +154,7 @@ impl NamespaceTreeRoot {
     /// particularly efficient, as it performs a breadth-first search. The results of this search
     /// are memoized to avoid repeated lookups, reducing the impact of the BFS.
     #[must_use]
-    pub fn find_namespace_by_id(&self, id: &NamespaceId) -> (Vec<Rc<str>>, NamespaceTreeCell) {
+    pub fn find_namespace_by_id(&self, id: &NamespaceId) -> (Vec<Arc<str>>, NamespaceTreeCell) {
         if let Some(res) = self.memo.borrow().get(id) {
             return res.clone();
         }
@@ -183,7 +185,7 @@ impl NamespaceTreeRoot {
         let parent = parent.unwrap_or_else(|| self.root_id());
         let (_, parent_node) = self.find_namespace_by_id(&parent);
         let (_, existing_ns) = self.find_namespace_by_id(&new_child);
-        if let Some(val) = parent_node.borrow().children.get(&Rc::from(alias))
+        if let Some(val) = parent_node.borrow().children.get(&Arc::from(alias))
             && val.borrow().id != existing_ns.borrow().id
         {
             return Err(ClobberedNamespace);
@@ -192,7 +194,7 @@ impl NamespaceTreeRoot {
         parent_node
             .borrow_mut()
             .children
-            .insert(Rc::from(alias), existing_ns);
+            .insert(Arc::from(alias), existing_ns);
 
         Ok(())
     }
@@ -201,7 +203,7 @@ impl NamespaceTreeRoot {
     /// Primarily used for appending namespaces to a parent namespace which represents a module/external package..
     pub fn insert_or_find_namespace_from_root(
         &mut self,
-        ns: impl Into<Vec<Rc<str>>>,
+        ns: impl Into<Vec<Arc<str>>>,
         root: NamespaceId,
     ) -> NamespaceId {
         let ns = ns.into();
@@ -218,7 +220,7 @@ impl NamespaceTreeRoot {
 
     pub fn insert_or_find_namespace_from_root_with_id(
         &mut self,
-        mut ns: Vec<Rc<str>>,
+        mut ns: Vec<Arc<str>>,
         root: NamespaceId,
         base_id: NamespaceId,
     ) -> Result<(), ClobberedNamespace> {
@@ -245,11 +247,11 @@ impl NamespaceTreeRoot {
     }
 
     /// Each item in this iterator is the same, single namespace. The reason there are multiple paths for it,
-    /// each represented by a `Vec<Rc<str>>`, is because there may be multiple paths to the same
+    /// each represented by a `Vec<Arc<str>>`, is because there may be multiple paths to the same
     /// namespace, through aliasing or re-exports.
-    pub fn iter(&self) -> std::collections::btree_map::IntoValues<NamespaceId, Vec<Vec<Rc<str>>>> {
+    pub fn iter(&self) -> std::collections::btree_map::IntoValues<NamespaceId, Vec<Vec<Arc<str>>>> {
         let mut stack = vec![(vec![], self.tree.clone())];
-        let mut result: Vec<(NamespaceId, Vec<Rc<str>>)> = vec![];
+        let mut result: Vec<(NamespaceId, Vec<Arc<str>>)> = vec![];
         while let Some((names, node)) = stack.pop() {
             result.push((node.borrow().id, names.clone()));
             for (name, child) in node.borrow().children() {
@@ -278,8 +280,8 @@ impl NamespaceTreeRoot {
 }
 
 impl IntoIterator for &NamespaceTreeRoot {
-    type Item = Vec<Vec<Rc<str>>>;
-    type IntoIter = std::collections::btree_map::IntoValues<NamespaceId, Vec<Vec<Rc<str>>>>;
+    type Item = Vec<Vec<Arc<str>>>;
+    type IntoIter = std::collections::btree_map::IntoValues<NamespaceId, Vec<Vec<Arc<str>>>>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
@@ -290,11 +292,11 @@ impl Default for NamespaceTreeRoot {
     fn default() -> Self {
         Self {
             assigner: 0,
-            tree: Rc::new(RefCell::new(NamespaceTreeNode {
+            tree: Arc::new(SyncCell::new(NamespaceTreeNode {
                 children: FxHashMap::default(),
                 id: NamespaceId::new(0),
             })),
-            memo: RefCell::new(FxHashMap::default()),
+            memo: SyncCell::new(FxHashMap::default()),
         }
     }
 }
@@ -303,25 +305,25 @@ impl Default for NamespaceTreeRoot {
 /// Supports interior mutability of children for inserting new nodes.
 #[derive(Clone)]
 pub struct NamespaceTreeNode {
-    pub children: FxHashMap<Rc<str>, NamespaceTreeCell>,
+    pub children: FxHashMap<Arc<str>, NamespaceTreeCell>,
     pub id: NamespaceId,
 }
 
 impl NamespaceTreeNode {
     /// Create a new namespace tree node with the given ID and children. The `id` should come from the `NamespaceTreeRoot` assigner.
     #[must_use]
-    fn new(id: NamespaceId, children: FxHashMap<Rc<str>, NamespaceTreeCell>) -> Self {
+    fn new(id: NamespaceId, children: FxHashMap<Arc<str>, NamespaceTreeCell>) -> Self {
         Self { children, id }
     }
 
     /// Get a reference to the children of the namespace tree node.
     #[must_use]
-    pub fn children(&self) -> &FxHashMap<Rc<str>, NamespaceTreeCell> {
+    pub fn children(&self) -> &FxHashMap<Arc<str>, NamespaceTreeCell> {
         &self.children
     }
 
     /// See [`FxHashMap::get`] for more information.
-    fn get(&self, component: &Rc<str>) -> Option<NamespaceTreeCell> {
+    fn get(&self, component: &Arc<str>) -> Option<NamespaceTreeCell> {
         self.children.get(component).cloned()
     }
 
@@ -347,8 +349,8 @@ impl NamespaceTreeNode {
         let mut rover: Option<NamespaceTreeCell> = None;
         for component in ns {
             if let Some(next_ns) = match rover {
-                None => self.get(&Rc::from(component)),
-                Some(buf) => buf.borrow().get(&Rc::from(component)),
+                None => self.get(&Arc::from(component)),
+                Some(buf) => buf.borrow().get(&Arc::from(component)),
             } {
                 rover = Some(next_ns);
             } else {
@@ -367,7 +369,7 @@ impl NamespaceTreeNode {
         assigner: &mut usize,
     ) -> Option<NamespaceId>
     where
-        I: Iterator<Item = Rc<str>>,
+        I: Iterator<Item = Arc<str>>,
     {
         let next_item = iter.next()?;
         let next_node = self.children.get_mut(&next_item);
@@ -388,12 +390,12 @@ impl NamespaceTreeNode {
         if iter.peek().is_none() {
             let new_node_id = new_node.id;
             self.children
-                .insert(next_item, Rc::new(RefCell::new(new_node)));
+                .insert(next_item, Arc::new(SyncCell::new(new_node)));
             Some(new_node_id)
         } else {
             let id = new_node.insert_or_find_namespace(iter, assigner);
             self.children
-                .insert(next_item, Rc::new(RefCell::new(new_node)));
+                .insert(next_item, Arc::new(SyncCell::new(new_node)));
             id
         }
     }
@@ -401,10 +403,10 @@ impl NamespaceTreeNode {
     fn find_namespace_by_id(
         &self,
         id: NamespaceId,
-        names_buf: &[Rc<str>],
+        names_buf: &[Arc<str>],
         // `ids_visited` is used to avoid infinite loops in the case of cycles in the namespace tree.
         ids_visited: &mut FxHashSet<NamespaceId>,
-    ) -> Option<(Vec<Rc<str>>, NamespaceTreeCell)> {
+    ) -> Option<(Vec<Arc<str>>, NamespaceTreeCell)> {
         if ids_visited.contains(&self.id) {
             return None;
         }
@@ -15,7 +15,7 @@ fn test_tree_construction() {
     for i in 0..3 {
         for j in 'a'..'d' {
             let _ = root.insert_or_find_namespace(
-                vec![Rc::from(format!("ns{i}")), Rc::from(format!("ns{j}"))].into_iter(),
+                vec![Arc::from(format!("ns{i}")), Arc::from(format!("ns{j}"))].into_iter(),
             );
         }
     }
@@ -58,10 +58,10 @@ fn test_find_id() {
     for i in 0..3 {
         for j in 'a'..'d' {
             id_buf.push(root.insert_or_find_namespace(
-                vec![Rc::from(format!("ns{i}")), Rc::from(format!("ns{j}"))].into_iter(),
+                vec![Arc::from(format!("ns{i}")), Arc::from(format!("ns{j}"))].into_iter(),
             ));
         }
-        id_buf.push(root.insert_or_find_namespace(vec![Rc::from(format!("ns{i}"))].into_iter()));
+        id_buf.push(root.insert_or_find_namespace(vec![Arc::from(format!("ns{i}"))].into_iter()));
     }
     let mut result_buf = vec![];
     for id in id_buf {
@@ -74,7 +74,7 @@ fn test_find_id() {
                     "ns0",
                     "nsa",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -83,7 +83,7 @@ fn test_find_id() {
                     "ns0",
                     "nsb",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -92,7 +92,7 @@ fn test_find_id() {
                     "ns0",
                     "nsc",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -100,7 +100,7 @@ fn test_find_id() {
                 [
                     "ns0",
                 ],
-                RefCell {
+                SyncCell {
                     value: 
                       children: [
                         nsc(id 4) {empty node},
@@ -115,7 +115,7 @@ fn test_find_id() {
                     "ns1",
                     "nsa",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -124,7 +124,7 @@ fn test_find_id() {
                     "ns1",
                     "nsb",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -133,7 +133,7 @@ fn test_find_id() {
                     "ns1",
                     "nsc",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -141,7 +141,7 @@ fn test_find_id() {
                 [
                     "ns1",
                 ],
-                RefCell {
+                SyncCell {
                     value: 
                       children: [
                         nsc(id 8) {empty node},
@@ -156,7 +156,7 @@ fn test_find_id() {
                     "ns2",
                     "nsa",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -165,7 +165,7 @@ fn test_find_id() {
                     "ns2",
                     "nsb",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -174,7 +174,7 @@ fn test_find_id() {
                     "ns2",
                     "nsc",
                 ],
-                RefCell {
+                SyncCell {
                     value: empty node},
                 },
             ),
@@ -182,7 +182,7 @@ fn test_find_id() {
                 [
                     "ns2",
                 ],
-                RefCell {
+                SyncCell {
                     value: 
                       children: [
                         nsc(id 12) {empty node},
@@ -204,7 +204,7 @@ fn test_insert_or_find_namespace() {
     for i in 0..3 {
         for j in 'a'..'d' {
             let id = root.insert_or_find_namespace(
-                vec![Rc::from(format!("ns{i}")), Rc::from(format!("ns{j}"))].into_iter(),
+                vec![Arc::from(format!("ns{i}")), Arc::from(format!("ns{j}"))].into_iter(),
             );
             ids.push(id.into());
         }
@@ -237,11 +237,11 @@ fn test_get_namespace_id() {
     let mut names_to_query_buf = vec![];
     for i in 0..3 {
         for j in 'a'..'d' {
-            let name = vec![Rc::from(format!("ns{i}")), Rc::from(format!("ns{j}"))];
+            let name = vec![Arc::from(format!("ns{i}")), Arc::from(format!("ns{j}"))];
             let _ = root.insert_or_find_namespace(name.clone());
             names_to_query_buf.push(name);
         }
-        let name = vec![Rc::from(format!("ns{i}"))];
+        let name = vec![Arc::from(format!("ns{i}"))];
         let _ = root.insert_or_find_namespace(name.clone());
         names_to_query_buf.push(name);
     }
@@ -323,7 +323,7 @@ fn test_tree_iter() {
     for i in 0..3 {
         for j in 'a'..'d' {
             let _ = root.insert_or_find_namespace(
-                vec![Rc::from(format!("ns{i}")), Rc::from(format!("ns{j}"))].into_iter(),
+                vec![Arc::from(format!("ns{i}")), Arc::from(format!("ns{j}"))].into_iter(),
             );
         }
     }
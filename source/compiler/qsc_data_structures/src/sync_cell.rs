@@ -0,0 +1,123 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug, Formatter},
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError},
+};
+
+/// A mutable memory location with dynamically checked borrow rules, like [`std::cell::RefCell`],
+/// that can be shared between threads.
+///
+/// Borrowing follows the same rules as `RefCell`: any number of shared borrows or a single mutable
+/// borrow, with a panic instead of a deadlock when a borrow conflicts. Values that are only read
+/// after they are built, such as the types in a compiled package, can be read from several threads
+/// at once.
+#[derive(Default)]
+pub struct SyncCell<T> {
+    value: RwLock<T>,
+}
+
+impl<T> SyncCell<T> {
+    /// Creates a new cell containing `value`.
+    #[must_use]
+    pub const fn new(value: T) -> Self {
+        Self {
+            value: RwLock::new(value),
+        }
+    }
+
+    /// Immutably borrows the wrapped value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is currently mutably borrowed.
+    pub fn borrow(&self) -> RwLockReadGuard<'_, T> {
+        match self.value.try_read() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(TryLockError::WouldBlock) => panic!("value should not be mutably borrowed"),
+        }
+    }
+
+    /// Mutably borrows the wrapped value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is currently borrowed.
+    pub fn borrow_mut(&self) -> RwLockWriteGuard<'_, T> {
+        self.try_borrow_mut().expect("value should not be borrowed")
+    }
+
+    /// Mutably borrows the wrapped value, returning an error if the value is currently borrowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BorrowMutError`] if the value is currently borrowed.
+    pub fn try_borrow_mut(&self) -> Result<RwLockWriteGuard<'_, T>, BorrowMutError> {
+        match self.value.try_write() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Ok(err.into_inner()),
+            Err(TryLockError::WouldBlock) => Err(BorrowMutError),
+        }
+    }
+
+    /// Replaces the wrapped value with a new one, returning the old value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is currently borrowed.
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut *self.borrow_mut(), value)
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.value
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// An error returned by [`SyncCell::try_borrow_mut`] when the value is already borrowed.
+#[derive(Debug)]
+pub struct BorrowMutError;
+
+impl<T: Clone> Clone for SyncCell<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.borrow().clone())
+    }
+}
+
+impl<T: Debug> Debug for SyncCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncCell")
+            .field("value", &*self.borrow())
+            .finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SyncCell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        *self.borrow() == *other.borrow()
+    }
+}
+
+impl<T: Eq> Eq for SyncCell<T> {}
+
+impl<T: PartialOrd> PartialOrd for SyncCell<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.borrow().partial_cmp(&*other.borrow())
+    }
+}
+
+impl<T: Ord> Ord for SyncCell<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.borrow().cmp(&*other.borrow())
+    }
+}
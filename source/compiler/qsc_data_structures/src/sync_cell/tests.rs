@@ -0,0 +1,72 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::SyncCell;
+use expect_test::expect;
+use std::{sync::Arc, thread};
+
+#[test]
+fn borrow_mut_updates_value() {
+    let cell = SyncCell::new(1);
+    *cell.borrow_mut() += 1;
+    assert_eq!(*cell.borrow(), 2);
+    assert_eq!(cell.replace(5), 2);
+    assert_eq!(cell.into_inner(), 5);
+}
+
+#[test]
+fn shared_borrows_can_overlap() {
+    let cell = SyncCell::new(vec![1, 2]);
+    let first = cell.borrow();
+    let second = cell.borrow();
+    assert_eq!(*first, *second);
+}
+
+#[test]
+#[should_panic(expected = "value should not be mutably borrowed")]
+fn borrow_while_mutably_borrowed_panics() {
+    let cell = SyncCell::new(0);
+    let _guard = cell.borrow_mut();
+    drop(cell.borrow());
+}
+
+#[test]
+#[should_panic(expected = "value should not be borrowed")]
+fn borrow_mut_while_borrowed_panics() {
+    let cell = SyncCell::new(0);
+    let _guard = cell.borrow();
+    drop(cell.borrow_mut());
+}
+
+#[test]
+fn try_borrow_mut_while_borrowed_fails() {
+    let cell = SyncCell::new(0);
+    let guard = cell.borrow();
+    assert!(cell.try_borrow_mut().is_err());
+    drop(guard);
+    assert!(cell.try_borrow_mut().is_ok());
+}
+
+#[test]
+fn cell_can_be_read_from_other_threads() {
+    let cell = Arc::new(SyncCell::new(String::from("shared")));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let cell = Arc::clone(&cell);
+            thread::spawn(move || cell.borrow().len())
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().expect("thread should not panic"), 6);
+    }
+}
+
+#[test]
+fn debug_shows_value() {
+    expect![[r#"
+        SyncCell {
+            value: "a",
+        }
+    "#]]
+    .assert_debug_eq(&SyncCell::new("a"));
+}
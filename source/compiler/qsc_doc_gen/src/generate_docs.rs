@@ -18,23 +18,22 @@ use rustc_hash::FxHashMap;
 use std::collections::BTreeMap;
 use std::fmt::Write;
 use std::fmt::{Display, Formatter, Result};
-use std::rc::Rc;
 use std::sync::Arc;
 
 // Name, Metadata, Content
-type Files = Vec<(Rc<str>, Rc<str>, Rc<str>)>;
-type FilesWithMetadata = Vec<(Rc<str>, Rc<Metadata>, Rc<str>)>;
+type Files = Vec<(Arc<str>, Arc<str>, Arc<str>)>;
+type FilesWithMetadata = Vec<(Arc<str>, Arc<Metadata>, Arc<str>)>;
 
 // Namespace -> metadata for items
-type ToC = FxHashMap<Rc<str>, Vec<Rc<Metadata>>>;
+type ToC = FxHashMap<Arc<str>, Vec<Arc<Metadata>>>;
 
 struct Metadata {
     uid: String,
     title: String,
     kind: MetadataKind,
     package: PackageKind,
-    namespace: Rc<str>,
-    name: Rc<str>,
+    namespace: Arc<str>,
+    name: Arc<str>,
     summary: String,
     signature: String,
 }
@@ -377,7 +376,7 @@ pub fn generate_docs(
 
     let mut result: Files = files
         .into_iter()
-        .map(|(name, metadata, content)| (name, Rc::from(metadata.to_string().as_str()), content))
+        .map(|(name, metadata, content)| (name, Arc::from(metadata.to_string().as_str()), content))
         .collect();
 
     generate_toc(&mut toc, &mut result);
@@ -393,7 +392,7 @@ fn generate_doc_for_item<'a>(
     item: &'a Item,
     display: &'a CodeDisplay,
     files: &mut FilesWithMetadata,
-) -> Option<(Rc<str>, Rc<Metadata>)> {
+) -> Option<(Arc<str>, Arc<Metadata>)> {
     let (true_package, true_item) = resolve_export(
         default_package_id,
         package,
@@ -419,9 +418,9 @@ fn generate_doc_for_item<'a>(
     } else {
         generate_file_content(package_kind, &ns, item, display)?
     };
-    let file_name = Rc::from(format!("{ns}/{}.md", metadata.name).as_str());
-    let file_content = Rc::from(content.as_str());
-    let met = Rc::from(metadata);
+    let file_name = Arc::from(format!("{ns}/{}.md", metadata.name).as_str());
+    let file_content = Arc::from(content.as_str());
+    let met = Arc::from(metadata);
     files.push((file_name, met.clone(), file_content));
 
     Some((ns.clone(), met))
@@ -429,7 +428,7 @@ fn generate_doc_for_item<'a>(
 
 fn generate_file_content(
     package_kind: PackageKind,
-    ns: &Rc<str>,
+    ns: &Arc<str>,
     item: &Item,
     display: &CodeDisplay,
 ) -> Option<(Metadata, String)> {
@@ -463,10 +462,10 @@ Fully qualified name: {fqn}
 #[allow(clippy::assigning_clones)]
 fn generate_exported_file_content(
     package_kind: PackageKind,
-    ns: &Rc<str>,
+    ns: &Arc<str>,
     item: &Item,
     display: &CodeDisplay,
-    true_ns: &Rc<str>,
+    true_ns: &Arc<str>,
     true_item: &Item,
 ) -> Option<(Metadata, String)> {
     let mut metadata = get_metadata(package_kind.clone(), ns.clone(), item, display)?;
@@ -503,7 +502,11 @@ Fully qualified name: {fqn}
     Some((metadata, content))
 }
 
-fn generate_index_file(files: &mut FilesWithMetadata, ns: &Rc<str>, items: &mut Vec<Rc<Metadata>>) {
+fn generate_index_file(
+    files: &mut FilesWithMetadata,
+    ns: &Arc<str>,
+    items: &mut Vec<Arc<Metadata>>,
+) {
     if items.is_empty() {
         return;
     }
@@ -553,16 +556,16 @@ The {ns} namespace contains the following items:
 ",
     );
 
-    let rc_met = Rc::from(metadata);
-    items.insert(0, rc_met.clone());
+    let met = Arc::from(metadata);
+    items.insert(0, met.clone());
 
-    let file_name = Rc::from(format!("{ns}/index.md").as_str());
-    let file_content = Rc::from(content.as_str());
-    files.push((file_name, rc_met, file_content));
+    let file_name = Arc::from(format!("{ns}/index.md").as_str());
+    let file_content = Arc::from(content.as_str());
+    files.push((file_name, met, file_content));
 }
 
 fn generate_top_index(files: &mut FilesWithMetadata, toc: &mut ToC) {
-    let empty_ns: Rc<str> = Rc::from(String::new().as_str());
+    let empty_ns: Arc<str> = Arc::from(String::new().as_str());
     let metadata = Metadata {
         uid: "Microsoft.Quantum.apiref-toc".to_string(),
         title: "Q# standard libraries for the Azure Quantum Development Kit".to_string(),
@@ -578,7 +581,11 @@ fn generate_top_index(files: &mut FilesWithMetadata, toc: &mut ToC) {
 
     let contents = table_of_contents();
 
-    files.push((Rc::from("index.md"), Rc::from(metadata), Rc::from(contents)));
+    files.push((
+        Arc::from("index.md"),
+        Arc::from(metadata),
+        Arc::from(contents),
+    ));
 
     toc.insert(empty_ns, vec![]);
 }
@@ -626,13 +633,13 @@ fn generate_toc(map: &mut ToC, files: &mut Files) {
     let content = format!("{header}\n{table}");
     let content = content.as_str();
 
-    let file_name = Rc::from("toc.yml");
-    let file_metadata = Rc::from("");
-    let file_content = Rc::from(content);
+    let file_name = Arc::from("toc.yml");
+    let file_metadata = Arc::from("");
+    let file_content = Arc::from(content);
     files.push((file_name, file_metadata, file_content));
 }
 
-fn get_namespace(package: &Package, item: &Item) -> Option<Rc<str>> {
+fn get_namespace(package: &Package, item: &Item) -> Option<Arc<str>> {
     let local_id = item.parent?;
     let parent = package
         .items
@@ -687,7 +694,7 @@ fn resolve_export<'a>(
 
 fn get_metadata(
     package_kind: PackageKind,
-    ns: Rc<str>,
+    ns: Arc<str>,
     item: &Item,
     display: &CodeDisplay,
 ) -> Option<Metadata> {
@@ -732,7 +739,7 @@ fn get_metadata(
 
 /// Generates summary documentation organized by namespace.
 /// Returns a map of namespace -> metadata items for easier testing and manipulation.
-fn generate_summaries_map() -> BTreeMap<String, Vec<Rc<Metadata>>> {
+fn generate_summaries_map() -> BTreeMap<String, Vec<Arc<Metadata>>> {
     let compilation = Compilation::new(None, None, None);
 
     // Use the shared logic to build ToC structure
@@ -773,7 +780,7 @@ fn metadata_to_markdown(item: &Metadata) -> String {
 }
 
 /// Generates markdown summary for a single namespace
-fn generate_namespace_summary(namespace: &str, items: &[Rc<Metadata>]) -> String {
+fn generate_namespace_summary(namespace: &str, items: &[Arc<Metadata>]) -> String {
     let mut result = format!("# {namespace}\n\n");
 
     for item in items {
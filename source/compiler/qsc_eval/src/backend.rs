@@ -3,17 +3,30 @@
 
 use crate::debug::Frame;
 use crate::val::{self, Value};
-use crate::{noise::PauliNoise, val::unwrap_tuple};
-use ndarray::Array2;
+use crate::{
+    noise::{NoiseConfig, PauliNoise},
+    val::unwrap_tuple,
+};
+use ndarray::{Array1, Array2};
 use num_bigint::BigUint;
 use num_complex::Complex;
 use num_traits::Zero;
 use qdk_simulators::QuantumSim;
 use rand::{Rng, RngCore};
 use rand::{SeedableRng, rngs::StdRng};
+use rustc_hash::FxHashMap;
+use std::collections::BTreeSet;
+
+mod dense;
+
+pub use dense::{DenseSim, DoubleDouble, Precision};
 
 #[cfg(test)]
 mod noise_tests;
+#[cfg(test)]
+mod reference_tests;
+#[cfg(test)]
+mod tests;
 
 /// The trait that must be implemented by a quantum backend, whose functions will be invoked when
 /// quantum intrinsics are called.
@@ -115,6 +128,101 @@ pub trait Backend {
     fn set_seed(&mut self, _seed: Option<u64>) {}
 }
 
+/// Forwards to the boxed backend, so that hosts can select a backend at runtime
+/// (for example, `Box<dyn Backend>`) and still pass it anywhere a `Backend` is expected.
+impl<B: Backend + ?Sized> Backend for Box<B> {
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        (**self).ccx(ctl0, ctl1, q);
+    }
+    fn cx(&mut self, ctl: usize, q: usize) {
+        (**self).cx(ctl, q);
+    }
+    fn cy(&mut self, ctl: usize, q: usize) {
+        (**self).cy(ctl, q);
+    }
+    fn cz(&mut self, ctl: usize, q: usize) {
+        (**self).cz(ctl, q);
+    }
+    fn h(&mut self, q: usize) {
+        (**self).h(q);
+    }
+    fn m(&mut self, q: usize) -> val::Result {
+        (**self).m(q)
+    }
+    fn mresetz(&mut self, q: usize) -> val::Result {
+        (**self).mresetz(q)
+    }
+    fn reset(&mut self, q: usize) {
+        (**self).reset(q);
+    }
+    fn rx(&mut self, theta: f64, q: usize) {
+        (**self).rx(theta, q);
+    }
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        (**self).rxx(theta, q0, q1);
+    }
+    fn ry(&mut self, theta: f64, q: usize) {
+        (**self).ry(theta, q);
+    }
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        (**self).ryy(theta, q0, q1);
+    }
+    fn rz(&mut self, theta: f64, q: usize) {
+        (**self).rz(theta, q);
+    }
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        (**self).rzz(theta, q0, q1);
+    }
+    fn sadj(&mut self, q: usize) {
+        (**self).sadj(q);
+    }
+    fn s(&mut self, q: usize) {
+        (**self).s(q);
+    }
+    fn sx(&mut self, q: usize) {
+        (**self).sx(q);
+    }
+    fn swap(&mut self, q0: usize, q1: usize) {
+        (**self).swap(q0, q1);
+    }
+    fn tadj(&mut self, q: usize) {
+        (**self).tadj(q);
+    }
+    fn t(&mut self, q: usize) {
+        (**self).t(q);
+    }
+    fn x(&mut self, q: usize) {
+        (**self).x(q);
+    }
+    fn y(&mut self, q: usize) {
+        (**self).y(q);
+    }
+    fn z(&mut self, q: usize) {
+        (**self).z(q);
+    }
+    fn qubit_allocate(&mut self) -> usize {
+        (**self).qubit_allocate()
+    }
+    fn qubit_release(&mut self, q: usize) -> bool {
+        (**self).qubit_release(q)
+    }
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        (**self).qubit_swap_id(q0, q1);
+    }
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        (**self).capture_quantum_state()
+    }
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        (**self).qubit_is_zero(q)
+    }
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        (**self).custom_intrinsic(name, arg)
+    }
+    fn set_seed(&mut self, seed: Option<u64>) {
+        (**self).set_seed(seed);
+    }
+}
+
 /// Trait receiving trace events for quantum execution. Each method records
 /// an operation along with the current call stack when stack/source location
 /// tracing is enabled. If stack tracing is disabled, the stack parameter
@@ -136,6 +244,9 @@ pub trait Tracer {
     fn reset(&mut self, stack: &[Frame], q: usize);
     fn custom_intrinsic(&mut self, stack: &[Frame], name: &str, arg: Value);
     fn is_stack_tracing_enabled(&self) -> bool;
+    /// Receives the name of the variable a qubit is bound to, sent just before the qubit is
+    /// released, for qubits bound to a variable.
+    fn qubit_label(&mut self, _stack: &[Frame], _q: usize, _label: &str) {}
 }
 
 /// Backend wrapper that forwards execution to a concrete `Backend` while
@@ -169,6 +280,11 @@ impl<'a, B: Backend> TracingBackend<'a, B> {
         }
     }
 
+    #[must_use]
+    pub fn is_tracing(&self) -> bool {
+        self.tracer.is_some()
+    }
+
     #[must_use]
     pub fn is_stacks_enabled(&self) -> bool {
         if let Some(tracer) = &self.tracer {
@@ -411,6 +527,12 @@ impl<'a, B: Backend> TracingBackend<'a, B> {
         b
     }
 
+    pub fn qubit_label(&mut self, q: usize, label: &str, stack: &[Frame]) {
+        if let Some(tracer) = &mut self.tracer {
+            tracer.qubit_label(stack, q, label);
+        }
+    }
+
     pub fn qubit_swap_id(&mut self, q0: usize, q1: usize, stack: &[Frame]) {
         if let OptionalBackend::Some(backend) = &mut self.backend {
             backend.qubit_swap_id(q0, q1);
@@ -498,13 +620,61 @@ impl SequentialAllocator {
     }
 }
 
+/// The numeric tolerance used by diagnostic checks such as `CheckZero`. A qubit counts as being
+/// in the zero state when the probability of measuring `One` is at most
+/// `absolute + relative * norm`, where `norm` is the squared norm of the whole state. The relative
+/// term absorbs the drift in normalization that accumulates over long circuits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    /// The absolute tolerance on the probability.
+    pub absolute: f64,
+    /// The tolerance on the probability, relative to the squared norm of the state.
+    pub relative: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            absolute: 1e-10,
+            relative: 0.0,
+        }
+    }
+}
+
+impl Tolerance {
+    /// Returns true if the given probability is negligible for a state with the given squared norm.
+    #[must_use]
+    pub fn is_negligible(&self, probability: f64, norm: f64) -> bool {
+        probability <= self.absolute + self.relative * norm
+    }
+}
+
+/// Memory usage statistics collected by a simulator over its lifetime.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulatorStats {
+    /// The number of qubit allocations performed.
+    pub qubit_allocations: usize,
+    /// The number of qubits currently allocated.
+    pub qubits_in_use: usize,
+    /// The largest number of qubits allocated at the same time.
+    pub peak_qubits: usize,
+    /// An upper bound on the largest number of non-zero amplitudes stored for the state, updated
+    /// after each gate that can split amplitudes.
+    pub peak_state_size: usize,
+}
+
 /// Default backend used when targeting sparse simulation.
+#[derive(Clone)]
 pub struct SparseSim {
     /// Noiseless Sparse simulator to be used by this instance.
     pub sim: QuantumSim,
     /// Pauli noise that is applied after a gate or before a measurement is executed.
     /// Service functions aren't subject to noise.
     pub noise: PauliNoise,
+    /// Pauli noise for specific gates, keyed by gate name, used instead of `noise`.
+    pub gate_noise: FxHashMap<String, PauliNoise>,
+    /// Probability that a measurement reports the opposite of the measured result.
+    pub readout_error: f64,
     /// Loss probability for the qubit, which is applied before a measurement.
     pub loss: f64,
     /// A bit vector that tracks which qubits were lost.
@@ -512,6 +682,16 @@ pub struct SparseSim {
     /// Random number generator to sample Pauli noise.
     /// Noise is not applied when rng is None.
     pub rng: Option<StdRng>,
+    /// The tolerance used by diagnostic checks on the state.
+    pub tolerance: Tolerance,
+    /// Memory usage statistics, which are only collected when set.
+    pub stats: Option<SimulatorStats>,
+    /// The ids of the allocated qubits. The simulator orders the bits of the state it reports by
+    /// qubit id, so the bit for a qubit is its position among these ids.
+    qubit_ids: BTreeSet<usize>,
+    /// An upper bound on the number of non-zero amplitudes in the state. Only gates that split
+    /// amplitudes can grow the state, and it can never outgrow the allocated qubits.
+    state_size: usize,
 }
 
 impl Default for SparseSim {
@@ -526,9 +706,15 @@ impl SparseSim {
         Self {
             sim: QuantumSim::new(None),
             noise: PauliNoise::default(),
+            gate_noise: FxHashMap::default(),
+            readout_error: f64::zero(),
             loss: f64::zero(),
             lost_qubits: BigUint::zero(),
             rng: None,
+            tolerance: Tolerance::default(),
+            stats: None,
+            qubit_ids: BTreeSet::new(),
+            state_size: 1,
         }
     }
 
@@ -541,16 +727,74 @@ impl SparseSim {
 
     fn set_noise(&mut self, noise: &PauliNoise) {
         self.noise = *noise;
-        if noise.is_noiseless() && self.loss.is_zero() {
-            self.rng = None;
-        } else {
-            self.rng = Some(StdRng::from_entropy());
-        }
+        self.update_rng();
     }
 
     pub fn set_loss(&mut self, loss: f64) {
         self.loss = loss;
-        if loss.is_zero() && self.noise.is_noiseless() {
+        self.update_rng();
+    }
+
+    /// Configures the simulator with the given device noise model, replacing any
+    /// previously configured noise.
+    pub fn set_noise_config(&mut self, config: &NoiseConfig) -> Result<(), String> {
+        config.validate()?;
+        self.noise = config.default_noise()?;
+        self.gate_noise = config
+            .gate_names()
+            .map(|gate| Ok((gate.to_string(), config.noise_for_gate(gate)?)))
+            .collect::<Result<_, String>>()?;
+        self.readout_error = config.readout;
+        self.loss = config.loss;
+        self.update_rng();
+        Ok(())
+    }
+
+    /// Starts collecting memory usage statistics, discarding any collected so far.
+    /// The state size is bounded from the gates applied, so collection never reads the state.
+    pub fn collect_stats(&mut self) {
+        self.stats = Some(SimulatorStats {
+            qubits_in_use: self.qubit_ids.len(),
+            peak_qubits: self.qubit_ids.len(),
+            peak_state_size: self.state_size,
+            ..SimulatorStats::default()
+        });
+    }
+
+    /// Returns the number of amplitudes in a state of the given number of qubits.
+    fn full_state_size(qubits: usize) -> usize {
+        u32::try_from(qubits)
+            .ok()
+            .and_then(|qubits| 1_usize.checked_shl(qubits))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Updates the state size bound after a gate on the given number of qubits, which can split
+    /// each amplitude into as many amplitudes as there are basis states of those qubits.
+    fn grow_state_size(&mut self, qubits: usize) {
+        self.state_size = self
+            .state_size
+            .saturating_mul(Self::full_state_size(qubits))
+            .min(Self::full_state_size(self.qubit_ids.len()));
+        if let Some(stats) = &mut self.stats {
+            stats.peak_state_size = stats.peak_state_size.max(self.state_size);
+        }
+    }
+
+    /// Updates the state size bound after the given number of allocated qubits are left in a basis
+    /// state.
+    fn shrink_state_size(&mut self, settled_qubits: usize) {
+        self.state_size = self.state_size.min(Self::full_state_size(
+            self.qubit_ids.len().saturating_sub(settled_qubits),
+        ));
+    }
+
+    fn update_rng(&mut self) {
+        if self.noise.is_noiseless()
+            && self.gate_noise.values().all(PauliNoise::is_noiseless)
+            && self.readout_error.is_zero()
+            && self.loss.is_zero()
+        {
             self.rng = None;
         } else {
             self.rng = Some(StdRng::from_entropy());
@@ -562,7 +806,17 @@ impl SparseSim {
         self.rng.is_none()
     }
 
+    fn apply_gate_noise(&mut self, gate: &str, q: usize) {
+        let noise = self.gate_noise.get(gate).copied().unwrap_or(self.noise);
+        self.apply_pauli_noise(&noise, q);
+    }
+
     fn apply_noise(&mut self, q: usize) {
+        let noise = self.noise;
+        self.apply_pauli_noise(&noise, q);
+    }
+
+    fn apply_pauli_noise(&mut self, noise: &PauliNoise, q: usize) {
         if self.is_qubit_lost(q) {
             // If the qubit is already lost, we don't apply noise.
             return;
@@ -583,13 +837,13 @@ impl SparseSim {
                 return;
             }
 
-            // Apply noise with a probability distribution defined in `self.noise`.
+            // Apply noise with a probability distribution defined in `noise`.
             let p = rng.gen_range(0.0..1.0);
-            if p >= self.noise.distribution[2] {
+            if p >= noise.distribution[2] {
                 // In the most common case we don't apply noise
-            } else if p < self.noise.distribution[0] {
+            } else if p < noise.distribution[0] {
                 self.sim.x(q);
-            } else if p < self.noise.distribution[1] {
+            } else if p < noise.distribution[1] {
                 self.sim.y(q);
             } else {
                 self.sim.z(q);
@@ -598,10 +852,75 @@ impl SparseSim {
         // No noise applied if rng is None.
     }
 
+    /// Flips the reported measurement result with the configured readout error probability.
+    fn apply_readout_error(&mut self, res: bool) -> bool {
+        match &mut self.rng {
+            Some(rng) if self.readout_error > 0.0 => {
+                res ^ (rng.gen_range(0.0..1.0) < self.readout_error)
+            }
+            _ => res,
+        }
+    }
+
     /// Checks if the qubit is lost.
     fn is_qubit_lost(&self, q: usize) -> bool {
         self.lost_qubits.bit(q as u64)
     }
+
+    /// Prepares the given normalized big-endian amplitudes on qubits that are all in |0⟩, without
+    /// building the unitary for the whole register. The qubit at `depth` is rotated, controlled on
+    /// the qubits before it having the values `prefix`, so that it splits the weight of the
+    /// amplitudes starting with `prefix` between its two values. The rotations of the last qubit
+    /// also set the phases.
+    fn prepare_from_zero(
+        &mut self,
+        qubits: &[usize],
+        depth: usize,
+        prefix: usize,
+        amplitudes: &[Complex<f64>],
+    ) {
+        let (zero, one) = amplitudes.split_at(amplitudes.len() / 2);
+        let (alpha, beta) = if depth + 1 == qubits.len() {
+            (zero[0], one[0])
+        } else {
+            let weight = |half: &[Complex<f64>]| {
+                Complex::new(half.iter().map(Complex::norm_sqr).sum::<f64>().sqrt(), 0.0)
+            };
+            (weight(zero), weight(one))
+        };
+        let norm = (alpha.norm_sqr() + beta.norm_sqr()).sqrt();
+        if norm < 1e-12 {
+            return;
+        }
+        let (alpha, beta) = (alpha / norm, beta / norm);
+        let rotation = ndarray::array![[alpha, -beta.conj()], [beta, alpha.conj()]];
+
+        // Controls whose value in the prefix is zero are flipped around the rotation.
+        let controls = &qubits[..depth];
+        let flipped = controls
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| prefix >> (depth - 1 - i) & 1 == 0)
+            .map(|(_, &q)| q)
+            .collect::<Vec<_>>();
+        for &q in &flipped {
+            self.sim.x(q);
+        }
+        if controls.is_empty() {
+            self.sim.apply(&rotation, &qubits[depth..=depth], None);
+        } else {
+            self.sim
+                .apply(&rotation, &qubits[depth..=depth], Some(controls));
+        }
+        for &q in &flipped {
+            self.sim.x(q);
+        }
+
+        if depth + 1 < qubits.len() {
+            self.prepare_from_zero(qubits, depth + 1, prefix << 1, zero);
+            self.prepare_from_zero(qubits, depth + 1, (prefix << 1) | 1, one);
+        }
+    }
 }
 
 impl Backend for SparseSim {
@@ -628,44 +947,45 @@ impl Backend for SparseSim {
                 self.sim.mcx(&[ctl0, ctl1], q);
             }
         }
-        self.apply_noise(ctl0);
-        self.apply_noise(ctl1);
-        self.apply_noise(q);
+        self.apply_gate_noise("ccx", ctl0);
+        self.apply_gate_noise("ccx", ctl1);
+        self.apply_gate_noise("ccx", q);
     }
 
     fn cx(&mut self, ctl: usize, q: usize) {
         if !self.is_qubit_lost(ctl) && !self.is_qubit_lost(q) {
             self.sim.mcx(&[ctl], q);
         }
-        self.apply_noise(ctl);
-        self.apply_noise(q);
+        self.apply_gate_noise("cx", ctl);
+        self.apply_gate_noise("cx", q);
     }
 
     fn cy(&mut self, ctl: usize, q: usize) {
         if !self.is_qubit_lost(ctl) && !self.is_qubit_lost(q) {
             self.sim.mcy(&[ctl], q);
         }
-        self.apply_noise(ctl);
-        self.apply_noise(q);
+        self.apply_gate_noise("cy", ctl);
+        self.apply_gate_noise("cy", q);
     }
 
     fn cz(&mut self, ctl: usize, q: usize) {
         if !self.is_qubit_lost(ctl) && !self.is_qubit_lost(q) {
             self.sim.mcz(&[ctl], q);
         }
-        self.apply_noise(ctl);
-        self.apply_noise(q);
+        self.apply_gate_noise("cz", ctl);
+        self.apply_gate_noise("cz", q);
     }
 
     fn h(&mut self, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.h(q);
         }
-        self.apply_noise(q);
+        self.grow_state_size(1);
+        self.apply_gate_noise("h", q);
     }
 
     fn m(&mut self, q: usize) -> val::Result {
-        self.apply_noise(q);
+        self.apply_gate_noise("m", q);
         if self.is_qubit_lost(q) {
             // If the qubit is lost, we cannot measure it.
             // Mark it as no longer lost so it becomes usable again, since
@@ -673,11 +993,13 @@ impl Backend for SparseSim {
             self.lost_qubits.set_bit(q as u64, false);
             return val::Result::Loss;
         }
-        val::Result::Val(self.sim.measure(q))
+        let res = self.sim.measure(q);
+        self.shrink_state_size(1);
+        val::Result::Val(self.apply_readout_error(res))
     }
 
     fn mresetz(&mut self, q: usize) -> val::Result {
-        self.apply_noise(q); // Applying noise before measurement
+        self.apply_gate_noise("mresetz", q); // Applying noise before measurement
         if self.is_qubit_lost(q) {
             // If the qubit is lost, we cannot measure it.
             // Mark it as no longer lost so it becomes usable again, since
@@ -689,8 +1011,9 @@ impl Backend for SparseSim {
         if res {
             self.sim.x(q);
         }
-        self.apply_noise(q); // Applying noise after reset
-        val::Result::Val(res)
+        self.shrink_state_size(1);
+        self.apply_gate_noise("mresetz", q); // Applying noise after reset
+        val::Result::Val(self.apply_readout_error(res))
     }
 
     fn reset(&mut self, q: usize) {
@@ -702,7 +1025,8 @@ impl Backend for SparseSim {
         if !self.is_qubit_lost(q) {
             self.sim.rx(theta, q);
         }
-        self.apply_noise(q);
+        self.grow_state_size(1);
+        self.apply_gate_noise("rx", q);
     }
 
     fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
@@ -726,15 +1050,17 @@ impl Backend for SparseSim {
                 self.sim.h(q0);
             }
         }
-        self.apply_noise(q0);
-        self.apply_noise(q1);
+        self.grow_state_size(1);
+        self.apply_gate_noise("rxx", q0);
+        self.apply_gate_noise("rxx", q1);
     }
 
     fn ry(&mut self, theta: f64, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.ry(theta, q);
         }
-        self.apply_noise(q);
+        self.grow_state_size(1);
+        self.apply_gate_noise("ry", q);
     }
 
     fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
@@ -766,15 +1092,16 @@ impl Backend for SparseSim {
                 self.sim.h(q0);
             }
         }
-        self.apply_noise(q0);
-        self.apply_noise(q1);
+        self.grow_state_size(1);
+        self.apply_gate_noise("ryy", q0);
+        self.apply_gate_noise("ryy", q1);
     }
 
     fn rz(&mut self, theta: f64, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.rz(theta, q);
         }
-        self.apply_noise(q);
+        self.apply_gate_noise("rz", q);
     }
 
     fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
@@ -794,22 +1121,22 @@ impl Backend for SparseSim {
                 self.sim.mcx(&[q1], q0);
             }
         }
-        self.apply_noise(q0);
-        self.apply_noise(q1);
+        self.apply_gate_noise("rzz", q0);
+        self.apply_gate_noise("rzz", q1);
     }
 
     fn sadj(&mut self, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.sadj(q);
         }
-        self.apply_noise(q);
+        self.apply_gate_noise("sadj", q);
     }
 
     fn s(&mut self, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.s(q);
         }
-        self.apply_noise(q);
+        self.apply_gate_noise("s", q);
     }
 
     fn sx(&mut self, q: usize) {
@@ -818,58 +1145,71 @@ impl Backend for SparseSim {
             self.sim.s(q);
             self.sim.h(q);
         }
-        self.apply_noise(q);
+        self.grow_state_size(1);
+        self.apply_gate_noise("sx", q);
     }
 
     fn swap(&mut self, q0: usize, q1: usize) {
         if !self.is_qubit_lost(q0) && !self.is_qubit_lost(q1) {
             self.sim.swap_qubit_ids(q0, q1);
         }
-        self.apply_noise(q0);
-        self.apply_noise(q1);
+        self.apply_gate_noise("swap", q0);
+        self.apply_gate_noise("swap", q1);
     }
 
     fn tadj(&mut self, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.tadj(q);
         }
-        self.apply_noise(q);
+        self.apply_gate_noise("tadj", q);
     }
 
     fn t(&mut self, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.t(q);
         }
-        self.apply_noise(q);
+        self.apply_gate_noise("t", q);
     }
 
     fn x(&mut self, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.x(q);
         }
-        self.apply_noise(q);
+        self.apply_gate_noise("x", q);
     }
 
     fn y(&mut self, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.y(q);
         }
-        self.apply_noise(q);
+        self.apply_gate_noise("y", q);
     }
 
     fn z(&mut self, q: usize) {
         if !self.is_qubit_lost(q) {
             self.sim.z(q);
         }
-        self.apply_noise(q);
+        self.apply_gate_noise("z", q);
     }
 
     fn qubit_allocate(&mut self) -> usize {
+        if let Some(stats) = &mut self.stats {
+            stats.qubit_allocations += 1;
+            stats.qubits_in_use += 1;
+            stats.peak_qubits = stats.peak_qubits.max(stats.qubits_in_use);
+        }
         // Fresh qubit start in ground state even with noise.
-        self.sim.allocate()
+        let q = self.sim.allocate();
+        self.qubit_ids.insert(q);
+        q
     }
 
     fn qubit_release(&mut self, q: usize) -> bool {
+        self.qubit_ids.remove(&q);
+        self.shrink_state_size(0);
+        if let Some(stats) = &mut self.stats {
+            stats.qubits_in_use = stats.qubits_in_use.saturating_sub(1);
+        }
         if self.is_noiseless() {
             let was_zero = self.sim.qubit_is_zero(q);
             self.sim.release(q);
@@ -917,7 +1257,20 @@ impl Backend for SparseSim {
 
     fn qubit_is_zero(&mut self, q: usize) -> bool {
         // This is a service function rather than a measurement so it doesn't incur noise.
-        self.sim.qubit_is_zero(q)
+        let bit = self.qubit_ids.range(..q).count() as u64;
+        let (state, _) = self.sim.get_state();
+        let (one, norm) = state
+            .iter()
+            .fold((0.0, 0.0), |(one, norm), (label, amplitude)| {
+                let probability = amplitude.norm_sqr();
+                let one = if label.bit(bit) {
+                    one + probability
+                } else {
+                    one
+                };
+                (one, norm + probability)
+            });
+        self.tolerance.is_negligible(one, norm)
     }
 
     fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
@@ -1003,6 +1356,42 @@ impl Backend for SparseSim {
                     }
 
                     self.sim.apply(&matrix, &qubits, None);
+                    self.grow_state_size(qubits.len());
+                }
+
+                Some(Ok(Value::unit()))
+            }
+            "PreparePureStateFromAmplitudes" => {
+                let [coefficients, qubits] = unwrap_tuple(arg);
+                let Some(qubits) = qubits
+                    .unwrap_array()
+                    .iter()
+                    .map(|q| q.clone().unwrap_qubit().try_deref().map(|q| q.0))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return Some(Err("qubit used after release".to_string()));
+                };
+                let coefficients = coefficients
+                    .unwrap_array()
+                    .iter()
+                    .map(|elem| {
+                        let [re, im] = unwrap_tuple(elem.clone());
+                        Complex::<f64>::new(re.unwrap_double(), im.unwrap_double())
+                    })
+                    .collect::<Vec<_>>();
+                let amplitudes = match normalized_amplitudes(&coefficients, qubits.len()) {
+                    Ok(amplitudes) => amplitudes,
+                    Err(message) => return Some(Err(message)),
+                };
+
+                if !qubits.is_empty() && qubits.iter().all(|&q| !self.is_qubit_lost(q)) {
+                    if qubits.iter().all(|&q| self.sim.qubit_is_zero(q)) {
+                        self.prepare_from_zero(&qubits, 0, 0, &amplitudes);
+                    } else {
+                        let matrix = state_preparation_unitary(&amplitudes);
+                        self.sim.apply(&matrix, &qubits, None);
+                    }
+                    self.grow_state_size(qubits.len());
                 }
 
                 Some(Ok(Value::unit()))
@@ -1026,6 +1415,60 @@ impl Backend for SparseSim {
     }
 }
 
+/// Normalizes the given big-endian amplitudes and pads them with zeros to the size of the state
+/// of the given number of qubits.
+fn normalized_amplitudes(
+    coefficients: &[Complex<f64>],
+    qubit_count: usize,
+) -> Result<Vec<Complex<f64>>, String> {
+    let dim = 1 << qubit_count;
+    if coefficients.len() > dim {
+        return Err(format!(
+            "expected at most {dim} coefficients for {qubit_count} qubits, got {}",
+            coefficients.len()
+        ));
+    }
+    let norm = coefficients
+        .iter()
+        .map(Complex::norm_sqr)
+        .sum::<f64>()
+        .sqrt();
+    if norm < 1e-12 {
+        return Err("coefficients must not all be zero".to_string());
+    }
+
+    let mut amplitudes = vec![Complex::zero(); dim];
+    for (amplitude, coefficient) in amplitudes.iter_mut().zip(coefficients) {
+        *amplitude = coefficient / norm;
+    }
+    Ok(amplitudes)
+}
+
+/// Builds a unitary that maps |0...0⟩ to the state with the given normalized big-endian
+/// amplitudes. The unitary is the Householder reflection that exchanges the two states, with a
+/// global phase chosen so that the first column is exactly the target state.
+fn state_preparation_unitary(amplitudes: &[Complex<f64>]) -> Array2<Complex<f64>> {
+    let dim = amplitudes.len();
+    let target = Array1::from(amplitudes.to_vec());
+    let phase = if target[0].norm() > 1e-12 {
+        target[0] / target[0].norm()
+    } else {
+        Complex::new(1.0, 0.0)
+    };
+
+    // The reflection about v = phase·|0⟩ - target maps phase·|0⟩ to the target state.
+    let mut v = target.map(|amplitude| -amplitude);
+    v[0] += phase;
+    let v_norm_sqr = v.iter().map(Complex::norm_sqr).sum::<f64>();
+    let mut matrix = Array2::<Complex<f64>>::eye(dim);
+    if v_norm_sqr > 1e-24 {
+        for ((i, j), elem) in matrix.indexed_iter_mut() {
+            *elem -= v[i] * v[j].conj() * (2.0 / v_norm_sqr);
+        }
+    }
+    matrix.map(|elem| elem * phase)
+}
+
 fn unwrap_matrix_as_array2(matrix: Value, qubits: &[usize]) -> Array2<Complex<f64>> {
     let matrix: Vec<Vec<Complex<f64>>> = matrix
         .unwrap_array()
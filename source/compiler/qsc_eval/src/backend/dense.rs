@@ -0,0 +1,553 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+mod factor;
+mod precision;
+
+use super::{Backend, Tolerance, unwrap_matrix_as_array2};
+use crate::val::{self, Value, unwrap_tuple};
+use factor::Factor;
+use ndarray::Array2;
+use num_bigint::BigUint;
+use num_complex::Complex;
+use num_traits::{One, Zero};
+pub use precision::{DoubleDouble, Precision};
+use precision::{lift, lower};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rustc_hash::FxHashMap;
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4};
+
+/// A 2x2 gate matrix, indexed by row and then column.
+type Matrix<P = f64> = [[Complex<P>; 2]; 2];
+
+/// Amplitudes with a squared norm at or below this value are left out of captured states.
+const CAPTURE_THRESHOLD: f64 = 1e-24;
+
+/// A backend that stores the state of the allocated qubits in dense arrays.
+///
+/// The state is kept as a tensor product of factors, each a dense vector over a group of qubits.
+/// Every qubit starts in its own factor, and two factors are merged only when a multi-qubit gate
+/// acts across them. Measuring a qubit splits it back out into its own factor. Gates on a factor
+/// touch all `2^k` amplitudes of that factor, so many independent registers cost the sum of their
+/// sizes rather than the product, while the cost within a factor does not depend on how entangled
+/// it is. This is also the layout that an accelerator-backed state vector uses, which makes it the
+/// natural fallback for small registers.
+///
+/// Uncontrolled single-qubit gates are not applied right away. Consecutive gates on the same qubit
+/// are multiplied into one pending 2x2 matrix, which is applied in a single pass over the state
+/// the next time anything else touches that qubit. Diagonal gates stay diagonal when fused, so a
+/// run of phase and `Rz` gates is applied without mixing any amplitudes.
+///
+/// Amplitudes and fused gates are stored with the parts in `P`, which is `f64` by default. With
+/// [`DoubleDouble`], rounding errors no longer build up in the state over a deep circuit, at
+/// several times the cost per gate. The entries of each gate are still computed as `f64`.
+#[derive(Clone)]
+pub struct DenseSim<P = f64> {
+    /// The factors whose tensor product is the state of the allocated qubits.
+    factors: Vec<Factor<P>>,
+    /// The index into `factors` of the factor that holds each allocated qubit, keyed by id.
+    owner: FxHashMap<usize, usize>,
+    /// The global phase left behind by factors whose qubits have all been released.
+    phase: Complex<P>,
+    /// The fused single-qubit gates that have not been applied to the state yet, keyed by id.
+    pending: FxHashMap<usize, Matrix<P>>,
+    /// Random number generator used to sample measurements.
+    rng: StdRng,
+    /// The tolerance used by diagnostic checks on the state.
+    pub tolerance: Tolerance,
+}
+
+impl<P: Precision> Default for DenseSim<P> {
+    fn default() -> Self {
+        Self::with_precision()
+    }
+}
+
+impl DenseSim {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_precision()
+    }
+}
+
+impl<P: Precision> DenseSim<P> {
+    /// Creates a simulator that stores amplitudes with the parts in `P`.
+    #[must_use]
+    pub fn with_precision() -> Self {
+        Self {
+            factors: Vec::new(),
+            owner: FxHashMap::default(),
+            phase: Complex::one(),
+            pending: FxHashMap::default(),
+            rng: StdRng::from_entropy(),
+            tolerance: Tolerance::default(),
+        }
+    }
+
+    /// Returns the number of qubits currently allocated.
+    #[must_use]
+    pub fn qubit_count(&self) -> usize {
+        self.owner.len()
+    }
+
+    /// Returns the number of unentangled factors the state is currently split into.
+    #[must_use]
+    pub fn factor_count(&self) -> usize {
+        self.factors.len()
+    }
+
+    fn factor_of(&self, q: usize) -> usize {
+        *self.owner.get(&q).expect("qubit should be allocated")
+    }
+
+    /// Merges the factors holding the given qubits and returns the index of the merged factor.
+    fn join(&mut self, qubits: &[usize]) -> usize {
+        let mut target = self.factor_of(qubits[0]);
+        for &q in &qubits[1..] {
+            let other = self.factor_of(q);
+            if other != target {
+                target = self.merge(target, other);
+            }
+        }
+        target
+    }
+
+    /// Merges two factors into the one with the lower index and returns that index.
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        let (keep, drop) = (a.min(b), a.max(b));
+        let dropped = self.remove_factor(drop);
+        for &q in dropped.bits.keys() {
+            self.owner.insert(q, keep);
+        }
+        self.factors[keep].absorb(dropped);
+        keep
+    }
+
+    /// Removes the factor at the index, updating the owner of the factor moved into its place.
+    fn remove_factor(&mut self, index: usize) -> Factor<P> {
+        let removed = self.factors.swap_remove(index);
+        if let Some(moved) = self.factors.get(index) {
+            for &q in moved.bits.keys() {
+                self.owner.insert(q, index);
+            }
+        }
+        removed
+    }
+
+    /// Fuses the matrix into the pending gate for qubit `q`, after any gates already pending.
+    fn fuse(&mut self, q: usize, m: &Matrix) {
+        let m = lift_matrix(m);
+        let fused = match self.pending.get(&q) {
+            Some(prior) => mul(&m, prior),
+            None => m,
+        };
+        self.pending.insert(q, fused);
+    }
+
+    /// Applies the pending gate for qubit `q`, if there is one, to the state.
+    fn flush(&mut self, q: usize) {
+        if let Some(m) = self.pending.remove(&q)
+            && !is_identity(&m)
+        {
+            let f = self.factor_of(q);
+            self.factors[f].apply_matrix(&[], q, &m);
+        }
+    }
+
+    /// Applies all pending gates to the state.
+    fn flush_all(&mut self) {
+        let mut ids = self.pending.keys().copied().collect::<Vec<_>>();
+        ids.sort_unstable();
+        for q in ids {
+            self.flush(q);
+        }
+    }
+
+    /// Applies the matrix to qubit `q`, on the basis states where all of the controls are one.
+    fn apply(&mut self, ctls: &[usize], q: usize, m: &Matrix) {
+        self.flush(q);
+        for &ctl in ctls {
+            self.flush(ctl);
+        }
+        let mut qubits = vec![q];
+        qubits.extend_from_slice(ctls);
+        let f = self.join(&qubits);
+        self.factors[f].apply_matrix(ctls, q, &lift_matrix(m));
+    }
+
+    /// Applies `exp(-iθ/2 Z⊗Z)` to the two qubits.
+    fn zz_rotation(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.flush(q0);
+        self.flush(q1);
+        let f = self.join(&[q0, q1]);
+        self.factors[f].zz_rotation(theta, q0, q1);
+    }
+
+    /// Returns the probability of measuring `One` on the qubit and the squared norm of its factor.
+    fn probability_of_one(&mut self, q: usize) -> (f64, f64) {
+        self.flush(q);
+        self.factors[self.factor_of(q)].probability_of_one(q)
+    }
+
+    /// Measures the qubit in the computational basis, collapsing and renormalizing the state. The
+    /// measured qubit is left in a basis state, so it is split out into its own factor.
+    fn measure(&mut self, q: usize) -> bool {
+        let (one, norm) = self.probability_of_one(q);
+        let result = self.rng.gen_range(0.0..norm) < one;
+        let kept = if result { one } else { norm - one };
+        let f = self.factor_of(q);
+        let factor = &mut self.factors[f];
+        factor.collapse(q, result, 1.0 / kept.sqrt());
+        if factor.bits.len() > 1 {
+            factor.extract(q, result);
+            let mut single = Factor::new(q);
+            if result {
+                single.amplitudes.swap(0, 1);
+            }
+            self.owner.insert(q, self.factors.len());
+            self.factors.push(single);
+        }
+        result
+    }
+}
+
+fn c(re: f64, im: f64) -> Complex<f64> {
+    Complex::new(re, im)
+}
+
+fn lift_matrix<P: Precision>(m: &Matrix) -> Matrix<P> {
+    m.map(|row| row.map(lift))
+}
+
+/// Returns the product `a * b`, which applies `b` first.
+fn mul<P: Precision>(a: &Matrix<P>, b: &Matrix<P>) -> Matrix<P> {
+    let entry = |row: usize, col: usize| a[row][0] * b[0][col] + a[row][1] * b[1][col];
+    [[entry(0, 0), entry(0, 1)], [entry(1, 0), entry(1, 1)]]
+}
+
+/// Returns true if the matrix is the identity up to floating-point rounding, as when a gate is
+/// followed by its adjoint.
+fn is_identity<P: Precision>(m: &Matrix<P>) -> bool {
+    let tolerance = (4.0 * f64::EPSILON).powi(2);
+    let negligible = |value: Complex<P>| value.norm_sqr().to_f64() <= tolerance;
+    negligible(m[0][0] - Complex::one())
+        && negligible(m[0][1])
+        && negligible(m[1][0])
+        && negligible(m[1][1] - Complex::one())
+}
+
+fn diag(d0: Complex<f64>, d1: Complex<f64>) -> Matrix {
+    [[d0, Complex::zero()], [Complex::zero(), d1]]
+}
+
+fn h() -> Matrix {
+    [
+        [c(FRAC_1_SQRT_2, 0.0), c(FRAC_1_SQRT_2, 0.0)],
+        [c(FRAC_1_SQRT_2, 0.0), c(-FRAC_1_SQRT_2, 0.0)],
+    ]
+}
+
+fn x() -> Matrix {
+    [
+        [Complex::zero(), Complex::one()],
+        [Complex::one(), Complex::zero()],
+    ]
+}
+
+fn y() -> Matrix {
+    [
+        [Complex::zero(), c(0.0, -1.0)],
+        [c(0.0, 1.0), Complex::zero()],
+    ]
+}
+
+fn z() -> Matrix {
+    diag(Complex::one(), c(-1.0, 0.0))
+}
+
+fn phase(theta: f64) -> Matrix {
+    diag(Complex::one(), Complex::from_polar(1.0, theta))
+}
+
+fn rx(theta: f64) -> Matrix {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [[c(cos, 0.0), c(0.0, -sin)], [c(0.0, -sin), c(cos, 0.0)]]
+}
+
+fn ry(theta: f64) -> Matrix {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [[c(cos, 0.0), c(-sin, 0.0)], [c(sin, 0.0), c(cos, 0.0)]]
+}
+
+fn rz(theta: f64) -> Matrix {
+    diag(
+        Complex::from_polar(1.0, -theta / 2.0),
+        Complex::from_polar(1.0, theta / 2.0),
+    )
+}
+
+fn sx() -> Matrix {
+    [[c(0.5, 0.5), c(0.5, -0.5)], [c(0.5, -0.5), c(0.5, 0.5)]]
+}
+
+impl<P: Precision> Backend for DenseSim<P> {
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.apply(&[ctl0, ctl1], q, &x());
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.apply(&[ctl], q, &x());
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.apply(&[ctl], q, &y());
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.apply(&[ctl], q, &z());
+    }
+
+    fn h(&mut self, q: usize) {
+        self.fuse(q, &h());
+    }
+
+    fn m(&mut self, q: usize) -> val::Result {
+        val::Result::Val(self.measure(q))
+    }
+
+    fn mresetz(&mut self, q: usize) -> val::Result {
+        let res = self.measure(q);
+        if res {
+            self.apply(&[], q, &x());
+        }
+        val::Result::Val(res)
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.mresetz(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.fuse(q, &rx(theta));
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        // X⊗X is Z⊗Z in the Hadamard basis.
+        self.h(q0);
+        self.h(q1);
+        self.zz_rotation(theta, q0, q1);
+        self.h(q0);
+        self.h(q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.fuse(q, &ry(theta));
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        // Y = (SH) Z (SH)†, so Y⊗Y is Z⊗Z after applying (SH)† to both qubits.
+        for q in [q0, q1] {
+            self.sadj(q);
+            self.h(q);
+        }
+        self.zz_rotation(theta, q0, q1);
+        for q in [q0, q1] {
+            self.h(q);
+            self.s(q);
+        }
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.fuse(q, &rz(theta));
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.zz_rotation(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.fuse(q, &phase(-FRAC_PI_2));
+    }
+
+    fn s(&mut self, q: usize) {
+        self.fuse(q, &phase(FRAC_PI_2));
+    }
+
+    fn sx(&mut self, q: usize) {
+        self.fuse(q, &sx());
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        // Exchanging the states of two qubits is the same as exchanging which qubit each id names.
+        self.qubit_swap_id(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.fuse(q, &phase(-FRAC_PI_4));
+    }
+
+    fn t(&mut self, q: usize) {
+        self.fuse(q, &phase(FRAC_PI_4));
+    }
+
+    fn x(&mut self, q: usize) {
+        self.fuse(q, &x());
+    }
+
+    fn y(&mut self, q: usize) {
+        self.fuse(q, &y());
+    }
+
+    fn z(&mut self, q: usize) {
+        self.fuse(q, &z());
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let q = (0..)
+            .find(|q| !self.owner.contains_key(q))
+            .expect("there should be a free qubit id");
+        self.owner.insert(q, self.factors.len());
+        self.factors.push(Factor::new(q));
+        q
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        let was_zero = self.qubit_is_zero(q);
+        if !was_zero {
+            self.reset(q);
+        }
+        let f = self.factor_of(q);
+        self.factors[f].extract(q, false);
+        self.owner.remove(&q);
+        if self.factors[f].bits.is_empty() {
+            let removed = self.remove_factor(f);
+            self.phase *= removed.amplitudes[0];
+        }
+        was_zero
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        let f0 = self.owner.remove(&q0);
+        let f1 = self.owner.remove(&q1);
+        let bit0 = f0.map(|f| self.factors[f].bits.remove(&q0));
+        let bit1 = f1.map(|f| self.factors[f].bits.remove(&q1));
+        if let (Some(f), Some(Some(bit))) = (f0, bit0) {
+            self.factors[f].bits.insert(q1, bit);
+            self.owner.insert(q1, f);
+        }
+        if let (Some(f), Some(Some(bit))) = (f1, bit1) {
+            self.factors[f].bits.insert(q0, bit);
+            self.owner.insert(q0, f);
+        }
+        let pending0 = self.pending.remove(&q0);
+        let pending1 = self.pending.remove(&q1);
+        if let Some(m) = pending0 {
+            self.pending.insert(q1, m);
+        }
+        if let Some(m) = pending1 {
+            self.pending.insert(q0, m);
+        }
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.flush_all();
+        let mut full = Factor::scalar(self.phase);
+        for factor in &self.factors {
+            full.absorb(factor.clone());
+        }
+        // Qubits are reported in order of id, with the lowest id as the most significant bit.
+        let mut ids = self.owner.keys().copied().collect::<Vec<_>>();
+        ids.sort_unstable();
+        let count = ids.len();
+        let mut state = full
+            .amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(_, amplitude)| amplitude.norm_sqr().to_f64() > CAPTURE_THRESHOLD)
+            .map(|(i, amplitude)| {
+                let mut label = BigUint::zero();
+                for (position, id) in ids.iter().enumerate() {
+                    if i & full.mask(*id) != 0 {
+                        label.set_bit((count - 1 - position) as u64, true);
+                    }
+                }
+                (label, lower(*amplitude))
+            })
+            .collect::<Vec<_>>();
+        state.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        (state, count)
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        let (one, norm) = self.probability_of_one(q);
+        self.tolerance.is_negligible(one, norm)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        match name {
+            "GlobalPhase" => {
+                let [ctls, theta] = unwrap_tuple(arg);
+                let ctls = ctls
+                    .unwrap_array()
+                    .iter()
+                    .map(|q| q.clone().unwrap_qubit().deref().0)
+                    .collect::<Vec<_>>();
+                let phase = Complex::from_polar(1.0, theta.unwrap_double());
+                if ctls.is_empty() {
+                    self.phase *= lift(phase);
+                } else {
+                    for &ctl in &ctls {
+                        self.flush(ctl);
+                    }
+                    let f = self.join(&ctls);
+                    self.factors[f].apply_phase(&ctls, phase);
+                }
+                Some(Ok(Value::unit()))
+            }
+            "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
+            "EndEstimateCaching"
+            | "AccountForEstimatesInternal"
+            | "BeginRepeatEstimatesInternal"
+            | "EndRepeatEstimatesInternal"
+            | "EnableMemoryComputeArchitecture" => Some(Ok(Value::unit())),
+            "Apply" => {
+                let [matrix, qubits] = unwrap_tuple(arg);
+                let qubits = qubits
+                    .unwrap_array()
+                    .iter()
+                    .filter_map(|q| q.clone().unwrap_qubit().try_deref().map(|q| q.0))
+                    .collect::<Vec<_>>();
+                let matrix = unwrap_matrix_as_array2(matrix, &qubits);
+                let adj = matrix.t().map(Complex::<f64>::conj);
+                if (matrix.dot(&adj) - Array2::<Complex<f64>>::eye(1 << qubits.len()))
+                    .map(|x| x.norm())
+                    .sum()
+                    > 1e-9
+                {
+                    return Some(Err("matrix is not unitary".to_string()));
+                }
+                if qubits.is_empty() {
+                    self.phase *= lift(matrix[[0, 0]]);
+                } else {
+                    for &q in &qubits {
+                        self.flush(q);
+                    }
+                    let f = self.join(&qubits);
+                    self.factors[f].apply_unitary(&matrix, &qubits);
+                }
+                Some(Ok(Value::unit()))
+            }
+            _ => None,
+        }
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{
+    Matrix,
+    precision::{Precision, lift},
+};
+use ndarray::Array2;
+use num_complex::Complex;
+use num_traits::{One, Zero};
+use rustc_hash::FxHashMap;
+
+/// A group of qubits whose joint state is stored as one dense vector of amplitudes. The state of
+/// the whole simulator is the tensor product of its factors.
+#[derive(Clone)]
+pub(super) struct Factor<P> {
+    /// The amplitudes of the factor, where the value of each qubit is a bit of the index.
+    pub(super) amplitudes: Vec<Complex<P>>,
+    /// The bit of the amplitude index that holds the value of each qubit, keyed by id.
+    pub(super) bits: FxHashMap<usize, usize>,
+}
+
+impl<P: Precision> Factor<P> {
+    /// Creates a factor holding a single qubit in the zero state.
+    pub(super) fn new(q: usize) -> Self {
+        let mut bits = FxHashMap::default();
+        bits.insert(q, 0);
+        Self {
+            amplitudes: vec![Complex::one(), Complex::zero()],
+            bits,
+        }
+    }
+
+    /// Creates a factor with no qubits, whose only amplitude is the given scalar.
+    pub(super) fn scalar(value: Complex<P>) -> Self {
+        Self {
+            amplitudes: vec![value],
+            bits: FxHashMap::default(),
+        }
+    }
+
+    pub(super) fn mask(&self, q: usize) -> usize {
+        1 << *self.bits.get(&q).expect("qubit should be in this factor")
+    }
+
+    fn ctl_mask(&self, ctls: &[usize]) -> usize {
+        ctls.iter().fold(0, |mask, &ctl| mask | self.mask(ctl))
+    }
+
+    /// Replaces this factor with its tensor product with `other`. The qubits of `other` take the
+    /// bits above the ones already in use.
+    pub(super) fn absorb(&mut self, other: Self) {
+        let shift = self.bits.len();
+        self.amplitudes = other
+            .amplitudes
+            .iter()
+            .flat_map(|high| self.amplitudes.iter().map(move |low| low * high))
+            .collect();
+        self.bits
+            .extend(other.bits.into_iter().map(|(q, bit)| (q, bit + shift)));
+    }
+
+    /// Applies the matrix to qubit `q`, on the basis states where all of the controls are one.
+    pub(super) fn apply_matrix(&mut self, ctls: &[usize], q: usize, m: &Matrix<P>) {
+        let ctl_mask = self.ctl_mask(ctls);
+        let target = self.mask(q);
+        if m[0][1].is_zero() && m[1][0].is_zero() {
+            // A diagonal matrix only rescales amplitudes, so each one is visited once.
+            for (i, amplitude) in self.amplitudes.iter_mut().enumerate() {
+                if i & ctl_mask == ctl_mask {
+                    *amplitude *= if i & target == 0 { m[0][0] } else { m[1][1] };
+                }
+            }
+            return;
+        }
+        for i in 0..self.amplitudes.len() {
+            if i & target == 0 && i & ctl_mask == ctl_mask {
+                let j = i | target;
+                let (a0, a1) = (self.amplitudes[i], self.amplitudes[j]);
+                self.amplitudes[i] = m[0][0] * a0 + m[0][1] * a1;
+                self.amplitudes[j] = m[1][0] * a0 + m[1][1] * a1;
+            }
+        }
+    }
+
+    /// Multiplies the amplitudes where all of the controls are one by the phase.
+    pub(super) fn apply_phase(&mut self, ctls: &[usize], phase: Complex<f64>) {
+        let ctl_mask = self.ctl_mask(ctls);
+        let phase = lift::<P>(phase);
+        for (i, amplitude) in self.amplitudes.iter_mut().enumerate() {
+            if i & ctl_mask == ctl_mask {
+                *amplitude *= phase;
+            }
+        }
+    }
+
+    /// Applies `exp(-iθ/2 Z⊗Z)`, which multiplies each amplitude by a phase that depends only on
+    /// the parity of the two qubits.
+    pub(super) fn zz_rotation(&mut self, theta: f64, q0: usize, q1: usize) {
+        let (m0, m1) = (self.mask(q0), self.mask(q1));
+        let even = lift::<P>(Complex::from_polar(1.0, -theta / 2.0));
+        let odd = lift::<P>(Complex::from_polar(1.0, theta / 2.0));
+        for (i, amplitude) in self.amplitudes.iter_mut().enumerate() {
+            let parity = (i & m0 != 0) ^ (i & m1 != 0);
+            *amplitude *= if parity { odd } else { even };
+        }
+    }
+
+    /// Returns the probability of measuring `One` on the qubit and the squared norm of the factor.
+    pub(super) fn probability_of_one(&self, q: usize) -> (f64, f64) {
+        let mask = self.mask(q);
+        let (one, norm) = self.amplitudes.iter().enumerate().fold(
+            (P::zero(), P::zero()),
+            |(one, norm), (i, amplitude)| {
+                let probability = amplitude.norm_sqr();
+                let one = if i & mask == 0 {
+                    one
+                } else {
+                    one + probability
+                };
+                (one, norm + probability)
+            },
+        );
+        (one.to_f64(), norm.to_f64())
+    }
+
+    /// Projects the qubit onto `result` and rescales the remaining amplitudes by `scale`.
+    pub(super) fn collapse(&mut self, q: usize, result: bool, scale: f64) {
+        let mask = self.mask(q);
+        let scale = P::from(scale);
+        for (i, amplitude) in self.amplitudes.iter_mut().enumerate() {
+            if (i & mask != 0) == result {
+                *amplitude *= scale;
+            } else {
+                *amplitude = Complex::zero();
+            }
+        }
+    }
+
+    /// Removes the qubit's bit from the factor, keeping the amplitudes where it has `value`. The
+    /// qubit must already be in that basis state.
+    pub(super) fn extract(&mut self, q: usize, value: bool) {
+        let bit = self
+            .bits
+            .remove(&q)
+            .expect("qubit should be in this factor");
+        let low = (1 << bit) - 1;
+        let set = usize::from(value) << bit;
+        self.amplitudes = (0..self.amplitudes.len() / 2)
+            .map(|i| self.amplitudes[(i & low) | ((i & !low) << 1) | set])
+            .collect();
+        for other in self.bits.values_mut() {
+            if *other > bit {
+                *other -= 1;
+            }
+        }
+    }
+
+    /// Applies a unitary on the given qubits, where the first qubit is the most significant bit of
+    /// the matrix row and column indices.
+    pub(super) fn apply_unitary(&mut self, matrix: &Array2<Complex<f64>>, qubits: &[usize]) {
+        let masks = qubits.iter().map(|&q| self.mask(q)).collect::<Vec<_>>();
+        let all = masks.iter().fold(0, |all, mask| all | mask);
+        let dim = 1 << qubits.len();
+        // Maps a row or column of the matrix to the bits it sets in an amplitude index.
+        let offset = |k: usize| {
+            masks
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| k >> (qubits.len() - 1 - i) & 1 == 1)
+                .fold(0, |offset, (_, mask)| offset | mask)
+        };
+        let offsets = (0..dim).map(offset).collect::<Vec<_>>();
+        let mut block = vec![Complex::zero(); dim];
+        for base in 0..self.amplitudes.len() {
+            if base & all != 0 {
+                continue;
+            }
+            for (k, value) in block.iter_mut().enumerate() {
+                *value = self.amplitudes[base | offsets[k]];
+            }
+            for (row, &row_offset) in offsets.iter().enumerate() {
+                self.amplitudes[base | row_offset] = block
+                    .iter()
+                    .enumerate()
+                    .map(|(col, value)| lift::<P>(matrix[[row, col]]) * value)
+                    .sum();
+            }
+        }
+    }
+}
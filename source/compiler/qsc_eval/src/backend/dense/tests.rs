@@ -0,0 +1,354 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{DenseSim, DoubleDouble, Precision};
+use crate::{
+    backend::{Backend, SparseSim},
+    val::{self, Qubit, Value},
+};
+use num_bigint::BigUint;
+use num_complex::Complex;
+use num_traits::Zero;
+use std::{f64::consts::FRAC_1_SQRT_2, rc::Rc};
+
+fn assert_state(sim: &mut DenseSim, expected: &[(u32, Complex<f64>)]) {
+    let (state, _) = sim.capture_quantum_state();
+    assert_eq!(state.len(), expected.len(), "state: {state:?}");
+    for ((label, amplitude), (expected_label, expected_amplitude)) in state.iter().zip(expected) {
+        assert_eq!(*label, BigUint::from(*expected_label));
+        assert!(
+            (amplitude - expected_amplitude).norm() < 1e-12,
+            "amplitude {amplitude} should be {expected_amplitude}"
+        );
+    }
+}
+
+fn assert_matches_sparse(dense: &mut DenseSim, sparse: &mut SparseSim) {
+    let (expected, _) = sparse.capture_quantum_state();
+    let (actual, _) = dense.capture_quantum_state();
+    assert_eq!(actual.len(), expected.len());
+    // The states are compared up to global phase, using the first amplitude as the reference.
+    let phase = expected[0].1 / actual[0].1;
+    for ((label, amplitude), (expected_label, expected_amplitude)) in actual.iter().zip(&expected) {
+        assert_eq!(label, expected_label);
+        assert!((amplitude * phase - expected_amplitude).norm() < 1e-10);
+    }
+}
+
+#[test]
+fn bell_state_is_reported_with_lowest_id_as_most_significant_bit() {
+    let mut sim = DenseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.x(q0);
+    assert_state(&mut sim, &[(0b10, Complex::new(1.0, 0.0))]);
+    sim.x(q0);
+    sim.h(q0);
+    sim.cx(q0, q1);
+    let amplitude = Complex::new(FRAC_1_SQRT_2, 0.0);
+    assert_state(&mut sim, &[(0b00, amplitude), (0b11, amplitude)]);
+}
+
+#[test]
+fn measurement_collapses_entangled_qubits() {
+    let mut sim = DenseSim::new();
+    sim.set_seed(Some(42));
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.cx(q0, q1);
+    let r0 = sim.m(q0);
+    let r1 = sim.m(q1);
+    assert_eq!(r0, r1);
+    let val::Result::Val(one) = r0 else {
+        panic!("measurement should produce a value");
+    };
+    assert_state(
+        &mut sim,
+        &[(if one { 0b11 } else { 0b00 }, Complex::new(1.0, 0.0))],
+    );
+}
+
+#[test]
+fn released_qubit_ids_are_reused_and_state_shrinks() {
+    let mut sim = DenseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    let q2 = sim.qubit_allocate();
+    sim.x(q2);
+    assert!(sim.qubit_release(q1));
+    assert_eq!(sim.qubit_count(), 2);
+    assert_state(&mut sim, &[(0b01, Complex::new(1.0, 0.0))]);
+    assert_eq!(sim.qubit_allocate(), q1);
+    assert!(sim.qubit_is_zero(q0));
+    assert!(!sim.qubit_is_zero(q2));
+    assert!(
+        !sim.qubit_release(q2),
+        "released qubit was in the one state"
+    );
+}
+
+#[test]
+fn swapping_ids_swaps_qubit_values() {
+    let mut sim = DenseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.x(q0);
+    sim.qubit_swap_id(q0, q1);
+    assert!(sim.qubit_is_zero(q0));
+    assert!(!sim.qubit_is_zero(q1));
+}
+
+#[test]
+fn two_qubit_rotations_match_sparse_simulator() {
+    let mut dense = DenseSim::new();
+    let mut sparse = SparseSim::new();
+    for sim in [&mut dense as &mut dyn Backend, &mut sparse] {
+        let q0 = sim.qubit_allocate();
+        let q1 = sim.qubit_allocate();
+        sim.h(q0);
+        sim.ry(0.3, q1);
+        sim.rxx(0.7, q0, q1);
+        sim.ryy(1.1, q0, q1);
+        sim.rzz(-0.4, q0, q1);
+        sim.sx(q1);
+        sim.cy(q1, q0);
+    }
+    assert_matches_sparse(&mut dense, &mut sparse);
+}
+
+#[test]
+fn controlled_global_phase_applies_only_when_controls_are_one() {
+    let mut sim = DenseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    let ctl = Rc::new(Qubit(q0));
+    let arg = Value::Tuple(
+        vec![
+            Value::Array(vec![Value::Qubit((&ctl).into())].into()),
+            Value::Double(std::f64::consts::PI),
+        ]
+        .into(),
+        None,
+    );
+    let result = sim.custom_intrinsic("GlobalPhase", arg);
+    assert_eq!(result, Some(Ok(Value::unit())));
+    let amplitude = FRAC_1_SQRT_2;
+    assert_state(
+        &mut sim,
+        &[
+            (0b00, Complex::new(amplitude, 0.0)),
+            (0b10, Complex::new(-amplitude, 0.0)),
+        ],
+    );
+    assert!(sim.qubit_is_zero(q1));
+}
+
+#[test]
+fn apply_rejects_non_unitary_matrix() {
+    let mut sim = DenseSim::new();
+    let q = Rc::new(Qubit(sim.qubit_allocate()));
+    let entry = |re: f64| Value::Tuple(vec![Value::Double(re), Value::Double(0.0)].into(), None);
+    let row = |a: f64, b: f64| Value::Array(vec![entry(a), entry(b)].into());
+    let arg = Value::Tuple(
+        vec![
+            Value::Array(vec![row(1.0, 1.0), row(0.0, 1.0)].into()),
+            Value::Array(vec![Value::Qubit((&q).into())].into()),
+        ]
+        .into(),
+        None,
+    );
+    assert_eq!(
+        sim.custom_intrinsic("Apply", arg),
+        Some(Err("matrix is not unitary".to_string()))
+    );
+}
+
+#[test]
+fn consecutive_single_qubit_gates_are_fused_until_the_qubit_is_used() {
+    let mut sim = DenseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.t(q0);
+    sim.rz(0.3, q0);
+    sim.x(q1);
+    assert_eq!(sim.pending.len(), 2);
+    assert_eq!(
+        sim.factors[sim.owner[&q0]].amplitudes[0],
+        Complex::new(1.0, 0.0),
+        "no gate applied yet"
+    );
+    sim.cx(q0, q1);
+    assert!(sim.pending.is_empty());
+
+    let mut sparse = SparseSim::new();
+    let (s0, s1) = (sparse.qubit_allocate(), sparse.qubit_allocate());
+    sparse.h(s0);
+    sparse.t(s0);
+    sparse.rz(0.3, s0);
+    sparse.x(s1);
+    sparse.cx(s0, s1);
+    assert_matches_sparse(&mut sim, &mut sparse);
+}
+
+#[test]
+fn gate_followed_by_its_adjoint_leaves_state_untouched() {
+    let mut sim = DenseSim::new();
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    sim.s(q);
+    sim.sadj(q);
+    sim.h(q);
+    sim.flush(q);
+    assert!(sim.pending.is_empty());
+    assert_eq!(
+        sim.factors[sim.owner[&q]].amplitudes,
+        vec![Complex::new(1.0, 0.0), Complex::zero()]
+    );
+}
+
+#[test]
+fn fused_diagonal_gates_stay_diagonal() {
+    let mut sim = DenseSim::new();
+    let q = sim.qubit_allocate();
+    sim.rz(0.1, q);
+    sim.t(q);
+    sim.s(q);
+    sim.z(q);
+    let m = sim.pending[&q];
+    assert!(m[0][1].is_zero() && m[1][0].is_zero());
+}
+
+#[test]
+fn pending_gates_follow_swapped_ids() {
+    let mut sim = DenseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.x(q0);
+    sim.qubit_swap_id(q0, q1);
+    assert!(sim.qubit_is_zero(q0));
+    assert!(!sim.qubit_is_zero(q1));
+}
+
+#[test]
+fn factors_merge_on_entangling_gates_and_split_on_measurement() {
+    let mut sim = DenseSim::new();
+    sim.set_seed(Some(7));
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    let q2 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.h(q2);
+    assert_eq!(sim.factor_count(), 3);
+    sim.cx(q0, q1);
+    assert_eq!(sim.factor_count(), 2);
+    assert_eq!(sim.owner[&q0], sim.owner[&q1]);
+    let result = sim.m(q0);
+    assert_eq!(sim.factor_count(), 3);
+    assert_eq!(sim.m(q1), result);
+    assert_eq!(sim.factors[sim.owner[&q1]].amplitudes.len(), 2);
+}
+
+#[test]
+fn many_independent_qubits_do_not_grow_a_single_state_vector() {
+    // A single dense vector over 64 qubits could not be allocated, but independent factors can.
+    let mut sim = DenseSim::new();
+    sim.set_seed(Some(0));
+    let qubits = (0..64).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    for pair in qubits.chunks(2) {
+        sim.h(pair[0]);
+        sim.cx(pair[0], pair[1]);
+    }
+    assert_eq!(sim.factor_count(), 32);
+    for pair in qubits.chunks(2) {
+        assert_eq!(sim.m(pair[0]), sim.m(pair[1]));
+    }
+    for q in qubits {
+        sim.reset(q);
+        assert!(sim.qubit_release(q));
+    }
+    assert_eq!(sim.factor_count(), 0);
+}
+
+#[test]
+fn swap_across_factors_matches_sparse_simulator() {
+    let mut dense = DenseSim::new();
+    let mut sparse = SparseSim::new();
+    for sim in [&mut dense as &mut dyn Backend, &mut sparse] {
+        let q0 = sim.qubit_allocate();
+        let q1 = sim.qubit_allocate();
+        let q2 = sim.qubit_allocate();
+        sim.ry(0.4, q0);
+        sim.h(q1);
+        sim.cx(q1, q2);
+        sim.swap(q0, q2);
+        sim.t(q2);
+        sim.cz(q0, q2);
+    }
+    assert_matches_sparse(&mut dense, &mut sparse);
+}
+
+#[test]
+fn double_double_keeps_bits_that_f64_rounds_away() {
+    let one = DoubleDouble::from(1.0);
+    let tiny = DoubleDouble::from(1e-20);
+    assert!(((one + tiny - one).to_f64() - 1e-20).abs() < 1e-36);
+    let three = DoubleDouble::from(3.0);
+    assert!(((one / three) * three - one).to_f64().abs() < 1e-30);
+}
+
+#[test]
+fn extended_precision_matches_default_precision() {
+    let mut double = DenseSim::new();
+    let mut extended = DenseSim::<DoubleDouble>::with_precision();
+    for sim in [&mut double as &mut dyn Backend, &mut extended] {
+        let q0 = sim.qubit_allocate();
+        let q1 = sim.qubit_allocate();
+        sim.ry(0.4, q0);
+        sim.h(q1);
+        sim.cx(q1, q0);
+        sim.rzz(0.7, q0, q1);
+        sim.t(q0);
+    }
+    let (expected, _) = double.capture_quantum_state();
+    let (actual, _) = extended.capture_quantum_state();
+    assert_eq!(actual.len(), expected.len());
+    for ((label, amplitude), (expected_label, expected_amplitude)) in actual.iter().zip(&expected) {
+        assert_eq!(label, expected_label);
+        assert!((amplitude - expected_amplitude).norm() < 1e-12);
+    }
+}
+
+/// Prepares a state with irrational amplitudes and then applies `SX` a multiple of four times,
+/// which is the identity in exact arithmetic. Returns the states before and after.
+fn repeated_sx<P: Precision>(
+    mut sim: DenseSim<P>,
+) -> (Vec<(BigUint, Complex<f64>)>, Vec<(BigUint, Complex<f64>)>) {
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.ry(0.3, q0);
+    sim.t(q0);
+    sim.cz(q1, q0);
+    let (before, _) = sim.capture_quantum_state();
+    for _ in 0..4000 {
+        sim.sx(q0);
+        // The controlled gate does nothing with its control in zero, but it makes each `SX` be
+        // applied to the state rather than fused into one identity matrix.
+        sim.cz(q1, q0);
+    }
+    let (after, _) = sim.capture_quantum_state();
+    (before, after)
+}
+
+#[test]
+fn extended_precision_does_not_drift_over_deep_circuit() {
+    let (before, after) = repeated_sx(DenseSim::<DoubleDouble>::with_precision());
+    assert_eq!(after, before);
+
+    let (before, after) = repeated_sx(DenseSim::new());
+    for ((_, amplitude), (_, expected)) in after.iter().zip(&before) {
+        assert!((amplitude - expected).norm() < 1e-10);
+    }
+}
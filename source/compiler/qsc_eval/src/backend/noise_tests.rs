@@ -3,7 +3,7 @@
 
 use crate::{
     backend::{Backend, SparseSim},
-    noise::PauliNoise,
+    noise::{NoiseConfig, PauliNoise},
     state::{fmt_complex, format_state_id},
     val,
 };
@@ -227,3 +227,82 @@ fn measure_with_loss_returns_loss() {
         "Expected measurement with loss to return None"
     );
 }
+
+#[test]
+fn noise_config_from_json() {
+    let config = NoiseConfig::from_json(
+        r#"{
+            "pauli": { "z": 0.01 },
+            "gates": { "cx": { "x": 0.02, "y": 0.02, "z": 0.02 } },
+            "readout": 0.05,
+            "relaxation": { "t1": 100.0, "t2": 50.0, "durations": { "h": 1.0 } }
+        }"#,
+    )
+    .expect("noise config should be valid");
+    let names = config.gate_names().collect::<Vec<_>>();
+    assert_eq!(names, vec!["cx", "h"]);
+    let h = config.noise_for_gate("h").expect("h noise should be valid");
+    assert!(
+        h.distribution[0] > 0.0,
+        "Expected relaxation to add X errors."
+    );
+    assert!(
+        h.distribution[2] > 0.01,
+        "Expected relaxation to add to the default noise."
+    );
+    let x = config.noise_for_gate("x").expect("x noise should be valid");
+    assert!(
+        (x.distribution[2] - 0.01).abs() < f64::EPSILON,
+        "Expected default noise for unconfigured gate."
+    );
+}
+
+#[test]
+fn noise_config_rejects_invalid_values() {
+    let _ = NoiseConfig::from_json(r#"{ "readout": 1.5 }"#)
+        .expect_err("readout probability above 1 should result in error.");
+    let _ = NoiseConfig::from_json(r#"{ "gates": { "h": { "x": 0.6, "z": 0.6 } } }"#)
+        .expect_err("gate probabilities summing above 1 should result in error.");
+    let _ = NoiseConfig::from_json(r#"{ "relaxation": { "t1": 10.0, "t2": 30.0 } }"#)
+        .expect_err("T2 above 2 * T1 should result in error.");
+    let _ = NoiseConfig::from_json(r#"{ "unknown": 0.1 }"#)
+        .expect_err("unknown fields should result in error.");
+}
+
+#[test]
+fn noise_config_gate_specific_noise() {
+    let config = NoiseConfig::from_json(r#"{ "gates": { "x": { "x": 1.0 } } }"#)
+        .expect("noise config should be valid");
+    let mut sim = SparseSim::new();
+    sim.set_noise_config(&config)
+        .expect("noise config should be accepted");
+    assert!(!sim.is_noiseless(), "Expected noisy simulator.");
+    let q = sim.qubit_allocate();
+    sim.x(q); // Followed by X. So, no op.
+    check_state(&mut sim, &expect!["|0⟩: 1.0000+0.0000𝑖 "]);
+    sim.h(q); // No noise configured for H.
+    check_state(
+        &mut sim,
+        &expect!["|0⟩: 0.7071+0.0000𝑖 |1⟩: 0.7071+0.0000𝑖 "],
+    );
+}
+
+#[test]
+fn noise_config_readout_error() {
+    let config =
+        NoiseConfig::from_json(r#"{ "readout": 1.0 }"#).expect("noise config should be valid");
+    let mut sim = SparseSim::new();
+    sim.set_noise_config(&config)
+        .expect("noise config should be accepted");
+    let q = sim.qubit_allocate();
+    for _ in 0..100 {
+        assert!(
+            sim.mresetz(q).unwrap_bool(),
+            "Expected One for 100% readout error."
+        );
+    }
+    assert!(
+        sim.qubit_release(q),
+        "Expected readout error not to change the qubit state."
+    );
+}
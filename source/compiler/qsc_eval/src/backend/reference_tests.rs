@@ -0,0 +1,326 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Randomized tests that run small circuits on [`SparseSim`] and compare the resulting state
+//! against a dense state-vector reference. Each circuit is generated from a fixed seed so that
+//! failures are reproducible, and states are compared up to global phase.
+
+use crate::backend::{Backend, SparseSim};
+use num_complex::Complex64;
+use num_traits::ToPrimitive;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_4, PI};
+
+const SEEDS: u64 = 200;
+const MAX_QUBITS: usize = 4;
+const GATES_PER_CIRCUIT: usize = 24;
+
+#[derive(Clone, Copy, Debug)]
+enum Gate {
+    H(usize),
+    X(usize),
+    Y(usize),
+    Z(usize),
+    S(usize),
+    SAdj(usize),
+    T(usize),
+    TAdj(usize),
+    Rx(f64, usize),
+    Ry(f64, usize),
+    Rz(f64, usize),
+    Rxx(f64, usize, usize),
+    Ryy(f64, usize, usize),
+    Rzz(f64, usize, usize),
+    Cx(usize, usize),
+    Cy(usize, usize),
+    Cz(usize, usize),
+    Swap(usize, usize),
+    Ccx(usize, usize, usize),
+}
+
+type Matrix = [[Complex64; 2]; 2];
+
+fn c(re: f64, im: f64) -> Complex64 {
+    Complex64::new(re, im)
+}
+
+fn diag(d0: Complex64, d1: Complex64) -> Matrix {
+    [[d0, c(0.0, 0.0)], [c(0.0, 0.0), d1]]
+}
+
+fn h() -> Matrix {
+    [
+        [c(FRAC_1_SQRT_2, 0.0), c(FRAC_1_SQRT_2, 0.0)],
+        [c(FRAC_1_SQRT_2, 0.0), c(-FRAC_1_SQRT_2, 0.0)],
+    ]
+}
+
+fn x() -> Matrix {
+    [[c(0.0, 0.0), c(1.0, 0.0)], [c(1.0, 0.0), c(0.0, 0.0)]]
+}
+
+fn y() -> Matrix {
+    [[c(0.0, 0.0), c(0.0, -1.0)], [c(0.0, 1.0), c(0.0, 0.0)]]
+}
+
+fn z() -> Matrix {
+    diag(c(1.0, 0.0), c(-1.0, 0.0))
+}
+
+fn phase(theta: f64) -> Matrix {
+    diag(c(1.0, 0.0), Complex64::from_polar(1.0, theta))
+}
+
+fn rx(theta: f64) -> Matrix {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [[c(cos, 0.0), c(0.0, -sin)], [c(0.0, -sin), c(cos, 0.0)]]
+}
+
+fn ry(theta: f64) -> Matrix {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [[c(cos, 0.0), c(-sin, 0.0)], [c(sin, 0.0), c(cos, 0.0)]]
+}
+
+fn rz(theta: f64) -> Matrix {
+    diag(
+        Complex64::from_polar(1.0, -theta / 2.0),
+        Complex64::from_polar(1.0, theta / 2.0),
+    )
+}
+
+/// A dense state vector where qubit `q` is stored in bit `bits[q]` of the basis state index.
+#[derive(Clone)]
+struct Reference {
+    amplitudes: Vec<Complex64>,
+    bits: Vec<usize>,
+}
+
+impl Reference {
+    fn new(bits: Vec<usize>) -> Self {
+        let mut amplitudes = vec![c(0.0, 0.0); 1 << bits.len()];
+        amplitudes[0] = c(1.0, 0.0);
+        Self { amplitudes, bits }
+    }
+
+    fn mask(&self, q: usize) -> usize {
+        1 << self.bits[q]
+    }
+
+    fn apply(&mut self, ctls: &[usize], q: usize, m: &Matrix) {
+        let ctl_mask = ctls.iter().fold(0, |mask, &ctl| mask | self.mask(ctl));
+        let target = self.mask(q);
+        for i in 0..self.amplitudes.len() {
+            if i & target == 0 && i & ctl_mask == ctl_mask {
+                let j = i | target;
+                let (a0, a1) = (self.amplitudes[i], self.amplitudes[j]);
+                self.amplitudes[i] = m[0][0] * a0 + m[0][1] * a1;
+                self.amplitudes[j] = m[1][0] * a0 + m[1][1] * a1;
+            }
+        }
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        let (m0, m1) = (self.mask(q0), self.mask(q1));
+        for i in 0..self.amplitudes.len() {
+            if i & m0 != 0 && i & m1 == 0 {
+                self.amplitudes.swap(i, i ^ m0 ^ m1);
+            }
+        }
+    }
+
+    /// Applies `exp(-iθ/2 P⊗P)` for the given Pauli `P`, which is `cos(θ/2) I - i sin(θ/2) P⊗P`.
+    fn pauli_pair_rotation(&mut self, theta: f64, pauli: &Matrix, q0: usize, q1: usize) {
+        let mut rotated = self.clone();
+        rotated.apply(&[], q0, pauli);
+        rotated.apply(&[], q1, pauli);
+        let (sin, cos) = (theta / 2.0).sin_cos();
+        for (amp, rotated) in self.amplitudes.iter_mut().zip(rotated.amplitudes) {
+            *amp = *amp * cos - rotated * c(0.0, sin);
+        }
+    }
+
+    fn run(&mut self, gate: Gate) {
+        match gate {
+            Gate::H(q) => self.apply(&[], q, &h()),
+            Gate::X(q) => self.apply(&[], q, &x()),
+            Gate::Y(q) => self.apply(&[], q, &y()),
+            Gate::Z(q) => self.apply(&[], q, &z()),
+            Gate::S(q) => self.apply(&[], q, &phase(PI / 2.0)),
+            Gate::SAdj(q) => self.apply(&[], q, &phase(-PI / 2.0)),
+            Gate::T(q) => self.apply(&[], q, &phase(FRAC_PI_4)),
+            Gate::TAdj(q) => self.apply(&[], q, &phase(-FRAC_PI_4)),
+            Gate::Rx(theta, q) => self.apply(&[], q, &rx(theta)),
+            Gate::Ry(theta, q) => self.apply(&[], q, &ry(theta)),
+            Gate::Rz(theta, q) => self.apply(&[], q, &rz(theta)),
+            Gate::Rxx(theta, q0, q1) => self.pauli_pair_rotation(theta, &x(), q0, q1),
+            Gate::Ryy(theta, q0, q1) => self.pauli_pair_rotation(theta, &y(), q0, q1),
+            Gate::Rzz(theta, q0, q1) => self.pauli_pair_rotation(theta, &z(), q0, q1),
+            Gate::Cx(ctl, q) => self.apply(&[ctl], q, &x()),
+            Gate::Cy(ctl, q) => self.apply(&[ctl], q, &y()),
+            Gate::Cz(ctl, q) => self.apply(&[ctl], q, &z()),
+            Gate::Swap(q0, q1) => self.swap(q0, q1),
+            Gate::Ccx(ctl0, ctl1, q) => self.apply(&[ctl0, ctl1], q, &x()),
+        }
+    }
+}
+
+fn run_on_sim(sim: &mut SparseSim, ids: &[usize], gate: Gate) {
+    match gate {
+        Gate::H(q) => sim.h(ids[q]),
+        Gate::X(q) => sim.x(ids[q]),
+        Gate::Y(q) => sim.y(ids[q]),
+        Gate::Z(q) => sim.z(ids[q]),
+        Gate::S(q) => sim.s(ids[q]),
+        Gate::SAdj(q) => sim.sadj(ids[q]),
+        Gate::T(q) => sim.t(ids[q]),
+        Gate::TAdj(q) => sim.tadj(ids[q]),
+        Gate::Rx(theta, q) => sim.rx(theta, ids[q]),
+        Gate::Ry(theta, q) => sim.ry(theta, ids[q]),
+        Gate::Rz(theta, q) => sim.rz(theta, ids[q]),
+        Gate::Rxx(theta, q0, q1) => sim.rxx(theta, ids[q0], ids[q1]),
+        Gate::Ryy(theta, q0, q1) => sim.ryy(theta, ids[q0], ids[q1]),
+        Gate::Rzz(theta, q0, q1) => sim.rzz(theta, ids[q0], ids[q1]),
+        Gate::Cx(ctl, q) => sim.cx(ids[ctl], ids[q]),
+        Gate::Cy(ctl, q) => sim.cy(ids[ctl], ids[q]),
+        Gate::Cz(ctl, q) => sim.cz(ids[ctl], ids[q]),
+        Gate::Swap(q0, q1) => sim.swap(ids[q0], ids[q1]),
+        Gate::Ccx(ctl0, ctl1, q) => sim.ccx(ids[ctl0], ids[ctl1], ids[q]),
+    }
+}
+
+/// Picks `N` distinct qubits out of `qubits`.
+fn distinct<const N: usize>(rng: &mut StdRng, qubits: usize) -> [usize; N] {
+    let mut picked = [0; N];
+    for i in 0..N {
+        picked[i] = loop {
+            let q = rng.gen_range(0..qubits);
+            if !picked[..i].contains(&q) {
+                break q;
+            }
+        };
+    }
+    picked
+}
+
+fn random_gate(rng: &mut StdRng, qubits: usize) -> Gate {
+    let kinds = match qubits {
+        1 => 11,
+        2 => 18,
+        _ => 19,
+    };
+    let theta = rng.gen_range(-PI..PI);
+    match rng.gen_range(0..kinds) {
+        0 => Gate::H(rng.gen_range(0..qubits)),
+        1 => Gate::X(rng.gen_range(0..qubits)),
+        2 => Gate::Y(rng.gen_range(0..qubits)),
+        3 => Gate::Z(rng.gen_range(0..qubits)),
+        4 => Gate::S(rng.gen_range(0..qubits)),
+        5 => Gate::SAdj(rng.gen_range(0..qubits)),
+        6 => Gate::T(rng.gen_range(0..qubits)),
+        7 => Gate::TAdj(rng.gen_range(0..qubits)),
+        8 => Gate::Rx(theta, rng.gen_range(0..qubits)),
+        9 => Gate::Ry(theta, rng.gen_range(0..qubits)),
+        10 => Gate::Rz(theta, rng.gen_range(0..qubits)),
+        11 => {
+            let [q0, q1] = distinct(rng, qubits);
+            Gate::Rxx(theta, q0, q1)
+        }
+        12 => {
+            let [q0, q1] = distinct(rng, qubits);
+            Gate::Ryy(theta, q0, q1)
+        }
+        13 => {
+            let [q0, q1] = distinct(rng, qubits);
+            Gate::Rzz(theta, q0, q1)
+        }
+        14 => {
+            let [ctl, q] = distinct(rng, qubits);
+            Gate::Cx(ctl, q)
+        }
+        15 => {
+            let [ctl, q] = distinct(rng, qubits);
+            Gate::Cy(ctl, q)
+        }
+        16 => {
+            let [ctl, q] = distinct(rng, qubits);
+            Gate::Cz(ctl, q)
+        }
+        17 => {
+            let [q0, q1] = distinct(rng, qubits);
+            Gate::Swap(q0, q1)
+        }
+        _ => {
+            let [ctl0, ctl1, q] = distinct(rng, qubits);
+            Gate::Ccx(ctl0, ctl1, q)
+        }
+    }
+}
+
+/// Captures the simulator state as a dense vector indexed the same way as [`Reference`].
+fn dense_state(sim: &mut SparseSim, qubits: usize) -> Vec<Complex64> {
+    let (state, count) = sim.capture_quantum_state();
+    assert_eq!(count, qubits);
+    let mut dense = vec![c(0.0, 0.0); 1 << qubits];
+    for (index, amplitude) in state {
+        let index = index.to_usize().expect("state index should fit in usize");
+        dense[index] = amplitude;
+    }
+    dense
+}
+
+/// Finds which bit of the captured state index holds each qubit, by flipping one qubit at a time.
+fn state_bits(sim: &mut SparseSim, ids: &[usize]) -> Vec<usize> {
+    ids.iter()
+        .map(|&id| {
+            sim.x(id);
+            let state = dense_state(sim, ids.len());
+            sim.x(id);
+            let index = state
+                .iter()
+                .position(|amplitude| amplitude.norm() > 0.5)
+                .expect("state should have a basis state with nonzero amplitude");
+            assert!(index.is_power_of_two(), "a single qubit should be flipped");
+            index.trailing_zeros() as usize
+        })
+        .collect()
+}
+
+/// Returns `|⟨expected|actual⟩|`, which is one when the states match up to global phase.
+fn overlap(expected: &[Complex64], actual: &[Complex64]) -> f64 {
+    expected
+        .iter()
+        .zip(actual)
+        .map(|(e, a)| e.conj() * a)
+        .sum::<Complex64>()
+        .norm()
+}
+
+#[test]
+fn random_circuits_match_dense_reference() {
+    for seed in 0..SEEDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let qubits = rng.gen_range(1..=MAX_QUBITS);
+        let circuit = (0..GATES_PER_CIRCUIT)
+            .map(|_| random_gate(&mut rng, qubits))
+            .collect::<Vec<_>>();
+
+        let mut sim = SparseSim::new();
+        let ids = (0..qubits)
+            .map(|_| sim.qubit_allocate())
+            .collect::<Vec<_>>();
+        let mut reference = Reference::new(state_bits(&mut sim, &ids));
+        for &gate in &circuit {
+            run_on_sim(&mut sim, &ids, gate);
+            reference.run(gate);
+        }
+
+        let actual = dense_state(&mut sim, qubits);
+        let overlap = overlap(&reference.amplitudes, &actual);
+        assert!(
+            (overlap - 1.0).abs() < 1e-9,
+            "seed {seed}: simulator state diverged from reference (overlap {overlap})\n\
+             circuit: {circuit:?}\nexpected: {:?}\nactual: {actual:?}",
+            reference.amplitudes
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::backend::{Backend, SimulatorStats, SparseSim, Tolerance};
+
+#[test]
+fn released_qubit_ids_are_reused() {
+    let mut sim = SparseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    assert!(sim.qubit_release(q0));
+    let q2 = sim.qubit_allocate();
+    assert_eq!(q0, q2);
+    assert!(sim.qubit_release(q1));
+    assert!(sim.qubit_release(q2));
+}
+
+#[test]
+fn state_stays_bounded_across_allocate_release_cycles() {
+    let mut sim = SparseSim::new();
+    let kept = sim.qubit_allocate();
+    sim.h(kept);
+    for _ in 0..1000 {
+        let qubits = [
+            sim.qubit_allocate(),
+            sim.qubit_allocate(),
+            sim.qubit_allocate(),
+        ];
+        sim.h(qubits[0]);
+        sim.cx(qubits[0], qubits[1]);
+        sim.cx(qubits[1], qubits[2]);
+        for q in qubits {
+            sim.mresetz(q);
+        }
+        for q in qubits {
+            assert!(q <= 3, "qubit id {q} should have been reused");
+            assert!(sim.qubit_release(q));
+        }
+        let (state, qubit_count) = sim.capture_quantum_state();
+        assert_eq!(qubit_count, 1);
+        assert_eq!(state.len(), 2);
+    }
+}
+
+#[test]
+fn qubit_is_zero_uses_configured_tolerance() {
+    let mut sim = SparseSim::new();
+    let q = sim.qubit_allocate();
+    // Leaves a probability of about 2.5e-9 of measuring `One`.
+    sim.ry(1e-4, q);
+    assert!(!sim.qubit_is_zero(q));
+    sim.tolerance = Tolerance {
+        absolute: 1e-8,
+        relative: 0.0,
+    };
+    assert!(sim.qubit_is_zero(q));
+    sim.tolerance = Tolerance {
+        absolute: 0.0,
+        relative: 1e-8,
+    };
+    assert!(sim.qubit_is_zero(q));
+}
+
+#[test]
+fn qubit_is_zero_finds_qubit_after_lower_id_is_released() {
+    let mut sim = SparseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    let q2 = sim.qubit_allocate();
+    assert!(sim.qubit_release(q1));
+    sim.x(q2);
+    assert!(sim.qubit_is_zero(q0));
+    assert!(!sim.qubit_is_zero(q2));
+}
+
+#[test]
+fn stats_track_qubit_and_state_high_water_marks() {
+    let mut sim = SparseSim::new();
+    sim.collect_stats();
+    let qs = [
+        sim.qubit_allocate(),
+        sim.qubit_allocate(),
+        sim.qubit_allocate(),
+    ];
+    for q in qs {
+        sim.h(q);
+    }
+    for q in qs {
+        sim.mresetz(q);
+        assert!(sim.qubit_release(q));
+    }
+    let q = sim.qubit_allocate();
+    assert_eq!(
+        sim.stats,
+        Some(SimulatorStats {
+            qubit_allocations: 4,
+            qubits_in_use: 1,
+            peak_qubits: 3,
+            peak_state_size: 8,
+        })
+    );
+    assert!(sim.qubit_release(q));
+}
+
+#[test]
+fn stats_bound_state_size_by_splitting_gates_and_qubits_in_use() {
+    let mut sim = SparseSim::new();
+    sim.collect_stats();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.cx(q0, q1);
+    sim.x(q1);
+    assert_eq!(sim.stats.map(|stats| stats.peak_state_size), Some(2));
+    sim.mresetz(q0);
+    sim.mresetz(q1);
+    assert!(sim.qubit_release(q1));
+    sim.h(q0);
+    sim.h(q0);
+    assert_eq!(sim.stats.map(|stats| stats.peak_state_size), Some(2));
+    assert!(sim.qubit_release(q0));
+}
+
+#[test]
+fn stats_are_not_collected_by_default() {
+    let mut sim = SparseSim::new();
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    sim.mresetz(q);
+    assert!(sim.qubit_release(q));
+    assert_eq!(sim.stats, None);
+}
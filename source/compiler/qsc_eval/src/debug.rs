@@ -1,8 +1,11 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use num_bigint::BigUint;
+use num_complex::Complex64;
 use qsc_data_structures::span::Span;
 
+use crate::intrinsic::utils::reduced_density_matrix;
 use qsc_data_structures::functors::FunctorApp;
 use qsc_fir::fir::{PackageId, StoreItemId};
 
@@ -30,6 +33,11 @@ impl CallStack {
         self.frames.len()
     }
 
+    #[must_use]
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
     #[must_use]
     pub fn to_frames(&self) -> Vec<Frame> {
         self.frames.clone()
@@ -43,3 +51,86 @@ impl CallStack {
         self.frames.pop()
     }
 }
+
+/// Whether an intrinsic can change the state of the qubits it is passed, so that qubit watches
+/// need to be checked after it. Diagnostics only read the state.
+#[must_use]
+pub fn is_state_changing_intrinsic(name: &str) -> bool {
+    !matches!(
+        name,
+        "DumpMachine"
+            | "DumpRegister"
+            | "DumpMatrix"
+            | "DumpBlochVector"
+            | "CheckZero"
+            | "CheckSeparable"
+            | "PauliExpectation"
+            | "EntanglementEntropy"
+            | "RegisterAmplitudes"
+    )
+}
+
+/// The change to a watched qubit that stops execution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QubitWatchKind {
+    /// Stops whenever the reduced state of the qubit changes.
+    StateChange,
+    /// Stops when the qubit becomes entangled with other qubits.
+    Entanglement,
+}
+
+/// A data breakpoint on a qubit, checked after each intrinsic.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct QubitWatch {
+    pub qubit: usize,
+    pub kind: QubitWatchKind,
+    pub fingerprint: Option<QubitFingerprint>,
+}
+
+impl QubitWatch {
+    /// Updates the fingerprint of the watched qubit from the given state, returning whether the
+    /// change is one that should stop execution.
+    pub fn update(&mut self, state: &[(BigUint, Complex64)], qubit_count: usize) -> bool {
+        let fingerprint = QubitFingerprint::new(self.qubit, state, qubit_count);
+        let previous = std::mem::replace(&mut self.fingerprint, fingerprint);
+        match self.kind {
+            QubitWatchKind::StateChange => previous != fingerprint,
+            QubitWatchKind::Entanglement => {
+                fingerprint.is_some_and(|f| f.entangled) && previous.is_none_or(|f| !f.entangled)
+            }
+        }
+    }
+}
+
+/// The reduced density matrix of a single qubit, rounded so that numerical noise does not
+/// register as a change.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct QubitFingerprint {
+    rho: [i64; 4],
+    entangled: bool,
+}
+
+impl QubitFingerprint {
+    const SCALE: f64 = 1e9;
+
+    /// Computes the fingerprint of the given qubit, or `None` if it is not part of the state.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn new(qubit: usize, state: &[(BigUint, Complex64)], qubit_count: usize) -> Option<Self> {
+        if qubit >= qubit_count {
+            return None;
+        }
+        let rho = reduced_density_matrix(&[qubit], state, qubit_count);
+        // The purity of the reduced state is below one exactly when the qubit is entangled.
+        let purity = rho[0][0].norm_sqr() + rho[1][1].norm_sqr() + 2.0 * rho[0][1].norm_sqr();
+        let round = |value: f64| (value * Self::SCALE).round() as i64;
+        Some(Self {
+            rho: [
+                round(rho[0][0].re),
+                round(rho[1][1].re),
+                round(rho[0][1].re),
+                round(rho[0][1].im),
+            ],
+            entangled: round(purity) < round(1.0),
+        })
+    }
+}
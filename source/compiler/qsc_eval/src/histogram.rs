@@ -0,0 +1,129 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::val::Value;
+use rustc_hash::FxHashMap;
+use std::{
+    cmp::Reverse,
+    fmt::{self, Display, Formatter},
+};
+
+/// The z-score for a 95% confidence level.
+pub const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Aggregated results of running a program for a number of shots, grouped by value.
+/// Values are compared structurally, so tuple and array results are grouped element-wise.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Histogram {
+    buckets: Vec<(Value, u64)>,
+    /// The position of each value's bucket, keyed by the value's structural debug representation
+    /// since values cannot be hashed.
+    indices: FxHashMap<String, usize>,
+    shots: u64,
+}
+
+impl Histogram {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the result of a single shot.
+    pub fn record(&mut self, value: Value) {
+        self.shots += 1;
+        let key = format!("{value:?}");
+        if let Some(&index) = self.indices.get(&key) {
+            self.buckets[index].1 += 1;
+        } else {
+            self.indices.insert(key, self.buckets.len());
+            self.buckets.push((value, 1));
+        }
+    }
+
+    /// The total number of recorded shots.
+    #[must_use]
+    pub fn shots(&self) -> u64 {
+        self.shots
+    }
+
+    /// The distinct values with their counts, in the order they were first recorded.
+    #[must_use]
+    pub fn buckets(&self) -> &[(Value, u64)] {
+        &self.buckets
+    }
+
+    /// The distinct values with their counts, most frequent first.
+    #[must_use]
+    pub fn sorted_buckets(&self) -> Vec<(Value, u64)> {
+        let mut buckets = self.buckets.clone();
+        buckets.sort_by_key(|(_, count)| Reverse(*count));
+        buckets
+    }
+
+    /// The number of shots that produced the given value.
+    #[must_use]
+    pub fn count(&self, value: &Value) -> u64 {
+        self.indices
+            .get(&format!("{value:?}"))
+            .map_or(0, |&index| self.buckets[index].1)
+    }
+
+    /// The fraction of shots that produced the given value.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn frequency(&self, value: &Value) -> f64 {
+        if self.shots == 0 {
+            return 0.0;
+        }
+        self.count(value) as f64 / self.shots as f64
+    }
+
+    /// The Wilson score interval for the probability of the given value, for the confidence
+    /// level corresponding to the z-score `z` (for example, [`Z_95`]).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn confidence_interval(&self, value: &Value, z: f64) -> (f64, f64) {
+        if self.shots == 0 {
+            return (0.0, 1.0);
+        }
+        let n = self.shots as f64;
+        let p = self.frequency(value);
+        let z2 = z * z;
+        let denominator = 1.0 + z2 / n;
+        let center = (p + z2 / (2.0 * n)) / denominator;
+        let half_width = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+        (
+            (center - half_width).max(0.0),
+            (center + half_width).min(1.0),
+        )
+    }
+}
+
+impl FromIterator<Value> for Histogram {
+    fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
+        let mut histogram = Self::new();
+        for value in iter {
+            histogram.record(value);
+        }
+        histogram
+    }
+}
+
+impl Display for Histogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (value, count) in self.sorted_buckets() {
+            let (low, high) = self.confidence_interval(&value, Z_95);
+            writeln!(
+                f,
+                "{value}: {count} ({:.2}%, 95% CI {:.2}%..{:.2}%)",
+                self.frequency(&value) * 100.0,
+                low * 100.0,
+                high * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{Histogram, Z_95};
+use crate::val::Value;
+use expect_test::expect;
+
+fn pair(a: bool, b: bool) -> Value {
+    Value::Tuple(vec![Value::Bool(a), Value::Bool(b)].into(), None)
+}
+
+#[test]
+fn groups_structured_values() {
+    let histogram = [
+        pair(true, false),
+        pair(false, false),
+        pair(true, false),
+        pair(true, false),
+    ]
+    .into_iter()
+    .collect::<Histogram>();
+    assert_eq!(histogram.shots(), 4);
+    assert_eq!(histogram.buckets().len(), 2);
+    assert_eq!(histogram.count(&pair(true, false)), 3);
+    assert_eq!(histogram.count(&pair(true, true)), 0);
+    assert!((histogram.frequency(&pair(false, false)) - 0.25).abs() < f64::EPSILON);
+}
+
+#[test]
+fn confidence_interval_contains_frequency() {
+    let histogram = std::iter::repeat_n(Value::RESULT_ONE, 30)
+        .chain(std::iter::repeat_n(Value::RESULT_ZERO, 70))
+        .collect::<Histogram>();
+    let (low, high) = histogram.confidence_interval(&Value::RESULT_ONE, Z_95);
+    assert!(low < 0.3 && 0.3 < high, "interval should contain 0.3");
+    assert!(low > 0.2 && high < 0.4, "interval should be narrow");
+}
+
+#[test]
+fn empty_histogram_has_no_information() {
+    let histogram = Histogram::new();
+    assert!(histogram.frequency(&Value::RESULT_ONE).abs() < f64::EPSILON);
+    let (low, high) = histogram.confidence_interval(&Value::RESULT_ONE, Z_95);
+    assert!(low.abs() < f64::EPSILON && (high - 1.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn display_sorts_by_count() {
+    let histogram = [Value::Int(1), Value::Int(2), Value::Int(2), Value::Int(2)]
+        .into_iter()
+        .collect::<Histogram>();
+    expect![[r#"
+        2: 3 (75.00%, 95% CI 30.06%..95.44%)
+        1: 1 (25.00%, 95% CI 4.56%..69.94%)
+    "#]]
+    .assert_eq(&histogram.to_string());
+}
+
+#[test]
+fn groups_values_that_display_alike_separately() {
+    let strings = |a: &str, b: &str| {
+        Value::Tuple(
+            vec![Value::String(a.into()), Value::String(b.into())].into(),
+            None,
+        )
+    };
+    let histogram = [
+        strings("a, b", "c"),
+        strings("a", "b, c"),
+        strings("a", "b, c"),
+    ]
+    .into_iter()
+    .collect::<Histogram>();
+    assert_eq!(histogram.buckets().len(), 2);
+    assert_eq!(histogram.count(&strings("a, b", "c")), 1);
+    assert_eq!(histogram.count(&strings("a", "b, c")), 2);
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-mod utils;
+pub(crate) mod utils;
 
 #[cfg(test)]
 mod tests;
@@ -11,13 +11,98 @@ use crate::{
     backend::{Backend, TracingBackend},
     debug::Frame,
     error::PackageSpan,
-    output::Receiver,
+    output::{Receiver, send_state},
+    state::pauli_expectation,
     val::{self, Value, unwrap_tuple},
 };
 use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_traits::{ToPrimitive, Zero};
+use qsc_fir::fir::{Pauli, StoreItemId};
 use rand::{Rng, rngs::StdRng};
 use rustc_hash::{FxHashMap, FxHashSet};
-use std::convert::TryFrom;
+use std::{convert::TryFrom, fmt::Debug};
+
+/// A host-provided implementation for a `body intrinsic` callable.
+pub type HostIntrinsic = dyn Fn(Value) -> Result<Value, String>;
+
+/// A registry of host-provided intrinsic implementations, keyed by the fully qualified
+/// name of the callable they implement (for example, `Test.Foo`).
+#[derive(Clone, Default)]
+pub struct HostIntrinsics {
+    map: FxHashMap<Rc<str>, Rc<HostIntrinsic>>,
+}
+
+impl HostIntrinsics {
+    /// Registers `f` as the implementation of the intrinsic callable with the given
+    /// fully qualified name, replacing any previous registration for that name.
+    pub fn register(
+        &mut self,
+        name: impl Into<Rc<str>>,
+        f: impl Fn(Value) -> Result<Value, String> + 'static,
+    ) {
+        self.map.insert(name.into(), Rc::new(f));
+    }
+
+    /// Gets the implementation registered for the given fully qualified name, if any.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Rc<HostIntrinsic>> {
+        self.map.get(name)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Registers implementations of the `Std.IO` file intrinsics backed by the host file system.
+    /// Without them, calls to those intrinsics fail.
+    pub fn enable_file_io(&mut self) {
+        self.register("Std.IO.ReadTextFile", |arg| {
+            let path = arg.unwrap_string();
+            std::fs::read_to_string(path.as_ref())
+                .map(|contents| Value::String(contents.into()))
+                .map_err(|e| format!("could not read `{path}`: {e}"))
+        });
+        self.register("Std.IO.WriteTextFile", |arg| {
+            let [path, contents] = unwrap_tuple(arg);
+            let path = path.unwrap_string();
+            std::fs::write(path.as_ref(), contents.unwrap_string().as_bytes())
+                .map(|()| Value::unit())
+                .map_err(|e| format!("could not write `{path}`: {e}"))
+        });
+    }
+
+    /// Registers implementations of the `Std.Environment` intrinsics that return the given
+    /// command-line arguments and read the host's environment variables.
+    /// Without them, calls to those intrinsics fail.
+    pub fn enable_environment_access(&mut self, args: Vec<String>) {
+        let args = Value::Array(
+            args.into_iter()
+                .map(|arg| Value::String(arg.into()))
+                .collect::<Vec<_>>()
+                .into(),
+        );
+        self.register("Std.Environment.CommandLineArguments", move |_| {
+            Ok(args.clone())
+        });
+        self.register("Std.Environment.TryEnvironmentVariable", |arg| {
+            Ok(match std::env::var(arg.unwrap_string().as_ref()) {
+                Ok(value) => Value::Tuple(
+                    [Value::Bool(true), Value::String(value.into())].into(),
+                    None,
+                ),
+                Err(_) => Value::Tuple([Value::Bool(false), Value::String("".into())].into(), None),
+            })
+        });
+    }
+}
+
+impl Debug for HostIntrinsics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.map.keys()).finish()
+    }
+}
 
 #[allow(clippy::too_many_lines)]
 #[allow(clippy::too_many_arguments)]
@@ -57,28 +142,17 @@ pub(crate) fn call<B: Backend>(
         }
         "DumpMachine" => {
             let (state, qubit_count) = sim.capture_quantum_state();
-            match out.state(state, qubit_count) {
+            match send_state(out, state, qubit_count) {
                 Ok(()) => Ok(Value::unit()),
                 Err(_) => Err(Error::OutputFail(name_span)),
             }
         }
         "DumpRegister" => {
-            let qubits = arg.unwrap_array();
-            let qubits_len = qubits.len();
-            let qubits = qubits
-                .iter()
-                .filter_map(|q| q.clone().unwrap_qubit().try_deref().map(|q| q.0))
-                .collect::<Vec<_>>();
-            if qubits.len() != qubits_len {
-                return Err(Error::QubitUsedAfterRelease(arg_span));
-            }
-            if qubits.len() != qubits.iter().collect::<FxHashSet<_>>().len() {
-                return Err(Error::QubitUniqueness(arg_span));
-            }
+            let qubits = unwrap_register(arg, arg_span)?;
             let (state, qubit_count) = sim.capture_quantum_state();
             let state = utils::split_state(&qubits, &state, qubit_count)
                 .map_err(|()| Error::QubitsNotSeparable(arg_span))?;
-            match out.state(state, qubits.len()) {
+            match send_state(out, state, qubits.len()) {
                 Ok(()) => Ok(Value::unit()),
                 Err(_) => Err(Error::OutputFail(name_span)),
             }
@@ -120,6 +194,95 @@ pub(crate) fn call<B: Backend>(
                     .0,
             ),
         )),
+        "PauliExpectation" => {
+            let [paulis, qubits] = unwrap_tuple(arg);
+            let paulis = paulis.unwrap_array();
+            let qubits = qubits.unwrap_array();
+            if paulis.len() != qubits.len() {
+                return Err(Error::IntrinsicFail(
+                    name.to_string(),
+                    "the number of Paulis must match the number of qubits".to_string(),
+                    arg_span,
+                ));
+            }
+            let qubits = qubits
+                .iter()
+                .filter_map(|q| q.clone().unwrap_qubit().try_deref().map(|q| q.0))
+                .collect::<Vec<_>>();
+            if qubits.len() != paulis.len() {
+                return Err(Error::QubitUsedAfterRelease(arg_span));
+            }
+            if qubits.len() != qubits.iter().collect::<FxHashSet<_>>().len() {
+                return Err(Error::QubitUniqueness(arg_span));
+            }
+            let paulis = paulis
+                .iter()
+                .map(|p| p.clone().unwrap_pauli())
+                .zip(qubits)
+                .collect::<Vec<_>>();
+            let (state, qubit_count) = sim.capture_quantum_state();
+            Ok(Value::Double(pauli_expectation(
+                &state,
+                qubit_count,
+                &paulis,
+            )))
+        }
+        "CheckSeparable" => {
+            let qubits = unwrap_register(arg, arg_span)?;
+            let (state, qubit_count) = sim.capture_quantum_state();
+            Ok(Value::Bool(
+                utils::split_state(&qubits, &state, qubit_count).is_ok(),
+            ))
+        }
+        "EntanglementEntropy" => {
+            let qubits = unwrap_register(arg, arg_span)?;
+            let (state, qubit_count) = sim.capture_quantum_state();
+            let rho = utils::reduced_density_matrix(&qubits, &state, qubit_count);
+            Ok(Value::Double(utils::von_neumann_entropy(&rho)))
+        }
+        "RegisterAmplitudes" => {
+            let qubits = unwrap_register(arg, arg_span)?;
+            let (state, qubit_count) = sim.capture_quantum_state();
+            let state = utils::split_state(&qubits, &state, qubit_count)
+                .map_err(|()| Error::QubitsNotSeparable(arg_span))?;
+            let mut amplitudes = vec![Complex64::zero(); 1 << qubits.len()];
+            for (label, amplitude) in state {
+                let index = label
+                    .to_usize()
+                    .expect("basis state label should fit in the register");
+                amplitudes[index] = amplitude;
+            }
+            Ok(Value::Array(
+                amplitudes
+                    .into_iter()
+                    .map(|amplitude| {
+                        Value::Tuple(
+                            vec![Value::Double(amplitude.re), Value::Double(amplitude.im)].into(),
+                            Some(Rc::new(StoreItemId::complex())),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .into(),
+            ))
+        }
+        "DumpBlochVector" => {
+            let q = arg
+                .unwrap_qubit()
+                .try_deref()
+                .ok_or(Error::QubitUsedAfterRelease(arg_span))?
+                .0;
+            let (state, qubit_count) = sim.capture_quantum_state();
+            // Adding zero normalizes negative zero so it isn't printed with a sign.
+            let [x, y, z] = [Pauli::X, Pauli::Y, Pauli::Z]
+                .map(|p| pauli_expectation(&state, qubit_count, &[(p, q)]) + 0.0);
+            let purity = (1.0 + x * x + y * y + z * z) / 2.0;
+            match out.message(&format!(
+                "Bloch vector of qubit {q}: x = {x:.4}, y = {y:.4}, z = {z:.4}, purity = {purity:.4}"
+            )) {
+                Ok(()) => Ok(Value::unit()),
+                Err(_) => Err(Error::OutputFail(name_span)),
+            }
+        }
         "ArcCos" => Ok(Value::Double(arg.unwrap_double().acos())),
         "ArcSin" => Ok(Value::Double(arg.unwrap_double().asin())),
         "ArcTan" => Ok(Value::Double(arg.unwrap_double().atan())),
@@ -135,6 +298,7 @@ pub(crate) fn call<B: Backend>(
         "Tanh" => Ok(Value::Double(arg.unwrap_double().tanh())),
         "Sqrt" => Ok(Value::Double(arg.unwrap_double().sqrt())),
         "Log" => Ok(Value::Double(arg.unwrap_double().ln())),
+        "ExpD" => Ok(Value::Double(arg.unwrap_double().exp())),
         "DrawRandomInt" => {
             let [lo, hi] = unwrap_tuple(arg);
             let lo = lo.unwrap_int();
@@ -159,8 +323,48 @@ pub(crate) fn call<B: Backend>(
             let p = arg.unwrap_double();
             Ok(Value::Bool(rng.gen_bool(p)))
         }
-        #[allow(clippy::cast_possible_truncation)]
-        "Truncate" => Ok(Value::Int(arg.unwrap_double() as i64)),
+        "Truncate" => {
+            let value = arg.unwrap_double();
+            // Only values in [-2^63, 2^63) can be represented as an Int, which also excludes NaN and infinities.
+            if (-9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0).contains(&value) {
+                #[allow(clippy::cast_possible_truncation)]
+                Ok(Value::Int(value as i64))
+            } else {
+                Err(Error::IntrinsicFail(
+                    name.to_string(),
+                    format!("{value} cannot be converted to an Int"),
+                    arg_span,
+                ))
+            }
+        }
+        "ExpModI"
+        | "ExpModL"
+        | "ExtendedGreatestCommonDivisorI"
+        | "ExtendedGreatestCommonDivisorL"
+        | "InverseModI"
+        | "InverseModL"
+        | "FactorialL"
+        | "BinomL" => math_intrinsic(name, arg, arg_span),
+        "Zipped" => Ok(zipped(arg)),
+        "Chunks" => chunks(name, arg, arg_span),
+        "Distinct" => distinct(name, arg, arg_span),
+        "Counts" => counts(name, arg, arg_span),
+        "GroupedByKey" => grouped_by_key(name, arg, arg_span),
+        "Union" => union(name, arg, arg_span),
+        "Intersection" => intersection(name, arg, arg_span),
+        "Difference" => difference(name, arg, arg_span),
+        "ReadTextFile" | "WriteTextFile" => Err(Error::IntrinsicFail(
+            name.to_string(),
+            "file access is not enabled by the host".to_string(),
+            name_span,
+        )),
+        "CommandLineArguments" | "TryEnvironmentVariable" => Err(Error::IntrinsicFail(
+            name.to_string(),
+            "environment access is not enabled by the host".to_string(),
+            name_span,
+        )),
+        "StringLength" | "Substring" | "Split" | "Contains" | "Uppercased" | "Lowercased"
+        | "TryStringAsInt" | "TryStringAsDouble" => string_intrinsic(name, arg, arg_span),
         "__quantum__qis__ccx__body" => three_qubit_gate(
             |ctl0, ctl1, q| sim.ccx(ctl0, ctl1, q, call_stack),
             arg,
@@ -233,6 +437,11 @@ pub(crate) fn call<B: Backend>(
             ),
         )),
         "__quantum__rt__read_loss" => Ok(Value::Bool(arg == Value::Result(val::Result::Loss))),
+        "PreparePureStateFromAmplitudes" => {
+            let [_, qubits] = unwrap_tuple(arg.clone());
+            unwrap_register(qubits, arg_span)?;
+            custom_intrinsic(name, name_span, arg, call_stack, sim)
+        }
         _ => {
             let qubits = arg.qubits();
             let qubits_len = qubits.len();
@@ -243,18 +452,66 @@ pub(crate) fn call<B: Backend>(
             if qubits.len() != qubits_len {
                 return Err(Error::QubitUsedAfterRelease(arg_span));
             }
-            if let Some(result) = sim.custom_intrinsic(name, arg, call_stack) {
-                match result {
-                    Ok(value) => Ok(value),
-                    Err(message) => Err(Error::IntrinsicFail(name.to_string(), message, name_span)),
-                }
-            } else {
-                Err(Error::UnknownIntrinsic(name.to_string(), name_span))
-            }
+            custom_intrinsic(name, name_span, arg, call_stack, sim)
         }
     }
 }
 
+/// Calls an intrinsic implemented by the backend.
+fn custom_intrinsic<B: Backend>(
+    name: &str,
+    name_span: PackageSpan,
+    arg: Value,
+    call_stack: &[Frame],
+    sim: &mut TracingBackend<'_, B>,
+) -> Result<Value, Error> {
+    match sim.custom_intrinsic(name, arg, call_stack) {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(message)) => Err(Error::IntrinsicFail(name.to_string(), message, name_span)),
+        None => Err(Error::UnknownIntrinsic(name.to_string(), name_span)),
+    }
+}
+
+/// Gets the ids of the qubits in a register argument, checking that they are live and unique.
+fn unwrap_register(arg: Value, arg_span: PackageSpan) -> Result<Vec<usize>, Error> {
+    let qubits = arg.unwrap_array();
+    let qubits_len = qubits.len();
+    let qubits = qubits
+        .iter()
+        .filter_map(|q| q.clone().unwrap_qubit().try_deref().map(|q| q.0))
+        .collect::<Vec<_>>();
+    if qubits.len() != qubits_len {
+        return Err(Error::QubitUsedAfterRelease(arg_span));
+    }
+    if qubits.len() != qubits.iter().collect::<FxHashSet<_>>().len() {
+        return Err(Error::QubitUniqueness(arg_span));
+    }
+    Ok(qubits)
+}
+
+fn big_int_result(
+    name: &str,
+    result: Result<BigInt, &str>,
+    arg_span: PackageSpan,
+) -> Result<BigInt, Error> {
+    result.map_err(|msg| Error::IntrinsicFail(name.to_string(), msg.to_string(), arg_span))
+}
+
+fn int_result(
+    name: &str,
+    result: Result<BigInt, &str>,
+    arg_span: PackageSpan,
+) -> Result<i64, Error> {
+    let value = big_int_result(name, result, arg_span)?;
+    i64::try_from(&value).map_err(|_| {
+        Error::IntrinsicFail(
+            name.to_string(),
+            format!("{value} cannot be represented as an Int"),
+            arg_span,
+        )
+    })
+}
+
 fn one_qubit_gate(
     mut gate: impl FnMut(usize),
     arg: Value,
@@ -367,6 +624,332 @@ fn two_qubit_rotation(
     }
 }
 
+/// Pairs up the elements of two arrays, truncating to the length of the shorter one.
+#[must_use]
+pub fn zipped(arg: Value) -> Value {
+    let [left, right] = unwrap_tuple(arg);
+    let zipped = left
+        .unwrap_array()
+        .iter()
+        .zip(right.unwrap_array().iter())
+        .map(|(l, r)| Value::Tuple([l.clone(), r.clone()].into(), None))
+        .collect::<Vec<_>>();
+    Value::Array(zipped.into())
+}
+
+/// Splits an array into chunks of the given size, where the last chunk may be shorter.
+pub fn chunks(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    let [chunk_size, array] = unwrap_tuple(arg);
+    let chunk_size = match usize::try_from(chunk_size.unwrap_int()) {
+        Ok(size) if size > 0 => size,
+        _ => {
+            return Err(Error::IntrinsicFail(
+                name.to_string(),
+                "`chunkSize` must be positive".to_string(),
+                arg_span,
+            ));
+        }
+    };
+    let chunks = array
+        .unwrap_array()
+        .chunks(chunk_size)
+        .map(|chunk| Value::Array(chunk.to_vec().into()))
+        .collect::<Vec<_>>();
+    Ok(Value::Array(chunks.into()))
+}
+
+/// A classical value that can be used as a key in the collection intrinsics.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum HashKey {
+    Array(Vec<HashKey>),
+    BigInt(BigInt),
+    Bool(bool),
+    Int(i64),
+    Loss,
+    Pauli(Pauli),
+    Result(bool),
+    String(Rc<str>),
+    Tuple(Vec<HashKey>),
+}
+
+impl HashKey {
+    /// Converts a value into a key, returning the type name of the first part of the value
+    /// that cannot be hashed.
+    fn try_from_value(value: &Value) -> Result<Self, &'static str> {
+        Ok(match value {
+            Value::Array(values) => HashKey::Array(
+                values
+                    .iter()
+                    .map(Self::try_from_value)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::BigInt(v) => HashKey::BigInt(v.clone()),
+            Value::Bool(v) => HashKey::Bool(*v),
+            Value::Int(v) => HashKey::Int(*v),
+            Value::Pauli(v) => HashKey::Pauli(*v),
+            Value::Result(val::Result::Val(v)) => HashKey::Result(*v),
+            Value::Result(val::Result::Loss) => HashKey::Loss,
+            Value::String(v) => HashKey::String(v.clone()),
+            Value::Tuple(values, _) => HashKey::Tuple(
+                values
+                    .iter()
+                    .map(Self::try_from_value)
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => return Err(value.type_name()),
+        })
+    }
+}
+
+fn hash_keys<'a>(
+    name: &str,
+    values: impl IntoIterator<Item = &'a Value>,
+    arg_span: PackageSpan,
+) -> Result<Vec<HashKey>, Error> {
+    values
+        .into_iter()
+        .map(|value| {
+            HashKey::try_from_value(value).map_err(|type_name| {
+                Error::IntrinsicFail(
+                    name.to_string(),
+                    format!("values of type {type_name} cannot be used as keys"),
+                    arg_span,
+                )
+            })
+        })
+        .collect()
+}
+
+/// Keeps the first occurrence of each element of `values` for which `keep` returns true.
+fn distinct_where(
+    name: &str,
+    values: &[Value],
+    arg_span: PackageSpan,
+    mut keep: impl FnMut(&HashKey) -> bool,
+) -> Result<Value, Error> {
+    let mut seen = FxHashSet::default();
+    let distinct = hash_keys(name, values, arg_span)?
+        .into_iter()
+        .zip(values)
+        .filter(|(key, _)| keep(key) && seen.insert(key.clone()))
+        .map(|(_, value)| value.clone())
+        .collect::<Vec<_>>();
+    Ok(Value::Array(distinct.into()))
+}
+
+/// Removes duplicate elements from an array, keeping the first occurrence of each.
+pub fn distinct(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    distinct_where(name, &arg.unwrap_array(), arg_span, |_| true)
+}
+
+/// Counts the occurrences of each distinct element of an array, in order of first occurrence.
+pub fn counts(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    let values = arg.unwrap_array();
+    let mut indices = FxHashMap::default();
+    let mut counts: Vec<(Value, i64)> = Vec::new();
+    for (key, value) in hash_keys(name, values.iter(), arg_span)?
+        .into_iter()
+        .zip(values.iter())
+    {
+        let index = *indices.entry(key).or_insert_with(|| {
+            counts.push((value.clone(), 0));
+            counts.len() - 1
+        });
+        counts[index].1 += 1;
+    }
+    let counts = counts
+        .into_iter()
+        .map(|(value, count)| Value::Tuple([value, Value::Int(count)].into(), None))
+        .collect::<Vec<_>>();
+    Ok(Value::Array(counts.into()))
+}
+
+/// Groups the values of an array of key-value pairs by key, in order of first occurrence.
+pub fn grouped_by_key(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    let pairs = arg.unwrap_array();
+    let pairs = pairs
+        .iter()
+        .map(|pair| unwrap_tuple::<2>(pair.clone()))
+        .collect::<Vec<_>>();
+    let mut indices = FxHashMap::default();
+    let mut groups: Vec<(Value, Vec<Value>)> = Vec::new();
+    for (key, [key_value, value]) in hash_keys(name, pairs.iter().map(|[key, _]| key), arg_span)?
+        .into_iter()
+        .zip(pairs)
+    {
+        let index = *indices.entry(key).or_insert_with(|| {
+            groups.push((key_value, Vec::new()));
+            groups.len() - 1
+        });
+        groups[index].1.push(value);
+    }
+    let groups = groups
+        .into_iter()
+        .map(|(key, values)| Value::Tuple([key, Value::Array(values.into())].into(), None))
+        .collect::<Vec<_>>();
+    Ok(Value::Array(groups.into()))
+}
+
+/// Returns the distinct elements that appear in either of two arrays.
+pub fn union(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    let [left, right] = unwrap_tuple(arg);
+    let values = left
+        .unwrap_array()
+        .iter()
+        .chain(right.unwrap_array().iter())
+        .cloned()
+        .collect::<Vec<_>>();
+    distinct_where(name, &values, arg_span, |_| true)
+}
+
+/// Returns the distinct elements of the first array that also appear in the second.
+pub fn intersection(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    let [left, right] = unwrap_tuple(arg);
+    let right = hash_keys(name, right.unwrap_array().iter(), arg_span)?
+        .into_iter()
+        .collect::<FxHashSet<_>>();
+    distinct_where(name, &left.unwrap_array(), arg_span, |key| {
+        right.contains(key)
+    })
+}
+
+/// Returns the distinct elements of the first array that do not appear in the second.
+pub fn difference(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    let [left, right] = unwrap_tuple(arg);
+    let right = hash_keys(name, right.unwrap_array().iter(), arg_span)?
+        .into_iter()
+        .collect::<FxHashSet<_>>();
+    distinct_where(name, &left.unwrap_array(), arg_span, |key| {
+        !right.contains(key)
+    })
+}
+
+/// Evaluates one of the integer arithmetic intrinsics from `Std.Math`.
+pub fn math_intrinsic(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    match name {
+        "ExpModI" => {
+            let [base, power, modulus] = unwrap_tuple(arg);
+            let result = utils::exp_mod(
+                &base.unwrap_int().into(),
+                &power.unwrap_int().into(),
+                &modulus.unwrap_int().into(),
+            );
+            int_result(name, result, arg_span).map(Value::Int)
+        }
+        "ExpModL" => {
+            let [base, power, modulus] = unwrap_tuple(arg);
+            let result = utils::exp_mod(
+                &base.unwrap_big_int(),
+                &power.unwrap_big_int(),
+                &modulus.unwrap_big_int(),
+            );
+            big_int_result(name, result, arg_span).map(Value::BigInt)
+        }
+        "ExtendedGreatestCommonDivisorI" => {
+            let [a, b] = unwrap_tuple(arg);
+            let (u, v) = utils::extended_gcd(&a.unwrap_int().into(), &b.unwrap_int().into());
+            let u = int_result(name, Ok(u), arg_span)?;
+            let v = int_result(name, Ok(v), arg_span)?;
+            Ok(Value::Tuple([Value::Int(u), Value::Int(v)].into(), None))
+        }
+        "ExtendedGreatestCommonDivisorL" => {
+            let [a, b] = unwrap_tuple(arg);
+            let (u, v) = utils::extended_gcd(&a.unwrap_big_int(), &b.unwrap_big_int());
+            Ok(Value::Tuple(
+                [Value::BigInt(u), Value::BigInt(v)].into(),
+                None,
+            ))
+        }
+        "InverseModI" => {
+            let [a, modulus] = unwrap_tuple(arg);
+            let result = utils::inverse_mod(&a.unwrap_int().into(), &modulus.unwrap_int().into());
+            int_result(name, result, arg_span).map(Value::Int)
+        }
+        "InverseModL" => {
+            let [a, modulus] = unwrap_tuple(arg);
+            let result = utils::inverse_mod(&a.unwrap_big_int(), &modulus.unwrap_big_int());
+            big_int_result(name, result, arg_span).map(Value::BigInt)
+        }
+        "FactorialL" => {
+            big_int_result(name, utils::factorial(arg.unwrap_int()), arg_span).map(Value::BigInt)
+        }
+        "BinomL" => {
+            let [n, k] = unwrap_tuple(arg);
+            let result = utils::binom(n.unwrap_int(), k.unwrap_int());
+            big_int_result(name, result, arg_span).map(Value::BigInt)
+        }
+        _ => panic!("`{name}` is not a math intrinsic"),
+    }
+}
+
+/// Evaluates one of the string manipulation intrinsics, where lengths and offsets count characters.
+pub fn string_intrinsic(name: &str, arg: Value, arg_span: PackageSpan) -> Result<Value, Error> {
+    let fail =
+        |message: &str| Error::IntrinsicFail(name.to_string(), message.to_string(), arg_span);
+    match name {
+        "StringLength" => Ok(Value::Int(
+            arg.unwrap_string()
+                .chars()
+                .count()
+                .try_into()
+                .expect("string length should fit into Int"),
+        )),
+        "Substring" => {
+            let [string, start, length] = unwrap_tuple(arg);
+            let (Ok(start), Ok(length)) = (
+                usize::try_from(start.unwrap_int()),
+                usize::try_from(length.unwrap_int()),
+            ) else {
+                return Err(fail("`start` and `length` must be non-negative"));
+            };
+            let string = string.unwrap_string();
+            if start.saturating_add(length) > string.chars().count() {
+                return Err(fail("substring is out of range"));
+            }
+            Ok(Value::String(
+                string
+                    .chars()
+                    .skip(start)
+                    .take(length)
+                    .collect::<String>()
+                    .into(),
+            ))
+        }
+        "Split" => {
+            let [string, separator] = unwrap_tuple(arg);
+            let separator = separator.unwrap_string();
+            if separator.is_empty() {
+                return Err(fail("`separator` must not be empty"));
+            }
+            let parts = string
+                .unwrap_string()
+                .split(separator.as_ref())
+                .map(|part| Value::String(part.into()))
+                .collect::<Vec<_>>();
+            Ok(Value::Array(parts.into()))
+        }
+        "Contains" => {
+            let [string, substring] = unwrap_tuple(arg);
+            Ok(Value::Bool(
+                string
+                    .unwrap_string()
+                    .contains(substring.unwrap_string().as_ref()),
+            ))
+        }
+        "Uppercased" => Ok(Value::String(arg.unwrap_string().to_uppercase().into())),
+        "Lowercased" => Ok(Value::String(arg.unwrap_string().to_lowercase().into())),
+        "TryStringAsInt" => Ok(match arg.unwrap_string().parse::<i64>() {
+            Ok(v) => Value::Tuple([Value::Bool(true), Value::Int(v)].into(), None),
+            Err(_) => Value::Tuple([Value::Bool(false), Value::Int(0)].into(), None),
+        }),
+        "TryStringAsDouble" => Ok(match arg.unwrap_string().parse::<f64>() {
+            Ok(v) => Value::Tuple([Value::Bool(true), Value::Double(v)].into(), None),
+            Err(_) => Value::Tuple([Value::Bool(false), Value::Double(0.0)].into(), None),
+        }),
+        _ => panic!("`{name}` is not a string intrinsic"),
+    }
+}
+
 /// Performs relabeling of qubits from the a given left array to the corresponding right array.
 /// The function will swap qubits with the given function to match the new relabeling, returning an error
 /// if the qubits are not unique or if the relabeling is not a valid permutation.
@@ -653,6 +653,72 @@ fn check_zero_false() {
     );
 }
 
+#[test]
+fn pauli_expectation_z() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            X(qs[1]);
+            let e = Std.Diagnostics.PauliExpectation([PauliZ, PauliZ], qs);
+            ResetAll(qs);
+            e
+        }"},
+        &expect!["-1.0"],
+    );
+}
+
+#[test]
+fn pauli_expectation_bell_state() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            H(qs[0]);
+            CNOT(qs[0], qs[1]);
+            let xx = Std.Diagnostics.PauliExpectation([PauliX, PauliX], qs);
+            let yy = Std.Diagnostics.PauliExpectation([PauliY, PauliY], qs);
+            let zi = Std.Diagnostics.PauliExpectation([PauliZ, PauliI], qs);
+            ResetAll(qs);
+            (Std.Math.AbsD(xx - 1.0) < 1e-12, Std.Math.AbsD(yy + 1.0) < 1e-12, Std.Math.AbsD(zi) < 1e-12)
+        }"},
+        &expect!["(true, true, true)"],
+    );
+}
+
+#[test]
+fn pauli_expectation_does_not_change_state() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            H(q);
+            let _ = Std.Diagnostics.PauliExpectation([PauliZ], [q]);
+            Std.Diagnostics.DumpMachine();
+            Reset(q);
+        }"},
+        &expect![[r#"
+            STATE:
+            |0⟩: 0.7071+0.0000𝑖
+            |1⟩: 0.7071+0.0000𝑖
+        "#]],
+    );
+}
+
+#[test]
+fn pauli_expectation_length_mismatch_fails() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            Std.Diagnostics.PauliExpectation([PauliZ], qs)
+        }"},
+        &expect![
+            "intrinsic callable `PauliExpectation` failed: the number of Paulis must match the number of qubits"
+        ],
+    );
+}
+
 #[test]
 fn check_zero_qubit_already_released_fails() {
     check_intrinsic_result(
@@ -800,6 +866,45 @@ fn truncate() {
     check_intrinsic_value("", "Microsoft.Quantum.Math.Truncate(-3.9)", &Value::Int(-3));
 }
 
+#[test]
+fn truncate_nan_fails() {
+    check_intrinsic_result(
+        "",
+        "Microsoft.Quantum.Math.Truncate(0.0 / 0.0)",
+        &expect!["intrinsic callable `Truncate` failed: NaN cannot be converted to an Int"],
+    );
+}
+
+#[test]
+fn truncate_out_of_range_fails() {
+    check_intrinsic_result(
+        "",
+        "Microsoft.Quantum.Math.Truncate(1.0e19)",
+        &expect![
+            "intrinsic callable `Truncate` failed: 10000000000000000000 cannot be converted to an Int"
+        ],
+    );
+}
+
+#[test]
+fn floor_infinity_fails() {
+    check_intrinsic_result(
+        "",
+        "Microsoft.Quantum.Math.Floor(-1.0 / 0.0)",
+        &expect!["intrinsic callable `Truncate` failed: -inf cannot be converted to an Int"],
+    );
+}
+
+#[test]
+fn exp_d() {
+    check_intrinsic_value("", "Microsoft.Quantum.Math.ExpD(0.0)", &Value::Double(1.0));
+    check_intrinsic_value(
+        "",
+        "Microsoft.Quantum.Math.ExpD(1.0)",
+        &Value::Double(std::f64::consts::E),
+    );
+}
+
 #[test]
 fn sqrt() {
     check_intrinsic_value("", "Microsoft.Quantum.Math.Sqrt(0.0)", &Value::Double(0.0));
@@ -1671,6 +1776,28 @@ fn qubit_release_non_zero_failure() {
     );
 }
 
+#[test]
+fn qubit_release_non_zero_failure_on_early_return() {
+    check_intrinsic_output(
+        indoc! {"
+            namespace Test {
+                operation Foo() : Int {
+                    for i in 0..3 {
+                        use q = Qubit();
+                        if i == 2 {
+                            X(q);
+                            return i;
+                        }
+                    }
+                    -1
+                }
+            }
+        "},
+        "Test.Foo()",
+        &expect!["Qubit0 released while not in |0⟩ state"],
+    );
+}
+
 #[test]
 fn qubit_double_release_fails() {
     check_intrinsic_result(
@@ -1890,6 +2017,40 @@ fn start_counting_qubits_called_twice_before_stop_fails() {
     );
 }
 
+#[test]
+fn stop_timer_returns_non_negative_elapsed_time() {
+    check_intrinsic_value(
+        "",
+        indoc! {"{
+            Std.Diagnostics.StartTimer();
+            let elapsed = Std.Diagnostics.StopTimer();
+            elapsed >= 0.0
+        }"},
+        &Value::Bool(true),
+    );
+}
+
+#[test]
+fn start_timer_called_twice_before_stop_fails() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            Std.Diagnostics.StartTimer();
+            Std.Diagnostics.StartTimer();
+        }"},
+        &expect!["timer already started"],
+    );
+}
+
+#[test]
+fn stop_timer_without_start_fails() {
+    check_intrinsic_output(
+        "",
+        "Std.Diagnostics.StopTimer()",
+        &expect!["timer not started"],
+    );
+}
+
 #[test]
 fn check_pauli_noise() {
     check_intrinsic_output(
@@ -2016,6 +2177,34 @@ fn comparsion_of_loss_result_to_other_loss_result_runtime_error() {
     );
 }
 
+#[test]
+fn comparison_of_result_arrays_containing_loss_runtime_error() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            Std.Diagnostics.ConfigureQubitLoss(1.0);
+            let results = MeasureEachZ(qs);
+            results == [Zero, Zero]
+        }"},
+        &expect!["cannot compare measurement result from qubit loss"],
+    );
+}
+
+#[test]
+fn comparison_of_result_arrays() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            X(qs[1]);
+            let results = MResetEachZ(qs);
+            (results == [Zero, One], results != [Zero, One], (results, 1) == ([One, One], 1))
+        }"},
+        &expect!["(true, false, false)"],
+    );
+}
+
 #[test]
 fn ccx_on_lost_target_is_noop() {
     check_intrinsic_output(
@@ -2841,3 +3030,243 @@ fn applyidlenoise_qubit_already_released_fails() {
         &expect!["qubit used after release"],
     );
 }
+
+#[test]
+fn dump_bloch_vector_pure_state() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            H(q);
+            S(q);
+            Std.Diagnostics.DumpBlochVector(q);
+            Reset(q);
+        }"},
+        &expect![[r#"
+            Bloch vector of qubit 0: x = 0.0000, y = 1.0000, z = 0.0000, purity = 1.0000
+        "#]],
+    );
+}
+
+#[test]
+fn dump_bloch_vector_entangled_qubit_is_mixed() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            H(qs[0]);
+            CNOT(qs[0], qs[1]);
+            Std.Diagnostics.DumpBlochVector(qs[1]);
+            ResetAll(qs);
+        }"},
+        &expect![[r#"
+            Bloch vector of qubit 1: x = 0.0000, y = 0.0000, z = 0.0000, purity = 0.5000
+        "#]],
+    );
+}
+
+#[test]
+fn check_separable() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[3];
+            H(qs[0]);
+            CNOT(qs[0], qs[1]);
+            H(qs[2]);
+            let results = (
+                Std.Diagnostics.CheckSeparable([qs[0]]),
+                Std.Diagnostics.CheckSeparable([qs[1], qs[0]]),
+                Std.Diagnostics.CheckSeparable([qs[2]]),
+            );
+            ResetAll(qs);
+            results
+        }"},
+        &expect!["(false, true, true)"],
+    );
+}
+
+#[test]
+fn entanglement_entropy() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[4];
+            H(qs[0]);
+            CNOT(qs[0], qs[1]);
+            H(qs[2]);
+            CNOT(qs[2], qs[3]);
+            Ry(0.5, qs[3]);
+            let bell = Std.Diagnostics.EntanglementEntropy([qs[0]]);
+            let two_bell = Std.Diagnostics.EntanglementEntropy([qs[0], qs[2]]);
+            let product = Std.Diagnostics.EntanglementEntropy([qs[0], qs[1]]);
+            ResetAll(qs);
+            (Std.Math.AbsD(bell - 1.0) < 1e-9, Std.Math.AbsD(two_bell - 2.0) < 1e-9, Std.Math.AbsD(product) < 1e-9)
+        }"},
+        &expect!["(true, true, true)"],
+    );
+}
+
+#[test]
+fn entanglement_entropy_partial() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            // cos(π/8)|00⟩ + sin(π/8)|11⟩ has entropy -c²log₂c² - s²log₂s² ≈ 0.6009.
+            Ry(Std.Math.PI() / 4.0, qs[0]);
+            CNOT(qs[0], qs[1]);
+            let entropy = Std.Diagnostics.EntanglementEntropy([qs[1]]);
+            ResetAll(qs);
+            Std.Math.Round(entropy * 10000.0)
+        }"},
+        &expect!["6009"],
+    );
+}
+
+#[test]
+fn entanglement_entropy_of_unentangled_superposition_is_zero() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            H(qs[0]);
+            S(qs[0]);
+            Rx(0.3, qs[1]);
+            let entropy = Std.Diagnostics.EntanglementEntropy([qs[0]]);
+            ResetAll(qs);
+            Std.Math.AbsD(entropy) < 1e-9
+        }"},
+        &expect!["true"],
+    );
+}
+
+#[test]
+fn prepare_pure_state_from_amplitudes() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            import Std.Math.Complex;
+            use qs = Qubit[2];
+            Std.StatePreparation.PreparePureStateFromAmplitudes([Complex(1.0, 0.0), Complex(0.0, 0.0), Complex(0.0, 1.0)], qs);
+            Std.Diagnostics.DumpMachine();
+            ResetAll(qs);
+        }"},
+        &expect![[r#"
+            STATE:
+            |00⟩: 0.7071+0.0000𝑖
+            |10⟩: 0.0000+0.7071𝑖
+        "#]],
+    );
+}
+
+#[test]
+fn prepare_pure_state_from_amplitudes_with_zero_first_amplitude() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            import Std.Math.Complex;
+            use qs = Qubit[2];
+            Std.StatePreparation.PreparePureStateFromAmplitudes([Complex(0.0, 0.0), Complex(0.0, -3.0), Complex(0.0, 0.0), Complex(4.0, 0.0)], qs);
+            Std.Diagnostics.DumpMachine();
+            ResetAll(qs);
+        }"},
+        &expect![[r#"
+            STATE:
+            |01⟩: 0.0000−0.6000𝑖
+            |11⟩: 0.8000+0.0000𝑖
+        "#]],
+    );
+}
+
+#[test]
+fn prepare_pure_state_from_amplitudes_on_entangled_register() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            import Std.Math.Complex;
+            use qs = Qubit[2];
+            H(qs[0]);
+            CNOT(qs[0], qs[1]);
+            Std.StatePreparation.PreparePureStateFromAmplitudes([Complex(0.0, 0.0), Complex(1.0, 0.0)], [qs[1]]);
+            Std.Diagnostics.DumpMachine();
+            ResetAll(qs);
+        }"},
+        &expect![[r#"
+            STATE:
+            |01⟩: 0.7071+0.0000𝑖
+            |10⟩: 0.7071+0.0000𝑖
+        "#]],
+    );
+}
+
+#[test]
+fn prepare_pure_state_from_amplitudes_with_duplicate_qubits_fails() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            import Std.Math.Complex;
+            use q = Qubit();
+            let one = Complex(1.0, 0.0);
+            Std.StatePreparation.PreparePureStateFromAmplitudes([one, one], [q, q]);
+        }"},
+        &expect!["qubits in invocation are not unique"],
+    );
+}
+
+#[test]
+fn prepare_pure_state_from_too_many_amplitudes_fails() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            import Std.Math.Complex;
+            use q = Qubit();
+            let one = Complex(1.0, 0.0);
+            Std.StatePreparation.PreparePureStateFromAmplitudes([one, one, one], [q]);
+        }"},
+        &expect![
+            "intrinsic callable `PreparePureStateFromAmplitudes` failed: expected at most 2 coefficients for 1 qubits, got 3"
+        ],
+    );
+}
+
+#[test]
+fn register_amplitudes() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[3];
+            H(qs[0]);
+            S(qs[0]);
+            X(qs[1]);
+            H(qs[2]);
+            let amplitudes = Std.Diagnostics.RegisterAmplitudes([qs[0], qs[1]]);
+            ResetAll(qs);
+            let r = 1.0 / Std.Math.Sqrt(2.0);
+            // The returned values are Complex, so they support complex arithmetic.
+            let sum = amplitudes[1] + amplitudes[3];
+            (
+                Length(amplitudes),
+                Std.Math.AbsD(amplitudes[1].Real - r) < 1e-9,
+                Std.Math.AbsD(amplitudes[3].Imag - r) < 1e-9,
+                Std.Math.AbsD(sum.Real - r) < 1e-9 and Std.Math.AbsD(sum.Imag - r) < 1e-9,
+                amplitudes[0].Real == 0.0 and amplitudes[2].Imag == 0.0,
+            )
+        }"},
+        &expect!["(4, true, true, true, true)"],
+    );
+}
+
+#[test]
+fn register_amplitudes_of_entangled_register_fails() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            H(qs[0]);
+            CNOT(qs[0], qs[1]);
+            Std.Diagnostics.RegisterAmplitudes([qs[0]])
+        }"},
+        &expect!["qubits are not separable"],
+    );
+}
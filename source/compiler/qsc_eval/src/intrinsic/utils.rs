@@ -3,9 +3,9 @@
 
 use std::collections::hash_map::Entry;
 
-use num_bigint::BigUint;
+use num_bigint::{BigInt, BigUint};
 use num_complex::{Complex, Complex64};
-use num_traits::Zero;
+use num_traits::{One, Signed, Zero};
 use rustc_hash::{FxHashMap, FxHashSet};
 
 /// Given a state and a set of qubits, split the state into two parts: the qubits to dump and the remaining qubits.
@@ -195,3 +195,190 @@ pub(crate) fn state_to_matrix(
 
     matrix
 }
+
+/// Computes the reduced density matrix of the given qubits by tracing out all other qubits.
+/// Rows and columns are indexed by the basis states of the given qubits, with the first
+/// qubit as the most significant bit.
+pub fn reduced_density_matrix(
+    qubits: &[usize],
+    state: &[(BigUint, Complex64)],
+    qubit_count: usize,
+) -> Vec<Vec<Complex64>> {
+    let (_, other_mask) = compute_mask(qubit_count, qubits);
+
+    // Group the amplitudes by the basis state of the other qubits, since only amplitudes that
+    // agree on the other qubits contribute to the same entry of the reduced density matrix.
+    let mut by_other_label: FxHashMap<BigUint, Vec<(usize, Complex64)>> = FxHashMap::default();
+    for (label, amplitude) in state {
+        let index = qubits.iter().fold(0, |index, q| {
+            (index << 1) | usize::from(label.bit((qubit_count - *q - 1) as u64))
+        });
+        by_other_label
+            .entry(label & &other_mask)
+            .or_default()
+            .push((index, *amplitude));
+    }
+
+    let dim = 1 << qubits.len();
+    let mut rho = vec![vec![Complex64::zero(); dim]; dim];
+    for amplitudes in by_other_label.values() {
+        for (i, a) in amplitudes {
+            for (j, b) in amplitudes {
+                rho[*i][*j] += a * b.conj();
+            }
+        }
+    }
+    rho
+}
+
+/// Computes the von Neumann entropy of the given density matrix, in bits.
+pub fn von_neumann_entropy(rho: &[Vec<Complex64>]) -> f64 {
+    let entropy: f64 = hermitian_eigenvalues(rho)
+        .into_iter()
+        .filter(|eigenvalue| !eigenvalue.is_nearly_zero() && *eigenvalue > 0.0)
+        .map(|eigenvalue| -eigenvalue * eigenvalue.log2())
+        .sum();
+    // Adding zero normalizes negative zero for product states.
+    entropy + 0.0
+}
+
+/// Computes the eigenvalues of a Hermitian matrix, in ascending order.
+/// The matrix H = A + iB is embedded in the real symmetric matrix [[A, -B], [B, A]], which has
+/// the same eigenvalues each repeated twice, and that matrix is diagonalized with the cyclic
+/// Jacobi method.
+fn hermitian_eigenvalues(matrix: &[Vec<Complex64>]) -> Vec<f64> {
+    let n = matrix.len();
+    let mut real = vec![vec![0.0; 2 * n]; 2 * n];
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, val) in row.iter().enumerate() {
+            real[i][j] = val.re;
+            real[i + n][j + n] = val.re;
+            real[i + n][j] = val.im;
+            real[i][j + n] = -val.im;
+        }
+    }
+
+    for _ in 0..100 {
+        let off_diagonal: f64 = real
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, val)| val * val)
+                    .sum::<f64>()
+            })
+            .sum();
+        if off_diagonal < 1e-24 {
+            break;
+        }
+        for p in 0..2 * n {
+            for q in p + 1..2 * n {
+                if real[p][q].abs() < 1e-15 {
+                    continue;
+                }
+                // Rotate rows and columns p and q so that the entry at (p, q) becomes zero.
+                let theta = (real[q][q] - real[p][p]) / (2.0 * real[p][q]);
+                let tan = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let cos = 1.0 / (tan * tan + 1.0).sqrt();
+                let sin = tan * cos;
+                for row in &mut real {
+                    let (row_p, row_q) = (row[p], row[q]);
+                    row[p] = cos * row_p - sin * row_q;
+                    row[q] = sin * row_p + cos * row_q;
+                }
+                let (head, tail) = real.split_at_mut(q);
+                for (val_p, val_q) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    let (old_p, old_q) = (*val_p, *val_q);
+                    *val_p = cos * old_p - sin * old_q;
+                    *val_q = sin * old_p + cos * old_q;
+                }
+            }
+        }
+    }
+
+    let mut eigenvalues = real
+        .iter()
+        .enumerate()
+        .map(|(i, row)| row[i])
+        .collect::<Vec<_>>();
+    eigenvalues.sort_by(f64::total_cmp);
+    eigenvalues.into_iter().step_by(2).collect()
+}
+
+/// Computes `base^power mod modulus`, with the same preconditions as the Q# `ExpModI` and `ExpModL`.
+pub fn exp_mod(base: &BigInt, power: &BigInt, modulus: &BigInt) -> Result<BigInt, &'static str> {
+    if power.is_negative() {
+        return Err("`power` must be non-negative");
+    }
+    if !modulus.is_positive() {
+        return Err("`modulus` must be positive");
+    }
+    if !base.is_positive() {
+        return Err("`expBase` must be positive");
+    }
+    Ok(base.modpow(power, modulus))
+}
+
+/// Returns a tuple `(u, v)` such that `u * a + v * b = gcd(a, b)`, where the gcd is non-negative.
+pub fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt) {
+    let (mut s1, mut s2) = (BigInt::one(), BigInt::zero());
+    let (mut t1, mut t2) = (BigInt::zero(), BigInt::one());
+    let (mut r1, mut r2) = (a.abs(), b.abs());
+
+    while !r2.is_zero() {
+        let quotient = &r1 / &r2;
+        let r = &r1 - &quotient * &r2;
+        r1 = std::mem::replace(&mut r2, r);
+        let s = &s1 - &quotient * &s2;
+        s1 = std::mem::replace(&mut s2, s);
+        let t = &t1 - &quotient * &t2;
+        t1 = std::mem::replace(&mut t2, t);
+    }
+
+    (s1 * a.signum(), t1 * b.signum())
+}
+
+/// Returns the multiplicative inverse of `a` modulo `modulus`, in the range `0..modulus`.
+pub fn inverse_mod(a: &BigInt, modulus: &BigInt) -> Result<BigInt, &'static str> {
+    if !modulus.is_positive() {
+        return Err("`modulus` must be positive");
+    }
+    let (u, v) = extended_gcd(a, modulus);
+    if !(&u * a + v * modulus).is_one() {
+        return Err("`a` and `modulus` must be co-prime");
+    }
+    let r = u % modulus;
+    Ok(if r.is_negative() { r + modulus } else { r })
+}
+
+/// The largest input accepted by [`factorial`], which bounds the time and memory it uses.
+pub const MAX_FACTORIAL_INPUT: i64 = 10_000;
+
+/// Returns `n!`, for `n` up to [`MAX_FACTORIAL_INPUT`].
+pub fn factorial(n: i64) -> Result<BigInt, &'static str> {
+    if n < 0 {
+        return Err("The factorial is not defined for negative inputs.");
+    }
+    if n > MAX_FACTORIAL_INPUT {
+        return Err("The factorial is only supported for inputs up to 10000.");
+    }
+    Ok((1..=n).map(BigInt::from).product())
+}
+
+/// Returns the exact binomial coefficient n-choose-k, which is zero when `k` is outside of `0..=n`.
+pub fn binom(n: i64, k: i64) -> Result<BigInt, &'static str> {
+    if n < 0 {
+        return Err("`n` must be non-negative");
+    }
+    if k < 0 || k > n {
+        return Ok(BigInt::zero());
+    }
+    // Each partial product is itself a binomial coefficient, so the division is exact.
+    let mut result = BigInt::one();
+    for i in 0..k.min(n - k) {
+        result = result * (n - i) / (i + 1);
+    }
+    Ok(result)
+}
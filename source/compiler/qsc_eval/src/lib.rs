@@ -20,18 +20,23 @@ mod tests;
 pub mod backend;
 pub mod debug;
 mod error;
+pub mod histogram;
 pub mod intrinsic;
 pub mod noise;
 pub mod output;
 pub mod state;
+pub mod trace;
 pub mod val;
 
 use crate::backend::{Backend, TracingBackend};
+use crate::intrinsic::HostIntrinsics;
 use crate::val::{
     Value, index_array, make_range, slice_array, update_index_range, update_index_single,
 };
 use core::panic;
-use debug::{CallStack, Frame};
+use debug::{
+    CallStack, Frame, QubitFingerprint, QubitWatch, QubitWatchKind, is_state_changing_intrinsic,
+};
 pub use error::PackageSpan;
 use miette::Diagnostic;
 use num_bigint::BigInt;
@@ -39,9 +44,9 @@ use output::Receiver;
 use qsc_data_structures::{functors::FunctorApp, index_map::IndexMap, span::Span};
 use qsc_fir::fir::{
     self, BinOp, BlockId, CallableImpl, ConfiguredExecGraph, ExecGraph, ExecGraphConfig,
-    ExecGraphNode, Expr, ExprId, ExprKind, Field, FieldAssign, Global, Lit, LocalItemId,
-    LocalVarId, PackageId, PackageStoreLookup, PatId, PatKind, PrimField, Res, StmtId, StoreItemId,
-    StringComponent, UnOp,
+    ExecGraphNode, Expr, ExprId, ExprKind, Field, FieldAssign, Global, ItemKind, Lit, LocalItemId,
+    LocalVarId, Mutability, PackageId, PackageStoreLookup, PatId, PatKind, PrimField, Res, StmtId,
+    StoreItemId, StringComponent, UnOp,
 };
 use qsc_fir::ty::Ty;
 use qsc_lowerer::map_fir_package_to_hir;
@@ -52,8 +57,9 @@ use std::{
     cell::RefCell,
     fmt::{self, Display, Formatter},
     iter,
-    ops::Neg,
+    ops::{AddAssign, Neg, Sub},
     rc::Rc,
+    time::Instant,
 };
 use thiserror::Error;
 use val::{Qubit, update_functor_app};
@@ -64,6 +70,14 @@ pub enum Error {
     #[diagnostic(code("Qsc.Eval.ArrayTooLarge"))]
     ArrayTooLarge(#[label("this array has too many items")] PackageSpan),
 
+    #[error("maximum call depth of {0} exceeded")]
+    #[diagnostic(help("this is usually caused by unbounded recursion"))]
+    #[diagnostic(code("Qsc.Eval.CallDepthExceeded"))]
+    CallDepthExceeded(
+        usize,
+        #[label("call exceeds the maximum depth")] PackageSpan,
+    ),
+
     #[error("callable already counted")]
     #[diagnostic(help(
         "counting for a given callable must be stopped before it can be started again"
@@ -141,6 +155,16 @@ pub enum Error {
     #[diagnostic(code("Qsc.Eval.QubitsNotCounted"))]
     QubitsNotCounted(#[label] PackageSpan),
 
+    #[error("timer already started")]
+    #[diagnostic(help("the timer must be stopped before it can be started again"))]
+    #[diagnostic(code("Qsc.Eval.TimerAlreadyStarted"))]
+    TimerAlreadyStarted(#[label] PackageSpan),
+
+    #[error("timer not started")]
+    #[diagnostic(help("the timer must be started before it can be stopped"))]
+    #[diagnostic(code("Qsc.Eval.TimerNotStarted"))]
+    TimerNotStarted(#[label] PackageSpan),
+
     #[error("qubits are not separable")]
     #[diagnostic(help(
         "subset of qubits provided as arguments must not be entangled with any qubits outside of the subset"
@@ -204,6 +228,7 @@ impl Error {
     pub fn span(&self) -> &PackageSpan {
         match self {
             Error::ArrayTooLarge(span)
+            | Error::CallDepthExceeded(_, span)
             | Error::CallableAlreadyCounted(span)
             | Error::CallableNotCounted(span)
             | Error::DivZero(span)
@@ -226,6 +251,8 @@ impl Error {
             | Error::ReleasedQubitNotZero(_, span)
             | Error::ResultComparisonUnsupported(span)
             | Error::ResultLossComparisonUnsupported(span)
+            | Error::TimerAlreadyStarted(span)
+            | Error::TimerNotStarted(span)
             | Error::UnboundName(span)
             | Error::UnknownIntrinsic(_, span)
             | Error::UnsupportedIntrinsicType(_, span)
@@ -264,15 +291,22 @@ impl Display for Spec {
 /// # Panics
 /// On internal error where no result is returned.
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(package = ?package))
+)]
 pub fn eval<B: Backend>(
     package: PackageId,
     seed: Option<u64>,
     exec_graph: ExecGraph,
     exec_graph_config: ExecGraphConfig,
     globals: &impl PackageStoreLookup,
+    host_intrinsics: &HostIntrinsics,
+    max_call_depth: Option<usize>,
     env: &mut Env,
     sim: &mut TracingBackend<'_, B>,
     receiver: &mut impl Receiver,
+    counts: &mut EvalCounts,
 ) -> Result<Value, (Error, Vec<Frame>)> {
     let mut state = State::new(
         package,
@@ -281,7 +315,12 @@ pub fn eval<B: Backend>(
         seed,
         ErrorBehavior::FailOnError,
     );
-    let res = state.eval(globals, env, sim, receiver, &[], StepAction::Continue)?;
+    state.set_host_intrinsics(host_intrinsics.clone());
+    state.set_max_call_depth(max_call_depth);
+    let unwind_point = env.unwind_point();
+    let res = state.eval(globals, env, sim, receiver, &[], StepAction::Continue);
+    *counts += state.counts();
+    let res = res.inspect_err(|_| env.unwind(unwind_point, sim))?;
     let StepResult::Return(value) = res else {
         panic!("eval should always return a value");
     };
@@ -294,16 +333,23 @@ pub fn eval<B: Backend>(
 /// # Panics
 /// On internal error where no result is returned.
 #[allow(clippy::too_many_arguments)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(package = ?package))
+)]
 pub fn invoke<B: Backend>(
     package: PackageId,
     seed: Option<u64>,
     globals: &impl PackageStoreLookup,
+    host_intrinsics: &HostIntrinsics,
+    max_call_depth: Option<usize>,
     exec_graph_config: ExecGraphConfig,
     env: &mut Env,
     sim: &mut TracingBackend<'_, B>,
     receiver: &mut impl Receiver,
     callable: Value,
     args: Value,
+    counts: &mut EvalCounts,
 ) -> Result<Value, (Error, Vec<Frame>)> {
     let mut state = State::new(
         package,
@@ -312,34 +358,70 @@ pub fn invoke<B: Backend>(
         seed,
         ErrorBehavior::FailOnError,
     );
+    state.set_host_intrinsics(host_intrinsics.clone());
+    state.set_max_call_depth(max_call_depth);
     // Push the callable value into the state stack and then the args value so they are ready for evaluation.
     state.set_val_register(callable);
     state.push_val();
     state.set_val_register(args);
 
+    let unwind_point = env.unwind_point();
+
     // Evaluate the call, which will pop the args and callable values from the stack and then either
     // a) prepare the call stack for the execution of the callable, or
     // b) invoke the callable directly if it is an intrinsic.
-    state
-        .eval_call(
-            env,
-            sim,
-            globals,
-            Span::default(),
-            Span::default(),
-            receiver,
-        )
-        .map_err(|e| (e, state.capture_stack()))?;
+    if let Err(e) = state.eval_call(
+        env,
+        sim,
+        globals,
+        Span::default(),
+        Span::default(),
+        receiver,
+    ) {
+        let frames = state.capture_stack();
+        *counts += state.counts();
+        env.unwind(unwind_point, sim);
+        return Err((e, frames));
+    }
 
     // Trigger evaluation of the state until the end of the stack is reached and a return value is obtained, which will be the final
     // result of the invocation.
-    let res = state.eval(globals, env, sim, receiver, &[], StepAction::Continue)?;
+    let res = state.eval(globals, env, sim, receiver, &[], StepAction::Continue);
+    *counts += state.counts();
+    let res = res.inspect_err(|_| env.unwind(unwind_point, sim))?;
     let StepResult::Return(value) = res else {
         panic!("eval should always return a value");
     };
     Ok(value)
 }
 
+/// Counts of the work done by an evaluation.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EvalCounts {
+    /// The number of calls to intrinsic callables.
+    pub intrinsic_calls: u64,
+    /// The number of qubits allocated.
+    pub qubits_allocated: u64,
+}
+
+impl AddAssign for EvalCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.intrinsic_calls += other.intrinsic_calls;
+        self.qubits_allocated += other.qubits_allocated;
+    }
+}
+
+impl Sub for EvalCounts {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            intrinsic_calls: self.intrinsic_calls - other.intrinsic_calls,
+            qubits_allocated: self.qubits_allocated - other.qubits_allocated,
+        }
+    }
+}
+
 /// The type of step action to take during evaluation
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum StepAction {
@@ -358,6 +440,8 @@ pub enum StepResult {
     StepOut,
     Return(Value),
     Fail(String),
+    /// A watched qubit changed in the way its watch looks for. Holds the id of the qubit.
+    QubitWatchHit(usize),
 }
 
 trait AsIndex {
@@ -382,6 +466,7 @@ pub struct Variable {
     pub name: Rc<str>,
     pub value: Value,
     pub span: Span,
+    pub mutability: Mutability,
 }
 
 #[derive(Debug, Clone)]
@@ -390,6 +475,28 @@ pub struct VariableInfo {
     pub name: Rc<str>,
     pub type_name: String,
     pub span: Span,
+    pub mutability: Mutability,
+}
+
+/// The variables bound in a single scope of the environment.
+#[derive(Debug, Clone)]
+pub struct ScopeInfo {
+    /// The id of the call stack frame the scope belongs to, where 0 is the top-level frame.
+    pub frame_id: usize,
+    /// The variables bound in the scope, in the order they were bound.
+    pub variables: Vec<VariableInfo>,
+}
+
+impl From<&Variable> for VariableInfo {
+    fn from(var: &Variable) -> Self {
+        Self {
+            name: var.name.clone(),
+            type_name: var.value.type_name().to_string(),
+            value: var.value.clone(),
+            span: var.span,
+            mutability: var.mutability,
+        }
+    }
 }
 
 pub struct Range {
@@ -422,9 +529,38 @@ impl Range {
     }
 }
 
+/// A qubit that is currently allocated.
+#[derive(Clone, Debug)]
+pub struct QubitInfo {
+    pub id: usize,
+    /// The location of the allocation.
+    pub allocated_at: PackageSpan,
+    /// The name of the innermost variable the qubit is bound to, indexed for qubits in arrays,
+    /// for example `aux` or `register[2]`.
+    pub label: Option<String>,
+}
+
+impl Display for QubitInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "q[{}]", self.id)?;
+        if let Some(label) = &self.label {
+            write!(f, " \"{label}\"")?;
+        }
+        Ok(())
+    }
+}
+
+/// The scopes and qubits of an [`Env`] before an evaluation, restored by [`Env::unwind`] if the
+/// evaluation fails.
+struct UnwindPoint {
+    scope_count: usize,
+    qubits: FxHashSet<usize>,
+}
+
+#[derive(Clone)]
 pub struct Env {
     scopes: Vec<Scope>,
-    qubits: FxHashSet<Rc<Qubit>>,
+    qubits: FxHashMap<Rc<Qubit>, PackageSpan>,
 }
 
 impl Default for Env {
@@ -432,7 +568,7 @@ impl Default for Env {
         // Always create a global scope for top-level statements.
         Self {
             scopes: vec![Scope::default()],
-            qubits: FxHashSet::default(),
+            qubits: FxHashMap::default(),
         }
     }
 }
@@ -513,20 +649,24 @@ impl Env {
 
         let variables_by_scope: Vec<Vec<VariableInfo>> = candidate_scopes
             .into_iter()
-            .map(|bindings| {
-                bindings
-                    .map(|(_, var)| VariableInfo {
-                        name: var.name.clone(),
-                        type_name: var.value.type_name().to_string(),
-                        value: var.value.clone(),
-                        span: var.span,
-                    })
-                    .collect()
-            })
+            .map(|bindings| bindings.map(|(_, var)| var.into()).collect())
             .collect();
         variables_by_scope.into_iter().flatten().collect::<Vec<_>>()
     }
 
+    /// Gets all scopes of the environment with their variables, from the outermost scope to
+    /// the innermost one.
+    #[must_use]
+    pub fn get_scopes(&self) -> Vec<ScopeInfo> {
+        self.scopes
+            .iter()
+            .map(|scope| ScopeInfo {
+                frame_id: scope.frame_id,
+                variables: scope.bindings.values().map(Into::into).collect(),
+            })
+            .collect()
+    }
+
     #[allow(clippy::len_without_is_empty)]
     #[must_use]
     pub fn len(&self) -> usize {
@@ -540,16 +680,98 @@ impl Env {
         variable.value = value;
     }
 
-    pub fn track_qubit(&mut self, qubit: Rc<Qubit>) {
-        self.qubits.insert(qubit);
+    fn unwind_point(&self) -> UnwindPoint {
+        UnwindPoint {
+            scope_count: self.scopes.len(),
+            qubits: self.qubits.keys().map(|q| q.0).collect(),
+        }
+    }
+
+    /// Leaves the scopes entered and releases the qubits allocated since the unwind point, for when
+    /// a failure propagates out of nested `use` blocks without running their releases. The qubits
+    /// are reset first, so they are released cleanly whatever state the failure left them in.
+    ///
+    /// The adjoint of a `within` block whose `apply` block failed is not run: conjugations are
+    /// lowered to plain statements before evaluation, so the evaluator can't tell which ones were
+    /// entered. Qubits allocated before the unwind point keep the state the failure left them in.
+    fn unwind<B: Backend>(&mut self, point: UnwindPoint, sim: &mut TracingBackend<'_, B>) {
+        self.scopes.truncate(point.scope_count.max(1));
+        let mut leaked = self
+            .qubits
+            .keys()
+            .filter(|q| !point.qubits.contains(&q.0))
+            .cloned()
+            .collect::<Vec<_>>();
+        leaked.sort_by_key(|q| q.0);
+        for qubit in leaked {
+            sim.reset(qubit.0, &[]);
+            sim.qubit_release(qubit.0, &[]);
+            self.qubits.remove(&qubit);
+        }
+    }
+
+    pub fn track_qubit(&mut self, qubit: Rc<Qubit>, allocated_at: PackageSpan) {
+        self.qubits.insert(qubit, allocated_at);
     }
 
     pub fn release_qubit(&mut self, qubit: &Rc<Qubit>) {
         self.qubits.remove(qubit);
     }
+
+    /// Gets the currently allocated qubits ordered by id, labeled with the variables they are
+    /// bound to where available.
+    #[must_use]
+    pub fn get_qubits(&self) -> Vec<QubitInfo> {
+        let mut labels = self.qubit_labels(|_| true);
+        let mut qubits = self
+            .qubits
+            .iter()
+            .map(|(q, allocated_at)| QubitInfo {
+                id: q.0,
+                allocated_at: *allocated_at,
+                label: labels.remove(&q.0),
+            })
+            .collect::<Vec<_>>();
+        qubits.sort_by_key(|q| q.id);
+        qubits
+    }
+
+    /// Labels qubits with the innermost variables they are bound to, using only the scopes whose
+    /// frame id is accepted by the filter.
+    fn qubit_labels(&self, include_frame: impl Fn(usize) -> bool) -> FxHashMap<usize, String> {
+        let mut labels = FxHashMap::default();
+        for scope in self.scopes.iter().rev() {
+            if !include_frame(scope.frame_id) {
+                continue;
+            }
+            for var in scope.bindings.values() {
+                if !var.name.starts_with('@') {
+                    collect_qubit_labels(&var.value, &var.name, &mut labels);
+                }
+            }
+        }
+        labels
+    }
+}
+
+fn collect_qubit_labels(value: &Value, label: &str, labels: &mut FxHashMap<usize, String>) {
+    match value {
+        Value::Qubit(q) => {
+            if let Some(q) = q.try_deref() {
+                // Scopes are visited innermost first, so keep the first label found.
+                labels.entry(q.0).or_insert_with(|| label.to_string());
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_qubit_labels(item, &format!("{label}[{i}]"), labels);
+            }
+        }
+        _ => {}
+    }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct Scope {
     bindings: IndexMap<LocalVarId, Variable>,
     frame_id: usize,
@@ -565,6 +787,10 @@ pub enum ErrorBehavior {
     StopOnError,
 }
 
+/// The default maximum depth of nested calls, which bounds the memory used by unbounded recursion.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 100_000;
+
+#[derive(Clone)]
 pub struct State {
     exec_graph_stack: Vec<ConfiguredExecGraph>,
     idx: u32,
@@ -578,9 +804,17 @@ pub struct State {
     rng: RefCell<StdRng>,
     call_counts: FxHashMap<CallableCountKey, i64>,
     qubit_counter: Option<QubitCounter>,
+    timer_start: Option<Instant>,
     error_behavior: ErrorBehavior,
     last_error: Option<(Error, Vec<Frame>)>,
     exec_graph_config: ExecGraphConfig,
+    host_intrinsics: HostIntrinsics,
+    max_call_depth: Option<usize>,
+    qubit_watches: Vec<QubitWatch>,
+    qubit_watch_hit: Option<usize>,
+    position: u64,
+    replay_to: Option<u64>,
+    counts: EvalCounts,
 }
 
 impl State {
@@ -609,9 +843,104 @@ impl State {
             rng,
             call_counts: FxHashMap::default(),
             qubit_counter: None,
+            timer_start: None,
             error_behavior,
             last_error: None,
             exec_graph_config,
+            host_intrinsics: HostIntrinsics::default(),
+            max_call_depth: Some(DEFAULT_MAX_CALL_DEPTH),
+            qubit_watches: Vec::new(),
+            qubit_watch_hit: None,
+            position: 0,
+            replay_to: None,
+            counts: EvalCounts::default(),
+        }
+    }
+
+    /// Sets the host-provided intrinsic implementations that take precedence over the
+    /// built-in intrinsics when evaluating `body intrinsic` callables.
+    pub fn set_host_intrinsics(&mut self, host_intrinsics: HostIntrinsics) {
+        self.host_intrinsics = host_intrinsics;
+    }
+
+    /// Sets the maximum depth of nested calls before evaluation fails with
+    /// [`Error::CallDepthExceeded`], or `None` for no limit.
+    pub fn set_max_call_depth(&mut self, max_call_depth: Option<usize>) {
+        self.max_call_depth = max_call_depth;
+    }
+
+    /// Watches the qubit with the given id, so that evaluation stops with
+    /// [`StepResult::QubitWatchHit`] after an intrinsic changes it in the given way.
+    pub fn watch_qubit(&mut self, sim: &mut impl Backend, qubit: usize, kind: QubitWatchKind) {
+        let (state, qubit_count) = sim.capture_quantum_state();
+        self.qubit_watches.retain(|watch| watch.qubit != qubit);
+        self.qubit_watches.push(QubitWatch {
+            qubit,
+            kind,
+            fingerprint: QubitFingerprint::new(qubit, &state, qubit_count),
+        });
+    }
+
+    /// Gets the number of statements, expressions and block exits evaluated so far, which
+    /// identifies any point where execution can stop, so that a deterministic replay of the
+    /// execution can reach it again.
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Gets the counts of the work done by the evaluation so far.
+    #[must_use]
+    pub fn counts(&self) -> EvalCounts {
+        self.counts
+    }
+
+    /// Makes evaluation stop when it reaches the given position, regardless of breakpoints and
+    /// the step action, so that a replay stops exactly where the original execution did.
+    pub fn replay_to(&mut self, position: u64) {
+        self.replay_to = Some(position);
+    }
+
+    fn reached_replay_target(&mut self) -> bool {
+        if self.replay_to == Some(self.position) {
+            self.replay_to = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Rolls the state back to the given snapshot of it, keeping the current qubit watches.
+    pub fn restore(&mut self, snapshot: &State) {
+        let qubit_watches = std::mem::take(&mut self.qubit_watches);
+        *self = snapshot.clone();
+        self.qubit_watches = qubit_watches;
+    }
+
+    /// Stops watching the qubit with the given id.
+    pub fn unwatch_qubit(&mut self, qubit: usize) {
+        self.qubit_watches.retain(|watch| watch.qubit != qubit);
+    }
+
+    fn check_qubit_watches<B: Backend>(&mut self, sim: &mut TracingBackend<'_, B>) {
+        if self.qubit_watches.is_empty() {
+            return;
+        }
+        let (state, qubit_count) = sim.capture_quantum_state();
+        for watch in &mut self.qubit_watches {
+            if watch.update(&state, qubit_count) && self.qubit_watch_hit.is_none() {
+                self.qubit_watch_hit = Some(watch.qubit);
+            }
+        }
+    }
+
+    fn check_call_depth(&self, call_span: Span) -> Result<(), Error> {
+        match self.max_call_depth {
+            Some(max) if self.call_stack.len() >= max => Err(Error::CallDepthExceeded(
+                max,
+                self.to_global_span(call_span),
+            )),
+            _ => Ok(()),
         }
     }
 
@@ -744,15 +1073,23 @@ impl State {
                 .last()
                 .expect("should have at least one stack frame");
             let res = match exec_graph.get(self.idx as usize) {
-                Some(ExecGraphNode::Bind(pat)) => {
+                Some(ExecGraphNode::Bind(pat, mutability)) => {
                     self.idx += 1;
-                    self.eval_bind(env, globals, *pat);
+                    self.eval_bind(env, globals, *pat, *mutability);
                     continue;
                 }
                 Some(ExecGraphNode::Expr(expr)) => {
                     self.idx += 1;
                     match self.eval_expr(env, sim, globals, out, *expr) {
-                        Ok(()) => continue,
+                        Ok(()) => {
+                            self.position += 1;
+                            let reached_replay_target = self.reached_replay_target();
+                            match self.qubit_watch_hit.take() {
+                                Some(qubit) => StepResult::QubitWatchHit(qubit),
+                                None if reached_replay_target => StepResult::StepIn,
+                                None => continue,
+                            }
+                        }
                         Err(e) => {
                             if self.error_behavior == ErrorBehavior::StopOnError {
                                 let error_str = e.to_string();
@@ -768,7 +1105,11 @@ impl State {
                 }
                 Some(ExecGraphNode::Stmt(stmt)) => {
                     self.idx += 1;
+                    self.position += 1;
                     self.current_span = globals.get_stmt((self.package, *stmt).into()).span;
+                    if self.reached_replay_target() {
+                        return Ok(StepResult::StepIn);
+                    }
 
                     match self.check_for_break(breakpoints, *stmt, step, current_frame) {
                         Some(value) => value,
@@ -829,6 +1170,11 @@ impl State {
                 }
                 Some(ExecGraphNode::BlockEnd(id)) => {
                     self.idx += 1;
+                    self.position += 1;
+                    if self.reached_replay_target() {
+                        self.current_span = block_exit_span(globals, self.package, *id);
+                        return Ok(StepResult::Next);
+                    }
                     match self.check_for_block_exit_break(globals, *id, step, current_frame) {
                         Some((result, span)) => {
                             self.current_span = span;
@@ -903,12 +1249,10 @@ impl State {
         current_frame: usize,
     ) -> Option<(StepResult, Span)> {
         if step == StepAction::Next && current_frame >= self.current_frame_id() {
-            let block = globals.get_block((self.package, block).into());
-            let span = Span {
-                lo: block.span.hi - 1,
-                hi: block.span.hi,
-            };
-            Some((StepResult::Next, span))
+            Some((
+                StepResult::Next,
+                block_exit_span(globals, self.package, block),
+            ))
         } else {
             None
         }
@@ -1112,9 +1456,15 @@ impl State {
         self.update_binding(env, globals, lhs, rhs)
     }
 
-    fn eval_bind(&mut self, env: &mut Env, globals: &impl PackageStoreLookup, pat: PatId) {
+    fn eval_bind(
+        &mut self,
+        env: &mut Env,
+        globals: &impl PackageStoreLookup,
+        pat: PatId,
+        mutability: Mutability,
+    ) {
         let val = self.take_val_register();
-        self.bind_value(env, globals, pat, val);
+        self.bind_value(env, globals, pat, val, mutability);
     }
 
     fn eval_binop(&mut self, op: BinOp, span: Span) -> Result<(), Error> {
@@ -1207,6 +1557,7 @@ impl State {
             }
             CallableImpl::Intrinsic => self.eval_intrinsic(
                 env,
+                globals,
                 callee_id,
                 functor,
                 callee,
@@ -1224,6 +1575,7 @@ impl State {
                     Spec::CtlAdj => specialized_implementation.ctl_adj.as_ref(),
                 }
                 .expect("missing specialization should be a compilation error");
+                self.check_call_depth(callable_span)?;
                 self.push_frame(
                     spec_decl.exec_graph.clone().select(self.exec_graph_config),
                     callee_id,
@@ -1245,6 +1597,7 @@ impl State {
                 Ok(())
             }
             CallableImpl::SimulatableIntrinsic(spec_decl) => {
+                self.check_call_depth(callable_span)?;
                 self.push_frame(
                     spec_decl.exec_graph.clone().select(self.exec_graph_config),
                     callee_id,
@@ -1271,6 +1624,7 @@ impl State {
     fn eval_intrinsic<B: Backend>(
         &mut self,
         env: &mut Env,
+        globals: &impl PackageStoreLookup,
         callee_id: StoreItemId,
         functor: FunctorApp,
         callee: &fir::CallableDecl,
@@ -1284,12 +1638,28 @@ impl State {
         self.push_frame(Vec::new().into(), callee_id, functor);
         self.current_span = callee_span.span;
         self.increment_call_count(callee_id, functor);
+        self.counts.intrinsic_calls += 1;
         let name = &callee.name.name;
+        let host_intrinsic = if self.host_intrinsics.is_empty() {
+            None
+        } else {
+            let qualified_name = qualified_name(globals, callee_id, name);
+            self.host_intrinsics
+                .get(&qualified_name)
+                .cloned()
+                .map(|f| (qualified_name, f))
+        };
+        if name.as_ref() == "DumpMachine" && out.qubits(&env.get_qubits()).is_err() {
+            return Err(Error::OutputFail(callee_span));
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(intrinsic = %name, "calling intrinsic");
         let val = match name.as_ref() {
             "__quantum__rt__qubit_allocate" => {
                 let q = sim.qubit_allocate(&call_stack);
+                self.counts.qubits_allocated += 1;
                 let q = Rc::new(Qubit(q));
-                env.track_qubit(Rc::clone(&q));
+                env.track_qubit(Rc::clone(&q), self.allocation_site());
                 if let Some(counter) = &mut self.qubit_counter {
                     counter.allocated(q.0);
                 }
@@ -1301,13 +1671,25 @@ impl State {
                     .try_deref()
                     .ok_or(Error::QubitDoubleRelease(arg_span))?;
                 env.release_qubit(&qubit);
+                if sim.is_tracing()
+                    && let Some(label) = self.user_qubit_label(env, qubit.0)
+                {
+                    sim.qubit_label(qubit.0, &label, &call_stack);
+                }
                 if sim.qubit_release(qubit.0, &call_stack) {
                     Value::unit()
                 } else {
                     return Err(Error::ReleasedQubitNotZero(qubit.0, arg_span));
                 }
             }
+            _ if host_intrinsic.is_some() => {
+                let (qualified_name, f) = host_intrinsic.expect("host intrinsic should be present");
+                f(arg).map_err(|msg| Error::IntrinsicFail(qualified_name, msg, callee_span))?
+            }
             _ => {
+                let watch_qubits = !self.qubit_watches.is_empty()
+                    && is_state_changing_intrinsic(name)
+                    && !arg.qubits().is_empty();
                 let val = intrinsic::call(
                     name,
                     callee_span,
@@ -1324,6 +1706,9 @@ impl State {
                         callee_span,
                     ));
                 }
+                if watch_qubits {
+                    self.check_qubit_watches(sim);
+                }
                 val
             }
         };
@@ -1594,7 +1979,14 @@ impl State {
         self.set_val_register(update);
     }
 
-    fn bind_value(&self, env: &mut Env, globals: &impl PackageStoreLookup, pat: PatId, val: Value) {
+    fn bind_value(
+        &self,
+        env: &mut Env,
+        globals: &impl PackageStoreLookup,
+        pat: PatId,
+        val: Value,
+        mutability: Mutability,
+    ) {
         let pat = globals.get_pat((self.package, pat).into());
         match &pat.kind {
             PatKind::Bind(variable) => {
@@ -1605,6 +1997,7 @@ impl State {
                         name: variable.name.clone(),
                         value: val,
                         span: variable.span,
+                        mutability,
                     },
                 );
             }
@@ -1612,7 +2005,7 @@ impl State {
             PatKind::Tuple(tup) => {
                 let val_tup = val.unwrap_tuple();
                 for (pat, val) in tup.iter().zip(val_tup.iter()) {
-                    self.bind_value(env, globals, *pat, val.clone());
+                    self.bind_value(env, globals, *pat, val.clone(), mutability);
                 }
             }
         }
@@ -1736,19 +2129,62 @@ impl State {
                     return Err(Error::QubitUniqueness(args_span));
                 }
 
-                self.bind_value(env, globals, spec_pat, Value::Array(ctls.into()));
-                self.bind_value(env, globals, decl_pat, merge_fixed_args(fixed_args, tup));
+                self.bind_value(
+                    env,
+                    globals,
+                    spec_pat,
+                    Value::Array(ctls.into()),
+                    Mutability::Immutable,
+                );
+                self.bind_value(
+                    env,
+                    globals,
+                    decl_pat,
+                    merge_fixed_args(fixed_args, tup),
+                    Mutability::Immutable,
+                );
             }
             None => self.bind_value(
                 env,
                 globals,
                 decl_pat,
                 merge_fixed_args(fixed_args, args_val),
+                Mutability::Immutable,
             ),
         }
         Ok(())
     }
 
+    /// The location of the innermost call outside the core library on the stack of a qubit
+    /// allocation, so that qubits allocated through the core array helpers are attributed to the
+    /// code that allocated the array.
+    fn allocation_site(&self) -> PackageSpan {
+        let frames = self.call_stack.frames();
+        let frame = frames
+            .iter()
+            .rev()
+            .find(|frame| frame.caller != PackageId::CORE)
+            .or(frames.last())
+            .expect("allocation intrinsic should be on the call stack");
+        PackageSpan {
+            package: map_fir_package_to_hir(frame.caller),
+            span: frame.span,
+        }
+    }
+
+    /// Gets the label of a qubit from the variables of callables outside the core library, so
+    /// that the parameters of the core release helpers don't hide the user's variable names.
+    fn user_qubit_label(&self, env: &Env, q: usize) -> Option<String> {
+        let frames = self.call_stack.frames();
+        env.qubit_labels(|frame_id| {
+            frame_id == 0
+                || frames
+                    .get(frame_id - 1)
+                    .is_none_or(|frame| frame.id.package != PackageId::CORE)
+        })
+        .remove(&q)
+    }
+
     fn to_global_span(&self, span: Span) -> PackageSpan {
         PackageSpan {
             package: map_fir_package_to_hir(self.package),
@@ -1795,6 +2231,28 @@ impl State {
                     Err(Error::QubitsNotCounted(span))
                 }
             }
+            "StartTimer" => {
+                // There is no monotonic clock on wasm32, so timing is only available natively.
+                if cfg!(target_arch = "wasm32") {
+                    return Err(Error::IntrinsicFail(
+                        name.to_string(),
+                        "timing is not supported on this platform".to_string(),
+                        span,
+                    ));
+                }
+                if self.timer_start.replace(Instant::now()).is_some() {
+                    Err(Error::TimerAlreadyStarted(span))
+                } else {
+                    Ok(Value::unit())
+                }
+            }
+            "StopTimer" => {
+                if let Some(start) = self.timer_start.take() {
+                    Ok(Value::Double(start.elapsed().as_secs_f64() * 1000.0))
+                } else {
+                    Err(Error::TimerNotStarted(span))
+                }
+            }
             _ => panic!("unknown counting call"),
         }
     }
@@ -1911,38 +2369,84 @@ fn lit_to_val(lit: &Lit) -> Value {
 }
 
 fn eval_binop_eq(lhs_val: Value, rhs_val: Value, rhs_span: PackageSpan) -> Result<Value, Error> {
-    match (lhs_val, rhs_val) {
-        (Value::Result(val::Result::Id(_)), _) | (_, Value::Result(val::Result::Id(_))) => {
-            // Comparison of result ids is nonsensical, so we prevent it.
-            // This code path is reachable when using the circuit builder backend
-            // since we don't currently do runtime capability analysis
-            // to prevent executing programs that do result comparisons.
-            Err(Error::ResultComparisonUnsupported(rhs_span))
+    Ok(Value::Bool(compare_values(&lhs_val, &rhs_val, rhs_span)?))
+}
+
+fn eval_binop_neq(lhs_val: Value, rhs_val: Value, rhs_span: PackageSpan) -> Result<Value, Error> {
+    Ok(Value::Bool(!compare_values(&lhs_val, &rhs_val, rhs_span)?))
+}
+
+/// A measurement result that has no meaningful value to compare, ordered by which error takes
+/// precedence when both kinds are found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum UncomparableResult {
+    None,
+    Loss,
+    Id,
+}
+
+/// Compares two values for equality, ensuring in the same walk that no measurement results within
+/// them, including those nested in arrays and tuples, are result ids or losses.
+fn compare_values(lhs: &Value, rhs: &Value, rhs_span: PackageSpan) -> Result<bool, Error> {
+    let mut found = UncomparableResult::None;
+    let equal = values_equal(lhs, rhs, &mut found);
+    match found {
+        // Comparison of result ids is nonsensical, so we prevent it.
+        // This code path is reachable when using the circuit builder backend
+        // since we don't currently do runtime capability analysis
+        // to prevent executing programs that do result comparisons.
+        UncomparableResult::Id => Err(Error::ResultComparisonUnsupported(rhs_span)),
+        // Loss is not comparable and should be checked ahead of time, so treat this as a runtime
+        // failure.
+        UncomparableResult::Loss => Err(Error::ResultLossComparisonUnsupported(rhs_span)),
+        UncomparableResult::None => Ok(equal),
+    }
+}
+
+/// Compares the values without stopping at the first difference, so that every result within
+/// them is checked, recording the uncomparable result with the highest precedence.
+fn values_equal(lhs: &Value, rhs: &Value, found: &mut UncomparableResult) -> bool {
+    match (lhs, rhs) {
+        (Value::Array(lhs_items), Value::Array(rhs_items)) => {
+            items_equal(lhs_items, rhs_items, found)
+        }
+        (Value::Tuple(lhs_items, lhs_id), Value::Tuple(rhs_items, rhs_id)) => {
+            items_equal(lhs_items, rhs_items, found) && lhs_id == rhs_id
         }
-        (Value::Result(val::Result::Loss), _) | (_, Value::Result(val::Result::Loss)) => {
-            // Loss is not comparable and should be checked ahead of time, so treat this as a runtime
-            // failure.
-            Err(Error::ResultLossComparisonUnsupported(rhs_span))
+        _ => {
+            find_uncomparable_results(lhs, found);
+            find_uncomparable_results(rhs, found);
+            lhs == rhs
         }
-        (lhs, rhs) => Ok(Value::Bool(lhs == rhs)),
     }
 }
 
-fn eval_binop_neq(lhs_val: Value, rhs_val: Value, rhs_span: PackageSpan) -> Result<Value, Error> {
-    match (lhs_val, rhs_val) {
-        (Value::Result(val::Result::Id(_)), _) | (_, Value::Result(val::Result::Id(_))) => {
-            // Comparison of result ids is nonsensical, so we prevent it.
-            // This code path is reachable when using the circuit builder backend
-            // since we don't currently do runtime capability analysis
-            // to prevent executing programs that do result comparisons.
-            Err(Error::ResultComparisonUnsupported(rhs_span))
+fn items_equal(lhs: &[Value], rhs: &[Value], found: &mut UncomparableResult) -> bool {
+    let mut equal = lhs.len() == rhs.len();
+    for (lhs, rhs) in lhs.iter().zip(rhs) {
+        equal &= values_equal(lhs, rhs, found);
+    }
+    for extra in lhs.iter().skip(rhs.len()).chain(rhs.iter().skip(lhs.len())) {
+        find_uncomparable_results(extra, found);
+    }
+    equal
+}
+
+fn find_uncomparable_results(value: &Value, found: &mut UncomparableResult) {
+    match value {
+        Value::Result(val::Result::Id(_)) => *found = UncomparableResult::Id,
+        Value::Result(val::Result::Loss) => *found = (*found).max(UncomparableResult::Loss),
+        Value::Array(items) => {
+            for item in items.iter() {
+                find_uncomparable_results(item, found);
+            }
         }
-        (Value::Result(val::Result::Loss), _) | (_, Value::Result(val::Result::Loss)) => {
-            // Loss is not comparable and should be checked ahead of time, so treat this as a runtime
-            // failure.
-            Err(Error::ResultLossComparisonUnsupported(rhs_span))
+        Value::Tuple(items, _) => {
+            for item in items.iter() {
+                find_uncomparable_results(item, found);
+            }
         }
-        (lhs, rhs) => Ok(Value::Bool(lhs != rhs)),
+        _ => {}
     }
 }
 
@@ -2296,10 +2800,14 @@ fn eval_binop_shl(lhs_val: Value, rhs_val: Value, rhs_span: PackageSpan) -> Resu
     Ok(match lhs_val {
         Value::BigInt(val) => {
             let rhs = rhs_val.unwrap_int();
+            let shift: u32 = rhs
+                .unsigned_abs()
+                .try_into()
+                .or(Err(Error::IntTooLarge(rhs, rhs_span)))?;
             if rhs > 0 {
-                Value::BigInt(val << rhs)
+                Value::BigInt(val << shift)
             } else {
-                Value::BigInt(val >> rhs.abs())
+                Value::BigInt(val >> shift)
             }
         }
         Value::Int(val) => {
@@ -2326,10 +2834,14 @@ fn eval_binop_shr(lhs_val: Value, rhs_val: Value, rhs_span: PackageSpan) -> Resu
     Ok(match lhs_val {
         Value::BigInt(val) => {
             let rhs = rhs_val.unwrap_int();
+            let shift: u32 = rhs
+                .unsigned_abs()
+                .try_into()
+                .or(Err(Error::IntTooLarge(rhs, rhs_span)))?;
             if rhs > 0 {
-                Value::BigInt(val >> rhs)
+                Value::BigInt(val >> shift)
             } else {
-                Value::BigInt(val << rhs.abs())
+                Value::BigInt(val << shift)
             }
         }
         Value::Int(val) => {
@@ -2464,6 +2976,21 @@ fn is_updatable_in_place(env: &Env, expr: &Expr) -> (bool, bool) {
     }
 }
 
+/// Builds the fully qualified name of a callable from its parent namespace, used as the key for
+/// host-provided intrinsics.
+fn qualified_name(globals: &impl PackageStoreLookup, id: StoreItemId, name: &str) -> String {
+    let parent = globals.get_item(id).parent.map(|item| {
+        globals.get_item(StoreItemId {
+            package: id.package,
+            item,
+        })
+    });
+    match parent.map(|parent| &parent.kind) {
+        Some(ItemKind::Namespace(namespace, _)) => format!("{}.{name}", namespace.name),
+        _ => name.to_string(),
+    }
+}
+
 fn is_counting_call(name: &str) -> bool {
     matches!(
         name,
@@ -2473,14 +3000,26 @@ fn is_counting_call(name: &str) -> bool {
             | "StopCountingFunction"
             | "StartCountingQubits"
             | "StopCountingQubits"
+            | "StartTimer"
+            | "StopTimer"
     )
 }
 
+/// The span of the closing brace of the block, where execution stops when stepping over the
+/// end of the block.
+fn block_exit_span(globals: &impl PackageStoreLookup, package: PackageId, block: BlockId) -> Span {
+    let block = globals.get_block((package, block).into());
+    Span {
+        lo: block.span.hi - 1,
+        hi: block.span.hi,
+    }
+}
+
 fn make_counting_key(id: StoreItemId, functor: FunctorApp) -> CallableCountKey {
     (id, functor.adjoint, functor.controlled > 0)
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct QubitCounter {
     seen: FxHashSet<usize>,
     count: i64,
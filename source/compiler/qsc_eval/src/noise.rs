@@ -1,6 +1,9 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
 #[derive(Copy, Clone, Debug)]
 pub struct PauliNoise {
     /// Pauli noise distribution for sampling.
@@ -38,3 +41,117 @@ impl PauliNoise {
         self.distribution[2] <= f64::EPSILON
     }
 }
+
+/// Probabilities of a Pauli X, Y, or Z error occurring after an operation.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PauliProbabilities {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// Amplitude and phase damping, characterized by the T1 and T2 times of the device and the
+/// duration of each gate, in the same time unit.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Relaxation {
+    pub t1: f64,
+    pub t2: f64,
+    /// Gate durations keyed by gate name, for example `h` or `cx`.
+    #[serde(default)]
+    pub durations: BTreeMap<String, f64>,
+}
+
+/// A device noise model that can be loaded from a JSON file.
+///
+/// Gate names are the lowercase names of the backend operations, for example `x`, `cx`, `rz`,
+/// or `mresetz`. Gates without an entry in `gates` use the default `pauli` probabilities.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NoiseConfig {
+    /// Pauli error probabilities applied after any gate without its own entry.
+    pub pauli: PauliProbabilities,
+    /// Pauli error probabilities for specific gates.
+    pub gates: BTreeMap<String, PauliProbabilities>,
+    /// Probability that a measurement reports the opposite result.
+    pub readout: f64,
+    /// Probability that a qubit is lost, applied before a measurement.
+    pub loss: f64,
+    /// Relaxation during gates, applied in addition to the Pauli error probabilities.
+    pub relaxation: Option<Relaxation>,
+}
+
+impl NoiseConfig {
+    /// Parses and validates a noise configuration from JSON.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that all probabilities and relaxation times describe a valid noise model.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.readout) {
+            return Err("readout error probability must be in between 0.0 and 1.0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.loss) {
+            return Err("loss probability must be in between 0.0 and 1.0".to_string());
+        }
+        if let Some(relaxation) = &self.relaxation {
+            if relaxation.t1 <= 0.0 || relaxation.t2 <= 0.0 || relaxation.t2 > 2.0 * relaxation.t1 {
+                return Err("relaxation times must satisfy 0 < T2 <= 2 * T1".to_string());
+            }
+            if relaxation.durations.values().any(|&d| d < 0.0) {
+                return Err("gate durations must not be negative".to_string());
+            }
+        }
+        self.default_noise()?;
+        for gate in self.gate_names() {
+            self.noise_for_gate(gate)?;
+        }
+        Ok(())
+    }
+
+    /// The Pauli noise applied after gates that have no specific configuration.
+    pub fn default_noise(&self) -> Result<PauliNoise, String> {
+        let p = self.pauli;
+        PauliNoise::from_probabilities(p.x, p.y, p.z)
+    }
+
+    /// The Pauli noise applied after the given gate. Relaxation during the gate is approximated
+    /// by its Pauli twirl and added to the configured Pauli error probabilities.
+    pub fn noise_for_gate(&self, gate: &str) -> Result<PauliNoise, String> {
+        let p = self.gates.get(gate).unwrap_or(&self.pauli);
+        let (mut px, mut py, mut pz) = (p.x, p.y, p.z);
+        let relaxation = self.relaxation.as_ref().and_then(|relaxation| {
+            relaxation
+                .durations
+                .get(gate)
+                .map(|&duration| (relaxation, duration))
+        });
+        if let Some((relaxation, duration)) = relaxation {
+            let amplitude = 1.0 - (-duration / relaxation.t1).exp();
+            let phase = 1.0 - (-duration / relaxation.t2).exp();
+            px += amplitude / 4.0;
+            py += amplitude / 4.0;
+            pz += phase / 2.0 - amplitude / 4.0;
+        }
+        PauliNoise::from_probabilities(px, py, pz)
+            .map_err(|e| format!("invalid noise for gate `{gate}`: {e}"))
+    }
+
+    /// The names of all gates that have gate-specific noise.
+    pub fn gate_names(&self) -> impl Iterator<Item = &str> {
+        let durations = self
+            .relaxation
+            .iter()
+            .flat_map(|relaxation| relaxation.durations.keys());
+        self.gates
+            .keys()
+            .chain(durations)
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+    }
+}
@@ -1,15 +1,48 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use std::io::{Cursor, Write};
+use std::{
+    io::{Cursor, Write},
+    num::NonZeroUsize,
+};
 
-use crate::state::{fmt_complex, format_state_id};
+use crate::{
+    QubitInfo,
+    state::{fmt_complex, format_state_id},
+};
 use num_bigint::BigUint;
 use num_complex::Complex64;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Error;
 
+/// Limits on the state output of `DumpMachine` and `DumpRegister`. The default sends the
+/// whole state at once through [`Receiver::state`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateDumpConfig {
+    /// When set, only this many entries with the largest probabilities are sent.
+    pub top_k: Option<usize>,
+    /// When set, entries are sent in chunks of at most this many entries.
+    pub chunk_size: Option<NonZeroUsize>,
+}
+
+/// A part of a state dump, sent through [`Receiver::state_chunk`] when the receiver
+/// asks for a limited dump.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateChunk {
+    pub entries: Vec<(BigUint, Complex64)>,
+    pub qubit_count: usize,
+    /// The position of this chunk in the dump, starting at zero.
+    pub index: usize,
+    /// Whether this is the final chunk of the dump.
+    pub is_last: bool,
+    /// The total probability of the entries sent so far, including this chunk.
+    pub captured_probability: f64,
+}
+
 pub trait Receiver {
     /// Receive state output
     /// # Errors
@@ -25,15 +58,128 @@ pub trait Receiver {
     /// # Errors
     /// This will return an error if handling the output fails.
     fn message(&mut self, msg: &str) -> Result<(), Error>;
+
+    /// Receive the allocated qubits with their labels, sent before the state output of
+    /// `DumpMachine` so that receivers can annotate the state with variable names.
+    /// # Errors
+    /// This will return an error if handling the output fails.
+    fn qubits(&mut self, _qubits: &[QubitInfo]) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// The limits to apply to state output. When these differ from the default,
+    /// state output is sent through [`Receiver::state_chunk`] instead of [`Receiver::state`].
+    fn state_dump_config(&self) -> StateDumpConfig {
+        StateDumpConfig::default()
+    }
+
+    /// Receive a chunk of state output. The default sends a dump that fits in one chunk
+    /// through [`Receiver::state`], so receivers that set a chunk size must override this.
+    /// # Errors
+    /// This will return an error if handling the output fails, or if the default is used
+    /// for a dump with more than one chunk.
+    fn state_chunk(&mut self, chunk: StateChunk) -> Result<(), Error> {
+        if chunk.index == 0 && chunk.is_last {
+            self.state(chunk.entries, chunk.qubit_count)
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+/// Sends state output to the receiver, applying the receiver's [`StateDumpConfig`].
+/// Without a top-K limit, chunks are taken from `state` as they are sent.
+/// # Errors
+/// This will return an error if handling the output fails.
+pub fn send_state<R: Receiver + ?Sized>(
+    out: &mut R,
+    state: impl IntoIterator<Item = (BigUint, Complex64)>,
+    qubit_count: usize,
+) -> Result<(), Error> {
+    let config = out.state_dump_config();
+    if config == StateDumpConfig::default() {
+        return out.state(state.into_iter().collect(), qubit_count);
+    }
+
+    match config.top_k {
+        Some(top_k) => {
+            let mut state: Vec<_> = state.into_iter().collect();
+            if top_k < state.len() {
+                state.select_nth_unstable_by(top_k, |(_, a), (_, b)| {
+                    b.norm_sqr().total_cmp(&a.norm_sqr())
+                });
+                state.truncate(top_k);
+                state.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            }
+            send_chunks(out, state.into_iter(), qubit_count, config.chunk_size)
+        }
+        None => send_chunks(out, state.into_iter(), qubit_count, config.chunk_size),
+    }
+}
+
+fn send_chunks<R: Receiver + ?Sized>(
+    out: &mut R,
+    state: impl Iterator<Item = (BigUint, Complex64)>,
+    qubit_count: usize,
+    chunk_size: Option<NonZeroUsize>,
+) -> Result<(), Error> {
+    let chunk_size = chunk_size.map_or(usize::MAX, NonZeroUsize::get);
+    let mut entries = state.peekable();
+    let mut captured_probability = 0.0;
+    let mut index = 0;
+    loop {
+        let chunk: Vec<_> = entries.by_ref().take(chunk_size).collect();
+        captured_probability += chunk.iter().map(|(_, amp)| amp.norm_sqr()).sum::<f64>();
+        let is_last = entries.peek().is_none();
+        out.state_chunk(StateChunk {
+            entries: chunk,
+            qubit_count,
+            index,
+            is_last,
+            captured_probability,
+        })?;
+        if is_last {
+            return Ok(());
+        }
+        index += 1;
+    }
 }
 
 pub struct GenericReceiver<'a> {
     writer: &'a mut dyn Write,
+    state_dump_config: StateDumpConfig,
 }
 
 impl<'a> GenericReceiver<'a> {
     pub fn new(writer: &'a mut impl Write) -> Self {
-        Self { writer }
+        Self {
+            writer,
+            state_dump_config: StateDumpConfig::default(),
+        }
+    }
+
+    /// Limits the state output written by this receiver.
+    #[must_use]
+    pub fn with_state_dump_config(mut self, state_dump_config: StateDumpConfig) -> Self {
+        self.state_dump_config = state_dump_config;
+        self
+    }
+
+    fn write_state_entries(
+        &mut self,
+        state: Vec<(BigUint, Complex64)>,
+        qubit_count: usize,
+    ) -> Result<(), Error> {
+        for (id, state) in state {
+            writeln!(
+                self.writer,
+                "{}: {}",
+                format_state_id(&id, qubit_count),
+                fmt_complex(&state),
+            )
+            .map_err(|_| Error)?;
+        }
+        Ok(())
     }
 }
 
@@ -41,12 +187,28 @@ impl Receiver for GenericReceiver<'_> {
     fn state(&mut self, state: Vec<(BigUint, Complex64)>, qubit_count: usize) -> Result<(), Error> {
         writeln!(self.writer, "STATE:").map_err(|_| Error)?;
         if qubit_count > 0 {
-            for (id, state) in state {
+            self.write_state_entries(state, qubit_count)?;
+        } else {
+            writeln!(self.writer, "No qubits allocated").map_err(|_| Error)?;
+        }
+        Ok(())
+    }
+
+    fn state_dump_config(&self) -> StateDumpConfig {
+        self.state_dump_config
+    }
+
+    fn state_chunk(&mut self, chunk: StateChunk) -> Result<(), Error> {
+        if chunk.index == 0 {
+            writeln!(self.writer, "STATE:").map_err(|_| Error)?;
+        }
+        if chunk.qubit_count > 0 {
+            self.write_state_entries(chunk.entries, chunk.qubit_count)?;
+            if chunk.is_last {
                 writeln!(
                     self.writer,
-                    "{}: {}",
-                    format_state_id(&id, qubit_count),
-                    fmt_complex(&state),
+                    "Captured probability: {:.4}",
+                    chunk.captured_probability
                 )
                 .map_err(|_| Error)?;
             }
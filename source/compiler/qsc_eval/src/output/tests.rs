@@ -0,0 +1,156 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{Error, GenericReceiver, Receiver, StateChunk, StateDumpConfig, send_state};
+use expect_test::{Expect, expect};
+use num_bigint::BigUint;
+use num_complex::Complex64;
+use std::num::NonZeroUsize;
+
+fn two_qubit_state() -> Vec<(BigUint, Complex64)> {
+    vec![
+        (BigUint::from(0_u32), Complex64::new(0.3, 0.0)),
+        (BigUint::from(1_u32), Complex64::new(0.7, 0.0)),
+        (BigUint::from(2_u32), Complex64::new(0.2, 0.0)),
+        (BigUint::from(3_u32), Complex64::new(0.6, 0.0)),
+    ]
+}
+
+fn check_state_output(config: StateDumpConfig, expect: &Expect) {
+    let mut buffer = Vec::new();
+    let mut out = GenericReceiver::new(&mut buffer).with_state_dump_config(config);
+    send_state(&mut out, two_qubit_state(), 2).expect("state output should succeed");
+    expect.assert_eq(&String::from_utf8(buffer).expect("output should be valid UTF-8"));
+}
+
+#[test]
+fn default_config_sends_whole_state() {
+    check_state_output(
+        StateDumpConfig::default(),
+        &expect![[r#"
+            STATE:
+            |00⟩: 0.3000+0.0000𝑖
+            |01⟩: 0.7000+0.0000𝑖
+            |10⟩: 0.2000+0.0000𝑖
+            |11⟩: 0.6000+0.0000𝑖
+        "#]],
+    );
+}
+
+#[test]
+fn top_k_keeps_most_probable_entries() {
+    check_state_output(
+        StateDumpConfig {
+            top_k: Some(2),
+            chunk_size: None,
+        },
+        &expect![[r#"
+            STATE:
+            |01⟩: 0.7000+0.0000𝑖
+            |11⟩: 0.6000+0.0000𝑖
+            Captured probability: 0.8500
+        "#]],
+    );
+}
+
+#[test]
+fn chunked_output_matches_whole_state_with_footer() {
+    check_state_output(
+        StateDumpConfig {
+            top_k: None,
+            chunk_size: NonZeroUsize::new(3),
+        },
+        &expect![[r#"
+            STATE:
+            |00⟩: 0.3000+0.0000𝑖
+            |01⟩: 0.7000+0.0000𝑖
+            |10⟩: 0.2000+0.0000𝑖
+            |11⟩: 0.6000+0.0000𝑖
+            Captured probability: 0.9800
+        "#]],
+    );
+}
+
+struct ChunkRecorder(Vec<(usize, usize, bool)>);
+
+impl Receiver for ChunkRecorder {
+    fn state(&mut self, _: Vec<(BigUint, Complex64)>, _: usize) -> Result<(), Error> {
+        unreachable!("state should be sent in chunks")
+    }
+
+    fn matrix(&mut self, _: Vec<Vec<Complex64>>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn message(&mut self, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn state_dump_config(&self) -> StateDumpConfig {
+        StateDumpConfig {
+            top_k: None,
+            chunk_size: NonZeroUsize::new(3),
+        }
+    }
+
+    fn state_chunk(&mut self, chunk: StateChunk) -> Result<(), Error> {
+        self.0
+            .push((chunk.index, chunk.entries.len(), chunk.is_last));
+        Ok(())
+    }
+}
+
+#[test]
+fn chunks_are_numbered_and_last_chunk_is_marked() {
+    let mut out = ChunkRecorder(Vec::new());
+    send_state(&mut out, two_qubit_state(), 2).expect("state output should succeed");
+    assert_eq!(out.0, vec![(0, 3, false), (1, 1, true)]);
+}
+
+struct SingleStateReceiver(usize);
+
+impl Receiver for SingleStateReceiver {
+    fn state(&mut self, _: Vec<(BigUint, Complex64)>, _: usize) -> Result<(), Error> {
+        self.0 += 1;
+        Ok(())
+    }
+
+    fn matrix(&mut self, _: Vec<Vec<Complex64>>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn message(&mut self, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn state_dump_config(&self) -> StateDumpConfig {
+        StateDumpConfig {
+            top_k: Some(2),
+            chunk_size: NonZeroUsize::new(1),
+        }
+    }
+}
+
+#[test]
+fn default_state_chunk_fails_for_more_than_one_chunk() {
+    let mut out = SingleStateReceiver(0);
+    assert_eq!(send_state(&mut out, two_qubit_state(), 2), Err(Error));
+    assert_eq!(out.0, 0);
+}
+
+#[test]
+fn top_k_with_chunks_keeps_most_probable_entries_in_order() {
+    check_state_output(
+        StateDumpConfig {
+            top_k: Some(3),
+            chunk_size: NonZeroUsize::new(2),
+        },
+        &expect![[r#"
+            STATE:
+            |00⟩: 0.3000+0.0000𝑖
+            |01⟩: 0.7000+0.0000𝑖
+            |11⟩: 0.6000+0.0000𝑖
+            Captured probability: 0.9400
+        "#]],
+    );
+}
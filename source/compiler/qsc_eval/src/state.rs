@@ -6,6 +6,8 @@ mod tests;
 
 use num_bigint::BigUint;
 use num_complex::{Complex, Complex64};
+use qsc_fir::fir::Pauli;
+use rustc_hash::FxHashMap;
 use std::{f64::consts::FRAC_1_SQRT_2, fmt::Write};
 
 #[must_use]
@@ -39,6 +41,54 @@ pub fn fmt_basis_state_label(id: &BigUint, qubit_count: usize) -> String {
     format!("{:0>qubit_count$}", id.to_str_radix(2))
 }
 
+/// Computes the expectation value ⟨ψ|P|ψ⟩ of the Pauli operator `P` that applies each given
+/// Pauli to the corresponding qubit, for a state in the format returned by
+/// `Backend::capture_quantum_state`. Each qubit must be less than `qubit_count` and appear at
+/// most once.
+#[must_use]
+pub fn pauli_expectation(
+    state: &[(BigUint, Complex64)],
+    qubit_count: usize,
+    paulis: &[(Pauli, usize)],
+) -> f64 {
+    let amplitudes = state
+        .iter()
+        .map(|(id, amplitude)| (id, amplitude))
+        .collect::<FxHashMap<_, _>>();
+    let mut expectation = Complex64::new(0.0, 0.0);
+    for (id, amplitude) in state {
+        // Apply P to the basis state, tracking the resulting basis state and phase.
+        let mut target = id.clone();
+        let mut phase = Complex64::new(1.0, 0.0);
+        for &(pauli, q) in paulis {
+            // Qubits are ordered with the first allocated qubit as the most significant bit.
+            let bit = (qubit_count - q - 1) as u64;
+            let is_one = id.bit(bit);
+            match pauli {
+                Pauli::I => {}
+                Pauli::X => target.set_bit(bit, !is_one),
+                Pauli::Y => {
+                    target.set_bit(bit, !is_one);
+                    phase *= if is_one {
+                        -Complex64::i()
+                    } else {
+                        Complex64::i()
+                    };
+                }
+                Pauli::Z => {
+                    if is_one {
+                        phase = -phase;
+                    }
+                }
+            }
+        }
+        if let Some(target_amplitude) = amplitudes.get(&target) {
+            expectation += target_amplitude.conj() * phase * amplitude;
+        }
+    }
+    expectation.re
+}
+
 #[must_use]
 fn is_significant(x: f64) -> bool {
     x.abs() > 1e-9
@@ -3,13 +3,16 @@
 
 use super::{
     AlgebraicNumber, CartesianForm, ComplexNumber, DecimalNumber, PolarForm, RationalNumber,
-    RealNumber, Term, get_matrix_latex, get_state_latex, write_latex_for_algebraic_number,
-    write_latex_for_cartesian_form, write_latex_for_complex_number, write_latex_for_decimal_number,
-    write_latex_for_polar_form, write_latex_for_real_number, write_latex_for_term,
+    RealNumber, Term, get_matrix_latex, get_state_latex, pauli_expectation,
+    write_latex_for_algebraic_number, write_latex_for_cartesian_form,
+    write_latex_for_complex_number, write_latex_for_decimal_number, write_latex_for_polar_form,
+    write_latex_for_real_number, write_latex_for_term,
 };
 use crate::state::{is_fractional_part_significant, is_significant};
 use expect_test::{Expect, expect};
+use num_bigint::BigUint;
 use num_complex::Complex64;
+use qsc_fir::fir::Pauli;
 use std::{
     f64::consts::{FRAC_1_SQRT_2, PI},
     time::Instant,
@@ -818,3 +821,29 @@ fn check_get_latex_perf() {
         Instant::now().duration_since(start)
     );
 }
+
+#[test]
+fn pauli_expectation_of_bell_state() {
+    let amplitude = Complex64::new(FRAC_1_SQRT_2, 0.0);
+    // (|00⟩ + |11⟩) / √2
+    let state = vec![
+        (BigUint::from(0_u8), amplitude),
+        (BigUint::from(3_u8), amplitude),
+    ];
+    let zz = pauli_expectation(&state, 2, &[(Pauli::Z, 0), (Pauli::Z, 1)]);
+    let yy = pauli_expectation(&state, 2, &[(Pauli::Y, 0), (Pauli::Y, 1)]);
+    let xi = pauli_expectation(&state, 2, &[(Pauli::X, 0), (Pauli::I, 1)]);
+    assert!((zz - 1.0).abs() < 1e-12);
+    assert!((yy + 1.0).abs() < 1e-12);
+    assert!(xi.abs() < 1e-12);
+}
+
+#[test]
+fn pauli_expectation_uses_qubit_order() {
+    // |10⟩, with qubit 0 in |1⟩ and qubit 1 in |0⟩.
+    let state = vec![(BigUint::from(2_u8), Complex64::new(1.0, 0.0))];
+    let z0 = pauli_expectation(&state, 2, &[(Pauli::Z, 0)]);
+    let z1 = pauli_expectation(&state, 2, &[(Pauli::Z, 1)]);
+    assert!((z0 + 1.0).abs() < 1e-12);
+    assert!((z1 - 1.0).abs() < 1e-12);
+}
@@ -1330,6 +1330,29 @@ fn binop_orl_shortcut() {
     check_expr("", r#"true or (fail "Shouldn't Fail")"#, &expect!["true"]);
 }
 
+#[test]
+fn binop_andl_orl_shortcut_skips_qubit_allocation() {
+    check_expr(
+        "",
+        indoc! {r#"{
+            let a = false and { use q = Qubit(); X(q); true };
+            let o = true or { use q = Qubit(); X(q); false };
+            use q = Qubit();
+            (a, o, $"{q}")
+        }"#},
+        &expect!["(false, true, Qubit0)"],
+    );
+}
+
+#[test]
+fn assignop_andl_and_orl_shortcut_skip_side_effects() {
+    check_expr(
+        "",
+        "{ mutable count = 0; mutable a = false; mutable o = true; set a and= { set count += 1; true }; set o or= { set count += 1; false }; (a, o, count) }",
+        &expect!["(false, true, 0)"],
+    );
+}
+
 #[test]
 fn binop_shl_bigint() {
     check_expr("", "4L <<< 2", &expect!["16"]);
@@ -1340,6 +1363,28 @@ fn binop_shl_bigint_negative() {
     check_expr("", "4L <<< -2", &expect!["1"]);
 }
 
+#[test]
+fn binop_shl_bigint_overflow() {
+    check_expr(
+        "",
+        "1L <<< 4294967296",
+        &expect![[r#"
+            IntTooLarge(
+                4294967296,
+                PackageSpan {
+                    package: PackageId(
+                        2,
+                    ),
+                    span: Span {
+                        lo: 7,
+                        hi: 17,
+                    },
+                },
+            )
+        "#]],
+    );
+}
+
 #[test]
 fn binop_shl_int() {
     check_expr("", "4 <<< 2", &expect!["16"]);
@@ -1388,6 +1433,28 @@ fn binop_shr_bigint_negative() {
     check_expr("", "4L >>> -2", &expect!["16"]);
 }
 
+#[test]
+fn binop_shr_bigint_overflow() {
+    check_expr(
+        "",
+        "1L >>> 4294967296",
+        &expect![[r#"
+            IntTooLarge(
+                4294967296,
+                PackageSpan {
+                    package: PackageId(
+                        2,
+                    ),
+                    span: Span {
+                        lo: 7,
+                        hi: 17,
+                    },
+                },
+            )
+        "#]],
+    );
+}
+
 #[test]
 fn binop_shr_int() {
     check_expr("", "4 >>> 2", &expect!["1"]);
@@ -1605,6 +1672,36 @@ fn for_loop_range_expr() {
     );
 }
 
+#[test]
+fn for_loop_range_is_not_materialized_expr() {
+    // A range with close to `Int` max elements would exhaust memory if it were collected into
+    // an array before iterating.
+    check_expr(
+        "",
+        indoc! {r#"{
+            for i in 0..9_223_372_036_854_775_806 {
+                if i == 3 {
+                    fail "Stopped at 3";
+                }
+            }
+        }"#},
+        &expect![[r#"
+            UserFail(
+                "Stopped at 3",
+                PackageSpan {
+                    package: PackageId(
+                        2,
+                    ),
+                    span: Span {
+                        lo: 78,
+                        hi: 97,
+                    },
+                },
+            )
+        "#]],
+    );
+}
+
 #[test]
 fn for_loop_array_expr() {
     check_expr(
@@ -2032,6 +2129,153 @@ fn while_expr() {
     );
 }
 
+#[test]
+fn while_in_operation_expr() {
+    check_expr(
+        indoc! {"
+            namespace Test {
+                operation CountOnes(n : Int) : Int {
+                    mutable ones = 0;
+                    mutable i = 0;
+                    use q = Qubit();
+                    while i < n {
+                        X(q);
+                        if M(q) == One {
+                            set ones += 1;
+                        }
+                        set i += 1;
+                    }
+                    Reset(q);
+                    ones
+                }
+            }
+        "},
+        "Test.CountOnes(5)",
+        &expect!["3"],
+    );
+}
+
+#[test]
+fn generic_callable_declarations_expr() {
+    check_expr(
+        indoc! {"
+            namespace Test {
+                operation Identity<'T>(x : 'T) : 'T {
+                    x
+                }
+                function Swapped<'T, 'U>(pair : ('T, 'U)) : ('U, 'T) {
+                    let (a, b) = pair;
+                    (b, a)
+                }
+            }
+        "},
+        "(Test.Identity(1), Test.Identity(true), Test.Swapped((true, 2)))",
+        &expect!["(1, true, (2, true))"],
+    );
+}
+
+#[test]
+fn generic_array_function_expr() {
+    check_expr(
+        indoc! {"
+            namespace Test {
+                function Mapped<'T, 'U>(f : 'T -> 'U, xs : 'T[]) : 'U[] {
+                    mutable ys = [];
+                    for x in xs {
+                        set ys += [f(x)];
+                    }
+                    ys
+                }
+                function IsPositive(x : Int) : Bool {
+                    x > 0
+                }
+                function BoolAsInt(b : Bool) : Int {
+                    if b { 1 } else { 0 }
+                }
+            }
+        "},
+        indoc! {"{
+            let positive = Test.Mapped(Test.IsPositive, [-1, 0, 2]);
+            (positive, Test.Mapped(Test.BoolAsInt, positive))
+        }"},
+        &expect!["([false, false, true], [0, 0, 1])"],
+    );
+}
+
+#[test]
+fn functor_characteristics_on_callable_types_expr() {
+    check_expr(
+        indoc! {"
+            namespace Test {
+                operation Flip(q : Qubit) : Unit is Adj + Ctl {
+                    X(q);
+                }
+                operation ApplyThenUndo(op : (Qubit => Unit is Adj), q : Qubit) : Unit {
+                    op(q);
+                    Adjoint op(q);
+                }
+                operation Run() : (Result, Result) {
+                    use (c, t) = (Qubit(), Qubit());
+                    ApplyThenUndo(Flip, t);
+                    let undone = M(t);
+                    X(c);
+                    Controlled Flip([c], t);
+                    let controlled = M(t);
+                    Reset(c);
+                    Reset(t);
+                    (undone, controlled)
+                }
+            }
+        "},
+        "Test.Run()",
+        &expect!["(Zero, One)"],
+    );
+}
+
+#[test]
+fn local_helpers_with_same_name_in_different_callables_expr() {
+    check_expr(
+        indoc! {"
+            namespace Test {
+                function Doubled(x : Int) : Int {
+                    function Helper(y : Int) : Int {
+                        2 * y
+                    }
+                    Helper(x)
+                }
+                operation Flipped() : Result {
+                    operation Helper(q : Qubit) : Unit {
+                        X(q);
+                    }
+                    use q = Qubit();
+                    Helper(q);
+                    MResetZ(q)
+                }
+            }
+        "},
+        "(Test.Doubled(21), Test.Flipped())",
+        &expect!["(42, One)"],
+    );
+}
+
+#[test]
+fn deeply_nested_expr() {
+    // Nested expressions are flattened into the exec graph, so evaluating them pushes onto the
+    // value stack instead of recursing.
+    let depth = 250;
+    let expr = format!("{}0{}", "(1 + ".repeat(depth), ")".repeat(depth));
+    check_expr("", &expr, &expect!["250"]);
+}
+
+#[test]
+fn long_statement_chain_expr() {
+    let expr = format!(
+        "{{ mutable x = 0; {} x }}",
+        "set x = x + 1; ".repeat(20_000)
+    );
+    check_expr("", &expr, &expect!["20000"]);
+}
+
 #[test]
 fn while_false_shortcut_expr() {
     check_expr(
@@ -2483,6 +2727,24 @@ fn assignupdate_expr_using_field_name() {
     );
 }
 
+#[test]
+fn udt_named_item_update_copies_expr() {
+    check_expr(
+        indoc! {"
+        namespace A {
+            newtype Pair = (First : Int, Second : Int);
+        }
+    "},
+        indoc! {"{
+            open A;
+            let p = Pair(1, 2);
+            let q = p w/ Second <- 5;
+            (p::First, p::Second, q::First, q::Second)
+        }"},
+        &expect!["(1, 2, 1, 5)"],
+    );
+}
+
 #[test]
 fn assignupdate_expr_using_range() {
     check_expr(
@@ -2950,6 +3212,65 @@ fn call_return_expr() {
     );
 }
 
+#[test]
+fn call_return_from_nested_loops_and_conditionals_expr() {
+    check_expr(
+        indoc! {"
+            namespace Test {
+                function Find(target : Int) : (Int, Int) {
+                    for i in 0..3 {
+                        mutable j = 0;
+                        while j < 3 {
+                            if i * 3 + j == target {
+                                return (i, j);
+                            }
+                            set j += 1;
+                        }
+                    }
+                    (-1, -1)
+                }
+            }
+        "},
+        "(Test.Find(5), Test.Find(12))",
+        &expect!["((1, 2), (-1, -1))"],
+    );
+}
+
+#[test]
+fn call_return_inside_use_block_releases_qubits_expr() {
+    check_expr(
+        indoc! {"
+            namespace Test {
+                operation Foo(n : Int) : Int {
+                    use outer = Qubit();
+                    for i in 0..n {
+                        use inner = Qubit[2];
+                        if i == 1 {
+                            within {
+                                X(inner[0]);
+                            } apply {
+                                CNOT(inner[0], inner[1]);
+                            }
+                            Reset(inner[1]);
+                            return i;
+                        }
+                    }
+                    -1
+                }
+            }
+        "},
+        indoc! {"{
+            mutable results = [];
+            for _ in 1..3 {
+                set results += [Test.Foo(5)];
+            }
+            use qs = Qubit[3];
+            (results, MResetEachZ(qs))
+        }"},
+        &expect!["([1, 1, 1], [Zero, Zero, Zero])"],
+    );
+}
+
 #[test]
 fn call_args_expr() {
     check_expr(
@@ -3332,6 +3653,19 @@ fn nested_interpolated_string_with_exprs() {
     );
 }
 
+#[test]
+fn interpolated_string_with_string_and_other_values() {
+    check_expr(
+        "",
+        indoc! {r#"{
+            let s = "x";
+            let n = 2;
+            $"a{s}b{n}{$"{s}"}"
+        }"#},
+        &expect!["axb2x"],
+    );
+}
+
 #[test]
 fn udt_unwrap() {
     check_expr(
@@ -3837,26 +4171,30 @@ fn partial_eval_stmt_with_mutable_variable_update() {
                         0
                         1
                     Stmt 1 [16-27]: Semi: 2
-                    Stmt 2 [28-31]: Expr: 5
-                    Stmt 3 [29-30]: Expr: 6
-                    Stmt 4 [32-43]: Semi: 7
+                    Stmt 2 [28-31]: Expr: 7
+                    Stmt 3 [29-30]: Expr: 8
+                    Stmt 4 [32-43]: Semi: 9
                 Exprs:
                     Expr 0 [0-44] [Type Unit]: Expr Block: 0
                     Expr 1 [13-14] [Type Int]: Lit: Int(0)
-                    Expr 2 [16-26] [Type Unit]: AssignOp (Add):
+                    Expr 2 [16-26] [Type Unit]: Assign:
                         3
                         4
                     Expr 3 [20-21] [Type Int]: Var: Local 0
-                    Expr 4 [25-26] [Type Int]: Lit: Int(1)
-                    Expr 5 [28-31] [Type Int]: Expr Block: 1
-                    Expr 6 [29-30] [Type Int]: Var: Local 0
-                    Expr 7 [32-42] [Type Unit]: Assign:
-                        8
-                        9
-                    Expr 8 [36-37] [Type Int]: Var: Local 0
-                    Expr 9 [40-42] [Type Int]: UnOp (Neg):
+                    Expr 4 [20-26] [Type Int]: BinOp (Add):
+                        5
+                        6
+                    Expr 5 [20-21] [Type Int]: Var: Local 0
+                    Expr 6 [25-26] [Type Int]: Lit: Int(1)
+                    Expr 7 [28-31] [Type Int]: Expr Block: 1
+                    Expr 8 [29-30] [Type Int]: Var: Local 0
+                    Expr 9 [32-42] [Type Unit]: Assign:
                         10
-                    Expr 10 [41-42] [Type Int]: Lit: Int(1)
+                        11
+                    Expr 10 [36-37] [Type Int]: Var: Local 0
+                    Expr 11 [40-42] [Type Int]: UnOp (Neg):
+                        12
+                    Expr 12 [41-42] [Type Int]: Lit: Int(1)
                 Pats:
                     Pat 0 [9-10] [Type Int]: Bind: Ident 0 [9-10] "x""#]],
         &expect!["1"],
@@ -3888,26 +4226,30 @@ fn partial_eval_stmt_with_mutable_variable_update_out_of_order_works() {
                         0
                         1
                     Stmt 1 [16-27]: Semi: 2
-                    Stmt 2 [28-31]: Expr: 5
-                    Stmt 3 [29-30]: Expr: 6
-                    Stmt 4 [32-43]: Semi: 7
+                    Stmt 2 [28-31]: Expr: 7
+                    Stmt 3 [29-30]: Expr: 8
+                    Stmt 4 [32-43]: Semi: 9
                 Exprs:
                     Expr 0 [0-44] [Type Unit]: Expr Block: 0
                     Expr 1 [13-14] [Type Int]: Lit: Int(0)
-                    Expr 2 [16-26] [Type Unit]: AssignOp (Add):
+                    Expr 2 [16-26] [Type Unit]: Assign:
                         3
                         4
                     Expr 3 [20-21] [Type Int]: Var: Local 0
-                    Expr 4 [25-26] [Type Int]: Lit: Int(1)
-                    Expr 5 [28-31] [Type Int]: Expr Block: 1
-                    Expr 6 [29-30] [Type Int]: Var: Local 0
-                    Expr 7 [32-42] [Type Unit]: Assign:
-                        8
-                        9
-                    Expr 8 [36-37] [Type Int]: Var: Local 0
-                    Expr 9 [40-42] [Type Int]: UnOp (Neg):
+                    Expr 4 [20-26] [Type Int]: BinOp (Add):
+                        5
+                        6
+                    Expr 5 [20-21] [Type Int]: Var: Local 0
+                    Expr 6 [25-26] [Type Int]: Lit: Int(1)
+                    Expr 7 [28-31] [Type Int]: Expr Block: 1
+                    Expr 8 [29-30] [Type Int]: Var: Local 0
+                    Expr 9 [32-42] [Type Unit]: Assign:
                         10
-                    Expr 10 [41-42] [Type Int]: Lit: Int(1)
+                        11
+                    Expr 10 [36-37] [Type Int]: Var: Local 0
+                    Expr 11 [40-42] [Type Int]: UnOp (Neg):
+                        12
+                    Expr 12 [41-42] [Type Int]: Lit: Int(1)
                 Pats:
                     Pat 0 [9-10] [Type Int]: Bind: Ident 0 [9-10] "x""#]],
         &expect!["-1"],
@@ -3939,26 +4281,30 @@ fn partial_eval_stmt_with_mutable_variable_update_repeat_stmts_works() {
                         0
                         1
                     Stmt 1 [16-27]: Semi: 2
-                    Stmt 2 [28-31]: Expr: 5
-                    Stmt 3 [29-30]: Expr: 6
-                    Stmt 4 [32-43]: Semi: 7
+                    Stmt 2 [28-31]: Expr: 7
+                    Stmt 3 [29-30]: Expr: 8
+                    Stmt 4 [32-43]: Semi: 9
                 Exprs:
                     Expr 0 [0-44] [Type Unit]: Expr Block: 0
                     Expr 1 [13-14] [Type Int]: Lit: Int(0)
-                    Expr 2 [16-26] [Type Unit]: AssignOp (Add):
+                    Expr 2 [16-26] [Type Unit]: Assign:
                         3
                         4
                     Expr 3 [20-21] [Type Int]: Var: Local 0
-                    Expr 4 [25-26] [Type Int]: Lit: Int(1)
-                    Expr 5 [28-31] [Type Int]: Expr Block: 1
-                    Expr 6 [29-30] [Type Int]: Var: Local 0
-                    Expr 7 [32-42] [Type Unit]: Assign:
-                        8
-                        9
-                    Expr 8 [36-37] [Type Int]: Var: Local 0
-                    Expr 9 [40-42] [Type Int]: UnOp (Neg):
+                    Expr 4 [20-26] [Type Int]: BinOp (Add):
+                        5
+                        6
+                    Expr 5 [20-21] [Type Int]: Var: Local 0
+                    Expr 6 [25-26] [Type Int]: Lit: Int(1)
+                    Expr 7 [28-31] [Type Int]: Expr Block: 1
+                    Expr 8 [29-30] [Type Int]: Var: Local 0
+                    Expr 9 [32-42] [Type Unit]: Assign:
                         10
-                    Expr 10 [41-42] [Type Int]: Lit: Int(1)
+                        11
+                    Expr 10 [36-37] [Type Int]: Var: Local 0
+                    Expr 11 [40-42] [Type Int]: UnOp (Neg):
+                        12
+                    Expr 12 [41-42] [Type Int]: Lit: Int(1)
                 Pats:
                     Pat 0 [9-10] [Type Int]: Bind: Ident 0 [9-10] "x""#]],
         &expect!["2"],
@@ -4077,9 +4423,9 @@ fn partial_eval_stmt_with_loop() {
                         1
                     Stmt 1 [16-43]: Expr: 2
                     Stmt 2 [30-41]: Semi: 6
-                    Stmt 3 [44-47]: Expr: 9
-                    Stmt 4 [45-46]: Expr: 10
-                    Stmt 5 [48-51]: Semi: 11
+                    Stmt 3 [44-47]: Expr: 11
+                    Stmt 4 [45-46]: Expr: 12
+                    Stmt 5 [48-51]: Semi: 13
                 Exprs:
                     Expr 0 [0-52] [Type Unit]: Expr Block: 0
                     Expr 1 [13-14] [Type Int]: Lit: Int(0)
@@ -4091,14 +4437,18 @@ fn partial_eval_stmt_with_loop() {
                         5
                     Expr 4 [22-23] [Type Int]: Var: Local 0
                     Expr 5 [26-27] [Type Int]: Lit: Int(3)
-                    Expr 6 [30-40] [Type Unit]: AssignOp (Add):
+                    Expr 6 [30-40] [Type Unit]: Assign:
                         7
                         8
                     Expr 7 [34-35] [Type Int]: Var: Local 0
-                    Expr 8 [39-40] [Type Int]: Lit: Int(1)
-                    Expr 9 [44-47] [Type Int]: Expr Block: 2
-                    Expr 10 [45-46] [Type Int]: Var: Local 0
-                    Expr 11 [48-50] [Type Unit]: Unit
+                    Expr 8 [34-40] [Type Int]: BinOp (Add):
+                        9
+                        10
+                    Expr 9 [34-35] [Type Int]: Var: Local 0
+                    Expr 10 [39-40] [Type Int]: Lit: Int(1)
+                    Expr 11 [44-47] [Type Int]: Expr Block: 2
+                    Expr 12 [45-46] [Type Int]: Var: Local 0
+                    Expr 13 [48-50] [Type Unit]: Unit
                 Pats:
                     Pat 0 [9-10] [Type Int]: Bind: Ident 0 [9-10] "x""#]],
         &expect!["3"],
@@ -0,0 +1,249 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! An opt-in log of the quantum operations performed during execution, recorded through the
+//! [`Tracer`] interface of the [`crate::backend::TracingBackend`].
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    backend::Tracer,
+    debug::Frame,
+    val::{self, Value},
+};
+use qsc_data_structures::span::Span;
+use qsc_fir::fir::PackageId;
+use std::fmt::{self, Display, Formatter};
+
+/// A quantum operation recorded in an [`ExecutionTrace`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceOp {
+    Allocate(usize),
+    Release(usize),
+    /// The ids of two qubits were exchanged, so that later operations on either id act on the
+    /// qubit previously known by the other.
+    SwapId(usize, usize),
+    /// The name of the variable a qubit was bound to, recorded just before the qubit is released.
+    Label(usize, String),
+    Gate {
+        name: String,
+        is_adjoint: bool,
+        targets: Vec<usize>,
+        controls: Vec<usize>,
+        theta: Option<f64>,
+    },
+    Measure {
+        name: String,
+        qubit: usize,
+        result: val::Result,
+    },
+    Reset(usize),
+    /// A simulator intrinsic without a dedicated backend method, with its full argument value.
+    Custom {
+        name: String,
+        arg: Value,
+    },
+}
+
+/// An operation together with the call stack that performed it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceEvent {
+    pub op: TraceOp,
+    /// The call stack, outermost frame first. The span of each frame is the location of the
+    /// call within the callable of that frame.
+    pub stack: Vec<Frame>,
+}
+
+impl TraceEvent {
+    /// The innermost location in the call stack that belongs to one of the given packages,
+    /// typically the user's source package, so that operations performed by library code are
+    /// attributed to the user code that called it.
+    #[must_use]
+    pub fn location(&self, packages: &[PackageId]) -> Option<(PackageId, Span)> {
+        self.stack
+            .iter()
+            .rev()
+            .find(|frame| packages.contains(&frame.id.package))
+            .map(|frame| (frame.id.package, frame.span))
+    }
+}
+
+/// A destination for the events of an [`ExecutionTrace`], which receives each event as soon as
+/// the operation is performed.
+pub trait TraceSink {
+    fn event(&mut self, event: TraceEvent);
+}
+
+impl TraceSink for Vec<TraceEvent> {
+    fn event(&mut self, event: TraceEvent) {
+        self.push(event);
+    }
+}
+
+/// A sink that passes each event to a callback instead of storing it, so that long executions
+/// can be traced without keeping every event in memory.
+pub struct TraceCallback<F: FnMut(TraceEvent)>(pub F);
+
+impl<F: FnMut(TraceEvent)> TraceSink for TraceCallback<F> {
+    fn event(&mut self, event: TraceEvent) {
+        (self.0)(event);
+    }
+}
+
+/// Records every quantum operation performed during execution, in order, into a [`TraceSink`].
+/// By default the events are collected in memory.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionTrace<S: TraceSink = Vec<TraceEvent>> {
+    sink: S,
+}
+
+impl ExecutionTrace {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.sink
+    }
+
+    #[must_use]
+    pub fn into_events(self) -> Vec<TraceEvent> {
+        self.sink
+    }
+}
+
+impl<S: TraceSink> ExecutionTrace<S> {
+    #[must_use]
+    pub fn with_sink(sink: S) -> Self {
+        Self { sink }
+    }
+
+    #[must_use]
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    fn record(&mut self, stack: &[Frame], op: TraceOp) {
+        self.sink.event(TraceEvent {
+            op,
+            stack: stack.to_vec(),
+        });
+    }
+}
+
+impl<S: TraceSink> Tracer for ExecutionTrace<S> {
+    fn qubit_allocate(&mut self, stack: &[Frame], q: usize) {
+        self.record(stack, TraceOp::Allocate(q));
+    }
+
+    fn qubit_release(&mut self, stack: &[Frame], q: usize) {
+        self.record(stack, TraceOp::Release(q));
+    }
+
+    fn qubit_swap_id(&mut self, stack: &[Frame], q0: usize, q1: usize) {
+        self.record(stack, TraceOp::SwapId(q0, q1));
+    }
+
+    fn qubit_label(&mut self, stack: &[Frame], q: usize, label: &str) {
+        self.record(stack, TraceOp::Label(q, label.to_string()));
+    }
+
+    fn gate(
+        &mut self,
+        stack: &[Frame],
+        name: &str,
+        is_adjoint: bool,
+        targets: &[usize],
+        controls: &[usize],
+        theta: Option<f64>,
+    ) {
+        self.record(
+            stack,
+            TraceOp::Gate {
+                name: name.to_string(),
+                is_adjoint,
+                targets: targets.to_vec(),
+                controls: controls.to_vec(),
+                theta,
+            },
+        );
+    }
+
+    fn measure(&mut self, stack: &[Frame], name: &str, q: usize, r: &val::Result) {
+        self.record(
+            stack,
+            TraceOp::Measure {
+                name: name.to_string(),
+                qubit: q,
+                result: *r,
+            },
+        );
+    }
+
+    fn reset(&mut self, stack: &[Frame], q: usize) {
+        self.record(stack, TraceOp::Reset(q));
+    }
+
+    fn custom_intrinsic(&mut self, stack: &[Frame], name: &str, arg: Value) {
+        self.record(
+            stack,
+            TraceOp::Custom {
+                name: name.to_string(),
+                arg,
+            },
+        );
+    }
+
+    fn is_stack_tracing_enabled(&self) -> bool {
+        true
+    }
+}
+
+impl Display for TraceOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceOp::Allocate(q) => write!(f, "allocate q[{q}]"),
+            TraceOp::Release(q) => write!(f, "release q[{q}]"),
+            TraceOp::SwapId(q0, q1) => write!(f, "swap ids q[{q0}], q[{q1}]"),
+            TraceOp::Label(q, label) => write!(f, "label q[{q}] \"{label}\""),
+            TraceOp::Gate {
+                name,
+                is_adjoint,
+                targets,
+                controls,
+                theta,
+            } => {
+                write!(f, "{name}")?;
+                if *is_adjoint {
+                    write!(f, "†")?;
+                }
+                if let Some(theta) = theta {
+                    write!(f, "({theta})")?;
+                }
+                write!(f, " {}", qubit_list(targets))?;
+                if !controls.is_empty() {
+                    write!(f, " controlled by {}", qubit_list(controls))?;
+                }
+                Ok(())
+            }
+            TraceOp::Measure {
+                name,
+                qubit,
+                result,
+            } => write!(f, "{name} q[{qubit}] => {}", Value::Result(*result)),
+            TraceOp::Reset(q) => write!(f, "Reset q[{q}]"),
+            TraceOp::Custom { name, arg } => write!(f, "{name} {arg}"),
+        }
+    }
+}
+
+fn qubit_list(qubits: &[usize]) -> String {
+    qubits
+        .iter()
+        .map(|q| format!("q[{q}]"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
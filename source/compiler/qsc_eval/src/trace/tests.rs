@@ -0,0 +1,91 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ExecutionTrace, TraceCallback};
+use crate::{backend::Tracer, debug::Frame, val};
+use expect_test::expect;
+use qsc_data_structures::{functors::FunctorApp, span::Span};
+use qsc_fir::fir::{PackageId, StoreItemId};
+
+fn frame(package: usize, lo: u32) -> Frame {
+    Frame {
+        span: Span { lo, hi: lo + 1 },
+        id: StoreItemId {
+            package: PackageId::from(package),
+            item: 0.into(),
+        },
+        caller: PackageId::from(package),
+        functor: FunctorApp::default(),
+    }
+}
+
+#[test]
+fn records_operations_in_order() {
+    let mut trace = ExecutionTrace::new();
+    trace.qubit_allocate(&[], 0);
+    trace.qubit_allocate(&[], 1);
+    trace.gate(&[], "H", false, &[0], &[], None);
+    trace.gate(&[], "X", false, &[1], &[0], None);
+    trace.gate(&[], "S", true, &[1], &[], None);
+    trace.gate(&[], "Rx", false, &[0], &[], Some(0.5));
+    trace.measure(&[], "MResetZ", 0, &val::Result::Val(true));
+    trace.reset(&[], 1);
+    trace.qubit_swap_id(&[], 0, 1);
+    trace.qubit_label(&[], 1, "aux");
+    trace.qubit_release(&[], 1);
+    let events = trace
+        .events()
+        .iter()
+        .map(|event| event.op.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    expect![[r#"
+        allocate q[0]
+        allocate q[1]
+        H q[0]
+        X q[1] controlled by q[0]
+        S† q[1]
+        Rx(0.5) q[0]
+        MResetZ q[0] => One
+        Reset q[1]
+        swap ids q[0], q[1]
+        label q[1] "aux"
+        release q[1]"#]]
+    .assert_eq(&events);
+}
+
+#[test]
+fn callback_sink_receives_each_event_as_it_happens() {
+    let mut lines = Vec::new();
+    let mut trace = ExecutionTrace::with_sink(TraceCallback(|event: super::TraceEvent| {
+        lines.push(event.op.to_string());
+    }));
+    trace.qubit_allocate(&[], 0);
+    trace.gate(&[], "H", false, &[0], &[], None);
+    trace.qubit_release(&[], 0);
+    drop(trace);
+    expect![[r#"
+        allocate q[0]
+        H q[0]
+        release q[0]"#]]
+    .assert_eq(&lines.join("\n"));
+}
+
+#[test]
+fn location_is_innermost_frame_in_given_packages() {
+    let mut trace = ExecutionTrace::new();
+    trace.gate(
+        &[frame(2, 10), frame(2, 20), frame(1, 5)],
+        "H",
+        false,
+        &[0],
+        &[],
+        None,
+    );
+    let event = &trace.events()[0];
+    assert_eq!(
+        event.location(&[PackageId::from(2)]),
+        Some((PackageId::from(2), Span { lo: 20, hi: 21 }))
+    );
+    assert_eq!(event.location(&[PackageId::from(3)]), None);
+}
@@ -1021,8 +1021,8 @@ pub type ConfiguredExecGraph = Rc<[ExecGraphNode]>;
 #[derive(Copy, Clone, Debug, PartialEq)]
 /// A node within the control flow graph.
 pub enum ExecGraphNode {
-    /// A binding of a value to a variable.
-    Bind(PatId),
+    /// A binding of a value to a variable with the given mutability.
+    Bind(PatId, Mutability),
     /// An expression to execute.
     Expr(ExprId),
     /// An unconditional jump with to given location.
@@ -13,7 +13,7 @@ use qsc_hir::{
     visit::{self, Visitor},
 };
 use rustc_hash::{FxHashMap, FxHashSet};
-use std::{iter, rc::Rc};
+use std::{iter, sync::Arc};
 
 pub(super) struct Lambda {
     pub(super) kind: CallableKind,
@@ -162,7 +162,7 @@ pub(super) fn partial_app_block(
     callee: Expr,
     arg: Expr,
     app: PartialApp,
-    arrow: Rc<Arrow>,
+    arrow: Arc<Arrow>,
     span: Span,
 ) -> Block {
     let call = Expr {
@@ -5,6 +5,7 @@
 mod tests;
 
 pub mod preprocess;
+pub mod timings;
 
 use crate::{
     lower::{self, Lowerer},
@@ -36,10 +37,11 @@ use qsc_hir::{
     validate::Validator as HirValidator,
     visit::Visitor as _,
 };
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, sync::Arc, time::Instant};
 use thiserror::Error;
+use timings::{Phase, Timings, timed};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct CompileUnit {
     pub package: hir::Package,
     pub ast: AstPackage,
@@ -57,7 +59,7 @@ impl CompileUnit {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct AstPackage {
     pub package: ast::Package,
     pub tys: Table,
@@ -87,9 +89,19 @@ pub(super) enum ErrorKind {
     Lower(#[from] lower::Error),
 }
 
+/// A store of compiled packages.
+///
+/// Cloning a store is cheap: the compiled packages are shared between the clones rather than
+/// copied. A store holding the standard library can be built once and cloned for each new
+/// compilation instead of recompiling the library every time. Packages added to a clone are
+/// not visible to the original.
+///
+/// The store is `Send` and `Sync`, so a store can be shared between threads, for example by
+/// compiling the standard library once per process and cloning the store on each thread.
+#[derive(Clone)]
 pub struct PackageStore {
-    core: global::Table,
-    units: IndexMap<PackageId, CompileUnit>,
+    core: Arc<global::Table>,
+    units: IndexMap<PackageId, Arc<CompileUnit>>,
     next_id: PackageId,
 }
 
@@ -104,9 +116,9 @@ impl PackageStore {
     pub fn new(core: CompileUnit) -> Self {
         let table = global::iter_package(Some(PackageId::CORE), &core.package).collect();
         let mut units = IndexMap::new();
-        units.insert(PackageId::CORE, core);
+        units.insert(PackageId::CORE, Arc::new(core));
         Self {
-            core: table,
+            core: Arc::new(table),
             units,
             next_id: PackageId::CORE.successor(),
         }
@@ -120,13 +132,13 @@ impl PackageStore {
     pub fn insert(&mut self, unit: CompileUnit) -> PackageId {
         let id = self.next_id;
         self.next_id = id.successor();
-        self.units.insert(id, unit);
+        self.units.insert(id, Arc::new(unit));
         id
     }
 
     #[must_use]
     pub fn get(&self, id: PackageId) -> Option<&CompileUnit> {
-        self.units.get(id)
+        self.units.get(id).map(AsRef::as_ref)
     }
 
     #[must_use]
@@ -141,7 +153,7 @@ impl PackageStore {
     pub fn open(mut self) -> OpenPackageStore {
         let id = self.next_id;
         self.next_id = id.successor();
-        self.units.insert(id, CompileUnit::default());
+        self.units.insert(id, Arc::new(CompileUnit::default()));
 
         OpenPackageStore {
             store: self,
@@ -186,16 +198,25 @@ impl OpenPackageStore {
     /// Returns a mutable reference to the open package,
     /// along with a reference to the core library that can be used
     /// to perform passes.
+    ///
+    /// If the open package is shared with a clone of the underlying store, it is copied first so
+    /// that the clone is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the open package id is missing from the store.
     #[must_use]
     pub fn get_open_mut(&mut self) -> (&global::Table, &mut CompileUnit) {
         let id = self.open;
 
         (
-            &self.store.core,
-            self.store
-                .units
-                .get_mut(id)
-                .expect("open package id should exist in store"),
+            self.store.core.as_ref(),
+            Arc::make_mut(
+                self.store
+                    .units
+                    .get_mut(id)
+                    .expect("open package id should exist in store"),
+            ),
         )
     }
 
@@ -207,19 +228,19 @@ impl OpenPackageStore {
     }
 }
 
-pub struct Iter<'a>(index_map::Iter<'a, PackageId, CompileUnit>);
+pub struct Iter<'a>(index_map::Iter<'a, PackageId, Arc<CompileUnit>>);
 
 impl<'a> Iterator for Iter<'a> {
     type Item = (PackageId, &'a CompileUnit);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()
+        self.0.next().map(|(id, unit)| (id, unit.as_ref()))
     }
 }
 
 impl DoubleEndedIterator for Iter<'_> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.0.next_back()
+        self.0.next_back().map(|(id, unit)| (id, unit.as_ref()))
     }
 }
 
@@ -240,26 +261,69 @@ pub fn compile(
     capabilities: TargetCapabilityFlags,
     language_features: LanguageFeatures,
 ) -> CompileUnit {
-    let (ast_package, parse_errors) = parse_all(&sources, language_features);
+    compile_timed(
+        store,
+        dependencies,
+        sources,
+        capabilities,
+        language_features,
+        None,
+    )
+}
+
+/// Compiles like [`compile`], adding the time spent in each phase to `timings` if given.
+#[must_use]
+pub fn compile_timed(
+    store: &PackageStore,
+    dependencies: &Dependencies,
+    sources: SourceMap,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+    mut timings: Option<&mut Timings>,
+) -> CompileUnit {
+    let (ast_package, parse_errors) =
+        parse_all_timed(&sources, language_features, timings.as_deref_mut());
 
-    compile_ast(
+    compile_ast_timed(
         store,
         dependencies,
         ast_package,
         sources,
         capabilities,
         parse_errors,
+        timings,
     )
 }
 
 #[allow(clippy::module_name_repetitions)]
 pub fn compile_ast(
+    store: &PackageStore,
+    dependencies: &Dependencies,
+    ast_package: ast::Package,
+    sources: SourceMap,
+    capabilities: TargetCapabilityFlags,
+    parse_errors: Vec<qsc_parse::Error>,
+) -> CompileUnit {
+    compile_ast_timed(
+        store,
+        dependencies,
+        ast_package,
+        sources,
+        capabilities,
+        parse_errors,
+        None,
+    )
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+fn compile_ast_timed(
     store: &PackageStore,
     dependencies: &Dependencies,
     mut ast_package: ast::Package,
     sources: SourceMap,
     capabilities: TargetCapabilityFlags,
     parse_errors: Vec<qsc_parse::Error>,
+    mut timings: Option<&mut Timings>,
 ) -> CompileUnit {
     let mut cond_compile = preprocess::Conditional::new(capabilities);
     cond_compile.visit_package(&mut ast_package);
@@ -277,18 +341,24 @@ pub fn compile_ast(
         locals,
         globals,
         errors: name_errors,
-    } = resolve_all(
-        store,
-        dependencies,
-        &mut hir_assigner,
-        &ast_package,
-        dropped_names.clone(),
-    );
-    let (tys, ty_errors) = typeck_all(store, dependencies, &ast_package, &names);
+    } = timed(timings.as_deref_mut(), Phase::Resolve, || {
+        resolve_all(
+            store,
+            dependencies,
+            &mut hir_assigner,
+            &ast_package,
+            dropped_names.clone(),
+        )
+    });
+    let (tys, ty_errors) = timed(timings.as_deref_mut(), Phase::Typeck, || {
+        typeck_all(store, dependencies, &ast_package, &names)
+    });
     let mut lowerer = Lowerer::new();
-    let package = lowerer
-        .with(&mut hir_assigner, &names, &tys)
-        .lower_package(&ast_package);
+    let package = timed(timings, Phase::Lower, || {
+        lowerer
+            .with(&mut hir_assigner, &names, &tys)
+            .lower_package(&ast_package)
+    });
     HirValidator::default().visit_package(&package);
     let lower_errors = lowerer.drain_errors();
 
@@ -325,7 +395,7 @@ pub fn compile_ast(
 #[must_use]
 pub fn core() -> CompileUnit {
     let store = PackageStore {
-        core: global::Table::default(),
+        core: Arc::new(global::Table::default()),
         units: IndexMap::new(),
         next_id: PackageId::CORE,
     };
@@ -376,11 +446,26 @@ pub fn parse_all(
     sources: &SourceMap,
     features: LanguageFeatures,
 ) -> (ast::Package, Vec<qsc_parse::Error>) {
+    parse_all_timed(sources, features, None)
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+fn parse_all_timed(
+    sources: &SourceMap,
+    features: LanguageFeatures,
+    timings: Option<&mut Timings>,
+) -> (ast::Package, Vec<qsc_parse::Error>) {
+    let parse_start = timings::start(timings);
+    let mut file_times = Vec::new();
     let mut namespaces = Vec::new();
     let mut errors = Vec::new();
     for source in sources.relative_sources() {
+        let file_start = parse_start.as_ref().map(|_| Instant::now());
         let (source_namespaces, source_errors) =
             qsc_parse::namespaces(&source.contents, Some(&source.name), features);
+        if let Some(file_start) = file_start {
+            file_times.push((source.name.clone(), file_start.elapsed()));
+        }
         for mut namespace in source_namespaces {
             Offsetter(source.offset).visit_namespace(&mut namespace);
             namespaces.push(TopLevelNode::Namespace(namespace));
@@ -406,6 +491,13 @@ pub fn parse_all(
         entry,
     };
 
+    if let Some((timings, parse_start)) = parse_start {
+        timings.add_phase(Phase::Parse, parse_start.elapsed());
+        for (name, time) in file_times {
+            timings.add_file(name, time);
+        }
+    }
+
     (package, errors)
 }
 
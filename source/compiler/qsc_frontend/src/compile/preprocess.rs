@@ -12,7 +12,7 @@ use qsc_ast::{
 };
 use qsc_data_structures::{span::Span, target::Profile};
 use qsc_hir::hir;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::{SourceMap, TargetCapabilityFlags};
 
@@ -48,8 +48,8 @@ impl Visitor<'_> for DetectEntryPointProfile {
 
 #[derive(PartialEq, Hash, Clone, Debug)]
 pub struct TrackedName {
-    pub name: Rc<str>,
-    pub namespace: Rc<str>,
+    pub name: Arc<str>,
+    pub namespace: Arc<str>,
 }
 
 pub(crate) struct Conditional {
@@ -124,12 +124,12 @@ impl MutVisitor for Conditional {
                     ItemKind::Callable(callable) => {
                         self.included_names.push(TrackedName {
                             name: callable.name.name.clone(),
-                            namespace: Rc::from(""),
+                            namespace: Arc::from(""),
                         });
                     }
                     ItemKind::Ty(ident, _) => self.included_names.push(TrackedName {
                         name: ident.name.clone(),
-                        namespace: Rc::from(""),
+                        namespace: Arc::from(""),
                     }),
                     _ => {}
                 }
@@ -138,12 +138,12 @@ impl MutVisitor for Conditional {
                     ItemKind::Callable(callable) => {
                         self.dropped_names.push(TrackedName {
                             name: callable.name.name.clone(),
-                            namespace: Rc::from(""),
+                            namespace: Arc::from(""),
                         });
                     }
                     ItemKind::Ty(ident, _) => self.dropped_names.push(TrackedName {
                         name: ident.name.clone(),
-                        namespace: Rc::from(""),
+                        namespace: Arc::from(""),
                     }),
                     _ => {}
                 }
@@ -3,13 +3,13 @@
 
 mod multiple_packages;
 
-use std::{rc::Rc, sync::Arc};
+use std::sync::Arc;
 
 use super::{CompileUnit, Error, PackageStore, SourceMap, compile};
 use crate::compile::TargetCapabilityFlags;
 
 use expect_test::expect;
-use indoc::indoc;
+use indoc::{formatdoc, indoc};
 use miette::Diagnostic;
 use qsc_data_structures::{
     language_features::LanguageFeatures, source::longest_common_prefix, span::Span,
@@ -358,7 +358,7 @@ fn insert_core_call() {
             let callee = Expr {
                 id: NodeId::default(),
                 span: Span::default(),
-                ty: Ty::Arrow(Rc::new(allocate_ty)),
+                ty: Ty::Arrow(Arc::new(allocate_ty)),
                 kind: ExprKind::Var(Res::Item(allocate.id), Vec::new()),
             };
 
@@ -579,6 +579,165 @@ fn package_dependency_internal_error() {
     .assert_eq(&unit2.package.to_string());
 }
 
+fn compile_with_internal_dependency(source: &str) -> Vec<Error> {
+    let mut store = PackageStore::new(super::core());
+    let sources1 = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Package1 {
+                    internal function Foo() : Int { 1 }
+                    internal newtype Pair = (Int, Int);
+                    function Baz() : Int { Foo() }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit1 = compile(
+        &store,
+        &[],
+        sources1,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit1.errors.is_empty(), "{:#?}", unit1.errors);
+    let package1 = store.insert(unit1);
+
+    let sources2 = SourceMap::new([("test".into(), source.into())], None);
+    compile(
+        &store,
+        &[(package1, Some(Arc::from("PackageAlias")))],
+        sources2,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    )
+    .errors
+}
+
+fn internal_item_errors(errors: &[Error]) -> Vec<(&str, &str)> {
+    errors
+        .iter()
+        .filter_map(|error| match &error.0 {
+            super::ErrorKind::Resolve(super::resolve::Error::Internal(name, qualified, _)) => {
+                Some((name.as_str(), qualified.as_str()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn package_dependency_internal_qualified_path() {
+    let errors = compile_with_internal_dependency(indoc! {"
+        namespace Package2 {
+            function Bar() : Int {
+                PackageAlias.Package1.Foo()
+            }
+        }
+    "});
+    assert_eq!(
+        vec![("PackageAlias.Package1.Foo", "PackageAlias.Package1.Foo")],
+        internal_item_errors(&errors)
+    );
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+    expect!["found a matching item `PackageAlias.Package1.Foo` in a dependency, but it is declared `internal` and can only be used within its own package"]
+        .assert_eq(&errors[0].help().expect("error should have help").to_string());
+}
+
+#[test]
+fn package_dependency_internal_through_open() {
+    let errors = compile_with_internal_dependency(indoc! {"
+        namespace Package2 {
+            open PackageAlias.Package1;
+            function Bar() : Int {
+                Foo() + Baz()
+            }
+        }
+    "});
+    assert_eq!(
+        vec![("Foo", "PackageAlias.Package1.Foo")],
+        internal_item_errors(&errors)
+    );
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+}
+
+#[test]
+fn package_dependency_internal_through_namespace_alias() {
+    let errors = compile_with_internal_dependency(indoc! {"
+        namespace Package2 {
+            open PackageAlias.Package1 as P;
+            function Bar() : Int {
+                P.Foo() + P.Baz()
+            }
+        }
+    "});
+    assert_eq!(
+        vec![("P.Foo", "PackageAlias.Package1.Foo")],
+        internal_item_errors(&errors)
+    );
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+}
+
+#[test]
+fn package_dependency_internal_import() {
+    let errors = compile_with_internal_dependency(indoc! {"
+        namespace Package2 {
+            import PackageAlias.Package1.Foo as Qux;
+            import PackageAlias.Package1.Baz;
+            function Bar() : Int {
+                Baz()
+            }
+        }
+    "});
+    assert_eq!(
+        vec![("PackageAlias.Package1.Foo", "PackageAlias.Package1.Foo")],
+        internal_item_errors(&errors)
+    );
+}
+
+#[test]
+fn package_dependency_internal_type() {
+    let errors = compile_with_internal_dependency(indoc! {"
+        namespace Package2 {
+            open PackageAlias.Package1;
+            function Bar(pair : Pair) : Unit {}
+        }
+    "});
+    assert_eq!(
+        vec![("Pair", "PackageAlias.Package1.Pair")],
+        internal_item_errors(&errors)
+    );
+}
+
+#[test]
+fn package_dependency_internal_shadowed_by_local_item() {
+    let errors = compile_with_internal_dependency(indoc! {"
+        namespace Package2 {
+            open PackageAlias.Package1;
+            function Foo() : Int { 2 }
+            function Bar() : Int {
+                Foo() + Baz()
+            }
+        }
+    "});
+    assert!(errors.is_empty(), "{errors:#?}");
+}
+
+#[test]
+fn package_dependency_internal_not_suggested_for_other_namespace() {
+    let errors = compile_with_internal_dependency(indoc! {"
+        namespace Package2 {
+            function Bar() : Int {
+                PackageAlias.Other.Foo()
+            }
+        }
+    "});
+    assert_eq!(internal_item_errors(&errors), Vec::<(&str, &str)>::new());
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+}
+
 #[test]
 fn package_dependency_udt() {
     let mut store = PackageStore::new(super::core());
@@ -1487,3 +1646,154 @@ fn export_namespace_with_same_name_as_newtype_does_not_cause_panic() {
     let unit = default_compile(sources);
     expect!["[]"].assert_eq(&format!("{:?}", unit.errors));
 }
+
+fn script_entry_output(source: &str) -> (Ty, Vec<Error>) {
+    let store = PackageStore::new(super::core());
+    let sources = SourceMap::new([("Script.qs".into(), source.into())], None);
+    let unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::ScriptMode,
+    );
+    let output = unit
+        .package
+        .items
+        .values()
+        .find_map(|item| match &item.kind {
+            ItemKind::Callable(decl) if decl.attrs.contains(&qsc_hir::hir::Attr::EntryPoint) => {
+                Some(decl.output.clone())
+            }
+            _ => None,
+        })
+        .expect("script should have an entry point");
+    (output, unit.errors)
+}
+
+#[test]
+fn script_entry_output_is_inferred_from_trailing_expression() {
+    let (output, errors) = script_entry_output(indoc! {"
+        function Helper() : Int { 1 }
+        let x = Helper();
+        (x + 1, true)
+    "});
+    assert!(errors.is_empty(), "{errors:#?}");
+    assert_eq!(
+        output,
+        Ty::Tuple(vec![Ty::Prim(Prim::Int), Ty::Prim(Prim::Bool)])
+    );
+}
+
+#[test]
+fn script_entry_output_is_unit_without_trailing_expression() {
+    let (output, errors) = script_entry_output("let x = 1;");
+    assert!(errors.is_empty(), "{errors:#?}");
+    assert_eq!(output, Ty::UNIT);
+}
+
+#[test]
+fn script_entry_does_not_clash_with_main() {
+    let (output, errors) = script_entry_output(indoc! {"
+        function Main() : Int { 1 }
+        Main()
+    "});
+    assert!(errors.is_empty(), "{errors:#?}");
+    assert_eq!(output, Ty::Prim(Prim::Int));
+}
+
+#[test]
+fn package_store_can_be_shared_between_threads() {
+    let mut store = PackageStore::new(super::core());
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Package1 {
+                    function Foo() : Int {
+                        1
+                    }
+                    export Foo;
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    let package1 = store.insert(unit);
+    let store = Arc::new(store);
+
+    let handles = (0..4)
+        .map(|i| {
+            let store = Arc::clone(&store);
+            std::thread::spawn(move || {
+                let mut store = PackageStore::clone(&store);
+                let sources = SourceMap::new(
+                    [(
+                        "test".into(),
+                        formatdoc! {"
+                            namespace Package2 {{
+                                function Bar() : Int {{
+                                    PackageAlias.Package1.Foo() + {i}
+                                }}
+                            }}
+                        "}
+                        .into(),
+                    )],
+                    None,
+                );
+                let unit = compile(
+                    &store,
+                    &[(package1, Some(Arc::from("PackageAlias")))],
+                    sources,
+                    TargetCapabilityFlags::all(),
+                    LanguageFeatures::default(),
+                );
+                assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+                store.insert(unit);
+                store.iter().count()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert_eq!(handle.join().expect("thread should not panic"), 3);
+    }
+    assert_eq!(store.iter().count(), 2);
+}
+
+#[test]
+fn open_package_shared_with_clone_is_copied_on_write() {
+    let mut store = PackageStore::new(super::core()).open();
+    let id = store.open_package_id();
+    let clone = store.package_store().clone();
+
+    let (_, unit) = store.get_open_mut();
+    unit.sources = SourceMap::new([("test".into(), "".into())], None);
+
+    assert!(
+        store
+            .package_store()
+            .get(id)
+            .expect("open package should exist")
+            .sources
+            .find_by_name("test")
+            .is_some()
+    );
+    assert!(
+        clone
+            .get(id)
+            .expect("open package should exist in clone")
+            .sources
+            .find_by_name("test")
+            .is_none()
+    );
+}
@@ -0,0 +1,151 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::source::SourceName;
+use serde_json::json;
+use std::{
+    fmt::{self, Display, Formatter},
+    time::{Duration, Instant},
+};
+
+/// A phase of compilation whose time is recorded in [`Timings`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Lexing and parsing. The lexer runs on demand as the parser consumes tokens, so the two
+    /// are measured together.
+    Parse,
+    Resolve,
+    Typeck,
+    /// Lowering from AST to HIR.
+    Lower,
+    /// The HIR passes run after the frontend, such as capability and borrow checks.
+    Passes,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(match self {
+            Phase::Parse => "parse",
+            Phase::Resolve => "resolve",
+            Phase::Typeck => "typeck",
+            Phase::Lower => "lower",
+            Phase::Passes => "passes",
+        })
+    }
+}
+
+/// Wall-clock time spent in each compilation phase, and in parsing each source file.
+///
+/// When several packages are compiled with the same `Timings`, phase times accumulate across
+/// them and every package's files are listed. Nothing is recorded on wasm32, which has no
+/// monotonic clock.
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    phases: Vec<(Phase, Duration)>,
+    files: Vec<(SourceName, Duration)>,
+}
+
+impl Timings {
+    /// The time spent in each phase, in the order the phases first ran.
+    #[must_use]
+    pub fn phases(&self) -> &[(Phase, Duration)] {
+        &self.phases
+    }
+
+    /// The time spent parsing each source file.
+    #[must_use]
+    pub fn files(&self) -> &[(SourceName, Duration)] {
+        &self.files
+    }
+
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, time)| *time).sum()
+    }
+
+    pub(crate) fn add_phase(&mut self, phase: Phase, time: Duration) {
+        match self.phases.iter_mut().find(|(p, _)| *p == phase) {
+            Some((_, total)) => *total += time,
+            None => self.phases.push((phase, time)),
+        }
+    }
+
+    pub(crate) fn add_file(&mut self, name: SourceName, time: Duration) {
+        self.files.push((name, time));
+    }
+
+    /// Renders the timings as JSON, with times in milliseconds.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        json!({
+            "phases": self.phases.iter().map(|(phase, time)| json!({
+                "phase": phase.to_string(),
+                "ms": millis(*time),
+            })).collect::<Vec<_>>(),
+            "files": self.files.iter().map(|(name, time)| json!({
+                "file": &**name,
+                "ms": millis(*time),
+            })).collect::<Vec<_>>(),
+            "totalMs": millis(self.total()),
+        })
+        .to_string()
+    }
+}
+
+/// Renders the timings as a table of phases followed by a table of files, with times in
+/// milliseconds.
+impl Display for Timings {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let width = self
+            .phases
+            .iter()
+            .map(|(phase, _)| phase.to_string().len())
+            .chain(self.files.iter().map(|(name, _)| name.len()))
+            .chain(["total".len(), "phase".len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(f, "{:<width$}  {:>10}", "phase", "time (ms)")?;
+        for (phase, time) in &self.phases {
+            writeln!(f, "{phase:<width$}  {:>10.3}", millis(*time))?;
+        }
+        writeln!(f, "{:<width$}  {:>10.3}", "total", millis(self.total()))?;
+        if !self.files.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "{:<width$}  {:>10}", "file", "parse (ms)")?;
+            for (name, time) in &self.files {
+                writeln!(f, "{name:<width$}  {:>10.3}", millis(*time))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs `f`, adding the time it takes to `phase` if timings are being recorded.
+pub fn timed<T>(timings: Option<&mut Timings>, phase: Phase, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("phase", %phase).entered();
+    match start(timings) {
+        Some((timings, start)) => {
+            let result = f();
+            timings.add_phase(phase, start.elapsed());
+            result
+        }
+        None => f(),
+    }
+}
+
+/// Starts a measurement if timings are being recorded and the platform has a clock.
+pub(crate) fn start(timings: Option<&mut Timings>) -> Option<(&mut Timings, Instant)> {
+    if cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    timings.map(|timings| (timings, Instant::now()))
+}
+
+/// Converts to milliseconds with microsecond resolution, saturating at about 71 minutes.
+fn millis(time: Duration) -> f64 {
+    f64::from(u32::try_from(time.as_micros()).unwrap_or(u32::MAX)) / 1000.0
+}
@@ -0,0 +1,49 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{Phase, Timings};
+use expect_test::expect;
+use std::time::Duration;
+
+fn timings() -> Timings {
+    let mut timings = Timings::default();
+    timings.add_phase(Phase::Parse, Duration::from_micros(1500));
+    timings.add_phase(Phase::Resolve, Duration::from_millis(2));
+    timings.add_phase(Phase::Parse, Duration::from_micros(500));
+    timings.add_file("Main.qs".into(), Duration::from_millis(1));
+    timings.add_file("Library.qs".into(), Duration::from_micros(250));
+    timings
+}
+
+#[test]
+fn phase_times_accumulate() {
+    assert_eq!(
+        timings().phases(),
+        &[
+            (Phase::Parse, Duration::from_millis(2)),
+            (Phase::Resolve, Duration::from_millis(2)),
+        ]
+    );
+    assert_eq!(timings().total(), Duration::from_millis(4));
+}
+
+#[test]
+fn table_lists_phases_and_files() {
+    expect![[r#"
+        phase        time (ms)
+        parse            2.000
+        resolve          2.000
+        total            4.000
+
+        file        parse (ms)
+        Main.qs          1.000
+        Library.qs       0.250
+    "#]]
+    .assert_eq(&timings().to_string());
+}
+
+#[test]
+fn json_reports_milliseconds() {
+    expect![[r#"{"files":[{"file":"Main.qs","ms":1.0},{"file":"Library.qs","ms":0.25}],"phases":[{"ms":2.0,"phase":"parse"},{"ms":2.0,"phase":"resolve"}],"totalMs":4.0}"#]]
+    .assert_eq(&timings().to_json());
+}
@@ -50,6 +50,8 @@ pub type Error = WithSource<compile::Error>;
 pub struct Increment {
     pub ast: AstPackage,
     pub hir: hir::Package,
+    /// Diagnostics that did not prevent compilation, such as callables redefined by the fragments.
+    pub warnings: Vec<Error>,
 }
 
 impl Increment {
@@ -181,7 +183,7 @@ impl Compiler {
     {
         accumulate_errors(parse_errors)?;
 
-        let (hir, errors) = self.resolve_check_lower(unit, &mut ast);
+        let (hir, errors, warnings) = self.resolve_check_lower(unit, &mut ast);
 
         accumulate_errors(errors)?;
 
@@ -194,6 +196,7 @@ impl Compiler {
                 tys: self.checker.table().clone(),
             },
             hir,
+            warnings,
         })
     }
 
@@ -219,7 +222,7 @@ impl Compiler {
             return Err(parse_errors);
         }
 
-        let (hir, errors) = self.resolve_check_lower(unit, &mut ast);
+        let (hir, errors, warnings) = self.resolve_check_lower(unit, &mut ast);
 
         if !errors.is_empty() {
             return Err(errors);
@@ -234,6 +237,7 @@ impl Compiler {
                 tys: self.checker.table().clone(),
             },
             hir,
+            warnings,
         })
     }
 
@@ -257,7 +261,7 @@ impl Compiler {
         &mut self,
         unit: &mut CompileUnit,
         ast: &mut ast::Package,
-    ) -> (hir::Package, Vec<Error>) {
+    ) -> (hir::Package, Vec<Error>, Vec<Error>) {
         let mut cond_compile = preprocess::Conditional::new(self.capabilities);
         cond_compile.visit_package(ast);
 
@@ -290,11 +294,17 @@ impl Compiler {
             .map(|e| WithSource::from_map(&unit.sources, e))
             .collect::<Vec<_>>();
 
+        let warnings = self
+            .resolver
+            .drain_warnings()
+            .map(|e| WithSource::from_map(&unit.sources, compile::Error(e.into())))
+            .collect::<Vec<_>>();
+
         if !errors.is_empty() {
             self.lowerer.clear_items();
         }
 
-        (package, errors)
+        (package, errors, warnings)
     }
 
     /// Creates a new `Package` by combining two packages.
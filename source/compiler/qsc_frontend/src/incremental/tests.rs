@@ -346,6 +346,55 @@ fn errors_across_multiple_lines() {
     .assert_debug_eq(&labels);
 }
 
+#[test]
+fn redefined_callable_produces_warning() {
+    let store = PackageStore::new(compile::core());
+    let mut compiler = Compiler::new(
+        &store,
+        &[],
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    let mut unit = CompileUnit::default();
+    let increment = compiler
+        .compile_fragments(
+            &mut unit,
+            "line_1",
+            "function Foo() : Int { 1 }",
+            fail_on_error,
+        )
+        .expect("should succeed");
+    assert!(increment.warnings.is_empty());
+    compiler.update(&mut unit, increment);
+
+    let increment = compiler
+        .compile_fragments(
+            &mut unit,
+            "line_2",
+            "function Foo() : Int { 2 } function Bar() : Int { Foo() }",
+            fail_on_error,
+        )
+        .expect("redefinition should not be an error");
+
+    let warnings = increment
+        .warnings
+        .iter()
+        .map(|w| {
+            (
+                w.code().expect("warning should have a code").to_string(),
+                w.severity(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        [(
+            "Qsc.Resolve.Redefinition".to_string(),
+            Some(miette::Severity::Warning)
+        )]
+    );
+}
+
 #[test]
 fn continue_after_parse_error() {
     let store = PackageStore::new(compile::core());
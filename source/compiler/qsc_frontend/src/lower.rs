@@ -28,8 +28,8 @@ use qsc_hir::{
 use std::{
     clone::Clone,
     iter::{once, repeat},
-    rc::Rc,
     str::FromStr,
+    sync::Arc,
     vec,
 };
 use thiserror::Error;
@@ -239,7 +239,7 @@ impl With<'_> {
             id,
             span: namespace.span,
             parent: None,
-            doc: Rc::clone(&namespace.doc),
+            doc: Arc::clone(&namespace.doc),
             attrs: Vec::new(),
             visibility: hir::Visibility::Public,
             kind: hir::ItemKind::Namespace(name, items),
@@ -330,7 +330,7 @@ impl With<'_> {
                     id: id.item,
                     span: item.span,
                     parent: self.lowerer.parent,
-                    doc: Rc::clone(&item.doc),
+                    doc: Arc::clone(&item.doc),
                     attrs,
                     visibility,
                     kind,
@@ -435,6 +435,27 @@ impl With<'_> {
                 // lower the attribute even if it has invalid args
                 Some(hir::Attr::Test)
             }
+            Ok(hir::Attr::Deprecated(_)) => {
+                let hint = match &*attr.arg.kind {
+                    ast::ExprKind::Tuple(args) if args.is_empty() => Some(Arc::from("")),
+                    // @Deprecated("hint")
+                    ast::ExprKind::Paren(inner) => match inner.kind.as_ref() {
+                        ast::ExprKind::Lit(lit) => match lit.as_ref() {
+                            ast::Lit::String(hint) => Some(Arc::clone(hint)),
+                            _ => None,
+                        },
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if hint.is_none() {
+                    self.lowerer.errors.push(Error::InvalidAttrArgs(
+                        "empty or string literal".to_string(),
+                        attr.arg.span,
+                    ));
+                }
+                hint.map(hir::Attr::Deprecated)
+            }
             Err(()) => {
                 self.lowerer.errors.push(Error::UnknownAttr(
                     attr.name.name.to_string(),
@@ -492,7 +513,12 @@ impl With<'_> {
         let kind = self.lower_callable_kind(decl.kind, attrs, decl.name.span);
         let name = self.lower_ident(&decl.name);
         let mut input = self.lower_pat(&decl.input);
-        let output = convert::ty_from_ast(self.names, &decl.output, &mut Default::default()).0;
+        let output = match decl.body.as_ref() {
+            ast::CallableBody::Block(block) if convert::has_inferred_output(decl) => {
+                self.tys.terms.get(block.id).map_or(Ty::Err, Clone::clone)
+            }
+            _ => convert::ty_from_ast(self.names, &decl.output, &mut Default::default()).0,
+        };
         let (generics, errs) = self.synthesize_callable_generics(&decl.generics, &mut input);
         let functors = convert::ast_callable_functors(decl);
 
@@ -813,6 +839,9 @@ impl With<'_> {
                     .map(|c| self.lower_string_component(c))
                     .collect(),
             ),
+            ast::ExprKind::Match(scrutinee, arms) => {
+                self.lower_match(scrutinee, arms, &ty, expr.span)
+            }
             ast::ExprKind::TernOp(ast::TernOp::Cond, cond, if_true, if_false) => hir::ExprKind::If(
                 Box::new(self.lower_expr(cond)),
                 Box::new(self.lower_expr(if_true)),
@@ -855,6 +884,106 @@ impl With<'_> {
         }
     }
 
+    /// Lowers a match expression into a block that binds the scrutinee once and then compares it
+    /// with the pattern of each arm in an `if`-`elif` chain. A hole pattern, or the last arm of an
+    /// exhaustive match, becomes the final `else` branch.
+    fn lower_match(
+        &mut self,
+        scrutinee: &ast::Expr,
+        arms: &[Box<ast::MatchArm>],
+        ty: &Ty,
+        span: Span,
+    ) -> hir::ExprKind {
+        let scrutinee = self.lower_expr(scrutinee);
+        let scrutinee_ty = scrutinee.ty.clone();
+        let scrutinee_span = scrutinee.span;
+        // The `@` prefix keeps the generated name from colliding with any name in the source.
+        let id = self.assigner.next_node();
+        let ident = hir::Ident {
+            id,
+            span: scrutinee_span,
+            name: format!("@scrutinee_{id}").into(),
+        };
+        let local_id = ident.id;
+        self.lowerer
+            .locals
+            .insert(local_id, (ident.clone(), scrutinee_ty.clone()));
+        let binding = hir::Stmt {
+            id: self.assigner.next_node(),
+            span: scrutinee_span,
+            kind: hir::StmtKind::Local(
+                hir::Mutability::Immutable,
+                hir::Pat {
+                    id: self.assigner.next_node(),
+                    span: scrutinee_span,
+                    ty: scrutinee_ty.clone(),
+                    kind: hir::PatKind::Bind(ident),
+                },
+                scrutinee,
+            ),
+        };
+
+        let exhaustive = typeck::match_is_exhaustive(arms);
+        let mut branches = Vec::new();
+        for (index, arm) in arms.iter().enumerate() {
+            let is_last = index + 1 == arms.len();
+            let cond = if *arm.pat.kind == ast::ExprKind::Hole || (exhaustive && is_last) {
+                None
+            } else {
+                let pat = self.lower_expr(&arm.pat);
+                let var = hir::Expr {
+                    id: self.assigner.next_node(),
+                    span: pat.span,
+                    ty: scrutinee_ty.clone(),
+                    kind: hir::ExprKind::Var(Res::Local(local_id), Vec::new()),
+                };
+                Some(hir::Expr {
+                    id: self.assigner.next_node(),
+                    span: pat.span,
+                    ty: Ty::Prim(qsc_hir::ty::Prim::Bool),
+                    kind: hir::ExprKind::BinOp(hir::BinOp::Eq, Box::new(var), Box::new(pat)),
+                })
+            };
+            let body = self.lower_expr(&arm.body);
+            let is_else = cond.is_none();
+            branches.push((arm.span, cond, body));
+            if is_else {
+                // Any later arms are unreachable.
+                break;
+            }
+        }
+
+        let chain = branches
+            .into_iter()
+            .rev()
+            .fold(None, |otherwise, (arm_span, cond, body)| match cond {
+                None => Some(body),
+                Some(cond) => Some(hir::Expr {
+                    id: self.assigner.next_node(),
+                    span: arm_span,
+                    ty: ty.clone(),
+                    kind: hir::ExprKind::If(
+                        Box::new(cond),
+                        Box::new(body),
+                        otherwise.map(Box::new),
+                    ),
+                }),
+            });
+
+        let mut stmts = vec![binding];
+        stmts.extend(chain.map(|chain| hir::Stmt {
+            id: self.assigner.next_node(),
+            span: chain.span,
+            kind: hir::StmtKind::Expr(chain),
+        }));
+        hir::ExprKind::Block(hir::Block {
+            id: self.assigner.next_node(),
+            span,
+            ty: ty.clone(),
+            stmts,
+        })
+    }
+
     fn lower_field_assign(&mut self, ty: &Ty, field_assign: &ast::FieldAssign) -> hir::FieldAssign {
         hir::FieldAssign {
             id: self.lower_id(field_assign.id),
@@ -868,7 +997,7 @@ impl With<'_> {
         &mut self,
         callee: &ast::Expr,
         arg: &ast::Expr,
-        arrow: Rc<Arrow>,
+        arrow: Arc<Arrow>,
         span: Span,
     ) -> hir::Block {
         let callee = self.lower_expr(callee);
@@ -940,7 +1069,7 @@ impl With<'_> {
             ast::StringComponent::Expr(expr) => {
                 hir::StringComponent::Expr(self.lower_expr(expr).into())
             }
-            ast::StringComponent::Lit(str) => hir::StringComponent::Lit(Rc::clone(str)),
+            ast::StringComponent::Lit(str) => hir::StringComponent::Lit(Arc::clone(str)),
         }
     }
 
@@ -1156,7 +1285,7 @@ impl With<'_> {
                 hir::ExprKind::Lit(hir::Lit::Result(hir::Result::Zero))
             }
             ast::Lit::String(value) => {
-                hir::ExprKind::String(vec![hir::StringComponent::Lit(Rc::clone(value))])
+                hir::ExprKind::String(vec![hir::StringComponent::Lit(Arc::clone(value))])
             }
         }
     }
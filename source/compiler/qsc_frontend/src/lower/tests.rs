@@ -7,6 +7,10 @@ use indoc::indoc;
 use qsc_data_structures::{
     language_features::LanguageFeatures, source::SourceMap, target::TargetCapabilityFlags,
 };
+use qsc_hir::{
+    hir::{Pat, PatKind},
+    visit::{self, Visitor},
+};
 
 fn check_hir(input: &str, expect: &Expect) {
     let sources = SourceMap::new([("test".into(), input.into())], None);
@@ -131,6 +135,46 @@ fn test_target_profile_attr_wrong_args() {
     );
 }
 
+#[test]
+fn test_deprecated_attr_allowed() {
+    check_errors(
+        indoc! {r#"
+            namespace input {
+                @Deprecated()
+                function Foo() : Unit {}
+                @Deprecated("use Foo instead")
+                function Bar() : Unit {}
+            }
+        "#},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn test_deprecated_attr_wrong_args() {
+    check_errors(
+        indoc! {"
+            namespace input {
+                @Deprecated(Bar)
+                function Foo() : Unit {}
+            }
+        "},
+        &expect![[r#"
+            [
+                InvalidAttrArgs(
+                    "empty or string literal",
+                    Span {
+                        lo: 33,
+                        hi: 38,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn test_unknown_attr() {
     check_errors(
@@ -2950,3 +2994,44 @@ fn literal_complex_lowers_as_struct_decl() {
                         ctl-adj: <none>"#]],
     );
 }
+
+struct BoundNames(Vec<String>);
+
+impl<'a> Visitor<'a> for BoundNames {
+    fn visit_pat(&mut self, pat: &'a Pat) {
+        if let PatKind::Bind(ident) = &pat.kind {
+            self.0.push(ident.name.to_string());
+        }
+        visit::walk_pat(self, pat);
+    }
+}
+
+#[test]
+fn match_binds_scrutinee_to_generated_name() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            "function Foo(r : Result) : Int { match r { Zero => 0, One => 1 } }".into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &PackageStore::new(compile::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::MatchExpressions,
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let mut names = BoundNames(Vec::new());
+    names.visit_package(&unit.package);
+    let [param, scrutinee] = &names.0[..] else {
+        panic!("expected two bindings, got {:?}", names.0);
+    };
+    assert_eq!(param, "r");
+    assert!(
+        scrutinee.starts_with("@scrutinee_"),
+        "unexpected scrutinee name {scrutinee}"
+    );
+}
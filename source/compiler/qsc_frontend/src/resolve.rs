@@ -29,7 +29,7 @@ use qsc_hir::{
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::cmp::Ordering;
-use std::{collections::hash_map::Entry, rc::Rc, str::FromStr, vec};
+use std::{collections::hash_map::Entry, str::FromStr, sync::Arc, vec};
 use thiserror::Error;
 
 /// The `Names` map contains an entry for every name in the source that was successfully
@@ -213,6 +213,21 @@ pub(super) enum Error {
     #[diagnostic(code("Qsc.Resolve.NotFound"))]
     NotAvailable(String, String, #[label] Span),
 
+    #[error("`{0}` not found")]
+    #[diagnostic(help(
+        "found a matching item `{1}` in a dependency, but it is declared `internal` and can only be used within its own package"
+    ))]
+    #[diagnostic(code("Qsc.Resolve.NotFound"))]
+    Internal(String, String, #[label] Span),
+
+    #[error("`{0}` redefines a callable declared in an earlier fragment")]
+    #[diagnostic(help(
+        "code from now on uses the new definition; callables declared earlier use it too if the signature is unchanged, and keep the previous one otherwise"
+    ))]
+    #[diagnostic(severity(Warning))]
+    #[diagnostic(code("Qsc.Resolve.Redefinition"))]
+    Redefinition(String, #[label] Span),
+
     #[error("use of unimplemented item `{0}`")]
     #[diagnostic(help("this item is not implemented and cannot be used"))]
     #[diagnostic(code("Qsc.Resolve.Unimplemented"))]
@@ -234,14 +249,14 @@ pub struct Scope {
     span: Span,
     kind: ScopeKind,
     /// Open statements. The key is the alias.
-    opens: FxHashMap<Option<Rc<str>>, Vec<Open>>,
+    opens: FxHashMap<Option<Arc<str>>, Vec<Open>>,
     /// Local newtype declarations.
-    tys: FxHashMap<Rc<str>, Res>,
+    tys: FxHashMap<Arc<str>, Res>,
     /// Local callable and newtype declarations.
-    terms: FxHashMap<Rc<str>, Res>,
+    terms: FxHashMap<Arc<str>, Res>,
     /// Importables, such as callables, types, and namespaces, and their aliases declared
     /// via imports or exports.
-    importables: FxHashMap<Rc<str>, Res>,
+    importables: FxHashMap<Arc<str>, Res>,
     /// Local variables, including callable parameters, for loop bindings, etc.
     /// The u32 is the `valid_at` offset - the lowest offset at which the variable name is available.
     /// It's used to determine which variables are visible at a specific offset in the scope.
@@ -249,9 +264,9 @@ pub struct Scope {
     /// Bug: Because we keep track of only one `valid_at` offset per name,
     /// when a variable is later shadowed in the same scope,
     /// it is missed in the list. <a href=https://github.com/microsoft/qsharp/issues/897 />
-    vars: FxHashMap<Rc<str>, (u32, NodeId)>,
+    vars: FxHashMap<Arc<str>, (u32, NodeId)>,
     /// Type parameters.
-    ty_vars: FxHashMap<Rc<str>, (ParamId, ClassConstraints)>,
+    ty_vars: FxHashMap<Arc<str>, (ParamId, ClassConstraints)>,
 }
 
 impl Scope {
@@ -363,18 +378,18 @@ impl Locals {
 #[derive(Debug)]
 pub enum Local {
     /// A local callable or UDT.
-    Item(ItemId, Rc<str>),
+    Item(ItemId, Arc<str>),
     /// A type parameter.
-    TyParam(ParamId, Rc<str>),
+    TyParam(ParamId, Arc<str>),
     /// A local variable or parameter.
-    Var(NodeId, Rc<str>),
+    Var(NodeId, Arc<str>),
     /// A namespace import (`import Foo as A` or `open Foo`)
-    NamespaceImport(NamespaceId, Option<Rc<str>>),
+    NamespaceImport(NamespaceId, Option<Arc<str>>),
 }
 
 impl Local {
     #[must_use]
-    pub fn name(&self) -> Option<&Rc<str>> {
+    pub fn name(&self) -> Option<&Arc<str>> {
         match self {
             Local::Item(_, name) | Local::TyParam(_, name) | Local::Var(_, name) => Some(name),
             Local::NamespaceImport(_, alias) => alias.as_ref(),
@@ -385,17 +400,20 @@ impl Local {
 #[derive(Debug, Default, Clone)]
 pub struct GlobalScope {
     /// Global names that are valid in a type context (UDTs, builtin types...)
-    tys: IndexMap<NamespaceId, FxHashMap<Rc<str>, Res>>,
+    tys: IndexMap<NamespaceId, FxHashMap<Arc<str>, Res>>,
     /// Global names that are valid in an expression context (callables, UDTs...)
-    terms: IndexMap<NamespaceId, FxHashMap<Rc<str>, Res>>,
+    terms: IndexMap<NamespaceId, FxHashMap<Arc<str>, Res>>,
     /// Global names that are valid in an import/export context (callables, UDTs, namespaces, and any aliases)
-    importables: IndexMap<NamespaceId, FxHashMap<Rc<str>, Res>>,
+    importables: IndexMap<NamespaceId, FxHashMap<Arc<str>, Res>>,
     /// Known namespaces, used to key into the above maps. Exported namespaces are also tracked here.
     namespaces: NamespaceTreeRoot,
     /// Known intrinsics, used to check for duplicates
-    intrinsics: FxHashSet<Rc<str>>,
+    intrinsics: FxHashSet<Arc<str>>,
     /// Known self-exports, used to check for duplicates
     self_exported_item_ids: FxHashMap<ItemId, Span>,
+    /// Fully qualified names of the internal callables and types of dependencies, keyed by item
+    /// name, used to explain why such an item cannot be found
+    dependency_internals: FxHashMap<Arc<str>, Vec<Arc<str>>>,
 }
 
 impl GlobalScope {
@@ -405,7 +423,7 @@ impl GlobalScope {
             .and_then(|items| items.get(name))
     }
 
-    pub fn table(&self, kind: NameKind) -> &IndexMap<NamespaceId, FxHashMap<Rc<str>, Res>> {
+    pub fn table(&self, kind: NameKind) -> &IndexMap<NamespaceId, FxHashMap<Arc<str>, Res>> {
         match kind {
             NameKind::Term => &self.terms,
             NameKind::Ty => &self.tys,
@@ -417,7 +435,7 @@ impl GlobalScope {
     /// item contents. This returns a [`NamespaceId`] which you can use to add more tys and terms to the scope.
     fn insert_or_find_namespace(
         &mut self,
-        name: impl IntoIterator<Item = Rc<str>>,
+        name: impl IntoIterator<Item = Arc<str>>,
         root: Option<NamespaceId>,
     ) -> NamespaceId {
         let name = name.into_iter().collect::<Vec<_>>();
@@ -450,7 +468,7 @@ impl GlobalScope {
     fn insert_alias_for_namespace(
         &mut self,
         existing_namespace: NamespaceId,
-        alias_name: &Rc<str>,
+        alias_name: &Arc<str>,
         alias_parent: NamespaceId,
     ) {
         // Any name collisions should have been detected before
@@ -477,7 +495,7 @@ impl GlobalScope {
             .join(".")
     }
 
-    pub fn namespace_children(&self, namespace_id: NamespaceId) -> Vec<Rc<str>> {
+    pub fn namespace_children(&self, namespace_id: NamespaceId) -> Vec<Arc<str>> {
         self.namespaces
             .find_namespace_by_id(&namespace_id)
             .1
@@ -544,10 +562,12 @@ pub(super) struct Resolver {
     locals: Locals,
     /// Errors encountered during resolution
     errors: Vec<Error>,
+    /// Warnings encountered during resolution, which do not prevent compilation
+    warnings: Vec<Error>,
     /// List of names that were dropped by the preprocessor
     dropped_names: Vec<TrackedName>,
     /// Visitor state: if currently within a callable scope, the parameters of the callable.
-    curr_params: Option<FxHashSet<Rc<str>>>,
+    curr_params: Option<FxHashSet<Arc<str>>>,
     /// Visitor state: Current chain of scopes
     curr_scope_chain: Vec<ScopeId>,
 }
@@ -578,6 +598,7 @@ impl Resolver {
             locals: Locals::default(),
             curr_scope_chain: Vec::new(),
             errors: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
@@ -601,6 +622,7 @@ impl Resolver {
             locals,
             curr_scope_chain: vec![scope_id],
             errors: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
@@ -620,6 +642,10 @@ impl Resolver {
         self.errors.drain(..)
     }
 
+    pub(super) fn drain_warnings(&mut self) -> vec::Drain<'_, Error> {
+        self.warnings.drain(..)
+    }
+
     fn with<'a>(&'a mut self, assigner: &'a mut Assigner) -> With<'a> {
         With {
             resolver: self,
@@ -649,6 +675,19 @@ impl Resolver {
                 }
                 TopLevelNode::Stmt(stmt) => {
                     if let ast::StmtKind::Item(item) = stmt.kind.as_ref() {
+                        // Fragments share one persistent scope, so a callable declared again
+                        // replaces the earlier declaration for all code that follows.
+                        if let ast::ItemKind::Callable(decl) = item.kind.as_ref()
+                            && matches!(
+                                self.current_scope_mut().importables.get(&decl.name.name),
+                                Some(Res::Importable(Importable::Callable(..)))
+                            )
+                        {
+                            self.warnings.push(Error::Redefinition(
+                                decl.name.name.to_string(),
+                                decl.name.span,
+                            ));
+                        }
                         self.bind_local_item(assigner, item);
                     }
                 }
@@ -762,6 +801,8 @@ impl Resolver {
                             format!("{}.{}", dropped_name.namespace, dropped_name.name),
                             span,
                         ))
+                    } else if let Some(qualified) = self.find_dependency_internal(&name) {
+                        Err(Error::Internal(name, qualified.to_string(), span))
                     } else {
                         Err(Error::NotFound(name, span))
                     }
@@ -772,6 +813,37 @@ impl Resolver {
         }
     }
 
+    /// Finds an internal item of a dependency that the unresolved `name` could refer to, which is
+    /// one whose fully qualified name ends with the whole path that was written, or with the path
+    /// after expanding a namespace alias at its start.
+    fn find_dependency_internal(&self, name: &str) -> Option<&Arc<str>> {
+        let item_name = name.rsplit('.').next().unwrap_or(name);
+        let candidates = self.globals.dependency_internals.get(item_name)?;
+        let mut paths = vec![name.to_string()];
+        if let Some((alias, rest)) = name.split_once('.') {
+            let alias = Some(Arc::from(alias));
+            paths.extend(
+                self.locals
+                    .get_scopes(&self.curr_scope_chain)
+                    .filter_map(|scope| scope.opens.get(&alias))
+                    .flatten()
+                    .map(|open| {
+                        format!(
+                            "{}.{rest}",
+                            self.globals.format_namespace_name(open.namespace)
+                        )
+                    }),
+            );
+        }
+        candidates.iter().find(|qualified| {
+            paths.iter().any(|path| {
+                qualified
+                    .strip_suffix(path.as_str())
+                    .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with('.'))
+            })
+        })
+    }
+
     /// # Arguments
     ///
     /// * `pat` - The pattern to bind.
@@ -788,18 +860,18 @@ impl Resolver {
         &mut self,
         pat: &ast::Pat,
         valid_at: u32,
-        bindings: &mut FxHashSet<Rc<str>>,
+        bindings: &mut FxHashSet<Arc<str>>,
     ) {
         match &*pat.kind {
             ast::PatKind::Bind(name, _) => {
-                if !bindings.insert(Rc::clone(&name.name)) {
+                if !bindings.insert(Arc::clone(&name.name)) {
                     self.errors
                         .push(Error::DuplicateBinding(name.name.to_string(), name.span));
                 }
                 self.names.insert(name.id, Res::Local(name.id));
                 self.current_scope_mut()
                     .vars
-                    .insert(Rc::clone(&name.name), (valid_at, name.id));
+                    .insert(Arc::clone(&name.name), (valid_at, name.id));
             }
             ast::PatKind::Discard(_) | ast::PatKind::Elided | ast::PatKind::Err => {}
             ast::PatKind::Paren(pat) => self.bind_pat_recursive(pat, valid_at, bindings),
@@ -834,12 +906,12 @@ impl Resolver {
             return;
         };
 
-        let alias = alias.as_ref().map(|a| Rc::clone(&a.name));
+        let alias = alias.as_ref().map(|a| Arc::clone(&a.name));
 
         self.bind_open(path, id, alias);
     }
 
-    fn bind_open(&mut self, path: &impl Idents, id: NamespaceId, alias: Option<Rc<str>>) {
+    fn bind_open(&mut self, path: &impl Idents, id: NamespaceId, alias: Option<Arc<str>>) {
         let current_opens = self.current_scope_mut().opens.entry(alias).or_default();
 
         let open = Open {
@@ -863,9 +935,9 @@ impl Resolver {
                 let scope = self.current_scope_mut();
                 scope
                     .terms
-                    .insert(Rc::clone(&decl.name.name), Res::Item(id, status));
+                    .insert(Arc::clone(&decl.name.name), Res::Item(id, status));
                 scope.importables.insert(
-                    Rc::clone(&decl.name.name),
+                    Arc::clone(&decl.name.name),
                     Res::Importable(Importable::Callable(id, status)),
                 );
             }
@@ -876,12 +948,12 @@ impl Resolver {
                 let scope = self.current_scope_mut();
                 scope
                     .tys
-                    .insert(Rc::clone(&name.name), Res::Item(id, status));
+                    .insert(Arc::clone(&name.name), Res::Item(id, status));
                 scope
                     .terms
-                    .insert(Rc::clone(&name.name), Res::Item(id, status));
+                    .insert(Arc::clone(&name.name), Res::Item(id, status));
                 scope.importables.insert(
-                    Rc::clone(&name.name),
+                    Arc::clone(&name.name),
                     Res::Importable(Importable::Ty(id, status)),
                 );
             }
@@ -892,12 +964,12 @@ impl Resolver {
                 let scope = self.current_scope_mut();
                 scope
                     .tys
-                    .insert(Rc::clone(&decl.name.name), Res::Item(id, status));
+                    .insert(Arc::clone(&decl.name.name), Res::Item(id, status));
                 scope
                     .terms
-                    .insert(Rc::clone(&decl.name.name), Res::Item(id, status));
+                    .insert(Arc::clone(&decl.name.name), Res::Item(id, status));
                 scope.importables.insert(
-                    Rc::clone(&decl.name.name),
+                    Arc::clone(&decl.name.name),
                     Res::Importable(Importable::Ty(id, status)),
                 );
             }
@@ -917,7 +989,7 @@ impl Resolver {
             .enumerate()
             .for_each(|(ix, type_parameter)| {
                 self.current_scope_mut().ty_vars.insert(
-                    Rc::clone(&type_parameter.ty.name),
+                    Arc::clone(&type_parameter.ty.name),
                     (ix.into(), type_parameter.constraints.clone()),
                 );
                 self.names.insert(
@@ -1026,10 +1098,10 @@ impl AstVisitor<'_> for With<'_> {
     }
 
     fn visit_callable_decl(&mut self, decl: &CallableDecl) {
-        fn collect_param_names(pat: &ast::Pat, names: &mut FxHashSet<Rc<str>>) {
+        fn collect_param_names(pat: &ast::Pat, names: &mut FxHashSet<Arc<str>>) {
             match &*pat.kind {
                 ast::PatKind::Bind(name, _) => {
-                    names.insert(Rc::clone(&name.name));
+                    names.insert(Arc::clone(&name.name));
                 }
                 ast::PatKind::Discard(_) | ast::PatKind::Elided | ast::PatKind::Err => {}
                 ast::PatKind::Paren(pat) => collect_param_names(pat, names),
@@ -1172,7 +1244,7 @@ pub(super) struct GlobalTable {
 
 impl GlobalTable {
     pub(super) fn new() -> Self {
-        let builtins: [(Rc<str>, Res); 10] = [
+        let builtins: [(Arc<str>, Res); 10] = [
             ("BigInt".into(), Res::PrimTy(Prim::BigInt)),
             ("Bool".into(), Res::PrimTy(Prim::Bool)),
             ("Double".into(), Res::PrimTy(Prim::Double)),
@@ -1184,13 +1256,13 @@ impl GlobalTable {
             ("String".into(), Res::PrimTy(Prim::String)),
             ("Unit".into(), Res::UnitTy),
         ];
-        let mut core: FxHashMap<Rc<str>, Res> = FxHashMap::default();
+        let mut core: FxHashMap<Arc<str>, Res> = FxHashMap::default();
         for (name, res) in builtins {
             core.insert(name, res);
         }
 
         let mut scope = GlobalScope::default();
-        let ns = scope.insert_or_find_namespace(vec![Rc::from("Std"), Rc::from("Core")], None);
+        let ns = scope.insert_or_find_namespace(vec![Arc::from("Std"), Arc::from("Core")], None);
 
         let mut tys = IndexMap::default();
         tys.insert(ns, core);
@@ -1204,6 +1276,7 @@ impl GlobalTable {
                 namespaces: scope.namespaces,
                 intrinsics: FxHashSet::default(),
                 self_exported_item_ids: FxHashMap::default(),
+                dependency_internals: FxHashMap::default(),
             },
         }
     }
@@ -1245,13 +1318,10 @@ impl GlobalTable {
         // if there is a package-level alias defined, use that for the root namespace.
         let package_root = alias.map(|alias| {
             self.scope
-                .insert_or_find_namespace(vec![Rc::from(alias)], None)
+                .insert_or_find_namespace(vec![Arc::from(alias)], None)
         });
 
-        for global in global::iter_package(Some(id), package).filter(|global| {
-            global.visibility == hir::Visibility::Public
-                || matches!(&global.kind, global::Kind::Callable(t) if t.intrinsic)
-        }) {
+        for global in global::iter_package(Some(id), package) {
             // If the namespace is `Main` and we have an alias, we treat it as the root of the package, so there's no
             // namespace prefix between the dependency alias and the defined items.
             let global_namespace = if global.namespace.len() == 1
@@ -1263,6 +1333,26 @@ impl GlobalTable {
                 global.namespace.clone()
             };
 
+            if global.visibility == hir::Visibility::Internal {
+                if matches!(global.kind, global::Kind::Ty(_) | global::Kind::Callable(_)) {
+                    let qualified = alias
+                        .into_iter()
+                        .chain(global_namespace.iter().map(AsRef::as_ref))
+                        .chain(std::iter::once(global.name.as_ref()))
+                        .collect::<Vec<_>>()
+                        .join(".");
+                    self.scope
+                        .dependency_internals
+                        .entry(global.name.clone())
+                        .or_default()
+                        .push(qualified.into());
+                }
+                // Internal intrinsics are still tracked so that duplicates can be detected.
+                if !matches!(&global.kind, global::Kind::Callable(t) if t.intrinsic) {
+                    continue;
+                }
+            }
+
             let namespace = self
                 .scope
                 .insert_or_find_namespace(global_namespace.clone(), package_root);
@@ -1416,7 +1506,7 @@ fn bind_namespace(
 ) -> NamespaceId {
     let mut parent_id = scope.root_namespace();
     let mut namespace_id = scope.root_namespace();
-    for part in namespace.name.rc_str_iter() {
+    for part in namespace.name.arc_str_iter() {
         // Bind every part of a dotted namespace declaration as
         // an importable, so import/export items can reference it.
         parent_id = namespace_id;
@@ -1450,7 +1540,7 @@ fn bind_namespace(
 /// Tries to extract a field name from an expression in cases where it is syntactically ambiguous
 /// whether the expression is a field name or a variable name. This applies to the index operand in
 /// a ternary update operator.
-pub(super) fn extract_field_name<'a>(names: &Names, expr: &'a ast::Expr) -> Option<&'a Rc<str>> {
+pub(super) fn extract_field_name<'a>(names: &Names, expr: &'a ast::Expr) -> Option<&'a Arc<str>> {
     // Follow the same reasoning as `is_field_update`.
     match &*expr.kind {
         ast::ExprKind::Path(PathKind::Ok(path))
@@ -1545,11 +1635,11 @@ fn bind_callable(
         scope
             .terms
             .get_mut_or_default(namespace)
-            .entry(Rc::clone(&name.name)),
+            .entry(Arc::clone(&name.name)),
         scope
             .importables
             .get_mut_or_default(namespace)
-            .entry(Rc::clone(&name.name)),
+            .entry(Arc::clone(&name.name)),
     ) {
         (Entry::Occupied(_), _) | (_, Entry::Occupied(_)) => errors.push(Error::Duplicate(
             name.name.to_string(),
@@ -1562,7 +1652,7 @@ fn bind_callable(
         }
     }
 
-    if decl_is_intrinsic(decl, &attrs) && !scope.intrinsics.insert(Rc::clone(&decl.name.name)) {
+    if decl_is_intrinsic(decl, &attrs) && !scope.intrinsics.insert(Arc::clone(&decl.name.name)) {
         errors.push(Error::DuplicateIntrinsic(
             decl.name.name.to_string(),
             decl.name.span,
@@ -1592,15 +1682,15 @@ fn bind_ty(
         scope
             .terms
             .get_mut_or_default(namespace)
-            .entry(Rc::clone(&name.name)),
+            .entry(Arc::clone(&name.name)),
         scope
             .tys
             .get_mut_or_default(namespace)
-            .entry(Rc::clone(&name.name)),
+            .entry(Arc::clone(&name.name)),
         scope
             .importables
             .get_mut_or_default(namespace)
-            .entry(Rc::clone(&name.name)),
+            .entry(Arc::clone(&name.name)),
     ) {
         (Entry::Occupied(_), _, _) | (_, Entry::Occupied(_), _) | (_, _, Entry::Occupied(_)) => {
             Err(vec![Error::Duplicate(
@@ -1879,7 +1969,7 @@ fn find_symbol_in_namespaces<T, O>(
     provided_namespace_name: Option<&[Ident]>,
     provided_symbol_name: &Ident,
     namespaces_to_search: T,
-    aliases: &FxHashMap<Option<Rc<str>>, Vec<(NamespaceId, O)>>,
+    aliases: &FxHashMap<Option<Arc<str>>, Vec<(NamespaceId, O)>>,
 ) -> FxHashMap<Res, O>
 where
     T: Iterator<Item = (NamespaceId, O)>,
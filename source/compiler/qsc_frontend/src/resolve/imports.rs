@@ -9,7 +9,7 @@ use qsc_ast::ast::{
 };
 use qsc_data_structures::{namespaces::NamespaceId, span::Span};
 use rustc_hash::FxHashMap;
-use std::{collections::hash_map::Entry, rc::Rc};
+use std::{collections::hash_map::Entry, sync::Arc};
 
 const MAX_ITERATIONS: usize = 100;
 
@@ -210,7 +210,7 @@ fn try_resolve_import_or_export_decl(
                         resolver.globals.format_namespace_name(*current_namespace);
                     if resolver.dropped_names.contains(&TrackedName {
                         name: valid_item.path.name.name.clone(),
-                        namespace: Rc::from(current_namespace_name),
+                        namespace: Arc::from(current_namespace_name),
                     }) {
                         continue;
                     }
@@ -284,7 +284,7 @@ fn bind_export(
     match global_scope
         .importables
         .get_mut_or_default(namespace)
-        .entry(Rc::clone(&name.name))
+        .entry(Arc::clone(&name.name))
     {
         Entry::Vacant(entry) => {
             entry.insert(Res::Importable(*imported_item));
@@ -331,18 +331,18 @@ fn bind_export(
             global_scope
                 .terms
                 .get_mut_or_default(namespace)
-                .insert(Rc::clone(&name.name), Res::Item(imported_item_id, status));
+                .insert(Arc::clone(&name.name), Res::Item(imported_item_id, status));
         }
         Importable::Ty(imported_item_id, status) => {
             let res = Res::Item(imported_item_id, status);
             global_scope
                 .terms
                 .get_mut_or_default(namespace)
-                .insert(Rc::clone(&name.name), res.clone());
+                .insert(Arc::clone(&name.name), res.clone());
             global_scope
                 .tys
                 .get_mut_or_default(namespace)
-                .insert(Rc::clone(&name.name), res);
+                .insert(Arc::clone(&name.name), res);
         }
         Importable::Namespace(original_namespace_id, _) => {
             global_scope.insert_alias_for_namespace(original_namespace_id, &name.name, namespace);
@@ -24,7 +24,7 @@ use qsc_data_structures::{
 };
 use qsc_hir::assigner::Assigner as HirAssigner;
 use rustc_hash::FxHashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 use std::{fmt::Write, vec};
 
 #[derive(Debug)]
@@ -50,7 +50,7 @@ struct Renamer<'a> {
     names: &'a Names,
     changes: Vec<(Span, Change)>,
     namespaces: NamespaceTreeRoot,
-    aliases: FxHashMap<Vec<Rc<str>>, NamespaceId>,
+    aliases: FxHashMap<Vec<Arc<str>>, NamespaceId>,
 }
 
 impl<'a> Renamer<'a> {
@@ -194,7 +194,7 @@ impl Renamer<'_> {
             .get_namespace_id(idents.str_iter())
             .or_else(|| {
                 self.aliases
-                    .get(&idents.rc_str_iter().cloned().collect::<Vec<_>>())
+                    .get(&idents.arc_str_iter().cloned().collect::<Vec<_>>())
                     .copied()
             })
     }
@@ -16,7 +16,7 @@ mod tests;
 
 use convert::TyConversionError;
 use miette::Diagnostic;
-use qsc_ast::ast::NodeId;
+use qsc_ast::ast::{self, NodeId};
 use qsc_data_structures::{index_map::IndexMap, span::Span};
 use qsc_hir::{
     hir::{CallableKind, ItemId},
@@ -139,6 +139,10 @@ enum ErrorKind {
     #[diagnostic(help("replace this hole with an expression of the expected type"))]
     #[diagnostic(code("Qsc.TypeCk.TyHole"))]
     TyHole(String, #[label] Span),
+    #[error("match expression does not cover every value")]
+    #[diagnostic(help("add an arm with the pattern `_` to match the remaining values"))]
+    #[diagnostic(code("Qsc.TypeCk.NonExhaustiveMatch"))]
+    NonExhaustiveMatch(#[label] Span),
     #[error("insufficient type information to infer type")]
     #[diagnostic(help("provide a type annotation"))]
     #[diagnostic(code("Qsc.TypeCk.AmbiguousTy"))]
@@ -192,6 +196,39 @@ enum ErrorKind {
     RecursiveTypeConstraint(#[label] Span),
 }
 
+/// Returns true if the patterns of a match expression cover every value of the matched type. Every
+/// pattern has the matched type, so without a hole, `_`, only the literals of `Bool`, `Pauli`, and
+/// `Result` can cover every value.
+pub(crate) fn match_is_exhaustive(arms: &[Box<ast::MatchArm>]) -> bool {
+    let mut bools = [false; 2];
+    let mut paulis = [false; 4];
+    let mut results = [false; 2];
+    for arm in arms {
+        match arm.pat.kind.as_ref() {
+            ast::ExprKind::Hole => return true,
+            ast::ExprKind::Lit(lit) => match lit.as_ref() {
+                ast::Lit::Bool(value) => bools[usize::from(*value)] = true,
+                ast::Lit::Pauli(pauli) => {
+                    paulis[match pauli {
+                        ast::Pauli::I => 0,
+                        ast::Pauli::X => 1,
+                        ast::Pauli::Y => 2,
+                        ast::Pauli::Z => 3,
+                    }] = true;
+                }
+                ast::Lit::Result(ast::Result::Zero) => results[0] = true,
+                ast::Lit::Result(ast::Result::One) => results[1] = true,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    [&bools[..], &paulis[..], &results[..]]
+        .iter()
+        .any(|covered| covered.iter().all(|&c| c))
+}
+
 impl From<TyConversionError> for Error {
     fn from(err: TyConversionError) -> Self {
         use TyConversionError::*;
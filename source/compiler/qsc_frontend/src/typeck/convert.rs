@@ -2,7 +2,7 @@
 // Licensed under the MIT License.
 
 //! Ascribe types to the AST and output HIR items. Put another way, converts the AST to the HIR.
-use std::{cell::RefCell, rc::Rc};
+use std::sync::Arc;
 
 use crate::resolve::{self, Names};
 
@@ -11,7 +11,7 @@ use qsc_ast::ast::{
     Path, PathKind, SetOp, Spec, StructDecl, TyDef, TyDefKind, TyKind,
     TypeParameter as AstTypeParameter,
 };
-use qsc_data_structures::span::Span;
+use qsc_data_structures::{span::Span, sync_cell::SyncCell};
 use qsc_hir::{
     hir::{self},
     ty::{
@@ -61,11 +61,11 @@ pub(crate) fn ty_from_ast(
             let functors = functors
                 .as_ref()
                 .map_or(FunctorSetValue::Empty, |f| eval_functor_expr(f.as_ref()));
-            let ty = Ty::Arrow(Rc::new(Arrow {
+            let ty = Ty::Arrow(Arc::new(Arrow {
                 kind: callable_kind_from_ast(*kind),
-                input: RefCell::new(input),
-                output: RefCell::new(output),
-                functors: RefCell::new(FunctorSet::Value(functors)),
+                input: SyncCell::new(input),
+                output: SyncCell::new(output),
+                functors: SyncCell::new(FunctorSet::Value(functors)),
             }));
             (ty, errors)
         }
@@ -149,16 +149,16 @@ pub(super) fn ast_struct_decl_as_ty_def(decl: &StructDecl) -> TyDef {
 
 pub(super) fn ast_ty_def_cons(
     names: &Names,
-    ty_name: &Rc<str>,
+    ty_name: &Arc<str>,
     id: hir::ItemId,
     def: &TyDef,
 ) -> (Scheme, Vec<TyConversionError>) {
     let (input, errors) = ast_ty_def_base(names, def);
     let ty = Arrow {
         kind: hir::CallableKind::Function,
-        input: RefCell::new(input),
-        output: RefCell::new(Ty::Udt(ty_name.clone(), hir::Res::Item(id))),
-        functors: RefCell::new(FunctorSet::Value(FunctorSetValue::Empty)),
+        input: SyncCell::new(input),
+        output: SyncCell::new(Ty::Udt(ty_name.clone(), hir::Res::Item(id))),
+        functors: SyncCell::new(FunctorSet::Value(FunctorSetValue::Empty)),
     };
     let scheme = Scheme::new(Vec::new(), Box::new(ty));
     (scheme, errors)
@@ -262,9 +262,15 @@ pub(super) fn scheme_for_ast_callable(
     let (mut input, new_errors) = ast_pat_ty(names, &callable.input);
     errors.extend(&mut new_errors.into_iter());
 
-    let (output, output_errors) = ty_from_ast(names, &callable.output, &mut Default::default());
-
-    errors.extend(output_errors);
+    // The output of a callable with an inferred output type is only known once its body has been
+    // checked, so callers see an error type.
+    let output = if has_inferred_output(callable) {
+        Ty::Err
+    } else {
+        let (output, output_errors) = ty_from_ast(names, &callable.output, &mut Default::default());
+        errors.extend(output_errors);
+        output
+    };
 
     let mut functor_params =
         synthesize_functor_params(&mut type_parameters.len().into(), &mut input);
@@ -273,14 +279,23 @@ pub(super) fn scheme_for_ast_callable(
 
     let ty = Arrow {
         kind,
-        input: RefCell::new(input),
-        output: RefCell::new(output),
-        functors: RefCell::new(FunctorSet::Value(ast_callable_functors(callable))),
+        input: SyncCell::new(input),
+        output: SyncCell::new(output),
+        functors: SyncCell::new(FunctorSet::Value(ast_callable_functors(callable))),
     };
 
     (Scheme::new(type_parameters, Box::new(ty)), errors)
 }
 
+/// Returns whether the output type of a callable is inferred from its body. This is the case for the
+/// entry point that the parser synthesizes from the statements of a script, which has an empty
+/// hole as its output type. A hole written in source code is never empty and is reported as a
+/// missing type.
+pub(crate) fn has_inferred_output(callable: &CallableDecl) -> bool {
+    matches!(*callable.output.kind, TyKind::Hole)
+        && callable.output.span.lo == callable.output.span.hi
+}
+
 /// Given a [`Ty`], find all arrow types and create type parameters, if necessary, for them.
 /// Recurses into container types to find all arrow types contained within the type.
 pub(crate) fn synthesize_functor_params(
@@ -294,7 +309,7 @@ pub(crate) fn synthesize_functor_params(
             match functors {
                 FunctorSet::Value(functors) if arrow.kind == hir::CallableKind::Operation => {
                     let param = HirTypeParameter::Functor(functors);
-                    // This uses `RefCell::replace` to update the functor set within the reference counted arrow.
+                    // This uses `SyncCell::replace` to update the functor set within the reference counted arrow.
                     // This should be safe as no other references to the functors should be held, but if they are
                     // this will panic at runtime.
                     // Note the `next_param` local binding is needed to ensure that the `functors` are not borrowed
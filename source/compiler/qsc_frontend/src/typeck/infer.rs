@@ -2,7 +2,7 @@
 // Licensed under the MIT License.
 
 use super::{Error, ErrorKind};
-use qsc_data_structures::{index_map::IndexMap, span::Span};
+use qsc_data_structures::{index_map::IndexMap, span::Span, sync_cell::SyncCell};
 use qsc_hir::{
     hir::{ItemId, PrimField, Res},
     ty::{
@@ -12,10 +12,9 @@ use qsc_hir::{
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::{
-    cell::RefCell,
     collections::{BTreeSet, VecDeque, hash_map::Entry},
     fmt::Debug,
-    rc::Rc,
+    sync::Arc,
 };
 
 const MAX_TY_RECURSION_DEPTH: i8 = 100;
@@ -80,7 +79,7 @@ pub(super) enum Class {
     // A user-defined class
     // When we actually support this, and don't just use it to generate an error,
     // it should have an ID here instead of a name
-    NonPrimitive(Rc<str>),
+    NonPrimitive(Arc<str>),
 }
 
 impl Class {
@@ -194,7 +193,7 @@ impl Class {
                 output,
             } => check_call(callee, &input, output, span),
             Class::Ctl { op, with_ctls } => check_ctl(op, with_ctls, span),
-            Class::Eq(ty) => check_eq(ty, span),
+            Class::Eq(ty) => check_eq(udts, ty, span),
             Class::Exp { base, power } => check_exp(base, power, span),
             Class::HasField { record, name, item } => {
                 check_has_field(udts, &record, name, item, span)
@@ -895,7 +894,7 @@ fn substitute_ty(solution: &Solution, ty: &mut Ty) -> bool {
             Ty::Err | Ty::Param { .. } | Ty::Prim(_) | Ty::Udt(_, _) => true,
             Ty::Array(item) => substitute_ty_recursive(solution, item, limit - 1),
             Ty::Arrow(arrow) => {
-                // These updates require borrowing the values inside the RefCells mutably.
+                // These updates require borrowing the values inside the cells mutably.
                 // This should be safe because no other code should be borrowing these values at the same time,
                 // but it will panic at runtime if any other borrows occur.
                 let a = substitute_ty_recursive(
@@ -1041,16 +1040,16 @@ fn check_call(callee: Ty, input: &ArgTy, output: Ty, span: Span) -> (Vec<Constra
 
     let mut app = input.apply(&arrow.input.borrow(), span);
     let expected = if app.holes.len() > 1 {
-        Ty::Arrow(Rc::new(Arrow {
+        Ty::Arrow(Arc::new(Arrow {
             kind: arrow.kind,
-            input: RefCell::new(Ty::Tuple(app.holes)),
+            input: SyncCell::new(Ty::Tuple(app.holes)),
             output: arrow.output.clone(),
             functors: arrow.functors.clone(),
         }))
     } else if let Some(hole) = app.holes.pop() {
-        Ty::Arrow(Rc::new(Arrow {
+        Ty::Arrow(Arc::new(Arrow {
             kind: arrow.kind,
-            input: RefCell::new(hole),
+            input: SyncCell::new(hole),
             output: arrow.output.clone(),
             functors: arrow.functors.clone(),
         }))
@@ -1075,7 +1074,7 @@ fn check_ctl(op: Ty, with_ctls: Ty, span: Span) -> (Vec<Constraint>, Vec<Error>)
     };
 
     let qubit_array = Ty::Array(Box::new(Ty::Prim(Prim::Qubit)));
-    let ctl_input = RefCell::new(Ty::Tuple(vec![qubit_array, arrow.input.borrow().clone()]));
+    let ctl_input = SyncCell::new(Ty::Tuple(vec![qubit_array, arrow.input.borrow().clone()]));
     let actual = *arrow.functors.borrow();
     (
         vec![
@@ -1085,7 +1084,7 @@ fn check_ctl(op: Ty, with_ctls: Ty, span: Span) -> (Vec<Constraint>, Vec<Error>)
                 span,
             },
             Constraint::Eq {
-                expected: Ty::Arrow(Rc::new(Arrow {
+                expected: Ty::Arrow(Arc::new(Arrow {
                     kind: arrow.kind,
                     input: ctl_input,
                     output: arrow.output.clone(),
@@ -1100,7 +1099,9 @@ fn check_ctl(op: Ty, with_ctls: Ty, span: Span) -> (Vec<Constraint>, Vec<Error>)
 }
 
 /// Checks that the class `Eq` is implemented for the given type.
-fn check_eq(ty: Ty, span: Span) -> (Vec<Constraint>, Vec<Error>) {
+/// Arrays, tuples and user-defined types support equality when all of their items do,
+/// and are compared item by item.
+fn check_eq(udts: &FxHashMap<ItemId, Udt>, ty: Ty, span: Span) -> (Vec<Constraint>, Vec<Error>) {
     match ty {
         Ty::Prim(
             Prim::BigInt
@@ -1137,6 +1138,16 @@ fn check_eq(ty: Ty, span: Span) -> (Vec<Constraint>, Vec<Error>) {
             }
         }
         Ty::Udt(_, Res::Item(id)) if id == ItemId::complex() => (Vec::new(), Vec::new()),
+        Ty::Udt(_, Res::Item(id)) if udts.contains_key(&id) => {
+            let mut pure_ty = udts[&id].get_pure_ty();
+            if let Some(package_id) = id.package {
+                pure_ty = pure_ty.with_package(package_id);
+            }
+            (
+                vec![Constraint::Class(Class::Eq(pure_ty), span)],
+                Vec::new(),
+            )
+        }
         _ => (
             Vec::new(),
             vec![Error(ErrorKind::MissingClassEq(ty.display(), span))],
@@ -14,13 +14,13 @@ use qsc_ast::ast::{
     PatKind, Path, PathKind, QubitInit, QubitInitKind, Spec, Stmt, StmtKind, StringComponent,
     TernOp, TyKind, TypeParameter, UnOp,
 };
-use qsc_data_structures::span::Span;
+use qsc_data_structures::{span::Span, sync_cell::SyncCell};
 use qsc_hir::{
     hir::{self, ItemId},
     ty::{Arrow, FunctorSet, FunctorSetValue, GenericArg, Prim, Scheme, Ty, Udt},
 };
 use rustc_hash::FxHashMap;
-use std::{cell::RefCell, rc::Rc};
+use std::sync::Arc;
 
 /// An inferred partial term has a type, but may be the result of a diverging (non-terminating)
 /// computation.
@@ -54,6 +54,7 @@ struct Context<'a> {
     table: &'a mut Table,
     return_ty: Option<Ty>,
     typed_holes: Vec<(NodeId, Span)>,
+    non_exhaustive_matches: Vec<Span>,
     /// New nodes that will be introduced into the parent `Context` after this context terminates
     new: Vec<NodeId>,
     inferrer: &'a mut Inferrer,
@@ -73,6 +74,7 @@ impl<'a> Context<'a> {
             table,
             return_ty: None,
             typed_holes: Vec::new(),
+            non_exhaustive_matches: Vec::new(),
             new,
             inferrer,
         }
@@ -111,11 +113,11 @@ impl<'a> Context<'a> {
     fn infer_ty(&mut self, ty: &ast::Ty) -> Ty {
         match &*ty.kind {
             TyKind::Array(item) => Ty::Array(Box::new(self.infer_ty(item))),
-            TyKind::Arrow(kind, input, output, functors) => Ty::Arrow(Rc::new(Arrow {
+            TyKind::Arrow(kind, input, output, functors) => Ty::Arrow(Arc::new(Arrow {
                 kind: convert::callable_kind_from_ast(*kind),
-                input: RefCell::new(self.infer_ty(input)),
-                output: RefCell::new(self.infer_ty(output)),
-                functors: RefCell::new(FunctorSet::Value(
+                input: SyncCell::new(self.infer_ty(input)),
+                output: SyncCell::new(self.infer_ty(output)),
+                functors: SyncCell::new(FunctorSet::Value(
                     functors.as_ref().map_or(FunctorSetValue::Empty, |f| {
                         convert::eval_functor_expr(f.as_ref())
                     }),
@@ -430,11 +432,11 @@ impl<'a> Context<'a> {
                 self.return_ty = prev_ret_ty;
                 self.inferrer
                     .eq(body.span, body_partial.ty, output_ty.clone());
-                converge(Ty::Arrow(Rc::new(Arrow {
+                converge(Ty::Arrow(Arc::new(Arrow {
                     kind: convert::callable_kind_from_ast(*kind),
-                    input: RefCell::new(input),
-                    output: RefCell::new(output_ty),
-                    functors: RefCell::new(self.inferrer.fresh_functor()),
+                    input: SyncCell::new(input),
+                    output: SyncCell::new(output_ty),
+                    functors: SyncCell::new(self.inferrer.fresh_functor()),
                 })))
             }
             ExprKind::Lit(lit) => match lit.as_ref() {
@@ -447,6 +449,33 @@ impl<'a> Context<'a> {
                 Lit::Result(_) => converge(Ty::Prim(Prim::Result)),
                 Lit::String(_) => converge(Ty::Prim(Prim::String)),
             },
+            ExprKind::Match(scrutinee, arms) => {
+                let scrutinee = self.infer_expr(scrutinee);
+                let ty = self.inferrer.fresh_ty(TySource::not_divergent(expr.span));
+                let mut arms_diverge = !arms.is_empty();
+                for arm in arms {
+                    if *arm.pat.kind == ExprKind::Hole {
+                        self.record(arm.pat.id, scrutinee.ty.clone());
+                    } else {
+                        let pat = self.infer_expr(&arm.pat);
+                        self.inferrer.eq(arm.pat.span, scrutinee.ty.clone(), pat.ty);
+                    }
+                    let mut body = self.infer_expr(&arm.body);
+                    if body.diverges {
+                        body = self.diverge();
+                        self.record(arm.body.id, body.ty.clone());
+                    }
+                    self.inferrer.eq(arm.body.span, ty.clone(), body.ty);
+                    arms_diverge &= body.diverges;
+                }
+                if !super::match_is_exhaustive(arms) {
+                    self.non_exhaustive_matches.push(expr.span);
+                }
+                Partial {
+                    ty,
+                    diverges: scrutinee.diverges || arms_diverge,
+                }
+            }
             ExprKind::Paren(expr) => self.infer_expr(expr),
             ExprKind::Path(path) => self.infer_path_kind(expr, path),
             ExprKind::Range(start, step, end) => {
@@ -642,7 +671,7 @@ impl<'a> Context<'a> {
                     };
                     let (ty, args) = self.inferrer.instantiate(scheme, expr.span);
                     self.table.generics.insert(expr.id, args);
-                    converge(Ty::Arrow(Rc::new(ty)))
+                    converge(Ty::Arrow(Arc::new(ty)))
                 }
                 Some(&Res::Local(node)) => converge(
                     self.table
@@ -1010,7 +1039,7 @@ impl<'a> Context<'a> {
                     "Complex",
                     "Complex type should be defined and well-known"
                 );
-                converge(Ty::Udt(Rc::clone(name), hir::Res::Item(complex_item_id)))
+                converge(Ty::Udt(Arc::clone(name), hir::Res::Item(complex_item_id)))
             }
         }
     }
@@ -1064,6 +1093,10 @@ impl<'a> Context<'a> {
             errs.push(Error(super::ErrorKind::TyHole(ty.display(), span)));
         }
 
+        for span in self.non_exhaustive_matches {
+            errs.push(Error(super::ErrorKind::NonExhaustiveMatch(span)));
+        }
+
         errs
     }
 }
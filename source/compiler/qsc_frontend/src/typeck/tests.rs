@@ -77,15 +77,36 @@ impl<'a> Visitor<'a> for TyCollector<'a> {
 }
 
 fn check(input: &str, entry_expr: &str, expect: &Expect) {
-    check_with_error_option(input, entry_expr, expect, false);
+    check_with_error_option(
+        input,
+        entry_expr,
+        expect,
+        false,
+        LanguageFeatures::default(),
+    );
 }
 
 fn check_allow_parse_errors(input: &str, entry_expr: &str, expect: &Expect) {
-    check_with_error_option(input, entry_expr, expect, true);
+    check_with_error_option(input, entry_expr, expect, true, LanguageFeatures::default());
+}
+
+fn check_with_features(
+    input: &str,
+    entry_expr: &str,
+    language_features: LanguageFeatures,
+    expect: &Expect,
+) {
+    check_with_error_option(input, entry_expr, expect, false, language_features);
 }
 
-fn check_with_error_option(input: &str, entry_expr: &str, expect: &Expect, allow_errors: bool) {
-    let (package, tys, errors) = compile(input, entry_expr, allow_errors);
+fn check_with_error_option(
+    input: &str,
+    entry_expr: &str,
+    expect: &Expect,
+    allow_errors: bool,
+    language_features: LanguageFeatures,
+) {
+    let (package, tys, errors) = compile(input, entry_expr, allow_errors, language_features);
     let mut collector = TyCollector {
         tys: &tys.terms,
         nodes: Vec::new(),
@@ -116,8 +137,9 @@ fn compile(
     input: &str,
     entry_expr: &str,
     allow_errors: bool,
+    language_features: LanguageFeatures,
 ) -> (Package, super::Table, Vec<compile::Error>) {
-    let mut package = parse(input, entry_expr, allow_errors);
+    let mut package = parse(input, entry_expr, allow_errors, language_features);
     AstAssigner::new().visit_package(&mut package);
     let mut assigner = HirAssigner::new();
 
@@ -141,8 +163,13 @@ fn compile(
     (package, tys, errors)
 }
 
-fn parse(input: &str, entry_expr: &str, allow_errors: bool) -> Package {
-    let (namespaces, errors) = qsc_parse::namespaces(input, None, LanguageFeatures::default());
+fn parse(
+    input: &str,
+    entry_expr: &str,
+    allow_errors: bool,
+    language_features: LanguageFeatures,
+) -> Package {
+    let (namespaces, errors) = qsc_parse::namespaces(input, None, language_features);
     assert!(
         allow_errors || errors.is_empty(),
         "parsing input failed: {errors:#?}"
@@ -151,7 +178,7 @@ fn parse(input: &str, entry_expr: &str, allow_errors: bool) -> Package {
     let entry = if entry_expr.is_empty() {
         None
     } else {
-        let (mut entry, errors) = qsc_parse::expr(entry_expr, LanguageFeatures::default());
+        let (mut entry, errors) = qsc_parse::expr(entry_expr, language_features);
         let offset = input
             .len()
             .try_into()
@@ -1182,6 +1209,59 @@ fn if_all_diverge() {
     );
 }
 
+#[test]
+fn match_exhaustive_literals() {
+    check_with_features(
+        "",
+        "match Zero { Zero => 0, One => 1 }",
+        LanguageFeatures::MatchExpressions,
+        &expect![[r#"
+            #1 0-34 "match Zero { Zero => 0, One => 1 }" : Int
+            #2 6-10 "Zero" : Result
+            #4 13-17 "Zero" : Result
+            #5 21-22 "0" : Int
+            #7 24-27 "One" : Result
+            #8 31-32 "1" : Int
+        "#]],
+    );
+}
+
+#[test]
+fn match_int_without_hole_is_not_exhaustive() {
+    check_with_features(
+        "",
+        "match 1 { 0 => true, 1 => false }",
+        LanguageFeatures::MatchExpressions,
+        &expect![[r#"
+            #1 0-33 "match 1 { 0 => true, 1 => false }" : Bool
+            #2 6-7 "1" : Int
+            #4 10-11 "0" : Int
+            #5 15-19 "true" : Bool
+            #7 21-22 "1" : Int
+            #8 26-31 "false" : Bool
+            Error(Type(Error(NonExhaustiveMatch(Span { lo: 0, hi: 33 }))))
+        "#]],
+    );
+}
+
+#[test]
+fn match_pattern_must_have_scrutinee_type() {
+    check_with_features(
+        "",
+        "match PauliX { Zero => 1, _ => 2 }",
+        LanguageFeatures::MatchExpressions,
+        &expect![[r#"
+            #1 0-34 "match PauliX { Zero => 1, _ => 2 }" : Int
+            #2 6-12 "PauliX" : Pauli
+            #4 15-19 "Zero" : Result
+            #5 23-24 "1" : Int
+            #7 26-27 "_" : Pauli
+            #8 31-32 "2" : Int
+            Error(Type(Error(TyMismatch("Pauli", "Result", Span { lo: 15, hi: 19 }))))
+        "#]],
+    );
+}
+
 #[test]
 fn ternop_cond_error() {
     check(
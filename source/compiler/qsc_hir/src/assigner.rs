@@ -10,7 +10,7 @@ use crate::{
 /// It is used when visiting a package to assign IDs to all elements. Identifiers are resolved and
 /// replaced with canonical IDs in this process. The AST gets all IDs resolved after the symbol resolution
 /// run.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Assigner {
     next_node: NodeId,
     next_item: LocalItemId,
@@ -0,0 +1,521 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A canonical text serialization of HIR, intended for golden tests and external tools.
+//!
+//! Where the [`Display`](std::fmt::Display) output of a package puts every node on its own line and
+//! includes source spans, the canonical form writes s-expressions with explicit node ids and types,
+//! putting each statement on its own line indented by its block depth. Spans and doc comments are
+//! left out, so the output only changes when the lowered program changes.
+//!
+//! Each item, top-level statement, and entry expression starts a line with one of the keywords
+//! `item`, `stmt`, or `entry`. Nodes are written as `(Kind#id [type] children...)`, resolutions as
+//! `local:N`, `item:N`, or `item:N@P` for items in another package, and missing optional children
+//! as `_`. The closing parentheses of a block follow its last statement.
+
+use crate::{
+    hir::{
+        Block, CallableDecl, Expr, ExprKind, Field, Ident, Idents, Item, ItemKind, Lit, Mutability,
+        Package, Pat, PatKind, QubitInit, QubitInitKind, QubitSource, Res, SpecBody, SpecDecl,
+        Stmt, StmtKind, StringComponent, Visibility,
+    },
+    ty::{GenericArg, Udt, UdtDef, UdtDefKind},
+};
+use std::fmt::{self, Write};
+
+const INDENT: &str = "    ";
+
+/// Renders the package in the canonical text format, starting a line for each item, top-level
+/// statement, and entry expression and for each statement nested in a block.
+#[must_use]
+pub fn render(package: &Package) -> String {
+    let mut printer = Printer::default();
+    write_package(&mut printer, package).expect("writing to a string should succeed");
+    printer.out
+}
+
+/// Renders a single expression in the canonical text format.
+#[must_use]
+pub fn render_expr(expr: &Expr) -> String {
+    let mut printer = Printer::default();
+    write_expr(&mut printer, expr).expect("writing to a string should succeed");
+    printer.out
+}
+
+/// A string writer that tracks the block nesting depth used to indent statements.
+#[derive(Default)]
+struct Printer {
+    out: String,
+    depth: usize,
+}
+
+impl Printer {
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+    }
+}
+
+impl Write for Printer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.push_str(s);
+        Ok(())
+    }
+}
+
+fn write_package(w: &mut Printer, package: &Package) -> fmt::Result {
+    for item in package.items.values() {
+        write_item(w, item)?;
+        w.write_char('\n')?;
+    }
+    for stmt in &package.stmts {
+        w.write_str("stmt ")?;
+        write_stmt(w, stmt)?;
+        w.write_char('\n')?;
+    }
+    if let Some(entry) = &package.entry {
+        w.write_str("entry ")?;
+        write_expr(w, entry)?;
+        w.write_char('\n')?;
+    }
+    Ok(())
+}
+
+fn write_item(w: &mut Printer, item: &Item) -> fmt::Result {
+    write!(w, "item {}", item.id)?;
+    if let Some(parent) = item.parent {
+        write!(w, " parent={parent}")?;
+    }
+    w.write_str(match item.visibility {
+        Visibility::Public => " public",
+        Visibility::Internal => " internal",
+    })?;
+    if !item.attrs.is_empty() {
+        w.write_str(" (attrs")?;
+        for attr in &item.attrs {
+            write!(w, " {attr:?}")?;
+        }
+        w.write_char(')')?;
+    }
+    w.write_char(' ')?;
+    match &item.kind {
+        ItemKind::Callable(decl) => write_callable(w, decl),
+        ItemKind::Namespace(name, items) => {
+            w.write_str("(Namespace ")?;
+            write_idents(w, name)?;
+            for item in items {
+                write!(w, " {item}")?;
+            }
+            w.write_char(')')
+        }
+        ItemKind::Ty(name, udt) => {
+            w.write_str("(Ty ")?;
+            write_ident(w, name)?;
+            w.write_char(' ')?;
+            write_udt(w, udt)?;
+            w.write_char(')')
+        }
+        ItemKind::Export(name, res) => {
+            w.write_str("(Export ")?;
+            write_ident(w, name)?;
+            w.write_char(' ')?;
+            write_res(w, *res)?;
+            w.write_char(')')
+        }
+    }
+}
+
+fn write_callable(w: &mut Printer, decl: &CallableDecl) -> fmt::Result {
+    write!(w, "(Callable#{} {} ", decl.id, decl.kind)?;
+    write_ident(w, &decl.name)?;
+    if !decl.generics.is_empty() {
+        w.write_str(" (generics")?;
+        for param in &decl.generics {
+            write!(w, " [{param}]")?;
+        }
+        w.write_char(')')?;
+    }
+    if !decl.attrs.is_empty() {
+        w.write_str(" (attrs")?;
+        for attr in &decl.attrs {
+            write!(w, " {attr:?}")?;
+        }
+        w.write_char(')')?;
+    }
+    w.write_str(" (input ")?;
+    write_pat(w, &decl.input)?;
+    write!(
+        w,
+        ") (output [{}]) (functors [{}])",
+        decl.output, decl.functors
+    )?;
+    write_spec(w, "body", &decl.body)?;
+    for (name, spec) in [
+        ("adj", &decl.adj),
+        ("ctl", &decl.ctl),
+        ("ctl_adj", &decl.ctl_adj),
+    ] {
+        if let Some(spec) = spec {
+            write_spec(w, name, spec)?;
+        }
+    }
+    w.write_char(')')
+}
+
+fn write_spec(w: &mut Printer, name: &str, spec: &SpecDecl) -> fmt::Result {
+    write!(w, " ({name} (Spec#{} ", spec.id)?;
+    match &spec.body {
+        SpecBody::Gen(spec_gen) => write!(w, "(Gen {spec_gen:?})")?,
+        SpecBody::Impl(input, block) => {
+            w.write_str("(Impl ")?;
+            write_opt(w, input.as_ref(), write_pat)?;
+            w.write_char(' ')?;
+            write_block(w, block)?;
+            w.write_char(')')?;
+        }
+    }
+    w.write_str("))")
+}
+
+fn write_udt(w: &mut Printer, udt: &Udt) -> fmt::Result {
+    write!(w, "(Udt {:?} ", udt.name)?;
+    write_udt_def(w, &udt.definition)?;
+    w.write_char(')')
+}
+
+fn write_udt_def(w: &mut Printer, def: &UdtDef) -> fmt::Result {
+    match &def.kind {
+        UdtDefKind::Field(field) => {
+            w.write_str("(Field ")?;
+            match &field.name {
+                Some(name) => write!(w, "{name:?}")?,
+                None => w.write_char('_')?,
+            }
+            write!(w, " [{}])", field.ty)
+        }
+        UdtDefKind::Tuple(defs) => {
+            w.write_str("(Tuple")?;
+            for def in defs {
+                w.write_char(' ')?;
+                write_udt_def(w, def)?;
+            }
+            w.write_char(')')
+        }
+    }
+}
+
+fn write_block(w: &mut Printer, block: &Block) -> fmt::Result {
+    write!(w, "(Block#{} [{}]", block.id, block.ty)?;
+    w.depth += 1;
+    for stmt in &block.stmts {
+        w.newline();
+        write_stmt(w, stmt)?;
+    }
+    w.depth -= 1;
+    w.write_char(')')
+}
+
+fn write_stmt(w: &mut Printer, stmt: &Stmt) -> fmt::Result {
+    match &stmt.kind {
+        StmtKind::Expr(expr) => {
+            write!(w, "(Expr#{} ", stmt.id)?;
+            write_expr(w, expr)?;
+        }
+        StmtKind::Item(item) => write!(w, "(Item#{} {item}", stmt.id)?,
+        StmtKind::Local(mutability, pat, expr) => {
+            let mutability = match mutability {
+                Mutability::Immutable => "immutable",
+                Mutability::Mutable => "mutable",
+            };
+            write!(w, "(Local#{} {mutability} ", stmt.id)?;
+            write_pat(w, pat)?;
+            w.write_char(' ')?;
+            write_expr(w, expr)?;
+        }
+        StmtKind::Qubit(source, pat, init, block) => {
+            let source = match source {
+                QubitSource::Fresh => "fresh",
+                QubitSource::Dirty => "dirty",
+            };
+            write!(w, "(Qubit#{} {source} ", stmt.id)?;
+            write_pat(w, pat)?;
+            w.write_char(' ')?;
+            write_qubit_init(w, init)?;
+            w.write_char(' ')?;
+            write_opt(w, block.as_ref(), write_block)?;
+        }
+        StmtKind::Semi(expr) => {
+            write!(w, "(Semi#{} ", stmt.id)?;
+            write_expr(w, expr)?;
+        }
+    }
+    w.write_char(')')
+}
+
+fn write_pat(w: &mut Printer, pat: &Pat) -> fmt::Result {
+    match &pat.kind {
+        PatKind::Bind(name) => {
+            write!(w, "(Bind#{} [{}] ", pat.id, pat.ty)?;
+            write_ident(w, name)?;
+        }
+        PatKind::Discard => write!(w, "(Discard#{} [{}]", pat.id, pat.ty)?,
+        PatKind::Tuple(pats) => {
+            write!(w, "(Tuple#{} [{}]", pat.id, pat.ty)?;
+            for pat in pats {
+                w.write_char(' ')?;
+                write_pat(w, pat)?;
+            }
+        }
+        PatKind::Err => write!(w, "(Err#{} [{}]", pat.id, pat.ty)?,
+    }
+    w.write_char(')')
+}
+
+fn write_qubit_init(w: &mut Printer, init: &QubitInit) -> fmt::Result {
+    match &init.kind {
+        QubitInitKind::Array(len) => {
+            write!(w, "(Array#{} [{}] ", init.id, init.ty)?;
+            write_expr(w, len)?;
+        }
+        QubitInitKind::Single => write!(w, "(Single#{} [{}]", init.id, init.ty)?,
+        QubitInitKind::Tuple(inits) => {
+            write!(w, "(Tuple#{} [{}]", init.id, init.ty)?;
+            for init in inits {
+                w.write_char(' ')?;
+                write_qubit_init(w, init)?;
+            }
+        }
+        QubitInitKind::Err => write!(w, "(Err#{} [{}]", init.id, init.ty)?,
+    }
+    w.write_char(')')
+}
+
+#[allow(clippy::too_many_lines)]
+fn write_expr(w: &mut Printer, expr: &Expr) -> fmt::Result {
+    let name = match &expr.kind {
+        ExprKind::Array(_) => "Array",
+        ExprKind::ArrayRepeat(..) => "ArrayRepeat",
+        ExprKind::Assign(..) => "Assign",
+        ExprKind::AssignOp(..) => "AssignOp",
+        ExprKind::AssignField(..) => "AssignField",
+        ExprKind::AssignIndex(..) => "AssignIndex",
+        ExprKind::BinOp(..) => "BinOp",
+        ExprKind::Block(_) => "Block",
+        ExprKind::Call(..) => "Call",
+        ExprKind::Closure(..) => "Closure",
+        ExprKind::Conjugate(..) => "Conjugate",
+        ExprKind::Fail(_) => "Fail",
+        ExprKind::Field(..) => "Field",
+        ExprKind::For(..) => "For",
+        ExprKind::Hole => "Hole",
+        ExprKind::If(..) => "If",
+        ExprKind::Index(..) => "Index",
+        ExprKind::Lit(_) => "Lit",
+        ExprKind::Range(..) => "Range",
+        ExprKind::Repeat(..) => "Repeat",
+        ExprKind::Return(_) => "Return",
+        ExprKind::Struct(..) => "Struct",
+        ExprKind::String(_) => "String",
+        ExprKind::UpdateIndex(..) => "UpdateIndex",
+        ExprKind::Tuple(_) => "Tuple",
+        ExprKind::UnOp(..) => "UnOp",
+        ExprKind::UpdateField(..) => "UpdateField",
+        ExprKind::Var(..) => "Var",
+        ExprKind::While(..) => "While",
+        ExprKind::Err => "Err",
+    };
+    write!(w, "({name}#{} [{}]", expr.id, expr.ty)?;
+
+    match &expr.kind {
+        ExprKind::Array(exprs) | ExprKind::Tuple(exprs) => write_exprs(w, exprs)?,
+        ExprKind::ArrayRepeat(a, b)
+        | ExprKind::Assign(a, b)
+        | ExprKind::Call(a, b)
+        | ExprKind::Index(a, b) => write_exprs(w, [&**a, &**b])?,
+        ExprKind::AssignOp(op, lhs, rhs) | ExprKind::BinOp(op, lhs, rhs) => {
+            write!(w, " {op:?}")?;
+            write_exprs(w, [&**lhs, &**rhs])?;
+        }
+        ExprKind::AssignField(record, field, value)
+        | ExprKind::UpdateField(record, field, value) => {
+            w.write_char(' ')?;
+            write_expr(w, record)?;
+            w.write_char(' ')?;
+            write_field(w, field)?;
+            w.write_char(' ')?;
+            write_expr(w, value)?;
+        }
+        ExprKind::AssignIndex(a, b, c) | ExprKind::UpdateIndex(a, b, c) => {
+            write_exprs(w, [&**a, &**b, &**c])?;
+        }
+        ExprKind::Block(block) => {
+            w.write_char(' ')?;
+            write_block(w, block)?;
+        }
+        ExprKind::Closure(captures, item) => {
+            write!(w, " {item}")?;
+            for capture in captures {
+                write!(w, " local:{capture}")?;
+            }
+        }
+        ExprKind::Conjugate(within, apply) => {
+            w.write_char(' ')?;
+            write_block(w, within)?;
+            w.write_char(' ')?;
+            write_block(w, apply)?;
+        }
+        ExprKind::Fail(expr) | ExprKind::Return(expr) => write_exprs(w, [&**expr])?,
+        ExprKind::Field(record, field) => {
+            w.write_char(' ')?;
+            write_expr(w, record)?;
+            w.write_char(' ')?;
+            write_field(w, field)?;
+        }
+        ExprKind::For(pat, iter, block) => {
+            w.write_char(' ')?;
+            write_pat(w, pat)?;
+            w.write_char(' ')?;
+            write_expr(w, iter)?;
+            w.write_char(' ')?;
+            write_block(w, block)?;
+        }
+        ExprKind::Hole | ExprKind::Err => {}
+        ExprKind::If(cond, body, otherwise) => {
+            write_exprs(w, [&**cond, &**body])?;
+            w.write_char(' ')?;
+            write_opt(w, otherwise.as_deref(), write_expr)?;
+        }
+        ExprKind::Lit(lit) => {
+            w.write_char(' ')?;
+            write_lit(w, lit)?;
+        }
+        ExprKind::Range(start, step, end) => {
+            for part in [start, step, end] {
+                w.write_char(' ')?;
+                write_opt(w, part.as_deref(), write_expr)?;
+            }
+        }
+        ExprKind::Repeat(body, until, fixup) => {
+            w.write_char(' ')?;
+            write_block(w, body)?;
+            w.write_char(' ')?;
+            write_expr(w, until)?;
+            w.write_char(' ')?;
+            write_opt(w, fixup.as_ref(), write_block)?;
+        }
+        ExprKind::Struct(res, copy, fields) => {
+            w.write_char(' ')?;
+            write_res(w, *res)?;
+            w.write_char(' ')?;
+            write_opt(w, copy.as_deref(), write_expr)?;
+            for field in fields {
+                write!(w, " (FieldAssign#{} ", field.id)?;
+                write_field(w, &field.field)?;
+                w.write_char(' ')?;
+                write_expr(w, &field.value)?;
+                w.write_char(')')?;
+            }
+        }
+        ExprKind::String(components) => {
+            for component in components {
+                match component {
+                    StringComponent::Expr(expr) => write_exprs(w, [&**expr])?,
+                    StringComponent::Lit(lit) => write!(w, " {lit:?}")?,
+                }
+            }
+        }
+        ExprKind::UnOp(op, operand) => {
+            write!(w, " {op:?}")?;
+            write_exprs(w, [&**operand])?;
+        }
+        ExprKind::Var(res, args) => {
+            w.write_char(' ')?;
+            write_res(w, *res)?;
+            if !args.is_empty() {
+                w.write_str(" (generics")?;
+                for arg in args {
+                    match arg {
+                        GenericArg::Ty(ty) => write!(w, " [{ty}]")?,
+                        GenericArg::Functor(functors) => write!(w, " [{functors}]")?,
+                    }
+                }
+                w.write_char(')')?;
+            }
+        }
+        ExprKind::While(cond, block) => {
+            write_exprs(w, [&**cond])?;
+            w.write_char(' ')?;
+            write_block(w, block)?;
+        }
+    }
+
+    w.write_char(')')
+}
+
+fn write_exprs<'a>(w: &mut Printer, exprs: impl IntoIterator<Item = &'a Expr>) -> fmt::Result {
+    for expr in exprs {
+        w.write_char(' ')?;
+        write_expr(w, expr)?;
+    }
+    Ok(())
+}
+
+fn write_opt<T>(
+    w: &mut Printer,
+    value: Option<&T>,
+    write: impl FnOnce(&mut Printer, &T) -> fmt::Result,
+) -> fmt::Result {
+    match value {
+        Some(value) => write(w, value),
+        None => w.write_char('_'),
+    }
+}
+
+fn write_lit(w: &mut Printer, lit: &Lit) -> fmt::Result {
+    match lit {
+        Lit::BigInt(val) => write!(w, "{val}L"),
+        Lit::Bool(val) => write!(w, "{val}"),
+        Lit::Double(val) => write!(w, "{val:?}"),
+        Lit::Int(val) => write!(w, "{val}"),
+        Lit::Pauli(val) => write!(w, "Pauli{val:?}"),
+        Lit::Result(val) => write!(w, "{val:?}"),
+    }
+}
+
+fn write_field(w: &mut Printer, field: &Field) -> fmt::Result {
+    match field {
+        Field::Path(path) => {
+            w.write_str("path(")?;
+            for (i, index) in path.indices.iter().enumerate() {
+                if i > 0 {
+                    w.write_char(',')?;
+                }
+                write!(w, "{index}")?;
+            }
+            w.write_char(')')
+        }
+        Field::Prim(prim) => write!(w, "prim({prim:?})"),
+        Field::Err => w.write_str("err"),
+    }
+}
+
+fn write_res(w: &mut Printer, res: Res) -> fmt::Result {
+    match res {
+        Res::Err => w.write_str("err"),
+        Res::Item(id) => match id.package {
+            None => write!(w, "item:{}", id.item),
+            Some(package) => write!(w, "item:{}@{package}", id.item),
+        },
+        Res::Local(node) => write!(w, "local:{node}"),
+    }
+}
+
+fn write_ident(w: &mut Printer, ident: &Ident) -> fmt::Result {
+    write!(w, "{:?}#{}", ident.name, ident.id)
+}
+
+fn write_idents(w: &mut Printer, idents: &Idents) -> fmt::Result {
+    write!(w, "{:?}", idents.name())
+}
@@ -12,12 +12,12 @@ use qsc_data_structures::{
     namespaces::{NamespaceId, NamespaceTreeRoot},
 };
 use rustc_hash::FxHashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct Global {
-    pub namespace: Vec<Rc<str>>,
-    pub name: Rc<str>,
+    pub namespace: Vec<Arc<str>>,
+    pub name: Arc<str>,
     pub visibility: Visibility,
     pub status: ItemStatus,
     pub kind: Kind,
@@ -54,8 +54,8 @@ pub struct Callable {
 /// A lookup table used for looking up global core items for insertion in `qsc_passes`.
 #[derive(Default)]
 pub struct Table {
-    tys: FxHashMap<NamespaceId, FxHashMap<Rc<str>, Ty>>,
-    callables: FxHashMap<NamespaceId, FxHashMap<Rc<str>, Callable>>,
+    tys: FxHashMap<NamespaceId, FxHashMap<Arc<str>, Ty>>,
+    callables: FxHashMap<NamespaceId, FxHashMap<Arc<str>, Callable>>,
     namespaces: NamespaceTreeRoot,
 }
 
@@ -138,7 +138,7 @@ impl PackageIter<'_> {
         match (&item.kind, &parent) {
             (ItemKind::Callable(decl), Some(ItemKind::Namespace(namespace, _))) => Some(Global {
                 namespace: namespace.into(),
-                name: Rc::clone(&decl.name.name),
+                name: Arc::clone(&decl.name.name),
                 visibility,
                 status,
                 kind: Kind::Callable(Callable {
@@ -149,7 +149,7 @@ impl PackageIter<'_> {
             }),
             (ItemKind::Callable(decl), None) => Some(Global {
                 namespace: Vec::new(),
-                name: Rc::clone(&decl.name.name),
+                name: Arc::clone(&decl.name.name),
                 visibility,
                 status,
                 kind: Kind::Callable(Callable {
@@ -160,14 +160,14 @@ impl PackageIter<'_> {
             }),
             (ItemKind::Ty(name, _def), Some(ItemKind::Namespace(namespace, _))) => Some(Global {
                 namespace: namespace.into(),
-                name: Rc::clone(&name.name),
+                name: Arc::clone(&name.name),
                 visibility,
                 status,
                 kind: Kind::Ty(Ty { id: item_id }),
             }),
             (ItemKind::Ty(name, _def), None) => Some(Global {
                 namespace: Vec::new(),
-                name: Rc::clone(&name.name),
+                name: Arc::clone(&name.name),
                 visibility,
                 status,
                 kind: Kind::Ty(Ty { id: item_id }),
@@ -179,8 +179,8 @@ impl PackageIter<'_> {
                     .expect("namespace name should not be empty");
                 // Parent namespace can be empty
                 Some(Global {
-                    namespace: parent.iter().map(|i| Rc::clone(&i.name)).collect(),
-                    name: Rc::clone(&name.name),
+                    namespace: parent.iter().map(|i| Arc::clone(&i.name)).collect(),
+                    name: Arc::clone(&name.name),
                     visibility: Visibility::Public,
                     status,
                     kind: Kind::Namespace(item_id),
@@ -191,7 +191,7 @@ impl PackageIter<'_> {
                 Some(ItemKind::Namespace(namespace, _)),
             ) => Some(Global {
                 namespace: namespace.into(),
-                name: Rc::clone(&name.name),
+                name: Arc::clone(&name.name),
                 visibility,
                 status,
                 // Export items can refer to different packages, so be sure
@@ -7,15 +7,14 @@
 use crate::ty::{Arrow, FunctorSet, FunctorSetValue, GenericArg, Scheme, Ty, TypeParameter, Udt};
 use indenter::{Indented, indented};
 use num_bigint::BigInt;
-use qsc_data_structures::{index_map::IndexMap, span::Span};
+use qsc_data_structures::{index_map::IndexMap, span::Span, sync_cell::SyncCell};
 use std::{
-    cell::RefCell,
     cmp::Ordering,
     fmt::{self, Debug, Display, Formatter, Write},
     hash::{Hash, Hasher},
-    rc::Rc,
     result,
     str::FromStr,
+    sync::Arc,
 };
 
 fn set_indentation<'a, 'b>(
@@ -350,7 +349,7 @@ pub struct Item {
     /// The parent item.
     pub parent: Option<LocalItemId>,
     /// The documentation.
-    pub doc: Rc<str>,
+    pub doc: Arc<str>,
     /// The attributes.
     pub attrs: Vec<Attr>,
     /// The visibility.
@@ -464,9 +463,9 @@ impl CallableDecl {
             self.generics.clone(),
             Box::new(Arrow {
                 kind: self.kind,
-                input: RefCell::new(self.input.ty.clone()),
-                output: RefCell::new(self.output.clone()),
-                functors: RefCell::new(FunctorSet::Value(self.functors)),
+                input: SyncCell::new(self.input.ty.clone()),
+                output: SyncCell::new(self.output.clone()),
+                functors: SyncCell::new(FunctorSet::Value(self.functors)),
             }),
         )
     }
@@ -1128,7 +1127,7 @@ pub enum StringComponent {
     /// An expression.
     Expr(Box<Expr>),
     /// A string literal.
-    Lit(Rc<str>),
+    Lit(Arc<str>),
 }
 
 /// A pattern.
@@ -1268,13 +1267,13 @@ impl<'a> IntoIterator for &'a Idents {
         self.iter()
     }
 }
-impl From<Idents> for Vec<Rc<str>> {
+impl From<Idents> for Vec<Arc<str>> {
     fn from(v: Idents) -> Self {
         v.0.iter().map(|i| i.name.clone()).collect()
     }
 }
 
-impl From<&Idents> for Vec<Rc<str>> {
+impl From<&Idents> for Vec<Arc<str>> {
     fn from(v: &Idents) -> Self {
         v.0.iter().map(|i| i.name.clone()).collect()
     }
@@ -1377,7 +1376,7 @@ impl Idents {
     /// The stringified dot-separated path of the idents in this [`Idents`]
     /// E.g. `a.b.c`
     #[must_use]
-    pub fn name(&self) -> Rc<str> {
+    pub fn name(&self) -> Arc<str> {
         if self.0.len() == 1 {
             return self.0[0].name.clone();
         }
@@ -1388,7 +1387,7 @@ impl Idents {
             }
             buf.push_str(&ident.name);
         }
-        Rc::from(buf)
+        Arc::from(buf)
     }
 }
 /// An identifier.
@@ -1399,7 +1398,7 @@ pub struct Ident {
     /// The span.
     pub span: Span,
     /// The identifier name.
-    pub name: Rc<str>,
+    pub name: Arc<str>,
 }
 
 impl Display for Ident {
@@ -1428,6 +1427,9 @@ pub enum Attr {
     Reset,
     /// Indicates that a callable is a test case.
     Test,
+    /// Indicates that a callable is deprecated, with a hint describing what to use instead.
+    /// The hint is empty when none is given.
+    Deprecated(Arc<str>),
 }
 
 impl Attr {
@@ -1446,6 +1448,9 @@ The `not` operator is also supported to negate the attribute, e.g. `not Adaptive
             Attr::Measurement => "Indicates that an intrinsic callable is a measurement. This means that the operation will be marked as \"irreversible\" in the generated QIR, and output Result types will be moved to the arguments.",
             Attr::Reset => "Indicates that an intrinsic callable is a reset. This means that the operation will be marked as \"irreversible\" in the generated QIR.",
             Attr::Test =>  "Indicates that a callable is a test case.",
+            Attr::Deprecated(_) => "Indicates that a callable is deprecated and should no longer be used. Uses of the callable generate a warning.
+
+An optional string argument describes the replacement, e.g. `@Deprecated(\"use Foo instead\")`.",
         }
     }
 }
@@ -1462,6 +1467,7 @@ impl FromStr for Attr {
             "Measurement" => Ok(Self::Measurement),
             "Reset" => Ok(Self::Reset),
             "Test" => Ok(Self::Test),
+            "Deprecated" => Ok(Self::Deprecated(Arc::from(""))),
             _ => Err(()),
         }
     }
@@ -2,9 +2,11 @@
 // Licensed under the MIT License.
 
 pub mod assigner;
+pub mod canonical;
 pub mod global;
 pub mod hir;
 pub mod mut_visit;
+pub mod rewrite;
 pub mod ty;
 pub mod validate;
 pub mod visit;
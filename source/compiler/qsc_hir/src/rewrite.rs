@@ -1,9 +1,12 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+//! Reusable [`MutVisitor`] implementations for passes that rewrite the HIR tree, such as
+//! specialization generation, inlining, and loop unrolling.
+
 use std::collections::hash_map::Entry;
 
-use qsc_hir::{
+use crate::{
     assigner::Assigner,
     hir::{
         Block, CallableDecl, Expr, ExprKind, Ident, NodeId, Pat, QubitInit, Res, SpecDecl, Stmt,
@@ -13,15 +16,21 @@ use qsc_hir::{
         walk_qubit_init, walk_spec_decl, walk_stmt,
     },
 };
+use qsc_data_structures::span::Span;
 use rustc_hash::FxHashMap;
 
-pub(crate) struct NodeIdRefresher<'a> {
+/// Assigns fresh node ids to every node in a subtree, so that a copy of the subtree can be placed
+/// elsewhere in the same package without duplicating ids. References to locals declared inside
+/// the subtree are updated to the new ids, while references to locals declared outside of it are
+/// left unchanged.
+pub struct NodeIdRefresher<'a> {
     assigner: &'a mut Assigner,
     replacements: FxHashMap<NodeId, NodeId>,
 }
 
 impl<'a> NodeIdRefresher<'a> {
-    pub(crate) fn new(assigner: &'a mut Assigner) -> Self {
+    #[must_use]
+    pub fn new(assigner: &'a mut Assigner) -> Self {
         Self {
             assigner,
             replacements: FxHashMap::default(),
@@ -102,3 +111,25 @@ impl MutVisitor for NodeIdRefresher<'_> {
         walk_ident(self, ident);
     }
 }
+
+/// Gives a span to every node in a subtree that does not have one yet, leaving existing spans
+/// untouched. Passes use this so that synthesized nodes point at the source construct they were
+/// generated from, while nodes copied from user code keep their original locations.
+pub struct SpanFiller {
+    span: Span,
+}
+
+impl SpanFiller {
+    #[must_use]
+    pub fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+impl MutVisitor for SpanFiller {
+    fn visit_span(&mut self, span: &mut Span) {
+        if *span == Span::default() {
+            *span = self.span;
+        }
+    }
+}
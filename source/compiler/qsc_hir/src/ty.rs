@@ -2,14 +2,13 @@
 // Licensed under the MIT License.
 
 use indenter::{Indented, indented};
-use qsc_data_structures::span::Span;
+use qsc_data_structures::{span::Span, sync_cell::SyncCell};
 use rustc_hash::FxHashMap;
 
 use crate::hir::{CallableKind, FieldPath, Functor, ItemId, PackageId, Res};
 use std::{
-    cell::RefCell,
     fmt::{self, Debug, Display, Formatter, Write},
-    rc::Rc,
+    sync::Arc,
 };
 
 fn set_indentation<'a, 'b>(
@@ -30,12 +29,12 @@ pub enum Ty {
     /// An array type.
     Array(Box<Ty>),
     /// An arrow type: `->` for a function or `=>` for an operation.
-    Arrow(Rc<Arrow>),
+    Arrow(Arc<Arrow>),
     /// A placeholder type variable used during type inference.
     Infer(InferTyId),
     /// A type parameter.
     Param {
-        name: Rc<str>,
+        name: Arc<str>,
         id: ParamId,
         bounds: ClassConstraints,
     },
@@ -44,7 +43,7 @@ pub enum Ty {
     /// A tuple type.
     Tuple(Vec<Ty>),
     /// A user-defined type.
-    Udt(Rc<str>, Res),
+    Udt(Arc<str>, Res),
     /// An invalid type.
     #[default]
     Err,
@@ -113,7 +112,7 @@ pub enum ClassConstraint {
     /// Whether or not 'T can be displayed as a string (converted to a string).
     Show,
     /// A class that is not built-in to the compiler.
-    NonNativeClass(Rc<str>),
+    NonNativeClass(Arc<str>),
 }
 
 impl std::fmt::Display for ClassConstraint {
@@ -145,7 +144,7 @@ impl Ty {
         match self {
             Ty::Infer(_) | Ty::Param { .. } | Ty::Prim(_) | Ty::Err => self.clone(),
             Ty::Array(item) => Ty::Array(Box::new(item.with_package(package))),
-            Ty::Arrow(arrow) => Ty::Arrow(Rc::new(arrow.with_package(package))),
+            Ty::Arrow(arrow) => Ty::Arrow(Arc::new(arrow.with_package(package))),
             Ty::Tuple(items) => Ty::Tuple(
                 items
                     .iter()
@@ -269,8 +268,8 @@ impl Scheme {
             params: self.params.clone(),
             ty: Box::new(Arrow {
                 kind: self.ty.kind,
-                input: RefCell::new(self.ty.input.borrow().with_package(package)),
-                output: RefCell::new(self.ty.output.borrow().with_package(package)),
+                input: SyncCell::new(self.ty.input.borrow().with_package(package)),
+                output: SyncCell::new(self.ty.output.borrow().with_package(package)),
                 functors: self.ty.functors.clone(),
             }),
         }
@@ -321,7 +320,7 @@ fn instantiate_ty<'a>(
     match ty {
         Ty::Err | Ty::Infer(_) | Ty::Prim(_) | Ty::Udt(_, _) => Ok(ty.clone()),
         Ty::Array(item) => Ok(Ty::Array(Box::new(instantiate_ty(arg, item)?))),
-        Ty::Arrow(arrow) => Ok(Ty::Arrow(Rc::new(instantiate_arrow_ty(arg, arrow)?))),
+        Ty::Arrow(arrow) => Ok(Ty::Arrow(Arc::new(instantiate_arrow_ty(arg, arrow)?))),
         Ty::Param { id, .. } => match arg(id) {
             Some(GenericArg::Ty(ty_arg)) => Ok(ty_arg.clone()),
             Some(_) => Err(InstantiationError::Kind(*id)),
@@ -354,9 +353,9 @@ fn instantiate_arrow_ty<'a>(
 
     Ok(Arrow {
         kind: arrow.kind,
-        input: RefCell::new(input),
-        output: RefCell::new(output),
-        functors: RefCell::new(functors),
+        input: SyncCell::new(input),
+        output: SyncCell::new(output),
+        functors: SyncCell::new(functors),
     })
 }
 
@@ -382,7 +381,7 @@ impl Display for TypeParameter {
 pub enum TypeParameter {
     /// A type parameter.
     Ty {
-        name: Rc<str>,
+        name: Arc<str>,
         bounds: ClassConstraints,
     },
     /// A functor parameter with a minimal set (lower bound) of functors.
@@ -397,7 +396,7 @@ pub struct TypeParamName {
     /// The span.
     pub span: Span,
     /// The name.
-    pub name: Rc<str>,
+    pub name: Arc<str>,
 }
 
 impl Display for TypeParamName {
@@ -464,11 +463,11 @@ pub struct Arrow {
     /// Whether the callable is a function or an operation.
     pub kind: CallableKind,
     /// The input type to the callable.
-    pub input: RefCell<Ty>,
+    pub input: SyncCell<Ty>,
     /// The output type from the callable.
-    pub output: RefCell<Ty>,
+    pub output: SyncCell<Ty>,
     /// The functors supported by the callable.
-    pub functors: RefCell<FunctorSet>,
+    pub functors: SyncCell<FunctorSet>,
 }
 
 impl Arrow {
@@ -476,8 +475,8 @@ impl Arrow {
     pub fn with_package(&self, package: PackageId) -> Self {
         Self {
             kind: self.kind,
-            input: RefCell::new(self.input.borrow().with_package(package)),
-            output: RefCell::new(self.output.borrow().with_package(package)),
+            input: SyncCell::new(self.input.borrow().with_package(package)),
+            output: SyncCell::new(self.output.borrow().with_package(package)),
             functors: self.functors.clone(),
         }
     }
@@ -495,7 +494,7 @@ impl Display for Arrow {
             self.input.borrow(),
             self.output.borrow()
         )?;
-        if self.functors != RefCell::new(FunctorSet::Value(FunctorSetValue::Empty)) {
+        if self.functors != SyncCell::new(FunctorSet::Value(FunctorSetValue::Empty)) {
             f.write_str(" is ")?;
             Display::fmt(&self.functors.borrow(), f)?;
         }
@@ -651,7 +650,7 @@ pub struct Udt {
     /// The span.
     pub span: Span,
     /// The name.
-    pub name: Rc<str>,
+    pub name: Arc<str>,
     // The definition.
     pub definition: UdtDef,
 }
@@ -679,9 +678,9 @@ impl Udt {
             params: Vec::new(),
             ty: Box::new(Arrow {
                 kind: CallableKind::Function,
-                input: RefCell::new(self.get_pure_ty()),
-                output: RefCell::new(Ty::Udt(self.name.clone(), Res::Item(id))),
-                functors: RefCell::new(FunctorSet::Value(FunctorSetValue::Empty)),
+                input: SyncCell::new(self.get_pure_ty()),
+                output: SyncCell::new(Ty::Udt(self.name.clone(), Res::Item(id))),
+                functors: SyncCell::new(FunctorSet::Value(FunctorSetValue::Empty)),
             }),
         }
     }
@@ -850,7 +849,7 @@ pub struct UdtField {
     /// The span of the field name.
     pub name_span: Option<Span>,
     /// The field name.
-    pub name: Option<Rc<str>>,
+    pub name: Option<Arc<str>>,
     // The field type.
     pub ty: Ty,
 }
@@ -34,6 +34,7 @@ impl LintGroup {
                     LintKind::Hir(DeprecatedFunctionConstructor),
                     LintKind::Hir(DeprecatedWithOperator),
                     LintKind::Hir(DeprecatedDoubleColonOperator),
+                    LintKind::Hir(DeprecatedCallableUse),
                 ]
             }
         }
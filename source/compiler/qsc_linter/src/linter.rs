@@ -15,7 +15,7 @@ use qsc_frontend::compile::{CompileUnit, PackageStore};
 use qsc_hir::hir::{Item, ItemId};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 /// The entry point to the linter. It takes a [`qsc_frontend::compile::CompileUnit`]
 /// as input and outputs a [`Vec<Lint>`](Lint).
@@ -172,7 +172,7 @@ pub struct Lint {
     /// The message the user will see in the code editor.
     pub message: &'static str,
     /// The help text the user will see in the code editor.
-    pub help: &'static str,
+    pub help: Cow<'static, str>,
     /// An enum identifying this lint.
     pub kind: LintKind,
     /// The suggested edits to fix the lint.
@@ -219,7 +219,7 @@ impl Diagnostic for Lint {
         if self.help.is_empty() {
             None
         } else {
-            Some(Box::new(self.help))
+            Some(Box::new(&self.help))
         }
     }
 }
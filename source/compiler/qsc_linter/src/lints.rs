@@ -10,7 +10,7 @@ macro_rules! lint {
             span: $span,
             level: $lint.level,
             message: $lint.message(),
-            help: $lint.help(),
+            help: $lint.help().into(),
             kind: $lint.lint_kind(),
             code_action_edits: vec![],
         }
@@ -20,7 +20,7 @@ macro_rules! lint {
             span: $span,
             level: $lint.level,
             message: $lint.message(),
-            help: $lint.help(),
+            help: $lint.help().into(),
             kind: $lint.lint_kind(),
             code_action_edits: $code_action_edits,
         }
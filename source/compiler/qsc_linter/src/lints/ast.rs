@@ -4,7 +4,8 @@
 use super::lint;
 use crate::linter::{Compilation, ast::declare_ast_lints};
 use qsc_ast::ast::{
-    BinOp, Block, Expr, ExprKind, Item, ItemKind, Lit, NodeId, Stmt, StmtKind, TernOp,
+    BinOp, Block, Expr, ExprKind, Functor, Item, ItemKind, Lit, NodeId, Stmt, StmtKind, TernOp,
+    UnOp,
 };
 use qsc_data_structures::span::Span;
 use qsc_hir::ty::Ty;
@@ -35,6 +36,7 @@ declare_ast_lints! {
     (DiscourageChainAssignment, LintLevel::Warn, "discouraged use of chain assignment", "assignment expressions always return `Unit`, so chaining them may not be useful"),
     (DeprecatedAssignUpdateExpr, LintLevel::Allow, "deprecated use of update assignment expressions", "update assignment expressions \"a w/= b <- c\" are deprecated; consider using explicit assignment instead \"a[b] = c\""),
     (DeprecatedUpdateExpr, LintLevel::Allow, "deprecated use of update expressions", "update expressions \"a w/ b <- c\" are deprecated; consider using explicit assignment instead"),
+    (DoubleAdjoint, LintLevel::Warn, "redundant double adjoint", "applying `Adjoint` twice has no effect, so both can be removed"),
 }
 
 #[derive(Default)]
@@ -300,3 +302,34 @@ impl AstLintPass for DeprecatedUpdateExpr {
         }
     }
 }
+
+#[derive(Default)]
+struct DoubleAdjoint {
+    level: LintLevel,
+    // The inner adjoint of an already reported pair, which shouldn't be reported again.
+    suppressed_expr: Option<NodeId>,
+}
+
+impl AstLintPass for DoubleAdjoint {
+    fn check_expr(&mut self, expr: &Expr, buffer: &mut Vec<Lint>, compilation: Compilation) {
+        if self.suppressed_expr == Some(expr.id) {
+            self.suppressed_expr = None;
+            return;
+        }
+        if let ExprKind::UnOp(UnOp::Functor(Functor::Adj), inner) = expr.kind.as_ref()
+            && let inner = strip_parens(inner)
+            && let ExprKind::UnOp(UnOp::Functor(Functor::Adj), operand) = inner.kind.as_ref()
+        {
+            self.suppressed_expr = Some(inner.id);
+            let edit = vec![(compilation.get_source_code(operand.span), expr.span)];
+            buffer.push(lint!(self, expr.span, edit));
+        }
+    }
+}
+
+fn strip_parens(expr: &Expr) -> &Expr {
+    match expr.kind.as_ref() {
+        ExprKind::Paren(inner) => strip_parens(inner),
+        _ => expr,
+    }
+}
@@ -4,14 +4,15 @@
 use qsc_data_structures::span::Span;
 use qsc_hir::{
     hir::{
-        BinOp, Block, CallableDecl, CallableKind, Expr, ExprKind, Field, ItemKind, Res, SpecBody,
-        SpecDecl, Stmt, StmtKind, UnOp,
+        Attr, BinOp, Block, CallableDecl, CallableKind, Expr, ExprKind, Field, ItemKind,
+        Mutability, NodeId, Pat, PatKind, Res, SpecBody, SpecDecl, Stmt, StmtKind, UnOp,
     },
     ty::{Prim, Ty},
     visit::{self, Visitor},
 };
+use rustc_hash::FxHashSet;
 use std::fmt::Write;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::linter::{Compilation, hir::declare_hir_lints};
 
@@ -40,6 +41,8 @@ declare_hir_lints! {
     (DeprecatedFunctionConstructor, LintLevel::Allow, "deprecated function constructors", "function constructors for struct types are deprecated, use `new` instead"),
     (DeprecatedWithOperator, LintLevel::Allow, "deprecated `w/` and `w/=` operators for structs", "`w/` and `w/=` operators for structs are deprecated, use `new` instead"),
     (DeprecatedDoubleColonOperator, LintLevel::Allow, "deprecated `::` for field access", "`::` operator is deprecated, use `.` instead"),
+    (DeprecatedCallableUse, LintLevel::Warn, "use of deprecated callable", "this callable is deprecated and may be removed in a future release"),
+    (NeedlessMutability, LintLevel::Allow, "mutable variable is never reassigned", "declare the variable with `let` instead"),
     (AmbiguousUnaryOperatorAfterIf, LintLevel::Warn, "ambiguous unary operator after if-expression", "consider wrapping the if-expression in parentheses or using a semicolon to clarify the intended use of the operator"),
 }
 
@@ -173,6 +176,134 @@ impl HirLintPass for NeedlessOperation {
     }
 }
 
+#[derive(Default)]
+struct DeprecatedCallableUse {
+    level: LintLevel,
+}
+
+/// Creates a lint for each reference to a callable marked `@Deprecated`, using the hint
+/// given in the attribute as the help text when there is one.
+impl HirLintPass for DeprecatedCallableUse {
+    fn check_expr(&mut self, expr: &Expr, buffer: &mut Vec<Lint>, compilation: Compilation) {
+        if let ExprKind::Var(Res::Item(item_id), _) = &expr.kind {
+            let item = compilation.resolve_item_id(item_id);
+            if let Some(Attr::Deprecated(hint)) = item
+                .attrs
+                .iter()
+                .find(|attr| matches!(attr, Attr::Deprecated(_)))
+            {
+                let mut lint = lint!(self, expr.span);
+                if !hint.is_empty() {
+                    lint.help = hint.to_string().into();
+                }
+                buffer.push(lint);
+            }
+        }
+    }
+}
+
+/// Collects the mutable bindings in a callable and the local variables assigned to in it.
+#[derive(Default)]
+struct MutableBindings {
+    /// The binding identifier, its span, and the span of the declaring statement.
+    bindings: Vec<(NodeId, Span, Span)>,
+    assigned: FxHashSet<NodeId>,
+}
+
+impl MutableBindings {
+    fn collect_bindings(&mut self, pat: &Pat, stmt_span: Span) {
+        match &pat.kind {
+            // Names starting with `@` are introduced by compiler passes, not by the user.
+            PatKind::Bind(ident) if !ident.name.starts_with('@') => {
+                self.bindings.push((ident.id, ident.span, stmt_span));
+            }
+            PatKind::Tuple(pats) => {
+                for pat in pats {
+                    self.collect_bindings(pat, stmt_span);
+                }
+            }
+            PatKind::Bind(_) | PatKind::Discard | PatKind::Err => {}
+        }
+    }
+
+    fn collect_assigned(&mut self, lhs: &Expr) {
+        match &lhs.kind {
+            ExprKind::Var(Res::Local(id), _) => {
+                self.assigned.insert(*id);
+            }
+            ExprKind::Tuple(items) => {
+                for item in items {
+                    self.collect_assigned(item);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Visitor<'_> for MutableBindings {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        if let StmtKind::Local(Mutability::Mutable, pat, _) = &stmt.kind {
+            self.collect_bindings(pat, stmt.span);
+        }
+        visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Assign(lhs, _)
+            | ExprKind::AssignOp(_, lhs, _)
+            | ExprKind::AssignField(lhs, _, _)
+            | ExprKind::AssignIndex(lhs, _, _) => self.collect_assigned(lhs),
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+#[derive(Default)]
+struct NeedlessMutability {
+    level: LintLevel,
+}
+
+/// Creates a lint for `mutable` bindings that are never reassigned, offering to replace
+/// `mutable` with `let` when the binding is the only one in its statement.
+impl HirLintPass for NeedlessMutability {
+    fn check_callable_decl(
+        &mut self,
+        decl: &CallableDecl,
+        buffer: &mut Vec<Lint>,
+        compilation: Compilation,
+    ) {
+        let mut collector = MutableBindings::default();
+        collector.visit_callable_decl(decl);
+        for &(id, span, stmt_span) in &collector.bindings {
+            if collector.assigned.contains(&id) {
+                continue;
+            }
+            let stmt_bindings = collector
+                .bindings
+                .iter()
+                .filter(|(_, _, s)| *s == stmt_span)
+                .count();
+            let keyword = Span {
+                lo: stmt_span.lo,
+                hi: stmt_span.lo + 7,
+            };
+            let code_action_edits = if stmt_bindings == 1
+                && compilation
+                    .get_source_code(stmt_span)
+                    .starts_with("mutable")
+            {
+                vec![("let".to_string(), keyword)]
+            } else {
+                vec![]
+            };
+            buffer.push(lint!(self, span, code_action_edits));
+        }
+    }
+}
+
 #[derive(Default)]
 struct DeprecatedFunctionConstructor {
     level: LintLevel,
@@ -194,9 +325,9 @@ impl HirLintPass for DeprecatedFunctionConstructor {
 
 struct WithOperatorLint {
     span: Span,
-    ty_name: Rc<str>,
+    ty_name: Arc<str>,
     is_w_eq: bool,
-    field_assigns: Vec<(Rc<str>, Rc<str>)>,
+    field_assigns: Vec<(Arc<str>, Arc<str>)>,
 }
 
 #[derive(Default)]
@@ -226,7 +357,7 @@ impl HirLintPass for DeprecatedWithOperator {
                         } else {
                             panic!("field should be a path");
                         };
-                        let field_value = Rc::from(compilation.get_source_code(value.span));
+                        let field_value = Arc::from(compilation.get_source_code(value.span));
                         let field_info = (field_name, field_value);
 
                         match &mut self.lint_info {
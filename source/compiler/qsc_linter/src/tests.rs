@@ -12,7 +12,6 @@ use qsc_data_structures::{
     language_features::LanguageFeatures, source::SourceMap, span::Span,
     target::TargetCapabilityFlags,
 };
-use qsc_frontend::compile::{self, PackageStore};
 use qsc_hir::hir::CallableKind;
 use qsc_passes::PackageType;
 
@@ -132,6 +131,49 @@ fn lint_group() {
     );
 }
 
+#[test]
+fn lint_config_promotes_and_silences_individual_lints() {
+    check_with_config(
+        &wrap_in_callable("let x = 1 / 0;;;", CallableKind::Function),
+        Some(&[
+            LintOrGroupConfig::Lint(crate::LintConfig {
+                kind: crate::LintKind::Ast(crate::AstLint::RedundantSemicolons),
+                level: LintLevel::Error,
+            }),
+            LintOrGroupConfig::Lint(crate::LintConfig {
+                kind: crate::LintKind::Ast(crate::AstLint::DivisionByZero),
+                level: LintLevel::Allow,
+            }),
+        ]),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: ";;",
+                    level: Error,
+                    message: "redundant semicolons",
+                    help: "remove the redundant semicolons",
+                    code_action_edits: [
+                        (
+                            "",
+                            Span {
+                                lo: 85,
+                                hi: 87,
+                            },
+                        ),
+                    ],
+                },
+                SrcLint {
+                    source: "1 / 0",
+                    level: Allow,
+                    message: "attempt to divide by zero",
+                    help: "division by zero will fail at runtime",
+                    code_action_edits: [],
+                },
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn multiple_lints() {
     check(
@@ -657,6 +699,40 @@ fn deprecated_with_eq_op_for_structs() {
     );
 }
 
+#[test]
+fn deprecated_callable_use() {
+    check(
+        indoc! {r#"
+        @Deprecated("use Bar instead")
+        function Foo() : Unit {}
+        @Deprecated()
+        function Baz() : Unit {}
+        function Bar() : Unit {
+            Foo();
+            Baz();
+        }
+    "#},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Foo",
+                    level: Warn,
+                    message: "use of deprecated callable",
+                    help: "use Bar instead",
+                    code_action_edits: [],
+                },
+                SrcLint {
+                    source: "Baz",
+                    level: Warn,
+                    message: "use of deprecated callable",
+                    help: "this callable is deprecated and may be removed in a future release",
+                    code_action_edits: [],
+                },
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn deprecated_double_colon_op() {
     check(
@@ -776,6 +852,123 @@ fn deprecated_update_expr_lint() {
                     help: "update expressions \"a w/ b <- c\" are deprecated; consider using explicit assignment instead",
                     code_action_edits: [],
                 },
+                SrcLint {
+                    source: "arr",
+                    level: Allow,
+                    message: "mutable variable is never reassigned",
+                    help: "declare the variable with `let` instead",
+                    code_action_edits: [
+                        (
+                            "let",
+                            Span {
+                                lo: 71,
+                                hi: 78,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn double_adjoint() {
+    check(
+        &wrap_in_callable("Adjoint Adjoint H(q);", CallableKind::Operation),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Adjoint Adjoint H",
+                    level: Warn,
+                    message: "redundant double adjoint",
+                    help: "applying `Adjoint` twice has no effect, so both can be removed",
+                    code_action_edits: [
+                        (
+                            "H",
+                            Span {
+                                lo: 72,
+                                hi: 89,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn double_adjoint_in_parens_is_reported_once() {
+    check(
+        &wrap_in_callable("Adjoint (Adjoint (Adjoint H))(q);", CallableKind::Operation),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Adjoint (Adjoint (Adjoint H))",
+                    level: Warn,
+                    message: "redundant double adjoint",
+                    help: "applying `Adjoint` twice has no effect, so both can be removed",
+                    code_action_edits: [
+                        (
+                            "(Adjoint H)",
+                            Span {
+                                lo: 72,
+                                hi: 101,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn needless_mutability() {
+    check(
+        &wrap_in_callable(
+            "mutable x = 1; mutable y = 2; set y += x;",
+            CallableKind::Function,
+        ),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "x",
+                    level: Allow,
+                    message: "mutable variable is never reassigned",
+                    help: "declare the variable with `let` instead",
+                    code_action_edits: [
+                        (
+                            "let",
+                            Span {
+                                lo: 71,
+                                hi: 78,
+                            },
+                        ),
+                    ],
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn needless_mutability_in_tuple_has_no_code_action() {
+    check(
+        &wrap_in_callable(
+            "mutable (a, b) = (1, 2); set a = b;",
+            CallableKind::Function,
+        ),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "b",
+                    level: Allow,
+                    message: "mutable variable is never reassigned",
+                    help: "declare the variable with `let` instead",
+                    code_action_edits: [],
+                },
             ]
         "#]],
     );
@@ -920,8 +1113,7 @@ fn check_that_hir_lints_are_deduplicated_in_operations_with_multiple_specializat
 }
 
 fn compile_and_collect_lints(source: &str, config: Option<&[LintOrGroupConfig]>) -> Vec<Lint> {
-    let mut store = PackageStore::new(compile::core());
-    let std = store.insert(compile::std(&store, TargetCapabilityFlags::all()));
+    let (std, mut store) = qsc::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
     let sources = SourceMap::new([("source.qs".into(), source.into())], None);
     let (unit, _) = qsc::compile::compile(
         &store,
@@ -991,7 +1183,7 @@ struct SrcLint {
     source: String,
     level: LintLevel,
     message: &'static str,
-    help: &'static str,
+    help: String,
     code_action_edits: Vec<(String, Span)>,
 }
 
@@ -1001,7 +1193,7 @@ impl SrcLint {
             source: source[lint.span].into(),
             level: lint.level,
             message: lint.message,
-            help: lint.help,
+            help: lint.help.to_string(),
             code_action_edits: lint
                 .code_action_edits
                 .iter()
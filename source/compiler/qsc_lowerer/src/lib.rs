@@ -302,7 +302,7 @@ impl Lowerer {
                             .id,
                     ),
                     span: name.span(),
-                    name: name.name(),
+                    name: Rc::from(name.name().as_ref()),
                 };
                 let items = items.iter().map(|i| lower_local_item_id(*i)).collect();
                 fir::ItemKind::Namespace(name, items)
@@ -329,7 +329,7 @@ impl Lowerer {
             id: lower_local_item_id(item.id),
             span: item.span,
             parent: item.parent.map(lower_local_item_id),
-            doc: Rc::clone(&item.doc),
+            doc: Rc::from(item.doc.as_ref()),
             attrs,
             visibility: lower_visibility(item.visibility),
             kind,
@@ -469,10 +469,11 @@ impl Lowerer {
             hir::StmtKind::Expr(expr) => fir::StmtKind::Expr(self.lower_expr(expr)),
             hir::StmtKind::Item(item) => fir::StmtKind::Item(lower_local_item_id(*item)),
             hir::StmtKind::Local(mutability, pat, expr) => {
+                let mutability = lower_mutability(*mutability);
                 let pat = self.lower_pat(pat);
                 let expr = self.lower_expr(expr);
-                self.exec_graph.push(ExecGraphNode::Bind(pat));
-                fir::StmtKind::Local(lower_mutability(*mutability), pat, expr)
+                self.exec_graph.push(ExecGraphNode::Bind(pat, mutability));
+                fir::StmtKind::Local(mutability, pat, expr)
             }
             hir::StmtKind::Qubit(_, _, _, _) => {
                 panic!("qubit statements should have been eliminated by passes");
@@ -829,7 +830,7 @@ impl Lowerer {
                 self.exec_graph.push(ExecGraphNode::Store);
                 fir::StringComponent::Expr(expr)
             }
-            hir::StringComponent::Lit(str) => fir::StringComponent::Lit(Rc::clone(str)),
+            hir::StringComponent::Lit(str) => fir::StringComponent::Lit(Rc::from(str.as_ref())),
         }
     }
 
@@ -886,13 +887,13 @@ impl Lowerer {
         fir::Ident {
             id: self.lower_local_id(ident.id),
             span: ident.span,
-            name: ident.name.clone(),
+            name: Rc::from(ident.name.as_ref()),
         }
     }
 
     fn lower_udt(&mut self, udt: &qsc_hir::ty::Udt) -> qsc_fir::ty::Udt {
         let span = udt.span;
-        let name = udt.name.clone();
+        let name = Rc::from(udt.name.as_ref());
         let definition = self.lower_udt_defn(&udt.definition);
         qsc_fir::ty::Udt {
             span,
@@ -956,7 +957,7 @@ impl Lowerer {
     fn lower_udt_field(&mut self, field: &qsc_hir::ty::UdtField) -> qsc_fir::ty::UdtField {
         qsc_fir::ty::UdtField {
             ty: self.lower_ty(&field.ty),
-            name: field.name.clone(),
+            name: field.name.as_deref().map(Rc::from),
             name_span: field.name_span,
         }
     }
@@ -977,7 +978,7 @@ impl Lowerer {
     ) -> qsc_fir::ty::TypeParameter {
         match g {
             qsc_hir::ty::TypeParameter::Ty { name, bounds } => qsc_fir::ty::TypeParameter::Ty {
-                name: name.clone(),
+                name: Rc::from(name.as_ref()),
                 bounds: self.lower_class_constraints(bounds),
             },
             qsc_hir::ty::TypeParameter::Functor(value) => {
@@ -1002,7 +1003,7 @@ impl Lowerer {
                 power: self.lower_ty(power),
             },
             HirClass::Add => FirClass::Add,
-            HirClass::NonNativeClass(name) => FirClass::NonNativeClass(name.clone()),
+            HirClass::NonNativeClass(name) => FirClass::NonNativeClass(Rc::from(name.as_ref())),
             HirClass::Iterable { item } => FirClass::Iterable {
                 item: self.lower_ty(item),
             },
@@ -1026,7 +1027,10 @@ fn lower_attrs(attrs: &[hir::Attr]) -> Vec<fir::Attr> {
             hir::Attr::Measurement => Some(fir::Attr::Measurement),
             hir::Attr::Reset => Some(fir::Attr::Reset),
             hir::Attr::Test => Some(fir::Attr::Test),
-            hir::Attr::SimulatableIntrinsic | hir::Attr::Unimplemented | hir::Attr::Config => None,
+            hir::Attr::SimulatableIntrinsic
+            | hir::Attr::Unimplemented
+            | hir::Attr::Config
+            | hir::Attr::Deprecated(_) => None,
         })
         .collect()
 }
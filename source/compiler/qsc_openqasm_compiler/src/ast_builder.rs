@@ -1,7 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use num_bigint::BigInt;
 
@@ -26,7 +26,7 @@ where
     S: AsRef<str>,
 {
     let alloc_ident = Ident {
-        name: Rc::from("__quantum__rt__qubit_allocate"),
+        name: Arc::from("__quantum__rt__qubit_allocate"),
         ..Default::default()
     };
     let path_expr = Expr {
@@ -56,7 +56,7 @@ where
                 kind: Box::new(PatKind::Bind(
                     Box::new(Ident {
                         span: name_span,
-                        name: Rc::from(name.as_ref()),
+                        name: Arc::from(name.as_ref()),
                         ..Default::default()
                     }),
                     None,
@@ -81,7 +81,7 @@ where
     S: AsRef<str>,
 {
     let alloc_ident = Ident {
-        name: Rc::from("AllocateQubitArray"),
+        name: Arc::from("AllocateQubitArray"),
         ..Default::default()
     };
 
@@ -121,7 +121,7 @@ where
                     Box::new(Ident {
                         id: NodeId::default(),
                         span: name_span,
-                        name: Rc::from(name.as_ref()),
+                        name: Arc::from(name.as_ref()),
                     }),
                     None,
                 )),
@@ -148,7 +148,7 @@ where
     let ident: Ident = Ident {
         id: NodeId::default(),
         span: name_span,
-        name: Rc::from(name.as_ref()),
+        name: Arc::from(name.as_ref()),
     };
     let qubit_kind = StmtKind::Qubit(
         QubitSource::Fresh,
@@ -190,7 +190,7 @@ where
 
     let ident: Ident = Ident {
         span: name_span,
-        name: Rc::from(name.as_ref()),
+        name: Arc::from(name.as_ref()),
         ..Default::default()
     };
     let qubit_kind = StmtKind::Qubit(
@@ -277,7 +277,7 @@ pub(crate) fn build_lit_int_expr(value: i64, span: Span) -> Expr {
 
 fn build_ident(name: &str) -> Ident {
     Ident {
-        name: Rc::from(name),
+        name: Arc::from(name),
         ..Default::default()
     }
 }
@@ -351,7 +351,7 @@ pub(crate) fn build_binary_expr(
 
 pub(crate) fn build_math_call_from_exprs(name: &str, exprs: Vec<Expr>, span: Span) -> Expr {
     let alloc_ident = Ident {
-        name: Rc::from(name),
+        name: Arc::from(name),
         ..Default::default()
     };
     let path_expr = Expr {
@@ -393,7 +393,7 @@ pub(crate) fn build_path_ident_expr<S: AsRef<str>>(
     let ident = ast::Ident {
         id: NodeId::default(),
         span: name_span,
-        name: Rc::from(name.as_ref()),
+        name: Arc::from(name.as_ref()),
     };
     let path = ast::Path {
         id: NodeId::default(),
@@ -427,7 +427,7 @@ pub(crate) fn build_assignment_statement(lhs: Expr, rhs: Expr, assignment_span:
 pub(crate) fn build_convert_call_expr(expr: Expr, name: &str) -> Expr {
     let span = expr.span;
     let cast_ident = Ident {
-        name: Rc::from(name),
+        name: Arc::from(name),
         ..Default::default()
     };
     let path_expr = Expr {
@@ -457,7 +457,7 @@ pub(crate) fn build_convert_call_expr(expr: Expr, name: &str) -> Expr {
 pub(crate) fn build_array_reverse_expr(expr: Expr) -> Expr {
     let span = expr.span;
     let cast_ident = Ident {
-        name: Rc::from("Reversed"),
+        name: Arc::from("Reversed"),
         ..Default::default()
     };
     let path_expr = Expr {
@@ -699,7 +699,7 @@ pub(crate) fn build_global_call_with_one_param<S: AsRef<str>>(
     let ident = ast::Ident {
         id: NodeId::default(),
         span: name_span,
-        name: Rc::from(name.as_ref()),
+        name: Arc::from(name.as_ref()),
     };
     let callee_expr = ast::Expr {
         id: NodeId::default(),
@@ -826,7 +826,7 @@ pub(crate) fn build_call_no_params(
 ) -> Expr {
     let segments = build_idents(idents);
     let fn_name = Ident {
-        name: Rc::from(name),
+        name: Arc::from(name),
         ..Default::default()
     };
     let path_expr = Expr {
@@ -875,7 +875,7 @@ pub(crate) fn build_call_with_param(
 ) -> Expr {
     let segments = build_idents(idents);
     let fn_name = Ident {
-        name: Rc::from(name),
+        name: Arc::from(name),
         span: name_span,
         ..Default::default()
     };
@@ -914,7 +914,7 @@ pub(crate) fn build_call_with_params(
 ) -> Expr {
     let segments = build_idents(idents);
     let fn_name = Ident {
-        name: Rc::from(name),
+        name: Arc::from(name),
         span: name_span,
         ..Default::default()
     };
@@ -1097,7 +1097,7 @@ pub(crate) fn build_implicit_return_stmt(output_expr: Expr) -> Stmt {
 
 pub(crate) fn build_path_ident_ty<S: AsRef<str>>(name: S) -> Ty {
     let ident = ast::Ident {
-        name: Rc::from(name.as_ref()),
+        name: Arc::from(name.as_ref()),
         ..Default::default()
     };
     let path = ast::PathKind::Ok(Box::new(ast::Path {
@@ -1148,7 +1148,7 @@ pub(crate) fn build_angle_ty_ident() -> Ty {
 
 pub(crate) fn build_complex_ty_ident() -> Ty {
     let ident = ast::Ident {
-        name: Rc::from("Complex"),
+        name: Arc::from("Complex"),
         ..Default::default()
     };
     let path = ast::PathKind::Ok(Box::new(ast::Path {
@@ -1173,7 +1173,7 @@ pub(crate) fn build_top_level_ns_with_items<S: AsRef<str>>(
         id: NodeId::default(),
         span: whole_span,
         name: [Ident {
-            name: Rc::from(ns.as_ref()),
+            name: Arc::from(ns.as_ref()),
             span: Span::default(),
             id: NodeId::default(),
         }]
@@ -1204,7 +1204,7 @@ pub(crate) fn build_operation_with_stmts<S: AsRef<str>>(
             id: NodeId::default(),
             span: Span::default(),
             name: Box::new(qsc_ast::ast::Ident {
-                name: Rc::from("EntryPoint"),
+                name: Arc::from("EntryPoint"),
                 ..Default::default()
             }),
             arg: Box::new(create_unit_expr(Span::default())),
@@ -1242,7 +1242,7 @@ pub(crate) fn build_operation_with_stmts<S: AsRef<str>>(
                 span: whole_span,
                 kind: qsc_ast::ast::CallableKind::Operation,
                 name: Box::new(qsc_ast::ast::Ident {
-                    name: Rc::from(name.as_ref()),
+                    name: Arc::from(name.as_ref()),
                     ..Default::default()
                 }),
                 generics: Box::new([]),
@@ -1265,7 +1265,7 @@ pub(crate) fn build_arg_pat(name: String, span: Span, ty: Ty) -> Pat {
     qsc_ast::ast::Pat {
         kind: Box::new(qsc_ast::ast::PatKind::Bind(
             Box::new(qsc_ast::ast::Ident {
-                name: Rc::from(name),
+                name: Arc::from(name),
                 span,
                 ..Default::default()
             }),
@@ -1658,7 +1658,7 @@ fn build_idents(idents: &[&str]) -> Option<Box<[Ident]>> {
     let idents = idents
         .iter()
         .map(|name| Ident {
-            name: Rc::from(*name),
+            name: Arc::from(*name),
             ..Default::default()
         })
         .collect::<Vec<_>>();
@@ -25,8 +25,8 @@ use crate::{
 use num_bigint::BigInt;
 use num_traits::Num;
 use qsc_ast::ast::{
-    self, BinOp, CallableKind, Expr, ExprKind, FieldAccess, FieldAssign, Functor, Lit, NodeId, Pat,
-    PatKind, PathKind, Pauli, StringComponent, TernOp, UnOp,
+    self, BinOp, CallableKind, Expr, ExprKind, FieldAccess, FieldAssign, Functor, Lit, MatchArm,
+    NodeId, Pat, PatKind, PathKind, Pauli, StringComponent, TernOp, UnOp,
 };
 use qsc_data_structures::{language_features::LanguageFeatures, span::Span};
 use std::{result, str::FromStr};
@@ -99,6 +99,7 @@ pub(super) fn is_stmt_final(kind: &ExprKind) -> bool {
             | ExprKind::Conjugate(..)
             | ExprKind::For(..)
             | ExprKind::If(..)
+            | ExprKind::Match(..)
             | ExprKind::Repeat(..)
             | ExprKind::While(..)
     )
@@ -213,6 +214,13 @@ fn expr_base(s: &mut ParserContext) -> Result<Box<Expr>> {
         Ok(Box::new(ExprKind::For(vars, iter, body)))
     } else if token(s, TokenKind::Keyword(Keyword::If)).is_ok() {
         expr_if(s)
+    } else if s.contains_language_feature(LanguageFeatures::MatchExpressions)
+        && s.peek().kind == TokenKind::Ident
+        && s.read() == "match"
+    {
+        // `match` is only reserved when match expressions are enabled, so it is not a keyword.
+        s.advance();
+        expr_match(s)
     } else if let Some(components) = opt(s, expr_interpolate)? {
         Ok(Box::new(ExprKind::Interpolate(
             components.into_boxed_slice(),
@@ -346,6 +354,77 @@ fn expr_if(s: &mut ParserContext) -> Result<Box<ExprKind>> {
     Ok(Box::new(ExprKind::If(cond, body, otherwise)))
 }
 
+/// A match expression excluding the `match` keyword,
+/// e.g. `a { Zero => b, One => c }`
+fn expr_match(s: &mut ParserContext) -> Result<Box<ExprKind>> {
+    let scrutinee = expr(s)?;
+    token(s, TokenKind::Open(Delim::Brace))?;
+    // Arms are parsed eagerly so that an invalid pattern is reported as such, rather than as a
+    // missing closing brace.
+    let mut arms = Vec::new();
+    while token(s, TokenKind::Close(Delim::Brace)).is_err() {
+        arms.push(parse_match_arm(s)?);
+        if token(s, TokenKind::Comma).is_err() {
+            token(s, TokenKind::Close(Delim::Brace))?;
+            break;
+        }
+    }
+    Ok(Box::new(ExprKind::Match(
+        scrutinee,
+        arms.into_boxed_slice(),
+    )))
+}
+
+fn parse_match_arm(s: &mut ParserContext) -> Result<Box<MatchArm>> {
+    let lo = s.peek().span.lo;
+    let pat = match_pat(s)?;
+    token(s, TokenKind::FatArrow)?;
+    let body = expr(s)?;
+    Ok(Box::new(MatchArm {
+        id: NodeId::default(),
+        span: s.span(lo),
+        pat,
+        body,
+    }))
+}
+
+/// A match pattern, which is a hole, `_`, or a literal with an optional sign.
+fn match_pat(s: &mut ParserContext) -> Result<Box<Expr>> {
+    let lo = s.peek().span.lo;
+    let kind = if token(s, TokenKind::Keyword(Keyword::Underscore)).is_ok() {
+        ExprKind::Hole
+    } else if token(s, TokenKind::ClosedBinOp(ClosedBinOp::Minus)).is_ok() {
+        let lit_lo = s.peek().span.lo;
+        let lit = match_pat_lit(s)?;
+        ExprKind::UnOp(
+            UnOp::Neg,
+            Box::new(Expr {
+                id: NodeId::default(),
+                span: s.span(lit_lo),
+                kind: Box::new(ExprKind::Lit(Box::new(lit))),
+            }),
+        )
+    } else {
+        ExprKind::Lit(Box::new(match_pat_lit(s)?))
+    };
+
+    Ok(Box::new(Expr {
+        id: NodeId::default(),
+        span: s.span(lo),
+        kind: Box::new(kind),
+    }))
+}
+
+fn match_pat_lit(s: &mut ParserContext) -> Result<Lit> {
+    lit(s)?.ok_or_else(|| {
+        Error::new(ErrorKind::Rule(
+            "match pattern",
+            s.peek().kind,
+            s.peek().span,
+        ))
+    })
+}
+
 fn expr_array(s: &mut ParserContext) -> Result<Box<ExprKind>> {
     token(s, TokenKind::Open(Delim::Bracket))?;
     let kind = expr_array_core(s)?;
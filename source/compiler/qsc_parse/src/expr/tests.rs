@@ -2,7 +2,7 @@
 // Licensed under the MIT License.
 
 use super::expr;
-use crate::tests::check;
+use crate::tests::{check, check_match_expressions};
 use expect_test::expect;
 
 #[test]
@@ -2858,3 +2858,68 @@ fn parenthesized_expr_in_for_is_valid() {
                     Stmt _id_ [16-18]: Expr: Expr _id_ [16-18]: Unit"#]],
     );
 }
+
+#[test]
+fn match_expr() {
+    check_match_expressions(
+        expr,
+        "match r { Zero => 0, _ => -1 }",
+        &expect![[r#"
+            Expr _id_ [0-30]: Match:
+                Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "r")
+                MatchArm _id_ [10-19]:
+                    Expr _id_ [10-14]: Lit: Result(Zero)
+                    Expr _id_ [18-19]: Lit: Int(0)
+                MatchArm _id_ [21-28]:
+                    Expr _id_ [21-22]: Hole
+                    Expr _id_ [26-28]: UnOp (Neg):
+                        Expr _id_ [27-28]: Lit: Int(1)"#]],
+    );
+}
+
+#[test]
+fn match_expr_negative_pattern() {
+    check_match_expressions(
+        expr,
+        "match i { -1 => PauliX, _ => PauliI }",
+        &expect![[r#"
+            Expr _id_ [0-37]: Match:
+                Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "i")
+                MatchArm _id_ [10-22]:
+                    Expr _id_ [10-12]: UnOp (Neg):
+                        Expr _id_ [11-12]: Lit: Int(1)
+                    Expr _id_ [16-22]: Lit: Pauli(X)
+                MatchArm _id_ [24-35]:
+                    Expr _id_ [24-25]: Hole
+                    Expr _id_ [29-35]: Lit: Pauli(I)"#]],
+    );
+}
+
+#[test]
+fn match_expr_invalid_pattern() {
+    check_match_expressions(
+        expr,
+        "match r { x => 0 }",
+        &expect![[r#"
+            Error(
+                Rule(
+                    "match pattern",
+                    Ident,
+                    Span {
+                        lo: 10,
+                        hi: 11,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn match_is_ident_without_feature() {
+    check(
+        expr,
+        "match r { _ => 0 }",
+        &expect![[r#"Expr _id_ [0-5]: Path: Path _id_ [0-5] (Ident _id_ [0-5] "match")"#]],
+    );
+}
@@ -7,7 +7,7 @@
 #[cfg(test)]
 mod tests;
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::{
     Error, Result,
@@ -31,10 +31,10 @@ use crate::{
     ty::array_or_arrow,
 };
 use qsc_ast::ast::{
-    Attr, Block, CallableBody, CallableDecl, CallableKind, FieldDef, FunctorExpr, Ident, Idents,
-    ImportKind, ImportOrExportDecl, ImportOrExportItem, Item, ItemKind, Namespace, NodeId, Pat,
-    PatKind, Path, PathKind, Spec, SpecBody, SpecDecl, SpecGen, Stmt, StmtKind, StructDecl,
-    TopLevelNode, Ty, TyDef, TyDefKind, TyKind,
+    Attr, Block, CallableBody, CallableDecl, CallableKind, Expr, ExprKind, FieldDef, FunctorExpr,
+    Ident, Idents, ImportKind, ImportOrExportDecl, ImportOrExportItem, Item, ItemKind, Namespace,
+    NodeId, Pat, PatKind, Path, PathKind, Spec, SpecBody, SpecDecl, SpecGen, Stmt, StmtKind,
+    StructDecl, TopLevelNode, Ty, TyDef, TyDefKind, TyKind,
 };
 use qsc_data_structures::language_features::LanguageFeatures;
 use qsc_data_structures::span::Span;
@@ -168,7 +168,7 @@ pub fn parse_namespaces_or_implicit(
 ) -> Result<Vec<Namespace>> {
     let lo = s.peek().span.lo;
     let doc = parse_doc(s);
-    let doc = Rc::from(doc.unwrap_or_default());
+    let doc = Arc::from(doc.unwrap_or_default());
     s.expect(WordKinds::Namespace);
     #[allow(clippy::unnecessary_unwrap)]
     if source_name.is_some() && s.peek().kind != TokenKind::Keyword(Keyword::Namespace) {
@@ -196,7 +196,11 @@ pub fn parse_namespaces_or_implicit(
 
 pub fn parse_implicit_namespace(source_name: &str, s: &mut ParserContext) -> Result<Namespace> {
     let lo = s.peek().span.lo;
-    let items = parse_namespace_block_contents(s)?;
+    let items = if s.contains_language_feature(LanguageFeatures::ScriptMode) {
+        parse_script_contents(s)?
+    } else {
+        parse_namespace_block_contents(s)?
+    };
     recovering_token(s, TokenKind::Eof);
 
     let span = s.span(lo);
@@ -302,6 +306,110 @@ fn parse_namespace_block_contents(s: &mut ParserContext) -> Result<Vec<Box<Item>
     Ok(items)
 }
 
+/// Parses the contents of a script, where items can be mixed with top level statements. The
+/// statements are gathered, in order, into the body of a synthesized operation marked as the entry
+/// point. The operation is named `Main`, or `Main` followed by a number if the script already
+/// declares an item with that name. When the last statement is an expression, the return type is
+/// an empty hole that the type checker infers from the body; otherwise it is `Unit`.
+#[allow(clippy::vec_box)]
+fn parse_script_contents(s: &mut ParserContext) -> Result<Vec<Box<Item>>> {
+    let stmts = stmt::parse_many(s)?;
+    let (items, stmts): (Vec<_>, Vec<_>) = stmts
+        .into_iter()
+        .partition(|stmt| matches!(&*stmt.kind, StmtKind::Item(_)));
+    let mut items = items
+        .into_iter()
+        .filter_map(|stmt| match *stmt.kind {
+            StmtKind::Item(item) => Some(item),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if let (Some(first), Some(last)) = (stmts.first(), stmts.last()) {
+        check_semis(s, &stmts);
+        let span = Span {
+            lo: first.span.lo,
+            hi: last.span.hi,
+        };
+        let name_span = Span {
+            lo: span.lo,
+            hi: span.lo,
+        };
+        let output = if matches!(&*last.kind, StmtKind::Expr(_)) {
+            TyKind::Hole
+        } else {
+            TyKind::Tuple(Vec::new().into_boxed_slice())
+        };
+        let name = script_main_name(&items);
+        let entry_point = Attr {
+            id: NodeId::default(),
+            span: name_span,
+            name: Box::new(Ident {
+                id: NodeId::default(),
+                span: name_span,
+                name: "EntryPoint".into(),
+            }),
+            arg: Box::new(Expr {
+                id: NodeId::default(),
+                span: name_span,
+                kind: Box::new(ExprKind::Tuple(Vec::new().into_boxed_slice())),
+            }),
+        };
+        items.push(Box::new(Item {
+            id: NodeId::default(),
+            span,
+            doc: "".into(),
+            attrs: vec![Box::new(entry_point)].into_boxed_slice(),
+            kind: Box::new(ItemKind::Callable(Box::new(CallableDecl {
+                id: NodeId::default(),
+                span,
+                kind: CallableKind::Operation,
+                name: Box::new(Ident {
+                    id: NodeId::default(),
+                    span: name_span,
+                    name,
+                }),
+                generics: Vec::new().into_boxed_slice(),
+                input: Box::new(Pat {
+                    id: NodeId::default(),
+                    span: name_span,
+                    kind: Box::new(PatKind::Tuple(Vec::new().into_boxed_slice())),
+                }),
+                output: Box::new(Ty {
+                    id: NodeId::default(),
+                    span: name_span,
+                    kind: Box::new(output),
+                }),
+                functors: None,
+                body: Box::new(CallableBody::Block(Box::new(Block {
+                    id: NodeId::default(),
+                    span,
+                    stmts: stmts.into_boxed_slice(),
+                }))),
+            }))),
+        }));
+    }
+
+    Ok(items)
+}
+
+/// Returns a name for the entry point of a script that no item of the script already uses.
+fn script_main_name(items: &[Box<Item>]) -> Arc<str> {
+    let is_used = |name: &str| {
+        items.iter().any(|item| match &*item.kind {
+            ItemKind::Callable(decl) => decl.name.name.as_ref() == name,
+            ItemKind::Ty(ident, _) => ident.name.as_ref() == name,
+            ItemKind::Struct(decl) => decl.name.name.as_ref() == name,
+            _ => false,
+        })
+    };
+    std::iter::once("Main".to_string())
+        .chain((1..).map(|n| format!("Main{n}")))
+        .find(|name| !is_used(name))
+        .expect("an unused name should be found")
+        .into()
+}
+
 /// See [GH Issue 941](https://github.com/microsoft/qsharp/issues/941) for context.
 /// We want to anticipate docstrings in places people might
 /// put them, but throw them away. This is to maintain
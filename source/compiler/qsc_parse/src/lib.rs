@@ -194,6 +194,10 @@ trait Parser<T>: FnMut(&mut ParserContext) -> Result<T> {}
 impl<T, F: FnMut(&mut ParserContext) -> Result<T>> Parser<T> for F {}
 
 #[must_use]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(source = source_name))
+)]
 pub fn namespaces(
     input: &str,
     source_name: Option<&str>,
@@ -213,6 +217,7 @@ pub fn namespaces(
 }
 
 #[must_use]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
 pub fn top_level_nodes(
     input: &str,
     language_features: LanguageFeatures,
@@ -229,6 +234,7 @@ pub fn top_level_nodes(
 }
 
 #[must_use]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
 pub fn expr(input: &str, language_features: LanguageFeatures) -> (Box<Expr>, Vec<Error>) {
     let mut scanner = ParserContext::new(input, language_features);
     match expr::expr_eof(&mut scanner) {
@@ -171,6 +171,15 @@ impl<'a> Scanner<'a> {
         if self.peek.kind != TokenKind::Eof {
             self.offset = self.peek.span.hi;
             let (peek, errors) = next_ok(&mut self.tokens);
+            #[cfg(feature = "tracing")]
+            if let Some(token) = &peek {
+                tracing::trace!(
+                    kind = %token.kind,
+                    lo = token.span.lo,
+                    hi = token.span.hi,
+                    "lexed token"
+                );
+            }
             self.errors
                 .extend(errors.into_iter().map(|e| Error::new(ErrorKind::Lex(e))));
             self.peek = peek.unwrap_or_else(|| eof(self.input.len()));
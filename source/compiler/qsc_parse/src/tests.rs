@@ -7,6 +7,7 @@ use expect_test::Expect;
 use qsc_data_structures::language_features::LanguageFeatures;
 use std::fmt::Display;
 
+mod corpus;
 mod implicit_namespace;
 
 pub(super) fn check<T: Display>(parser: impl Parser<T>, input: &str, expect: &Expect) {
@@ -62,14 +63,39 @@ pub(super) fn check_seq<T: Display>(
     });
 }
 
+/// This function is the same as `check`, but it uses the match expressions language feature.
+pub(super) fn check_match_expressions<T: Display>(
+    parser: impl Parser<T>,
+    input: &str,
+    expect: &Expect,
+) {
+    check_map_with_features(
+        parser,
+        input,
+        LanguageFeatures::MatchExpressions,
+        expect,
+        ToString::to_string,
+    );
+}
+
 /// This function is the same as `check_map`, but it uses the v2 preview syntax language feature.
 fn check_map_v2_preview<T>(
+    parser: impl Parser<T>,
+    input: &str,
+    expect: &Expect,
+    f: impl FnOnce(&T) -> String,
+) {
+    check_map_with_features(parser, input, LanguageFeatures::V2PreviewSyntax, expect, f);
+}
+
+fn check_map_with_features<T>(
     mut parser: impl Parser<T>,
     input: &str,
+    features: LanguageFeatures,
     expect: &Expect,
     f: impl FnOnce(&T) -> String,
 ) {
-    let mut scanner = ParserContext::new(input, LanguageFeatures::V2PreviewSyntax);
+    let mut scanner = ParserContext::new(input, features);
     let result = parser(&mut scanner);
     let errors = scanner.into_errors();
     match result {
@@ -0,0 +1,120 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Whole-program parser tests over the `.qs` files in the `corpus` directory. Every file is parsed
+//! and its AST and diagnostics are compared against the `.snap` file next to it. Files under
+//! `corpus/valid` must parse without errors, while files under `corpus/errors` must report at
+//! least one. After adding a file, run the tests with `UPDATE_EXPECT=1` to create its snapshot.
+//!
+//! The samples and katas in the repository are parsed as well. There are too many of them to keep
+//! snapshots for, so they are only checked for parsing without errors.
+
+use expect_test::expect_file;
+use qsc_data_structures::language_features::LanguageFeatures;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn corpus_files(kind: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/corpus")
+        .join(kind);
+    let mut files = fs::read_dir(&dir)
+        .expect("corpus directory should be readable")
+        .map(|entry| entry.expect("corpus entry should be readable").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "qs"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+fn repo_files(dir: &str) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_qs_files(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../..")
+            .join(dir),
+        &mut files,
+    );
+    files.sort();
+    files
+}
+
+fn collect_qs_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).expect("directory should be readable") {
+        let path = entry.expect("directory entry should be readable").path();
+        if path.is_dir() {
+            collect_qs_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "qs") {
+            files.push(path);
+        }
+    }
+}
+
+fn check_parses(dir: &str) {
+    let files = repo_files(dir);
+    assert!(!files.is_empty(), "`{dir}` should contain .qs files");
+
+    for path in files {
+        let input = fs::read_to_string(&path).expect("source file should be readable");
+        let name = path.to_string_lossy();
+        let (_, errors) = crate::namespaces(&input, Some(&name), LanguageFeatures::default());
+        assert!(
+            errors.is_empty(),
+            "unexpected parse errors for {}: {errors:#?}",
+            path.display()
+        );
+    }
+}
+
+fn check_corpus(kind: &str, expect_errors: bool) {
+    let files = corpus_files(kind);
+    assert!(
+        !files.is_empty(),
+        "corpus directory `{kind}` should contain .qs files"
+    );
+
+    for path in files {
+        let input = fs::read_to_string(&path).expect("corpus file should be readable");
+        let (namespaces, errors) = crate::namespaces(&input, None, LanguageFeatures::default());
+        assert_eq!(
+            !errors.is_empty(),
+            expect_errors,
+            "unexpected parse result for {}: {errors:#?}",
+            path.display()
+        );
+
+        let ast = namespaces
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let actual = if errors.is_empty() {
+            ast
+        } else {
+            format!("{ast}\n\n{errors:#?}")
+        };
+        expect_file![path.with_extension("snap")].assert_eq(&actual);
+    }
+}
+
+#[test]
+fn valid_corpus_parses_without_errors() {
+    check_corpus("valid", false);
+}
+
+#[test]
+fn error_corpus_reports_errors() {
+    check_corpus("errors", true);
+}
+
+#[test]
+fn samples_parse_without_errors() {
+    check_parses("samples");
+}
+
+#[test]
+fn katas_parse_without_errors() {
+    check_parses("katas");
+}
@@ -4,6 +4,8 @@
 #![allow(clippy::too_many_lines)]
 
 use expect_test::expect;
+use indoc::indoc;
+use qsc_ast::ast::{CallableBody, CallableKind, ItemKind};
 use qsc_data_structures::language_features::LanguageFeatures;
 
 #[test]
@@ -398,3 +400,106 @@ oper",
         )"#]]
     .assert_eq(&result);
 }
+
+fn callable_names(namespace: &qsc_ast::ast::Namespace) -> Vec<&str> {
+    namespace
+        .items
+        .iter()
+        .filter_map(|item| match &*item.kind {
+            ItemKind::Callable(decl) => Some(decl.name.name.as_ref()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn script_mode_wraps_top_level_statements_in_main() {
+    let (namespaces, errors) = crate::namespaces(
+        indoc! {r#"
+            function Helper() : Int { 1 }
+            let x = Helper();
+            Message($"{x}");
+        "#},
+        Some("code/src/Script.qs"),
+        LanguageFeatures::ScriptMode,
+    );
+    assert!(errors.is_empty(), "{errors:#?}");
+    assert_eq!(namespaces.len(), 1);
+    assert_eq!(callable_names(&namespaces[0]), ["Helper", "Main"]);
+
+    let ItemKind::Callable(main) = &*namespaces[0].items[1].kind else {
+        panic!("expected `Main` to be a callable");
+    };
+    assert_eq!(main.kind, CallableKind::Operation);
+    let CallableBody::Block(block) = &*main.body else {
+        panic!("expected `Main` to have a block body");
+    };
+    assert_eq!(block.stmts.len(), 2);
+}
+
+#[test]
+fn script_mode_without_statements_has_no_main() {
+    let (namespaces, errors) = crate::namespaces(
+        "function Helper() : Int { 1 }",
+        Some("code/src/Script.qs"),
+        LanguageFeatures::ScriptMode,
+    );
+    assert!(errors.is_empty(), "{errors:#?}");
+    assert_eq!(callable_names(&namespaces[0]), ["Helper"]);
+}
+
+#[test]
+fn script_mode_requires_semicolons_between_statements() {
+    let (_, errors) = crate::namespaces(
+        "let x = 1;\nx\nx",
+        Some("code/src/Script.qs"),
+        LanguageFeatures::ScriptMode,
+    );
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+}
+
+#[test]
+fn top_level_statements_rejected_without_script_mode() {
+    let (_, errors) = crate::namespaces(
+        "let x = 1;",
+        Some("code/src/Script.qs"),
+        LanguageFeatures::default(),
+    );
+    assert!(!errors.is_empty());
+}
+
+#[test]
+fn script_mode_main_does_not_clash_with_items() {
+    let (namespaces, errors) = crate::namespaces(
+        indoc! {"
+            operation Main() : Unit {}
+            function Main1() : Unit {}
+            Main();
+        "},
+        Some("code/src/Script.qs"),
+        LanguageFeatures::ScriptMode,
+    );
+    assert!(errors.is_empty(), "{errors:#?}");
+    assert_eq!(callable_names(&namespaces[0]), ["Main", "Main1", "Main2"]);
+    assert_eq!(
+        namespaces[0].items[2].attrs[0].name.name.as_ref(),
+        "EntryPoint"
+    );
+}
+
+#[test]
+fn script_mode_main_infers_output_from_trailing_expression() {
+    let output_kinds = ["let x = 1;\nx", "let x = 1;"].map(|source| {
+        let (namespaces, errors) = crate::namespaces(
+            source,
+            Some("code/src/Script.qs"),
+            LanguageFeatures::ScriptMode,
+        );
+        assert!(errors.is_empty(), "{errors:#?}");
+        let ItemKind::Callable(main) = &*namespaces[0].items[0].kind else {
+            panic!("expected `Main` to be a callable");
+        };
+        main.output.kind.to_string()
+    });
+    assert_eq!(output_kinds, ["Hole", "Unit"]);
+}
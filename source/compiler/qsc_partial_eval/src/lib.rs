@@ -20,7 +20,10 @@ use qsc_eval::{
     self, Error as EvalError, ErrorBehavior, PackageSpan, State, StepAction, StepResult, Variable,
     are_ctls_unique,
     backend::TracingBackend,
-    intrinsic::qubit_relabel,
+    intrinsic::{
+        chunks, counts, difference, distinct, grouped_by_key, intersection, math_intrinsic,
+        qubit_relabel, string_intrinsic, union, zipped,
+    },
     output::GenericReceiver,
     resolve_closure,
     val::{
@@ -309,7 +312,7 @@ impl<'a> PartialEvaluator<'a> {
     fn bind_value_to_immutable_ident(&mut self, ident: &Ident, value: Value) {
         // If the value is not a variable, bind it to the classical map.
         if !matches!(value, Value::Var(_)) {
-            self.bind_value_in_classical_map(ident, &value);
+            self.bind_value_in_classical_map(ident, &value, Mutability::Immutable);
         }
 
         // Always bind the value to the hybrid map.
@@ -319,7 +322,7 @@ impl<'a> PartialEvaluator<'a> {
     fn bind_value_to_mutable_ident(&mut self, ident: &Ident, value: Value) {
         // If the value is not a variable, bind it to the classical map.
         if !matches!(value, Value::Var(_)) {
-            self.bind_value_in_classical_map(ident, &value);
+            self.bind_value_in_classical_map(ident, &value, Mutability::Mutable);
         }
 
         // Always bind the value to the hybrid map but do it differently depending of the value type.
@@ -335,12 +338,18 @@ impl<'a> PartialEvaluator<'a> {
         }
     }
 
-    fn bind_value_in_classical_map(&mut self, ident: &Ident, value: &Value) {
+    fn bind_value_in_classical_map(
+        &mut self,
+        ident: &Ident,
+        value: &Value,
+        mutability: Mutability,
+    ) {
         // Create a variable and bind it to the classical environment.
         let var = Variable {
             name: ident.name.clone(),
             value: value.clone(),
             span: ident.span,
+            mutability,
         };
         let scope = self.eval_context.get_current_scope_mut();
         scope.env.bind_variable_in_top_frame(ident.id, var);
@@ -1661,6 +1670,51 @@ impl<'a> PartialEvaluator<'a> {
                 })
             }
             .map_err(std::convert::Into::into),
+            // Array intrinsics only rearrange elements, so they also apply to arrays holding dynamic values.
+            "Zipped" => Ok(zipped(args_value)),
+            "Chunks" => chunks(&callable_decl.name.name, args_value, args_span)
+                .map_err(std::convert::Into::into),
+            // Collection intrinsics hash their elements, so they fail on dynamic values.
+            "Distinct" => distinct(&callable_decl.name.name, args_value, args_span)
+                .map_err(std::convert::Into::into),
+            "Counts" => counts(&callable_decl.name.name, args_value, args_span)
+                .map_err(std::convert::Into::into),
+            "GroupedByKey" => grouped_by_key(&callable_decl.name.name, args_value, args_span)
+                .map_err(std::convert::Into::into),
+            "Union" => union(&callable_decl.name.name, args_value, args_span)
+                .map_err(std::convert::Into::into),
+            "Intersection" => intersection(&callable_decl.name.name, args_value, args_span)
+                .map_err(std::convert::Into::into),
+            "Difference" => difference(&callable_decl.name.name, args_value, args_span)
+                .map_err(std::convert::Into::into),
+            "StringLength" | "Substring" | "Split" | "Contains" | "Uppercased" | "Lowercased"
+            | "TryStringAsInt" | "TryStringAsDouble" => {
+                string_intrinsic(&callable_decl.name.name, args_value, args_span)
+                    .map_err(std::convert::Into::into)
+            }
+            // Integer arithmetic intrinsics are evaluated at compile time, so their arguments must be static.
+            "ExpModI"
+            | "ExpModL"
+            | "ExtendedGreatestCommonDivisorI"
+            | "ExtendedGreatestCommonDivisorL"
+            | "InverseModI"
+            | "InverseModL"
+            | "FactorialL"
+            | "BinomL" => {
+                let is_dynamic = |value: &Value| matches!(value, Value::Var(_));
+                let has_dynamic_arg = match &args_value {
+                    Value::Tuple(args, _) => args.iter().any(is_dynamic),
+                    arg => is_dynamic(arg),
+                };
+                if has_dynamic_arg {
+                    return Err(Error::Unimplemented(
+                        format!("`{}` with dynamic arguments", callable_decl.name.name),
+                        callee_expr_span,
+                    ));
+                }
+                math_intrinsic(&callable_decl.name.name, args_value, args_span)
+                    .map_err(std::convert::Into::into)
+            }
             "__quantum__qis__m__body" => Ok(self.measure_qubit(builder::m_decl(), args_value)),
             "__quantum__qis__mresetz__body" => {
                 Ok(self.measure_qubit(builder::mresetz_decl(), args_value))
@@ -1668,6 +1722,7 @@ impl<'a> PartialEvaluator<'a> {
             // The following intrinsic operations and functions are no-ops.
             "BeginEstimateCaching" => Ok(Value::Bool(true)),
             "DumpRegister"
+            | "DumpBlochVector"
             | "DumpOperation"
             | "AccountForEstimatesInternal"
             | "BeginRepeatEstimatesInternal"
@@ -1675,8 +1730,16 @@ impl<'a> PartialEvaluator<'a> {
             | "EnableMemoryComputeArchitecture"
             | "ApplyIdleNoise"
             | "GlobalPhase" => Ok(Value::unit()),
-            "CheckZero" => Err(Error::UnsupportedSimulationIntrinsic(
-                "CheckZero".to_string(),
+            "CheckZero"
+            | "PauliExpectation"
+            | "CheckSeparable"
+            | "EntanglementEntropy"
+            | "RegisterAmplitudes"
+            | "ReadTextFile"
+            | "WriteTextFile"
+            | "CommandLineArguments"
+            | "TryEnvironmentVariable" => Err(Error::UnsupportedSimulationIntrinsic(
+                callable_decl.name.name.to_string(),
                 callee_expr_span,
             )),
             // The following intrinsic functions and operations should never make it past conditional compilation and
@@ -2879,6 +2942,7 @@ impl<'a> PartialEvaluator<'a> {
                         name: ident.name.clone(),
                         value: ctls_value,
                         span: ident.span,
+                        mutability: Mutability::Immutable,
                     };
                     let ctl_arg = Arg::Var(ident.id, variable);
                     Some(ctl_arg)
@@ -2905,6 +2969,7 @@ impl<'a> PartialEvaluator<'a> {
                     name: ident.name.clone(),
                     value,
                     span: ident.span,
+                    mutability: Mutability::Immutable,
                 };
                 vec![Arg::Var(ident.id, variable)]
             }
@@ -3581,26 +3646,51 @@ fn eval_bin_op_with_integer_literals(
     rhs_literal: Literal,
     bin_op_expr_span: PackageSpan, // For diagnostic purposes only
 ) -> Result<Value, Error> {
+    // Arithmetic matches the simulator: results wrap on overflow, while invalid operands are
+    // reported as errors rather than panics.
     fn eval_integer_div(lhs_int: i64, rhs_int: i64, span: PackageSpan) -> Result<Value, Error> {
         match (lhs_int, rhs_int) {
             (_, 0) => Err(EvalError::DivZero(span).into()),
-            (lhs, rhs) => Ok(Value::Int(lhs / rhs)),
+            (lhs, rhs) => Ok(Value::Int(lhs.wrapping_div(rhs))),
         }
     }
 
     fn eval_integer_mod(lhs_int: i64, rhs_int: i64, span: PackageSpan) -> Result<Value, Error> {
         match (lhs_int, rhs_int) {
             (_, 0) => Err(EvalError::DivZero(span).into()),
-            (lhs, rhs) => Ok(Value::Int(lhs % rhs)),
+            (lhs, rhs) => Ok(Value::Int(lhs.wrapping_rem(rhs))),
         }
     }
 
     fn eval_integer_exp(lhs_int: i64, rhs_int: i64, span: PackageSpan) -> Result<Value, Error> {
+        if rhs_int < 0 {
+            return Err(EvalError::InvalidNegativeInt(rhs_int, span).into());
+        }
         let Ok(rhs_int_as_u32) = u32::try_from(rhs_int) else {
             return Err(EvalError::IntTooLarge(rhs_int, span).into());
         };
 
-        Ok(Value::Int(lhs_int.pow(rhs_int_as_u32)))
+        lhs_int
+            .checked_pow(rhs_int_as_u32)
+            .map(Value::Int)
+            .ok_or_else(|| EvalError::IntTooLarge(rhs_int, span).into())
+    }
+
+    fn eval_integer_shift(
+        lhs_int: i64,
+        rhs_int: i64,
+        left: bool,
+        span: PackageSpan,
+    ) -> Result<Value, Error> {
+        let too_large = || Error::from(EvalError::IntTooLarge(rhs_int, span));
+        let shift = u32::try_from(rhs_int.unsigned_abs()).map_err(|_| too_large())?;
+        // A negative shift amount shifts in the opposite direction.
+        let shifted = if left == (rhs_int > 0) {
+            lhs_int.checked_shl(shift)
+        } else {
+            lhs_int.checked_shr(shift)
+        };
+        shifted.map(Value::Int).ok_or_else(too_large)
     }
 
     // Validate that both literals are integers.
@@ -3615,17 +3705,17 @@ fn eval_bin_op_with_integer_literals(
         BinOp::Gte => Ok(Value::Bool(lhs_int >= rhs_int)),
         BinOp::Lt => Ok(Value::Bool(lhs_int < rhs_int)),
         BinOp::Lte => Ok(Value::Bool(lhs_int <= rhs_int)),
-        BinOp::Add => Ok(Value::Int(lhs_int + rhs_int)),
-        BinOp::Sub => Ok(Value::Int(lhs_int - rhs_int)),
-        BinOp::Mul => Ok(Value::Int(lhs_int * rhs_int)),
+        BinOp::Add => Ok(Value::Int(lhs_int.wrapping_add(rhs_int))),
+        BinOp::Sub => Ok(Value::Int(lhs_int.wrapping_sub(rhs_int))),
+        BinOp::Mul => Ok(Value::Int(lhs_int.wrapping_mul(rhs_int))),
         BinOp::Div => eval_integer_div(lhs_int, rhs_int, bin_op_expr_span),
         BinOp::Mod => eval_integer_mod(lhs_int, rhs_int, bin_op_expr_span),
         BinOp::Exp => eval_integer_exp(lhs_int, rhs_int, bin_op_expr_span),
         BinOp::AndB => Ok(Value::Int(lhs_int & rhs_int)),
         BinOp::OrB => Ok(Value::Int(lhs_int | rhs_int)),
         BinOp::XorB => Ok(Value::Int(lhs_int ^ rhs_int)),
-        BinOp::Shl => Ok(Value::Int(lhs_int << rhs_int)),
-        BinOp::Shr => Ok(Value::Int(lhs_int >> rhs_int)),
+        BinOp::Shl => eval_integer_shift(lhs_int, rhs_int, true, bin_op_expr_span),
+        BinOp::Shr => eval_integer_shift(lhs_int, rhs_int, false, bin_op_expr_span),
         _ => panic!("invalid integer operator: {bin_op:?}"),
     }
 }
@@ -8,6 +8,7 @@ mod branching;
 mod calls;
 mod classical_args;
 mod dynamic_vars;
+mod integer_literals;
 mod intrinsics;
 mod loops;
 mod misc;
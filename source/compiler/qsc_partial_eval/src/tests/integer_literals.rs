@@ -0,0 +1,155 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::eval_bin_op_with_integer_literals;
+use expect_test::{Expect, expect};
+use qsc_data_structures::span::Span;
+use qsc_eval::PackageSpan;
+use qsc_fir::fir::{BinOp, PackageId};
+use qsc_rir::rir::Literal;
+
+fn check(bin_op: BinOp, lhs: i64, rhs: i64, expect: &Expect) {
+    let span = PackageSpan {
+        package: PackageId::from(2),
+        span: Span { lo: 0, hi: 1 },
+    };
+    let result = eval_bin_op_with_integer_literals(
+        bin_op,
+        Literal::Integer(lhs),
+        Literal::Integer(rhs),
+        span,
+    );
+    expect.assert_eq(&format!("{result:?}"));
+}
+
+#[test]
+fn integer_add_of_literals_wraps_on_overflow() {
+    check(
+        BinOp::Add,
+        i64::MAX,
+        1,
+        &expect!["Ok(Int(-9223372036854775808))"],
+    );
+    check(BinOp::Add, -3, -4, &expect!["Ok(Int(-7))"]);
+    check(BinOp::Add, 5, 0, &expect!["Ok(Int(5))"]);
+}
+
+#[test]
+fn integer_sub_of_literals_wraps_on_overflow() {
+    check(
+        BinOp::Sub,
+        i64::MIN,
+        1,
+        &expect!["Ok(Int(9223372036854775807))"],
+    );
+    check(BinOp::Sub, -3, 4, &expect!["Ok(Int(-7))"]);
+    check(BinOp::Sub, 0, 5, &expect!["Ok(Int(-5))"]);
+}
+
+#[test]
+fn integer_mul_of_literals_wraps_on_overflow() {
+    check(BinOp::Mul, i64::MAX, 2, &expect!["Ok(Int(-2))"]);
+    check(BinOp::Mul, -3, 4, &expect!["Ok(Int(-12))"]);
+    check(BinOp::Mul, i64::MAX, 0, &expect!["Ok(Int(0))"]);
+}
+
+#[test]
+fn integer_div_of_literals_wraps_on_overflow_and_raises_error_on_zero_divisor() {
+    check(
+        BinOp::Div,
+        i64::MIN,
+        -1,
+        &expect!["Ok(Int(-9223372036854775808))"],
+    );
+    check(BinOp::Div, -7, 2, &expect!["Ok(Int(-3))"]);
+    check(
+        BinOp::Div,
+        7,
+        0,
+        &expect![[
+            r#"Err(EvaluationFailed("division by zero", PackageSpan { package: PackageId(2), span: Span { lo: 0, hi: 1 } }))"#
+        ]],
+    );
+}
+
+#[test]
+fn integer_mod_of_literals_wraps_on_overflow_and_raises_error_on_zero_divisor() {
+    check(BinOp::Mod, i64::MIN, -1, &expect!["Ok(Int(0))"]);
+    check(BinOp::Mod, -7, 2, &expect!["Ok(Int(-1))"]);
+    check(
+        BinOp::Mod,
+        7,
+        0,
+        &expect![[
+            r#"Err(EvaluationFailed("division by zero", PackageSpan { package: PackageId(2), span: Span { lo: 0, hi: 1 } }))"#
+        ]],
+    );
+}
+
+#[test]
+fn integer_exp_of_literals_raises_error_on_overflow_and_negative_exponent() {
+    check(
+        BinOp::Exp,
+        2,
+        63,
+        &expect![[
+            r#"Err(EvaluationFailed("integer too large for operation", PackageSpan { package: PackageId(2), span: Span { lo: 0, hi: 1 } }))"#
+        ]],
+    );
+    check(
+        BinOp::Exp,
+        2,
+        1 << 32,
+        &expect![[
+            r#"Err(EvaluationFailed("integer too large for operation", PackageSpan { package: PackageId(2), span: Span { lo: 0, hi: 1 } }))"#
+        ]],
+    );
+    check(
+        BinOp::Exp,
+        2,
+        -1,
+        &expect![[
+            r#"Err(EvaluationFailed("negative integers cannot be used here: -1", PackageSpan { package: PackageId(2), span: Span { lo: 0, hi: 1 } }))"#
+        ]],
+    );
+    check(BinOp::Exp, -2, 3, &expect!["Ok(Int(-8))"]);
+    check(BinOp::Exp, 0, 0, &expect!["Ok(Int(1))"]);
+}
+
+#[test]
+fn integer_shl_of_literals_raises_error_on_overflow_and_shifts_right_by_negative_amount() {
+    check(
+        BinOp::Shl,
+        1,
+        64,
+        &expect![[
+            r#"Err(EvaluationFailed("integer too large for operation", PackageSpan { package: PackageId(2), span: Span { lo: 0, hi: 1 } }))"#
+        ]],
+    );
+    check(
+        BinOp::Shl,
+        1,
+        i64::MIN,
+        &expect![[
+            r#"Err(EvaluationFailed("integer too large for operation", PackageSpan { package: PackageId(2), span: Span { lo: 0, hi: 1 } }))"#
+        ]],
+    );
+    check(BinOp::Shl, -8, 1, &expect!["Ok(Int(-16))"]);
+    check(BinOp::Shl, 8, -1, &expect!["Ok(Int(4))"]);
+    check(BinOp::Shl, 5, 0, &expect!["Ok(Int(5))"]);
+}
+
+#[test]
+fn integer_shr_of_literals_raises_error_on_overflow_and_shifts_left_by_negative_amount() {
+    check(
+        BinOp::Shr,
+        1,
+        64,
+        &expect![[
+            r#"Err(EvaluationFailed("integer too large for operation", PackageSpan { package: PackageId(2), span: Span { lo: 0, hi: 1 } }))"#
+        ]],
+    );
+    check(BinOp::Shr, -8, 1, &expect!["Ok(Int(-4))"]);
+    check(BinOp::Shr, 8, -1, &expect!["Ok(Int(16))"]);
+    check(BinOp::Shr, 5, 0, &expect!["Ok(Int(5))"]);
+}
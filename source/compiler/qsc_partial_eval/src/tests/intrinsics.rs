@@ -1,7 +1,11 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use super::{assert_block_instructions, assert_blocks, assert_callable, get_rir_program};
+use super::{
+    assert_block_instructions, assert_blocks, assert_callable, get_partial_evaluation_error,
+    get_rir_program,
+};
+use crate::Error;
 use expect_test::{Expect, expect};
 use indoc::{formatdoc, indoc};
 use qsc_rir::rir::{BlockId, CallableId};
@@ -955,6 +959,31 @@ fn call_to_dump_register_does_not_generate_instructions() {
     );
 }
 
+#[test]
+fn call_to_dump_bloch_vector_does_not_generate_instructions() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            import Std.Diagnostics.*;
+            @EntryPoint()
+            operation Main() : Unit {
+                use q = Qubit();
+                DumpBlochVector(q);
+            }
+        }
+        "#,
+    });
+    assert_block_instructions(
+        &program,
+        BlockId(0),
+        &expect![[r#"
+            Block:
+                Call id(1), args( Pointer, )
+                Call id(2), args( Integer(0), EmptyTag, )
+                Return"#]],
+    );
+}
+
 #[test]
 fn use_of_noise_does_not_generate_instructions() {
     let program = get_rir_program(indoc! {
@@ -1344,3 +1373,54 @@ fn call_to_intrinsic_operation_that_returns_qubit_value_should_fail() {
         }
     "});
 }
+
+#[test]
+fn call_to_math_intrinsic_with_static_arguments_is_evaluated() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            import Std.Math.*;
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                mutable x = 0;
+                if MResetZ(q) == One {
+                    set x = ExpModI(2, 10, 1000) + InverseModI(3, 7);
+                }
+                x
+            }
+        }
+    "});
+    assert!(
+        program
+            .callables
+            .iter()
+            .all(|(_, callable)| !["ExpModI", "InverseModI"].contains(&callable.name.as_str())),
+        "math intrinsics should not be emitted as calls"
+    );
+    assert!(
+        program
+            .blocks
+            .iter()
+            .any(|(_, block)| block.to_string().contains("Store Integer(29)")),
+        "the result of the math intrinsics should be stored as a literal"
+    );
+}
+
+#[test]
+fn call_to_math_intrinsic_with_dynamic_arguments_fails() {
+    let error = get_partial_evaluation_error(indoc! {"
+        namespace Test {
+            import Std.Math.*;
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                let power = MResetZ(q) == One ? 3 | 4;
+                ExpModI(2, power, 5)
+            }
+        }
+    "});
+    let Error::Unimplemented(message, _) = error else {
+        panic!("expected an unimplemented error, got {error:?}");
+    };
+    expect!["`ExpModI` with dynamic arguments"].assert_eq(&message);
+}
@@ -180,6 +180,47 @@ fn use_of_dynamic_qubit_yields_errors() {
     );
 }
 
+#[test]
+fn every_unsupported_construct_is_reported_with_its_span() {
+    check_profile(
+        r#"
+    namespace Test {
+        operation Foo() : Unit {
+            use q = Qubit();
+            let b = M(q) == Zero;
+        }
+        operation Bar() : Unit {
+            use control = Qubit();
+            if M(control) == Zero {
+                use q = Qubit();
+            }
+        }
+    }"#,
+        &expect![[r#"
+            [
+                UseOfDynamicBool(
+                    Span {
+                        lo: 104,
+                        hi: 116,
+                    },
+                ),
+                UseOfDynamicBool(
+                    Span {
+                        lo: 211,
+                        hi: 229,
+                    },
+                ),
+                UseOfDynamicQubit(
+                    Span {
+                        lo: 248,
+                        hi: 264,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn use_of_dynamic_big_int_yields_errors() {
     check_profile(
@@ -11,10 +11,10 @@ use qsc_hir::{
     },
     ty::{GenericArg, Prim, Ty},
 };
-use std::rc::Rc;
+use std::sync::Arc;
 
-pub(crate) fn generated_name(name: &str) -> Rc<str> {
-    Rc::from(format!("@{name}"))
+pub(crate) fn generated_name(name: &str) -> Arc<str> {
+    Arc::from(format!("@{name}"))
 }
 
 pub(crate) fn gen_ident(assigner: &mut Assigner, label: &str, ty: Ty, span: Span) -> IdentTemplate {
@@ -31,7 +31,7 @@ pub(crate) fn gen_ident(assigner: &mut Assigner, label: &str, ty: Ty, span: Span
 pub(crate) struct IdentTemplate {
     pub id: NodeId,
     pub span: Span,
-    pub name: Rc<str>,
+    pub name: Arc<str>,
     pub ty: Ty,
 }
 
@@ -108,7 +108,7 @@ pub(crate) fn create_gen_core_ref(
     Expr {
         id: NodeId::default(),
         span,
-        ty: Ty::Arrow(Rc::new(ty)),
+        ty: Ty::Arrow(Arc::new(ty)),
         kind: ExprKind::Var(Res::Item(callable.id), generics),
     }
 }
@@ -16,6 +16,7 @@ use qsc_hir::{
         StmtKind,
     },
     mut_visit::{self, MutVisitor},
+    rewrite::NodeIdRefresher,
     ty::Ty,
     visit::{self, Visitor},
 };
@@ -24,7 +25,6 @@ use thiserror::Error;
 
 use crate::{
     common::generated_name,
-    id_update::NodeIdRefresher,
     invert_block::adj_invert_block,
     spec_gen::adj_gen::{self, AdjDistrib},
 };
@@ -3,13 +3,13 @@
 
 #![allow(clippy::too_many_lines)]
 
-use expect_test::{Expect, expect};
+use expect_test::{Expect, ExpectFile, expect, expect_file};
 use indoc::indoc;
 use qsc_data_structures::{
     language_features::LanguageFeatures, source::SourceMap, target::TargetCapabilityFlags,
 };
 use qsc_frontend::compile::{self, PackageStore, compile};
-use qsc_hir::{validate::Validator, visit::Visitor};
+use qsc_hir::{canonical, validate::Validator, visit::Visitor};
 
 use crate::conjugate_invert::invert_conjugate_exprs;
 
@@ -34,6 +34,24 @@ fn check(file: &str, expect: &Expect) {
     }
 }
 
+fn check_golden(file: &str, expect: &ExpectFile) {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let mut unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let errors = invert_conjugate_exprs(store.core(), &mut unit.package, &mut unit.assigner);
+    assert!(errors.is_empty(), "{errors:?}");
+    Validator::default().visit_package(&unit.package);
+    expect.assert_eq(&canonical::render(&unit.package));
+}
+
 #[test]
 fn conjugate_invert() {
     check(
@@ -110,6 +128,28 @@ fn conjugate_invert() {
     );
 }
 
+#[test]
+fn conjugate_invert_golden() {
+    check_golden(
+        indoc! {"
+            namespace Test {
+                operation B(i : Int) : Unit is Adj {}
+                operation A() : Unit {
+                    within {
+                        B(1);
+                        B(2);
+                    }
+                    apply {
+                        B(3);
+                        B(4);
+                    }
+                }
+            }
+        "},
+        &expect_file!["golden/conjugate_invert.hir"],
+    );
+}
+
 #[test]
 fn conjugate_invert_with_output() {
     check(
@@ -0,0 +1,104 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use std::mem::take;
+
+use qsc_data_structures::span::Span;
+use qsc_hir::{
+    assigner::Assigner,
+    hir::{BinOp, Expr, ExprKind, Res, StringComponent},
+    mut_visit::{MutVisitor, walk_expr},
+    ty::{Prim, Ty},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Rewrites compound assignments into plain assignments, so `set x += e` becomes
+/// `set x = x + e`. Array appends are left as compound assignments, since the evaluator
+/// updates those in place.
+///
+/// Interpolated strings become concatenations, so `$"a{s}b{n}"` becomes
+/// `"a" + s + "b" + $"{n}"`: holes that are already strings are used directly, and the
+/// only interpolations left are single holes that convert a non-string value.
+///
+/// `within`/`apply` blocks are not handled here, since the earlier conjugate inversion pass
+/// already replaces them with the within-block, the apply-block and the adjoint of the
+/// within-block.
+pub(crate) struct Desugar<'a> {
+    pub(crate) assigner: &'a mut Assigner,
+}
+
+impl MutVisitor for Desugar<'_> {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        walk_expr(self, expr);
+
+        if let ExprKind::String(components) = &mut expr.kind {
+            let is_simple = match components.as_slice() {
+                [] | [StringComponent::Lit(_)] => true,
+                [StringComponent::Expr(hole)] => hole.ty != Ty::Prim(Prim::String),
+                _ => false,
+            };
+            if !is_simple {
+                let components = take(components);
+                expr.kind = self.concat(components, expr.span).kind;
+            }
+            return;
+        }
+
+        if let ExprKind::AssignOp(op, lhs, rhs) = &mut expr.kind
+            && matches!(lhs.kind, ExprKind::Var(Res::Local(_), _))
+            && !matches!(lhs.ty, Ty::Array(_))
+        {
+            let op = *op;
+            let lhs = take(lhs);
+            let rhs = take(rhs);
+            let lhs_ref = Expr {
+                id: self.assigner.next_node(),
+                span: lhs.span,
+                ty: lhs.ty.clone(),
+                kind: lhs.kind.clone(),
+            };
+            let value = Expr {
+                id: self.assigner.next_node(),
+                span: Span {
+                    lo: lhs.span.lo,
+                    hi: rhs.span.hi,
+                },
+                ty: lhs.ty.clone(),
+                kind: ExprKind::BinOp(op, Box::new(lhs_ref), rhs),
+            };
+            expr.kind = ExprKind::Assign(lhs, Box::new(value));
+        }
+    }
+}
+
+impl Desugar<'_> {
+    /// Joins the components of an interpolated string with string concatenation.
+    fn concat(&mut self, components: Vec<StringComponent>, span: Span) -> Expr {
+        components
+            .into_iter()
+            .map(|component| match component {
+                StringComponent::Expr(hole) if hole.ty == Ty::Prim(Prim::String) => *hole,
+                component => Expr {
+                    id: self.assigner.next_node(),
+                    span: match &component {
+                        StringComponent::Expr(hole) => hole.span,
+                        StringComponent::Lit(_) => span,
+                    },
+                    ty: Ty::Prim(Prim::String),
+                    kind: ExprKind::String(vec![component]),
+                },
+            })
+            .reduce(|lhs, rhs| Expr {
+                id: self.assigner.next_node(),
+                span: Span {
+                    lo: lhs.span.lo.min(rhs.span.lo),
+                    hi: lhs.span.hi.max(rhs.span.hi),
+                },
+                ty: Ty::Prim(Prim::String),
+                kind: ExprKind::BinOp(BinOp::Add, Box::new(lhs), Box::new(rhs)),
+            })
+            .expect("interpolated string should have components")
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use expect_test::{Expect, expect};
+use indoc::indoc;
+use qsc_data_structures::{
+    language_features::LanguageFeatures, source::SourceMap, target::TargetCapabilityFlags,
+};
+use qsc_frontend::compile::{self, PackageStore, compile};
+use qsc_hir::{mut_visit::MutVisitor, validate::Validator, visit::Visitor};
+
+use crate::desugar::Desugar;
+
+fn check(file: &str, expect: &Expect) {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let mut unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+    Desugar {
+        assigner: &mut unit.assigner,
+    }
+    .visit_package(&mut unit.package);
+    Validator::default().visit_package(&unit.package);
+    expect.assert_eq(&unit.package.to_string());
+}
+
+#[test]
+fn compound_assign_becomes_assign() {
+    check(
+        indoc! {"
+        namespace test {
+            operation Main() : Unit {
+                mutable x = 1;
+                set x += 2;
+            }
+        }
+        "},
+        &expect![[r#"
+            Package:
+                Item 0 [0-97] (Public):
+                    Namespace (Ident 13 [10-14] "test"): Item 1
+                Item 1 [21-95] (Internal):
+                    Parent: 0
+                    Callable 0 [21-95] (operation):
+                        name: Ident 1 [31-35] "Main"
+                        input: Pat 2 [35-37] [Type Unit]: Unit
+                        output: Unit
+                        functors: empty set
+                        body: SpecDecl 3 [21-95]: Impl:
+                            Block 4 [45-95] [Type Unit]:
+                                Stmt 5 [55-69]: Local (Mutable):
+                                    Pat 6 [63-64] [Type Int]: Bind: Ident 7 [63-64] "x"
+                                    Expr 8 [67-68] [Type Int]: Lit: Int(1)
+                                Stmt 9 [78-89]: Semi: Expr 10 [78-88] [Type Unit]: Assign:
+                                    Expr 11 [82-83] [Type Int]: Var: Local 7
+                                    Expr 15 [82-88] [Type Int]: BinOp (Add):
+                                        Expr 14 [82-83] [Type Int]: Var: Local 7
+                                        Expr 12 [87-88] [Type Int]: Lit: Int(2)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn array_append_is_unchanged() {
+    check(
+        indoc! {"
+        namespace test {
+            operation Main() : Unit {
+                mutable arr = [1];
+                set arr += [2];
+            }
+        }
+        "},
+        &expect![[r#"
+            Package:
+                Item 0 [0-105] (Public):
+                    Namespace (Ident 15 [10-14] "test"): Item 1
+                Item 1 [21-103] (Internal):
+                    Parent: 0
+                    Callable 0 [21-103] (operation):
+                        name: Ident 1 [31-35] "Main"
+                        input: Pat 2 [35-37] [Type Unit]: Unit
+                        output: Unit
+                        functors: empty set
+                        body: SpecDecl 3 [21-103]: Impl:
+                            Block 4 [45-103] [Type Unit]:
+                                Stmt 5 [55-73]: Local (Mutable):
+                                    Pat 6 [63-66] [Type Int[]]: Bind: Ident 7 [63-66] "arr"
+                                    Expr 8 [69-72] [Type Int[]]: Array:
+                                        Expr 9 [70-71] [Type Int]: Lit: Int(1)
+                                Stmt 10 [82-97]: Semi: Expr 11 [82-96] [Type Unit]: AssignOp (Add):
+                                    Expr 12 [86-89] [Type Int[]]: Var: Local 7
+                                    Expr 13 [93-96] [Type Int[]]: Array:
+                                        Expr 14 [94-95] [Type Int]: Lit: Int(2)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn interpolated_string_becomes_concatenation() {
+    check(
+        indoc! {r#"
+        namespace test {
+            operation Main() : Unit {
+                let s = "x";
+                let t = $"a{s}b{1}";
+            }
+        }
+        "#},
+        &expect![[r#"
+            Package:
+                Item 0 [0-104] (Public):
+                    Namespace (Ident 15 [10-14] "test"): Item 1
+                Item 1 [21-102] (Internal):
+                    Parent: 0
+                    Callable 0 [21-102] (operation):
+                        name: Ident 1 [31-35] "Main"
+                        input: Pat 2 [35-37] [Type Unit]: Unit
+                        output: Unit
+                        functors: empty set
+                        body: SpecDecl 3 [21-102]: Impl:
+                            Block 4 [45-102] [Type Unit]:
+                                Stmt 5 [55-67]: Local (Immutable):
+                                    Pat 6 [59-60] [Type String]: Bind: Ident 7 [59-60] "s"
+                                    Expr 8 [63-66] [Type String]: String:
+                                        Lit: "x"
+                                Stmt 9 [76-96]: Local (Immutable):
+                                    Pat 10 [80-81] [Type String]: Bind: Ident 11 [80-81] "t"
+                                    Expr 12 [84-95] [Type String]: BinOp (Add):
+                                        Expr 20 [84-95] [Type String]: BinOp (Add):
+                                            Expr 19 [84-95] [Type String]: BinOp (Add):
+                                                Expr 16 [84-95] [Type String]: String:
+                                                    Lit: "a"
+                                                Expr 13 [88-89] [Type String]: Var: Local 7
+                                            Expr 17 [84-95] [Type String]: String:
+                                                Lit: "b"
+                                        Expr 18 [92-93] [Type String]: String:
+                                            Expr: Expr 14 [92-93] [Type Int]: Lit: Int(1)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn single_interpolated_value_is_unchanged() {
+    check(
+        indoc! {r#"
+        namespace test {
+            operation Main() : Unit {
+                let t = $"{1}";
+            }
+        }
+        "#},
+        &expect![[r#"
+            Package:
+                Item 0 [0-78] (Public):
+                    Namespace (Ident 10 [10-14] "test"): Item 1
+                Item 1 [21-76] (Internal):
+                    Parent: 0
+                    Callable 0 [21-76] (operation):
+                        name: Ident 1 [31-35] "Main"
+                        input: Pat 2 [35-37] [Type Unit]: Unit
+                        output: Unit
+                        functors: empty set
+                        body: SpecDecl 3 [21-76]: Impl:
+                            Block 4 [45-76] [Type Unit]:
+                                Stmt 5 [55-70]: Local (Immutable):
+                                    Pat 6 [59-60] [Type String]: Bind: Ident 7 [59-60] "t"
+                                    Expr 8 [63-69] [Type String]: String:
+                                        Expr: Expr 9 [66-67] [Type Int]: Lit: Int(1)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>"#]],
+    );
+}
@@ -3,17 +3,19 @@
 
 #![allow(clippy::too_many_lines)]
 
-use expect_test::{Expect, expect};
+use expect_test::{Expect, ExpectFile, expect, expect_file};
 use indoc::indoc;
 use qsc_data_structures::{
     language_features::LanguageFeatures, source::SourceMap, target::TargetCapabilityFlags,
 };
 use qsc_frontend::compile::{self, PackageStore, compile};
-use qsc_hir::{mut_visit::MutVisitor, validate::Validator, visit::Visitor};
+use qsc_hir::{
+    canonical, hir::Package, mut_visit::MutVisitor, validate::Validator, visit::Visitor,
+};
 
 use crate::index_assignment::ConvertToWSlash;
 
-fn check(file: &str, expect: &Expect) {
+fn lower(file: &str) -> Package {
     let store = PackageStore::new(compile::core());
     let sources = SourceMap::new([("test".into(), file.into())], None);
     let mut unit = compile(
@@ -29,7 +31,15 @@ fn check(file: &str, expect: &Expect) {
     }
     .visit_package(&mut unit.package);
     Validator::default().visit_package(&unit.package);
-    expect.assert_eq(&unit.package.to_string());
+    unit.package
+}
+
+fn check(file: &str, expect: &Expect) {
+    expect.assert_eq(&lower(file).to_string());
+}
+
+fn check_golden(file: &str, expect: &ExpectFile) {
+    expect.assert_eq(&canonical::render(&lower(file)));
 }
 
 #[test]
@@ -403,3 +413,16 @@ fn convert_2d_array_assign_op() {
                         ctl-adj: <none>"#]],
     );
 }
+
+#[test]
+fn convert_2d_array_assign_op_golden() {
+    check_golden(
+        indoc! {r#"
+        operation Main() : Unit {
+            mutable arr = [[0, 1], [2, 3]];
+            arr[0][1] *= 2;
+        }
+        "#},
+        &expect_file!["golden/convert_2d_array_assign_op.hir"],
+    );
+}
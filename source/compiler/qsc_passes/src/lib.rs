@@ -6,8 +6,8 @@ mod callable_limits;
 mod capabilitiesck;
 mod common;
 mod conjugate_invert;
+mod desugar;
 mod entry_point;
-mod id_update;
 mod index_assignment;
 mod invert_block;
 mod logic_sep;
@@ -20,6 +20,7 @@ mod test_attribute;
 
 use callable_limits::CallableLimits;
 use capabilitiesck::{check_supported_capabilities, lower_store, run_rca_pass};
+use desugar::Desugar;
 use entry_point::generate_entry_expr;
 use index_assignment::ConvertToWSlash;
 use loop_unification::LoopUni;
@@ -125,6 +126,9 @@ impl PassContext {
         LoopUni { core, assigner }.visit_package(package);
         Validator::default().visit_package(package);
 
+        Desugar { assigner }.visit_package(package);
+        Validator::default().visit_package(package);
+
         ReplaceQubitAllocation::new(core, assigner).visit_package(package);
         Validator::default().visit_package(package);
 
@@ -175,6 +179,12 @@ pub fn run_core_passes(core: &mut CompileUnit) -> Vec<Error> {
     .visit_package(&mut core.package);
     Validator::default().visit_package(&core.package);
 
+    Desugar {
+        assigner: &mut core.assigner,
+    }
+    .visit_package(&mut core.package);
+    Validator::default().visit_package(&core.package);
+
     ReplaceQubitAllocation::new(&table, &mut core.assigner).visit_package(&mut core.package);
     Validator::default().visit_package(&core.package);
 
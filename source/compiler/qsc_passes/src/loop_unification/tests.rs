@@ -3,17 +3,19 @@
 
 #![allow(clippy::too_many_lines)]
 
-use expect_test::{Expect, expect};
+use expect_test::{Expect, ExpectFile, expect, expect_file};
 use indoc::indoc;
 use qsc_data_structures::{
     language_features::LanguageFeatures, source::SourceMap, target::TargetCapabilityFlags,
 };
 use qsc_frontend::compile::{self, PackageStore, compile};
-use qsc_hir::{mut_visit::MutVisitor, validate::Validator, visit::Visitor};
+use qsc_hir::{
+    canonical, hir::Package, mut_visit::MutVisitor, validate::Validator, visit::Visitor,
+};
 
 use crate::loop_unification::LoopUni;
 
-fn check(file: &str, expect: &Expect) {
+fn lower(file: &str) -> Package {
     let store = PackageStore::new(compile::core());
     let sources = SourceMap::new([("test".into(), file.into())], None);
     let mut unit = compile(
@@ -30,7 +32,15 @@ fn check(file: &str, expect: &Expect) {
     }
     .visit_package(&mut unit.package);
     Validator::default().visit_package(&unit.package);
-    expect.assert_eq(&unit.package.to_string());
+    unit.package
+}
+
+fn check(file: &str, expect: &Expect) {
+    expect.assert_eq(&lower(file).to_string());
+}
+
+fn check_golden(file: &str, expect: &ExpectFile) {
+    expect.assert_eq(&canonical::render(&lower(file)));
 }
 
 #[test]
@@ -96,6 +106,22 @@ fn convert_for_array() {
     );
 }
 
+#[test]
+fn convert_for_array_golden() {
+    check_golden(
+        indoc! {r#"
+        namespace test {
+            operation Main(arr : Int[]) : Unit {
+                for i in arr {
+                    let x = "Hello World";
+                }
+            }
+        }
+        "#},
+        &expect_file!["golden/convert_for_array.hir"],
+    );
+}
+
 #[test]
 fn convert_for_array_deconstruct() {
     check(
@@ -493,6 +519,25 @@ fn convert_repeat_fixup() {
     );
 }
 
+#[test]
+fn convert_repeat_fixup_golden() {
+    check_golden(
+        indoc! {r#"
+        namespace test {
+            operation Main() : Unit {
+                repeat {
+                    let x = "Hello World";
+                } until true
+                fixup {
+                    let y = "Fixup";
+                }
+            }
+        }
+        "#},
+        &expect_file!["golden/convert_repeat_fixup.hir"],
+    );
+}
+
 #[test]
 fn convert_repeat_nested() {
     check(
@@ -2,15 +2,17 @@
 // Licensed under the MIT License.
 
 use crate::replace_qubit_allocation::ReplaceQubitAllocation;
-use expect_test::{Expect, expect};
+use expect_test::{Expect, ExpectFile, expect, expect_file};
 use indoc::indoc;
 use qsc_data_structures::{
     language_features::LanguageFeatures, source::SourceMap, target::TargetCapabilityFlags,
 };
 use qsc_frontend::compile::{self, PackageStore, compile};
-use qsc_hir::{mut_visit::MutVisitor, validate::Validator, visit::Visitor};
+use qsc_hir::{
+    canonical, hir::Package, mut_visit::MutVisitor, validate::Validator, visit::Visitor,
+};
 
-fn check(file: &str, expect: &Expect) {
+fn lower(file: &str) -> Package {
     let store = PackageStore::new(compile::core());
     let sources = SourceMap::new([("test".into(), file.into())], None);
     let mut unit = compile(
@@ -23,7 +25,15 @@ fn check(file: &str, expect: &Expect) {
     assert!(unit.errors.is_empty(), "{:?}", unit.errors);
     ReplaceQubitAllocation::new(store.core(), &mut unit.assigner).visit_package(&mut unit.package);
     Validator::default().visit_package(&unit.package);
-    expect.assert_eq(&unit.package.to_string());
+    unit.package
+}
+
+fn check(file: &str, expect: &Expect) {
+    expect.assert_eq(&lower(file).to_string());
+}
+
+fn check_golden(file: &str, expect: &ExpectFile) {
+    expect.assert_eq(&canonical::render(&lower(file)));
 }
 
 #[test]
@@ -380,6 +390,23 @@ fn test_qubit_block() {
     );
 }
 
+#[test]
+fn test_qubit_block_golden() {
+    check_golden(
+        indoc! { "namespace input {
+            operation Foo() : Unit {
+                use (a, b) = (Qubit(), Qubit()) {
+                    let x = 3;
+                    use c = Qubit();
+                    let y = 3;
+                }
+                let z = 3;
+            }
+        }" },
+        &expect_file!["golden/test_qubit_block.hir"],
+    );
+}
+
 #[test]
 fn test_qubit_nested_block() {
     check(
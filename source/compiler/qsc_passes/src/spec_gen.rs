@@ -7,7 +7,7 @@ mod ctl_gen;
 #[cfg(test)]
 mod tests;
 
-use crate::{id_update::NodeIdRefresher, invert_block::adj_invert_block};
+use crate::invert_block::adj_invert_block;
 
 use self::{adj_gen::AdjDistrib, ctl_gen::CtlDistrib};
 use miette::Diagnostic;
@@ -20,6 +20,7 @@ use qsc_hir::{
         PatKind, Res, SpecBody, SpecDecl, SpecGen,
     },
     mut_visit::{MutVisitor, walk_item},
+    rewrite::NodeIdRefresher,
     ty::{Prim, Ty},
 };
 use std::option::Option;
@@ -1,10 +1,10 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use std::{cell::RefCell, rc::Rc};
+use std::sync::Arc;
 
 use miette::Diagnostic;
-use qsc_data_structures::span::Span;
+use qsc_data_structures::{span::Span, sync_cell::SyncCell};
 use qsc_hir::{
     hir::{CallableKind, Expr, ExprKind, Functor, NodeId, Res, UnOp},
     mut_visit::{MutVisitor, walk_expr},
@@ -43,9 +43,9 @@ impl MutVisitor for CtlDistrib {
                         if functors.contains(&Functor::Ctl) {
                             op.kind = ExprKind::UnOp(UnOp::Functor(Functor::Ctl), op.clone());
                             op.id = NodeId::default();
-                            let input_ty = Ty::Arrow(Rc::new(Arrow {
+                            let input_ty = Ty::Arrow(Arc::new(Arrow {
                                 kind: CallableKind::Operation,
-                                input: RefCell::new(Ty::Tuple(vec![
+                                input: SyncCell::new(Ty::Tuple(vec![
                                     Ty::Array(Box::new(Ty::Prim(Prim::Qubit))),
                                     arrow.input.borrow().clone(),
                                 ])),
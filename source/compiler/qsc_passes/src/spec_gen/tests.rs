@@ -3,13 +3,13 @@
 
 #![allow(clippy::too_many_lines)]
 
-use expect_test::{Expect, expect};
+use expect_test::{Expect, ExpectFile, expect, expect_file};
 use indoc::indoc;
 use qsc_data_structures::{
     language_features::LanguageFeatures, source::SourceMap, target::TargetCapabilityFlags,
 };
 use qsc_frontend::compile::{self, PackageStore, compile};
-use qsc_hir::{validate::Validator, visit::Visitor};
+use qsc_hir::{canonical, validate::Validator, visit::Visitor};
 
 use crate::spec_gen::generate_specs;
 
@@ -34,6 +34,24 @@ fn check(file: &str, expect: &Expect) {
     }
 }
 
+fn check_golden(file: &str, expect: &ExpectFile) {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let mut unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let errors = generate_specs(store.core(), &mut unit.package, &mut unit.assigner);
+    assert!(errors.is_empty(), "{errors:?}");
+    Validator::default().visit_package(&unit.package);
+    expect.assert_eq(&canonical::render(&unit.package));
+}
+
 #[test]
 fn generate_specs_body_intrinsic_should_fail() {
     check(
@@ -469,6 +487,30 @@ fn generate_ctladj_distrib() {
     );
 }
 
+#[test]
+fn generate_ctladj_distrib_golden() {
+    check_golden(
+        indoc! {"
+            namespace test {
+                operation A(q : Qubit) : Unit is Ctl + Adj {
+                    body ... {}
+                    adjoint ... {}
+                    controlled (ctls, ...) {}
+                }
+                operation B(q : Qubit) : Unit is Ctl + Adj {
+                    body ... {
+                        A(q);
+                    }
+                    adjoint ... {
+                        Adjoint A(q);
+                    }
+                }
+            }
+        "},
+        &expect_file!["golden/generate_ctladj_distrib.hir"],
+    );
+}
+
 #[test]
 fn generate_ctl_skip_conjugate_apply_block() {
     check(
@@ -52,6 +52,17 @@ pub fn cx_decl() -> Callable {
     }
 }
 
+#[must_use]
+pub fn swap_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__swap__body".to_string(),
+        input_type: vec![Ty::Qubit, Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
 #[must_use]
 pub fn rx_decl() -> Callable {
     Callable {
@@ -63,6 +74,94 @@ pub fn rx_decl() -> Callable {
     }
 }
 
+#[must_use]
+pub fn cz_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__cz__body".to_string(),
+        input_type: vec![Ty::Qubit, Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
+#[must_use]
+pub fn cy_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__cy__body".to_string(),
+        input_type: vec![Ty::Qubit, Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
+#[must_use]
+pub fn ccx_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__ccx__body".to_string(),
+        input_type: vec![Ty::Qubit, Ty::Qubit, Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
+#[must_use]
+pub fn rz_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__rz__body".to_string(),
+        input_type: vec![Ty::Double, Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
+#[must_use]
+pub fn s_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__s__body".to_string(),
+        input_type: vec![Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
+#[must_use]
+pub fn sx_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__sx__body".to_string(),
+        input_type: vec![Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
+#[must_use]
+pub fn t_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__t__body".to_string(),
+        input_type: vec![Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
+#[must_use]
+pub fn t_adj_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__t__adj".to_string(),
+        input_type: vec![Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
 #[must_use]
 pub fn m_decl() -> Callable {
     Callable {
@@ -1,21 +1,31 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+mod approximate_rotations;
 mod build_dominator_graph;
+mod decompose_gates;
 mod defer_meas;
+mod defer_terminal_meas;
+mod lower_conditionals;
 mod reindex_qubits;
 mod remap_block_ids;
+mod route_qubits;
 mod simplify_control_flow;
 mod ssa_check;
 mod ssa_transform;
 mod type_check;
 mod unreachable_code_check;
 
+pub use approximate_rotations::{MAX_APPROXIMATION_T_COUNT, approximate_rotations};
 use build_dominator_graph::build_dominator_graph;
+pub use decompose_gates::{Error as DecompositionError, GateSet, decompose_gates};
 use defer_meas::defer_measurements;
+pub use defer_terminal_meas::defer_terminal_measurements;
+pub use lower_conditionals::{Error as ConditionalLoweringError, lower_measurement_conditionals};
 use qsc_data_structures::target::TargetCapabilityFlags;
 use reindex_qubits::reindex_qubits;
 use remap_block_ids::remap_block_ids;
+pub use route_qubits::{CouplingMap, Error as RoutingError, RoutingReport, route_qubits};
 use simplify_control_flow::simplify_control_flow;
 use ssa_check::check_ssa_form;
 use ssa_transform::transform_to_ssa;
@@ -0,0 +1,275 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::{VecDeque, hash_map::Entry},
+    f64::consts::{FRAC_1_SQRT_2, FRAC_PI_4},
+};
+
+use num_complex::Complex64;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::decompose_gates::{Error, find_or_add_callable};
+use crate::{
+    builder,
+    rir::{Instruction, Literal, Operand, Program},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CliffordT {
+    H,
+    S,
+    T,
+}
+
+type Matrix = [[Complex64; 2]; 2];
+
+/// The largest `max_t_count` accepted by [`approximate_rotations`]. The search visits up to
+/// `2^max_t_count` normal forms for each distinct angle.
+pub const MAX_APPROXIMATION_T_COUNT: u32 = 16;
+
+/// Replaces each call to `Rz` by an angle known at compile time with a sequence of H, S and T gates
+/// that approximates it to within `precision`, up to global phase, using as few T gates as possible.
+/// Sequences are found by exhaustive search over Clifford+T normal forms, so `max_t_count` bounds
+/// the work done for each distinct angle. Other rotations should first be decomposed into `Rz` with
+/// `decompose_gates`.
+/// Returns an error, leaving the program unchanged, if a rotation cannot be approximated or if
+/// `max_t_count` is larger than [`MAX_APPROXIMATION_T_COUNT`].
+pub fn approximate_rotations(
+    program: &mut Program,
+    precision: f64,
+    max_t_count: u32,
+) -> Result<(), Error> {
+    if max_t_count > MAX_APPROXIMATION_T_COUNT {
+        return Err(Error::TCountLimitExceeded(
+            max_t_count,
+            MAX_APPROXIMATION_T_COUNT,
+        ));
+    }
+    let Some((rz_id, _)) = program
+        .callables
+        .iter()
+        .find(|(_, callable)| callable.name == builder::rz_decl().name)
+    else {
+        return Ok(());
+    };
+
+    let synthesizer = Synthesizer::new(precision, max_t_count);
+    let mut callables = program.callables.clone();
+    let mut words = FxHashMap::default();
+    let mut blocks = Vec::new();
+    for (block_id, block) in program.blocks.iter() {
+        let mut instrs = Vec::with_capacity(block.0.len());
+        for instr in &block.0 {
+            let Instruction::Call(callable_id, args, None) = instr else {
+                instrs.push(instr.clone());
+                continue;
+            };
+            if *callable_id != rz_id {
+                instrs.push(instr.clone());
+                continue;
+            }
+            let &[Operand::Literal(Literal::Double(theta)), q] = args.as_slice() else {
+                return Err(Error::DynamicApproximation);
+            };
+            let word = match words.entry(theta.to_bits()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    synthesizer
+                        .synthesize(theta)
+                        .ok_or(Error::PrecisionNotReached(theta, precision, max_t_count))?,
+                ),
+            };
+            for gate in word.iter() {
+                let decl = match gate {
+                    CliffordT::H => builder::h_decl(),
+                    CliffordT::S => builder::s_decl(),
+                    CliffordT::T => builder::t_decl(),
+                };
+                let id = find_or_add_callable(&mut callables, decl);
+                instrs.push(Instruction::Call(id, vec![q], None));
+            }
+        }
+        blocks.push((block_id, instrs));
+    }
+
+    for (block_id, instrs) in blocks {
+        program
+            .blocks
+            .get_mut(block_id)
+            .expect("block should exist")
+            .0 = instrs;
+    }
+    callables.remove(rz_id);
+    program.callables = callables;
+    Ok(())
+}
+
+struct Synthesizer {
+    /// The 24 single-qubit Cliffords up to global phase, as gates in time order and as matrices.
+    cliffords: Vec<(Vec<CliffordT>, Matrix)>,
+    precision: f64,
+    max_t_count: u32,
+}
+
+impl Synthesizer {
+    fn new(precision: f64, max_t_count: u32) -> Self {
+        Self {
+            cliffords: cliffords(),
+            precision,
+            max_t_count,
+        }
+    }
+
+    /// Finds the sequence of gates, in time order, with the lowest T count that approximates
+    /// `Rz(theta)`. Every Clifford+T unitary has a unique Matsumoto-Amano normal form
+    /// `(T | ε) (HT | SHT)* C` for a Clifford `C`, so the search enumerates those forms by
+    /// increasing T count.
+    fn synthesize(&self, theta: f64) -> Option<Vec<CliffordT>> {
+        let target = rz(theta);
+        for t_count in 0..=self.max_t_count {
+            for leading_t in [false, true] {
+                let Some(syllables) = t_count.checked_sub(u32::from(leading_t)) else {
+                    continue;
+                };
+                let mut letters = Vec::new();
+                let mut unitary = identity();
+                if leading_t {
+                    letters.push(CliffordT::T);
+                    unitary = gate_matrix(CliffordT::T);
+                }
+                if let Some(word) = self.search(&mut letters, &unitary, syllables, &target) {
+                    return Some(word);
+                }
+            }
+        }
+        None
+    }
+
+    /// Searches the normal forms that extend `letters`, which are in matrix order and multiply to
+    /// `unitary`, with the given number of remaining syllables.
+    fn search(
+        &self,
+        letters: &mut Vec<CliffordT>,
+        unitary: &Matrix,
+        syllables: u32,
+        target: &Matrix,
+    ) -> Option<Vec<CliffordT>> {
+        if syllables == 0 {
+            let (clifford, _) = self
+                .cliffords
+                .iter()
+                .find(|(_, c)| distance(&mul(unitary, c), target) <= self.precision)?;
+            // The gates in time order are the reverse of the letters in matrix order.
+            return Some(
+                clifford
+                    .iter()
+                    .chain(letters.iter().rev())
+                    .copied()
+                    .collect(),
+            );
+        }
+        for syllable in [
+            &[CliffordT::H, CliffordT::T][..],
+            &[CliffordT::S, CliffordT::H, CliffordT::T][..],
+        ] {
+            let next = syllable
+                .iter()
+                .fold(*unitary, |acc, gate| mul(&acc, &gate_matrix(*gate)));
+            let len = letters.len();
+            letters.extend_from_slice(syllable);
+            let word = self.search(letters, &next, syllables - 1, target);
+            letters.truncate(len);
+            if word.is_some() {
+                return word;
+            }
+        }
+        None
+    }
+}
+
+/// Enumerates the single-qubit Cliffords up to global phase by breadth-first search over words in
+/// H and S, keeping the shortest word for each.
+fn cliffords() -> Vec<(Vec<CliffordT>, Matrix)> {
+    let mut seen = FxHashSet::default();
+    let mut queue = VecDeque::from([Vec::new()]);
+    let mut cliffords = Vec::new();
+    while let Some(word) = queue.pop_front() {
+        let matrix = word
+            .iter()
+            .fold(identity(), |acc, gate| mul(&gate_matrix(*gate), &acc));
+        if !seen.insert(phase_key(&matrix)) {
+            continue;
+        }
+        for gate in [CliffordT::H, CliffordT::S] {
+            let mut next = word.clone();
+            next.push(gate);
+            queue.push_back(next);
+        }
+        cliffords.push((word, matrix));
+    }
+    cliffords
+}
+
+/// Returns a key that is equal for matrices that are equal up to global phase.
+#[allow(clippy::cast_possible_truncation)]
+fn phase_key(matrix: &Matrix) -> [i64; 8] {
+    let entries = [matrix[0][0], matrix[0][1], matrix[1][0], matrix[1][1]];
+    let pivot = entries
+        .iter()
+        .find(|entry| entry.norm() > 1e-6)
+        .expect("unitary should have a non-zero entry");
+    let phase = *pivot / pivot.norm();
+    let mut key = [0; 8];
+    for (i, entry) in entries.iter().enumerate() {
+        let entry = *entry / phase;
+        key[2 * i] = (entry.re * 1e6).round() as i64;
+        key[2 * i + 1] = (entry.im * 1e6).round() as i64;
+    }
+    key
+}
+
+fn gate_matrix(gate: CliffordT) -> Matrix {
+    let zero = Complex64::new(0.0, 0.0);
+    let one = Complex64::new(1.0, 0.0);
+    match gate {
+        CliffordT::H => {
+            let h = Complex64::new(FRAC_1_SQRT_2, 0.0);
+            [[h, h], [h, -h]]
+        }
+        CliffordT::S => [[one, zero], [zero, Complex64::i()]],
+        CliffordT::T => [[one, zero], [zero, Complex64::from_polar(1.0, FRAC_PI_4)]],
+    }
+}
+
+fn rz(theta: f64) -> Matrix {
+    let zero = Complex64::new(0.0, 0.0);
+    [
+        [Complex64::from_polar(1.0, -theta / 2.0), zero],
+        [zero, Complex64::from_polar(1.0, theta / 2.0)],
+    ]
+}
+
+fn identity() -> Matrix {
+    let zero = Complex64::new(0.0, 0.0);
+    let one = Complex64::new(1.0, 0.0);
+    [[one, zero], [zero, one]]
+}
+
+fn mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let entry = |i: usize, j: usize| a[i][0] * b[0][j] + a[i][1] * b[1][j];
+    [[entry(0, 0), entry(0, 1)], [entry(1, 0), entry(1, 1)]]
+}
+
+/// Returns the distance between two unitaries up to global phase, which is zero exactly when they
+/// are equal up to global phase.
+fn distance(u: &Matrix, v: &Matrix) -> f64 {
+    let trace: Complex64 = (0..2)
+        .flat_map(|i| (0..2).map(move |j| (i, j)))
+        .map(|(i, j)| u[i][j].conj() * v[i][j])
+        .sum();
+    (1.0 - trace.norm() / 2.0).max(0.0).sqrt()
+}
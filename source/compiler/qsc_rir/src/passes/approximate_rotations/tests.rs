@@ -0,0 +1,141 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use crate::{
+    builder,
+    rir::{
+        Block, BlockId, CallableId, Instruction, Literal, Operand, Program, Ty, Variable,
+        VariableId,
+    },
+};
+use expect_test::expect;
+
+use super::{
+    CliffordT, Error, MAX_APPROXIMATION_T_COUNT, Synthesizer, approximate_rotations, distance,
+    gate_matrix, identity, mul, rz,
+};
+
+fn rz_program(angles: &[f64]) -> Program {
+    let mut program = Program::default();
+    program.callables.insert(CallableId(0), builder::rz_decl());
+    let mut instrs = angles
+        .iter()
+        .map(|angle| {
+            Instruction::Call(
+                CallableId(0),
+                vec![
+                    Operand::Literal(Literal::Double(*angle)),
+                    Operand::Literal(Literal::Qubit(0)),
+                ],
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+    instrs.push(Instruction::Return);
+    program.blocks.insert(BlockId(0), Block(instrs));
+    program
+}
+
+#[test]
+fn exact_rotations_use_minimal_gates() {
+    let mut program = rz_program(&[FRAC_PI_4, FRAC_PI_2]);
+
+    approximate_rotations(&mut program, 1e-6, 4).expect("approximation should succeed");
+
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    assert_eq!(
+        program.get_callable(CallableId(1)).name,
+        "__quantum__qis__t__body"
+    );
+    assert_eq!(
+        program.get_callable(CallableId(2)).name,
+        "__quantum__qis__s__body"
+    );
+    assert!(program.callables.get(CallableId(0)).is_none());
+}
+
+#[test]
+fn approximation_is_within_precision() {
+    let synthesizer = Synthesizer::new(0.05, 12);
+    let word = synthesizer
+        .synthesize(0.3)
+        .expect("approximation should succeed");
+
+    let unitary = word
+        .iter()
+        .fold(identity(), |acc, gate| mul(&gate_matrix(*gate), &acc));
+    assert!(distance(&unitary, &rz(0.3)) <= 0.05);
+    assert!(word.contains(&CliffordT::T));
+}
+
+#[test]
+fn finer_precision_needs_more_t_gates() {
+    let t_count = |precision| {
+        Synthesizer::new(precision, 12)
+            .synthesize(0.3)
+            .expect("approximation should succeed")
+            .iter()
+            .filter(|gate| **gate == CliffordT::T)
+            .count()
+    };
+    assert!(t_count(0.2) <= t_count(0.05));
+}
+
+#[test]
+fn unreachable_precision_fails_and_leaves_program_unchanged() {
+    let mut program = rz_program(&[0.3]);
+    let original = program.to_string();
+
+    assert_eq!(
+        approximate_rotations(&mut program, 1e-6, 2),
+        Err(Error::PrecisionNotReached(0.3, 1e-6, 2))
+    );
+    assert_eq!(program.to_string(), original);
+}
+
+#[test]
+fn t_count_above_limit_fails_and_leaves_program_unchanged() {
+    let mut program = rz_program(&[0.3]);
+    let original = program.to_string();
+
+    assert_eq!(
+        approximate_rotations(&mut program, 1e-6, MAX_APPROXIMATION_T_COUNT + 1),
+        Err(Error::TCountLimitExceeded(
+            MAX_APPROXIMATION_T_COUNT + 1,
+            MAX_APPROXIMATION_T_COUNT
+        ))
+    );
+    assert_eq!(program.to_string(), original);
+}
+
+#[test]
+fn dynamic_rotation_fails() {
+    let mut program = rz_program(&[0.3]);
+    let angle = Operand::Variable(Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Double,
+    });
+    program
+        .blocks
+        .get_mut(BlockId(0))
+        .expect("block should exist")
+        .0[0] = Instruction::Call(
+        CallableId(0),
+        vec![angle, Operand::Literal(Literal::Qubit(0))],
+        None,
+    );
+
+    assert_eq!(
+        approximate_rotations(&mut program, 1e-6, 4),
+        Err(Error::DynamicApproximation)
+    );
+}
@@ -0,0 +1,292 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    f64::consts::{FRAC_PI_2, FRAC_PI_4, PI},
+    fmt::{self, Display, Formatter},
+};
+
+use miette::Diagnostic;
+use qsc_data_structures::index_map::IndexMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use thiserror::Error;
+
+use crate::{
+    builder,
+    rir::{Callable, CallableId, Instruction, Literal, Operand, Program},
+};
+
+const QIS_PREFIX: &str = "__quantum__qis__";
+
+/// A restricted set of gates that the gates of a program can be decomposed into.
+/// Measurements and resets are always kept as they are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GateSet {
+    /// CZ, Rz and SX, with arbitrary rotation angles.
+    CzRzSx,
+    /// CNOT, H, T and adjoint T. Only rotations by multiples of π/4 can be decomposed exactly.
+    CxHT,
+}
+
+impl Display for GateSet {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::CzRzSx => write!(f, "{{CZ, Rz, SX}}"),
+            Self::CxHT => write!(f, "{{CNOT, H, T}}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Diagnostic, Error, PartialEq)]
+pub enum Error {
+    #[error("gate `{0}` cannot be decomposed into the {1} gate set")]
+    #[diagnostic(code("Qsc.Rir.UnsupportedGate"))]
+    UnsupportedGate(String, GateSet),
+
+    #[error("rotation by {0} cannot be expressed exactly in the {1} gate set")]
+    #[diagnostic(help(
+        "only rotation angles that are multiples of π/4 have an exact Clifford+T decomposition"
+    ))]
+    #[diagnostic(code("Qsc.Rir.InexactRotation"))]
+    InexactRotation(f64, GateSet),
+
+    #[error("rotation by a dynamic angle cannot be expressed in the {0} gate set")]
+    #[diagnostic(help(
+        "only rotation angles known at compile time can be decomposed into Clifford+T gates"
+    ))]
+    #[diagnostic(code("Qsc.Rir.DynamicRotation"))]
+    DynamicRotation(GateSet),
+
+    #[error("rotation by {0} cannot be approximated to precision {1} with at most {2} T gates")]
+    #[diagnostic(help("use a coarser precision or allow more T gates"))]
+    #[diagnostic(code("Qsc.Rir.PrecisionNotReached"))]
+    PrecisionNotReached(f64, f64, u32),
+
+    #[error("rotation by a dynamic angle cannot be approximated with Clifford+T gates")]
+    #[diagnostic(help(
+        "only rotation angles known at compile time can be approximated with Clifford+T gates"
+    ))]
+    #[diagnostic(code("Qsc.Rir.DynamicApproximation"))]
+    DynamicApproximation,
+
+    #[error("a maximum T count of {0} is larger than the supported limit of {1}")]
+    #[diagnostic(help(
+        "the approximation search takes time exponential in the maximum T count; use a smaller one"
+    ))]
+    #[diagnostic(code("Qsc.Rir.TCountLimitExceeded"))]
+    TCountLimitExceeded(u32, u32),
+}
+
+/// The gates that all supported intrinsics are first decomposed into, before being lowered into
+/// the target gate set. Angles come before qubits, matching the QIR signatures.
+#[derive(Clone, Copy)]
+enum Gate {
+    H(Operand),
+    Sx(Operand),
+    Rz(Operand, Operand),
+    Cx(Operand, Operand),
+    Cz(Operand, Operand),
+}
+
+/// Rewrites every gate in the program into the given gate set, up to global phase, and removes the
+/// declarations of gates that are no longer called. Calls that are not gates, such as measurements,
+/// resets and output recording, are left unchanged.
+/// Returns an error, leaving the program unchanged, if a gate cannot be decomposed exactly.
+pub fn decompose_gates(program: &mut Program, gate_set: GateSet) -> Result<(), Error> {
+    let mut callables = program.callables.clone();
+    let mut target_ids = FxHashMap::default();
+    let mut decomposed_ids = FxHashSet::default();
+    let mut blocks = Vec::new();
+
+    for (block_id, block) in program.blocks.iter() {
+        let mut instrs = Vec::with_capacity(block.0.len());
+        for instr in &block.0 {
+            let Instruction::Call(callable_id, args, None) = instr else {
+                instrs.push(instr.clone());
+                continue;
+            };
+            let name = &program.get_callable(*callable_id).name;
+            let Some(gates) = decompose(name, args, gate_set)? else {
+                instrs.push(instr.clone());
+                continue;
+            };
+            decomposed_ids.insert(*callable_id);
+            for gate in gates {
+                for gate in lower(gate, gate_set)? {
+                    let (decl, args) = target_call(gate, gate_set);
+                    let id = *target_ids
+                        .entry(decl.name.clone())
+                        .or_insert_with(|| find_or_add_callable(&mut callables, decl));
+                    instrs.push(Instruction::Call(id, args, None));
+                }
+            }
+        }
+        blocks.push((block_id, instrs));
+    }
+
+    for (block_id, instrs) in blocks {
+        program
+            .blocks
+            .get_mut(block_id)
+            .expect("block should exist")
+            .0 = instrs;
+    }
+    let target_ids = target_ids.into_values().collect::<FxHashSet<_>>();
+    callables.retain(|id, _| !decomposed_ids.contains(&id) || target_ids.contains(&id));
+    program.callables = callables;
+    Ok(())
+}
+
+/// Decomposes a call to the callable with the given name into gates, or returns `None` if the
+/// callable is not a gate.
+fn decompose(name: &str, args: &[Operand], gate_set: GateSet) -> Result<Option<Vec<Gate>>, Error> {
+    let Some(gate_name) = name.strip_prefix(QIS_PREFIX) else {
+        return Ok(None);
+    };
+    let gates = match (gate_name, args) {
+        ("barrier__body" | "m__body" | "mresetz__body" | "reset__body", _) => return Ok(None),
+        ("h__body", &[q]) => vec![Gate::H(q)],
+        ("x__body", &[q]) => vec![Gate::H(q), rz(PI, q), Gate::H(q)],
+        ("y__body", &[q]) => vec![rz(PI, q), Gate::H(q), rz(PI, q), Gate::H(q)],
+        ("z__body", &[q]) => vec![rz(PI, q)],
+        ("s__body", &[q]) => vec![rz(FRAC_PI_2, q)],
+        ("s__adj", &[q]) => vec![rz(-FRAC_PI_2, q)],
+        ("t__body", &[q]) => vec![rz(FRAC_PI_4, q)],
+        ("t__adj", &[q]) => vec![rz(-FRAC_PI_4, q)],
+        ("sx__body", &[q]) => vec![Gate::Sx(q)],
+        ("rx__body", &[theta, q]) => vec![Gate::H(q), Gate::Rz(theta, q), Gate::H(q)],
+        ("ry__body", &[theta, q]) => vec![
+            rz(-FRAC_PI_2, q),
+            Gate::H(q),
+            Gate::Rz(theta, q),
+            Gate::H(q),
+            rz(FRAC_PI_2, q),
+        ],
+        ("rz__body", &[theta, q]) => vec![Gate::Rz(theta, q)],
+        ("cx__body", &[c, t]) => vec![Gate::Cx(c, t)],
+        ("cz__body", &[c, t]) => vec![Gate::Cz(c, t)],
+        ("cy__body", &[c, t]) => vec![rz(-FRAC_PI_2, t), Gate::Cx(c, t), rz(FRAC_PI_2, t)],
+        ("rzz__body", &[theta, a, b]) => rzz(theta, a, b),
+        ("rxx__body", &[theta, a, b]) => [vec![Gate::H(a), Gate::H(b)], rzz(theta, a, b)]
+            .into_iter()
+            .flatten()
+            .chain([Gate::H(a), Gate::H(b)])
+            .collect(),
+        ("ryy__body", &[theta, a, b]) => [
+            vec![rz(-FRAC_PI_2, a), rz(-FRAC_PI_2, b), Gate::H(a), Gate::H(b)],
+            rzz(theta, a, b),
+        ]
+        .into_iter()
+        .flatten()
+        .chain([Gate::H(a), Gate::H(b), rz(FRAC_PI_2, a), rz(FRAC_PI_2, b)])
+        .collect(),
+        ("swap__body", &[a, b]) => vec![Gate::Cx(a, b), Gate::Cx(b, a), Gate::Cx(a, b)],
+        ("ccx__body", &[a, b, c]) => vec![
+            Gate::H(c),
+            Gate::Cx(b, c),
+            rz(-FRAC_PI_4, c),
+            Gate::Cx(a, c),
+            rz(FRAC_PI_4, c),
+            Gate::Cx(b, c),
+            rz(-FRAC_PI_4, c),
+            Gate::Cx(a, c),
+            rz(FRAC_PI_4, b),
+            rz(FRAC_PI_4, c),
+            Gate::H(c),
+            Gate::Cx(a, b),
+            rz(FRAC_PI_4, a),
+            rz(-FRAC_PI_4, b),
+            Gate::Cx(a, b),
+        ],
+        _ => return Err(Error::UnsupportedGate(name.to_string(), gate_set)),
+    };
+    Ok(Some(gates))
+}
+
+fn rz(angle: f64, q: Operand) -> Gate {
+    Gate::Rz(Operand::Literal(Literal::Double(angle)), q)
+}
+
+fn rzz(theta: Operand, a: Operand, b: Operand) -> Vec<Gate> {
+    vec![Gate::Cx(a, b), Gate::Rz(theta, b), Gate::Cx(a, b)]
+}
+
+/// Lowers a gate into gates that are all native to the target gate set.
+fn lower(gate: Gate, gate_set: GateSet) -> Result<Vec<Gate>, Error> {
+    Ok(match (gate_set, gate) {
+        (GateSet::CzRzSx, Gate::H(q)) => vec![rz(FRAC_PI_2, q), Gate::Sx(q), rz(FRAC_PI_2, q)],
+        (GateSet::CzRzSx, Gate::Cx(c, t)) => [
+            lower(Gate::H(t), gate_set)?,
+            vec![Gate::Cz(c, t)],
+            lower(Gate::H(t), gate_set)?,
+        ]
+        .concat(),
+        (GateSet::CxHT, Gate::Sx(q)) => [
+            vec![Gate::H(q)],
+            lower(rz(FRAC_PI_2, q), gate_set)?,
+            vec![Gate::H(q)],
+        ]
+        .concat(),
+        (GateSet::CxHT, Gate::Cz(c, t)) => vec![Gate::H(t), Gate::Cx(c, t), Gate::H(t)],
+        (GateSet::CxHT, Gate::Rz(theta, q)) => {
+            let Operand::Literal(Literal::Double(theta)) = theta else {
+                return Err(Error::DynamicRotation(gate_set));
+            };
+            let eighths = (theta / FRAC_PI_4).round();
+            if (theta - eighths * FRAC_PI_4).abs() > 1e-10 {
+                return Err(Error::InexactRotation(theta, gate_set));
+            }
+            // Rz(kπ/4) is T^k up to global phase, and T^8 is the identity.
+            #[allow(clippy::cast_possible_truncation)]
+            let count = usize::try_from((eighths as i64).rem_euclid(8))
+                .expect("remainder should be non-negative");
+            if count <= 4 {
+                vec![rz(FRAC_PI_4, q); count]
+            } else {
+                vec![rz(-FRAC_PI_4, q); 8 - count]
+            }
+        }
+        (_, gate) => vec![gate],
+    })
+}
+
+/// Returns the declaration and arguments of the call that implements a gate native to the target
+/// gate set. In the Clifford+T gate set, rotations have been lowered to rotations by ±π/4, which
+/// are T and adjoint T.
+fn target_call(gate: Gate, gate_set: GateSet) -> (Callable, Vec<Operand>) {
+    match (gate_set, gate) {
+        (GateSet::CxHT, Gate::Rz(Operand::Literal(Literal::Double(theta)), q)) if theta > 0.0 => {
+            (builder::t_decl(), vec![q])
+        }
+        (GateSet::CxHT, Gate::Rz(_, q)) => (builder::t_adj_decl(), vec![q]),
+        (_, Gate::H(q)) => (builder::h_decl(), vec![q]),
+        (_, Gate::Sx(q)) => (builder::sx_decl(), vec![q]),
+        (_, Gate::Rz(theta, q)) => (builder::rz_decl(), vec![theta, q]),
+        (_, Gate::Cx(c, t)) => (builder::cx_decl(), vec![c, t]),
+        (_, Gate::Cz(c, t)) => (builder::cz_decl(), vec![c, t]),
+    }
+}
+
+pub(super) fn find_or_add_callable(
+    callables: &mut IndexMap<CallableId, Callable>,
+    decl: Callable,
+) -> CallableId {
+    if let Some((id, _)) = callables
+        .iter()
+        .find(|(_, callable)| callable.name == decl.name)
+    {
+        return id;
+    }
+    let id = CallableId(
+        callables
+            .iter()
+            .map(|(id, _)| id.0 + 1)
+            .max()
+            .unwrap_or_default(),
+    );
+    callables.insert(id, decl);
+    id
+}
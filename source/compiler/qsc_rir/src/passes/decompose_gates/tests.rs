@@ -0,0 +1,227 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use std::f64::consts::FRAC_PI_2;
+
+use crate::{
+    builder,
+    rir::{
+        Block, BlockId, Callable, CallableId, CallableType, Instruction, Literal, Operand, Program,
+        Ty, Variable, VariableId,
+    },
+};
+use expect_test::expect;
+
+use super::{Error, GateSet, decompose_gates};
+
+fn program_with_calls(callables: Vec<Callable>, calls: Vec<(u32, Vec<Operand>)>) -> Program {
+    let mut program = Program::default();
+    for (id, callable) in callables.into_iter().enumerate() {
+        program.callables.insert(CallableId::from(id), callable);
+    }
+    let mut instrs = calls
+        .into_iter()
+        .map(|(id, args)| Instruction::Call(CallableId(id), args, None))
+        .collect::<Vec<_>>();
+    instrs.push(Instruction::Return);
+    program.blocks.insert(BlockId(0), Block(instrs));
+    program
+}
+
+fn qubit(id: u32) -> Operand {
+    Operand::Literal(Literal::Qubit(id))
+}
+
+fn double(value: f64) -> Operand {
+    Operand::Literal(Literal::Double(value))
+}
+
+fn callable_names(program: &Program) -> String {
+    program
+        .callables
+        .iter()
+        .map(|(id, callable)| format!("{}: {}\n", id.0, callable.name))
+        .collect()
+}
+
+fn count_calls(program: &Program, name: &str) -> usize {
+    program
+        .get_block(BlockId(0))
+        .0
+        .iter()
+        .filter(|instr| {
+            matches!(instr, Instruction::Call(id, _, _) if program.get_callable(*id).name == name)
+        })
+        .count()
+}
+
+#[test]
+fn cx_decomposes_into_cz_rz_sx() {
+    let mut program = program_with_calls(
+        vec![builder::cx_decl(), builder::m_decl()],
+        vec![
+            (0, vec![qubit(0), qubit(1)]),
+            (1, vec![qubit(1), Operand::Literal(Literal::Result(0))]),
+        ],
+    );
+
+    decompose_gates(&mut program, GateSet::CzRzSx).expect("decomposition should succeed");
+
+    expect![[r#"
+        Block:
+            Call id(2), args( Double(1.5707963267948966), Qubit(1), )
+            Call id(3), args( Qubit(1), )
+            Call id(2), args( Double(1.5707963267948966), Qubit(1), )
+            Call id(4), args( Qubit(0), Qubit(1), )
+            Call id(2), args( Double(1.5707963267948966), Qubit(1), )
+            Call id(3), args( Qubit(1), )
+            Call id(2), args( Double(1.5707963267948966), Qubit(1), )
+            Call id(1), args( Qubit(1), Result(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    expect![[r#"
+        1: __quantum__qis__m__body
+        2: __quantum__qis__rz__body
+        3: __quantum__qis__sx__body
+        4: __quantum__qis__cz__body
+    "#]]
+    .assert_eq(&callable_names(&program));
+}
+
+#[test]
+fn rx_by_multiple_of_pi_over_4_decomposes_into_h_and_t() {
+    let mut program = program_with_calls(
+        vec![builder::rx_decl()],
+        vec![(0, vec![double(FRAC_PI_2), qubit(0)])],
+    );
+
+    decompose_gates(&mut program, GateSet::CxHT).expect("decomposition should succeed");
+
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(0), )
+            Call id(2), args( Qubit(0), )
+            Call id(1), args( Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    expect![[r#"
+        1: __quantum__qis__h__body
+        2: __quantum__qis__t__body
+    "#]]
+    .assert_eq(&callable_names(&program));
+}
+
+#[test]
+fn ccx_decomposes_into_seven_t_gates() {
+    let mut program = program_with_calls(
+        vec![Callable {
+            name: "__quantum__qis__ccx__body".to_string(),
+            input_type: vec![Ty::Qubit, Ty::Qubit, Ty::Qubit],
+            output_type: None,
+            body: None,
+            call_type: CallableType::Regular,
+        }],
+        vec![(0, vec![qubit(0), qubit(1), qubit(2)])],
+    );
+
+    decompose_gates(&mut program, GateSet::CxHT).expect("decomposition should succeed");
+
+    assert_eq!(
+        count_calls(&program, "__quantum__qis__t__body")
+            + count_calls(&program, "__quantum__qis__t__adj"),
+        7
+    );
+    assert_eq!(count_calls(&program, "__quantum__qis__cx__body"), 6);
+    assert_eq!(count_calls(&program, "__quantum__qis__h__body"), 2);
+}
+
+#[test]
+fn inexact_rotation_fails_and_leaves_program_unchanged() {
+    let mut program = program_with_calls(
+        vec![builder::rx_decl()],
+        vec![(0, vec![double(0.3), qubit(0)])],
+    );
+    let original = program.to_string();
+
+    assert_eq!(
+        decompose_gates(&mut program, GateSet::CxHT),
+        Err(Error::InexactRotation(0.3, GateSet::CxHT))
+    );
+    assert_eq!(program.to_string(), original);
+}
+
+#[test]
+fn dynamic_rotation_fails_for_clifford_t() {
+    let angle = Operand::Variable(Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Double,
+    });
+    let mut program = program_with_calls(
+        vec![Callable {
+            name: "__quantum__qis__rz__body".to_string(),
+            input_type: vec![Ty::Double, Ty::Qubit],
+            output_type: None,
+            body: None,
+            call_type: CallableType::Regular,
+        }],
+        vec![(0, vec![angle, qubit(0)])],
+    );
+
+    assert_eq!(
+        decompose_gates(&mut program.clone(), GateSet::CxHT),
+        Err(Error::DynamicRotation(GateSet::CxHT))
+    );
+    decompose_gates(&mut program, GateSet::CzRzSx).expect("decomposition should succeed");
+}
+
+#[test]
+fn unknown_gate_fails() {
+    let mut program = program_with_calls(
+        vec![Callable {
+            name: "__quantum__qis__custom__body".to_string(),
+            input_type: vec![Ty::Qubit],
+            output_type: None,
+            body: None,
+            call_type: CallableType::Regular,
+        }],
+        vec![(0, vec![qubit(0)])],
+    );
+
+    assert_eq!(
+        decompose_gates(&mut program, GateSet::CzRzSx),
+        Err(Error::UnsupportedGate(
+            "__quantum__qis__custom__body".to_string(),
+            GateSet::CzRzSx
+        ))
+    );
+}
+
+#[test]
+fn barrier_passes_through() {
+    let mut program = program_with_calls(
+        vec![
+            builder::h_decl(),
+            Callable {
+                name: "__quantum__qis__barrier__body".to_string(),
+                input_type: Vec::new(),
+                output_type: None,
+                body: None,
+                call_type: CallableType::Regular,
+            },
+        ],
+        vec![(0, vec![qubit(0)]), (1, Vec::new()), (0, vec![qubit(0)])],
+    );
+
+    decompose_gates(&mut program, GateSet::CxHT).expect("decomposition should succeed");
+
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(0), )
+            Call id(1), args( )
+            Call id(0), args( Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
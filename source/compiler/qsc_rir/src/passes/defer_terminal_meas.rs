@@ -0,0 +1,120 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use super::{
+    ConditionalLoweringError, lower_conditionals::lower_measurement_conditionals_only,
+    simplify_control_flow::simplify_control_flow,
+};
+use crate::{
+    builder,
+    rir::{Callable, CallableType, Instruction, Literal, Operand, Program},
+};
+
+/// Moves measurements to the end of the circuit wherever that does not change the behavior of the
+/// program, after first replacing branches on measurement results with quantum controls as
+/// [`lower_measurement_conditionals`](super::lower_measurement_conditionals) does. Branches on
+/// classical values are left in place.
+///
+/// Unlike `defer_measurements`, which requires that measured qubits are never used again, the moves
+/// this pass makes are legal for any program: a measurement is only moved past the instructions
+/// that follow it in its block when none of them read its result, and every one of them either
+/// leaves the measured qubit untouched or acts on it only through a gate that is diagonal in the
+/// computational basis, such as the control of a CNOT. Measurements that reset the qubit are never
+/// moved. Deferred measurements are placed before the output recordings and terminator at the end
+/// of their block, keeping their relative order.
+///
+/// Returns the errors from lowering conditionals, leaving the program unchanged, if any branch on a
+/// measurement result cannot be lowered.
+pub fn defer_terminal_measurements(
+    program: &mut Program,
+) -> Result<(), Vec<ConditionalLoweringError>> {
+    lower_measurement_conditionals_only(program)?;
+    simplify_control_flow(program);
+
+    let block_ids = program.blocks.iter().map(|(id, _)| id).collect::<Vec<_>>();
+    for block_id in block_ids {
+        let instrs = &program.get_block(block_id).0;
+        let tail_start = instrs
+            .iter()
+            .rposition(|instr| match instr {
+                Instruction::Return | Instruction::Branch(..) | Instruction::Jump(..) => false,
+                Instruction::Call(id, _, _) => {
+                    program.get_callable(*id).call_type != CallableType::OutputRecording
+                }
+                _ => true,
+            })
+            .map_or(0, |idx| idx + 1);
+
+        let mut kept = Vec::new();
+        let mut deferred = Vec::new();
+        for (idx, instr) in instrs[..tail_start].iter().enumerate() {
+            if is_deferrable(program, instr, &instrs[idx + 1..tail_start]) {
+                deferred.push(instr.clone());
+            } else {
+                kept.push(instr.clone());
+            }
+        }
+        if deferred.is_empty() {
+            continue;
+        }
+        kept.extend(deferred);
+        kept.extend(instrs[tail_start..].iter().cloned());
+        program.get_block_mut(block_id).0 = kept;
+    }
+    Ok(())
+}
+
+/// Returns whether the instruction is a measurement that commutes with every later instruction.
+fn is_deferrable(program: &Program, instr: &Instruction, later: &[Instruction]) -> bool {
+    let Instruction::Call(id, args, _) = instr else {
+        return false;
+    };
+    let callable = program.get_callable(*id);
+    if callable.call_type != CallableType::Measurement
+        || callable.name == builder::mresetz_decl().name
+    {
+        return false;
+    }
+    let &[
+        Operand::Literal(Literal::Qubit(qubit)),
+        Operand::Literal(Literal::Result(result)),
+    ] = args.as_slice()
+    else {
+        return false;
+    };
+    later.iter().all(|instr| match instr {
+        Instruction::Call(id, args, _) => {
+            let callable = program.get_callable(*id);
+            let diagonal = diagonal_qubit_args(callable);
+            !args.contains(&Operand::Literal(Literal::Result(result)))
+                && args.iter().enumerate().all(|(idx, arg)| {
+                    *arg != Operand::Literal(Literal::Qubit(qubit)) || diagonal.contains(&idx)
+                })
+        }
+        _ => true,
+    })
+}
+
+/// Returns the positions of the qubit arguments through which a gate acts diagonally in the
+/// computational basis, so that a measurement of those qubits commutes with the gate.
+fn diagonal_qubit_args(callable: &Callable) -> &'static [usize] {
+    if callable.call_type != CallableType::Regular {
+        return &[];
+    }
+    match callable.name.as_str() {
+        "__quantum__qis__z__body"
+        | "__quantum__qis__s__body"
+        | "__quantum__qis__s__adj"
+        | "__quantum__qis__t__body"
+        | "__quantum__qis__t__adj"
+        | "__quantum__qis__cx__body"
+        | "__quantum__qis__cy__body" => &[0],
+        "__quantum__qis__cz__body" | "__quantum__qis__ccx__body" => &[0, 1],
+        "__quantum__qis__rz__body" => &[1],
+        "__quantum__qis__rzz__body" => &[1, 2],
+        _ => &[],
+    }
+}
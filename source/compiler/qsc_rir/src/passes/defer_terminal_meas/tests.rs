@@ -0,0 +1,233 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use crate::{
+    builder,
+    passes::ConditionalLoweringError,
+    rir::{
+        Block, BlockId, CallableId, Instruction, Literal, Operand, Program, Ty, Variable,
+        VariableId,
+    },
+};
+use expect_test::expect;
+
+use super::defer_terminal_measurements;
+
+const M: CallableId = CallableId(0);
+const READ_RESULT: CallableId = CallableId(1);
+const X: CallableId = CallableId(2);
+const H: CallableId = CallableId(3);
+const RECORD: CallableId = CallableId(4);
+
+fn qubit(id: u32) -> Operand {
+    Operand::Literal(Literal::Qubit(id))
+}
+
+fn result(id: u32) -> Operand {
+    Operand::Literal(Literal::Result(id))
+}
+
+fn bool_var(id: u32) -> Variable {
+    Variable {
+        variable_id: VariableId(id),
+        ty: Ty::Boolean,
+    }
+}
+
+fn program_with_blocks(blocks: Vec<Vec<Instruction>>) -> Program {
+    let mut program = Program::default();
+    program.num_qubits = 2;
+    program.num_results = 2;
+    program.callables.insert(M, builder::m_decl());
+    program
+        .callables
+        .insert(READ_RESULT, builder::read_result_decl());
+    program.callables.insert(X, builder::x_decl());
+    program.callables.insert(H, builder::h_decl());
+    program
+        .callables
+        .insert(RECORD, builder::result_record_decl());
+    for (id, instrs) in blocks.into_iter().enumerate() {
+        program.blocks.insert(
+            BlockId(u32::try_from(id).expect("block id should fit in u32")),
+            Block(instrs),
+        );
+    }
+    program
+}
+
+fn blocks(program: &Program) -> String {
+    program
+        .blocks
+        .iter()
+        .map(|(id, block)| format!("{}: {block}\n", id.0))
+        .collect()
+}
+
+/// Builds a program that measures qubit 0, applies the given gate to qubit 1 if the result is
+/// `One`, and then records the result.
+fn conditional_program(gate: CallableId) -> Program {
+    program_with_blocks(vec![
+        vec![
+            Instruction::Call(M, vec![qubit(0), result(0)], None),
+            Instruction::Call(READ_RESULT, vec![result(0)], Some(bool_var(0))),
+            Instruction::Branch(bool_var(0), BlockId(1), BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(gate, vec![qubit(1)], None),
+            Instruction::Jump(BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(
+                RECORD,
+                vec![result(0), Operand::Literal(Literal::Pointer)],
+                None,
+            ),
+            Instruction::Return,
+        ],
+    ])
+}
+
+#[test]
+fn measurement_is_deferred_past_gate_controlled_on_measured_qubit() {
+    let mut program = conditional_program(X);
+
+    defer_terminal_measurements(&mut program).expect("deferral should succeed");
+
+    expect![[r#"
+        0: Block:
+            Call id(5), args( Qubit(0), Qubit(1), )
+            Call id(0), args( Qubit(0), Result(0), )
+            Call id(4), args( Result(0), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks(&program));
+    assert_eq!(
+        program.get_callable(CallableId(5)).name,
+        "__quantum__qis__cx__body"
+    );
+}
+
+#[test]
+fn measurement_is_not_deferred_past_non_diagonal_gate_on_measured_qubit() {
+    let mut program = program_with_blocks(vec![vec![
+        Instruction::Call(M, vec![qubit(0), result(0)], None),
+        Instruction::Call(H, vec![qubit(0)], None),
+        Instruction::Call(M, vec![qubit(1), result(1)], None),
+        Instruction::Call(X, vec![qubit(0)], None),
+        Instruction::Return,
+    ]]);
+
+    defer_terminal_measurements(&mut program).expect("deferral should succeed");
+
+    expect![[r#"
+        0: Block:
+            Call id(0), args( Qubit(0), Result(0), )
+            Call id(3), args( Qubit(0), )
+            Call id(2), args( Qubit(0), )
+            Call id(0), args( Qubit(1), Result(1), )
+            Return
+    "#]]
+    .assert_eq(&blocks(&program));
+}
+
+#[test]
+fn measurement_with_reset_is_not_deferred() {
+    let mut program = program_with_blocks(vec![vec![
+        Instruction::Call(M, vec![qubit(0), result(0)], None),
+        Instruction::Call(X, vec![qubit(1)], None),
+        Instruction::Return,
+    ]]);
+    program.callables.insert(M, builder::mresetz_decl());
+
+    defer_terminal_measurements(&mut program).expect("deferral should succeed");
+
+    expect![[r#"
+        0: Block:
+            Call id(0), args( Qubit(0), Result(0), )
+            Call id(2), args( Qubit(1), )
+            Return
+    "#]]
+    .assert_eq(&blocks(&program));
+}
+
+#[test]
+fn conditional_that_cannot_be_lowered_is_reported() {
+    let mut program = conditional_program(H);
+    let original = blocks(&program);
+
+    assert_eq!(
+        defer_terminal_measurements(&mut program),
+        Err(vec![ConditionalLoweringError::UnsupportedConditionalGate(
+            "__quantum__qis__h__body".to_string()
+        )])
+    );
+    assert_eq!(blocks(&program), original);
+}
+
+#[test]
+fn conditional_on_qubit_used_after_measurement_is_reported() {
+    let mut program = conditional_program(X);
+    program
+        .blocks
+        .get_mut(BlockId(0))
+        .expect("block should exist")
+        .0
+        .insert(1, Instruction::Call(H, vec![qubit(0)], None));
+    let original = blocks(&program);
+
+    assert_eq!(
+        defer_terminal_measurements(&mut program),
+        Err(vec![
+            ConditionalLoweringError::MeasuredQubitUsedBeforeBranch(
+                "__quantum__qis__h__body".to_string(),
+                0,
+                0
+            )
+        ])
+    );
+    assert_eq!(blocks(&program), original);
+}
+
+#[test]
+fn classical_branch_is_kept_and_measurement_before_it_is_deferred() {
+    let mut program = program_with_blocks(vec![
+        vec![
+            Instruction::Store(Operand::Literal(Literal::Bool(true)), bool_var(0)),
+            Instruction::Call(M, vec![qubit(0), result(0)], None),
+            Instruction::Call(X, vec![qubit(1)], None),
+            Instruction::Branch(bool_var(0), BlockId(1), BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(X, vec![qubit(1)], None),
+            Instruction::Jump(BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(
+                RECORD,
+                vec![result(0), Operand::Literal(Literal::Pointer)],
+                None,
+            ),
+            Instruction::Return,
+        ],
+    ]);
+
+    defer_terminal_measurements(&mut program).expect("deferral should succeed");
+
+    expect![[r#"
+        0: Block:
+            Variable(0, Boolean) = Store Bool(true)
+            Call id(2), args( Qubit(1), )
+            Call id(0), args( Qubit(0), Result(0), )
+            Branch Variable(0, Boolean), 1, 2
+        1: Block:
+            Call id(2), args( Qubit(1), )
+            Jump(2)
+        2: Block:
+            Call id(4), args( Result(0), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks(&program));
+}
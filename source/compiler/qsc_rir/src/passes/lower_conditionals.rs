@@ -0,0 +1,469 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use miette::Diagnostic;
+use qsc_data_structures::index_map::IndexMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use thiserror::Error;
+
+use super::decompose_gates::find_or_add_callable;
+use crate::{
+    builder,
+    rir::{
+        Block, BlockId, Callable, CallableId, CallableType, Instruction, Literal, Operand, Program,
+        VariableId,
+    },
+    utils::{build_predecessors_map, get_all_block_successors},
+};
+
+#[derive(Clone, Debug, Diagnostic, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("the branch at the end of block {0} is not conditioned on a measurement result")]
+    #[diagnostic(code("Qsc.Rir.UnsupportedCondition"))]
+    UnsupportedCondition(u32),
+
+    #[error(
+        "the result of `{0}` cannot be used as a condition because the measured qubit is reset"
+    )]
+    #[diagnostic(help(
+        "measure without resetting so that the measured qubit can control the conditional gates"
+    ))]
+    #[diagnostic(code("Qsc.Rir.MeasurementWithReset"))]
+    MeasurementWithReset(String),
+
+    #[error("the blocks of the branch at the end of block {0} do not rejoin immediately")]
+    #[diagnostic(help(
+        "only single conditional blocks that both start and end at the branch can be lowered"
+    ))]
+    #[diagnostic(code("Qsc.Rir.UnsupportedConditionalControlFlow"))]
+    UnsupportedControlFlow(u32),
+
+    #[error("block {0} uses a classical value that depends on a measurement result")]
+    #[diagnostic(code("Qsc.Rir.ConditionalValue"))]
+    ConditionalValue(u32),
+
+    #[error("block {0} contains an instruction that is not a gate")]
+    #[diagnostic(help("only gates can be conditioned on a measurement result"))]
+    #[diagnostic(code("Qsc.Rir.UnsupportedConditionalInstruction"))]
+    UnsupportedConditionalInstruction(u32),
+
+    #[error("gate `{0}` cannot be conditioned on a measurement result")]
+    #[diagnostic(help(
+        "only X, Y, Z and CNOT gates have controlled forms that can replace a conditional"
+    ))]
+    #[diagnostic(code("Qsc.Rir.UnsupportedConditionalGate"))]
+    UnsupportedConditionalGate(String),
+
+    #[error("gate `{0}` is conditioned on the measurement of qubit {1}, which it acts on")]
+    #[diagnostic(code("Qsc.Rir.ConditionalGateOnMeasuredQubit"))]
+    ConditionalGateOnMeasuredQubit(String, u32),
+
+    #[error(
+        "`{0}` acts on qubit {1} between its measurement and the branch at the end of block {2}"
+    )]
+    #[diagnostic(help(
+        "the measured qubit controls the lowered gates, so it must not be used again before the branch"
+    ))]
+    #[diagnostic(code("Qsc.Rir.MeasuredQubitUsedBeforeBranch"))]
+    MeasuredQubitUsedBeforeBranch(String, u32, u32),
+}
+
+/// Lowers branches on measurement results into straight-line code using the deferred measurement
+/// principle: a gate applied only when a qubit was measured as `One` is replaced by the same gate
+/// controlled on that qubit, and a gate applied only when it was measured as `Zero` is additionally
+/// surrounded by X gates on the qubit. This removes the branches from programs such as
+/// `if M(q) == One { X(target); }` so they can be emitted for targets without branching.
+///
+/// Only branches whose blocks contain X, Y, Z and CNOT gates and immediately rejoin are lowered,
+/// and the measured qubit must not be reset by the measurement or used again before the branch.
+/// Returns every construct that cannot be lowered, leaving the program unchanged, if any branch
+/// cannot be lowered.
+pub fn lower_measurement_conditionals(program: &mut Program) -> Result<(), Vec<Error>> {
+    lower_conditionals(program, false)
+}
+
+/// Lowers branches on measurement results like [`lower_measurement_conditionals`], leaving any
+/// branch whose condition is not the reading of a measurement result in place rather than
+/// reporting it.
+pub(crate) fn lower_measurement_conditionals_only(program: &mut Program) -> Result<(), Vec<Error>> {
+    lower_conditionals(program, true)
+}
+
+fn lower_conditionals(program: &mut Program, skip_classical: bool) -> Result<(), Vec<Error>> {
+    let mut pass = LowerConditionalsPass {
+        program,
+        preds: build_predecessors_map(program),
+        measurements: measurements(program),
+        callables: program.callables.clone(),
+    };
+    let mut errors = Vec::new();
+    let mut lowered = Vec::new();
+    for (block_id, block) in program.blocks.iter() {
+        let Some(&Instruction::Branch(cond, true_id, false_id)) = block.0.last() else {
+            continue;
+        };
+        if skip_classical && !pass.is_measurement_branch(block, cond.variable_id) {
+            continue;
+        }
+        match pass.lower_branch(block_id, block, cond.variable_id, true_id, false_id) {
+            Ok(branch) => lowered.push(branch),
+            Err(branch_errors) => errors.extend(branch_errors),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut callables = pass.callables;
+    for branch in lowered {
+        program.blocks.insert(branch.block_id, Block(branch.instrs));
+        for arm_id in branch.removed_blocks {
+            program.blocks.remove(arm_id);
+        }
+    }
+    remove_unused_readouts(program, &mut callables);
+    program.callables = callables;
+    Ok(())
+}
+
+struct LowerConditionalsPass<'a> {
+    program: &'a Program,
+    preds: IndexMap<BlockId, Vec<BlockId>>,
+    /// The qubit measured into each result, along with the measurement callable and where the
+    /// measurement is.
+    measurements: FxHashMap<u32, (u32, CallableId, BlockId, usize)>,
+    callables: IndexMap<CallableId, Callable>,
+}
+
+struct LoweredBranch {
+    block_id: BlockId,
+    instrs: Vec<Instruction>,
+    removed_blocks: Vec<BlockId>,
+}
+
+impl LowerConditionalsPass<'_> {
+    fn lower_branch(
+        &mut self,
+        block_id: BlockId,
+        block: &Block,
+        cond: VariableId,
+        true_id: BlockId,
+        false_id: BlockId,
+    ) -> Result<LoweredBranch, Vec<Error>> {
+        let (result, negated) = self
+            .condition(block, cond)
+            .ok_or_else(|| vec![Error::UnsupportedCondition(block_id.0)])?;
+        let &(control, measurement_id, measurement_block_id, measurement_idx) = self
+            .measurements
+            .get(&result)
+            .ok_or_else(|| vec![Error::UnsupportedCondition(block_id.0)])?;
+        let measurement = self.program.get_callable(measurement_id);
+        if measurement.name == builder::mresetz_decl().name {
+            return Err(vec![Error::MeasurementWithReset(measurement.name.clone())]);
+        }
+        self.check_unused_before_branch(block_id, measurement_block_id, measurement_idx, control)
+            .map_err(|e| vec![e])?;
+        let (true_id, false_id) = if negated {
+            (false_id, true_id)
+        } else {
+            (true_id, false_id)
+        };
+
+        // Each arm is either a block that jumps to where the branch rejoins, or the rejoining block
+        // itself when the arm does nothing.
+        let (rejoin_id, arms) = match (
+            self.arm_target(block_id, true_id),
+            self.arm_target(block_id, false_id),
+        ) {
+            (Some(t), Some(f)) if t == f => (t, vec![(true_id, true), (false_id, false)]),
+            (Some(t), _) if t == false_id => (t, vec![(true_id, true)]),
+            (_, Some(f)) if f == true_id => (f, vec![(false_id, false)]),
+            _ => return Err(vec![Error::UnsupportedControlFlow(block_id.0)]),
+        };
+        if self
+            .program
+            .get_block(rejoin_id)
+            .0
+            .iter()
+            .any(|instr| matches!(instr, Instruction::Phi(..)))
+        {
+            return Err(vec![Error::ConditionalValue(rejoin_id.0)]);
+        }
+
+        let mut errors = Vec::new();
+        let mut instrs = block.0[..block.0.len() - 1].to_vec();
+        let mut removed_blocks = Vec::new();
+        for (arm_id, when_one) in arms {
+            let arm = self.program.get_block(arm_id);
+            let mut gates = Vec::new();
+            for instr in &arm.0[..arm.0.len() - 1] {
+                match self.controlled(arm_id, instr, control) {
+                    Ok(gate) => gates.push(gate),
+                    Err(e) => errors.push(e),
+                }
+            }
+            if !when_one && !gates.is_empty() {
+                let x_id = find_or_add_callable(&mut self.callables, builder::x_decl());
+                let flip = Instruction::Call(x_id, vec![qubit(control)], None);
+                gates.insert(0, flip.clone());
+                gates.push(flip);
+            }
+            instrs.extend(gates);
+            removed_blocks.push(arm_id);
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        instrs.push(Instruction::Jump(rejoin_id));
+        Ok(LoweredBranch {
+            block_id,
+            instrs,
+            removed_blocks,
+        })
+    }
+
+    /// Checks that no instruction that can run between the measurement and the branch acts on the
+    /// measured qubit, since the lowered gates use the qubit as it was measured. This covers gates,
+    /// resets and further measurements of the qubit, as well as reuse of its id after release.
+    fn check_unused_before_branch(
+        &self,
+        branch_id: BlockId,
+        measurement_block_id: BlockId,
+        measurement_idx: usize,
+        control: u32,
+    ) -> Result<(), Error> {
+        let check = |instrs: &[Instruction]| {
+            instrs.iter().try_for_each(|instr| match instr {
+                Instruction::Call(id, args, _) if args.contains(&qubit(control)) => {
+                    Err(Error::MeasuredQubitUsedBeforeBranch(
+                        self.program.get_callable(*id).name.clone(),
+                        control,
+                        branch_id.0,
+                    ))
+                }
+                _ => Ok(()),
+            })
+        };
+
+        let branch_block = &self.program.get_block(branch_id).0;
+        if measurement_block_id == branch_id {
+            return check(&branch_block[measurement_idx + 1..branch_block.len() - 1]);
+        }
+        check(&self.program.get_block(measurement_block_id).0[measurement_idx + 1..])?;
+        check(&branch_block[..branch_block.len() - 1])?;
+
+        // Any block reachable from the measurement that can also reach the branch may run in
+        // between, including the measurement and branch blocks themselves when they are in a loop.
+        let reachable = get_all_block_successors(measurement_block_id, self.program);
+        let mut reaching = FxHashSet::default();
+        let mut to_visit = self.preds.get(branch_id).cloned().unwrap_or_default();
+        while let Some(id) = to_visit.pop() {
+            if reaching.insert(id) {
+                to_visit.extend(self.preds.get(id).into_iter().flatten().copied());
+            }
+        }
+        reachable
+            .into_iter()
+            .filter(|id| reaching.contains(id))
+            .try_for_each(|id| check(&self.program.get_block(id).0))
+    }
+
+    /// Returns true if the branch condition is the reading of a measured result.
+    fn is_measurement_branch(&self, block: &Block, cond: VariableId) -> bool {
+        self.condition(block, cond)
+            .is_some_and(|(result, _)| self.measurements.contains_key(&result))
+    }
+
+    /// Traces a branch condition back through stores and negations to the reading of a result,
+    /// returning the result and whether the condition is negated.
+    fn condition(&self, block: &Block, mut var: VariableId) -> Option<(u32, bool)> {
+        let mut negated = false;
+        for instr in block.0.iter().rev() {
+            match instr {
+                Instruction::Store(Operand::Variable(source), target)
+                    if target.variable_id == var =>
+                {
+                    var = source.variable_id;
+                }
+                Instruction::LogicalNot(Operand::Variable(source), target)
+                    if target.variable_id == var =>
+                {
+                    var = source.variable_id;
+                    negated = !negated;
+                }
+                Instruction::Call(id, args, Some(target)) if target.variable_id == var => {
+                    return match (self.program.get_callable(*id).call_type, args.as_slice()) {
+                        (CallableType::Readout, &[Operand::Literal(Literal::Result(result))]) => {
+                            Some((result, negated))
+                        }
+                        _ => None,
+                    };
+                }
+                Instruction::Store(_, target)
+                | Instruction::LogicalNot(_, target)
+                | Instruction::Call(_, _, Some(target))
+                    if target.variable_id == var =>
+                {
+                    return None;
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Returns the block an arm of a branch jumps to, if the arm is only reached from the branch.
+    fn arm_target(&self, branch_id: BlockId, arm_id: BlockId) -> Option<BlockId> {
+        match (
+            self.program.get_block(arm_id).0.last(),
+            self.preds.get(arm_id).map(Vec::as_slice),
+        ) {
+            (Some(&Instruction::Jump(target)), Some(&[pred])) if pred == branch_id => Some(target),
+            _ => None,
+        }
+    }
+
+    /// Returns the gate in an arm of a branch, controlled on the measured qubit.
+    fn controlled(
+        &mut self,
+        arm_id: BlockId,
+        instr: &Instruction,
+        control: u32,
+    ) -> Result<Instruction, Error> {
+        let Instruction::Call(id, args, None) = instr else {
+            return Err(Error::UnsupportedConditionalInstruction(arm_id.0));
+        };
+        let callable = self.program.get_callable(*id);
+        if callable.call_type != CallableType::Regular {
+            return Err(Error::UnsupportedConditionalInstruction(arm_id.0));
+        }
+        if args.contains(&qubit(control)) {
+            return Err(Error::ConditionalGateOnMeasuredQubit(
+                callable.name.clone(),
+                control,
+            ));
+        }
+        let decl = match (callable.name.as_str(), args.as_slice()) {
+            ("__quantum__qis__x__body", &[_]) => builder::cx_decl(),
+            ("__quantum__qis__y__body", &[_]) => builder::cy_decl(),
+            ("__quantum__qis__z__body", &[_]) => builder::cz_decl(),
+            ("__quantum__qis__cx__body", &[_, _]) => builder::ccx_decl(),
+            _ => return Err(Error::UnsupportedConditionalGate(callable.name.clone())),
+        };
+        let controlled_id = find_or_add_callable(&mut self.callables, decl);
+        let args = [qubit(control)]
+            .into_iter()
+            .chain(args.iter().copied())
+            .collect();
+        Ok(Instruction::Call(controlled_id, args, None))
+    }
+}
+
+fn qubit(id: u32) -> Operand {
+    Operand::Literal(Literal::Qubit(id))
+}
+
+/// Finds the qubit measured into each result, along with the measurement callable and the block
+/// and index of the measurement.
+fn measurements(program: &Program) -> FxHashMap<u32, (u32, CallableId, BlockId, usize)> {
+    let mut measurements = FxHashMap::default();
+    for (block_id, block) in program.blocks.iter() {
+        for (idx, instr) in block.0.iter().enumerate() {
+            if let Instruction::Call(id, args, _) = instr
+                && program.get_callable(*id).call_type == CallableType::Measurement
+                && let &[
+                    Operand::Literal(Literal::Qubit(q)),
+                    Operand::Literal(Literal::Result(r)),
+                ] = args.as_slice()
+            {
+                measurements.insert(r, (q, *id, block_id, idx));
+            }
+        }
+    }
+    measurements
+}
+
+/// Removes the readouts of results, and the stores and negations of their values, that are no
+/// longer used now that the branches on them are gone. Readout callables that are no longer called
+/// are removed too.
+fn remove_unused_readouts(program: &mut Program, callables: &mut IndexMap<CallableId, Callable>) {
+    loop {
+        let mut removed = false;
+        for block_id in program.blocks.iter().map(|(id, _)| id).collect::<Vec<_>>() {
+            let unused = program
+                .get_block(block_id)
+                .0
+                .iter()
+                .position(|instr| match instr {
+                    Instruction::Store(Operand::Variable(_), target)
+                    | Instruction::LogicalNot(Operand::Variable(_), target) => {
+                        !is_used(program, target.variable_id)
+                    }
+                    Instruction::Call(id, _, Some(target)) => {
+                        callables
+                            .get(*id)
+                            .is_some_and(|callable| callable.call_type == CallableType::Readout)
+                            && !is_used(program, target.variable_id)
+                    }
+                    _ => false,
+                });
+            if let Some(idx) = unused {
+                program
+                    .blocks
+                    .get_mut(block_id)
+                    .expect("block should exist")
+                    .0
+                    .remove(idx);
+                removed = true;
+            }
+        }
+        if !removed {
+            break;
+        }
+    }
+
+    callables.retain(|id, callable| {
+        callable.call_type != CallableType::Readout
+            || program.blocks.iter().any(|(_, block)| {
+                block.0.iter().any(
+                    |instr| matches!(instr, Instruction::Call(call_id, _, _) if *call_id == id),
+                )
+            })
+    });
+}
+
+fn is_used(program: &Program, var: VariableId) -> bool {
+    let uses = |operand: &Operand| matches!(operand, Operand::Variable(v) if v.variable_id == var);
+    program.blocks.iter().any(|(_, block)| {
+        block.0.iter().any(|instr| match instr {
+            Instruction::Store(operand, _)
+            | Instruction::LogicalNot(operand, _)
+            | Instruction::BitwiseNot(operand, _) => uses(operand),
+            Instruction::Add(lhs, rhs, _)
+            | Instruction::Sub(lhs, rhs, _)
+            | Instruction::Mul(lhs, rhs, _)
+            | Instruction::Sdiv(lhs, rhs, _)
+            | Instruction::Srem(lhs, rhs, _)
+            | Instruction::Shl(lhs, rhs, _)
+            | Instruction::Ashr(lhs, rhs, _)
+            | Instruction::Fadd(lhs, rhs, _)
+            | Instruction::Fsub(lhs, rhs, _)
+            | Instruction::Fmul(lhs, rhs, _)
+            | Instruction::Fdiv(lhs, rhs, _)
+            | Instruction::Fcmp(_, lhs, rhs, _)
+            | Instruction::Icmp(_, lhs, rhs, _)
+            | Instruction::LogicalAnd(lhs, rhs, _)
+            | Instruction::LogicalOr(lhs, rhs, _)
+            | Instruction::BitwiseAnd(lhs, rhs, _)
+            | Instruction::BitwiseOr(lhs, rhs, _)
+            | Instruction::BitwiseXor(lhs, rhs, _) => uses(lhs) || uses(rhs),
+            Instruction::Call(_, args, _) => args.iter().any(uses),
+            Instruction::Phi(args, _) => args.iter().any(|(operand, _)| uses(operand)),
+            Instruction::Branch(v, _, _) => v.variable_id == var,
+            Instruction::Jump(_) | Instruction::Return => false,
+        })
+    })
+}
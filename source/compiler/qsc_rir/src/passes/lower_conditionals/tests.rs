@@ -0,0 +1,276 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use crate::{
+    builder,
+    rir::{
+        Block, BlockId, CallableId, ConditionCode, Instruction, Literal, Operand, Program, Ty,
+        Variable, VariableId,
+    },
+};
+use expect_test::expect;
+
+use super::{Error, lower_measurement_conditionals};
+
+const M: CallableId = CallableId(0);
+const READ_RESULT: CallableId = CallableId(1);
+const X: CallableId = CallableId(2);
+const H: CallableId = CallableId(3);
+const RESET: CallableId = CallableId(5);
+
+fn qubit(id: u32) -> Operand {
+    Operand::Literal(Literal::Qubit(id))
+}
+
+fn bool_var(id: u32) -> Variable {
+    Variable {
+        variable_id: VariableId(id),
+        ty: Ty::Boolean,
+    }
+}
+
+/// Builds a program that measures qubit 0 and branches on the result, where the condition is
+/// computed by the given instructions into variable 1.
+fn branch_program(condition: Vec<Instruction>, arm: Vec<Instruction>) -> Program {
+    let mut program = Program::default();
+    program.num_qubits = 2;
+    program.callables.insert(M, builder::m_decl());
+    program
+        .callables
+        .insert(READ_RESULT, builder::read_result_decl());
+    program.callables.insert(X, builder::x_decl());
+    program.callables.insert(H, builder::h_decl());
+    let mut entry = vec![
+        Instruction::Call(
+            M,
+            vec![qubit(0), Operand::Literal(Literal::Result(0))],
+            None,
+        ),
+        Instruction::Call(
+            READ_RESULT,
+            vec![Operand::Literal(Literal::Result(0))],
+            Some(bool_var(0)),
+        ),
+    ];
+    entry.extend(condition);
+    entry.push(Instruction::Branch(bool_var(1), BlockId(1), BlockId(2)));
+    program.blocks.insert(BlockId(0), Block(entry));
+    let mut arm = arm;
+    arm.push(Instruction::Jump(BlockId(2)));
+    program.blocks.insert(BlockId(1), Block(arm));
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Return]));
+    program
+}
+
+fn blocks(program: &Program) -> String {
+    program
+        .blocks
+        .iter()
+        .map(|(id, block)| format!("{}: {block}\n", id.0))
+        .collect()
+}
+
+#[test]
+fn gate_conditioned_on_one_becomes_controlled_gate() {
+    let mut program = branch_program(
+        vec![Instruction::Store(
+            Operand::Variable(bool_var(0)),
+            bool_var(1),
+        )],
+        vec![Instruction::Call(X, vec![qubit(1)], None)],
+    );
+
+    lower_measurement_conditionals(&mut program).expect("lowering should succeed");
+
+    expect![[r#"
+        0: Block:
+            Call id(0), args( Qubit(0), Result(0), )
+            Call id(4), args( Qubit(0), Qubit(1), )
+            Jump(2)
+        2: Block:
+            Return
+    "#]]
+    .assert_eq(&blocks(&program));
+    assert_eq!(
+        program.get_callable(CallableId(4)).name,
+        "__quantum__qis__cx__body"
+    );
+    assert!(program.callables.get(READ_RESULT).is_none());
+}
+
+#[test]
+fn gate_conditioned_on_zero_is_surrounded_by_x_on_control() {
+    let mut program = branch_program(
+        vec![Instruction::LogicalNot(
+            Operand::Variable(bool_var(0)),
+            bool_var(1),
+        )],
+        vec![Instruction::Call(X, vec![qubit(1)], None)],
+    );
+
+    lower_measurement_conditionals(&mut program).expect("lowering should succeed");
+
+    expect![[r#"
+        0: Block:
+            Call id(0), args( Qubit(0), Result(0), )
+            Call id(2), args( Qubit(0), )
+            Call id(4), args( Qubit(0), Qubit(1), )
+            Call id(2), args( Qubit(0), )
+            Jump(2)
+        2: Block:
+            Return
+    "#]]
+    .assert_eq(&blocks(&program));
+}
+
+#[test]
+fn every_construct_that_cannot_be_lowered_is_reported() {
+    let mut program = branch_program(
+        vec![Instruction::Store(
+            Operand::Variable(bool_var(0)),
+            bool_var(1),
+        )],
+        vec![
+            Instruction::Call(H, vec![qubit(1)], None),
+            Instruction::Call(X, vec![qubit(0)], None),
+        ],
+    );
+    let original = blocks(&program);
+
+    assert_eq!(
+        lower_measurement_conditionals(&mut program),
+        Err(vec![
+            Error::UnsupportedConditionalGate("__quantum__qis__h__body".to_string()),
+            Error::ConditionalGateOnMeasuredQubit("__quantum__qis__x__body".to_string(), 0),
+        ])
+    );
+    assert_eq!(blocks(&program), original);
+}
+
+#[test]
+fn measurement_with_reset_cannot_be_a_condition() {
+    let mut program = branch_program(
+        vec![Instruction::Store(
+            Operand::Variable(bool_var(0)),
+            bool_var(1),
+        )],
+        vec![Instruction::Call(X, vec![qubit(1)], None)],
+    );
+    program.callables.insert(M, builder::mresetz_decl());
+
+    assert_eq!(
+        lower_measurement_conditionals(&mut program),
+        Err(vec![Error::MeasurementWithReset(
+            "__quantum__qis__mresetz__body".to_string()
+        )])
+    );
+}
+
+#[test]
+fn branch_on_computed_value_is_unsupported() {
+    let mut program = branch_program(
+        vec![Instruction::Icmp(
+            ConditionCode::Eq,
+            Operand::Variable(bool_var(0)),
+            Operand::Literal(Literal::Bool(true)),
+            bool_var(1),
+        )],
+        vec![Instruction::Call(X, vec![qubit(1)], None)],
+    );
+
+    assert_eq!(
+        lower_measurement_conditionals(&mut program),
+        Err(vec![Error::UnsupportedCondition(0)])
+    );
+}
+
+fn check_measured_qubit_used_before_branch(used: Instruction, name: &str) {
+    let mut program = branch_program(
+        vec![
+            used,
+            Instruction::Store(Operand::Variable(bool_var(0)), bool_var(1)),
+        ],
+        vec![Instruction::Call(X, vec![qubit(1)], None)],
+    );
+    program.callables.insert(RESET, builder::reset_decl());
+    let original = blocks(&program);
+
+    assert_eq!(
+        lower_measurement_conditionals(&mut program),
+        Err(vec![Error::MeasuredQubitUsedBeforeBranch(
+            name.to_string(),
+            0,
+            0
+        )])
+    );
+    assert_eq!(blocks(&program), original);
+}
+
+#[test]
+fn gate_on_measured_qubit_before_branch_is_unsupported() {
+    check_measured_qubit_used_before_branch(
+        Instruction::Call(H, vec![qubit(0)], None),
+        "__quantum__qis__h__body",
+    );
+}
+
+#[test]
+fn reset_of_measured_qubit_before_branch_is_unsupported() {
+    check_measured_qubit_used_before_branch(
+        Instruction::Call(RESET, vec![qubit(0)], None),
+        "__quantum__qis__reset__body",
+    );
+}
+
+#[test]
+fn second_measurement_of_measured_qubit_before_branch_is_unsupported() {
+    check_measured_qubit_used_before_branch(
+        Instruction::Call(
+            M,
+            vec![qubit(0), Operand::Literal(Literal::Result(1))],
+            None,
+        ),
+        "__quantum__qis__m__body",
+    );
+}
+
+#[test]
+fn reuse_of_measured_qubit_id_in_intermediate_block_is_unsupported() {
+    // The measurement is in block 3, which jumps through block 4, where the released id of the
+    // measured qubit is reused, to the branch in block 0.
+    let mut program = branch_program(
+        vec![Instruction::Store(
+            Operand::Variable(bool_var(0)),
+            bool_var(1),
+        )],
+        vec![Instruction::Call(X, vec![qubit(1)], None)],
+    );
+    let entry = program.get_block(BlockId(0)).0.clone();
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![entry[0].clone(), Instruction::Jump(BlockId(4))]),
+    );
+    program.blocks.insert(
+        BlockId(4),
+        Block(vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Jump(BlockId(0)),
+        ]),
+    );
+    program
+        .blocks
+        .insert(BlockId(0), Block(entry[1..].to_vec()));
+
+    assert_eq!(
+        lower_measurement_conditionals(&mut program),
+        Err(vec![Error::MeasuredQubitUsedBeforeBranch(
+            "__quantum__qis__h__body".to_string(),
+            0,
+            0
+        )])
+    );
+}
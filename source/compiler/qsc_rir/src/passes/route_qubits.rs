@@ -0,0 +1,246 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::VecDeque;
+
+use miette::Diagnostic;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::decompose_gates::find_or_add_callable;
+use crate::{
+    builder,
+    rir::{BlockId, Instruction, Literal, Operand, Program},
+};
+
+#[derive(Clone, Debug, Diagnostic, Error, PartialEq)]
+pub enum Error {
+    #[error("invalid coupling map: {0}")]
+    #[diagnostic(code("Qsc.Rir.InvalidCouplingMap"))]
+    InvalidCouplingMap(String),
+
+    #[error("program uses {0} qubits but the device only has {1}")]
+    #[diagnostic(code("Qsc.Rir.TooManyQubits"))]
+    TooManyQubits(u32, u32),
+
+    #[error("physical qubits {0} and {1} are not connected in the coupling map")]
+    #[diagnostic(code("Qsc.Rir.DisconnectedQubits"))]
+    DisconnectedQubits(u32, u32),
+
+    #[error("gate `{0}` acts on more than two qubits")]
+    #[diagnostic(help("decompose the program into one- and two-qubit gates before routing"))]
+    #[diagnostic(code("Qsc.Rir.UnroutableGate"))]
+    UnroutableGate(String),
+
+    #[error("routing is only supported for programs with a single block")]
+    #[diagnostic(code("Qsc.Rir.RoutingControlFlow"))]
+    ControlFlow,
+}
+
+/// The physical qubits of a device and the pairs of them that two-qubit gates can act on.
+/// Couplings are undirected.
+///
+/// In JSON, this is written as `{ "numQubits": 3, "edges": [[0, 1], [1, 2]] }`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CouplingMap {
+    pub num_qubits: u32,
+    pub edges: Vec<(u32, u32)>,
+}
+
+impl CouplingMap {
+    /// Parses a coupling map from JSON, checking that every edge is between two distinct qubits of
+    /// the device.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let map = serde_json::from_str::<Self>(json)
+            .map_err(|e| Error::InvalidCouplingMap(e.to_string()))?;
+        for &(a, b) in &map.edges {
+            if a >= map.num_qubits || b >= map.num_qubits {
+                return Err(Error::InvalidCouplingMap(format!(
+                    "edge ({a}, {b}) refers to a qubit outside of the device"
+                )));
+            }
+            if a == b {
+                return Err(Error::InvalidCouplingMap(format!(
+                    "edge ({a}, {b}) couples a qubit to itself"
+                )));
+            }
+        }
+        Ok(map)
+    }
+
+    fn neighbors(&self) -> Vec<Vec<u32>> {
+        let mut neighbors = vec![Vec::new(); self.num_qubits as usize];
+        for &(a, b) in &self.edges {
+            neighbors[a as usize].push(b);
+            neighbors[b as usize].push(a);
+        }
+        neighbors
+    }
+}
+
+/// The outcome of routing a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoutingReport {
+    /// The number of SWAP gates that were inserted.
+    pub swap_count: usize,
+    /// The physical qubit that holds each logical qubit at the end of the program.
+    pub final_layout: Vec<u32>,
+}
+
+/// Maps the logical qubits of the program onto the physical qubits of a device so that every
+/// two-qubit gate acts on coupled qubits. Logical qubit `i` starts on physical qubit `i`, and when a
+/// gate acts on uncoupled qubits, SWAP gates move the first qubit along a shortest path towards the
+/// second. All later calls use the updated layout.
+/// Note that this pass has several assumptions:
+/// 1. The program is a single block, as produced for targets without branching.
+/// 2. No call acts on more than two qubits, which `decompose_gates` can ensure.
+/// 3. No dynamic qubits are used.
+///
+/// Returns an error, leaving the program unchanged, if the program cannot be routed.
+pub fn route_qubits(
+    program: &mut Program,
+    coupling_map: &CouplingMap,
+) -> Result<RoutingReport, Error> {
+    if program.num_qubits > coupling_map.num_qubits {
+        return Err(Error::TooManyQubits(
+            program.num_qubits,
+            coupling_map.num_qubits,
+        ));
+    }
+    if program.blocks.iter().count() != 1 {
+        return Err(Error::ControlFlow);
+    }
+
+    let neighbors = coupling_map.neighbors();
+    // Unused physical qubits are treated as holding logical qubits beyond those of the program, so
+    // that the layout is always a permutation.
+    let mut layout = (0..coupling_map.num_qubits).collect::<Vec<_>>();
+    let mut callables = program.callables.clone();
+    let mut swap_id = None;
+    let mut swap_count = 0;
+    let mut instrs = Vec::new();
+
+    for instr in &program.get_block(BlockId(0)).0 {
+        let Instruction::Call(callable_id, args, var) = instr else {
+            instrs.push(instr.clone());
+            continue;
+        };
+        let qubits = args
+            .iter()
+            .filter_map(|arg| match arg {
+                Operand::Literal(Literal::Qubit(q)) => Some(*q),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        match qubits.as_slice() {
+            [] | [_] => {}
+            &[a, b] => {
+                let path =
+                    shortest_path(&neighbors, layout[a as usize], layout[b as usize]).ok_or(
+                        Error::DisconnectedQubits(layout[a as usize], layout[b as usize]),
+                    )?;
+                // Move the first qubit until it is next to the second, which is the last step of the path.
+                for pair in path[..path.len() - 1].windows(2) {
+                    let id = *swap_id.get_or_insert_with(|| {
+                        find_or_add_callable(&mut callables, builder::swap_decl())
+                    });
+                    instrs.push(Instruction::Call(
+                        id,
+                        vec![
+                            Operand::Literal(Literal::Qubit(pair[0])),
+                            Operand::Literal(Literal::Qubit(pair[1])),
+                        ],
+                        None,
+                    ));
+                    swap_physical(&mut layout, pair[0], pair[1]);
+                    swap_count += 1;
+                }
+            }
+            _ => {
+                return Err(Error::UnroutableGate(
+                    program.get_callable(*callable_id).name.clone(),
+                ));
+            }
+        }
+        let args = args
+            .iter()
+            .map(|arg| match arg {
+                Operand::Literal(Literal::Qubit(q)) => {
+                    Operand::Literal(Literal::Qubit(layout[*q as usize]))
+                }
+                _ => *arg,
+            })
+            .collect();
+        instrs.push(Instruction::Call(*callable_id, args, *var));
+    }
+
+    let final_layout = layout[..program.num_qubits as usize].to_vec();
+    if swap_count > 0 {
+        program.num_qubits = program.num_qubits.max(
+            instrs
+                .iter()
+                .flat_map(|instr| match instr {
+                    Instruction::Call(_, args, _) => args.as_slice(),
+                    _ => &[][..],
+                })
+                .filter_map(|arg| match arg {
+                    Operand::Literal(Literal::Qubit(q)) => Some(q + 1),
+                    _ => None,
+                })
+                .max()
+                .unwrap_or_default(),
+        );
+    }
+    program
+        .blocks
+        .get_mut(BlockId(0))
+        .expect("block should exist")
+        .0 = instrs;
+    program.callables = callables;
+    Ok(RoutingReport {
+        swap_count,
+        final_layout,
+    })
+}
+
+/// Exchanges the logical qubits held by two physical qubits.
+fn swap_physical(layout: &mut [u32], a: u32, b: u32) {
+    for physical in layout.iter_mut() {
+        if *physical == a {
+            *physical = b;
+        } else if *physical == b {
+            *physical = a;
+        }
+    }
+}
+
+/// Finds a shortest path between two physical qubits by breadth-first search, including both ends.
+fn shortest_path(neighbors: &[Vec<u32>], from: u32, to: u32) -> Option<Vec<u32>> {
+    let mut prev = vec![None; neighbors.len()];
+    let mut queue = VecDeque::from([from]);
+    prev[from as usize] = Some(from);
+    while let Some(q) = queue.pop_front() {
+        if q == to {
+            let mut path = vec![to];
+            while let Some(&p) = path.last() {
+                if p == from {
+                    break;
+                }
+                path.push(prev[p as usize].expect("visited qubit should have a predecessor"));
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &next in &neighbors[q as usize] {
+            if prev[next as usize].is_none() {
+                prev[next as usize] = Some(q);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,131 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use crate::{
+    builder,
+    rir::{Block, BlockId, CallableId, Instruction, Literal, Operand, Program},
+};
+use expect_test::expect;
+
+use super::{CouplingMap, Error, RoutingReport, route_qubits};
+
+fn line(num_qubits: u32) -> CouplingMap {
+    CouplingMap {
+        num_qubits,
+        edges: (1..num_qubits).map(|q| (q - 1, q)).collect(),
+    }
+}
+
+fn cx_program(num_qubits: u32, pairs: &[(u32, u32)]) -> Program {
+    let mut program = Program::default();
+    program.num_qubits = num_qubits;
+    program.callables.insert(CallableId(0), builder::cx_decl());
+    let mut instrs = pairs
+        .iter()
+        .map(|&(c, t)| {
+            Instruction::Call(
+                CallableId(0),
+                vec![
+                    Operand::Literal(Literal::Qubit(c)),
+                    Operand::Literal(Literal::Qubit(t)),
+                ],
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+    instrs.push(Instruction::Return);
+    program.blocks.insert(BlockId(0), Block(instrs));
+    program
+}
+
+#[test]
+fn coupling_map_parses_from_json() {
+    assert_eq!(
+        CouplingMap::from_json(r#"{ "numQubits": 3, "edges": [[0, 1], [1, 2]] }"#),
+        Ok(line(3))
+    );
+}
+
+#[test]
+fn coupling_map_with_edge_outside_device_is_invalid() {
+    assert_eq!(
+        CouplingMap::from_json(r#"{ "numQubits": 2, "edges": [[0, 2]] }"#),
+        Err(Error::InvalidCouplingMap(
+            "edge (0, 2) refers to a qubit outside of the device".to_string()
+        ))
+    );
+}
+
+#[test]
+fn connected_gates_are_unchanged() {
+    let mut program = cx_program(3, &[(0, 1), (2, 1)]);
+    let original = program.to_string();
+
+    let report = route_qubits(&mut program, &line(3)).expect("routing should succeed");
+
+    assert_eq!(
+        report,
+        RoutingReport {
+            swap_count: 0,
+            final_layout: vec![0, 1, 2],
+        }
+    );
+    assert_eq!(program.to_string(), original);
+}
+
+#[test]
+fn distant_gate_inserts_swaps_and_later_gates_use_new_layout() {
+    let mut program = cx_program(4, &[(0, 3), (0, 2)]);
+
+    let report = route_qubits(&mut program, &line(4)).expect("routing should succeed");
+
+    assert_eq!(
+        report,
+        RoutingReport {
+            swap_count: 2,
+            final_layout: vec![2, 0, 1, 3],
+        }
+    );
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(0), Qubit(1), )
+            Call id(1), args( Qubit(1), Qubit(2), )
+            Call id(0), args( Qubit(2), Qubit(3), )
+            Call id(0), args( Qubit(2), Qubit(1), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    assert_eq!(
+        program.get_callable(CallableId(1)).name,
+        "__quantum__qis__swap__body"
+    );
+}
+
+#[test]
+fn too_many_qubits_fails() {
+    let mut program = cx_program(3, &[(0, 1)]);
+
+    assert_eq!(
+        route_qubits(&mut program, &line(2)),
+        Err(Error::TooManyQubits(3, 2))
+    );
+}
+
+#[test]
+fn disconnected_qubits_fail_and_leave_program_unchanged() {
+    let mut program = cx_program(2, &[(0, 1)]);
+    let original = program.to_string();
+
+    assert_eq!(
+        route_qubits(
+            &mut program,
+            &CouplingMap {
+                num_qubits: 2,
+                edges: Vec::new(),
+            }
+        ),
+        Err(Error::DisconnectedQubits(0, 1))
+    );
+    assert_eq!(program.to_string(), original);
+}
@@ -10,7 +10,7 @@ use qsc::{
     },
     parse::completion::PathKind,
 };
-use std::rc::Rc;
+use std::sync::Arc;
 
 /// Provides additional syntactic context for the cursor offset,
 /// for various special cases where it's needed.
@@ -31,7 +31,7 @@ enum Context<'a> {
         record: &'a Expr,
     },
     /// The cursor is on an attribute.
-    AttrArg(Rc<str>),
+    AttrArg(Arc<str>),
 }
 
 impl<'a> AstContext<'a> {
@@ -120,7 +120,7 @@ impl AstContext<'_> {
     /// Returns the path kind and the path qualifier before the cursor offset.
     ///
     /// Returns `None` if the cursor is not on a path.
-    pub fn path_segment_context(&self) -> Option<(PathKind, Vec<Rc<str>>)> {
+    pub fn path_segment_context(&self) -> Option<(PathKind, Vec<Arc<str>>)> {
         let Some(Context::Path(path_kind)) = self.context else {
             return None;
         };
@@ -153,7 +153,7 @@ impl AstContext<'_> {
 
     /// Returns the name of the attribute, if the cursor is on an attribute argument.
     /// If the cursor is not on an attribute argument, returns `None`.
-    pub fn get_name_of_attr_for_attr_arg(&self) -> Option<Rc<str>> {
+    pub fn get_name_of_attr_for_attr_arg(&self) -> Option<Arc<str>> {
         if let Some(Context::AttrArg(name)) = &self.context {
             Some(name.clone())
         } else {
@@ -169,7 +169,7 @@ impl AstContext<'_> {
             .take_while(|i| i.span.hi < self.offset)
     }
 
-    fn segments_before_offset(&self) -> Vec<Rc<str>> {
+    fn segments_before_offset(&self) -> Vec<Arc<str>> {
         self.idents_before_cursor()
             .map(|i| i.name.clone())
             .collect::<Vec<_>>()
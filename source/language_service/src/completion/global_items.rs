@@ -13,7 +13,7 @@ use qsc::{
     resolve::{Local, NameKind},
 };
 use rustc_hash::FxHashSet;
-use std::{cmp::Ordering, mem::take, rc::Rc};
+use std::{cmp::Ordering, mem::take, sync::Arc};
 
 /// Provides the globals that are visible or importable at the cursor offset.
 pub(super) struct Globals<'a> {
@@ -99,7 +99,7 @@ impl<'a> Globals<'a> {
 
     /// Returns all terms, and any namespaces that may lead to terms, that
     /// match the given qualifier prefix.
-    pub fn expr_names_in(&self, qualifier: &[Rc<str>]) -> Vec<Vec<Completion>> {
+    pub fn expr_names_in(&self, qualifier: &[Arc<str>]) -> Vec<Vec<Completion>> {
         let mut groups = self.items_in(qualifier, NameKind::Term);
 
         groups.extend(self.namespaces_in(qualifier));
@@ -108,7 +108,7 @@ impl<'a> Globals<'a> {
 
     /// Returns all types, and any namespaces that may lead to types, that
     /// match the given qualifier prefix.
-    pub fn type_names_in(&self, qualifier: &[Rc<str>]) -> Vec<Vec<Completion>> {
+    pub fn type_names_in(&self, qualifier: &[Arc<str>]) -> Vec<Vec<Completion>> {
         let mut groups = self.items_in(qualifier, NameKind::Ty);
 
         groups.extend(self.namespaces_in(qualifier));
@@ -117,7 +117,7 @@ impl<'a> Globals<'a> {
 
     /// Returns all importables, and any namespaces that may lead to importables, that
     /// match the given qualifier prefix.
-    pub fn importable_names_in(&self, qualifier: &[Rc<str>]) -> Vec<Vec<Completion>> {
+    pub fn importable_names_in(&self, qualifier: &[Arc<str>]) -> Vec<Vec<Completion>> {
         let mut groups = self.items_in(qualifier, NameKind::Importable);
 
         groups.extend(self.namespaces_in(qualifier));
@@ -125,7 +125,7 @@ impl<'a> Globals<'a> {
     }
 
     /// Returns all namespaces that match the given qualifier prefix.
-    pub fn namespaces_in(&self, qualifier: &[Rc<str>]) -> Vec<Vec<Completion>> {
+    pub fn namespaces_in(&self, qualifier: &[Arc<str>]) -> Vec<Vec<Completion>> {
         let namespaces_matching_qualifier = self.namespaces_matching_qualifier(qualifier);
 
         let mut children = namespaces_matching_qualifier
@@ -177,7 +177,7 @@ impl<'a> Globals<'a> {
     ///     - namespace `A`
     ///     - namespace `B` if an `import B as A;` is in scope
     ///     - namespace `C.A` if an `import C.*` is in scope
-    fn items_in(&'a self, qualifier: &[Rc<str>], name_kind: NameKind) -> Vec<Vec<Completion>> {
+    fn items_in(&'a self, qualifier: &[Arc<str>], name_kind: NameKind) -> Vec<Vec<Completion>> {
         let namespaces = self
             .namespaces_matching_qualifier(qualifier)
             .map(|namespace_id| (namespace_id, Availability::Qualified));
@@ -252,7 +252,7 @@ impl<'a> Globals<'a> {
     /// `A.` inside of the namespace `H` will return `B`, `D` and `G`.
     fn namespaces_matching_qualifier(
         &self,
-        qualifier: &[Rc<str>],
+        qualifier: &[Arc<str>],
     ) -> impl Iterator<Item = NamespaceId> {
         let global_scope = &self.compilation.user_unit().ast.globals;
         self.locals
@@ -485,7 +485,7 @@ struct ItemInfo<'a> {
     namespace_id: NamespaceId,
     /// Can be the original name for the decl,
     /// or the alias if this item is found through an import.
-    name: Rc<str>,
+    name: Arc<str>,
     decl: ItemDecl<'a>,
     availability: Availability,
 }
@@ -517,7 +517,7 @@ enum Availability {
     ///
     /// e.g. `Foo` will appear as `Bar.Foo` if it's under an open
     /// namespace that is aliased as `Bar`.
-    InAliasedNamespace(Rc<str>),
+    InAliasedNamespace(Arc<str>),
     /// Not in scope at all. Needs an auto-import entry.
     NeedImport(NamespaceId),
 }
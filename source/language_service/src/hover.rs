@@ -14,8 +14,8 @@ use qsc::hir::Attr;
 use qsc::line_column::{Encoding, Position, Range};
 use qsc::{Span, ast, hir};
 use std::fmt::Display;
-use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::Arc;
 
 pub(crate) fn get_hover(
     compilation: &Compilation,
@@ -310,14 +310,14 @@ impl HoverGenerator<'_> {
     }
 }
 
-fn get_namespace_name(item: &hir::Item, package: &hir::Package) -> Rc<str> {
+fn get_namespace_name(item: &hir::Item, package: &hir::Package) -> Arc<str> {
     item.parent
         .and_then(|parent_id| package.items.get(parent_id))
         .map_or_else(
-            || Rc::from(""),
+            || Arc::from(""),
             |parent| match &parent.kind {
                 hir::ItemKind::Namespace(namespace, _) => namespace.name(),
-                _ => Rc::from(""),
+                _ => Arc::from(""),
             },
         )
 }
@@ -2,7 +2,7 @@
 // Licensed under the MIT License.
 
 use std::mem::replace;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::compilation::Compilation;
 use qsc::ast::visit::{
@@ -102,9 +102,9 @@ pub(crate) trait Handler<'package> {
 pub(crate) struct LocatorContext<'package> {
     pub(crate) current_callable: Option<&'package ast::CallableDecl>,
     pub(crate) lambda_params: Vec<&'package ast::Pat>,
-    pub(crate) current_item_doc: Rc<str>,
-    pub(crate) current_item_name: Rc<str>,
-    pub(crate) current_namespace: Rc<str>,
+    pub(crate) current_item_doc: Arc<str>,
+    pub(crate) current_item_name: Arc<str>,
+    pub(crate) current_namespace: Arc<str>,
     pub(crate) in_params: bool,
     pub(crate) in_lambda_params: bool,
     pub(crate) current_udt_id: Option<&'package hir::ItemId>,
@@ -128,13 +128,13 @@ impl<'inner, 'package, T: Handler<'package>> Locator<'inner, 'package, T> {
             offset,
             compilation,
             context: LocatorContext {
-                current_namespace: Rc::from(""),
+                current_namespace: Arc::from(""),
                 current_callable: None,
                 in_params: false,
                 lambda_params: vec![],
                 in_lambda_params: false,
-                current_item_doc: Rc::from(""),
-                current_item_name: Rc::from(""),
+                current_item_doc: Arc::from(""),
+                current_item_name: Arc::from(""),
                 current_udt_id: None,
             },
         }
@@ -4,7 +4,7 @@
 #[cfg(test)]
 mod tests;
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::compilation::{Compilation, CompilationKind, source_position_to_package_offset};
 use crate::name_locator::{Handler, Locator, LocatorContext};
@@ -204,7 +204,7 @@ impl<'a> ReferenceFinder<'a> {
         }
     }
 
-    pub fn for_item(&self, item_id: &hir::ItemId, name_filter: Option<&Rc<str>>) -> Vec<Location> {
+    pub fn for_item(&self, item_id: &hir::ItemId, name_filter: Option<&Arc<str>>) -> Vec<Location> {
         let mut locations = vec![];
 
         let (def, _, resolved_item_id) = self
@@ -249,7 +249,7 @@ impl<'a> ReferenceFinder<'a> {
         locations
     }
 
-    pub fn for_field(&self, ty_item_id: &hir::ItemId, field_name: Rc<str>) -> Vec<Location> {
+    pub fn for_field(&self, ty_item_id: &hir::ItemId, field_name: Arc<str>) -> Vec<Location> {
         let mut locations = vec![];
 
         let (ty_def, _, resolved_ty_item_id) = self
@@ -349,7 +349,7 @@ impl<'a> ReferenceFinder<'a> {
 struct FindItemRefs<'a> {
     item_id: &'a hir::ItemId,
     compilation: &'a Compilation,
-    name_filter: Option<&'a Rc<str>>,
+    name_filter: Option<&'a Arc<str>>,
     locations: Vec<Span>,
 }
 
@@ -392,7 +392,7 @@ impl FindItemRefs<'_> {
 
 struct FindFieldRefs<'a> {
     ty_item_id: &'a hir::ItemId,
-    field_name: Rc<str>,
+    field_name: Arc<str>,
     compilation: &'a Compilation,
     locations: Vec<Span>,
 }
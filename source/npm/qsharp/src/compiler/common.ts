@@ -26,6 +26,8 @@ interface DumpMsg {
   state: Dump;
   stateLatex: string | null;
   qubitCount: number;
+  /** The variable names of the allocated qubits, keyed by qubit id. */
+  qubitLabels: Record<string, string>;
 }
 
 interface MatrixMsg {
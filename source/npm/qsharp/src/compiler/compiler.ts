@@ -58,6 +58,7 @@ export interface ICompiler {
     shots: number,
     pauliNoise: number[],
     qubitLoss: number,
+    tolerance: [number, number] | undefined,
     eventHandler: IQscEventTarget,
   ): Promise<void>;
 
@@ -188,6 +189,7 @@ export class Compiler implements ICompiler {
     shots: number,
     pauliNoise: number[],
     qubitLoss: number,
+    tolerance: [number, number] | undefined,
     eventHandler: IQscEventTarget,
   ): Promise<void> {
     await callAndTransformExceptions(async () =>
@@ -198,6 +200,7 @@ export class Compiler implements ICompiler {
         shots!,
         pauliNoise,
         qubitLoss,
+        tolerance,
       ),
     );
   }
@@ -324,6 +327,7 @@ export function onCompilerEvent(msg: string, eventTarget: IQscEventTarget) {
         state: qscMsg.state,
         stateLatex: qscMsg.stateLatex,
         qubitCount: qscMsg.qubitCount,
+        qubitLabels: qscMsg.qubitLabels,
       });
       break;
     case "Result":
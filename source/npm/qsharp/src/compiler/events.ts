@@ -10,7 +10,12 @@ export type QscEventData =
   | { type: "Message"; detail: string }
   | {
       type: "DumpMachine";
-      detail: { state: Dump; stateLatex: string | null; qubitCount: number };
+      detail: {
+        state: Dump;
+        stateLatex: string | null;
+        qubitCount: number;
+        qubitLabels: Record<string, string>;
+      };
     }
   | { type: "Matrix"; detail: { matrix: number[][][]; matrixLatex: string } }
   | { type: "Result"; detail: Result };
@@ -115,6 +120,7 @@ export class QscEventTarget implements IQscEventTarget {
     state: Dump;
     stateLatex: string | null;
     qubitCount: number;
+    qubitLabels: Record<string, string>;
   }) {
     this.ensureActiveShot();
 
@@ -124,6 +130,7 @@ export class QscEventTarget implements IQscEventTarget {
       state: detail.state,
       stateLatex: detail.stateLatex,
       qubitCount: detail.qubitCount,
+      qubitLabels: detail.qubitLabels,
     });
 
     this.queueUiRefresh();
@@ -183,6 +183,8 @@ export interface Unitary extends BaseOperation {
   controls?: Register[];
   /** Whether gate is an adjoint operation. */
   isAdjoint?: boolean;
+  /** Exact values of the numeric arguments, which `args` only holds rounded for display. */
+  params?: number[];
 }
 
 /**
@@ -55,6 +55,8 @@ export interface IDebugService {
     bps: number[],
     eventHandler: IQscEventTarget,
   ): Promise<IStructStepResult>;
+  watchQubit(qubit: number, entanglement: boolean): Promise<void>;
+  unwatchQubit(qubit: number): Promise<void>;
   dispose(): Promise<void>;
 }
 
@@ -142,6 +144,14 @@ export class QSharpDebugService implements IDebugService {
     return this.debugService.eval_step_out(event_cb, ids);
   }
 
+  async watchQubit(qubit: number, entanglement: boolean): Promise<void> {
+    this.debugService.watch_qubit(qubit, entanglement);
+  }
+
+  async unwatchQubit(qubit: number): Promise<void> {
+    this.debugService.unwatch_qubit(qubit);
+  }
+
   async dispose() {
     this.debugService.free();
   }
@@ -166,6 +176,7 @@ export function onCompilerEvent(msg: string, eventTarget: IQscEventTarget) {
         state: qscMsg.state,
         stateLatex: qscMsg.stateLatex,
         qubitCount: qscMsg.qubitCount,
+        qubitLabels: qscMsg.qubitLabels,
       });
       break;
     case "Result":
@@ -202,6 +213,8 @@ export const debugServiceProtocol: ServiceProtocol<
     evalNext: "requestWithProgress",
     evalStepIn: "requestWithProgress",
     evalStepOut: "requestWithProgress",
+    watchQubit: "request",
+    unwatchQubit: "request",
     dispose: "request",
   },
   eventNames: ["DumpMachine", "Message", "Matrix", "Result"],
@@ -12,6 +12,7 @@ from ._qsharp import (
     logical_counts,
     set_quantum_seed,
     set_classical_seed,
+    set_tolerance,
     dump_machine,
     dump_circuit,
     StateDump,
@@ -44,6 +45,7 @@ __all__ = [
     "run",
     "set_quantum_seed",
     "set_classical_seed",
+    "set_tolerance",
     "dump_machine",
     "dump_circuit",
     "compile",
@@ -305,6 +305,15 @@ class Interpreter:
         """
         ...
 
+    def set_tolerance(self, absolute: float = 1e-10, relative: float = 0.0) -> None:
+        """
+        Sets the numeric tolerance for diagnostic checks such as `CheckZero`.
+
+        :param absolute: The absolute tolerance on the probability of measuring `One`.
+        :param relative: The tolerance on that probability relative to the squared norm of the state.
+        """
+        ...
+
     def dump_machine(self) -> StateDumpData:
         """
         Returns the sparse state vector of the simulator as a StateDump object.
@@ -982,6 +982,21 @@ def set_classical_seed(seed: Optional[int]) -> None:
     get_interpreter().set_classical_seed(seed)
 
 
+def set_tolerance(absolute: float = 1e-10, relative: float = 0.0) -> None:
+    """
+    Sets the numeric tolerance used by diagnostic checks such as `CheckZero`
+    and `CheckAllZero`. A qubit counts as being in the zero state when the
+    probability of measuring `One` is at most `absolute + relative * norm`,
+    where `norm` is the squared norm of the whole state.
+    This applies to all Q# code executed after the call.
+
+    :param absolute: The absolute tolerance on the probability.
+    :param relative: The tolerance on the probability, relative to the squared
+        norm of the state.
+    """
+    get_interpreter().set_tolerance(absolute, relative)
+
+
 def dump_machine() -> StateDump:
     """
     Returns the sparse state vector of the simulator as a StateDump object.
@@ -28,12 +28,12 @@ use num_bigint::BigUint;
 use num_complex::Complex64;
 use pyo3::{
     IntoPyObjectExt, create_exception,
-    exceptions::{PyException, PyValueError},
+    exceptions::{PyException, PyUserWarning, PyValueError},
     prelude::*,
     types::{PyDict, PyList, PyString, PyTuple, PyType},
 };
 use qsc::{
-    LanguageFeatures, PackageType, SourceMap,
+    LanguageFeatures, PackageType, SourceMap, Tolerance,
     error::WithSource,
     fir::{self},
     hir::ty::{Prim, Ty},
@@ -50,7 +50,9 @@ use qsc::{
 use resource_estimator::{
     self as re, estimate_call, estimate_expr, logical_counts_call, logical_counts_expr,
 };
-use std::{cell::RefCell, fmt::Write, path::PathBuf, rc::Rc, str::FromStr, sync::Arc};
+use std::{
+    cell::RefCell, ffi::CString, fmt::Write, path::PathBuf, rc::Rc, str::FromStr, sync::Arc,
+};
 
 /// If the classes are not Send, the Python interpreter
 /// will not be able to use them in a separate thread.
@@ -89,6 +91,10 @@ fn verify_classes_are_sendable() {
 #[pymodule]
 fn _native<'a>(py: Python<'a>, m: &Bound<'a, PyModule>) -> PyResult<()> {
     verify_classes_are_sendable();
+    // Builds with the `tracing` feature write the compiler's trace output, filtered by the
+    // `QSC_LOG` environment variable, to stderr.
+    #[cfg(feature = "tracing")]
+    qsc::logging::init();
     m.add_class::<OutputSemantics>()?;
     m.add_class::<ProgramType>()?;
     m.add_class::<TargetProfile>()?;
@@ -480,7 +486,9 @@ impl Interpreter {
         callback: Option<Py<PyAny>>,
     ) -> PyResult<Py<PyAny>> {
         let mut receiver = OptionalCallbackReceiver { callback, py };
-        match self.interpreter.eval_fragments(&mut receiver, input) {
+        let result = self.interpreter.eval_fragments(&mut receiver, input);
+        emit_warnings(py, self.interpreter.take_warnings())?;
+        match result {
             Ok(value) => {
                 if let Some(make_callable) = &self.make_callable {
                     // Get any global callables from the evaluated input and add them to the environment. This will grab
@@ -632,6 +640,13 @@ impl Interpreter {
         self.interpreter.set_classical_seed(seed);
     }
 
+    /// Sets the numeric tolerance for diagnostic checks such as `CheckZero`.
+    #[pyo3(signature=(absolute=1e-10, relative=0.0))]
+    fn set_tolerance(&mut self, absolute: f64, relative: f64) {
+        self.interpreter
+            .set_tolerance(Tolerance { absolute, relative });
+    }
+
     /// Dumps the quantum state of the interpreter.
     /// Returns a tuple of (amplitudes, num_qubits), where amplitudes is a dictionary from integer indices to
     /// pairs of real and imaginary amplitudes.
@@ -1040,6 +1055,17 @@ create_exception!(
     "An error returned from the OpenQASM parser."
 );
 
+/// Reports compiler warnings, such as a callable redefined by a later cell, as Python
+/// `UserWarning`s.
+fn emit_warnings(py: Python, warnings: Vec<interpret::Error>) -> PyResult<()> {
+    let category = py.get_type::<PyUserWarning>();
+    for warning in warnings {
+        let message = CString::new(format_error(&warning)).unwrap_or_default();
+        PyErr::warn(py, category.as_any(), &message, 1)?;
+    }
+    Ok(())
+}
+
 pub(crate) fn format_errors(errors: Vec<interpret::Error>) -> String {
     errors
         .into_iter()
@@ -1411,7 +1437,7 @@ impl From<GlobalCallable> for Value {
 fn create_py_callable(
     py: Python,
     make_callable: &Py<PyAny>,
-    namespace: &[Rc<str>],
+    namespace: &[Arc<str>],
     name: &str,
     val: Value,
 ) -> PyResult<()> {
@@ -1437,7 +1463,7 @@ fn create_py_class(
     ctx: &interpret::Interpreter,
     py: Python,
     make_class: &Py<PyAny>,
-    namespace: &[Rc<str>],
+    namespace: &[Arc<str>],
     name: &str,
     ty: &Ty,
 ) -> PyResult<()> {
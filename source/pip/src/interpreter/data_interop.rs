@@ -21,7 +21,7 @@ use qsc::{
     interpret::{self, Value},
 };
 use rustc_hash::FxHashMap;
-use std::rc::Rc;
+use std::{rc::Rc, sync::Arc};
 
 /// Instances of this enum represent a Q# type. This is used
 /// to send the definitions of Q# UDTs defined by the user to Python
@@ -145,7 +145,7 @@ pub(super) enum PrimitiveValue {
 /// is anonymous.
 pub(super) fn collect_udt_fields<'ctx, 'udt_def>(
     udt: &'udt_def qsc::hir::ty::Udt,
-) -> PyResult<Vec<(Rc<str>, &'ctx Ty)>>
+) -> PyResult<Vec<(Arc<str>, &'ctx Ty)>>
 where
     'udt_def: 'ctx,
 {
@@ -157,7 +157,7 @@ where
 fn collect_udt_fields_rec<'ctx, 'udt_def>(
     udt_name: &str,
     udt_def: &'udt_def qsc::hir::ty::UdtDef,
-    buffer: &mut Vec<(Rc<str>, &'ctx Ty)>,
+    buffer: &mut Vec<(Arc<str>, &'ctx Ty)>,
 ) -> PyResult<()>
 where
     'udt_def: 'ctx,
@@ -105,6 +105,14 @@ def test_classical_seed() -> None:
     assert value1 != value3
 
 
+def test_set_tolerance_relaxes_check_zero() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    code = "{ use q = Qubit(); Ry(1.0e-4, q); let zero = Std.Diagnostics.CheckZero(q); Reset(q); zero }"
+    assert qsharp.eval(code) is False
+    qsharp.set_tolerance(absolute=1e-8)
+    assert qsharp.eval(code) is True
+
+
 def test_dump_machine() -> None:
     qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
     qsharp.eval(
@@ -58,7 +58,12 @@ function formatPhase(real: number, imag: number) {
 function formatQuantumState(
   evt: Event & {
     type: "DumpMachine";
-    detail: { state: Dump; stateLatex: string | null; qubitCount: number };
+    detail: {
+      state: Dump;
+      stateLatex: string | null;
+      qubitCount: number;
+      qubitLabels: Record<string, string>;
+    };
   },
 ) {
   const stateTable = evt.detail.state;
@@ -90,6 +95,12 @@ function formatQuantumState(
       rows.push(` ${basis} | ${amplitude} | ${probability} | ${phase}`);
     }
     out_str += rows.join("\n");
+    const labels = Object.entries(evt.detail.qubitLabels ?? {})
+      .map(([id, label]) => `q[${id}] "${label}"`)
+      .join(", ");
+    if (labels) {
+      out_str += `\n Qubits: ${labels}`;
+    }
   }
   return out_str;
 }
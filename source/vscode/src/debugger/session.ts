@@ -78,6 +78,7 @@ export class QscDebugSession extends LoggingDebugSession {
   private eventTarget: QscEventTarget;
   private supportsVariableType = false;
   private revealedCircuit = false;
+  private watchedQubits: number[] = [];
 
   public constructor(
     private debugService: IDebugServiceWorker,
@@ -180,7 +181,7 @@ export class QscDebugSession extends LoggingDebugSession {
     response.body.supportsStepBack = false;
 
     // make VS Code support data breakpoints
-    response.body.supportsDataBreakpoints = false;
+    response.body.supportsDataBreakpoints = true;
 
     // make VS Code support completion in REPL
     response.body.supportsCompletionsRequest = false;
@@ -336,6 +337,11 @@ export class QscDebugSession extends LoggingDebugSession {
       evt.body.hitBreakpointIds = [result.value];
       log.trace(`raising breakpoint event`);
       this.sendEvent(evt);
+    } else if (result.id == StepResultId.QubitWatchHit) {
+      log.trace(`raising data breakpoint event for qubit ${result.value}`);
+      this.sendEvent(
+        new StoppedEvent("data breakpoint", QscDebugSession.threadID),
+      );
     } else if (result.id == StepResultId.Return) {
       await this.endSession(`ending session`, 0);
     } else if (result.id == StepResultId.Fail) {
@@ -676,6 +682,62 @@ export class QscDebugSession extends LoggingDebugSession {
     this.sendResponse(response);
   }
 
+  protected async dataBreakpointInfoRequest(
+    response: DebugProtocol.DataBreakpointInfoResponse,
+    args: DebugProtocol.DataBreakpointInfoArguments,
+  ): Promise<void> {
+    log.trace(`dataBreakpointInfoRequest: %O`, args);
+    response.body = {
+      dataId: null,
+      description: "Data breakpoints are only supported on qubit variables",
+    };
+
+    // Only locals bound to a single qubit can be watched, since the debugger
+    // watches qubits rather than variables.
+    if (args.variablesReference !== undefined) {
+      const [handle, frameID] = this.variableHandles.get(
+        args.variablesReference,
+      );
+      if (handle === "locals") {
+        const locals = await this.debugService.getLocalVariables(frameID);
+        const local = [...locals]
+          .reverse()
+          .find((local) => local.name === args.name);
+        const qubit = local?.value.match(/^Qubit(\d+)$/)?.[1];
+        if (qubit !== undefined) {
+          response.body = {
+            dataId: qubit,
+            description: `${args.name} (Qubit${qubit})`,
+            accessTypes: ["write"],
+            canPersist: false,
+          };
+        }
+      }
+    }
+
+    log.trace(`dataBreakpointInfoResponse: %O`, response);
+    this.sendResponse(response);
+  }
+
+  protected async setDataBreakpointsRequest(
+    response: DebugProtocol.SetDataBreakpointsResponse,
+    args: DebugProtocol.SetDataBreakpointsArguments,
+  ): Promise<void> {
+    log.trace(`setDataBreakpointsRequest: %O`, args);
+    for (const qubit of this.watchedQubits) {
+      await this.debugService.unwatchQubit(qubit);
+    }
+    this.watchedQubits = args.breakpoints.map((bp) => Number(bp.dataId));
+    for (const qubit of this.watchedQubits) {
+      await this.debugService.watchQubit(qubit, false);
+    }
+    response.body = {
+      breakpoints: this.watchedQubits.map(() => ({ verified: true })),
+    };
+    log.trace(`setDataBreakpointsResponse: %O`, response);
+    this.sendResponse(response);
+  }
+
   protected threadsRequest(response: DebugProtocol.ThreadsResponse): void {
     log.trace(`threadRequest`);
     response.body = {
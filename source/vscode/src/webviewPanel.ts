@@ -158,6 +158,7 @@ export function registerWebViewCommands(context: ExtensionContext) {
         parseInt(numberOfShots),
         noise,
         qubitLoss,
+        undefined,
         evtTarget,
       );
       sendTelemetryEvent(
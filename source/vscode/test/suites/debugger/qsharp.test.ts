@@ -475,6 +475,71 @@ suite("Q# Debugger Tests", function suite() {
     ]);
   });
 
+  test("Show quantum state scope", async () => {
+    // Set a breakpoint on line 9 of foo.qs (8 when 0-indexed)
+    // This will be in the operation `Foo` after `H(q)` has run.
+    vscode.debug.addBreakpoints([
+      new vscode.SourceBreakpoint(
+        new vscode.Location(fooUri, new vscode.Position(8, 0)),
+      ),
+    ]);
+
+    // Start a debug session.
+    await vscode.debug.startDebugging(workspaceFolder, {
+      name: "Launch foo.qs",
+      type: "qsharp",
+      request: "launch",
+      program: "${workspaceFolder}src/foo.qs",
+      stopOnEntry: false,
+    });
+
+    // Should hit the breakpoint set above.
+    await waitUntilPausedAndAssertVariables([
+      { name: "q", type: undefined, value: "Qubit0", variablesReference: 0 },
+    ]);
+
+    // Request scopes for the frame with frameId 0 (Foo's frame).
+    await vscode.debug.activeDebugSession?.customRequest("scopes", {
+      frameId: 0,
+    });
+    const scopes = (await waitUntilResponse(
+      "scopes",
+    )) as DebugProtocol.ScopesResponse;
+
+    // Request variables for the Quantum State scope.
+    const variablesReference = scopes.body.scopes.find(
+      (scope) => scope.name === "Quantum State",
+    )?.variablesReference;
+
+    assert.isNotNull(
+      variablesReference,
+      "Expected to find a variables reference for the Quantum State scope",
+    );
+
+    await vscode.debug.activeDebugSession?.customRequest("variables", {
+      variablesReference,
+    });
+
+    const variables = (await waitUntilResponse(
+      "variables",
+    )) as DebugProtocol.VariablesResponse;
+
+    assert.deepEqual(variables.body.variables, [
+      {
+        name: "|0⟩",
+        type: "Complex",
+        value: "0.7071+0.0000𝑖",
+        variablesReference: 0,
+      },
+      {
+        name: "|1⟩",
+        type: "Complex",
+        value: "0.7071+0.0000𝑖",
+        variablesReference: 0,
+      },
+    ]);
+  });
+
   /**
    * Wait until the debugger has entered the paused state and then asserts
    * that the stack traces matches the `expectedStackTrace`.
@@ -10,7 +10,7 @@ use crate::{
 };
 use qsc::fir::StmtId;
 use qsc::fmt_complex;
-use qsc::interpret::{Debugger, Error, StepAction, StepResult};
+use qsc::interpret::{Debugger, Error, QubitWatchKind, StepAction, StepResult};
 use qsc::line_column::Encoding;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -155,7 +155,7 @@ impl DebugService {
     where
         F: Fn(&str),
     {
-        let mut out = CallbackReceiver { event_cb };
+        let mut out = CallbackReceiver::new(event_cb);
         let result = self.debugger_mut().eval_step(&mut out, bps, step);
         let mut success = true;
 
@@ -215,6 +215,21 @@ impl DebugService {
         VariableList { variables }.into()
     }
 
+    /// Sets a data breakpoint on the qubit with the given id, which stops execution when the
+    /// qubit becomes entangled if `entanglement` is set, or on any change to its state otherwise.
+    pub fn watch_qubit(&mut self, qubit: usize, entanglement: bool) {
+        let kind = if entanglement {
+            QubitWatchKind::Entanglement
+        } else {
+            QubitWatchKind::StateChange
+        };
+        self.debugger_mut().watch_qubit(qubit, kind);
+    }
+
+    pub fn unwatch_qubit(&mut self, qubit: usize) {
+        self.debugger_mut().unwatch_qubit(qubit);
+    }
+
     fn debugger(&self) -> &Debugger {
         self.debugger
             .as_ref()
@@ -292,6 +307,11 @@ impl From<StepResult> for StructStepResult {
                 value: 0,
                 error: Some(error),
             },
+            StepResult::QubitWatchHit(qubit) => StructStepResult {
+                id: StepResultId::QubitWatchHit.into(),
+                value: qubit,
+                error: None,
+            },
         }
     }
 }
@@ -305,6 +325,7 @@ pub enum StepResultId {
     StepOut = 3,
     Return = 4,
     Fail = 5,
+    QubitWatchHit = 6,
 }
 
 impl From<StepResultId> for usize {
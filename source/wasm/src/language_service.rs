@@ -629,7 +629,7 @@ serializable_type! {
     },
     r#"export interface INotebookMetadata {
         targetProfile?: "base" | "adaptive_ri" | "adaptive_rif" | "unrestricted";
-        languageFeatures?: "v2-preview-syntax"[];
+        languageFeatures?: ("v2-preview-syntax" | "script-mode" | "match-expressions")[];
         manifest?: string;
         projectRoot?: string;
     }"#,
@@ -12,12 +12,12 @@ use num_complex::Complex64;
 use project_system::{ProgramConfig, into_openqasm_arg, into_qsc_args, is_openqasm_program};
 use qsc::{
     LanguageFeatures, PackageStore, PackageType, PauliNoise, SourceContents, SourceMap, SourceName,
-    SparseSim, TargetCapabilityFlags,
+    SparseSim, TargetCapabilityFlags, Tolerance,
     compile::{self, Dependencies, package_store_with_stdlib},
     format_state_id, get_matrix_latex, get_state_latex,
     hir::PackageId,
     interpret::{
-        self, CircuitEntryPoint,
+        self, CircuitEntryPoint, QubitInfo,
         output::{self, Receiver},
     },
     openqasm::{CompileRawQasmResult, io::InMemorySourceResolver},
@@ -57,6 +57,14 @@ pub fn git_hash() -> String {
     git_hash.into()
 }
 
+/// Returns the compiler's version and supported profiles, language features and backends
+/// as JSON.
+#[wasm_bindgen]
+#[must_use]
+pub fn get_features() -> String {
+    qsc::features().to_json()
+}
+
 #[wasm_bindgen]
 pub fn get_qir(program: ProgramConfig) -> Result<String, String> {
     if is_openqasm_program(&program) {
@@ -322,6 +330,20 @@ where
     F: FnMut(&str),
 {
     event_cb: F,
+    /// The variable names of the qubits in the next state dump, keyed by qubit id.
+    qubit_labels: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<F> CallbackReceiver<F>
+where
+    F: FnMut(&str),
+{
+    fn new(event_cb: F) -> Self {
+        Self {
+            event_cb,
+            qubit_labels: serde_json::Map::new(),
+        }
+    }
 }
 
 impl<F> Receiver for CallbackReceiver<F>
@@ -360,9 +382,10 @@ where
 
         let json_latex = serde_json::to_string(&get_state_latex(&state, qubit_count))
             .expect("serialization should succeed");
+        let json_labels = serde_json::Value::Object(std::mem::take(&mut self.qubit_labels));
         write!(
             dump_json,
-            r#" "stateLatex": {json_latex}, "qubitCount": {qubit_count} }} "#
+            r#" "stateLatex": {json_latex}, "qubitCount": {qubit_count}, "qubitLabels": {json_labels} }} "#
         )
         .expect("writing to string should succeed");
         (self.event_cb)(&dump_json);
@@ -413,6 +436,18 @@ where
         (self.event_cb)(&msg_json.to_string());
         Ok(())
     }
+
+    fn qubits(&mut self, qubits: &[QubitInfo]) -> Result<(), output::Error> {
+        self.qubit_labels = qubits
+            .iter()
+            .filter_map(|q| {
+                q.label
+                    .as_ref()
+                    .map(|label| (q.id.to_string(), label.clone().into()))
+            })
+            .collect();
+        Ok(())
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -426,11 +461,12 @@ fn run_internal_with_features<F>(
     dependencies: &Dependencies,
     pauliNoise: &PauliNoise,
     qubitLoss: f64,
+    tolerance: Tolerance,
 ) -> Result<(), Box<interpret::Error>>
 where
     F: FnMut(&str),
 {
-    let mut out = CallbackReceiver { event_cb };
+    let mut out = CallbackReceiver::new(event_cb);
     let mut interpreter = match interpret::Interpreter::new(
         sources,
         PackageType::Exe,
@@ -453,6 +489,7 @@ where
         let result = {
             let mut sim = SparseSim::new_with_noise(pauliNoise);
             sim.set_loss(qubitLoss);
+            sim.tolerance = tolerance;
             interpreter.eval_entry_with_sim(&mut sim, &mut out)
         };
         let mut success = true;
@@ -484,6 +521,7 @@ pub fn run(
         shots,
         &JsValue::null(),
         &JsValue::null(),
+        &JsValue::null(),
     )
 }
 
@@ -495,6 +533,7 @@ pub fn runWithNoise(
     shots: u32,
     pauliNoise: &JsValue,
     qubitLoss: &JsValue,
+    tolerance: &JsValue,
 ) -> Result<bool, JsValue> {
     if !event_cb.is_function() {
         return Err(JsError::new("Events callback function must be provided").into());
@@ -533,6 +572,26 @@ pub fn runWithNoise(
     // See if the qubitLoss JsValue is a number
     let qubitLoss = qubitLoss.as_f64().unwrap_or(0.0);
 
+    // See if the tolerance JsValue is an array of the absolute and relative tolerances
+    let tolerance = if tolerance.is_array() {
+        let toleranceArray = js_sys::Array::from(tolerance);
+        if toleranceArray.length() != 2 {
+            return Err(JsError::new("Tolerance must have absolute and relative values").into());
+        }
+        Tolerance {
+            absolute: toleranceArray
+                .get(0)
+                .as_f64()
+                .expect("Tolerances should be floats"),
+            relative: toleranceArray
+                .get(1)
+                .as_f64()
+                .expect("Tolerances should be floats"),
+        }
+    } else {
+        Tolerance::default()
+    };
+
     if is_openqasm_program(&program) {
         let (sources, capabilities) = into_openqasm_arg(program);
         let (entry_expr, mut interpreter) = get_interpreter_from_openqasm(&sources, capabilities)?;
@@ -540,11 +599,12 @@ pub fn runWithNoise(
             return Err(interpret_errors_into_qsharp_errors_json(err).into());
         }
 
-        let mut out = CallbackReceiver { event_cb };
+        let mut out = CallbackReceiver::new(event_cb);
         for _ in 0..shots {
             let result = {
                 let mut sim = SparseSim::new_with_noise(&noise);
                 sim.set_loss(qubitLoss);
+                sim.tolerance = tolerance;
                 interpreter.eval_entry_with_sim(&mut sim, &mut out)
             };
             let mut success = true;
@@ -581,6 +641,7 @@ pub fn runWithNoise(
             &deps[..],
             &noise,
             qubitLoss,
+            tolerance,
         ) {
             Ok(()) => Ok(true),
             Err(e) => Err(JsError::from(e).into()),
@@ -598,7 +659,7 @@ fn check_exercise_solution_internal(
     for exercise_source in exercise_sources {
         sources.push(exercise_source);
     }
-    let mut out = CallbackReceiver { event_cb };
+    let mut out = CallbackReceiver::new(event_cb);
     let result = check_solution(sources, &mut out);
     let mut runtime_success = true;
     let (exercise_success, msg) = match result {
@@ -754,7 +815,7 @@ export type TargetProfile = "base" | "adaptive_ri" | "adaptive_rif" | "unrestric
 
 #[wasm_bindgen(typescript_custom_section)]
 const LANGUAGE_FEATURES: &'static str = r#"
-export type LanguageFeatures = "v2-preview-syntax";
+export type LanguageFeatures = "v2-preview-syntax" | "script-mode" | "match-expressions";
 "#;
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -4,7 +4,8 @@
 use expect_test::expect;
 use indoc::indoc;
 use qsc::{
-    LanguageFeatures, PackageStore, PauliNoise, SourceMap, TargetCapabilityFlags, interpret,
+    LanguageFeatures, PackageStore, PauliNoise, SourceMap, TargetCapabilityFlags, Tolerance,
+    interpret,
 };
 
 use crate::get_qir_from_qsharp;
@@ -26,6 +27,7 @@ where
         &[(std_id, None)],
         &PauliNoise::default(),
         0.0, // qubit loss
+        Tolerance::default(),
     )
 }
 